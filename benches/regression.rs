@@ -0,0 +1,62 @@
+//! Regression thresholds for the criterion benchmarks.
+//!
+//! Shared by the benchmark harnesses of every crate through
+//! `#[path = "../../benches/regression.rs"] mod regression;`. Each harness
+//! lists the most time its benchmarks may take, and after a `cargo bench`
+//! run [`check`] compares criterion's mean estimates against them, failing
+//! the run if any benchmark got slower.
+//!
+//! Thresholds are set several times above what a laptop measures, so only
+//! real slips in the refresh loop trip them, not a noisy CI runner.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Fails the run if a benchmark's mean time is over its threshold.
+///
+/// Benchmarks that weren't run, e.g. because of a filter, are skipped, as
+/// are test runs (`cargo test --benches`), which don't measure anything.
+pub fn check(thresholds: &[(String, Duration)]) {
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+
+    let dir = criterion_dir();
+    let mut slow = Vec::new();
+    for (id, max) in thresholds {
+        let Some(mean) = mean_estimate(&dir.join(id).join("new/estimates.json")) else {
+            continue;
+        };
+        if mean > *max {
+            slow.push(format!("{id}: {mean:?} (threshold {max:?})"));
+        }
+    }
+
+    if !slow.is_empty() {
+        eprintln!("\nBenchmarks over their regression threshold:");
+        for line in &slow {
+            eprintln!("  {line}");
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Directory criterion writes its estimates to.
+fn criterion_dir() -> PathBuf {
+    if let Some(home) = std::env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    // `target/tmp`, next to `target/criterion`
+    Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .parent()
+        .map_or_else(|| PathBuf::from("target"), Path::to_path_buf)
+        .join("criterion")
+}
+
+/// Reads the mean from a criterion `estimates.json`.
+fn mean_estimate(path: &Path) -> Option<Duration> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let estimates: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    let nanos = estimates["mean"]["point_estimate"].as_f64()?;
+    Some(Duration::from_secs_f64(nanos / 1e9))
+}
@@ -0,0 +1,166 @@
+// Lint configuration for this crate
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::must_use_candidate)]
+
+//! # `ExactoBar` API
+//!
+//! The stable subset of `ExactoBar`'s usage types, for external tools and
+//! plugins.
+//!
+//! `exactobar-core` is an internal crate and changes whenever the app
+//! needs it to. This crate re-exports only the types other programs need
+//! to read usage data, and holds them to semver:
+//!
+//! - Items exported here are not removed or renamed without a major
+//!   version bump.
+//! - The JSON form of these types (as produced by `serde`) only grows:
+//!   new fields are optional or defaulted, and existing field names,
+//!   enum spellings and value formats stay the same. Data written by an
+//!   older version always deserializes in a newer one.
+//! - Deserializing ignores unknown fields, so a tool built against an
+//!   older version can read data written by a newer one.
+//!
+//! [`SCHEMA_VERSION`] changes only when one of these guarantees has to be
+//! broken.
+//!
+//! ## Types
+//!
+//! - [`ProviderKind`] - Which provider the data is for
+//! - [`UsageSnapshot`] - All usage windows for one provider at a point in time
+//! - [`UsageWindow`] - One quota window (session, weekly, premium tier, ...)
+//! - [`ModelUsage`] - A quota window for a single model
+//! - [`ProviderIdentity`] and [`LoginMethod`] - The account the data belongs to
+//! - [`FetchSource`] - How the data was obtained
+//!
+//! ## Example
+//!
+//! ```
+//! use exactobar_api::{UsageSnapshot, UsageWindow};
+//!
+//! let mut snapshot = UsageSnapshot::new();
+//! snapshot.primary = Some(UsageWindow::new(63.0));
+//!
+//! let json = serde_json::to_string(&snapshot).unwrap();
+//! let parsed: UsageSnapshot = serde_json::from_str(&json).unwrap();
+//! assert_eq!(parsed.primary.unwrap().remaining_percent(), 37.0);
+//! ```
+
+pub use exactobar_core::{
+    FetchSource, LoginMethod, ModelUsage, ProviderIdentity, ProviderKind, UsageSnapshot,
+    UsageWindow,
+};
+
+/// Version of the serialized format of the types in this crate.
+///
+/// Bumped only for changes that break the guarantees above; additive
+/// changes keep the same version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+/// These tests pin the wire format. If one fails, the change breaks
+/// external tools: make it additive instead, or bump [`SCHEMA_VERSION`]
+/// and the major version.
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn full_snapshot() -> UsageSnapshot {
+        let resets_at = Utc.with_ymd_and_hms(2025, 1, 15, 18, 0, 0).unwrap();
+
+        let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+        identity.account_email = Some("dev@example.com".to_string());
+        identity.plan_name = Some("Max".to_string());
+        identity.login_method = Some(LoginMethod::OAuth);
+
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow {
+            used_percent: 42.5,
+            window_minutes: Some(300),
+            resets_at: Some(resets_at),
+            reset_description: Some("in 2 hours".to_string()),
+        });
+        snapshot.secondary = Some(UsageWindow::new(10.0));
+        snapshot.updated_at = Utc.with_ymd_and_hms(2025, 1, 15, 16, 0, 0).unwrap();
+        snapshot.identity = Some(identity);
+        snapshot.fetch_source = FetchSource::OAuth;
+        snapshot.models = vec![ModelUsage::new("gemini-2.5-pro", UsageWindow::new(5.0))];
+        snapshot
+    }
+
+    #[test]
+    fn test_snapshot_wire_format() {
+        let value = serde_json::to_value(full_snapshot()).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "primary": {
+                    "used_percent": 42.5,
+                    "window_minutes": 300,
+                    "resets_at": "2025-01-15T18:00:00Z",
+                    "reset_description": "in 2 hours"
+                },
+                "secondary": {
+                    "used_percent": 10.0,
+                    "window_minutes": null,
+                    "resets_at": null,
+                    "reset_description": null
+                },
+                "tertiary": null,
+                "search": null,
+                "updated_at": "2025-01-15T16:00:00Z",
+                "identity": {
+                    "provider_id": "claude",
+                    "account_email": "dev@example.com",
+                    "account_organization": null,
+                    "plan_name": "Max",
+                    "login_method": "o_auth"
+                },
+                "fetch_source": "o_auth",
+                "models": [{
+                    "model": "gemini-2.5-pro",
+                    "window": {
+                        "used_percent": 5.0,
+                        "window_minutes": null,
+                        "resets_at": null,
+                        "reset_description": null
+                    }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_provider_kind_names() {
+        for kind in ProviderKind::all() {
+            let value = serde_json::to_value(kind).unwrap();
+            assert_eq!(value, json!(kind.cli_name()), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_reads_minimal_and_future_data() {
+        // Optional fields left out, plus fields a newer version might add
+        let json = r#"{
+            "primary": {"used_percent": 80.0, "window_minutes": null, "resets_at": null,
+                        "reset_description": null, "burn_rate": 1.5},
+            "secondary": null,
+            "updated_at": "2025-01-15T16:00:00Z",
+            "plan_tier": "pro"
+        }"#;
+        let snapshot: UsageSnapshot = serde_json::from_str(json).unwrap();
+
+        assert_eq!(snapshot.primary.unwrap().used_percent, 80.0);
+        assert_eq!(snapshot.fetch_source, FetchSource::Auto);
+        assert!(snapshot.models.is_empty());
+    }
+}
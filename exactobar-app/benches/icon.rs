@@ -0,0 +1,120 @@
+//! Menu bar icon benchmarks.
+//!
+//! The tray renders a frame with [`IconRenderer`] and encodes it to PNG on
+//! every refresh whose usage changed, and up to ten times a second while a
+//! loading or blink animation plays.
+
+use std::time::Duration;
+
+use criterion::{BenchmarkId, Criterion, criterion_group};
+use exactobar_core::{ProviderKind, StatusIndicator, UsageSnapshot, UsageWindow};
+
+// The icon module doesn't depend on GPUI, so it's built on its own here
+// rather than through the app.
+#[allow(dead_code)]
+#[path = "../src/icon/mod.rs"]
+mod icon;
+
+#[path = "../../benches/regression.rs"]
+mod regression;
+
+use icon::{IconAnimationState, IconLayout, IconRenderer, LoadingPattern, RenderMode};
+
+/// Most time rendering and encoding a frame may take.
+const FRAME_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Most time reusing a cached frame may take.
+const CACHED_THRESHOLD: Duration = Duration::from_micros(20);
+
+fn snapshot() -> UsageSnapshot {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(62.0));
+    snapshot.secondary = Some(UsageWindow::new(35.0));
+    snapshot
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    let snapshot = snapshot();
+    let mut group = c.benchmark_group("icon");
+    for &layout in IconLayout::all() {
+        for (mode_name, mode) in [
+            ("template", RenderMode::Template),
+            ("colored", RenderMode::Colored),
+        ] {
+            let renderer = IconRenderer::new().with_layout(layout).with_mode(mode);
+            group.bench_function(BenchmarkId::new(format!("{layout:?}"), mode_name), |b| {
+                b.iter(|| {
+                    renderer
+                        .render(
+                            ProviderKind::Claude,
+                            Some(&snapshot),
+                            false,
+                            Some(StatusIndicator::Minor),
+                            None,
+                        )
+                        .to_png()
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_animation(c: &mut Criterion) {
+    let snapshot = snapshot();
+    let renderer = IconRenderer::new();
+    let mut group = c.benchmark_group("icon");
+    group.bench_function("loading_frame", |b| {
+        let mut phase = 0.0;
+        b.iter(|| {
+            phase += 0.1;
+            renderer
+                .render_loading(ProviderKind::Claude, LoadingPattern::default(), phase)
+                .to_png()
+        });
+    });
+    group.bench_function("cached_frame", |b| {
+        b.iter(|| {
+            renderer
+                .render_cached(ProviderKind::Claude, Some(&snapshot), false, None, None)
+                .png()
+                .len()
+        });
+    });
+    group.bench_function("codex_blink_frame", |b| {
+        let animation = IconAnimationState::with_blink(0.5);
+        b.iter(|| {
+            renderer
+                .render(
+                    ProviderKind::Codex,
+                    Some(&snapshot),
+                    false,
+                    None,
+                    Some(&animation),
+                )
+                .to_png()
+        });
+    });
+    group.finish();
+}
+
+fn thresholds() -> Vec<(String, Duration)> {
+    let mut thresholds: Vec<_> = IconLayout::all()
+        .iter()
+        .flat_map(|layout| {
+            ["template", "colored"].map(|mode| (format!("icon/{layout:?}/{mode}"), FRAME_THRESHOLD))
+        })
+        .collect();
+    thresholds.push(("icon/loading_frame".to_string(), FRAME_THRESHOLD));
+    thresholds.push(("icon/codex_blink_frame".to_string(), FRAME_THRESHOLD));
+    thresholds.push(("icon/cached_frame".to_string(), CACHED_THRESHOLD));
+    thresholds
+}
+
+criterion_group!(benches, bench_layouts, bench_animation);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    regression::check(&thresholds());
+}
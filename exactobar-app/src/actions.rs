@@ -1,17 +1,30 @@
 //! Application actions.
 //!
-//! Simple action handlers for the app.
+//! Simple action handlers for the app, plus the [`AppCommand`] registry the
+//! command palette searches.
 
 use exactobar_core::ProviderKind;
+use exactobar_providers::ProviderRegistry;
 use gpui::*;
 use tracing::info;
 
+use crate::snooze::{self, SnoozeLength};
 use crate::state::{AppState, UsageModel};
+use crate::tray::SystemTray;
 use crate::windows;
+use crate::windows::settings::SettingsPane;
+
+actions!(exactobar, [OpenCommandPalette]);
 
 /// Registers all application actions.
-pub fn register_actions(_cx: &mut App) {
-    // Actions are handled via callbacks in the UI, not via global action dispatch
+pub fn register_actions(cx: &mut App) {
+    // Most actions are handled via callbacks in the UI; the command palette
+    // is also bound to ⌘K / Ctrl-K in any ExactoBar window.
+    cx.on_action(|_: &OpenCommandPalette, cx| windows::open_command_palette(cx));
+    cx.bind_keys([
+        KeyBinding::new("cmd-k", OpenCommandPalette, None),
+        KeyBinding::new("ctrl-k", OpenCommandPalette, None),
+    ]);
     info!("Actions registered");
 }
 
@@ -31,39 +44,225 @@ pub fn open_settings(cx: &mut App) {
     windows::open_settings(cx);
 }
 
-/// Quits the application.
+/// Quits the application, aborting fetches in flight.
 pub fn quit(cx: &mut App) {
+    crate::refresh::cancel_all_fetches();
     cx.quit();
 }
 
-/// Refreshes a provider asynchronously.
-fn refresh_provider_async(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut App) {
+/// Copies a one-line usage summary per enabled provider to the clipboard.
+pub fn copy_summary(cx: &mut App) {
+    let state = cx.global::<AppState>();
+    let now = chrono::Utc::now();
+    let summary = state
+        .enabled_providers(cx)
+        .into_iter()
+        .map(|p| crate::tooltip::tooltip_line(p, state.get_snapshot(p, cx).as_ref(), now))
+        .collect::<Vec<_>>()
+        .join("\n");
+    crate::menu::copy_to_clipboard(&summary);
+}
+
+/// Asks for a note and attaches it to the current time.
+pub fn add_note(cx: &mut App) {
     cx.spawn(async move |mut cx| {
-        // Mark as refreshing
-        let _ = cx.update_entity(&usage, |model, cx| {
-            model.set_refreshing(provider, true);
-            cx.notify();
-        });
-
-        // Execute fetch on Tokio runtime - MUST use this bridge!
-        // Direct pipeline.execute() calls will panic because tokio::process::Command
-        // requires a Tokio runtime, but GPUI runs on smol.
-        let result = crate::refresh::fetch_on_tokio(provider).await;
-
-        // Update state
-        let _ = cx.update_entity(&usage, |model, cx| {
-            model.set_refreshing(provider, false);
-            match result {
-                Ok(snapshot) => {
-                    model.set_snapshot(provider, snapshot);
-                    model.clear_error(provider);
-                }
-                Err(e) => {
-                    model.set_error(provider, e);
+        if let Some(text) = smol::unblock(prompt_for_note).await {
+            let _ = cx.update_global::<AppState, _>(|state, cx| state.add_note(text, cx));
+        }
+    })
+    .detach();
+}
+
+/// Prompts for a note using osascript (native macOS dialog).
+///
+/// Returns `None` if cancelled or empty.
+fn prompt_for_note() -> Option<String> {
+    let script = r#"
+        set dialogResult to display dialog "Note what you're doing (e.g. \"ran evals\"):" default answer "" buttons {"Cancel", "Add"} default button "Add"
+        if button returned of dialogResult is "Add" then
+            return text returned of dialogResult
+        else
+            return ""
+        end if
+        "#;
+
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
+
+// ============================================================================
+// Command Registry
+// ============================================================================
+
+/// A command that can be run from the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppCommand {
+    /// Open the menu on a provider.
+    SwitchProvider(ProviderKind),
+    /// Refresh one provider.
+    Refresh(ProviderKind),
+    /// Refresh all enabled providers.
+    RefreshAll,
+    /// Open a settings pane.
+    OpenSettings(SettingsPane),
+    /// Copy a usage summary to the clipboard.
+    CopySummary,
+    /// Note what's happening now, shown on usage graphs.
+    AddNote,
+    /// Open the usage history window.
+    OpenHistory,
+    /// Open the team leaderboard window.
+    OpenTeam,
+    /// Open a provider's detail window.
+    ShowDetails(ProviderKind),
+    /// Open a provider's usage or billing page in the browser.
+    OpenDashboard(ProviderKind),
+    /// Hold a provider's quota alerts for a while.
+    SnoozeAlerts {
+        provider: ProviderKind,
+        length: SnoozeLength,
+    },
+    /// Enable or disable a provider.
+    ToggleProvider {
+        provider: ProviderKind,
+        enabled: bool,
+    },
+    /// Quit ExactoBar.
+    Quit,
+}
+
+impl AppCommand {
+    /// Label shown in the palette and matched against the query.
+    pub fn label(&self) -> String {
+        match self {
+            AppCommand::SwitchProvider(p) => format!("Switch to {}", p.display_name()),
+            AppCommand::Refresh(p) => format!("Refresh {}", p.display_name()),
+            AppCommand::RefreshAll => "Refresh All".to_string(),
+            AppCommand::OpenSettings(pane) => format!("Open Settings: {}", pane.title()),
+            AppCommand::CopySummary => "Copy Usage Summary".to_string(),
+            AppCommand::AddNote => "Add Note…".to_string(),
+            AppCommand::OpenHistory => "Open Usage History".to_string(),
+            AppCommand::OpenTeam => "Open Team Leaderboard".to_string(),
+            AppCommand::ShowDetails(p) => format!("Show {} Details", p.display_name()),
+            AppCommand::OpenDashboard(p) => format!("Open {} Dashboard", p.display_name()),
+            AppCommand::SnoozeAlerts { provider, length } => format!(
+                "Snooze {} Alerts {}",
+                provider.display_name(),
+                length.label()
+            ),
+            AppCommand::ToggleProvider { provider, enabled } => format!(
+                "{} {}",
+                if *enabled { "Disable" } else { "Enable" },
+                provider.display_name()
+            ),
+            AppCommand::Quit => "Quit ExactoBar".to_string(),
+        }
+    }
+
+    /// Runs the command.
+    pub fn run(self, cx: &mut App) {
+        info!(command = %self.label(), "Running command");
+        match self {
+            AppCommand::SwitchProvider(provider) => {
+                cx.update_global::<SystemTray, _>(|tray, cx| {
+                    tray.toggle_menu(Some(provider), cx);
+                });
+            }
+            AppCommand::Refresh(provider) => {
+                let usage = cx.global::<AppState>().usage.clone();
+                refresh_provider_async(provider, usage, cx);
+            }
+            AppCommand::RefreshAll => refresh_all(cx),
+            AppCommand::OpenSettings(pane) => windows::open_settings_pane(pane, cx),
+            AppCommand::CopySummary => copy_summary(cx),
+            AppCommand::AddNote => add_note(cx),
+            AppCommand::OpenHistory => windows::open_history(cx),
+            AppCommand::OpenTeam => windows::open_team(cx),
+            AppCommand::ShowDetails(provider) => windows::open_provider_detail(provider, cx),
+            AppCommand::OpenDashboard(provider) => {
+                if let Some(url) = dashboard_url(provider) {
+                    crate::menu::open_url(url);
                 }
             }
-            cx.notify();
-        });
+            AppCommand::SnoozeAlerts { provider, length } => {
+                snooze::snooze(provider, length, cx);
+            }
+            AppCommand::ToggleProvider { provider, .. } => {
+                let settings = cx.global::<AppState>().settings.clone();
+                settings.update(cx, |model, cx| {
+                    model.toggle_provider(provider);
+                    cx.notify();
+                });
+            }
+            AppCommand::Quit => quit(cx),
+        }
+    }
+}
+
+/// Lists every command available right now.
+pub fn available_commands(cx: &App) -> Vec<AppCommand> {
+    let state = cx.global::<AppState>();
+    let settings = state.settings.read(cx);
+    let enabled = state.enabled_providers(cx);
+
+    let mut commands = vec![AppCommand::RefreshAll];
+    commands.extend(enabled.iter().map(|&p| AppCommand::SwitchProvider(p)));
+    commands.extend(enabled.iter().map(|&p| AppCommand::Refresh(p)));
+    commands.extend(enabled.iter().map(|&p| AppCommand::ShowDetails(p)));
+    commands.extend(
+        enabled
+            .iter()
+            .filter(|&&p| dashboard_url(p).is_some())
+            .map(|&p| AppCommand::OpenDashboard(p)),
+    );
+    commands.extend(
+        SettingsPane::all()
+            .iter()
+            .map(|&pane| AppCommand::OpenSettings(pane)),
+    );
+    if settings.settings().session_quota_notifications_enabled {
+        for &provider in &enabled {
+            for length in [SnoozeLength::Hour, SnoozeLength::UntilReset] {
+                commands.push(AppCommand::SnoozeAlerts { provider, length });
+            }
+        }
+    }
+    commands.push(AppCommand::CopySummary);
+    commands.push(AppCommand::AddNote);
+    commands.push(AppCommand::OpenHistory);
+    if settings.team_leaderboard_enabled() {
+        commands.push(AppCommand::OpenTeam);
+    }
+    commands.extend(
+        ProviderKind::all()
+            .iter()
+            .map(|&provider| AppCommand::ToggleProvider {
+                provider,
+                enabled: settings.is_provider_enabled(provider),
+            }),
+    );
+    commands.push(AppCommand::Quit);
+    commands
+}
+
+/// Returns a provider's usage or billing page, if it has one.
+fn dashboard_url(provider: ProviderKind) -> Option<&'static str> {
+    ProviderRegistry::get(provider)?
+        .metadata
+        .dashboard_url
+        .as_deref()
+}
+
+/// Refreshes a provider asynchronously.
+fn refresh_provider_async(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut App) {
+    cx.spawn(async move |mut cx| {
+        crate::refresh::refresh_provider(provider, usage, &mut cx).await;
     })
     .detach();
 }
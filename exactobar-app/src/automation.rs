@@ -0,0 +1,283 @@
+//! URL commands for Shortcuts and AppleScript.
+//!
+//! The app handles `exactobar://` URLs, so automations can drive it with
+//! the Shortcuts "Open URLs" or "Open X-Callback URL" actions, or with
+//! `open location "exactobar://refresh"` in AppleScript:
+//!
+//! | URL | Action |
+//! |-----|--------|
+//! | `exactobar://refresh` | Refreshes all enabled providers |
+//! | `exactobar://refresh?provider=claude` | Refreshes one provider |
+//! | `exactobar://usage?provider=claude` | Returns a provider's usage (see below) |
+//! | `exactobar://settings?pane=advanced` | Opens settings, on a pane if given |
+//! | `exactobar://dashboard?provider=claude` | Opens a provider's dashboard in the browser |
+//! | `exactobar://copy-summary` | Copies the usage summary to the clipboard |
+//! | `exactobar://snooze?provider=claude&for=1h` | Snoozes a provider's alerts (`for=1h` or `reset`) |
+//!
+//! Commands can also be written as x-callback-urls, like
+//! `exactobar://x-callback-url/usage?provider=claude`. When the command
+//! succeeds, its `x-success` URL is opened; `usage` adds `remaining`,
+//! `used` and `resets_at` to it, which Shortcuts hands to the next action.
+//! When it fails, `x-error` is opened with an `errorMessage`.
+//!
+//! Scripts that only read usage can also run the CLI, e.g.
+//! `do shell script "exactobar prompt -p claude -t '{remaining}'"`.
+//!
+//! macOS only routes the scheme to the app if the bundle's `Info.plist`
+//! lists `exactobar` under `CFBundleURLTypes`.
+
+use exactobar_core::ProviderKind;
+use exactobar_providers::ProviderRegistry;
+use futures::StreamExt;
+use futures::channel::mpsc::UnboundedReceiver;
+use gpui::*;
+use reqwest::Url;
+use tracing::{info, warn};
+
+use crate::actions::AppCommand;
+use crate::menu::open_url;
+use crate::snooze::SnoozeLength;
+use crate::state::AppState;
+use crate::windows::settings::SettingsPane;
+
+/// URL scheme handled by the app.
+pub const URL_SCHEME: &str = "exactobar";
+
+/// Host of x-callback-url style commands.
+const X_CALLBACK_HOST: &str = "x-callback-url";
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// What a URL asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlAction {
+    /// Run a command palette command.
+    Run(AppCommand),
+    /// Return a provider's usage to the caller.
+    Usage(ProviderKind),
+}
+
+/// A parsed `exactobar://` URL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlCommand {
+    pub action: UrlAction,
+    /// Opened with the results when the command succeeds.
+    pub x_success: Option<Url>,
+    /// Opened with an `errorMessage` when it fails.
+    pub x_error: Option<Url>,
+}
+
+impl UrlCommand {
+    /// Parses an `exactobar://` URL.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+        if url.scheme() != URL_SCHEME {
+            return Err(format!("Not an {URL_SCHEME}:// URL"));
+        }
+
+        let name = match url.host_str() {
+            Some(X_CALLBACK_HOST) => url.path().trim_matches('/'),
+            Some(host) => host,
+            None => "",
+        };
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        };
+        let provider = param("provider")
+            .map(|name| {
+                ProviderRegistry::get_by_cli_name(&name)
+                    .map(|desc| desc.id)
+                    .ok_or_else(|| format!("Unknown provider: {name}"))
+            })
+            .transpose()?;
+
+        let action = match name {
+            "refresh" => {
+                UrlAction::Run(provider.map_or(AppCommand::RefreshAll, AppCommand::Refresh))
+            }
+            "usage" => UrlAction::Usage(provider.ok_or("usage needs a provider")?),
+            "settings" => {
+                let pane = match param("pane") {
+                    Some(pane) => *SettingsPane::all()
+                        .iter()
+                        .find(|p| p.title().eq_ignore_ascii_case(&pane))
+                        .ok_or_else(|| format!("Unknown settings pane: {pane}"))?,
+                    None => SettingsPane::General,
+                };
+                UrlAction::Run(AppCommand::OpenSettings(pane))
+            }
+            "dashboard" => UrlAction::Run(AppCommand::OpenDashboard(
+                provider.ok_or("dashboard needs a provider")?,
+            )),
+            "copy-summary" => UrlAction::Run(AppCommand::CopySummary),
+            "snooze" => {
+                let length = match param("for") {
+                    Some(value) => SnoozeLength::parse(&value)
+                        .ok_or_else(|| format!("Unknown snooze length: {value}"))?,
+                    None => SnoozeLength::Hour,
+                };
+                UrlAction::Run(AppCommand::SnoozeAlerts {
+                    provider: provider.ok_or("snooze needs a provider")?,
+                    length,
+                })
+            }
+            "" => return Err("Missing command".to_string()),
+            other => return Err(format!("Unknown command: {other}")),
+        };
+
+        let callback = |key: &str| param(key).and_then(|u| Url::parse(&u).ok());
+        Ok(Self {
+            action,
+            x_success: callback("x-success"),
+            x_error: callback("x-error"),
+        })
+    }
+
+    /// Runs the command and opens its callback URL, if it has one.
+    pub fn run(self, cx: &mut App) {
+        let result = match self.action {
+            UrlAction::Run(command) => {
+                command.run(cx);
+                Ok(Vec::new())
+            }
+            UrlAction::Usage(provider) => usage_params(provider, cx),
+        };
+
+        let callback = match result {
+            Ok(params) => self.x_success.map(|url| with_params(url, &params)),
+            Err(e) => {
+                warn!(error = %e, "URL command failed");
+                self.x_error
+                    .map(|url| with_params(url, &[("errorMessage", e)]))
+            }
+        };
+        if let Some(url) = callback {
+            open_url(url.as_str());
+        }
+    }
+}
+
+/// Returns a provider's latest usage as callback parameters.
+fn usage_params(provider: ProviderKind, cx: &App) -> Result<Vec<(&'static str, String)>, String> {
+    let snapshot = cx
+        .global::<AppState>()
+        .get_snapshot(provider, cx)
+        .ok_or_else(|| format!("No usage for {} yet", provider.display_name()))?;
+    let window = snapshot
+        .primary
+        .as_ref()
+        .ok_or_else(|| format!("{} reported no usage window", provider.display_name()))?;
+
+    let mut params = vec![
+        ("remaining", format!("{:.0}", window.remaining_percent())),
+        ("used", format!("{:.0}", window.used_percent)),
+    ];
+    if let Some(resets_at) = window.resets_at {
+        params.push(("resets_at", resets_at.to_rfc3339()));
+    }
+    Ok(params)
+}
+
+/// Appends query parameters to a callback URL.
+fn with_params(mut url: Url, params: &[(&str, String)]) -> Url {
+    if !params.is_empty() {
+        url.query_pairs_mut().extend_pairs(params);
+    }
+    url
+}
+
+// ============================================================================
+// Listener
+// ============================================================================
+
+/// Runs the commands in URLs the app is asked to open.
+pub fn listen(mut urls: UnboundedReceiver<Vec<String>>, cx: &mut App) {
+    cx.spawn(async move |cx| {
+        while let Some(urls) = urls.next().await {
+            let _ = cx.update(|cx| {
+                for url in urls {
+                    match UrlCommand::parse(&url) {
+                        Ok(command) => {
+                            info!(%url, "Running URL command");
+                            command.run(cx);
+                        }
+                        Err(e) => warn!(%url, error = %e, "Ignoring URL"),
+                    }
+                }
+            });
+        }
+    })
+    .detach();
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let command = UrlCommand::parse("exactobar://refresh").unwrap();
+        assert_eq!(command.action, UrlAction::Run(AppCommand::RefreshAll));
+        assert_eq!(command.x_success, None);
+
+        let command = UrlCommand::parse("exactobar://refresh?provider=claude").unwrap();
+        assert_eq!(
+            command.action,
+            UrlAction::Run(AppCommand::Refresh(ProviderKind::Claude))
+        );
+
+        let command = UrlCommand::parse("exactobar://settings?pane=Advanced").unwrap();
+        assert_eq!(
+            command.action,
+            UrlAction::Run(AppCommand::OpenSettings(SettingsPane::Advanced))
+        );
+
+        let command = UrlCommand::parse("exactobar://dashboard?provider=cursor").unwrap();
+        assert_eq!(
+            command.action,
+            UrlAction::Run(AppCommand::OpenDashboard(ProviderKind::Cursor))
+        );
+
+        let command = UrlCommand::parse("exactobar://snooze?provider=claude&for=reset").unwrap();
+        assert_eq!(
+            command.action,
+            UrlAction::Run(AppCommand::SnoozeAlerts {
+                provider: ProviderKind::Claude,
+                length: SnoozeLength::UntilReset,
+            })
+        );
+
+        assert!(UrlCommand::parse("exactobar://usage").is_err());
+        assert!(UrlCommand::parse("exactobar://snooze?provider=claude&for=1y").is_err());
+        assert!(UrlCommand::parse("exactobar://dashboard").is_err());
+        assert!(UrlCommand::parse("exactobar://refresh?provider=nope").is_err());
+        assert!(UrlCommand::parse("exactobar://launch").is_err());
+        assert!(UrlCommand::parse("https://example.com/refresh").is_err());
+    }
+
+    #[test]
+    fn test_parse_x_callback_url() {
+        let command = UrlCommand::parse(
+            "exactobar://x-callback-url/usage?provider=codex&x-success=shortcuts%3A%2F%2Fx-callback-url%2Fdone%3Fid%3D1",
+        )
+        .unwrap();
+        assert_eq!(command.action, UrlAction::Usage(ProviderKind::Codex));
+
+        let success = with_params(
+            command.x_success.unwrap(),
+            &[("remaining", "72".to_string())],
+        );
+        assert_eq!(
+            success.as_str(),
+            "shortcuts://x-callback-url/done?id=1&remaining=72"
+        );
+    }
+}
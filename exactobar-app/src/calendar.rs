@@ -0,0 +1,228 @@
+//! Calendar awareness for quiet refreshes during meetings.
+//!
+//! When "Quiet During Meetings" is on, the app asks EventKit whether an
+//! event marked busy is happening now. While one is, quota and event
+//! notifications are held and background refreshes slow to
+//! [`MEETING_REFRESH_INTERVAL`]; the regular cadence resumes when the event
+//! ends. A threshold crossed during a meeting is notified on the first
+//! refresh after it, since the notification tracker isn't advanced while
+//! quiet.
+//!
+//! Calendar access is requested the first time the setting is turned on.
+//! The bundle's `Info.plist` needs `NSCalendarsFullAccessUsageDescription`
+//! (and `NSCalendarsUsageDescription` before macOS 14) for the prompt to
+//! appear. Without access, or off macOS, no meeting is ever in progress.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, info};
+
+/// Slowest background refresh cadence while a meeting is in progress.
+pub const MEETING_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How long a calendar lookup is reused, so refreshing several providers
+/// doesn't query EventKit for each.
+const LOOKUP_TTL: Duration = Duration::from_secs(60);
+
+/// Last lookup: when it ran and when the busy event it found ends.
+static LAST_LOOKUP: Mutex<Option<(Instant, Option<DateTime<Utc>>)>> = Mutex::new(None);
+
+/// Returns when the current busy event ends, or `None` if there isn't one.
+///
+/// All-day events, and events marked free or tentative, don't count.
+pub fn busy_until() -> Option<DateTime<Utc>> {
+    let mut last = LAST_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((looked_up_at, until)) = *last {
+        if looked_up_at.elapsed() < LOOKUP_TTL {
+            return until.filter(|end| *end > Utc::now());
+        }
+    }
+
+    let until = platform::current_busy_event_end();
+    debug!(?until, "Checked calendar for a busy event");
+    *last = Some((Instant::now(), until));
+    until
+}
+
+/// Whether a busy event is in progress.
+pub fn in_meeting() -> bool {
+    busy_until().is_some()
+}
+
+/// How long to wait before the next background refresh: the regular
+/// `cadence`, stretched to [`MEETING_REFRESH_INTERVAL`] during a meeting
+/// but no later than just after it ends.
+pub fn refresh_delay(cadence: Duration, busy_until: Option<DateTime<Utc>>) -> Duration {
+    let Some(end) = busy_until else {
+        return cadence;
+    };
+    let until_end = (end - Utc::now())
+        .to_std()
+        .unwrap_or_default()
+        .saturating_add(Duration::from_secs(1));
+    cadence.max(MEETING_REFRESH_INTERVAL.min(until_end))
+}
+
+/// Asks for calendar access if it hasn't been granted or denied yet.
+pub fn request_access() {
+    info!("Requesting calendar access");
+    platform::request_access();
+    forget_lookup();
+}
+
+/// Drops the cached lookup, e.g. after access changes.
+pub fn forget_lookup() {
+    *LAST_LOOKUP.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block::ConcreteBlock;
+    use chrono::{DateTime, Utc};
+    use cocoa::base::{BOOL, NO, id, nil};
+    use objc::runtime::Class;
+    use objc::{class, msg_send, sel, sel_impl};
+    use tracing::{info, warn};
+
+    /// `EKEntityTypeEvent`.
+    const ENTITY_TYPE_EVENT: u64 = 0;
+    /// `EKAuthorizationStatusNotDetermined`.
+    const STATUS_NOT_DETERMINED: i64 = 0;
+    /// `EKAuthorizationStatusAuthorized` / `EKAuthorizationStatusFullAccess`.
+    const STATUS_FULL_ACCESS: i64 = 3;
+    /// `EKEventAvailabilityFree`.
+    const AVAILABILITY_FREE: i64 = 1;
+    /// `EKEventAvailabilityTentative`.
+    const AVAILABILITY_TENTATIVE: i64 = 2;
+
+    fn event_store_class() -> Option<&'static Class> {
+        Class::get("EKEventStore")
+    }
+
+    fn authorization_status() -> Option<i64> {
+        let class = event_store_class()?;
+        Some(unsafe { msg_send![class, authorizationStatusForEntityType: ENTITY_TYPE_EVENT] })
+    }
+
+    pub fn request_access() {
+        if authorization_status() != Some(STATUS_NOT_DETERMINED) {
+            return;
+        }
+        let Some(class) = event_store_class() else {
+            return;
+        };
+
+        let completion = ConcreteBlock::new(|granted: BOOL, _error: id| {
+            info!(granted = granted != NO, "Calendar access answered");
+            super::forget_lookup();
+        })
+        .copy();
+
+        unsafe {
+            // The store must outlive the request, so it's never released.
+            let store: id = msg_send![class, new];
+            let full_access: BOOL = msg_send![
+                store,
+                respondsToSelector: sel!(requestFullAccessToEventsWithCompletion:)
+            ];
+            if full_access != NO {
+                let _: () = msg_send![store, requestFullAccessToEventsWithCompletion: &*completion];
+            } else {
+                let _: () = msg_send![
+                    store,
+                    requestAccessToEntityType: ENTITY_TYPE_EVENT
+                    completion: &*completion
+                ];
+            }
+        }
+    }
+
+    pub fn current_busy_event_end() -> Option<DateTime<Utc>> {
+        if authorization_status()? != STATUS_FULL_ACCESS {
+            return None;
+        }
+        let class = event_store_class()?;
+
+        unsafe {
+            let store: id = msg_send![class, new];
+            if store == nil {
+                warn!("Failed to create an EventKit store");
+                return None;
+            }
+
+            let start: id = msg_send![class!(NSDate), date];
+            let end: id = msg_send![class!(NSDate), dateWithTimeIntervalSinceNow: 1.0f64];
+            let predicate: id = msg_send![
+                store,
+                predicateForEventsWithStartDate: start
+                endDate: end
+                calendars: nil
+            ];
+            let events: id = msg_send![store, eventsMatchingPredicate: predicate];
+
+            let count: usize = if events == nil {
+                0
+            } else {
+                msg_send![events, count]
+            };
+            let mut busy_until: Option<f64> = None;
+            for i in 0..count {
+                let event: id = msg_send![events, objectAtIndex: i];
+                let all_day: BOOL = msg_send![event, isAllDay];
+                let availability: i64 = msg_send![event, availability];
+                if all_day != NO
+                    || availability == AVAILABILITY_FREE
+                    || availability == AVAILABILITY_TENTATIVE
+                {
+                    continue;
+                }
+                let end_date: id = msg_send![event, endDate];
+                let end: f64 = msg_send![end_date, timeIntervalSince1970];
+                busy_until = Some(busy_until.map_or(end, |until| until.max(end)));
+            }
+
+            let _: () = msg_send![store, release];
+            busy_until.and_then(|secs| DateTime::from_timestamp(secs as i64, 0))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use chrono::{DateTime, Utc};
+
+    pub fn request_access() {}
+
+    pub fn current_busy_event_end() -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_delay() {
+        let cadence = Duration::from_secs(120);
+        assert_eq!(refresh_delay(cadence, None), cadence);
+
+        // Long meeting: slow to the meeting interval
+        let later = Utc::now() + chrono::Duration::hours(1);
+        assert_eq!(
+            refresh_delay(cadence, Some(later)),
+            MEETING_REFRESH_INTERVAL
+        );
+
+        // Meeting ends soon: refresh right after it
+        let soon = Utc::now() + chrono::Duration::minutes(5);
+        let delay = refresh_delay(cadence, Some(soon));
+        assert!(delay > Duration::from_secs(4 * 60) && delay <= Duration::from_secs(5 * 60 + 1));
+
+        // Never refresh faster than the regular cadence
+        let ending = Utc::now() + chrono::Duration::seconds(10);
+        assert_eq!(refresh_delay(cadence, Some(ending)), cadence);
+    }
+}
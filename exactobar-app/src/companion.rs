@@ -0,0 +1,43 @@
+//! Companion browser extension endpoint.
+//!
+//! Runs the localhost endpoint from `exactobar_fetch::host::companion` on the
+//! fetch Tokio runtime so that cookies pushed by the extension land in the
+//! same process-wide store the web strategies read from.
+
+use std::sync::Mutex;
+
+use exactobar_fetch::CompanionServer;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::refresh::tokio_runtime;
+
+/// Handle of the running endpoint task, if any.
+static ENDPOINT_TASK: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Starts (or restarts) the companion endpoint on `127.0.0.1:<port>`.
+pub fn start(port: u16, token: String) {
+    stop();
+
+    let handle = tokio_runtime().spawn(async move {
+        match CompanionServer::bind(port, token).await {
+            Ok(server) => server.serve().await,
+            Err(e) => error!(port, error = %e, "Failed to bind companion endpoint"),
+        }
+    });
+
+    if let Ok(mut task) = ENDPOINT_TASK.lock() {
+        *task = Some(handle);
+    }
+}
+
+/// Stops the companion endpoint and forgets any pushed cookies.
+pub fn stop() {
+    if let Ok(mut task) = ENDPOINT_TASK.lock() {
+        if let Some(handle) = task.take() {
+            handle.abort();
+            exactobar_fetch::host::companion::clear_cookies();
+            info!("Companion endpoint stopped");
+        }
+    }
+}
@@ -23,7 +23,9 @@ impl ProviderCard {
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
         let is_refreshing = state.is_provider_refreshing(provider, cx);
-        let error = state.get_error(provider, cx);
+        let error = state
+            .get_error(provider, cx)
+            .filter(|_| state.is_failing(provider, cx));
         let descriptor = ProviderRegistry::get(provider);
 
         let name = descriptor
@@ -109,7 +111,7 @@ impl IntoElement for ProviderCard {
                 let mut usage_div = div().mt(px(12.0)).flex().flex_col().gap(px(8.0));
 
                 if let Some(primary) = &snap.primary {
-                    let remaining = 100.0 - primary.used_percent;
+                    let remaining = primary.remaining_percent();
                     usage_div = usage_div.child(
                         div()
                             .flex()
@@ -137,7 +139,7 @@ impl IntoElement for ProviderCard {
                 }
 
                 if let Some(secondary) = &snap.secondary {
-                    let remaining = 100.0 - secondary.used_percent;
+                    let remaining = secondary.remaining_percent();
                     usage_div = usage_div.child(
                         div()
                             .flex()
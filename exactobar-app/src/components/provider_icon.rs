@@ -37,6 +37,9 @@ impl ProviderIcon {
             ProviderKind::MiniMax => hsla(195.0 / 360.0, 1.0, 0.50, 1.0),
             ProviderKind::Antigravity => hsla(282.0 / 360.0, 1.0, 0.41, 1.0),
             ProviderKind::Synthetic => hsla(168.0 / 360.0, 1.0, 0.40, 1.0), // Teal
+            ProviderKind::GitHubModels => hsla(262.0 / 360.0, 0.69, 0.59, 1.0), // Purple
+            ProviderKind::HuggingFace => hsla(45.0 / 360.0, 1.0, 0.56, 1.0), // Yellow
+            ProviderKind::Replicate => hsla(7.0 / 360.0, 0.83, 0.57, 1.0), // Red
         }
     }
 
@@ -55,6 +58,9 @@ impl ProviderIcon {
             ProviderKind::MiniMax => "M",
             ProviderKind::Antigravity => "∞",
             ProviderKind::Synthetic => "S",
+            ProviderKind::GitHubModels => "◈",
+            ProviderKind::HuggingFace => "H",
+            ProviderKind::Replicate => "R",
         }
     }
 }
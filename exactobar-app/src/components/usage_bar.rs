@@ -2,6 +2,8 @@
 
 use gpui::*;
 
+use crate::theme::LevelColors;
+
 /// Progress bar showing usage percentage.
 pub struct UsageBar {
     /// Percentage remaining (0-100).
@@ -31,12 +33,13 @@ impl UsageBar {
     }
 
     fn fill_color(&self) -> Hsla {
+        let colors = LevelColors::current();
         if self.percent > 50.0 {
-            hsla(142.0 / 360.0, 0.71, 0.45, 1.0) // Green
+            colors.good
         } else if self.percent > 20.0 {
-            hsla(38.0 / 360.0, 0.92, 0.50, 1.0) // Yellow
+            colors.warning
         } else {
-            hsla(0.0, 0.84, 0.60, 1.0) // Red
+            colors.danger
         }
     }
 }
@@ -0,0 +1,239 @@
+//! Background token cost scans.
+//!
+//! Scanning local logs can take a while on first run (or after the index
+//! is discarded), so scans run on a blocking thread pool instead of the UI
+//! thread. Progress is streamed back over a channel into the
+//! [`UsageModel`], and a scan can be cancelled from the menu card.
+//!
+//! Once a provider has been scanned, a [`LiveCostWatcher`] follows its logs
+//! so today's spend updates as soon as the CLI writes new usage.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use exactobar_core::ProviderKind;
+use exactobar_providers::cost::{self, ScanProgress};
+use exactobar_providers::{LiveCostWatcher, LogIndex, PricingTable, ProviderRegistry};
+use exactobar_store::CostUsageSnapshot;
+use gpui::*;
+use tracing::{debug, info, warn};
+
+use crate::state::{AppState, UsageModel};
+
+/// Days of history shown in the menu card.
+const COST_DAYS: u32 = 30;
+
+/// Running log watchers by provider.
+static LIVE_WATCHERS: OnceLock<Mutex<HashMap<ProviderKind, LiveCostWatcher>>> = OnceLock::new();
+
+fn live_watchers() -> &'static Mutex<HashMap<ProviderKind, LiveCostWatcher>> {
+    LIVE_WATCHERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn is_live(provider: ProviderKind) -> bool {
+    live_watchers()
+        .lock()
+        .is_ok_and(|watchers| watchers.contains_key(&provider))
+}
+
+// ============================================================================
+// Scan State
+// ============================================================================
+
+/// A cost scan in progress.
+#[derive(Debug, Clone)]
+pub struct CostScan {
+    /// Files scanned so far.
+    pub files_done: usize,
+    /// Files to scan in total (zero until the logs are listed).
+    pub files_total: usize,
+    /// Set to stop the scan early.
+    cancel: Arc<AtomicBool>,
+}
+
+impl CostScan {
+    fn new(cancel: Arc<AtomicBool>) -> Self {
+        Self {
+            files_done: 0,
+            files_total: 0,
+            cancel,
+        }
+    }
+
+    /// Asks the scan to stop after the current file.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+// ============================================================================
+// Background Scan
+// ============================================================================
+
+/// Returns true if the provider has local logs to compute costs from.
+pub fn supports_cost(provider: ProviderKind) -> bool {
+    ProviderRegistry::get(provider)
+        .is_some_and(|d| d.token_cost.supports_token_cost && d.token_cost.log_directory.is_some())
+}
+
+/// Starts a background cost scan for a provider.
+///
+/// Does nothing if the provider has no cost logs, a scan is already
+/// running, or its logs are already being watched.
+pub fn spawn_cost_scan(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut App) {
+    if !supports_cost(provider) || usage.read(cx).cost_scan(provider).is_some() || is_live(provider)
+    {
+        return;
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    usage.update(cx, |model, cx| {
+        model.start_cost_scan(provider, CostScan::new(cancel.clone()));
+        cx.notify();
+    });
+
+    let (progress_tx, progress_rx) = smol::channel::unbounded::<ScanProgress>();
+
+    cx.spawn(async move |mut cx| {
+        debug!(provider = ?provider, "Starting cost scan");
+
+        // The sender is dropped when the scan finishes, ending the loop below
+        let scan = smol::unblock(move || {
+            scan_blocking(provider, &cancel, &mut |progress| {
+                let _ = progress_tx.try_send(progress);
+            })
+        });
+
+        while let Ok(progress) = progress_rx.recv().await {
+            let _ = cx.update_entity(&usage, |model, cx| {
+                model.set_cost_scan_progress(provider, progress.files_done, progress.files_total);
+                cx.notify();
+            });
+        }
+
+        let result = scan.await;
+        let scanned = matches!(result, Ok(Some(_)));
+
+        let _ = cx.update_entity(&usage, |model, cx| {
+            model.finish_cost_scan(provider);
+            match result {
+                Ok(Some(snapshot)) => model.set_cost(provider, snapshot),
+                Ok(None) => debug!(provider = ?provider, "No cost logs found"),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                    info!(provider = ?provider, "Cost scan cancelled");
+                }
+                Err(e) => warn!(provider = ?provider, error = %e, "Cost scan failed"),
+            }
+            cx.notify();
+        });
+
+        // Follow the logs from here on
+        if scanned {
+            let _ = cx.update(|cx| start_live_watcher(provider, usage, cx));
+        }
+    })
+    .detach();
+}
+
+/// Starts following a provider's logs, updating its cost as they change.
+fn start_live_watcher(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut App) {
+    let Some(desc) = ProviderRegistry::get(provider) else {
+        return;
+    };
+
+    let (snapshot_tx, snapshot_rx) = smol::channel::unbounded::<CostUsageSnapshot>();
+    let watcher = LiveCostWatcher::spawn(
+        &desc.token_cost,
+        LogIndex::default_path(desc.cli_name()),
+        COST_DAYS,
+        PricingTable::load_default(),
+        move |snapshot| {
+            let _ = snapshot_tx.try_send(snapshot);
+        },
+    );
+
+    match watcher {
+        Ok(Some(watcher)) => {
+            if let Ok(mut watchers) = live_watchers().lock() {
+                watchers.insert(provider, watcher);
+            }
+        }
+        Ok(None) => return,
+        Err(e) => {
+            warn!(provider = ?provider, error = %e, "Failed to watch cost logs");
+            return;
+        }
+    }
+
+    // Ends when the watcher is dropped and its sender with it
+    cx.spawn(async move |mut cx| {
+        while let Ok(snapshot) = snapshot_rx.recv().await {
+            let _ = cx.update_entity(&usage, |model, cx| {
+                model.set_cost(provider, snapshot);
+                cx.notify();
+            });
+        }
+    })
+    .detach();
+}
+
+/// Stops following a provider's logs.
+fn stop_live_watcher(provider: ProviderKind) {
+    if let Ok(mut watchers) = live_watchers().lock() {
+        watchers.remove(&provider);
+    }
+}
+
+/// Starts a background cost scan if cost tracking is enabled in settings.
+pub fn scan_if_enabled(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut App) {
+    let enabled = cx
+        .global::<AppState>()
+        .settings
+        .read(cx)
+        .settings()
+        .cost_usage_enabled;
+    if enabled {
+        spawn_cost_scan(provider, usage, cx);
+    } else {
+        stop_live_watcher(provider);
+    }
+}
+
+/// Scans a provider's logs and saves the index (runs off the UI thread).
+fn scan_blocking(
+    provider: ProviderKind,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(ScanProgress),
+) -> std::io::Result<Option<CostUsageSnapshot>> {
+    let Some(desc) = ProviderRegistry::get(provider) else {
+        return Ok(None);
+    };
+
+    let pricing = PricingTable::load_default();
+    let index_path = LogIndex::default_path(desc.cli_name());
+    let mut index = LogIndex::load(&index_path);
+
+    let result = cost::scan_provider_with_progress(
+        &desc.token_cost,
+        &mut index,
+        COST_DAYS,
+        &pricing,
+        cancel,
+        progress,
+    );
+
+    // Saved even when cancelled so the next scan resumes where this one stopped
+    if !matches!(result, Ok(None)) {
+        if let Err(e) = index.save(&index_path) {
+            warn!(provider = ?provider, error = %e, "Failed to save cost index");
+        }
+    }
+
+    result
+}
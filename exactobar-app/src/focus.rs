@@ -0,0 +1,101 @@
+//! macOS Focus (Do Not Disturb) awareness.
+//!
+//! When "Respect Focus" is on and a Focus is active, warnings and event
+//! notifications are held; only critical alerts for the providers listed in
+//! `focus_overrides` get through. Held alerts are delivered on the first
+//! refresh after the Focus ends, since the notification tracker isn't
+//! advanced for them.
+//!
+//! macOS has no public API for another app's view of the Focus state, so
+//! this reads the assertions file Control Center writes when a Focus is
+//! turned on by hand, `~/Library/DoNotDisturb/DB/Assertions.json`. Reading
+//! it needs Full Disk Access; without it, or off macOS, no Focus is ever
+//! active. Focuses started by a schedule aren't recorded there.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tracing::debug;
+
+/// How long a Focus lookup is reused, so refreshing several providers
+/// doesn't read the file for each.
+const LOOKUP_TTL: Duration = Duration::from_secs(30);
+
+/// Last lookup: when it ran and whether a Focus was active.
+static LAST_LOOKUP: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// Whether a Focus is active.
+pub fn is_active() -> bool {
+    let mut last = LAST_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((looked_up_at, active)) = *last {
+        if looked_up_at.elapsed() < LOOKUP_TTL {
+            return active;
+        }
+    }
+
+    let active = assertions_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .is_some_and(|json| has_active_assertion(&json));
+    debug!(active, "Checked Focus state");
+    *last = Some((Instant::now(), active));
+    active
+}
+
+/// Path of the Focus assertions file.
+fn assertions_path() -> Option<PathBuf> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    let home = std::env::var_os("HOME")?;
+    Some(PathBuf::from(home).join("Library/DoNotDisturb/DB/Assertions.json"))
+}
+
+#[derive(Deserialize)]
+struct Assertions {
+    #[serde(default)]
+    data: Vec<AssertionStore>,
+}
+
+#[derive(Deserialize)]
+struct AssertionStore {
+    #[serde(default, rename = "storeAssertionRecords")]
+    records: Vec<serde_json::Value>,
+}
+
+/// Whether an assertions file records a Focus that's on.
+fn has_active_assertion(json: &str) -> bool {
+    serde_json::from_str::<Assertions>(json).is_ok_and(|assertions| {
+        assertions
+            .data
+            .iter()
+            .any(|store| !store.records.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_active_assertion() {
+        let active = r#"{
+            "data": [{
+                "storeAssertionRecords": [{
+                    "assertionDetails": {
+                        "assertionDetailsModeIdentifier": "com.apple.donotdisturb.mode.default"
+                    },
+                    "assertionStartDateTimestamp": 750000000.0
+                }]
+            }],
+            "header": {"timestamp": 750000000.0}
+        }"#;
+        assert!(has_active_assertion(active));
+
+        let inactive = r#"{"data": [{"storeAssertionRecords": []}], "header": {}}"#;
+        assert!(!has_active_assertion(inactive));
+        assert!(!has_active_assertion(r#"{"data": [{}]}"#));
+        assert!(!has_active_assertion("not json"));
+    }
+}
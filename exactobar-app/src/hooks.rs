@@ -0,0 +1,330 @@
+//! Shell hooks for automations.
+//!
+//! Users can configure shell commands in settings that run on usage events:
+//!
+//! - `on_threshold` - usage crossed the hook's threshold (e.g. 95% used)
+//! - `on_reset` - usage dropped back after the quota window reset
+//! - `on_fetch_error` - a provider started failing to fetch, past its
+//!   failure tolerance
+//!
+//! Commands run through `sh -c` on a `smol::unblock()` thread with these
+//! environment variables set:
+//!
+//! | Variable | Value |
+//! |----------|-------|
+//! | `EXACTOBAR_EVENT` | `on_threshold`, `on_reset` or `on_fetch_error` |
+//! | `EXACTOBAR_PROVIDER` | CLI name, e.g. `claude` |
+//! | `EXACTOBAR_PROVIDER_NAME` | Display name, e.g. `Claude` |
+//! | `EXACTOBAR_USED_PERCENT` | Primary window usage (not for errors) |
+//! | `EXACTOBAR_REMAINING_PERCENT` | Primary window remaining (not for errors) |
+//! | `EXACTOBAR_RESETS_AT` | RFC 3339 reset time, when known |
+//! | `EXACTOBAR_THRESHOLD` | The hook's threshold (`on_threshold` only) |
+//! | `EXACTOBAR_PREVIOUS_USED_PERCENT` | Usage before the reset (`on_reset` only) |
+//! | `EXACTOBAR_ERROR` | Fetch error message (`on_fetch_error` only) |
+
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use exactobar_core::{ProviderKind, UsageSnapshot};
+use exactobar_store::{HookEvent, ShellHook};
+use gpui::App;
+use tracing::{debug, info, warn};
+
+use crate::state::AppState;
+
+/// Percentage points usage must drop by to count as a reset.
+///
+/// Some providers report slightly lower usage between refreshes as old
+/// requests age out, which shouldn't fire `on_reset`.
+const RESET_DROP: f64 = 5.0;
+
+/// Global hook tracker, remembering usage between refreshes.
+static HOOK_TRACKER: once_cell::sync::Lazy<Mutex<HookTracker>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HookTracker::default()));
+
+// ============================================================================
+// Hook Tracker
+// ============================================================================
+
+/// A hook due to run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookRun {
+    /// Command line passed to `sh -c`.
+    pub command: String,
+    /// Environment describing the event.
+    pub env: Vec<(&'static str, String)>,
+}
+
+/// Tracks usage between refreshes to detect hook events.
+#[derive(Default)]
+pub struct HookTracker {
+    /// Last primary usage per provider.
+    last_used: HashMap<ProviderKind, f64>,
+    /// Providers whose last fetch failed.
+    failing: HashSet<ProviderKind>,
+}
+
+impl HookTracker {
+    /// Records a fetch result and returns the hooks it triggers.
+    pub fn observe(
+        &mut self,
+        provider: ProviderKind,
+        result: Result<&UsageSnapshot, &str>,
+        hooks: &[ShellHook],
+    ) -> Vec<HookRun> {
+        let snapshot = match result {
+            Ok(snapshot) => snapshot,
+            Err(error) => {
+                // Only the first failure in a row runs hooks
+                if !self.failing.insert(provider) {
+                    return Vec::new();
+                }
+                return runs(hooks, provider, HookEvent::OnFetchError, |env| {
+                    env.push(("EXACTOBAR_ERROR", error.to_string()));
+                });
+            }
+        };
+
+        self.failing.remove(&provider);
+        let Some(window) = snapshot.primary.as_ref() else {
+            return Vec::new();
+        };
+        let used = window.used_percent;
+        let previous = self.last_used.insert(provider, used);
+
+        let usage_env = |env: &mut Vec<(&'static str, String)>| {
+            env.push(("EXACTOBAR_USED_PERCENT", format!("{used:.1}")));
+            env.push((
+                "EXACTOBAR_REMAINING_PERCENT",
+                format!("{:.1}", window.remaining_percent()),
+            ));
+            if let Some(resets_at) = window.resets_at {
+                env.push(("EXACTOBAR_RESETS_AT", resets_at.to_rfc3339()));
+            }
+        };
+
+        let mut due: Vec<HookRun> = hooks
+            .iter()
+            .filter(|hook| hook.matches(provider, HookEvent::OnThreshold))
+            .filter(|hook| used >= hook.threshold && previous.is_none_or(|p| p < hook.threshold))
+            .map(|hook| {
+                let mut run = HookRun::new(hook, provider, HookEvent::OnThreshold);
+                usage_env(&mut run.env);
+                run.env
+                    .push(("EXACTOBAR_THRESHOLD", format!("{:.1}", hook.threshold)));
+                run
+            })
+            .collect();
+
+        if let Some(previous) = previous.filter(|p| used + RESET_DROP <= *p) {
+            due.extend(runs(hooks, provider, HookEvent::OnReset, |env| {
+                usage_env(env);
+                env.push(("EXACTOBAR_PREVIOUS_USED_PERCENT", format!("{previous:.1}")));
+            }));
+        }
+
+        due
+    }
+}
+
+impl HookRun {
+    fn new(hook: &ShellHook, provider: ProviderKind, event: HookEvent) -> Self {
+        Self {
+            command: hook.command.clone(),
+            env: vec![
+                ("EXACTOBAR_EVENT", event.as_str().to_string()),
+                ("EXACTOBAR_PROVIDER", provider.cli_name().to_string()),
+                (
+                    "EXACTOBAR_PROVIDER_NAME",
+                    provider.display_name().to_string(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Builds runs for every hook matching the event, adding event details.
+fn runs(
+    hooks: &[ShellHook],
+    provider: ProviderKind,
+    event: HookEvent,
+    details: impl Fn(&mut Vec<(&'static str, String)>),
+) -> Vec<HookRun> {
+    hooks
+        .iter()
+        .filter(|hook| hook.matches(provider, event))
+        .map(|hook| {
+            let mut run = HookRun::new(hook, provider, event);
+            details(&mut run.env);
+            run
+        })
+        .collect()
+}
+
+// ============================================================================
+// Running Hooks
+// ============================================================================
+
+/// Runs the hooks triggered by a fetch result.
+pub fn run_for_fetch(provider: ProviderKind, result: Result<&UsageSnapshot, &str>, cx: &App) {
+    let state = cx.global::<AppState>();
+    let settings = state.settings.read(cx).settings();
+
+    // Failures within the provider's tolerance aren't seen yet, so
+    // `on_fetch_error` runs once it's exceeded
+    if result.is_err() && !state.is_failing(provider, cx) {
+        return;
+    }
+
+    let due = match HOOK_TRACKER.lock() {
+        Ok(mut tracker) => tracker.observe(provider, result, &settings.hooks),
+        Err(_) => return,
+    };
+
+    for run in due {
+        spawn_hook(run);
+    }
+}
+
+/// Runs a hook in the background, logging failures.
+fn spawn_hook(run: HookRun) {
+    info!(command = %run.command, "Running shell hook");
+
+    smol::unblock(move || {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(&run.command)
+            .envs(run.env.iter().map(|(k, v)| (*k, v.as_str())))
+            .stdin(Stdio::null())
+            .output();
+
+        match output {
+            Ok(output) if output.status.success() => {
+                debug!(command = %run.command, "Shell hook finished");
+            }
+            Ok(output) => warn!(
+                command = %run.command,
+                status = %output.status,
+                stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                "Shell hook failed"
+            ),
+            Err(e) => warn!(command = %run.command, error = %e, "Failed to run shell hook"),
+        }
+    })
+    .detach();
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exactobar_core::UsageWindow;
+
+    fn snapshot(used_percent: f64) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(used_percent));
+        snapshot
+    }
+
+    fn hook(event: HookEvent, threshold: f64) -> ShellHook {
+        ShellHook {
+            event,
+            command: format!("echo {event}"),
+            providers: vec![],
+            threshold,
+        }
+    }
+
+    fn env<'a>(run: &'a HookRun, key: &str) -> Option<&'a str> {
+        run.env
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_threshold_fires_once_when_crossed() {
+        let hooks = vec![hook(HookEvent::OnThreshold, 95.0)];
+        let mut tracker = HookTracker::default();
+
+        assert!(
+            tracker
+                .observe(ProviderKind::Claude, Ok(&snapshot(90.0)), &hooks)
+                .is_empty()
+        );
+
+        let due = tracker.observe(ProviderKind::Claude, Ok(&snapshot(96.0)), &hooks);
+        assert_eq!(due.len(), 1);
+        assert_eq!(env(&due[0], "EXACTOBAR_EVENT"), Some("on_threshold"));
+        assert_eq!(env(&due[0], "EXACTOBAR_PROVIDER"), Some("claude"));
+        assert_eq!(env(&due[0], "EXACTOBAR_USED_PERCENT"), Some("96.0"));
+        assert_eq!(env(&due[0], "EXACTOBAR_THRESHOLD"), Some("95.0"));
+
+        // Staying above the threshold doesn't fire again
+        assert!(
+            tracker
+                .observe(ProviderKind::Claude, Ok(&snapshot(98.0)), &hooks)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_reset_fires_on_drop() {
+        let hooks = vec![hook(HookEvent::OnReset, 80.0)];
+        let mut tracker = HookTracker::default();
+
+        tracker.observe(ProviderKind::Codex, Ok(&snapshot(70.0)), &hooks);
+        assert!(
+            tracker
+                .observe(ProviderKind::Codex, Ok(&snapshot(68.0)), &hooks)
+                .is_empty()
+        );
+
+        let due = tracker.observe(ProviderKind::Codex, Ok(&snapshot(2.0)), &hooks);
+        assert_eq!(due.len(), 1);
+        assert_eq!(env(&due[0], "EXACTOBAR_EVENT"), Some("on_reset"));
+        assert_eq!(
+            env(&due[0], "EXACTOBAR_PREVIOUS_USED_PERCENT"),
+            Some("68.0")
+        );
+    }
+
+    #[test]
+    fn test_fetch_error_fires_on_first_failure() {
+        let mut claude_only = hook(HookEvent::OnFetchError, 80.0);
+        claude_only.providers = vec![ProviderKind::Claude];
+        let hooks = vec![claude_only];
+        let mut tracker = HookTracker::default();
+
+        let due = tracker.observe(ProviderKind::Claude, Err("timed out"), &hooks);
+        assert_eq!(due.len(), 1);
+        assert_eq!(env(&due[0], "EXACTOBAR_ERROR"), Some("timed out"));
+
+        // Still failing
+        assert!(
+            tracker
+                .observe(ProviderKind::Claude, Err("timed out"), &hooks)
+                .is_empty()
+        );
+
+        // Recovers, then fails again
+        tracker.observe(ProviderKind::Claude, Ok(&snapshot(10.0)), &hooks);
+        assert_eq!(
+            tracker
+                .observe(ProviderKind::Claude, Err("timed out"), &hooks)
+                .len(),
+            1
+        );
+
+        // Other providers are filtered out
+        assert!(
+            tracker
+                .observe(ProviderKind::Codex, Err("timed out"), &hooks)
+                .is_empty()
+        );
+    }
+}
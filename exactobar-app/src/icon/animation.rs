@@ -2,7 +2,19 @@
 //!
 //! This module contains the [`IconAnimationState`] struct which tracks
 //! animation parameters for animated icon rendering, particularly the
-//! Codex eye blink animation.
+//! Codex eye blink animation, and the [`AttentionAnimation`] played when a
+//! provider crosses the critical threshold.
+
+use std::f32::consts::TAU;
+use std::time::{Duration, Instant};
+
+use exactobar_store::AttentionStyle;
+
+/// Length of one pulse (fade out and back in).
+const PULSE_PERIOD_SECS: f32 = 1.2;
+
+/// Length of one blink (off, then on).
+const BLINK_PERIOD_SECS: f32 = 0.8;
 
 /// Animation state for provider icons.
 ///
@@ -16,6 +28,8 @@ pub struct IconAnimationState {
     pub tilt_degrees: f32,
     /// Wiggle offset for "surprise me" mode
     pub wiggle_offset: f32,
+    /// Attention dimming (0.0 = fully visible, 1.0 = invisible)
+    pub attention: f32,
 }
 
 impl Default for IconAnimationState {
@@ -24,6 +38,7 @@ impl Default for IconAnimationState {
             blink_phase: 0.0,
             tilt_degrees: 0.0,
             wiggle_offset: 0.0,
+            attention: 0.0,
         }
     }
 }
@@ -42,6 +57,15 @@ impl IconAnimationState {
         }
     }
 
+    /// Returns true if nothing is animating, so the icon looks as it would
+    /// without an animation.
+    pub fn is_idle(&self) -> bool {
+        self.blink_phase == 0.0
+            && self.tilt_degrees == 0.0
+            && self.wiggle_offset == 0.0
+            && self.attention == 0.0
+    }
+
     /// Returns true if the eye is fully closed.
     pub fn is_closed(&self) -> bool {
         self.blink_phase >= 1.0
@@ -53,6 +77,51 @@ impl IconAnimationState {
     }
 }
 
+/// Attention animation started when a provider crosses the critical threshold.
+#[derive(Debug, Clone, Copy)]
+pub struct AttentionAnimation {
+    style: AttentionStyle,
+    intensity: f32,
+    duration: Duration,
+    started: Instant,
+}
+
+impl AttentionAnimation {
+    /// Starts an animation now.
+    pub fn start(style: AttentionStyle, intensity: f32, duration: Duration) -> Self {
+        Self {
+            style,
+            intensity: intensity.clamp(0.0, 1.0),
+            duration,
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns the current dimming, or `None` once the animation is over.
+    pub fn level(&self) -> Option<f32> {
+        self.level_at(self.started.elapsed())
+    }
+
+    /// Returns the dimming `elapsed` after the start, or `None` once over.
+    pub fn level_at(&self, elapsed: Duration) -> Option<f32> {
+        if elapsed >= self.duration {
+            return None;
+        }
+
+        let t = elapsed.as_secs_f32();
+        match self.style {
+            AttentionStyle::Off => None,
+            AttentionStyle::Pulse => {
+                Some(self.intensity * (0.5 - 0.5 * (t * TAU / PULSE_PERIOD_SECS).cos()))
+            }
+            AttentionStyle::Blink => {
+                let off = (t / BLINK_PERIOD_SECS).fract() < 0.5;
+                Some(if off { self.intensity } else { 0.0 })
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
@@ -91,4 +160,32 @@ mod tests {
         assert!(!state.is_closed());
         assert!(state.is_open());
     }
+
+    #[test]
+    fn test_attention_pulse() {
+        let anim = AttentionAnimation::start(AttentionStyle::Pulse, 0.8, Duration::from_secs(5));
+
+        // Starts fully visible, dims most halfway through a pulse
+        assert_eq!(anim.level_at(Duration::ZERO), Some(0.0));
+        let half = Duration::from_secs_f32(PULSE_PERIOD_SECS / 2.0);
+        assert!((anim.level_at(half).unwrap() - 0.8).abs() < 1e-4);
+
+        // Over after the duration
+        assert_eq!(anim.level_at(Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn test_attention_blink() {
+        let anim = AttentionAnimation::start(AttentionStyle::Blink, 2.0, Duration::from_secs(5));
+
+        // Intensity is clamped; the icon goes dark, then comes back
+        assert_eq!(anim.level_at(Duration::from_millis(100)), Some(1.0));
+        assert_eq!(anim.level_at(Duration::from_millis(500)), Some(0.0));
+    }
+
+    #[test]
+    fn test_attention_off() {
+        let anim = AttentionAnimation::start(AttentionStyle::Off, 1.0, Duration::from_secs(5));
+        assert_eq!(anim.level_at(Duration::ZERO), None);
+    }
 }
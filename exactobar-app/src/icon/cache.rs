@@ -0,0 +1,118 @@
+//! Cache of rendered icons.
+//!
+//! Most refreshes don't change what the menu bar shows, so icons are kept
+//! by what they're drawn from, with usage rounded to whole percents (finer
+//! than the meters can show), and an unchanged refresh reuses the icon and
+//! its PNG instead of drawing it again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use exactobar_core::{ProviderKind, StatusIndicator, UsageSnapshot, UsageWindow};
+
+use super::{RenderMode, RenderedIcon};
+
+/// Icons kept before the cache starts over.
+const MAX_CACHED_ICONS: usize = 64;
+
+/// What a cached icon was drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IconCacheKey {
+    provider: ProviderKind,
+    /// Whole percents of the session and weekly windows, if there's usage.
+    usage: Option<(Option<u8>, Option<u8>)>,
+    stale: bool,
+    status: Option<StatusIndicator>,
+    mode: RenderMode,
+    /// Hundredths of the renderer's scale.
+    scale: u16,
+}
+
+impl IconCacheKey {
+    /// Creates the key of an icon drawn from these inputs.
+    pub fn new(
+        provider: ProviderKind,
+        snapshot: Option<&UsageSnapshot>,
+        stale: bool,
+        status: Option<StatusIndicator>,
+        mode: RenderMode,
+        scale: f32,
+    ) -> Self {
+        let percent =
+            |window: Option<&UsageWindow>| window.map(|w| w.clamped_used_percent().round() as u8);
+        Self {
+            provider,
+            usage: snapshot.map(|s| (percent(s.primary.as_ref()), percent(s.secondary.as_ref()))),
+            stale,
+            status,
+            mode,
+            scale: (scale * 100.0).round() as u16,
+        }
+    }
+}
+
+/// A rendered icon and its PNG, encoded when first asked for.
+pub struct CachedIcon {
+    /// The icon.
+    pub icon: RenderedIcon,
+    png: OnceLock<Vec<u8>>,
+}
+
+impl CachedIcon {
+    /// Wraps a rendered icon.
+    pub fn new(icon: RenderedIcon) -> Self {
+        Self {
+            icon,
+            png: OnceLock::new(),
+        }
+    }
+
+    /// Returns the icon as PNG bytes.
+    pub fn png(&self) -> &[u8] {
+        self.png.get_or_init(|| self.icon.to_png())
+    }
+}
+
+/// Rendered icons by what they were drawn from.
+#[derive(Default)]
+pub struct IconCache {
+    icons: Mutex<HashMap<IconCacheKey, Arc<CachedIcon>>>,
+}
+
+impl IconCache {
+    /// Returns the icon for `key`, rendering it with `render` if it isn't
+    /// cached.
+    pub fn get_or_render(
+        &self,
+        key: IconCacheKey,
+        render: impl FnOnce() -> RenderedIcon,
+    ) -> Arc<CachedIcon> {
+        let mut icons = self.icons.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(icon) = icons.get(&key) {
+            return Arc::clone(icon);
+        }
+        if icons.len() >= MAX_CACHED_ICONS {
+            icons.clear();
+        }
+        let icon = Arc::new(CachedIcon::new(render()));
+        icons.insert(key, Arc::clone(&icon));
+        icon
+    }
+
+    /// Drops every cached icon, e.g. when the layout changes.
+    pub fn clear(&self) {
+        self.icons
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the number of cached icons.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.icons
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
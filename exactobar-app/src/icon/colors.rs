@@ -1,11 +1,14 @@
 //! Color management for icon rendering.
 //!
 //! This module contains the [`IconColors`] struct and helper functions
-//! for managing colors in both template (grayscale) and colored modes.
+//! for managing colors in template (grayscale), adaptive and colored modes.
 
-use exactobar_core::ProviderKind;
+use exactobar_core::{ProviderKind, StatusIndicator};
+use exactobar_store::ColorPalette;
 use tiny_skia::{Color, Paint};
 
+use super::MenuBarAppearance;
+
 /// Color palette for icon rendering.
 pub struct IconColors {
     pub track: Color,      // Bar background
@@ -14,39 +17,122 @@ pub struct IconColors {
     pub warning: Color,    // 20-50% remaining
     pub danger: Color,     // <20% remaining
     pub loading: Color,    // Loading animation
+    pub outline: Color,    // Meter outline, when drawn
 }
 
 impl IconColors {
     /// Template mode colors (grayscale for macOS template images).
-    pub fn template(stale: bool) -> Self {
-        let opacity = if stale { 140 } else { 204 }; // ~55% or ~80%
+    ///
+    /// The high contrast palette makes the track and fill more opaque.
+    pub fn template(stale: bool, palette: ColorPalette) -> Self {
+        Self::monochrome((0, 0, 0), stale, palette)
+    }
+
+    /// Adaptive mode colors: white on a dark menu bar, black on a light one.
+    pub fn adaptive(appearance: MenuBarAppearance, stale: bool, palette: ColorPalette) -> Self {
+        let ink = match appearance {
+            MenuBarAppearance::Light => (0, 0, 0),
+            MenuBarAppearance::Dark => (255, 255, 255),
+        };
+        Self::monochrome(ink, stale, palette)
+    }
+
+    /// Shades of one color, told apart by opacity.
+    fn monochrome((r, g, b): (u8, u8, u8), stale: bool, palette: ColorPalette) -> Self {
+        let high_contrast = palette == ColorPalette::HighContrast;
+        let opacity = match (stale, high_contrast) {
+            (false, false) => 204, // ~80%
+            (true, false) => 140,  // ~55%
+            (false, true) => 255,
+            (true, true) => 178, // ~70%
+        };
+        let track = if high_contrast { 153 } else { 102 }; // 60% or 40%
 
         Self {
-            track: Color::from_rgba8(0, 0, 0, 102),       // 40% opacity
-            fill_stale: Color::from_rgba8(0, 0, 0, 140),  // 55% opacity
-            good: Color::from_rgba8(0, 0, 0, opacity),    // 80% or 55%
-            warning: Color::from_rgba8(0, 0, 0, opacity), // Same for template
-            danger: Color::from_rgba8(0, 0, 0, opacity),  // Same for template
-            loading: Color::from_rgba8(0, 0, 0, 128),     // 50% opacity
+            track: Color::from_rgba8(r, g, b, track),
+            fill_stale: Color::from_rgba8(r, g, b, 140), // 55% opacity
+            good: Color::from_rgba8(r, g, b, opacity),
+            warning: Color::from_rgba8(r, g, b, opacity), // Same for template
+            danger: Color::from_rgba8(r, g, b, opacity),  // Same for template
+            loading: Color::from_rgba8(r, g, b, 128),     // 50% opacity
+            outline: Color::from_rgba8(r, g, b, 255),
         }
     }
 
     /// Colored mode with provider brand colors.
-    pub fn colored(provider: ProviderKind, stale: bool) -> Self {
-        let brand = provider_brand_color(provider);
+    ///
+    /// Palettes other than the standard one replace the brand color with
+    /// their own, so every level keeps its meaning.
+    pub fn colored(provider: ProviderKind, stale: bool, palette: ColorPalette) -> Self {
         let alpha_mult = if stale { 0.7 } else { 1.0 };
+        let (good, warning, danger) = level_colors(palette, provider);
+        let track = match palette {
+            ColorPalette::HighContrast => Color::from_rgba8(110, 110, 110, 230),
+            _ => Color::from_rgba8(80, 80, 80, 180),
+        };
 
         Self {
-            track: Color::from_rgba8(80, 80, 80, 180),
-            fill_stale: with_alpha(brand, 0.6),
-            good: with_alpha(brand, alpha_mult),
-            warning: with_alpha(Color::from_rgba8(255, 193, 7, 255), alpha_mult),
-            danger: with_alpha(Color::from_rgba8(244, 67, 54, 255), alpha_mult),
+            track,
+            fill_stale: with_alpha(good, 0.6),
+            good: with_alpha(good, alpha_mult),
+            warning: with_alpha(warning, alpha_mult),
+            danger: with_alpha(danger, alpha_mult),
             loading: Color::from_rgba8(150, 150, 150, 200),
+            outline: Color::from_rgba8(160, 160, 160, 255),
         }
     }
 }
 
+/// Gets a palette's good, warning and danger colors.
+fn level_colors(palette: ColorPalette, provider: ProviderKind) -> (Color, Color, Color) {
+    match palette {
+        ColorPalette::Standard => (
+            provider_brand_color(provider),
+            Color::from_rgba8(255, 193, 7, 255),
+            Color::from_rgba8(244, 67, 54, 255),
+        ),
+        // Okabe-Ito blue, orange and vermillion
+        ColorPalette::ColorblindSafe => (
+            Color::from_rgba8(0, 114, 178, 255),
+            Color::from_rgba8(230, 159, 0, 255),
+            Color::from_rgba8(213, 94, 0, 255),
+        ),
+        ColorPalette::HighContrast => (
+            Color::from_rgba8(0, 204, 0, 255),
+            Color::from_rgba8(255, 214, 0, 255),
+            Color::from_rgba8(255, 0, 0, 255),
+        ),
+    }
+}
+
+/// Gets the status dot color for an incident, if it gets a dot.
+pub fn status_color(indicator: StatusIndicator, palette: ColorPalette) -> Option<Color> {
+    let (minor, major, critical) = match palette {
+        ColorPalette::Standard => (
+            Color::from_rgba8(255, 193, 7, 255), // Yellow
+            Color::from_rgba8(255, 152, 0, 255), // Orange
+            Color::from_rgba8(244, 67, 54, 255), // Red
+        ),
+        ColorPalette::ColorblindSafe => (
+            Color::from_rgba8(240, 228, 66, 255), // Yellow
+            Color::from_rgba8(230, 159, 0, 255),  // Orange
+            Color::from_rgba8(213, 94, 0, 255),   // Vermillion
+        ),
+        ColorPalette::HighContrast => (
+            Color::from_rgba8(255, 235, 0, 255), // Yellow
+            Color::from_rgba8(255, 128, 0, 255), // Orange
+            Color::from_rgba8(255, 0, 0, 255),   // Red
+        ),
+    };
+
+    match indicator {
+        StatusIndicator::Minor => Some(minor),
+        StatusIndicator::Major => Some(major),
+        StatusIndicator::Critical => Some(critical),
+        _ => None, // Don't draw for None/Unknown
+    }
+}
+
 /// Gets the brand color for a provider.
 pub fn provider_brand_color(provider: ProviderKind) -> Color {
     match provider {
@@ -63,6 +149,9 @@ pub fn provider_brand_color(provider: ProviderKind) -> Color {
         ProviderKind::MiniMax => Color::from_rgba8(0, 191, 255, 255), // Deep sky blue
         ProviderKind::Antigravity => Color::from_rgba8(148, 0, 211, 255), // Violet
         ProviderKind::Synthetic => Color::from_rgba8(0, 204, 179, 255), // Teal/cyan
+        ProviderKind::GitHubModels => Color::from_rgba8(130, 80, 223, 255), // GitHub purple
+        ProviderKind::HuggingFace => Color::from_rgba8(255, 210, 30, 255), // Hugging Face yellow
+        ProviderKind::Replicate => Color::from_rgba8(237, 74, 51, 255), // Replicate red
     }
 }
 
@@ -0,0 +1,74 @@
+//! Loading patterns for provider icons.
+//!
+//! While a provider's first fetch is in flight there's no usage to show,
+//! so the meter plays one of the [`LoadingPattern`]s instead: both bars
+//! move to a pattern driven by a phase the tray advances on a timer.
+
+use std::f64::consts::{PI, TAU};
+
+/// How the bars move while a provider loads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadingPattern {
+    /// Both bars fill and drain together, the weekly bar half a beat behind.
+    #[default]
+    KnightRider,
+    /// The bars sweep up one after the other and start over.
+    Cylon,
+    /// One bar fills while the other drains.
+    OutsideIn,
+    /// The bars fill at different speeds.
+    Race,
+    /// The bars breathe between a third and full.
+    Pulse,
+}
+
+impl LoadingPattern {
+    /// Returns all patterns.
+    pub fn all() -> &'static [LoadingPattern] {
+        &[
+            LoadingPattern::KnightRider,
+            LoadingPattern::Cylon,
+            LoadingPattern::OutsideIn,
+            LoadingPattern::Race,
+            LoadingPattern::Pulse,
+        ]
+    }
+
+    /// Returns the name the debug loading pattern setting uses.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoadingPattern::KnightRider => "knight-rider",
+            LoadingPattern::Cylon => "cylon",
+            LoadingPattern::OutsideIn => "outside-in",
+            LoadingPattern::Race => "race",
+            LoadingPattern::Pulse => "pulse",
+        }
+    }
+
+    /// Returns the pattern with `name`, ignoring case and `_` vs `-`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace('_', "-");
+        Self::all()
+            .iter()
+            .copied()
+            .find(|pattern| pattern.name() == name)
+    }
+
+    /// Returns how full (0-100) the session and weekly bars are at `phase`,
+    /// in radians.
+    pub fn fills(&self, phase: f64) -> (f32, f32) {
+        let wave = |phase: f64| (phase.sin() + 1.0) / 2.0;
+        let ramp = |phase: f64| (phase / TAU).rem_euclid(1.0);
+        let (primary, secondary) = match self {
+            LoadingPattern::KnightRider => (wave(phase), wave(phase - PI / 2.0)),
+            LoadingPattern::Cylon => (ramp(phase), ramp(phase - PI)),
+            LoadingPattern::OutsideIn => (wave(phase), 1.0 - wave(phase)),
+            LoadingPattern::Race => (ramp(phase * 1.5), ramp(phase)),
+            LoadingPattern::Pulse => {
+                let level = |phase| 1.0 / 3.0 + wave(phase) * 2.0 / 3.0;
+                (level(phase), level(phase - PI / 4.0))
+            }
+        };
+        ((primary * 100.0) as f32, (secondary * 100.0) as f32)
+    }
+}
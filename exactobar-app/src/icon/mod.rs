@@ -1,24 +1,42 @@
 //! Dynamic icon rendering for menu bar.
 //!
 //! Renders provider icons with usage meters using tiny-skia.
-//! Supports both colored mode and template mode (grayscale for macOS).
+//! Supports colored mode, template mode (grayscale for macOS) and adaptive
+//! mode (drawn for the menu bar's light or dark appearance).
+//! The meter is drawn in one of the [`IconLayout`]s picked in settings,
+//! optionally outlined.
+//!
+//! Icons are laid out in design pixels, [`ICON_WIDTH`] x [`ICON_HEIGHT`],
+//! sized for a standard menu bar on a 2x display. The renderer's
+//! [scale](IconRenderer::with_scale) maps them to device pixels, so 1x
+//! external monitors and taller menu bars get sharp icons of their own
+//! rather than a resampled 2x bitmap (see [`icon_scale`]).
 //!
 //! # Module Structure
 //!
 //! - [`animation`] - Animation state for provider icons
+//! - [`cache`] - Rendered icons reused across unchanged refreshes
 //! - [`colors`] - Color management and palettes
 //! - [`codex_eye`] - Codex-specific eye icon drawing
+//! - [`loading`] - Patterns played while a provider loads
 //! - [`rendered`] - Rendered icon output struct
 
 mod animation;
+mod cache;
 mod codex_eye;
 mod colors;
+mod loading;
 mod rendered;
 
-pub use animation::IconAnimationState;
+pub use animation::{AttentionAnimation, IconAnimationState};
+pub use exactobar_store::{ColorPalette, IconLayout};
+pub use loading::LoadingPattern;
 pub use rendered::RenderedIcon;
 
-use colors::{IconColors, create_paint};
+use std::sync::Arc;
+
+use cache::{CachedIcon, IconCache, IconCacheKey};
+use colors::{IconColors, create_paint, status_color};
 use exactobar_core::{ProviderKind, StatusIndicator, UsageSnapshot};
 use tiny_skia::*;
 
@@ -31,6 +49,15 @@ pub const ICON_SIZE: u32 = 36;
 pub const ICON_WIDTH: u32 = 36;
 pub const ICON_HEIGHT: u32 = 22;
 
+/// Backing scale factor the icon dimensions are designed for.
+pub const DESIGN_BACKING_SCALE: f64 = 2.0;
+
+/// Thickness of a standard macOS menu bar, in points.
+pub const STANDARD_MENU_BAR_THICKNESS: f64 = 22.0;
+
+/// Most a taller-than-standard menu bar enlarges the icon.
+const MAX_MENU_BAR_GROWTH: f64 = 1.5;
+
 /// Usage bar dimensions.
 const BAR_WIDTH: f32 = 24.0;
 const BAR_HEIGHT_THICK: f32 = 6.0; // Session bar
@@ -45,18 +72,59 @@ const STATUS_DOT_MARGIN: f32 = 2.0;
 /// Credits bar (thicker when in credits mode).
 const CREDITS_BAR_HEIGHT: f32 = 8.0;
 
+/// Bar of the single bar layout.
+const SINGLE_BAR_HEIGHT: f32 = 8.0;
+
+/// Outline drawn around meters for contrast.
+const OUTLINE_WIDTH: f32 = 1.0;
+
+/// Ring gauge dimensions.
+const RING_STROKE: f32 = 3.0;
+const RING_MARGIN: f32 = 2.0;
+const RING_SEGMENTS: f32 = 64.0;
+
+/// Digits of the number layout, 3x5 pixels, one row per byte (high bit left).
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Shown by the number layout when there's no usage.
+const DASH_GLYPH: [u8; 5] = [0b000, 0b000, 0b111, 0b000, 0b000];
+
 // ============================================================================
 // Rendering Mode
 // ============================================================================
 
 /// How to render the icon.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum RenderMode {
     /// Template mode - grayscale, system applies color based on appearance.
     #[default]
     Template,
     /// Colored mode - uses provider brand colors.
     Colored,
+    /// Adaptive mode - white or black for the menu bar's appearance, for
+    /// wallpapers template tinting washes out.
+    Adaptive,
+}
+
+/// Whether the menu bar is light or dark.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MenuBarAppearance {
+    /// Dark icons on a light menu bar.
+    #[default]
+    Light,
+    /// Light icons on a dark menu bar.
+    Dark,
 }
 
 // ============================================================================
@@ -68,6 +136,13 @@ pub struct IconRenderer {
     width: u32,
     height: u32,
     mode: RenderMode,
+    layout: IconLayout,
+    palette: ColorPalette,
+    appearance: MenuBarAppearance,
+    outline: bool,
+    /// Device pixels per design pixel.
+    scale: f32,
+    cache: IconCache,
 }
 
 impl Default for IconRenderer {
@@ -83,6 +158,12 @@ impl IconRenderer {
             width: ICON_WIDTH,
             height: ICON_HEIGHT,
             mode: RenderMode::Template,
+            layout: IconLayout::default(),
+            palette: ColorPalette::default(),
+            appearance: MenuBarAppearance::default(),
+            outline: false,
+            scale: 1.0,
+            cache: IconCache::default(),
         }
     }
 
@@ -92,6 +173,12 @@ impl IconRenderer {
             width,
             height,
             mode: RenderMode::Template,
+            layout: IconLayout::default(),
+            palette: ColorPalette::default(),
+            appearance: MenuBarAppearance::default(),
+            outline: false,
+            scale: 1.0,
+            cache: IconCache::default(),
         }
     }
 
@@ -101,6 +188,117 @@ impl IconRenderer {
         self
     }
 
+    /// Changes the rendering mode, e.g. after a settings change.
+    pub fn set_mode(&mut self, mode: RenderMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the rendering mode.
+    pub fn mode(&self) -> RenderMode {
+        self.mode
+    }
+
+    /// Sets what the usage meter shows.
+    pub fn with_layout(mut self, layout: IconLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Changes what the usage meter shows, e.g. after a settings change.
+    pub fn set_layout(&mut self, layout: IconLayout) {
+        if layout != self.layout {
+            self.layout = layout;
+            self.cache.clear();
+        }
+    }
+
+    /// Returns what the usage meter shows.
+    pub fn layout(&self) -> IconLayout {
+        self.layout
+    }
+
+    /// Sets the palette usage levels and status dots are drawn in.
+    pub fn with_palette(mut self, palette: ColorPalette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Changes the palette, e.g. after a settings change.
+    pub fn set_palette(&mut self, palette: ColorPalette) {
+        if palette != self.palette {
+            self.palette = palette;
+            self.cache.clear();
+        }
+    }
+
+    /// Sets the menu bar appearance adaptive icons are drawn for.
+    pub fn with_appearance(mut self, appearance: MenuBarAppearance) -> Self {
+        self.appearance = appearance;
+        self
+    }
+
+    /// Changes the menu bar appearance, e.g. when the system switches
+    /// between light and dark.
+    pub fn set_appearance(&mut self, appearance: MenuBarAppearance) {
+        if appearance != self.appearance {
+            self.appearance = appearance;
+            self.cache.clear();
+        }
+    }
+
+    /// Returns the menu bar appearance adaptive icons are drawn for.
+    pub fn appearance(&self) -> MenuBarAppearance {
+        self.appearance
+    }
+
+    /// Sets whether meters are outlined.
+    pub fn with_outline(mut self, outline: bool) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Changes whether meters are outlined, e.g. after a settings change.
+    pub fn set_outline(&mut self, outline: bool) {
+        if outline != self.outline {
+            self.outline = outline;
+            self.cache.clear();
+        }
+    }
+
+    /// Sets how many device pixels a design pixel covers.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Changes the scale icons are drawn at, e.g. before drawing for a
+    /// display with another backing scale. Icons are cached per scale.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Returns how many device pixels a design pixel covers.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns the size of rendered icons in device pixels.
+    pub fn pixel_size(&self) -> (u32, u32) {
+        let scaled = |size: u32| ((size as f32 * self.scale).round() as u32).max(1);
+        (scaled(self.width), scaled(self.height))
+    }
+
+    /// Creates an empty pixmap at the rendered size.
+    fn new_pixmap(&self) -> Pixmap {
+        let (width, height) = self.pixel_size();
+        Pixmap::new(width, height).unwrap()
+    }
+
+    /// Maps design pixels to device pixels.
+    fn transform(&self) -> Transform {
+        Transform::from_scale(self.scale, self.scale)
+    }
+
     /// Renders an icon for a provider's current usage.
     ///
     /// # Arguments
@@ -117,25 +315,26 @@ impl IconRenderer {
         status: Option<StatusIndicator>,
         animation: Option<&IconAnimationState>,
     ) -> RenderedIcon {
-        let mut pixmap = Pixmap::new(self.width, self.height).unwrap();
+        let mut pixmap = self.new_pixmap();
         pixmap.fill(Color::TRANSPARENT);
 
         let colors = self.get_colors(provider, stale);
 
-        // Provider-specific rendering
-        match provider {
-            ProviderKind::Codex => {
+        // Session usage drives every layout but the dual bars
+        let session = snapshot
+            .and_then(|s| s.primary.as_ref())
+            .map(|w| w.clamped_used_percent() as f32);
+
+        // Layout- and provider-specific rendering
+        match (self.layout, provider) {
+            (IconLayout::DualBars, ProviderKind::Codex) => {
                 // Use eye design for Codex
                 // fill_percent = used percentage (bar fills as usage increases)
-                let fill_percent = snapshot
-                    .as_ref()
-                    .and_then(|s| s.primary.as_ref())
-                    .map(|w| w.used_percent as f32)
-                    .unwrap_or(50.0);
+                let fill_percent = session.unwrap_or(50.0);
                 let blink = animation.map(|a| a.blink_phase).unwrap_or(0.0);
                 self.draw_codex_eye(&mut pixmap, fill_percent, blink, &colors, stale);
             }
-            _ => {
+            (IconLayout::DualBars, _) => {
                 // Use standard dual-bar for other providers
                 if let Some(snap) = snapshot {
                     self.draw_usage_bars(&mut pixmap, snap, &colors, stale);
@@ -143,6 +342,15 @@ impl IconRenderer {
                     self.draw_placeholder(&mut pixmap, &colors);
                 }
             }
+            (IconLayout::SingleBar, _) => {
+                self.draw_single_bar(&mut pixmap, session, &colors, stale);
+            }
+            (IconLayout::Ring, _) => {
+                self.draw_ring(&mut pixmap, session, &colors, stale);
+            }
+            (IconLayout::Number, _) => {
+                self.draw_number(&mut pixmap, session, &colors, stale);
+            }
         }
 
         // Draw status overlay if there's an incident
@@ -152,13 +360,42 @@ impl IconRenderer {
             }
         }
 
+        // Fade the whole icon for the attention animation
+        if let Some(anim) = animation {
+            if anim.attention > 0.0 {
+                dim_pixmap(&mut pixmap, anim.attention);
+            }
+        }
+
         RenderedIcon {
             data: pixmap.data().to_vec(),
-            width: self.width,
-            height: self.height,
+            width: pixmap.width(),
+            height: pixmap.height(),
         }
     }
 
+    /// Renders an icon like [`render`](Self::render), reusing the icon
+    /// last drawn from the same usage (to the whole percent), status, mode
+    /// and scale.
+    ///
+    /// Icons in the middle of an animation are always drawn afresh and
+    /// not cached.
+    pub fn render_cached(
+        &self,
+        provider: ProviderKind,
+        snapshot: Option<&UsageSnapshot>,
+        stale: bool,
+        status: Option<StatusIndicator>,
+        animation: Option<&IconAnimationState>,
+    ) -> Arc<CachedIcon> {
+        let render = || self.render(provider, snapshot, stale, status, animation);
+        if animation.is_some_and(|a| !a.is_idle()) {
+            return Arc::new(CachedIcon::new(render()));
+        }
+        let key = IconCacheKey::new(provider, snapshot, stale, status, self.mode, self.scale);
+        self.cache.get_or_render(key, render)
+    }
+
     /// Renders an icon showing credits instead of usage windows.
     pub fn render_credits(
         &self,
@@ -166,7 +403,7 @@ impl IconRenderer {
         credits_remaining_percent: Option<f64>,
         stale: bool,
     ) -> RenderedIcon {
-        let mut pixmap = Pixmap::new(self.width, self.height).unwrap();
+        let mut pixmap = self.new_pixmap();
         pixmap.fill(Color::TRANSPARENT);
 
         let colors = self.get_colors(provider, stale);
@@ -179,37 +416,42 @@ impl IconRenderer {
 
         RenderedIcon {
             data: pixmap.data().to_vec(),
-            width: self.width,
-            height: self.height,
+            width: pixmap.width(),
+            height: pixmap.height(),
         }
     }
 
-    /// Renders a loading animation frame.
-    pub fn render_loading(&self, provider: ProviderKind, phase: f64) -> RenderedIcon {
-        let mut pixmap = Pixmap::new(self.width, self.height).unwrap();
+    /// Renders a frame of a loading pattern, `phase` radians in.
+    pub fn render_loading(
+        &self,
+        provider: ProviderKind,
+        pattern: LoadingPattern,
+        phase: f64,
+    ) -> RenderedIcon {
+        let mut pixmap = self.new_pixmap();
         pixmap.fill(Color::TRANSPARENT);
 
         let colors = self.get_colors(provider, false);
-        self.draw_loading_animation(&mut pixmap, phase, &colors);
+        self.draw_loading_animation(&mut pixmap, pattern, phase, &colors);
 
         RenderedIcon {
             data: pixmap.data().to_vec(),
-            width: self.width,
-            height: self.height,
+            width: pixmap.width(),
+            height: pixmap.height(),
         }
     }
 
     /// Renders an error state icon.
     pub fn render_error(&self, provider: ProviderKind) -> RenderedIcon {
-        let mut pixmap = Pixmap::new(self.width, self.height).unwrap();
+        let mut pixmap = self.new_pixmap();
         pixmap.fill(Color::TRANSPARENT);
 
         self.draw_error_indicator(&mut pixmap, provider);
 
         RenderedIcon {
             data: pixmap.data().to_vec(),
-            width: self.width,
-            height: self.height,
+            width: pixmap.width(),
+            height: pixmap.height(),
         }
     }
 
@@ -219,8 +461,9 @@ impl IconRenderer {
 
     fn get_colors(&self, provider: ProviderKind, stale: bool) -> IconColors {
         match self.mode {
-            RenderMode::Template => IconColors::template(stale),
-            RenderMode::Colored => IconColors::colored(provider, stale),
+            RenderMode::Template => IconColors::template(stale, self.palette),
+            RenderMode::Colored => IconColors::colored(provider, stale, self.palette),
+            RenderMode::Adaptive => IconColors::adaptive(self.appearance, stale, self.palette),
         }
     }
 
@@ -247,7 +490,7 @@ impl IconRenderer {
         // Primary bar (session usage) - thicker
         // Fill = used percentage (bar fills left→right as usage increases)
         if let Some(primary) = &snapshot.primary {
-            let used = primary.used_percent as f32;
+            let used = primary.clamped_used_percent() as f32;
             self.draw_bar(
                 pixmap,
                 bar_x,
@@ -273,7 +516,7 @@ impl IconRenderer {
         // Secondary bar (weekly - hairline)
         // Fill = used percentage (bar fills left→right as usage increases)
         if let Some(secondary) = &snapshot.secondary {
-            let used = secondary.used_percent as f32;
+            let used = secondary.clamped_used_percent() as f32;
             self.draw_bar(
                 pixmap,
                 bar_x,
@@ -315,7 +558,7 @@ impl IconRenderer {
             &bg_path,
             &bg_paint,
             FillRule::Winding,
-            Transform::identity(),
+            self.transform(),
             None,
         );
 
@@ -334,10 +577,11 @@ impl IconRenderer {
                 &fill_path,
                 &fill_paint,
                 FillRule::Winding,
-                Transform::identity(),
+                self.transform(),
                 None,
             );
         }
+        self.draw_outline(pixmap, &bg_path, colors);
     }
 
     fn draw_empty_bar(
@@ -351,11 +595,24 @@ impl IconRenderer {
     ) {
         let path = self.rounded_rect_path(x, y, width, height, BAR_RADIUS.min(height / 2.0));
         let paint = create_paint(colors.track);
-        pixmap.fill_path(
-            &path,
-            &paint,
-            FillRule::Winding,
-            Transform::identity(),
+        pixmap.fill_path(&path, &paint, FillRule::Winding, self.transform(), None);
+        self.draw_outline(pixmap, &path, colors);
+    }
+
+    /// Strokes the edge of a meter, if meters are outlined.
+    fn draw_outline(&self, pixmap: &mut Pixmap, path: &Path, colors: &IconColors) {
+        if !self.outline {
+            return;
+        }
+        let stroke = Stroke {
+            width: OUTLINE_WIDTH,
+            ..Stroke::default()
+        };
+        pixmap.stroke_path(
+            path,
+            &create_paint(colors.outline),
+            &stroke,
+            self.transform(),
             None,
         );
     }
@@ -384,6 +641,157 @@ impl IconRenderer {
         );
     }
 
+    fn draw_single_bar(
+        &self,
+        pixmap: &mut Pixmap,
+        used: Option<f32>,
+        colors: &IconColors,
+        stale: bool,
+    ) {
+        let bar_x = self.width as f32 / 2.0 - BAR_WIDTH / 2.0;
+        let bar_y = self.height as f32 / 2.0 - SINGLE_BAR_HEIGHT / 2.0;
+
+        if let Some(used) = used {
+            self.draw_bar(
+                pixmap,
+                bar_x,
+                bar_y,
+                BAR_WIDTH,
+                SINGLE_BAR_HEIGHT,
+                used,
+                colors,
+                stale,
+            );
+        } else {
+            self.draw_empty_bar(pixmap, bar_x, bar_y, BAR_WIDTH, SINGLE_BAR_HEIGHT, colors);
+        }
+    }
+
+    /// Draws a ring that fills clockwise from the top as usage increases.
+    fn draw_ring(&self, pixmap: &mut Pixmap, used: Option<f32>, colors: &IconColors, stale: bool) {
+        let center_x = self.width as f32 / 2.0;
+        let center_y = self.height as f32 / 2.0;
+        let radius = center_x.min(center_y) - RING_MARGIN - RING_STROKE / 2.0;
+        let stroke = Stroke {
+            width: RING_STROKE,
+            ..Stroke::default()
+        };
+
+        // Background track
+        let mut pb = PathBuilder::new();
+        pb.push_circle(center_x, center_y, radius);
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(
+                &path,
+                &create_paint(colors.track),
+                &stroke,
+                self.transform(),
+                None,
+            );
+        }
+
+        // Just outside both edges of the track, so the arc doesn't cover it
+        if self.outline {
+            let offset = (RING_STROKE + OUTLINE_WIDTH) / 2.0;
+            let mut pb = PathBuilder::new();
+            pb.push_circle(center_x, center_y, radius + offset);
+            pb.push_circle(center_x, center_y, radius - offset);
+            if let Some(path) = pb.finish() {
+                self.draw_outline(pixmap, &path, colors);
+            }
+        }
+
+        // Filled arc, as line segments (tiny-skia has no arcs)
+        let Some(used) = used.filter(|u| *u > 0.0) else {
+            return;
+        };
+        let sweep = used.min(100.0) / 100.0 * std::f32::consts::TAU;
+        let steps = (RING_SEGMENTS * sweep / std::f32::consts::TAU)
+            .ceil()
+            .max(1.0) as u32;
+        let point = |angle: f32| {
+            // Angle 0 is 12 o'clock
+            (
+                center_x + radius * angle.sin(),
+                center_y - radius * angle.cos(),
+            )
+        };
+
+        let mut pb = PathBuilder::new();
+        let (x, y) = point(0.0);
+        pb.move_to(x, y);
+        for step in 1..=steps {
+            let (x, y) = point(sweep * step as f32 / steps as f32);
+            pb.line_to(x, y);
+        }
+        if let Some(path) = pb.finish() {
+            let fill_color = if stale {
+                colors.fill_stale
+            } else {
+                self.percent_to_color(used, colors)
+            };
+            pixmap.stroke_path(
+                &path,
+                &create_paint(fill_color),
+                &stroke,
+                self.transform(),
+                None,
+            );
+        }
+    }
+
+    /// Draws the used percentage in a pixel font, or a dash without usage.
+    fn draw_number(
+        &self,
+        pixmap: &mut Pixmap,
+        used: Option<f32>,
+        colors: &IconColors,
+        stale: bool,
+    ) {
+        let glyphs: Vec<[u8; 5]> = match used {
+            Some(used) => (used.round() as u32)
+                .to_string()
+                .bytes()
+                .map(|digit| DIGIT_GLYPHS[usize::from(digit - b'0')])
+                .collect(),
+            None => vec![DASH_GLYPH],
+        };
+        let color = match used {
+            Some(_) if stale => colors.fill_stale,
+            Some(used) => self.percent_to_color(used, colors),
+            None => colors.track,
+        };
+
+        // Scale the 3x5 glyphs to about half the icon's height, in whole
+        // device pixels so they stay crisp at any scale
+        let (width, height) = (pixmap.width() as f32, pixmap.height() as f32);
+        let scale = (height / 11.0).floor().max(1.0);
+        let advance = 4.0 * scale;
+        let text_width = advance * glyphs.len() as f32 - scale;
+        let left = ((width - text_width) / 2.0).round();
+        let top = ((height - 5.0 * scale) / 2.0).round();
+
+        let paint = create_paint(color);
+        for (index, glyph) in glyphs.iter().enumerate() {
+            let glyph_x = left + advance * index as f32;
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) == 0 {
+                        continue;
+                    }
+                    if let Some(rect) = Rect::from_xywh(
+                        glyph_x + col as f32 * scale,
+                        top + row as f32 * scale,
+                        scale,
+                        scale,
+                    ) {
+                        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+                    }
+                }
+            }
+        }
+    }
+
     fn draw_placeholder(&self, pixmap: &mut Pixmap, colors: &IconColors) {
         let center_x = self.width as f32 / 2.0;
         let center_y = self.height as f32 / 2.0;
@@ -411,42 +819,50 @@ impl IconRenderer {
         );
     }
 
-    fn draw_loading_animation(&self, pixmap: &mut Pixmap, phase: f64, colors: &IconColors) {
+    fn draw_loading_animation(
+        &self,
+        pixmap: &mut Pixmap,
+        pattern: LoadingPattern,
+        phase: f64,
+        colors: &IconColors,
+    ) {
         let center_x = self.width as f32 / 2.0;
         let center_y = self.height as f32 / 2.0;
         let bar_x = center_x - BAR_WIDTH / 2.0;
 
         let total_height = BAR_HEIGHT_THICK + BAR_SPACING + BAR_HEIGHT_THIN;
         let primary_y = center_y - total_height / 2.0;
+        let secondary_y = primary_y + BAR_HEIGHT_THICK + BAR_SPACING;
 
-        // Animated fill using sine wave
-        let animated_percent = ((phase.sin() + 1.0) / 2.0 * 100.0) as f32;
-
-        // Draw background
-        let bg_path =
-            self.rounded_rect_path(bar_x, primary_y, BAR_WIDTH, BAR_HEIGHT_THICK, BAR_RADIUS);
-        let bg_paint = create_paint(colors.track);
-        pixmap.fill_path(
-            &bg_path,
-            &bg_paint,
-            FillRule::Winding,
-            Transform::identity(),
-            None,
-        );
-
-        // Draw animated fill
-        let fill_width = BAR_WIDTH * animated_percent / 100.0;
-        if fill_width > 0.0 {
-            let fill_path =
-                self.rounded_rect_path(bar_x, primary_y, fill_width, BAR_HEIGHT_THICK, BAR_RADIUS);
-            let fill_paint = create_paint(colors.loading);
+        let (primary, secondary) = pattern.fills(phase);
+        let track_paint = create_paint(colors.track);
+        let fill_paint = create_paint(colors.loading);
+        for (y, height, percent) in [
+            (primary_y, BAR_HEIGHT_THICK, primary),
+            (secondary_y, BAR_HEIGHT_THIN, secondary),
+        ] {
+            let radius = BAR_RADIUS.min(height / 2.0);
+            let track_path = self.rounded_rect_path(bar_x, y, BAR_WIDTH, height, radius);
             pixmap.fill_path(
-                &fill_path,
-                &fill_paint,
+                &track_path,
+                &track_paint,
                 FillRule::Winding,
-                Transform::identity(),
+                self.transform(),
                 None,
             );
+
+            let fill_width = BAR_WIDTH * percent.clamp(0.0, 100.0) / 100.0;
+            if fill_width > 0.0 {
+                let fill_path = self.rounded_rect_path(bar_x, y, fill_width, height, radius);
+                pixmap.fill_path(
+                    &fill_path,
+                    &fill_paint,
+                    FillRule::Winding,
+                    self.transform(),
+                    None,
+                );
+            }
+            self.draw_outline(pixmap, &track_path, colors);
         }
     }
 
@@ -470,7 +886,7 @@ impl IconRenderer {
             size,
             stroke_width,
         ) {
-            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+            pixmap.fill_rect(rect, &paint, self.transform(), None);
         }
 
         // Draw vertical line
@@ -480,7 +896,7 @@ impl IconRenderer {
             stroke_width,
             size,
         ) {
-            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+            pixmap.fill_rect(rect, &paint, self.transform(), None);
         }
     }
 
@@ -489,11 +905,8 @@ impl IconRenderer {
         let x = self.width as f32 - STATUS_DOT_RADIUS - STATUS_DOT_MARGIN;
         let y = self.height as f32 - STATUS_DOT_RADIUS - STATUS_DOT_MARGIN;
 
-        let color = match indicator {
-            StatusIndicator::Minor => Color::from_rgba8(255, 193, 7, 255), // Yellow
-            StatusIndicator::Major => Color::from_rgba8(255, 152, 0, 255), // Orange
-            StatusIndicator::Critical => Color::from_rgba8(244, 67, 54, 255), // Red
-            _ => return, // Don't draw for None/Unknown
+        let Some(color) = status_color(indicator, self.palette) else {
+            return;
         };
 
         // Draw circle using path
@@ -501,13 +914,7 @@ impl IconRenderer {
         pb.push_circle(x, y, STATUS_DOT_RADIUS);
         if let Some(path) = pb.finish() {
             let paint = create_paint(color);
-            pixmap.fill_path(
-                &path,
-                &paint,
-                FillRule::Winding,
-                Transform::identity(),
-                None,
-            );
+            pixmap.fill_path(&path, &paint, FillRule::Winding, self.transform(), None);
         }
     }
 
@@ -549,5 +956,31 @@ impl IconRenderer {
     }
 }
 
+/// Returns the scale to draw icons at for a display with `backing_scale`
+/// device pixels per point and a menu bar `menu_bar_thickness` points tall.
+///
+/// A standard menu bar on a 2x display draws at 1.0; a 1x display at 0.5,
+/// with every meter on whole device pixels instead of downsampled. Taller
+/// menu bars enlarge the icon with them, up to a point.
+pub fn icon_scale(backing_scale: f64, menu_bar_thickness: f64) -> f32 {
+    let backing_scale = if backing_scale > 0.0 {
+        backing_scale
+    } else {
+        DESIGN_BACKING_SCALE
+    };
+    let growth = (menu_bar_thickness / STANDARD_MENU_BAR_THICKNESS).clamp(1.0, MAX_MENU_BAR_GROWTH);
+    (backing_scale / DESIGN_BACKING_SCALE * growth) as f32
+}
+
+/// Fades every pixel toward transparent by `amount` (0.0-1.0).
+///
+/// Pixels are premultiplied, so scaling all four channels keeps colors intact.
+fn dim_pixmap(pixmap: &mut Pixmap, amount: f32) {
+    let keep = 1.0 - amount.clamp(0.0, 1.0);
+    for byte in pixmap.data_mut() {
+        *byte = (f32::from(*byte) * keep).round() as u8;
+    }
+}
+
 #[cfg(test)]
 mod tests;
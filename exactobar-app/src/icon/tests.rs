@@ -62,10 +62,40 @@ fn test_render_credits() {
 #[test]
 fn test_render_loading() {
     let renderer = IconRenderer::new();
-    let icon = renderer.render_loading(ProviderKind::Codex, 0.5);
+    let icon = renderer.render_loading(ProviderKind::Codex, LoadingPattern::default(), 0.5);
     assert!(!icon.data.is_empty());
 }
 
+#[test]
+fn test_loading_patterns() {
+    let renderer = IconRenderer::new();
+    for &pattern in LoadingPattern::all() {
+        assert_eq!(LoadingPattern::from_name(pattern.name()), Some(pattern));
+
+        // Every pattern stays in range and moves
+        let frames: Vec<_> = (0..64)
+            .map(|step| pattern.fills(f64::from(step) * 0.1))
+            .collect();
+        for &(primary, secondary) in &frames {
+            assert!((0.0..=100.0).contains(&primary), "{pattern:?}");
+            assert!((0.0..=100.0).contains(&secondary), "{pattern:?}");
+        }
+        assert!(
+            frames.windows(2).any(|pair| pair[0] != pair[1]),
+            "{pattern:?}"
+        );
+
+        let first = renderer.render_loading(ProviderKind::Claude, pattern, 0.0);
+        let later = renderer.render_loading(ProviderKind::Claude, pattern, 1.0);
+        assert_ne!(first.data, later.data, "{pattern:?}");
+    }
+    assert_eq!(
+        LoadingPattern::from_name("Knight_Rider"),
+        Some(LoadingPattern::KnightRider)
+    );
+    assert_eq!(LoadingPattern::from_name("disco"), None);
+}
+
 #[test]
 fn test_render_error() {
     let renderer = IconRenderer::new();
@@ -187,3 +217,294 @@ fn test_render_codex_eye_with_status_indicator() {
     );
     assert!(!icon.data.is_empty());
 }
+
+// ============================================================================
+// Attention Animation Tests
+// ============================================================================
+
+#[test]
+fn test_render_attention_dims_icon() {
+    let renderer = IconRenderer::new();
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(97.0));
+
+    let normal = renderer.render(ProviderKind::Claude, Some(&snapshot), false, None, None);
+
+    let mut animation = IconAnimationState::new();
+    animation.attention = 1.0;
+    let hidden = renderer.render(
+        ProviderKind::Claude,
+        Some(&snapshot),
+        false,
+        None,
+        Some(&animation),
+    );
+
+    assert!(normal.data.iter().any(|&b| b > 0));
+    assert!(hidden.data.iter().all(|&b| b == 0));
+}
+
+// ============================================================================
+// Layout Tests
+// ============================================================================
+
+#[test]
+fn test_render_layouts() {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(42.0));
+    snapshot.secondary = Some(UsageWindow::new(10.0));
+
+    let mut icons = Vec::new();
+    for &layout in IconLayout::all() {
+        let renderer = IconRenderer::new().with_layout(layout);
+        assert_eq!(renderer.layout(), layout);
+
+        let icon = renderer.render(ProviderKind::Claude, Some(&snapshot), false, None, None);
+        assert!(icon.data.iter().any(|&b| b > 0), "{layout} drew nothing");
+        icons.push(icon.data);
+    }
+
+    // Every layout looks different
+    for (i, a) in icons.iter().enumerate() {
+        assert!(icons[i + 1..].iter().all(|b| a != b));
+    }
+}
+
+#[test]
+fn test_render_layouts_without_usage() {
+    for &layout in IconLayout::all() {
+        let renderer = IconRenderer::new().with_layout(layout);
+        let icon = renderer.render(ProviderKind::Claude, None, false, None, None);
+        assert!(icon.data.iter().any(|&b| b > 0), "{layout} drew nothing");
+    }
+}
+
+#[test]
+fn test_render_number_fits_three_digits() {
+    let renderer = IconRenderer::new().with_layout(IconLayout::Number);
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(100.0));
+
+    let icon = renderer.render(ProviderKind::Claude, Some(&snapshot), false, None, None);
+
+    // The outermost columns stay clear
+    let row_bytes = (icon.width * 4) as usize;
+    for row in icon.data.chunks_exact(row_bytes) {
+        assert_eq!(row[3], 0);
+        assert_eq!(row[row_bytes - 1], 0);
+    }
+}
+
+#[test]
+fn test_codex_eye_only_in_dual_bars() {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(42.0));
+
+    let render = |layout, provider| {
+        IconRenderer::new()
+            .with_layout(layout)
+            .render(provider, Some(&snapshot), false, None, None)
+            .data
+    };
+
+    assert_ne!(
+        render(IconLayout::DualBars, ProviderKind::Codex),
+        render(IconLayout::DualBars, ProviderKind::Claude)
+    );
+    assert_eq!(
+        render(IconLayout::Ring, ProviderKind::Codex),
+        render(IconLayout::Ring, ProviderKind::Claude)
+    );
+}
+
+// ============================================================================
+// Palette Tests
+// ============================================================================
+
+#[test]
+fn test_render_palettes() {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(90.0));
+
+    let icons: Vec<_> = ColorPalette::all()
+        .iter()
+        .map(|&palette| {
+            IconRenderer::new()
+                .with_mode(RenderMode::Colored)
+                .with_palette(palette)
+                .render(ProviderKind::Claude, Some(&snapshot), false, None, None)
+                .data
+        })
+        .collect();
+
+    for (i, a) in icons.iter().enumerate() {
+        assert!(icons[i + 1..].iter().all(|b| a != b));
+    }
+}
+
+#[test]
+fn test_status_colors() {
+    for &palette in ColorPalette::all() {
+        assert!(colors::status_color(StatusIndicator::None, palette).is_none());
+        let minor = colors::status_color(StatusIndicator::Minor, palette).unwrap();
+        let critical = colors::status_color(StatusIndicator::Critical, palette).unwrap();
+        assert_ne!(minor, critical);
+    }
+}
+
+// ============================================================================
+// Cache Tests
+// ============================================================================
+
+#[test]
+fn test_render_cached_reuses_icon() {
+    let renderer = IconRenderer::new();
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(62.3));
+
+    let first = renderer.render_cached(ProviderKind::Claude, Some(&snapshot), false, None, None);
+    // Less than a percent apart draws the same icon
+    snapshot.primary = Some(UsageWindow::new(62.4));
+    let second = renderer.render_cached(ProviderKind::Claude, Some(&snapshot), false, None, None);
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.png(), first.icon.to_png());
+
+    let incident = renderer.render_cached(
+        ProviderKind::Claude,
+        Some(&snapshot),
+        false,
+        Some(StatusIndicator::Major),
+        None,
+    );
+    assert!(!Arc::ptr_eq(&first, &incident));
+    assert_eq!(renderer.cache.len(), 2);
+}
+
+#[test]
+fn test_render_cached_skips_animations() {
+    let renderer = IconRenderer::new();
+    let blinking = IconAnimationState::with_blink(0.5);
+    renderer.render_cached(ProviderKind::Codex, None, false, None, Some(&blinking));
+    assert_eq!(renderer.cache.len(), 0);
+
+    let idle = IconAnimationState::new();
+    renderer.render_cached(ProviderKind::Codex, None, false, None, Some(&idle));
+    assert_eq!(renderer.cache.len(), 1);
+}
+
+#[test]
+fn test_layout_change_clears_cache() {
+    let mut renderer = IconRenderer::new().with_layout(IconLayout::DualBars);
+    renderer.render_cached(ProviderKind::Claude, None, false, None, None);
+
+    renderer.set_layout(IconLayout::DualBars);
+    assert_eq!(renderer.cache.len(), 1);
+    renderer.set_layout(IconLayout::Ring);
+    assert_eq!(renderer.cache.len(), 0);
+}
+
+// ============================================================================
+// Appearance Tests
+// ============================================================================
+
+#[test]
+fn test_adaptive_follows_appearance() {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(50.0));
+    let render = |appearance| {
+        IconRenderer::new()
+            .with_mode(RenderMode::Adaptive)
+            .with_appearance(appearance)
+            .render(ProviderKind::Claude, Some(&snapshot), false, None, None)
+            .data
+    };
+
+    let light = render(MenuBarAppearance::Light);
+    let dark = render(MenuBarAppearance::Dark);
+    // Same shape, black ink on light menu bars and white on dark ones
+    let alphas = |data: &[u8]| data.chunks(4).map(|px| px[3]).collect::<Vec<_>>();
+    assert_eq!(alphas(&light), alphas(&dark));
+    assert!(light.chunks(4).all(|px| px[0] == 0));
+    assert!(dark.chunks(4).any(|px| px[3] > 0 && px[0] > 0));
+}
+
+#[test]
+fn test_outline() {
+    for &layout in &[IconLayout::SingleBar, IconLayout::Ring] {
+        let render = |outline| {
+            IconRenderer::new()
+                .with_layout(layout)
+                .with_outline(outline)
+                .render(ProviderKind::Claude, None, false, None, None)
+                .data
+        };
+        assert_ne!(render(false), render(true), "{layout:?}");
+    }
+}
+
+#[test]
+fn test_appearance_change_clears_cache() {
+    let mut renderer = IconRenderer::new().with_mode(RenderMode::Adaptive);
+    renderer.render_cached(ProviderKind::Claude, None, false, None, None);
+
+    renderer.set_appearance(MenuBarAppearance::Light);
+    assert_eq!(renderer.cache.len(), 1);
+    renderer.set_appearance(MenuBarAppearance::Dark);
+    assert_eq!(renderer.cache.len(), 0);
+}
+
+// ============================================================================
+// Scale Tests
+// ============================================================================
+
+#[test]
+fn test_icon_scale() {
+    assert_eq!(icon_scale(2.0, STANDARD_MENU_BAR_THICKNESS), 1.0);
+    assert_eq!(icon_scale(1.0, STANDARD_MENU_BAR_THICKNESS), 0.5);
+    assert_eq!(icon_scale(2.0, 33.0), 1.5);
+    // Menu bars don't shrink icons, nor grow them without bound
+    assert_eq!(icon_scale(2.0, 11.0), 1.0);
+    assert_eq!(icon_scale(2.0, 88.0), 1.5);
+    assert_eq!(icon_scale(0.0, STANDARD_MENU_BAR_THICKNESS), 1.0);
+}
+
+#[test]
+fn test_render_at_scale() {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.primary = Some(UsageWindow::new(40.0));
+
+    for &layout in IconLayout::all() {
+        let renderer = IconRenderer::new().with_layout(layout).with_scale(0.5);
+        assert_eq!(renderer.pixel_size(), (ICON_WIDTH / 2, ICON_HEIGHT / 2));
+
+        let icon = renderer.render(ProviderKind::Claude, Some(&snapshot), false, None, None);
+        assert_eq!((icon.width, icon.height), (ICON_WIDTH / 2, ICON_HEIGHT / 2));
+        assert_eq!(icon.data.len(), (icon.width * icon.height * 4) as usize);
+        assert!(icon.data.chunks(4).any(|px| px[3] > 0), "{layout:?}");
+    }
+
+    let codex = IconRenderer::new().with_scale(1.5).render(
+        ProviderKind::Codex,
+        Some(&snapshot),
+        false,
+        None,
+        None,
+    );
+    assert_eq!((codex.width, codex.height), (54, 33));
+}
+
+#[test]
+fn test_render_cached_per_scale() {
+    let mut renderer = IconRenderer::new();
+    let retina = renderer.render_cached(ProviderKind::Claude, None, false, None, None);
+
+    renderer.set_scale(0.5);
+    let standard = renderer.render_cached(ProviderKind::Claude, None, false, None, None);
+    assert_eq!(standard.icon.width, ICON_WIDTH / 2);
+    assert_eq!(renderer.cache.len(), 2);
+
+    // Switching back reuses the icon drawn at that scale
+    renderer.set_scale(1.0);
+    let again = renderer.render_cached(ProviderKind::Claude, None, false, None, None);
+    assert!(Arc::ptr_eq(&retina, &again));
+}
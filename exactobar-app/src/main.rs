@@ -60,19 +60,34 @@
 //! ExactoBar - GPUI Menu Bar Application
 //!
 //! A macOS menu bar app for monitoring LLM provider usage.
+//!
+//! Pass `--locked` to show usage while blocking every credential change
+//! (API keys, cookie sources, companion cookie pushes, keychain prompts),
+//! e.g. on shared workstations.
 
 pub mod actions;
+pub mod automation;
+pub mod calendar;
+pub mod companion;
 pub mod components;
+pub mod cost;
+pub mod focus;
+pub mod hooks;
 pub mod icon;
 pub mod menu;
 pub mod notifications;
+pub mod ordering;
 pub mod refresh;
+pub mod snooze;
 pub mod state;
 pub mod theme;
+pub mod tooltip;
 pub mod tray;
 pub mod updater;
 pub mod windows;
 
+use exactobar_core::redact::{self, RedactingWriter};
+use exactobar_fetch::host::probe_cache;
 use gpui::*;
 use tracing::{Level, info};
 use tracing_subscriber::FmtSubscriber;
@@ -82,17 +97,40 @@ use crate::tray::SystemTray;
 
 /// Application entry point.
 fn main() {
-    // Initialize logging
+    // Initialize logging, with secrets redacted from logs and crash reports
+    redact::install_panic_hook();
     let subscriber = FmtSubscriber::builder()
         .with_max_level(Level::INFO)
         .with_target(false)
+        .with_writer(|| RedactingWriter::new(std::io::stdout()))
         .finish();
     tracing::subscriber::set_global_default(subscriber).ok();
 
     info!("ExactoBar starting...");
 
+    // `--locked` shows usage but blocks credential changes (shared machines)
+    if std::env::args().skip(1).any(|arg| arg == "--locked") {
+        info!("Locked mode: credential changes are disabled");
+        state::lock_by_flag();
+    }
+
+    // PTY probe output and fetch results are shared with `exactobar` CLI
+    // runs; fetch results only with runs for the same profile
+    probe_cache::set_probe_cache_dir(exactobar_store::default_cache_dir());
+    exactobar_fetch::set_snapshot_cache_dir(exactobar_store::profile_cache_dir(
+        &exactobar_store::current_profile(),
+    ));
+
+    // exactobar:// URLs from Shortcuts and AppleScript arrive before the
+    // app has finished launching, so they're queued until it has
+    let (url_tx, url_rx) = futures::channel::mpsc::unbounded();
+    let app = Application::new();
+    app.on_open_urls(move |urls| {
+        let _ = url_tx.unbounded_send(urls);
+    });
+
     // Run the GPUI application
-    Application::new().run(|cx: &mut App| {
+    app.run(move |cx: &mut App| {
         // IMPORTANT: Tray apps must not quit when the popup window closes!
         // On Linux, the default is to quit when last window closes.
         cx.set_quit_mode(QuitMode::Explicit);
@@ -118,6 +156,21 @@ fn main() {
             tray.start_animation_timer(cx);
         });
 
+        // Animate the icons of providers still loading their first usage
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            tray.start_loading_timer(cx);
+        });
+
+        // Keep the tooltip reset countdowns current
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            tray.start_tooltip_timer(cx);
+        });
+
+        // Redraw adaptive icons when the system switches light/dark
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            tray.start_appearance_observer(cx);
+        });
+
         // Debug: write icon PNG to temp file for verification
         #[cfg(debug_assertions)]
         {
@@ -136,6 +189,12 @@ fn main() {
         // Start background refresh task
         refresh::spawn_refresh_task(cx);
 
+        // Run commands from exactobar:// URLs
+        automation::listen(url_rx, cx);
+
+        // Start the companion extension endpoint if the user opted in
+        cx.global::<AppState>().settings.read(cx).apply_companion_endpoint();
+
         // Check for updates after a short delay (don't block startup)
         spawn_update_check(cx);
 
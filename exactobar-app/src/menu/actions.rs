@@ -1,9 +1,9 @@
 //! Action button components for quick provider actions.
 //!
-//! Provides Dashboard, Status, and Buy Credits buttons that open
+//! Provides Open Dashboard, Status, and Buy Credits buttons that open
 //! external URLs based on provider metadata.
 
-use exactobar_core::ProviderKind;
+use exactobar_core::{ProviderInfo, ProviderKind};
 use exactobar_providers::ProviderRegistry;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
@@ -36,7 +36,7 @@ pub fn open_url(url: &str) {
 }
 
 // ============================================================================
-// Action Buttons Section (Dashboard, Status, Buy Credits)
+// Action Buttons Section (Open Dashboard, Status, Buy Credits)
 // ============================================================================
 
 pub struct ActionButtonsSection {
@@ -59,6 +59,16 @@ impl ActionButtonsSection {
         }
     }
 
+    /// Buttons for a custom provider: its dashboard, if it has one.
+    pub fn custom(info: &ProviderInfo) -> Self {
+        Self {
+            dashboard_url: info.dashboard_url.clone(),
+            status_url: None,
+            supports_credits: false,
+            subscription_url: None,
+        }
+    }
+
     /// Returns true if there's at least one button to show.
     fn has_buttons(&self) -> bool {
         self.dashboard_url.is_some()
@@ -86,7 +96,7 @@ impl IntoElement for ActionButtonsSection {
 
         // Dashboard button
         if let Some(url) = self.dashboard_url.clone() {
-            row = row.child(ActionButton::new("Dashboard", "⌘D", move || {
+            row = row.child(ActionButton::new("Open Dashboard", "", move || {
                 open_url(&url);
             }));
         }
@@ -3,18 +3,29 @@
 //! The MenuCard shows provider identity, status, usage metrics,
 //! and action buttons in a cohesive card layout.
 
-use exactobar_core::{ProviderKind, UsageSnapshot};
+use chrono::{DateTime, Utc};
+use exactobar_core::{
+    BurnPlan, ProviderId, ProviderKind, ResetTime, ResetZone, UsageSnapshot, next_local_time,
+};
+use exactobar_fetch::FetchStage;
 use exactobar_providers::ProviderRegistry;
+use exactobar_providers::claude::WeeklySurfaceSplit;
+use exactobar_store::{AccountLabel, CostUsageSnapshot};
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 
 use crate::components::{ProviderIcon, Spinner};
+use crate::cost::CostScan;
+use crate::snooze::{self, SnoozeEnd, SnoozeLength};
 use crate::state::AppState;
 use crate::theme;
 
 use super::actions::ActionButtonsSection;
+use super::cost::CostSection;
 use super::error::{EnhancedErrorSection, InstallHint, get_install_hint};
-use super::usage::UsageMetricsSection;
+use super::models::{ModelsSection, ToggleHandler};
+use super::sparkline::Sparkline;
+use super::usage::{UsageMetricsSection, WeeklySplitSection};
 
 // ============================================================================
 // Menu Card Data
@@ -25,9 +36,21 @@ pub struct MenuCardData {
     pub provider_name: String,
     pub email: String,
     pub plan: Option<String>,
+    /// User's label for the account, if they named it
+    pub account_label: Option<AccountLabel>,
     pub snapshot: Option<UsageSnapshot>,
+    /// Pace against the session window's burn target, if one is set
+    pub burn_plan: Option<BurnPlan>,
+    /// Claude's weekly window split by surface (web extras)
+    pub weekly_split: Option<WeeklySurfaceSplit>,
+    /// Session usage this run, with resets marked
+    pub sparkline: Option<Sparkline>,
     pub is_refreshing: bool,
+    /// Stage of the refresh, if queued or refreshing
+    pub refresh_stage: Option<FetchStage>,
     pub error: Option<String>,
+    /// When refreshing resumes, if the provider rate limited us
+    pub rate_limited_until: Option<DateTime<Utc>>,
     /// Install hint when CLI is missing
     pub install_hint: Option<InstallHint>,
     pub session_label: &'static str,
@@ -36,6 +59,16 @@ pub struct MenuCardData {
     pub show_used: bool,
     /// Whether to show "Resets at 3:00 PM" instead of "Resets in 2h 30m"
     pub show_absolute: bool,
+    /// Timezone absolute reset times are shown in
+    pub reset_zone: ResetZone,
+    /// Whether to show the Cost section (cost tracking on, provider has logs)
+    pub show_cost: bool,
+    pub cost: Option<CostUsageSnapshot>,
+    pub cost_scan: Option<CostScan>,
+    /// Whether quota alerts are on for a session window that can be snoozed
+    pub snoozable: bool,
+    /// When the session window's snoozed alerts resume, if snoozed
+    pub snoozed_until: Option<SnoozeEnd>,
 }
 
 impl MenuCardData {
@@ -43,13 +76,20 @@ impl MenuCardData {
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
         let is_refreshing = state.is_provider_refreshing(provider, cx);
-        let error = state.get_error(provider, cx);
+        let refresh_stage = state.refresh_stage(provider, cx);
+        // A rate limited provider shows its cooldown, not the 429 error
+        let rate_limited_until = state.rate_limited_until(provider, cx);
+        let error = state
+            .get_error(provider, cx)
+            .filter(|_| rate_limited_until.is_none() && state.is_failing(provider, cx));
         let descriptor = ProviderRegistry::get(provider);
 
         // Read display settings
         let settings = state.settings.read(cx).settings();
         let show_used = settings.usage_bars_show_used;
         let show_absolute = settings.reset_times_show_absolute;
+        let reset_zone = ResetZone::from_setting(settings.reset_timezone.as_deref());
+        let show_cost = settings.cost_usage_enabled && crate::cost::supports_cost(provider);
 
         let provider_name = descriptor
             .map(|d| d.display_name().to_string())
@@ -70,23 +110,54 @@ impl MenuCardData {
             .unwrap_or("")
             .to_string();
         let plan = identity.and_then(|i| i.plan_name.clone());
+        let account_label = identity.and_then(|i| settings.account_label(i).cloned());
+        let now = Utc::now();
+        let burn_plan = settings
+            .burn_target(provider)
+            .and_then(|time| next_local_time(time, now))
+            .zip(snapshot.as_ref().and_then(|s| s.primary.as_ref()))
+            .and_then(|(target, window)| BurnPlan::new(window, target, now));
+
+        let usage = state.usage.read(cx);
+        let sparkline = Sparkline::from_history(usage.history(), usage.notes(), provider);
+        let weekly_split = usage
+            .claude_weekly_split()
+            .filter(|_| provider == ProviderKind::Claude && settings.claude_web_extras_enabled);
 
         // Detect install hints for missing CLIs
         let install_hint = error.as_ref().and_then(|e| get_install_hint(provider, e));
 
+        let snoozable = settings.session_quota_notifications_enabled
+            && snapshot.as_ref().is_some_and(|s| s.primary.is_some());
+        let snoozed_until = state
+            .snoozes
+            .get(provider, exactobar_store::WindowSlot::Primary, now);
+
         Self {
             provider,
             provider_name,
             email,
             plan,
+            account_label,
             snapshot,
+            burn_plan,
+            weekly_split,
+            sparkline,
             is_refreshing,
+            refresh_stage,
             error,
+            rate_limited_until,
             install_hint,
             session_label,
             weekly_label,
             show_used,
             show_absolute,
+            reset_zone,
+            show_cost,
+            cost: state.get_cost(provider, cx),
+            cost_scan: state.get_cost_scan(provider, cx),
+            snoozable,
+            snoozed_until,
         }
     }
 }
@@ -97,11 +168,32 @@ impl MenuCardData {
 
 pub struct MenuCard {
     data: MenuCardData,
+    models_expanded: bool,
+    on_toggle_models: Option<ToggleHandler>,
 }
 
 impl MenuCard {
     pub fn new(data: MenuCardData) -> Self {
-        Self { data }
+        Self {
+            data,
+            models_expanded: false,
+            on_toggle_models: None,
+        }
+    }
+
+    /// Whether the per-model breakdown is expanded.
+    pub fn models_expanded(mut self, expanded: bool) -> Self {
+        self.models_expanded = expanded;
+        self
+    }
+
+    /// Set a callback to be invoked when the Models header is clicked.
+    pub fn on_toggle_models(
+        mut self,
+        cb: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle_models = Some(Box::new(cb));
+        self
     }
 }
 
@@ -125,10 +217,19 @@ impl IntoElement for MenuCard {
             provider_name: self.data.provider_name.clone(),
             email: self.data.email.clone(),
             plan: self.data.plan.clone(),
+            account_label: self.data.account_label.clone(),
             is_refreshing: self.data.is_refreshing,
+            refresh_stage: self.data.refresh_stage,
             has_error: self.data.error.is_some(),
         });
 
+        if let Some(until) = self.data.rate_limited_until {
+            card = card.child(RateLimitRow {
+                until,
+                reset_zone: self.data.reset_zone,
+            });
+        }
+
         // Error display with install hints
         if let Some(ref err) = self.data.error {
             card = card.child(EnhancedErrorSection {
@@ -145,18 +246,290 @@ impl IntoElement for MenuCard {
                 Some("Search"),
                 self.data.show_used,
                 self.data.show_absolute,
+                self.data.reset_zone,
             ));
+            if let Some(split) = self.data.weekly_split {
+                card = card.child(WeeklySplitSection::new(split));
+            }
+            if let Some(limitation) = snap.limitation() {
+                card = card.child(
+                    div()
+                        .px(px(14.))
+                        .py(px(4.))
+                        .text_xs()
+                        .text_color(theme::warning())
+                        .child(format!("⚠ {}", limitation)),
+                );
+            }
+            if let Some(plan) = self.data.burn_plan {
+                card = card.child(BurnPlanRow {
+                    plan,
+                    reset_zone: self.data.reset_zone,
+                });
+            }
+            if let Some(sparkline) = self.data.sparkline {
+                card = card.child(sparkline);
+            }
         } else if !self.data.is_refreshing {
             card = card.child(PlaceholderSection);
         }
 
-        // Action buttons section (Dashboard, Status, Buy Credits)
+        // Token cost from local logs
+        if self.data.show_cost {
+            card = card.child(CostSection::new(
+                provider,
+                self.data.cost.clone(),
+                self.data.cost_scan.clone(),
+            ));
+        }
+
+        // Per-model breakdown (quota per model, or cost per model)
+        let quotas = self
+            .data
+            .snapshot
+            .as_ref()
+            .filter(|_| self.data.error.is_none())
+            .map(|snap| snap.models.as_slice())
+            .unwrap_or_default();
+        let cost = self.data.cost.as_ref().filter(|_| self.data.show_cost);
+        if let Some(mut models) = ModelsSection::new(provider, quotas, cost) {
+            models = models.expanded(self.models_expanded);
+            if let Some(on_toggle) = self.on_toggle_models {
+                models = models.on_toggle(on_toggle);
+            }
+            card = card.child(models);
+        }
+
+        // Snooze the session window's alerts
+        if self.data.snoozable {
+            card = card.child(SnoozeRow {
+                provider,
+                snoozed_until: self.data.snoozed_until,
+                reset_zone: self.data.reset_zone,
+            });
+        }
+
+        // Action buttons section (Open Dashboard, Status, Buy Credits)
         card = card.child(ActionButtonsSection::new(provider));
 
         card
     }
 }
 
+// ============================================================================
+// Burn Plan
+// ============================================================================
+
+/// Allowed burn rate to reach the burn target, and whether usage is ahead
+/// of or behind that pace.
+struct BurnPlanRow {
+    plan: BurnPlan,
+    reset_zone: ResetZone,
+}
+
+impl IntoElement for BurnPlanRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let delta = self.plan.pace_delta();
+        let pace = if delta.abs() < 1.0 {
+            "on pace".to_string()
+        } else if delta > 0.0 {
+            format!("{:.0}% ahead", delta)
+        } else {
+            format!("{:.0}% behind", -delta)
+        };
+        let color = if self.plan.is_over_pace() {
+            theme::warning()
+        } else {
+            theme::muted()
+        };
+
+        div()
+            .px(px(14.))
+            .py(px(4.))
+            .text_xs()
+            .text_color(color)
+            .child(format!(
+                "{:.0}%/h until {} · {}",
+                self.plan.allowed_per_hour,
+                ResetTime::new(self.plan.target).format_clock(&self.reset_zone),
+                pace
+            ))
+    }
+}
+
+// ============================================================================
+// Rate Limit
+// ============================================================================
+
+/// When refreshing resumes for a provider that rate limited us.
+struct RateLimitRow {
+    until: DateTime<Utc>,
+    reset_zone: ResetZone,
+}
+
+impl IntoElement for RateLimitRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        div()
+            .px(px(14.))
+            .py(px(4.))
+            .text_xs()
+            .text_color(theme::warning())
+            .child(format!(
+                "⏸ Rate limited — refreshing again at {}",
+                ResetTime::new(self.until).format_clock(&self.reset_zone)
+            ))
+    }
+}
+
+// ============================================================================
+// Snooze
+// ============================================================================
+
+/// Buttons that snooze a provider's quota alerts, or when snoozed, when
+/// they resume and a button to resume them now.
+struct SnoozeRow {
+    provider: ProviderKind,
+    snoozed_until: Option<SnoozeEnd>,
+    reset_zone: ResetZone,
+}
+
+impl IntoElement for SnoozeRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let provider = self.provider;
+        let button = |id: &'static str, label: &'static str| {
+            div()
+                .id(SharedString::from(format!("{}-{:?}", id, provider)))
+                .px(px(6.))
+                .rounded(px(4.))
+                .cursor_pointer()
+                .text_color(theme::text_secondary())
+                .hover(|s| s.bg(theme::hover()))
+                .child(label)
+        };
+
+        let row = div()
+            .px(px(14.))
+            .py(px(4.))
+            .flex()
+            .items_center()
+            .gap(px(4.))
+            .text_xs()
+            .text_color(theme::muted());
+
+        match self.snoozed_until {
+            Some(end) => {
+                let until = match end {
+                    SnoozeEnd::At(at) => ResetTime::new(at).format_clock(&self.reset_zone),
+                    SnoozeEnd::Reset => "the reset".to_string(),
+                };
+                row.child(format!("Alerts snoozed until {}", until)).child(
+                    button("snooze-resume", "Resume")
+                        .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+                            snooze::resume(provider, cx)
+                        }),
+                )
+            }
+            None => {
+                row.child("Snooze alerts:")
+                    .child(
+                        button("snooze-hour", "1 hour")
+                            .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+                                snooze::snooze(provider, SnoozeLength::Hour, cx)
+                            }),
+                    )
+                    .child(button("snooze-reset", "Until reset").on_mouse_down(
+                        MouseButton::Left,
+                        move |_, _window, cx| {
+                            snooze::snooze(provider, SnoozeLength::UntilReset, cx)
+                        },
+                    ))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Custom Provider Card
+// ============================================================================
+
+/// Card for a custom provider: a name header, its usage or error, and a
+/// link to its dashboard if it has one.
+pub struct CustomCard {
+    id: ProviderId,
+    result: Result<UsageSnapshot, String>,
+    show_used: bool,
+    show_absolute: bool,
+    reset_zone: ResetZone,
+}
+
+impl CustomCard {
+    pub fn new<V: 'static>(
+        id: ProviderId,
+        result: Result<UsageSnapshot, String>,
+        cx: &Context<V>,
+    ) -> Self {
+        let settings = cx.global::<AppState>().settings.read(cx).settings();
+        Self {
+            id,
+            result,
+            show_used: settings.usage_bars_show_used,
+            show_absolute: settings.reset_times_show_absolute,
+            reset_zone: ResetZone::from_setting(settings.reset_timezone.as_deref()),
+        }
+    }
+}
+
+impl IntoElement for CustomCard {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let info = ProviderRegistry::info(self.id);
+        let name = info
+            .as_ref()
+            .map_or_else(|| self.id.to_string(), |i| i.display_name.clone());
+
+        let header = div()
+            .px(px(14.))
+            .py(px(10.))
+            .bg(theme::card_background())
+            .border_b_1()
+            .border_color(theme::glass_separator())
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme::text_primary())
+                    .child(name),
+            );
+
+        let card = div().flex().flex_col().child(header);
+        let card = match self.result {
+            Ok(snapshot) => card.child(UsageMetricsSection::new(
+                &snapshot,
+                info.as_ref()
+                    .map_or("Session", |i| i.session_label.as_str()),
+                info.as_ref().map_or("Weekly", |i| i.weekly_label.as_str()),
+                None,
+                self.show_used,
+                self.show_absolute,
+                self.reset_zone,
+            )),
+            Err(error) => card.child(EnhancedErrorSection {
+                summary: error,
+                details: None,
+                install_hint: None,
+            }),
+        };
+        card.children(info.as_ref().map(ActionButtonsSection::custom))
+    }
+}
+
 // ============================================================================
 // Card Header
 // ============================================================================
@@ -166,7 +539,9 @@ struct CardHeader {
     provider_name: String,
     email: String,
     plan: Option<String>,
+    account_label: Option<AccountLabel>,
     is_refreshing: bool,
+    refresh_stage: Option<FetchStage>,
     has_error: bool,
 }
 
@@ -174,7 +549,10 @@ impl IntoElement for CardHeader {
     type Element = Div;
 
     fn into_element(self) -> Self::Element {
-        let status_text = if self.is_refreshing {
+        let provider = self.provider;
+        let status_text = if let Some(stage) = self.refresh_stage {
+            format!("{stage}...")
+        } else if self.is_refreshing {
             "Refreshing...".to_string()
         } else if self.has_error {
             "Error".to_string()
@@ -196,15 +574,23 @@ impl IntoElement for CardHeader {
                 .gap(px(8.))
                 .child(ProviderIcon::new(self.provider).size(px(18.)))
                 .child(
+                    // Clicking the name opens everything known about the provider
                     div()
                         .text_sm()
                         .font_weight(FontWeight::SEMIBOLD)
                         .text_color(theme::text_primary())
+                        .cursor_pointer()
+                        .hover(|s| s.underline())
+                        .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+                            crate::windows::open_provider_detail(provider, cx);
+                        })
                         .child(self.provider_name),
                 ),
         );
 
-        if !self.email.is_empty() {
+        if let Some(label) = self.account_label {
+            top_row = top_row.child(AccountTag::new(label));
+        } else if !self.email.is_empty() {
             top_row = top_row.child(div().text_xs().text_color(theme::muted()).child(self.email));
         }
 
@@ -215,6 +601,9 @@ impl IntoElement for CardHeader {
             .gap(px(6.))
             .child(div().text_xs().text_color(status_color).child(status_text));
 
+        if let Some(stage) = self.refresh_stage {
+            status_row = status_row.child(StepIndicator { stage });
+        }
         if self.is_refreshing {
             status_row = status_row.child(Spinner::new());
         }
@@ -244,6 +633,73 @@ impl IntoElement for CardHeader {
     }
 }
 
+// ============================================================================
+// Step Indicator
+// ============================================================================
+
+/// One dot per refresh step, filled up to the current one.
+struct StepIndicator {
+    stage: FetchStage,
+}
+
+impl IntoElement for StepIndicator {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let current = self.stage.step();
+        div()
+            .flex()
+            .items_center()
+            .gap(px(3.))
+            .children((0..FetchStage::STEP_COUNT).map(|step| {
+                let color = if step <= current {
+                    theme::accent()
+                } else {
+                    theme::glass_separator()
+                };
+                div().size(px(5.)).rounded_full().bg(color)
+            }))
+    }
+}
+
+// ============================================================================
+// Account Tag
+// ============================================================================
+
+/// Colored dot and name for a labeled account.
+pub struct AccountTag {
+    label: AccountLabel,
+}
+
+impl AccountTag {
+    pub fn new(label: AccountLabel) -> Self {
+        Self { label }
+    }
+}
+
+impl IntoElement for AccountTag {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        div()
+            .flex()
+            .items_center()
+            .gap(px(4.))
+            .child(
+                div()
+                    .size(px(8.))
+                    .rounded_full()
+                    .bg(theme::account_color(self.label.color)),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme::text_secondary())
+                    .child(self.label.name),
+            )
+    }
+}
+
 // ============================================================================
 // Placeholder Section
 // ============================================================================
@@ -0,0 +1,145 @@
+//! Compact provider rows.
+//!
+//! With the compact density, the All tab shows one line per provider
+//! instead of a card, so menus with many providers fit without scrolling.
+//! Clicking a row opens the provider's tab with its full card.
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use crate::components::{ProviderIcon, Spinner};
+use crate::theme;
+
+use super::card::MenuCardData;
+use super::models::ToggleHandler;
+use super::usage::usage_color;
+
+// ============================================================================
+// Compact Row
+// ============================================================================
+
+pub struct CompactRow {
+    data: MenuCardData,
+    on_click: Option<ToggleHandler>,
+}
+
+impl CompactRow {
+    pub fn new(data: MenuCardData) -> Self {
+        Self {
+            data,
+            on_click: None,
+        }
+    }
+
+    /// Set a callback to be invoked when the row is clicked.
+    pub fn on_click(
+        mut self,
+        cb: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(cb));
+        self
+    }
+
+    /// Formats a window's usage as the cards do: used or remaining.
+    fn percent(&self, used_percent: f64) -> String {
+        if self.data.show_used {
+            format!("{:.0}%", used_percent)
+        } else {
+            format!("{:.0}%", 100.0 - used_percent)
+        }
+    }
+}
+
+impl IntoElement for CompactRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let snapshot = self.data.snapshot.as_ref();
+        let session = snapshot
+            .and_then(|s| s.primary.as_ref())
+            .map(|w| w.clamped_used_percent());
+        let weekly = snapshot
+            .and_then(|s| s.secondary.as_ref())
+            .map(|w| w.clamped_used_percent());
+
+        let status = if self.data.error.is_some() {
+            div()
+                .text_xs()
+                .text_color(theme::error())
+                .child("Error")
+                .into_any_element()
+        } else if let Some(used) = session {
+            div()
+                .flex()
+                .items_center()
+                .gap(px(6.))
+                .child(
+                    div()
+                        .w(px(60.))
+                        .h(px(4.))
+                        .rounded(px(2.))
+                        .bg(theme::track())
+                        .child(
+                            div()
+                                .h_full()
+                                .w(relative((used / 100.0) as f32))
+                                .rounded(px(2.))
+                                .bg(usage_color(used)),
+                        ),
+                )
+                .child(
+                    div()
+                        .w(px(32.))
+                        .text_xs()
+                        .text_right()
+                        .text_color(theme::text_primary())
+                        .child(self.percent(used)),
+                )
+                .into_any_element()
+        } else if self.data.is_refreshing {
+            Spinner::new().size(px(12.)).into_any_element()
+        } else {
+            div()
+                .text_xs()
+                .text_color(theme::muted())
+                .child("—")
+                .into_any_element()
+        };
+
+        let hover_bg = theme::hover();
+        let weekly_text = weekly.map(|used| self.percent(used));
+        let mut row = div()
+            .px(px(14.))
+            .py(px(6.))
+            .flex()
+            .items_center()
+            .gap(px(8.))
+            .cursor_pointer()
+            .hover(move |s| s.bg(hover_bg))
+            .child(ProviderIcon::new(self.data.provider).size(px(14.)))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .overflow_hidden()
+                    .text_sm()
+                    .text_color(theme::text_primary())
+                    .child(self.data.provider_name),
+            )
+            .child(status)
+            // Weekly usage, muted, after the session bar
+            .child(
+                div()
+                    .w(px(32.))
+                    .text_xs()
+                    .text_right()
+                    .text_color(theme::muted())
+                    .when_some(weekly_text, |el, text| el.child(text)),
+            );
+
+        if let Some(on_click) = self.on_click {
+            row = row.on_mouse_down(MouseButton::Left, on_click);
+        }
+        row
+    }
+}
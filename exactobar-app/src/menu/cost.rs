@@ -0,0 +1,192 @@
+//! Token cost display for the menu card.
+//!
+//! Shows today's and 30-day spend computed from local logs. While a
+//! background scan is running, a spinner with file progress and a Cancel
+//! button are shown below the last known totals.
+
+use chrono::Utc;
+use exactobar_core::ProviderKind;
+use exactobar_store::CostUsageSnapshot;
+use gpui::*;
+
+use crate::components::Spinner;
+use crate::cost::CostScan;
+use crate::state::AppState;
+use crate::theme;
+
+// ============================================================================
+// Cost Section
+// ============================================================================
+
+pub struct CostSection {
+    provider: ProviderKind,
+    cost: Option<CostUsageSnapshot>,
+    scan: Option<CostScan>,
+}
+
+impl CostSection {
+    pub fn new(
+        provider: ProviderKind,
+        cost: Option<CostUsageSnapshot>,
+        scan: Option<CostScan>,
+    ) -> Self {
+        Self {
+            provider,
+            cost,
+            scan,
+        }
+    }
+}
+
+impl IntoElement for CostSection {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let mut section = div()
+            .px(px(14.))
+            .py(px(10.))
+            .bg(theme::card_background())
+            .border_b_1()
+            .border_color(theme::glass_separator())
+            .flex()
+            .flex_col()
+            .gap(px(4.))
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(theme::text_primary())
+                    .child("Cost"),
+            );
+
+        // Last known totals stay visible while a rescan runs
+        if let Some(cost) = &self.cost {
+            let today = cost.cost_on(Utc::now().date_naive());
+            section = section
+                .child(CostRow::new("Today", format!("${:.2}", today)))
+                .child(CostRow::new(
+                    "Last 30 days",
+                    format!("${:.2}", cost.total_cost_usd),
+                ));
+        }
+
+        if let Some(scan) = self.scan {
+            section = section.child(ScanProgressRow {
+                provider: self.provider,
+                scan,
+            });
+        } else if self.cost.is_none() {
+            section = section.child(
+                div()
+                    .text_xs()
+                    .text_color(theme::muted())
+                    .child("No cost data yet"),
+            );
+        }
+
+        section
+    }
+}
+
+// ============================================================================
+// Cost Row
+// ============================================================================
+
+struct CostRow {
+    label: &'static str,
+    value: String,
+}
+
+impl CostRow {
+    fn new(label: &'static str, value: String) -> Self {
+        Self { label, value }
+    }
+}
+
+impl IntoElement for CostRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme::text_secondary())
+                    .child(self.label),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(theme::text_primary())
+                    .child(self.value),
+            )
+    }
+}
+
+// ============================================================================
+// Scan Progress Row
+// ============================================================================
+
+struct ScanProgressRow {
+    provider: ProviderKind,
+    scan: CostScan,
+}
+
+impl IntoElement for ScanProgressRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let scan = self.scan;
+
+        let status = if scan.is_cancelled() {
+            "Cancelling...".to_string()
+        } else if scan.files_total == 0 {
+            "Scanning logs...".to_string()
+        } else {
+            format!("Scanning logs... {}/{}", scan.files_done, scan.files_total)
+        };
+
+        let mut row = div().flex().items_center().justify_between().child(
+            div()
+                .flex()
+                .items_center()
+                .gap(px(6.))
+                .child(Spinner::new().size(px(12.)))
+                .child(div().text_xs().text_color(theme::muted()).child(status)),
+        );
+
+        if !scan.is_cancelled() {
+            row = row.child(
+                div()
+                    .id(SharedString::from(format!(
+                        "cancel-cost-scan-{:?}",
+                        self.provider
+                    )))
+                    .px(px(8.))
+                    .py(px(2.))
+                    .rounded(px(4.))
+                    .cursor_pointer()
+                    .text_xs()
+                    .text_color(theme::text_secondary())
+                    .bg(theme::surface())
+                    .border_1()
+                    .border_color(theme::border())
+                    .hover(|s| s.bg(theme::hover()))
+                    .active(|s| s.bg(theme::active()))
+                    .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+                        scan.cancel();
+                        // Re-render to show "Cancelling..." until the scan stops
+                        let usage = cx.global::<AppState>().usage.clone();
+                        usage.update(cx, |_, cx| cx.notify());
+                    })
+                    .child("Cancel"),
+            );
+        }
+
+        row
+    }
+}
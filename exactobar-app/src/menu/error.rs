@@ -40,7 +40,7 @@ pub fn get_install_hint(provider: ProviderKind, error: &str) -> Option<InstallHi
         ProviderKind::Codex => ("codex CLI", "npm install -g @openai/codex"),
         ProviderKind::Claude => ("claude CLI", "npm install -g @anthropic/claude-code"),
         ProviderKind::Cursor => ("Cursor app", "Download from cursor.com"),
-        ProviderKind::Copilot => ("gh CLI", "brew install gh"),
+        ProviderKind::Copilot | ProviderKind::GitHubModels => ("gh CLI", "brew install gh"),
         ProviderKind::Gemini => ("gcloud CLI", "brew install google-cloud-sdk"),
         ProviderKind::Kiro => ("kiro CLI", "npm install -g kiro-cli"),
         ProviderKind::Factory => ("factory CLI", "npm install -g @anthropic/factory"),
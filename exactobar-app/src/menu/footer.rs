@@ -1,8 +1,10 @@
-//! Menu footer with action buttons (Refresh, Settings, Quit).
+//! Menu footer with action buttons (Refresh, Commands, History, Settings,
+//! Quit).
 //!
 //! These buttons actually work - they trigger real actions through
 //! the global AppState and window management.
 
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use tracing::info;
 
@@ -38,6 +40,10 @@ impl IntoElement for MenuFooter {
             .justify_between()
             // Refresh button - ACTUALLY REFRESHES
             .child(FooterActionButton::refresh())
+            // Commands button - OPENS THE COMMAND PALETTE
+            .child(FooterActionButton::commands())
+            // History button - OPENS THE USAGE HISTORY WINDOW
+            .child(FooterActionButton::history())
             // Settings button - OPENS SETTINGS
             .child(FooterActionButton::settings())
             // Quit button - ACTUALLY QUITS
@@ -59,6 +65,8 @@ impl Default for MenuFooter {
 #[derive(Clone, Copy, Debug)]
 enum FooterAction {
     Refresh,
+    Commands,
+    History,
     Settings,
     Quit,
 }
@@ -79,6 +87,22 @@ impl FooterActionButton {
         }
     }
 
+    fn commands() -> Self {
+        Self {
+            action: FooterAction::Commands,
+            label: "Commands",
+            shortcut: "⌘K",
+        }
+    }
+
+    fn history() -> Self {
+        Self {
+            action: FooterAction::History,
+            label: "History",
+            shortcut: "",
+        }
+    }
+
     fn settings() -> Self {
         Self {
             action: FooterAction::Settings,
@@ -108,7 +132,7 @@ impl IntoElement for FooterActionButton {
 
         div()
             .id(SharedString::from(label))
-            .px(px(10.))
+            .px(px(8.))
             .py(px(6.))
             .rounded(px(6.))
             .cursor_pointer()
@@ -126,6 +150,13 @@ impl IntoElement for FooterActionButton {
                             state.refresh_all(cx);
                         });
                     }
+                    FooterAction::Commands => {
+                        // Deferred so the window opens outside this event handler
+                        cx.defer(windows::open_command_palette);
+                    }
+                    FooterAction::History => {
+                        cx.defer(windows::open_history);
+                    }
                     FooterAction::Settings => {
                         tracing::trace!("Settings button clicked, opening settings window");
                         let task = cx.spawn(async move |mut cx| {
@@ -137,7 +168,7 @@ impl IntoElement for FooterActionButton {
                     }
                     FooterAction::Quit => {
                         // Quit the application
-                        cx.quit();
+                        crate::actions::quit(cx);
                     }
                 }
             })
@@ -150,6 +181,8 @@ impl IntoElement for FooterActionButton {
                     .text_color(theme::text_primary())
                     .child(label),
             )
-            .child(div().text_xs().text_color(theme::muted()).child(shortcut))
+            .when(!shortcut.is_empty(), |el| {
+                el.child(div().text_xs().text_color(theme::muted()).child(shortcut))
+            })
     }
 }
@@ -5,12 +5,20 @@
 //!
 //! Uses transparent backgrounds to let the window's blur effect show through.
 //!
+//! The panel closes when it loses focus, like a menu. Pinning it keeps it
+//! open on top of other windows, updating as refreshes come in, to watch
+//! usage during long agent sessions.
+//!
 //! # Module Structure
 //!
-//! - `mod.rs` - MenuPanel, MenuHeader, TrayMenu alias
-//! - `card.rs` - MenuCard, MenuCardData, CardHeader
+//! - `mod.rs` - MenuPanel, MenuHeader (with profile picker), TrayMenu alias
+//! - `card.rs` - MenuCard, MenuCardData, CardHeader, CustomCard, AccountTag
+//! - `compact.rs` - CompactRow (one line per provider, compact density)
 //! - `error.rs` - EnhancedErrorSection, InstallHint, clipboard helpers
-//! - `usage.rs` - UsageMetricsSection, ProgressBar
+//! - `usage.rs` - UsageMetricsSection, WeeklySplitSection, ProgressBar
+//! - `sparkline.rs` - Sparkline (session trend with reset markers)
+//! - `cost.rs` - CostSection, scan progress
+//! - `models.rs` - ModelsSection (per-model breakdown)
 //! - `actions.rs` - ActionButtonsSection, ActionButton, URL opening
 //! - `footer.rs` - MenuFooter, FooterActionButton
 
@@ -18,20 +26,28 @@
 
 mod actions;
 mod card;
+mod compact;
+mod cost;
 mod error;
 mod footer;
+mod models;
+mod sparkline;
 mod tabs;
 mod usage;
 
 pub use tabs::SelectedTab;
 
 // Re-exports for public API
-pub use card::{MenuCard, MenuCardData};
+pub(crate) use actions::open_url;
+pub use card::{CustomCard, MenuCard, MenuCardData};
 pub use error::{EnhancedErrorSection, InstallHint, copy_to_clipboard, get_install_hint};
 pub use footer::MenuFooter;
+pub(crate) use usage::{UsageMetricsSection, usage_color};
+
+use std::collections::{HashMap, HashSet};
 
 use exactobar_core::ProviderKind;
-use exactobar_store::ThemeMode;
+use exactobar_store::{AccountLabel, MenuDensity, ThemeMode};
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 use tracing::{debug, info};
@@ -39,6 +55,8 @@ use tracing::{debug, info};
 use crate::state::AppState;
 use crate::theme;
 
+use compact::CompactRow;
+
 // ============================================================================
 // Menu Panel
 // ============================================================================
@@ -47,8 +65,18 @@ use crate::theme;
 pub struct MenuPanel {
     /// Currently selected tab (All or a specific provider).
     selected_tab: SelectedTab,
+    /// Providers whose per-model breakdown is expanded.
+    expanded_models: HashSet<ProviderKind>,
     /// Theme mode subscription - forces re-render when theme changes.
     subscription: Option<gpui::Subscription>,
+    /// Usage subscription - re-renders as refreshes come in.
+    usage_subscription: Option<gpui::Subscription>,
+    /// Closes the panel when it loses focus, unless pinned.
+    activation_subscription: Option<gpui::Subscription>,
+    /// Whether the panel stays open when it loses focus.
+    pinned: bool,
+    /// Switcher groups collapsed to their header.
+    collapsed_groups: HashSet<String>,
 }
 
 impl MenuPanel {
@@ -59,10 +87,43 @@ impl MenuPanel {
             selected_tab: initial_provider
                 .map(SelectedTab::Provider)
                 .unwrap_or(SelectedTab::All),
+            expanded_models: HashSet::new(),
             subscription: None,
+            usage_subscription: None,
+            activation_subscription: None,
+            pinned: false,
+            collapsed_groups: HashSet::new(),
         }
     }
 
+    /// Renders the button that pins the panel open.
+    /// This must be called from render() where we have access to cx.listener().
+    fn render_pin_button(&self, hover_bg: Hsla, cx: &mut Context<Self>) -> impl IntoElement {
+        let pinned = self.pinned;
+        div()
+            .id("pin-menu")
+            .px(px(8.))
+            .py(px(2.))
+            .rounded(px(6.))
+            .text_xs()
+            .cursor_pointer()
+            .when(pinned, |el| {
+                el.bg(theme::accent()).text_color(gpui::white())
+            })
+            .when(!pinned, |el| {
+                el.text_color(theme::muted()).hover(move |s| s.bg(hover_bg))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, _window, cx| {
+                    this.pinned = !this.pinned;
+                    info!(pinned = this.pinned, "Menu pin toggled");
+                    cx.notify();
+                }),
+            )
+            .child(if pinned { "Pinned" } else { "Pin" })
+    }
+
     /// Renders the provider switcher with WORKING click handlers.
     /// This must be called from render() where we have access to cx.listener().
     fn render_provider_switcher(
@@ -73,6 +134,20 @@ impl MenuPanel {
         active_bg: Hsla,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
+        // Account labels, shown as a colored dot next to the provider name
+        let labels: HashMap<ProviderKind, AccountLabel> = {
+            let state = cx.global::<AppState>();
+            let settings = state.settings.read(cx).settings();
+            providers
+                .iter()
+                .filter_map(|&p| {
+                    let snapshot = state.get_snapshot(p, cx)?;
+                    let label = settings.account_label(snapshot.identity.as_ref()?)?;
+                    Some((p, label.clone()))
+                })
+                .collect()
+        };
+
         // Build the "All" tab button first
         let is_all_selected = self.selected_tab.is_all();
         let all_btn = div()
@@ -101,58 +176,234 @@ impl MenuPanel {
             )
             .child(div().text_sm().child("All"));
 
+        // Providers in the order the switcher lists them, by group
+        let mut sections = cx
+            .global::<AppState>()
+            .settings
+            .read(cx)
+            .settings()
+            .group_providers(providers);
+        let ungrouped = match sections.first() {
+            Some((None, _)) => sections.remove(0).1,
+            _ => Vec::new(),
+        };
+
         div()
             .px(px(10.))
             .py(px(8.))
             // TRUE LIQUID GLASS: NO background - let window blur shine through!
             .flex()
-            .flex_wrap()
+            .flex_col()
             .gap(px(4.))
-            // "All" tab first
-            .child(all_btn)
-            // Then individual provider tabs
-            .children(providers.iter().map(|&provider| {
-                let is_selected = self.selected_tab == SelectedTab::Provider(provider);
-                let name = provider.display_name();
-
-                let mut btn = div()
-                    .id(SharedString::from(format!("switch-{:?}", provider)))
-                    .px(px(10.))
-                    .py(px(5.))
-                    .rounded(px(6.))
-                    .cursor_pointer()
-                    .text_color(text_primary)
-                    // THE MAGIC: cx.listener() gives us access to `this`!
-                    .on_mouse_down(
-                        MouseButton::Left,
-                        cx.listener(move |this, _, _window, cx| {
-                            let p = provider; // Copy provider into closure (it's Copy)
-                            info!(provider = ?p, "Provider switch button clicked!");
-                            this.selected_tab = SelectedTab::Provider(p);
-
-                            // Check if this provider has data, if not trigger refresh
-                            let state = cx.global::<AppState>();
-                            let has_snapshot = state.get_snapshot(p, cx).is_some();
-                            if !has_snapshot {
-                                info!(provider = ?p, "No snapshot, triggering refresh");
-                                cx.update_global::<AppState, _>(|state, cx| {
-                                    state.refresh_provider(p, cx);
-                                });
-                            }
-
-                            cx.notify(); // Re-render with new selection!
-                        }),
-                    );
-
-                if is_selected {
-                    btn = btn.bg(theme::accent()).text_color(gpui::white());
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap(px(4.))
+                    // "All" tab first
+                    .child(all_btn)
+                    // Then providers outside any group
+                    .children(ungrouped.into_iter().map(|provider| {
+                        self.render_switch_button(
+                            provider,
+                            labels.get(&provider),
+                            text_primary,
+                            hover_bg,
+                            active_bg,
+                            cx,
+                        )
+                    })),
+            )
+            // Then a collapsible section per group
+            .children(sections.into_iter().map(|(group, members)| {
+                let group = group.unwrap_or_default();
+                let collapsed = self.collapsed_groups.contains(&group);
+                let header = if collapsed {
+                    format!("▸ {} ({})", group, members.len())
                 } else {
-                    btn = btn
-                        .hover(move |s| s.bg(hover_bg))
-                        .active(move |s| s.bg(active_bg));
-                }
+                    format!("▾ {}", group)
+                };
+                let toggled = group.clone();
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(2.))
+                    .child(
+                        div()
+                            .id(SharedString::from(format!("switch-group-{}", group)))
+                            .px(px(4.))
+                            .text_xs()
+                            .text_color(theme::muted())
+                            .cursor_pointer()
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _, _window, cx| {
+                                    if !this.collapsed_groups.remove(&toggled) {
+                                        this.collapsed_groups.insert(toggled.clone());
+                                    }
+                                    cx.notify();
+                                }),
+                            )
+                            .child(header),
+                    )
+                    .when(!collapsed, |el| {
+                        el.child(div().flex().flex_wrap().gap(px(4.)).children(
+                            members.into_iter().map(|provider| {
+                                self.render_switch_button(
+                                    provider,
+                                    labels.get(&provider),
+                                    text_primary,
+                                    hover_bg,
+                                    active_bg,
+                                    cx,
+                                )
+                            }),
+                        ))
+                    })
+            }))
+    }
+
+    /// Renders a provider's button in the switcher.
+    fn render_switch_button(
+        &self,
+        provider: ProviderKind,
+        label: Option<&AccountLabel>,
+        text_primary: Hsla,
+        hover_bg: Hsla,
+        active_bg: Hsla,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_selected = self.selected_tab == SelectedTab::Provider(provider);
+        let name = provider.display_name();
+
+        let mut btn = div()
+            .id(SharedString::from(format!("switch-{:?}", provider)))
+            .px(px(10.))
+            .py(px(5.))
+            .rounded(px(6.))
+            .cursor_pointer()
+            .text_color(text_primary)
+            // THE MAGIC: cx.listener() gives us access to `this`!
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _, _window, cx| {
+                    let p = provider; // Copy provider into closure (it's Copy)
+                    info!(provider = ?p, "Provider switch button clicked!");
+                    this.selected_tab = SelectedTab::Provider(p);
+
+                    // Check if this provider has data, if not trigger refresh
+                    let state = cx.global::<AppState>();
+                    let has_snapshot = state.get_snapshot(p, cx).is_some();
+                    if !has_snapshot {
+                        info!(provider = ?p, "No snapshot, triggering refresh");
+                        cx.update_global::<AppState, _>(|state, cx| {
+                            state.refresh_provider(p, cx);
+                        });
+                    }
+
+                    cx.notify(); // Re-render with new selection!
+                }),
+            );
+
+        if is_selected {
+            btn = btn.bg(theme::accent()).text_color(gpui::white());
+        } else {
+            btn = btn
+                .hover(move |s| s.bg(hover_bg))
+                .active(move |s| s.bg(active_bg));
+        }
+
+        btn.flex()
+            .items_center()
+            .gap(px(5.))
+            .when_some(label, |el, label| {
+                el.child(
+                    div()
+                        .size(px(6.))
+                        .rounded_full()
+                        .bg(theme::account_color(label.color)),
+                )
+            })
+            .child(div().text_sm().child(name))
+    }
+
+    /// Renders the profile picker shown in the header, or nothing when
+    /// there is only the default profile.
+    /// This must be called from render() where we have access to cx.listener().
+    fn render_profile_picker(
+        &self,
+        hover_bg: Hsla,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let (current, profiles) = {
+            let settings = cx.global::<AppState>().settings.read(cx);
+            (settings.profile().to_string(), settings.profiles())
+        };
+        if profiles.len() < 2 {
+            return None;
+        }
+
+        Some(
+            div()
+                .flex()
+                .flex_wrap()
+                .justify_end()
+                .gap(px(4.))
+                .children(profiles.into_iter().map(|profile| {
+                    let is_current = profile == current;
+                    let pill = div()
+                        .id(SharedString::from(format!("profile-{profile}")))
+                        .px(px(8.))
+                        .py(px(2.))
+                        .rounded(px(6.))
+                        .text_xs()
+                        .child(profile.clone());
+
+                    if is_current {
+                        pill.bg(theme::accent()).text_color(gpui::white())
+                    } else {
+                        pill.cursor_pointer()
+                            .text_color(theme::muted())
+                            .hover(move |s| s.bg(hover_bg))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _, _window, cx| {
+                                    info!(profile = %profile, "Profile picked");
+                                    // The new profile may not enable the selected provider
+                                    this.selected_tab = SelectedTab::All;
+                                    cx.update_global::<AppState, _>(|state, cx| {
+                                        state.switch_profile(&profile, cx);
+                                    });
+                                    cx.notify();
+                                }),
+                            )
+                    }
+                })),
+        )
+    }
+
+    /// Builds a provider's one-line row, opening its tab when clicked.
+    /// This must be called from render() where we have access to cx.listener().
+    fn render_compact_row(&self, provider: ProviderKind, cx: &mut Context<Self>) -> CompactRow {
+        CompactRow::new(MenuCardData::new(provider, cx)).on_click(cx.listener(
+            move |this, _, _window, cx| {
+                this.selected_tab = SelectedTab::Provider(provider);
+                cx.notify();
+            },
+        ))
+    }
 
-                btn.child(div().text_sm().child(name))
+    /// Builds a provider's card with a working Models disclosure.
+    /// This must be called from render() where we have access to cx.listener().
+    fn render_card(&self, provider: ProviderKind, cx: &mut Context<Self>) -> MenuCard {
+        MenuCard::new(MenuCardData::new(provider, cx))
+            .models_expanded(self.expanded_models.contains(&provider))
+            .on_toggle_models(cx.listener(move |this, _, _window, cx| {
+                if !this.expanded_models.remove(&provider) {
+                    this.expanded_models.insert(provider);
+                }
+                cx.notify();
             }))
     }
 }
@@ -180,6 +431,21 @@ impl Render for MenuPanel {
                 cx.notify(); // Re-render when settings change
             }));
         }
+        if self.usage_subscription.is_none() {
+            let usage = cx.global::<AppState>().usage.clone();
+            self.usage_subscription = Some(cx.observe(&usage, |_this, _model, cx| {
+                cx.notify(); // Re-render as refreshes come in
+            }));
+        }
+        if self.activation_subscription.is_none() {
+            // Close when the user clicks elsewhere, unless pinned
+            self.activation_subscription =
+                Some(cx.observe_window_activation(window, |this, window, _| {
+                    if !this.pinned && !window.is_window_active() {
+                        window.remove_window();
+                    }
+                }));
+        }
         info!(
             enabled_count = enabled.len(),
             providers = ?enabled,
@@ -200,16 +466,29 @@ impl Render for MenuPanel {
         let content = match self.selected_tab {
             SelectedTab::All => {
                 // Render all provider cards in a vertical stack (scrolling handled by wrapper)
+                let compact = settings_entity.read(cx).menu_density() == MenuDensity::Compact;
                 let cards: Vec<_> = enabled
                     .iter()
-                    .map(|&p| MenuCard::new(MenuCardData::new(p, cx)))
+                    .map(|&p| {
+                        if compact {
+                            self.render_compact_row(p, cx).into_any_element()
+                        } else {
+                            self.render_card(p, cx).into_any_element()
+                        }
+                    })
                     .collect();
 
+                // Custom providers follow the built-in ones
+                let custom_results = cx.global::<AppState>().usage.read(cx).custom_results();
+                let custom_cards = custom_results
+                    .into_iter()
+                    .map(|(id, result)| CustomCard::new(id, result, cx).into_any_element());
+
                 div()
                     .id("all-providers-content")
                     .flex()
                     .flex_col()
-                    .children(cards.into_iter().map(|card| {
+                    .children(cards.into_iter().chain(custom_cards).map(|card| {
                         // Wrap each card with a subtle separator
                         div().border_b_1().border_color(border_color).child(card)
                     }))
@@ -217,8 +496,9 @@ impl Render for MenuPanel {
             }
             SelectedTab::Provider(provider) => {
                 // Single provider view (existing behavior)
-                let card_data = MenuCardData::new(provider, cx);
-                div().child(MenuCard::new(card_data)).into_any_element()
+                div()
+                    .child(self.render_card(provider, cx))
+                    .into_any_element()
             }
         };
 
@@ -235,7 +515,11 @@ impl Render for MenuPanel {
             .flex_col()
             .max_h(px(600.)) // Max height for entire menu
             // Header (fixed height)
-            .child(MenuHeader::new())
+            .child(
+                MenuHeader::new()
+                    .profile_picker(self.render_profile_picker(hover_bg, cx))
+                    .pin_button(self.render_pin_button(hover_bg, cx)),
+            )
             // Provider switcher if multiple providers enabled - rendered here for cx.listener() access!
             .when(enabled.len() > 1, |el| {
                 el.child(self.render_provider_switcher(
@@ -270,11 +554,29 @@ impl Render for MenuPanel {
 // Menu Header
 // ============================================================================
 
-struct MenuHeader;
+struct MenuHeader {
+    profile_picker: Option<AnyElement>,
+    pin_button: Option<AnyElement>,
+}
 
 impl MenuHeader {
     fn new() -> Self {
-        Self
+        Self {
+            profile_picker: None,
+            pin_button: None,
+        }
+    }
+
+    /// Shows a profile picker on the right of the header.
+    fn profile_picker(mut self, picker: Option<impl IntoElement>) -> Self {
+        self.profile_picker = picker.map(IntoElement::into_any_element);
+        self
+    }
+
+    /// Shows the pin button at the right end of the header.
+    fn pin_button(mut self, button: impl IntoElement) -> Self {
+        self.pin_button = Some(button.into_any_element());
+        self
     }
 }
 
@@ -310,6 +612,14 @@ impl IntoElement for MenuHeader {
                             .child(env!("CARGO_PKG_VERSION")),
                     ),
             )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.))
+                    .children(self.profile_picker)
+                    .children(self.pin_button),
+            )
     }
 }
 
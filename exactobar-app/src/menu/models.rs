@@ -0,0 +1,200 @@
+//! Per-model breakdown for the menu card.
+//!
+//! A collapsible "Models" section listing each model's quota (for providers
+//! that limit models separately, like Antigravity and Gemini) or its share
+//! of the computed token cost, each with a mini progress bar.
+
+use exactobar_core::{ModelUsage, ProviderKind};
+use exactobar_store::CostUsageSnapshot;
+use gpui::*;
+
+use crate::theme;
+
+use super::usage::{ProgressBar, usage_color};
+
+/// Click handler for the section header.
+pub(super) type ToggleHandler = Box<dyn Fn(&MouseDownEvent, &mut Window, &mut App) + 'static>;
+
+// ============================================================================
+// Models Section
+// ============================================================================
+
+pub struct ModelsSection {
+    provider: ProviderKind,
+    rows: Vec<ModelRow>,
+    expanded: bool,
+    on_toggle: Option<ToggleHandler>,
+}
+
+impl ModelsSection {
+    /// Builds the section from quota models, falling back to cost per model.
+    ///
+    /// Returns `None` when there is nothing to break down.
+    pub fn new(
+        provider: ProviderKind,
+        quotas: &[ModelUsage],
+        cost: Option<&CostUsageSnapshot>,
+    ) -> Option<Self> {
+        let rows = if quotas.is_empty() {
+            cost.map(cost_rows).unwrap_or_default()
+        } else {
+            quota_rows(quotas)
+        };
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            provider,
+            rows,
+            expanded: false,
+            on_toggle: None,
+        })
+    }
+
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Set a callback to be invoked when the header is clicked.
+    pub fn on_toggle(
+        mut self,
+        cb: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle = Some(Box::new(cb));
+        self
+    }
+}
+
+impl IntoElement for ModelsSection {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let chevron = if self.expanded { "▾" } else { "▸" };
+
+        let mut header = div()
+            .id(SharedString::from(format!(
+                "toggle-models-{:?}",
+                self.provider
+            )))
+            .flex()
+            .items_center()
+            .justify_between()
+            .cursor_pointer()
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(theme::text_primary())
+                    .child(format!("{} Models", chevron)),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme::muted())
+                    .child(self.rows.len().to_string()),
+            );
+
+        if let Some(on_toggle) = self.on_toggle {
+            header = header.on_mouse_down(MouseButton::Left, move |event, window, cx| {
+                on_toggle(event, window, cx);
+            });
+        }
+
+        let mut section = div()
+            .px(px(14.))
+            .py(px(10.))
+            .bg(theme::card_background())
+            .border_b_1()
+            .border_color(theme::glass_separator())
+            .flex()
+            .flex_col()
+            .gap(px(6.))
+            .child(header);
+
+        if self.expanded {
+            section = section.children(self.rows);
+        }
+
+        section
+    }
+}
+
+/// Rows for providers with per-model quotas.
+fn quota_rows(quotas: &[ModelUsage]) -> Vec<ModelRow> {
+    quotas
+        .iter()
+        .map(|quota| {
+            let used = quota.window.clamped_used_percent();
+            ModelRow {
+                name: quota.model.clone(),
+                percent: used,
+                color: usage_color(used),
+                detail: format!("{:.0}% used", used),
+            }
+        })
+        .collect()
+}
+
+/// Rows showing each model's share of the computed cost.
+fn cost_rows(cost: &CostUsageSnapshot) -> Vec<ModelRow> {
+    if cost.total_cost_usd <= 0.0 {
+        return Vec::new();
+    }
+
+    cost.models
+        .iter()
+        .filter_map(|model| {
+            let usd = model.cost_usd?;
+            Some(ModelRow {
+                name: model.model_name.clone(),
+                percent: usd / cost.total_cost_usd * 100.0,
+                color: theme::accent(),
+                detail: format!("${:.2}", usd),
+            })
+        })
+        .collect()
+}
+
+// ============================================================================
+// Model Row
+// ============================================================================
+
+struct ModelRow {
+    name: String,
+    percent: f64,
+    color: Hsla,
+    detail: String,
+}
+
+impl IntoElement for ModelRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(3.))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme::text_secondary())
+                            .child(self.name),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(theme::text_primary())
+                            .child(self.detail),
+                    ),
+            )
+            .child(ProgressBar::new(self.percent, self.color))
+    }
+}
@@ -0,0 +1,153 @@
+//! Session usage sparkline.
+//!
+//! Draws recent session usage as a row of thin bars, with a dot above
+//! each point where the window reset and a tick above each point with a
+//! user note. The latest notes are listed under the bars.
+
+use chrono::Local;
+use exactobar_core::ProviderKind;
+use exactobar_store::{UsageHistory, UsageNote, WindowSlot};
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use super::usage::usage_color;
+use crate::theme;
+
+/// Most recent points shown.
+const MAX_POINTS: usize = 48;
+
+/// Most recent notes listed under the bars.
+const MAX_NOTES_LISTED: usize = 3;
+
+/// Height of the tallest bar.
+const HEIGHT: f32 = 22.;
+
+/// One bar of the sparkline.
+#[derive(Debug, Clone, PartialEq)]
+struct SparkPoint {
+    used_percent: f64,
+    /// Whether the session window reset at this point.
+    reset: bool,
+    /// Whether a note falls on this point.
+    noted: bool,
+}
+
+pub struct Sparkline {
+    points: Vec<SparkPoint>,
+    /// Notes in the drawn span, oldest first.
+    notes: Vec<UsageNote>,
+}
+
+impl Sparkline {
+    /// Builds a sparkline from a provider's history and notes, or None if
+    /// there are fewer than two points to draw.
+    pub fn from_history(
+        history: &UsageHistory,
+        notes: &[UsageNote],
+        provider: ProviderKind,
+    ) -> Option<Self> {
+        let entries = history.get(provider)?;
+        if entries.len() < 2 {
+            return None;
+        }
+
+        let resets: Vec<_> = history
+            .resets(provider)
+            .filter(|a| a.event.window() == Some(WindowSlot::Primary))
+            .map(|a| a.timestamp)
+            .collect();
+        let shown = &entries[entries.len().saturating_sub(MAX_POINTS)..];
+        let notes: Vec<UsageNote> = notes
+            .iter()
+            .filter(|n| n.applies_to(provider) && n.timestamp >= shown[0].timestamp)
+            .cloned()
+            .collect();
+
+        // A note marks the first point at or after it, or the last point
+        // for notes newer than the history.
+        let mut points: Vec<SparkPoint> = shown
+            .iter()
+            .map(|entry| SparkPoint {
+                used_percent: entry.value,
+                reset: resets.contains(&entry.timestamp),
+                noted: false,
+            })
+            .collect();
+        for note in &notes {
+            let index = shown
+                .partition_point(|e| e.timestamp < note.timestamp)
+                .min(points.len() - 1);
+            points[index].noted = true;
+        }
+
+        Some(Self { points, notes })
+    }
+}
+
+impl IntoElement for Sparkline {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let resets = self.points.iter().filter(|p| p.reset).count();
+        let caption = match resets {
+            0 => "Session trend".to_string(),
+            1 => "Session trend · 1 reset".to_string(),
+            n => format!("Session trend · {n} resets"),
+        };
+
+        div()
+            .px(px(14.))
+            .py(px(8.))
+            .bg(theme::card_background())
+            .border_b_1()
+            .border_color(theme::glass_separator())
+            .flex()
+            .flex_col()
+            .gap(px(4.))
+            .child(div().text_xs().text_color(theme::muted()).child(caption))
+            .child(
+                div()
+                    .h(px(HEIGHT + 6.))
+                    .flex()
+                    .items_end()
+                    .gap(px(1.))
+                    .children(self.points.into_iter().map(|point| {
+                        let height = (point.used_percent.clamp(0.0, 100.0) / 100.0) as f32 * HEIGHT;
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .items_center()
+                            .justify_end()
+                            .gap(px(2.))
+                            .when(point.noted, |el| {
+                                el.child(div().w(px(1.)).h(px(4.)).bg(theme::muted()))
+                            })
+                            .when(point.reset, |el| {
+                                el.child(div().size(px(4.)).rounded_full().bg(theme::accent()))
+                            })
+                            .child(
+                                div()
+                                    .w_full()
+                                    .h(px(height.max(1.)))
+                                    .rounded(px(1.))
+                                    .bg(usage_color(point.used_percent)),
+                            )
+                    })),
+            )
+            .children(
+                self.notes
+                    .iter()
+                    .rev()
+                    .take(MAX_NOTES_LISTED)
+                    .rev()
+                    .map(|note| {
+                        div().text_xs().text_color(theme::muted()).child(format!(
+                            "▏{} {}",
+                            note.timestamp.with_timezone(&Local).format("%a %H:%M"),
+                            note.text
+                        ))
+                    }),
+            )
+    }
+}
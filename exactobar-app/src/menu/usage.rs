@@ -1,10 +1,12 @@
 //! Usage metrics display components.
 //!
 //! Provides progress bars and usage metric rows for displaying
-//! session, weekly, and premium usage limits.
+//! session, weekly, and premium usage limits, and the split of Claude's
+//! weekly window between Claude Code and the web.
 
-use chrono::{DateTime, Local, Utc};
-use exactobar_core::UsageSnapshot;
+use chrono::Utc;
+use exactobar_core::{ResetTime, ResetZone, UsageSnapshot};
+use exactobar_providers::claude::WeeklySurfaceSplit;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 
@@ -21,12 +23,14 @@ pub struct UsageMetricsSection {
 struct UsageMetric {
     title: String,
     used_percent: f64,
-    resets_at: Option<DateTime<Utc>>,
+    reset: Option<ResetTime>,
     reset_description: Option<String>,
     /// When true, show "X% used" instead of "X% remaining"
     show_used: bool,
     /// When true, show "Resets at 3:00 PM" instead of "Resets in 2h 30m"
     show_absolute: bool,
+    /// Timezone absolute reset times are shown in
+    reset_zone: ResetZone,
 }
 
 impl UsageMetricsSection {
@@ -37,52 +41,31 @@ impl UsageMetricsSection {
         search_label: Option<&str>,
         show_used: bool,
         show_absolute: bool,
+        reset_zone: ResetZone,
     ) -> Self {
-        let mut metrics = Vec::new();
-
-        if let Some(primary) = &snapshot.primary {
-            metrics.push(UsageMetric {
-                title: session_label.to_string(),
-                used_percent: primary.used_percent,
-                resets_at: primary.resets_at,
-                reset_description: primary.reset_description.clone(),
-                show_used,
-                show_absolute,
-            });
-        }
-
-        if let Some(secondary) = &snapshot.secondary {
-            metrics.push(UsageMetric {
-                title: weekly_label.to_string(),
-                used_percent: secondary.used_percent,
-                resets_at: secondary.resets_at,
-                reset_description: secondary.reset_description.clone(),
-                show_used,
-                show_absolute,
-            });
-        }
-
-        if let Some(tertiary) = &snapshot.tertiary {
-            metrics.push(UsageMetric {
-                title: "Premium".to_string(),
-                used_percent: tertiary.used_percent,
-                resets_at: tertiary.resets_at,
-                reset_description: tertiary.reset_description.clone(),
-                show_used,
-                show_absolute,
-            });
-        }
-
-        if let Some(search) = &snapshot.search {
-            metrics.push(UsageMetric {
-                title: search_label.unwrap_or("Search").to_string(),
-                used_percent: search.used_percent,
-                resets_at: search.resets_at,
-                reset_description: search.reset_description.clone(),
-                show_used,
-                show_absolute,
-            });
-        }
+        let now = Utc::now();
+        let windows = [
+            (session_label, &snapshot.primary),
+            (weekly_label, &snapshot.secondary),
+            ("Premium", &snapshot.tertiary),
+            (search_label.unwrap_or("Search"), &snapshot.search),
+        ];
+
+        let metrics = windows
+            .into_iter()
+            .filter_map(|(title, window)| {
+                let window = window.as_ref()?;
+                Some(UsageMetric {
+                    title: title.to_string(),
+                    used_percent: window.clamped_used_percent(),
+                    reset: window.reset_time(now),
+                    reset_description: window.reset_description.clone(),
+                    show_used,
+                    show_absolute,
+                    reset_zone,
+                })
+            })
+            .collect();
 
         Self { metrics }
     }
@@ -127,19 +110,15 @@ impl UsageMetricRow {
     fn format_reset_time(&self) -> Option<String> {
         if self.metric.show_absolute {
             // Absolute time format: "Resets at 3:00 PM"
-            self.metric.resets_at.map(|reset_at| {
-                let local_time: DateTime<Local> = reset_at.into();
-                format!(
-                    "Resets at {}",
-                    local_time.format("%l:%M %p").to_string().trim()
-                )
-            })
+            self.metric
+                .reset
+                .map(|reset| format!("Resets at {}", reset.format_clock(&self.metric.reset_zone)))
         } else {
             // Relative time format: "Resets in 2h 30m" or use provider's description
-            if let Some(reset_at) = self.metric.resets_at {
+            if let Some(reset) = self.metric.reset {
                 let now = Utc::now();
-                if reset_at > now {
-                    let duration = reset_at - now;
+                if reset.at > now {
+                    let duration = reset.at - now;
                     let total_minutes = duration.num_minutes();
                     let hours = total_minutes / 60;
                     let minutes = total_minutes % 60;
@@ -168,7 +147,7 @@ impl IntoElement for UsageMetricRow {
     type Element = Div;
 
     fn into_element(self) -> Self::Element {
-        let used_percent = self.metric.used_percent.clamp(0.0, 100.0);
+        let used_percent = self.metric.used_percent;
 
         // Label always shows "X% used" - it's more intuitive!
         let percent_label = format!("{:.0}% used", used_percent);
@@ -217,13 +196,13 @@ impl IntoElement for UsageMetricRow {
 // Progress Bar (Capsule Style like CodexBar)
 // ============================================================================
 
-struct ProgressBar {
+pub(super) struct ProgressBar {
     percent: f64,
     color: Hsla,
 }
 
 impl ProgressBar {
-    fn new(percent: f64, color: Hsla) -> Self {
+    pub(super) fn new(percent: f64, color: Hsla) -> Self {
         Self {
             percent: percent.clamp(0.0, 100.0),
             color,
@@ -254,42 +233,100 @@ impl IntoElement for ProgressBar {
     }
 }
 
+// ============================================================================
+// Weekly Split Section
+// ============================================================================
+
+/// How much of Claude's shared weekly window Claude Code and the web used,
+/// as one bar with a segment per surface.
+pub struct WeeklySplitSection {
+    split: WeeklySurfaceSplit,
+}
+
+impl WeeklySplitSection {
+    pub fn new(split: WeeklySurfaceSplit) -> Self {
+        Self { split }
+    }
+}
+
+impl IntoElement for WeeklySplitSection {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let code_color = theme::accent();
+        let web_color = theme::transparent(theme::accent(), 0.45);
+        let legend = |color: Hsla, label: String| {
+            div()
+                .flex()
+                .items_center()
+                .gap(px(4.))
+                .child(div().size(px(6.)).rounded_full().bg(color))
+                .child(label)
+        };
+
+        div()
+            .px(px(14.))
+            .py(px(10.))
+            .bg(theme::card_background())
+            .border_b_1()
+            .border_color(theme::glass_separator())
+            .flex()
+            .flex_col()
+            .gap(px(4.))
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(theme::text_primary())
+                    .child("Weekly by Surface"),
+            )
+            .child(
+                div()
+                    .h(px(6.))
+                    .w_full()
+                    .bg(theme::track())
+                    .rounded(px(3.))
+                    .overflow_hidden()
+                    .flex()
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative((self.split.code_percent / 100.0) as f32))
+                            .bg(code_color),
+                    )
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative((self.split.web_percent / 100.0) as f32))
+                            .bg(web_color),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap(px(10.))
+                    .text_xs()
+                    .text_color(theme::text_secondary())
+                    .child(legend(
+                        code_color,
+                        format!("Claude Code {:.0}%", self.split.code_percent),
+                    ))
+                    .child(legend(
+                        web_color,
+                        format!("Web {:.0}%", self.split.web_percent),
+                    )),
+            )
+    }
+}
+
 // ============================================================================
 // Color Utilities
 // ============================================================================
 
-/// Returns a color based on usage percentage (USED, not remaining).
-/// Smooth gradient: Green (0%) → Yellow (50%) → Orange (80%) → Red (100%)
+/// Returns a color based on usage percentage (USED, not remaining), in
+/// the current palette.
 ///
 /// This makes intuitive sense: low usage = green (good), high usage = red (warning)
-fn usage_color(used_percent: f64) -> Hsla {
-    let used = used_percent as f32;
-    if used < 50.0 {
-        // Green to Yellow (0-50%)
-        let t = used / 50.0;
-        hsla(
-            (120.0 - t * 60.0) / 360.0, // Hue: 120 (green) → 60 (yellow)
-            0.7,
-            0.45,
-            1.0,
-        )
-    } else if used < 80.0 {
-        // Yellow to Orange (50-80%)
-        let t = (used - 50.0) / 30.0;
-        hsla(
-            (60.0 - t * 30.0) / 360.0, // Hue: 60 (yellow) → 30 (orange)
-            0.8,
-            0.5,
-            1.0,
-        )
-    } else {
-        // Orange to Red (80-100%)
-        let t = (used - 80.0) / 20.0;
-        hsla(
-            (30.0 - t * 30.0) / 360.0, // Hue: 30 (orange) → 0 (red)
-            0.85,
-            0.5,
-            1.0,
-        )
-    }
+pub(crate) fn usage_color(used_percent: f64) -> Hsla {
+    theme::color_for_usage(used_percent)
 }
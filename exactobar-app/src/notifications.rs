@@ -1,14 +1,47 @@
 //! Session quota notifications.
 //!
-//! Alerts users when they're approaching provider quota limits.
-
-use exactobar_core::{ProviderKind, UsageSnapshot};
+//! Alerts users when they're approaching provider quota limits. Each alert
+//! goes to the channels configured by the matching notification rules: the
+//! native notification center, Slack or Discord webhooks, or an ntfy topic.
+//! Rules can also subscribe to snapshot events such as window resets.
+//! Providers with a burn target also warn when usage runs ahead of plan.
+//! A corrupt settings file found at startup is reported natively.
+//! Rules can pick a system sound for their native alerts, and a test
+//! notification shows how a rule's alerts look and sound.
+//!
+//! Webhooks use `reqwest::blocking` on a `smol::unblock()` thread, like the
+//! updater, since GPUI runs on smol rather than Tokio.
+
+use exactobar_core::{BurnPlan, ProviderKind, UsageSnapshot};
+use exactobar_store::{
+    AlertLevel, NotificationChannel, NotificationRule, SettingsStore, SnapshotEvent,
+};
+use serde_json::json;
 use std::collections::HashMap;
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 // Notification thresholds
 const WARNING_THRESHOLD: f64 = 80.0; // Warn at 80% used
-const CRITICAL_THRESHOLD: f64 = 95.0; // Critical at 95% used
+pub(crate) const CRITICAL_THRESHOLD: f64 = 95.0; // Critical at 95% used
+
+/// Sounds in `/System/Library/Sounds` a rule can pick.
+pub const SYSTEM_SOUNDS: &[&str] = &[
+    "Basso",
+    "Blow",
+    "Bottle",
+    "Frog",
+    "Funk",
+    "Glass",
+    "Hero",
+    "Morse",
+    "Ping",
+    "Pop",
+    "Purr",
+    "Sosumi",
+    "Submarine",
+    "Tink",
+];
 
 /// Tracks notification state to avoid spamming
 #[derive(Default)]
@@ -30,11 +63,34 @@ impl Default for NotificationLevel {
     }
 }
 
+impl NotificationLevel {
+    /// The rule level this notification matches, if any.
+    pub fn alert_level(self) -> Option<AlertLevel> {
+        match self {
+            NotificationLevel::None => None,
+            NotificationLevel::Warning => Some(AlertLevel::Warning),
+            NotificationLevel::Critical => Some(AlertLevel::Critical),
+        }
+    }
+}
+
 impl NotificationTracker {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Level `should_notify` would return for this snapshot, without
+    /// recording it. Lets a held notification fire on a later refresh.
+    pub fn peek(
+        &self,
+        provider: ProviderKind,
+        snapshot: &UsageSnapshot,
+    ) -> Option<NotificationLevel> {
+        let current_level = Self::level(snapshot)?;
+        (current_level > self.last_level(provider) && current_level != NotificationLevel::None)
+            .then_some(current_level)
+    }
+
     /// Check if we should notify for this snapshot
     /// Returns the notification level if we should notify, None otherwise
     pub fn should_notify(
@@ -42,21 +98,8 @@ impl NotificationTracker {
         provider: ProviderKind,
         snapshot: &UsageSnapshot,
     ) -> Option<NotificationLevel> {
-        let used_percent = snapshot.primary.as_ref()?.used_percent;
-
-        let current_level = if used_percent >= CRITICAL_THRESHOLD {
-            NotificationLevel::Critical
-        } else if used_percent >= WARNING_THRESHOLD {
-            NotificationLevel::Warning
-        } else {
-            NotificationLevel::None
-        };
-
-        let last_level = self
-            .last_notified
-            .get(&provider)
-            .copied()
-            .unwrap_or_default();
+        let current_level = Self::level(snapshot)?;
+        let last_level = self.last_level(provider);
 
         // Only notify if we've crossed into a higher threshold
         if current_level > last_level {
@@ -74,6 +117,25 @@ impl NotificationTracker {
         None
     }
 
+    fn level(snapshot: &UsageSnapshot) -> Option<NotificationLevel> {
+        let used_percent = snapshot.primary.as_ref()?.used_percent;
+
+        Some(if used_percent >= CRITICAL_THRESHOLD {
+            NotificationLevel::Critical
+        } else if used_percent >= WARNING_THRESHOLD {
+            NotificationLevel::Warning
+        } else {
+            NotificationLevel::None
+        })
+    }
+
+    fn last_level(&self, provider: ProviderKind) -> NotificationLevel {
+        self.last_notified
+            .get(&provider)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Reset notification state for a provider (e.g., after quota reset)
     #[allow(dead_code)]
     pub fn reset(&mut self, provider: ProviderKind) {
@@ -87,11 +149,13 @@ impl NotificationTracker {
     }
 }
 
-/// Send a system notification
+/// Send a quota notification to each channel.
 pub fn send_quota_notification(
     provider: ProviderKind,
     level: NotificationLevel,
     used_percent: f64,
+    channels: Vec<NotificationChannel>,
+    sound: Option<String>,
 ) {
     let provider_name = provider.display_name();
 
@@ -117,9 +181,142 @@ pub fn send_quota_notification(
         provider = ?provider,
         level = ?level,
         percent = used_percent,
+        channels = channels.len(),
         "Sending quota notification"
     );
 
+    send_to_channels(&title, &body, &channels, sound.as_deref());
+}
+
+/// Deliver a notification natively or by webhook, per channel. The sound
+/// plays with the native alert.
+fn send_to_channels(
+    title: &str,
+    body: &str,
+    channels: &[NotificationChannel],
+    sound: Option<&str>,
+) {
+    let mut webhooks = Vec::new();
+    for channel in channels {
+        match webhook_request(channel, title, body) {
+            Some(request) => webhooks.push(request),
+            None => send_system_notification(title, body, sound),
+        }
+    }
+
+    if !webhooks.is_empty() {
+        smol::unblock(move || {
+            for request in webhooks {
+                request.send();
+            }
+        })
+        .detach();
+    }
+
+    debug!("Notification sent: {} - {}", title, body);
+}
+
+/// Tell the user they're burning through a window faster than its burn
+/// target allows.
+pub fn send_pace_notification(
+    provider: ProviderKind,
+    plan: &BurnPlan,
+    channels: Vec<NotificationChannel>,
+    sound: Option<String>,
+) {
+    let provider_name = provider.display_name();
+    let title = format!("{} Ahead of Plan", provider_name);
+    let body = format!(
+        "{:.0}% used, {:.0}% ahead of plan. Slow to {:.0}%/h to last until the target.",
+        plan.used_percent,
+        plan.pace_delta(),
+        plan.allowed_per_hour
+    );
+
+    info!(
+        provider = ?provider,
+        pace_delta = plan.pace_delta(),
+        channels = channels.len(),
+        "Sending pace notification"
+    );
+    send_to_channels(&title, &body, &channels, sound.as_deref());
+}
+
+/// Send a snapshot event notification to each channel.
+pub fn send_event_notification(
+    provider: ProviderKind,
+    event: &SnapshotEvent,
+    channels: Vec<NotificationChannel>,
+    sound: Option<String>,
+) {
+    let (title, body) = event_message(provider, event);
+
+    info!(
+        provider = ?provider,
+        event = %event.kind(),
+        channels = channels.len(),
+        "Sending event notification"
+    );
+    send_to_channels(&title, &body, &channels, sound.as_deref());
+}
+
+/// Title and body for a snapshot event notification. Resets and expiring
+/// logins get their own titles.
+fn event_message(provider: ProviderKind, event: &SnapshotEvent) -> (String, String) {
+    let provider_name = provider.display_name();
+    match event {
+        SnapshotEvent::WindowReset { window, .. } => (
+            format!("{} {} Reset", provider_name, window.label()),
+            format!(
+                "{} {} reset — you have a fresh window.",
+                provider_name,
+                window.label().to_lowercase()
+            ),
+        ),
+        SnapshotEvent::TokenExpiring { .. } => (
+            format!("{} Login Expiring", provider_name),
+            format!("{}.", event.describe()),
+        ),
+        _ => (
+            format!("{} Usage Changed", provider_name),
+            format!("{}.", event.describe()),
+        ),
+    }
+}
+
+/// Tell the user their settings file was corrupt, and whether it was
+/// restored from a backup or reset to defaults.
+pub fn send_settings_recovery_notification(store: &SettingsStore) {
+    let Some(issue) = store.load_issue() else {
+        return;
+    };
+
+    let body = match store.restored_from() {
+        Some(backup) => format!(
+            "{}. Restored the last good copy from {}.",
+            issue,
+            backup.display()
+        ),
+        None => format!("{}. No usable backup was found; using defaults.", issue),
+    };
+
+    warn!(%issue, "Settings file was corrupt");
+    send_system_notification("ExactoBar Settings Recovered", &body, None);
+}
+
+/// Send a sample notification through a rule's channels, with its sound.
+pub fn send_test_notification(rule: &NotificationRule) {
+    info!(channels = rule.channels.len(), sound = ?rule.sound, "Sending test notification");
+    send_to_channels(
+        "ExactoBar Test Notification",
+        "This is how alerts from this rule look and sound.",
+        &rule.channels,
+        rule.sound.as_deref(),
+    );
+}
+
+/// Show a native notification, with a system sound if given.
+fn send_system_notification(title: &str, body: &str, sound: Option<&str>) {
     // Use the system notification API
     #[cfg(target_os = "macos")]
     {
@@ -128,15 +325,112 @@ pub fn send_quota_notification(
         // Escape quotes in body/title to avoid AppleScript injection
         let escaped_body = body.replace('"', "\\\"").replace('\n', " ");
         let escaped_title = title.replace('"', "\\\"");
-        let script = format!(
+        let mut script = format!(
             "display notification \"{}\" with title \"{}\"",
             escaped_body, escaped_title
         );
+        if let Some(sound) = sound {
+            script.push_str(&format!(" sound name \"{}\"", sound.replace('"', "\\\"")));
+        }
 
         let _ = Command::new("osascript").args(["-e", &script]).spawn();
     }
 
-    debug!("Notification sent: {} - {}", title, body);
+    #[cfg(not(target_os = "macos"))]
+    let _ = (title, body, sound);
+}
+
+/// Play a system sound on its own, e.g. to preview it.
+pub fn play_sound(name: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+        let path = format!("/System/Library/Sounds/{}.aiff", name);
+        let _ = Command::new("afplay").arg(path).spawn();
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = name;
+}
+
+// ============================================================================
+// Webhook Channels
+// ============================================================================
+
+/// An HTTP POST to a notification service.
+#[derive(Debug, PartialEq)]
+struct WebhookRequest {
+    url: String,
+    body: WebhookBody,
+}
+
+#[derive(Debug, PartialEq)]
+enum WebhookBody {
+    /// JSON payload (Slack, Discord).
+    Json(serde_json::Value),
+    /// Plain-text message with a title header (ntfy).
+    Text { title: String, message: String },
+}
+
+impl WebhookRequest {
+    /// Sends the request (blocking), logging failures.
+    fn send(self) {
+        let client = match reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!(error = %e, "Failed to build notification HTTP client");
+                return;
+            }
+        };
+
+        let request = match self.body {
+            WebhookBody::Json(payload) => client.post(&self.url).json(&payload),
+            WebhookBody::Text { title, message } => {
+                client.post(&self.url).header("Title", title).body(message)
+            }
+        };
+
+        match request.send().and_then(|r| r.error_for_status()) {
+            Ok(_) => debug!(url = %redact(&self.url), "Webhook notification sent"),
+            Err(e) => warn!(url = %redact(&self.url), error = %e, "Webhook notification failed"),
+        }
+    }
+}
+
+/// Builds the request for a webhook channel (`None` for system notifications).
+fn webhook_request(
+    channel: &NotificationChannel,
+    title: &str,
+    body: &str,
+) -> Option<WebhookRequest> {
+    match channel {
+        NotificationChannel::System => None,
+        NotificationChannel::Slack { webhook_url } => Some(WebhookRequest {
+            url: webhook_url.clone(),
+            body: WebhookBody::Json(json!({ "text": format!("*{title}*\n{body}") })),
+        }),
+        NotificationChannel::Discord { webhook_url } => Some(WebhookRequest {
+            url: webhook_url.clone(),
+            body: WebhookBody::Json(json!({ "content": format!("**{title}**\n{body}") })),
+        }),
+        NotificationChannel::Ntfy { server, topic } => Some(WebhookRequest {
+            url: format!("{}/{}", server.trim_end_matches('/'), topic),
+            body: WebhookBody::Text {
+                title: title.to_string(),
+                message: body.to_string(),
+            },
+        }),
+    }
+}
+
+/// Strips the path from a URL for logging; webhook paths carry secrets.
+fn redact(url: &str) -> &str {
+    url.find("://")
+        .and_then(|scheme| url[scheme + 3..].find('/').map(|i| &url[..scheme + 3 + i]))
+        .unwrap_or(url)
 }
 
 #[cfg(test)]
@@ -182,6 +476,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_peek_does_not_record() {
+        let mut tracker = NotificationTracker::new();
+        let snap = make_snapshot(85.0);
+
+        // Peeking leaves the notification for a later refresh
+        assert_eq!(
+            tracker.peek(ProviderKind::Claude, &snap),
+            Some(NotificationLevel::Warning)
+        );
+        assert_eq!(
+            tracker.should_notify(ProviderKind::Claude, &snap),
+            Some(NotificationLevel::Warning)
+        );
+        assert!(tracker.peek(ProviderKind::Claude, &snap).is_none());
+    }
+
+    #[test]
+    fn test_event_message() {
+        let reset = SnapshotEvent::WindowReset {
+            window: exactobar_store::WindowSlot::Primary,
+            previous_percent: 92.0,
+            used_percent: 0.0,
+            resets_at: None,
+        };
+        assert_eq!(
+            event_message(ProviderKind::Claude, &reset),
+            (
+                "Claude Session Reset".to_string(),
+                "Claude session reset — you have a fresh window.".to_string()
+            )
+        );
+
+        let plan = SnapshotEvent::PlanChanged {
+            from: "Pro".to_string(),
+            to: "Max".to_string(),
+        };
+        assert_eq!(
+            event_message(ProviderKind::Claude, &plan).1,
+            "Plan changed from Pro to Max."
+        );
+    }
+
     #[test]
     fn test_reset_after_quota_refresh() {
         let mut tracker = NotificationTracker::new();
@@ -201,4 +538,48 @@ mod tests {
             Some(NotificationLevel::Warning)
         );
     }
+
+    #[test]
+    fn test_webhook_requests() {
+        assert!(webhook_request(&NotificationChannel::System, "Title", "Body").is_none());
+
+        let slack = NotificationChannel::Slack {
+            webhook_url: "https://hooks.slack.com/services/T/B/X".to_string(),
+        };
+        assert_eq!(
+            webhook_request(&slack, "Title", "Body").unwrap().body,
+            WebhookBody::Json(json!({ "text": "*Title*\nBody" }))
+        );
+
+        let discord = NotificationChannel::Discord {
+            webhook_url: "https://discord.com/api/webhooks/1/x".to_string(),
+        };
+        assert_eq!(
+            webhook_request(&discord, "Title", "Body").unwrap().body,
+            WebhookBody::Json(json!({ "content": "**Title**\nBody" }))
+        );
+
+        let ntfy = NotificationChannel::Ntfy {
+            server: "https://ntfy.example.com/".to_string(),
+            topic: "quota".to_string(),
+        };
+        let request = webhook_request(&ntfy, "Title", "Body").unwrap();
+        assert_eq!(request.url, "https://ntfy.example.com/quota");
+        assert_eq!(
+            request.body,
+            WebhookBody::Text {
+                title: "Title".to_string(),
+                message: "Body".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("https://hooks.slack.com/services/T/B/X"),
+            "https://hooks.slack.com"
+        );
+        assert_eq!(redact("https://ntfy.sh"), "https://ntfy.sh");
+    }
 }
@@ -0,0 +1,112 @@
+//! Usage-based provider ordering.
+//!
+//! With "Sort by usage" on, status items and switcher tabs are ordered by
+//! the remaining quota of each provider's tightest window, most constrained
+//! first. The order is recomputed after every refresh, starting from the
+//! previous one, and a provider only moves ahead of another once it has
+//! [`HYSTERESIS`] points less headroom, so two providers at similar usage
+//! don't swap places on every refresh.
+
+use std::collections::HashMap;
+
+use exactobar_core::ProviderKind;
+
+/// How many percentage points less remaining quota a provider needs before
+/// it moves ahead of another.
+pub const HYSTERESIS: f64 = 5.0;
+
+/// Orders `providers` by remaining percent, lowest first, keeping the
+/// `previous` order where the difference is within [`HYSTERESIS`].
+///
+/// Providers without usage count as fully unused. Providers not in
+/// `previous` start at the end, in the order given.
+pub fn by_remaining(
+    providers: &[ProviderKind],
+    previous: &[ProviderKind],
+    remaining: &HashMap<ProviderKind, f64>,
+) -> Vec<ProviderKind> {
+    let mut order: Vec<ProviderKind> = previous
+        .iter()
+        .copied()
+        .filter(|p| providers.contains(p))
+        .collect();
+    let added: Vec<ProviderKind> = providers
+        .iter()
+        .copied()
+        .filter(|p| !order.contains(p))
+        .collect();
+    order.extend(added);
+
+    let remaining_of = |p: ProviderKind| remaining.get(&p).copied().unwrap_or(100.0);
+    for i in 1..order.len() {
+        let mut j = i;
+        while j > 0 && remaining_of(order[j]) + HYSTERESIS < remaining_of(order[j - 1]) {
+            order.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_remaining() {
+        let providers = [
+            ProviderKind::Claude,
+            ProviderKind::Codex,
+            ProviderKind::Cursor,
+        ];
+        let remaining = HashMap::from([
+            (ProviderKind::Claude, 80.0),
+            (ProviderKind::Codex, 10.0),
+            (ProviderKind::Cursor, 50.0),
+        ]);
+
+        let order = by_remaining(&providers, &[], &remaining);
+        assert_eq!(
+            order,
+            [
+                ProviderKind::Codex,
+                ProviderKind::Cursor,
+                ProviderKind::Claude
+            ]
+        );
+
+        // Within the hysteresis: keep the previous order
+        let close = HashMap::from([
+            (ProviderKind::Claude, 48.0),
+            (ProviderKind::Codex, 10.0),
+            (ProviderKind::Cursor, 50.0),
+        ]);
+        assert_eq!(by_remaining(&providers, &order, &close), order);
+
+        // Clearly lower: move ahead
+        let lower = HashMap::from([
+            (ProviderKind::Claude, 40.0),
+            (ProviderKind::Codex, 10.0),
+            (ProviderKind::Cursor, 50.0),
+        ]);
+        assert_eq!(
+            by_remaining(&providers, &order, &lower),
+            [
+                ProviderKind::Codex,
+                ProviderKind::Claude,
+                ProviderKind::Cursor
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_remaining_without_usage() {
+        let providers = [ProviderKind::Claude, ProviderKind::Codex];
+        let remaining = HashMap::from([(ProviderKind::Codex, 60.0)]);
+
+        assert_eq!(
+            by_remaining(&providers, &[ProviderKind::Gemini], &remaining),
+            [ProviderKind::Codex, ProviderKind::Claude]
+        );
+    }
+}
@@ -9,14 +9,27 @@
 use std::sync::OnceLock;
 use std::time::Duration;
 
-use exactobar_core::{ProviderKind, UsageSnapshot};
-use exactobar_fetch::FetchContext;
-use exactobar_providers::ProviderRegistry;
+use chrono::{DateTime, NaiveDate, Utc};
+use exactobar_core::{BurnPlan, ProviderKind, UsageSnapshot, next_local_time};
+use exactobar_fetch::{
+    CancellationToken, FetchContext, FetchError, FetchStage, ProcessSandbox, ProgressSender,
+    ProviderBudget, RequestHeaders, SNAPSHOT_MAX_AGE, SourceMode, StrategySettings,
+};
+use exactobar_providers::claude::{ClaudeWebClient, WeeklySurfaceSplit};
+use exactobar_providers::{ProviderRegistry, compat, fetch_custom, sheets};
+use exactobar_store::{
+    AlertLevel, CustomProvider, HistoryStore, NotificationChannel, SnapshotDiffer, SnapshotEvent,
+    SnapshotEventKind, WidgetUsage, WindowSlot, default_widget_path,
+};
 use gpui::*;
 use smol::Timer;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::notifications::{NotificationTracker, send_quota_notification};
+use crate::calendar;
+use crate::focus;
+use crate::notifications::{
+    NotificationTracker, send_event_notification, send_pace_notification, send_quota_notification,
+};
 use crate::state::{AppState, UsageModel};
 
 /// Global notification tracker for quota alerts.
@@ -24,6 +37,30 @@ use crate::state::{AppState, UsageModel};
 static NOTIFICATION_TRACKER: once_cell::sync::Lazy<std::sync::Mutex<NotificationTracker>> =
     once_cell::sync::Lazy::new(|| std::sync::Mutex::new(NotificationTracker::new()));
 
+/// Window reset each provider was last warned about running ahead of its
+/// burn target for, so the warning goes out once per window.
+static PACE_WARNED: once_cell::sync::Lazy<
+    std::sync::Mutex<std::collections::HashMap<ProviderKind, DateTime<Utc>>>,
+> = once_cell::sync::Lazy::new(Default::default);
+
+/// UTC day the Google Sheets export last ran, so it runs once a day.
+static SHEETS_EXPORTED_ON: std::sync::Mutex<Option<NaiveDate>> = std::sync::Mutex::new(None);
+
+/// Global snapshot differ, remembering each provider's last snapshot so
+/// refreshes can report resets, plan and account changes, and big jumps.
+static SNAPSHOT_DIFFER: once_cell::sync::Lazy<std::sync::Mutex<SnapshotDiffer>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(SnapshotDiffer::new()));
+
+/// Token cancelling each provider's fetches in flight. A cancelled token is
+/// removed, so later fetches get a fresh one.
+static FETCH_CANCEL: once_cell::sync::Lazy<
+    std::sync::Mutex<std::collections::HashMap<ProviderKind, CancellationToken>>,
+> = once_cell::sync::Lazy::new(Default::default);
+
+/// Persisted usage history, loaded on the first successful fetch.
+static HISTORY_STORE: tokio::sync::Mutex<Option<HistoryStore>> =
+    tokio::sync::Mutex::const_new(None);
+
 /// Global Tokio runtime for fetch operations.
 /// We need this because the fetch/providers libraries use tokio::process::Command
 /// which requires a Tokio runtime, but GPUI runs on smol.
@@ -42,7 +79,7 @@ static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 /// 3. The application cannot function without fetching provider data
 ///
 /// This uses `OnceLock` so the panic can only occur once at initialization.
-fn tokio_runtime() -> &'static tokio::runtime::Runtime {
+pub(crate) fn tokio_runtime() -> &'static tokio::runtime::Runtime {
     TOKIO_RUNTIME.get_or_init(|| {
         tokio::runtime::Builder::new_multi_thread()
             .worker_threads(2)
@@ -68,15 +105,21 @@ pub fn spawn_refresh_task(cx: &mut App) {
         // Initial refresh after a short delay
         Timer::after(Duration::from_secs(2)).await;
 
+        queue_refreshes(&initial_providers, &usage, &mut cx);
         for provider in &initial_providers {
             refresh_provider(*provider, usage.clone(), &mut cx).await;
         }
+        refresh_custom_providers(usage.clone(), &mut cx).await;
+        export_sheets_if_due(&mut cx).await;
 
         loop {
             // Get refresh cadence from settings - try to get duration, default to 5 minutes
-            let duration_result = cx.update(|cx| {
-                let state = cx.global::<AppState>();
-                state.settings.read(cx).refresh_cadence().as_duration()
+            let (duration_result, quiet_during_meetings) = cx.update(|cx| {
+                let settings = cx.global::<AppState>().settings.read(cx);
+                (
+                    settings.refresh_cadence().as_duration(),
+                    settings.quiet_during_meetings(),
+                )
             });
 
             let duration: Duration = match duration_result {
@@ -88,6 +131,13 @@ pub fn spawn_refresh_task(cx: &mut App) {
                 }
             };
 
+            // Refresh less often while a meeting is in progress
+            let duration = if quiet_during_meetings {
+                calendar::refresh_delay(duration, smol::unblock(calendar::busy_until).await)
+            } else {
+                duration
+            };
+
             debug!("Sleeping {} seconds until next refresh", duration.as_secs());
             Timer::after(duration).await;
 
@@ -98,32 +148,121 @@ pub fn spawn_refresh_task(cx: &mut App) {
             });
 
             if let Some(providers) = Some(providers_result) {
+                queue_refreshes(&providers, &usage, &mut cx);
                 for provider in providers {
                     refresh_provider(provider, usage.clone(), &mut cx).await;
                 }
             }
+            refresh_custom_providers(usage.clone(), &mut cx).await;
+            export_sheets_if_due(&mut cx).await;
         }
     })
     .detach();
 }
 
+/// What [`fetch_on_tokio`] fetched for a provider.
+pub struct ProviderFetch {
+    /// The usage, or the error to show.
+    pub result: Result<UsageSnapshot, String>,
+    /// Whether the provider answered `429 Too Many Requests`.
+    pub rate_limited: bool,
+    /// Seconds the provider's `Retry-After` asked to wait, if it sent one.
+    pub retry_after: Option<u64>,
+    /// Whether the fetch was cancelled with [`cancel_fetch`]; the result
+    /// should be dropped rather than shown.
+    pub cancelled: bool,
+}
+
+impl From<Result<UsageSnapshot, String>> for ProviderFetch {
+    fn from(result: Result<UsageSnapshot, String>) -> Self {
+        Self {
+            result,
+            rate_limited: false,
+            retry_after: None,
+            cancelled: false,
+        }
+    }
+}
+
+/// Returns the token cancelling `provider`'s fetches.
+fn fetch_token(provider: ProviderKind) -> CancellationToken {
+    FETCH_CANCEL
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .entry(provider)
+        .or_default()
+        .clone()
+}
+
+/// Aborts `provider`'s fetches in flight, e.g. when it's turned off. Their
+/// requests are dropped and CLI probes killed.
+pub fn cancel_fetch(provider: ProviderKind) {
+    let token = FETCH_CANCEL
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .remove(&provider);
+    if let Some(token) = token {
+        debug!(provider = ?provider, "Cancelling fetch");
+        token.cancel();
+    }
+}
+
+/// Aborts every fetch in flight, e.g. on quit.
+pub fn cancel_all_fetches() {
+    let tokens: Vec<_> = FETCH_CANCEL
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .drain()
+        .collect();
+    for (_, token) in tokens {
+        token.cancel();
+    }
+}
+
 /// Executes a fetch operation on the Tokio runtime.
 /// This bridges the smol-based GPUI world with the tokio-based fetch world.
 ///
 /// **IMPORTANT**: All fetch operations MUST go through this function!
 /// The fetch/providers libraries use tokio::process::Command which requires
 /// a Tokio runtime. Calling them directly from smol will panic.
-pub async fn fetch_on_tokio(provider: ProviderKind) -> Result<UsageSnapshot, String> {
+pub async fn fetch_on_tokio(
+    provider: ProviderKind,
+    source_mode: SourceMode,
+    sandbox: ProcessSandbox,
+    strategies: StrategySettings,
+    base_url: Option<String>,
+    request_headers: RequestHeaders,
+    request_budget: ProviderBudget,
+    progress: Option<ProgressSender>,
+) -> ProviderFetch {
     let rt = tokio_runtime();
+    let cancel = fetch_token(provider);
 
     // Use spawn_blocking to run the tokio future on the tokio runtime
     // from within a smol context
     let result = smol::unblock(move || {
+        let version_warning = if source_mode.allows_cli() {
+            check_cli_version(provider)
+        } else {
+            None
+        };
+
         rt.block_on(async move {
-            let ctx = FetchContext::new();
+            let mut ctx = FetchContext::builder()
+                .source_mode(source_mode)
+                .build()
+                .with_sandbox(sandbox)
+                .with_strategies(strategies)
+                .with_base_url(base_url)
+                .with_request_headers(request_headers)
+                .with_request_budget(request_budget)
+                .with_cancellation(cancel);
+            if let Some(progress) = progress {
+                ctx = ctx.with_progress(progress);
+            }
             if let Some(desc) = ProviderRegistry::get(provider) {
-                let pipeline = desc.build_pipeline(&ctx);
-                let outcome = pipeline.execute(&ctx).await;
+                // Reuses a result an `exactobar` CLI run just fetched
+                let outcome = desc.fetch_shared(&ctx, SNAPSHOT_MAX_AGE).await;
 
                 match outcome.result {
                     Ok(fetch_result) => {
@@ -131,9 +270,25 @@ pub async fn fetch_on_tokio(provider: ProviderKind) -> Result<UsageSnapshot, Str
                             "Provider {:?} fetch succeeded with strategy {:?}",
                             provider, fetch_result.strategy_id
                         );
-                        Ok(fetch_result.snapshot)
+                        ProviderFetch::from(Ok(fetch_result.snapshot))
+                    }
+                    Err(FetchError::Cancelled) => {
+                        debug!("Provider {:?} fetch cancelled", provider);
+                        ProviderFetch {
+                            cancelled: true,
+                            ..ProviderFetch::from(Err("Cancelled".to_string()))
+                        }
                     }
                     Err(e) => {
+                        let (rate_limited, retry_after) = match &e {
+                            FetchError::RateLimited { retry_after } => (true, *retry_after),
+                            // Hold off until the budget allows a request again
+                            FetchError::BudgetExhausted { retry_after, .. } => {
+                                (true, Some(*retry_after))
+                            }
+                            _ => (false, None),
+                        };
+
                         // Build detailed error message including all strategy failures
                         let mut error_parts = vec![format!("Error: {}", e)];
 
@@ -157,13 +312,23 @@ pub async fn fetch_on_tokio(provider: ProviderKind) -> Result<UsageSnapshot, Str
                             }
                         }
 
+                        if let Some(warning) = version_warning {
+                            error_parts.push(String::new());
+                            error_parts.push(format!("Note: {}", warning));
+                        }
+
                         let detailed_error = error_parts.join("\n");
                         error!("Provider {:?} fetch failed:\n{}", provider, detailed_error);
-                        Err(detailed_error)
+                        ProviderFetch {
+                            result: Err(detailed_error),
+                            rate_limited,
+                            retry_after,
+                            cancelled: false,
+                        }
                     }
                 }
             } else {
-                Err("Provider not found".to_string())
+                ProviderFetch::from(Err("Provider not found".to_string()))
             }
         })
     })
@@ -172,8 +337,162 @@ pub async fn fetch_on_tokio(provider: ProviderKind) -> Result<UsageSnapshot, Str
     result
 }
 
+/// Runs the fetch `fetch` starts with a progress sender, showing the stages
+/// it reports on `provider`'s card until it finishes.
+pub async fn show_progress<F: Future>(
+    provider: ProviderKind,
+    usage: &Entity<UsageModel>,
+    cx: &mut AsyncApp,
+    fetch: impl FnOnce(ProgressSender) -> F,
+) -> F::Output {
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let fetch = fetch(progress_tx);
+
+    // The sender is dropped when the fetch finishes, ending the loop below
+    let show = async {
+        while let Some(progress) = progress_rx.recv().await {
+            let _ = cx.update_entity(usage, |model, cx| {
+                model.set_refresh_stage(provider, progress.stage);
+                cx.notify();
+            });
+        }
+    };
+    futures::join!(fetch, show).0
+}
+
+/// Marks `providers` as queued for a refresh, before refreshing them one by
+/// one.
+fn queue_refreshes(providers: &[ProviderKind], usage: &Entity<UsageModel>, cx: &mut AsyncApp) {
+    let _ = cx.update_entity(usage, |model, cx| {
+        for provider in providers {
+            model.set_refresh_stage(*provider, FetchStage::Queued);
+        }
+        cx.notify();
+    });
+}
+
+/// Checks the version of a provider's CLI, returning a warning if it's
+/// outside the versions its parsers support.
+///
+/// The version is detected once per install; the warning is logged then.
+fn check_cli_version(provider: ProviderKind) -> Option<String> {
+    let cli = &ProviderRegistry::get(provider)?.cli;
+    let first_check = compat::cached_check(cli).is_none();
+    let warning = compat::check_cli(cli)?.warning();
+    if first_check {
+        if let Some(warning) = &warning {
+            warn!("{}", warning);
+        }
+    }
+    warning
+}
+
+/// Runs a custom provider's command on the Tokio runtime.
+pub async fn fetch_custom_on_tokio(provider: CustomProvider) -> Result<UsageSnapshot, String> {
+    let rt = tokio_runtime();
+
+    smol::unblock(move || {
+        rt.block_on(async move {
+            let ctx = FetchContext::new();
+            fetch_custom(&provider, &ctx).await.map_err(|e| {
+                error!("Custom provider {} fetch failed: {}", provider.info.id, e);
+                format!("Error: {}", e)
+            })
+        })
+    })
+    .await
+}
+
+/// Splits Claude's weekly window between Claude Code and the web on the
+/// Tokio runtime, using the browser's claude.ai cookies.
+pub async fn fetch_claude_weekly_split() -> Result<WeeklySurfaceSplit, String> {
+    let rt = tokio_runtime();
+
+    smol::unblock(move || {
+        rt.block_on(async move {
+            let ctx = FetchContext::new();
+            ClaudeWebClient::new()
+                .fetch_weekly_split(&ctx)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    })
+    .await
+}
+
+/// Refreshes every enabled custom provider.
+async fn refresh_custom_providers(usage: Entity<UsageModel>, cx: &mut AsyncApp) {
+    let providers = cx.update(|cx| {
+        let state = cx.global::<AppState>();
+        state.settings.read(cx).custom_providers()
+    });
+
+    for provider in providers {
+        let id = provider.info.id;
+        debug!("Refreshing custom provider {}", id);
+        let result = fetch_custom_on_tokio(provider).await;
+        let _ = cx.update_entity(&usage, |model, cx| {
+            model.set_custom_result(id, result);
+            cx.notify();
+        });
+    }
+}
+
+/// Appends yesterday's usage to the Google Sheet, once per day, if an
+/// export is set up.
+async fn export_sheets_if_due(cx: &mut AsyncApp) {
+    let today = Utc::now().date_naive();
+    if *SHEETS_EXPORTED_ON.lock().unwrap() == Some(today) {
+        return;
+    }
+
+    let (export, tabs) = cx.update(|cx| {
+        let settings = cx.global::<AppState>().settings.read(cx).settings();
+        (settings.sheets_export.clone(), settings.sheets_tabs())
+    });
+    let Some(export) = export else {
+        return;
+    };
+    if tabs.is_empty() {
+        return;
+    }
+
+    let result = smol::unblock(move || {
+        tokio_runtime().block_on(sheets::export_due(
+            &export,
+            &tabs,
+            &exactobar_fetch::host::HttpClient::new(),
+            today,
+        ))
+    })
+    .await;
+
+    match result {
+        Ok(rows) => {
+            debug!(rows, "Google Sheets export done");
+            *SHEETS_EXPORTED_ON.lock().unwrap() = Some(today);
+        }
+        // Retried after the next refresh
+        Err(e) => warn!(error = %e, "Google Sheets export failed"),
+    }
+}
+
 /// Refreshes a single provider.
-async fn refresh_provider(provider: ProviderKind, usage: Entity<UsageModel>, cx: &mut AsyncApp) {
+pub async fn refresh_provider(
+    provider: ProviderKind,
+    usage: Entity<UsageModel>,
+    cx: &mut AsyncApp,
+) {
+    let rate_limited_until = cx.update(|cx| usage.read(cx).rate_limited_until(provider));
+    if let Some(until) = rate_limited_until {
+        debug!(provider = ?provider, %until, "Skipping refresh while rate limited");
+        // Take it off the queue
+        let _ = cx.update_entity(&usage, |model, cx| {
+            model.set_refreshing(provider, false);
+            cx.notify();
+        });
+        return;
+    }
     debug!("Refreshing provider {:?}", provider);
 
     // Mark as refreshing
@@ -182,32 +501,222 @@ async fn refresh_provider(provider: ProviderKind, usage: Entity<UsageModel>, cx:
         cx.notify();
     });
 
-    // Execute fetch on Tokio runtime
-    let result = fetch_on_tokio(provider).await;
+    // Execute fetch on Tokio runtime, with the provider's data source, sandbox,
+    // strategies, base URL, request headers and request budget
+    let (source_mode, sandbox, strategies, base_url, request_headers, request_budget) =
+        cx.update(|cx| {
+            let settings = cx.global::<AppState>().settings.read(cx);
+            (
+                settings.source_mode(provider),
+                settings.sandbox(provider),
+                settings.strategies(provider),
+                settings.base_url(provider),
+                settings.request_headers(provider),
+                settings.request_budget(provider),
+            )
+        });
+    let started_at = chrono::Utc::now();
+    let fetch = show_progress(provider, &usage, cx, |progress| {
+        fetch_on_tokio(
+            provider,
+            source_mode,
+            sandbox,
+            strategies,
+            base_url,
+            request_headers,
+            request_budget,
+            Some(progress),
+        )
+    })
+    .await;
+    if fetch.cancelled {
+        let _ = cx.update_entity(&usage, |model, cx| {
+            model.set_refreshing(provider, false);
+            cx.notify();
+        });
+        return;
+    }
+    let result = fetch.result;
+
+    // Persist any keychain denial the fetch just hit so we stop re-prompting
+    let _ = cx.update(|cx| {
+        let settings = cx.global::<AppState>().settings.clone();
+        settings.update(cx, |model, _| model.sync_keychain_denials());
+    });
+
+    // Count the failure (or success) before the hooks check the tolerance,
+    // and hold off refreshing a provider that rate limited us
+    let _ = cx.update_entity(&usage, |model, _| {
+        model.record_fetch(provider, started_at, &result);
+        model.record_rate_limit(provider, fetch.rate_limited, fetch.retry_after);
+    });
+
+    // Run any shell hooks this result triggers
+    let _ = cx.update(|cx| {
+        crate::hooks::run_for_fetch(provider, result.as_ref().map_err(String::as_str), cx);
+    });
 
-    // Check if notifications are enabled before we move result
-    let notify_enabled = cx.update(|cx| {
+    // Read notification settings before we move result
+    let settings = cx.update(|cx| cx.global::<AppState>().settings.read(cx).settings().clone());
+    // Hold notifications during meetings; the tracker isn't advanced, so a
+    // crossed threshold is notified on the first refresh after
+    let in_meeting = settings.quiet_during_meetings && smol::unblock(calendar::in_meeting).await;
+    // Likewise during Focus, except critical alerts for override providers
+    let in_focus = settings.respect_focus && smol::unblock(focus::is_active).await;
+    let quota_settings = Some(&settings)
+        .filter(|settings| settings.session_quota_notifications_enabled && !in_meeting);
+    // Windows whose alerts the user snoozed
+    let snoozed = cx.update(|cx| {
         cx.global::<AppState>()
-            .settings
-            .read(cx)
-            .settings()
-            .session_quota_notifications_enabled
+            .snoozes
+            .snoozed_windows(provider, Utc::now())
     });
+    let session_snoozed = snoozed.contains(&WindowSlot::Primary);
 
     // Check for quota notifications on successful fetch
-    if let Ok(ref snapshot) = result {
-        if notify_enabled {
-            if let Ok(mut tracker) = NOTIFICATION_TRACKER.lock() {
-                if let Some(level) = tracker.should_notify(provider, snapshot) {
-                    let percent = snapshot
-                        .primary
-                        .as_ref()
-                        .map(|w| w.used_percent)
-                        .unwrap_or(0.0);
-                    send_quota_notification(provider, level, percent);
-                }
+    if let (Ok(snapshot), Some(settings)) = (&result, quota_settings) {
+        if let Ok(mut tracker) = NOTIFICATION_TRACKER.lock() {
+            let held_for_focus = in_focus
+                && tracker
+                    .peek(provider, snapshot)
+                    .and_then(|level| level.alert_level())
+                    .is_some_and(|alert| !settings.breaks_focus(provider, alert));
+            if held_for_focus {
+                debug!(provider = ?provider, "Holding quota notification during Focus");
+            } else if session_snoozed {
+                debug!(provider = ?provider, "Holding snoozed quota notification");
+            } else if let Some(level) = tracker.should_notify(provider, snapshot) {
+                let percent = snapshot
+                    .primary
+                    .as_ref()
+                    .map(|w| w.used_percent)
+                    .unwrap_or(0.0);
+                let alert = level.alert_level();
+                let channels = alert
+                    .map(|alert| settings.notification_channels(provider, alert))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let sound = alert
+                    .and_then(|alert| settings.notification_sound(provider, alert))
+                    .map(str::to_string);
+                send_quota_notification(provider, level, percent, channels, sound);
+            }
+        }
+    }
+
+    // Warn once per window when usage runs ahead of the burn target; held
+    // during meetings, Focus and snoozes like other warnings
+    if let (Ok(snapshot), Some(settings)) = (&result, quota_settings) {
+        let now = Utc::now();
+        let plan = settings
+            .burn_target(provider)
+            .and_then(|time| next_local_time(time, now))
+            .zip(snapshot.primary.as_ref())
+            .and_then(|(target, window)| Some((BurnPlan::new(window, target, now)?, window)));
+        if let Some((plan, window)) =
+            plan.filter(|(plan, _)| plan.is_over_pace() && !in_focus && !session_snoozed)
+        {
+            let resets_at = window.resets_at.unwrap_or(plan.target);
+            let first_warning = PACE_WARNED
+                .lock()
+                .map(|mut warned| warned.insert(provider, resets_at) != Some(resets_at))
+                .unwrap_or(false);
+            if first_warning {
+                let channels = settings
+                    .notification_channels(provider, AlertLevel::Warning)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let sound = settings
+                    .notification_sound(provider, AlertLevel::Warning)
+                    .map(str::to_string);
+                send_pace_notification(provider, &plan, channels, sound);
+            }
+        }
+    }
+
+    // Diff against the last snapshot and notify rules subscribed to the events
+    let events = match &result {
+        Ok(snapshot) => SNAPSHOT_DIFFER
+            .lock()
+            .map(|mut differ| differ.observe(provider, snapshot))
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    for event in &events {
+        let is_reset = event.kind() == SnapshotEventKind::WindowReset;
+        if is_reset {
+            info!(provider = ?provider, event = %event.describe(), "Quota window reset");
+            // A fresh window ends its snooze
+            if let Some(window) = event.window() {
+                let _ = cx.update_global::<AppState, _>(|state, _| {
+                    state.snoozes.end(provider, window);
+                });
             }
+        } else if event
+            .window()
+            .is_some_and(|window| snoozed.contains(&window))
+        {
+            continue;
+        }
+
+        if in_meeting || in_focus {
+            continue;
+        }
+        let mut channels: Vec<NotificationChannel> = quota_settings
+            .map(|settings| settings.event_channels(provider, event.kind()))
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+        // Without a rule for it, an expiring login is warned about like a
+        // quota warning
+        if event.kind() == SnapshotEventKind::TokenExpiring && channels.is_empty() {
+            channels = quota_settings
+                .map(|settings| settings.notification_channels(provider, AlertLevel::Warning))
+                .unwrap_or_default()
+                .into_iter()
+                .cloned()
+                .collect();
         }
+        // Reset celebrations go to the notification center
+        if is_reset
+            && settings.reset_notifications_enabled
+            && !channels.contains(&NotificationChannel::System)
+        {
+            channels.push(NotificationChannel::System);
+        }
+        if !channels.is_empty() {
+            let sound = quota_settings
+                .and_then(|settings| settings.event_sound(provider, event.kind()))
+                .map(str::to_string);
+            send_event_notification(provider, event, channels, sound);
+        }
+    }
+
+    // Keep the usage history graphs are drawn from
+    if let Ok(snapshot) = &result {
+        let snapshot = snapshot.clone();
+        let events = events.clone();
+        smol::unblock(move || {
+            tokio_runtime().block_on(persist_history(provider, &snapshot, &events));
+        })
+        .await;
+    }
+
+    // Split Claude's shared weekly window by surface, when web extras are on
+    if provider == ProviderKind::Claude {
+        let split = if settings.claude_web_extras_enabled && result.is_ok() {
+            fetch_claude_weekly_split()
+                .await
+                .map_err(|e| warn!(error = %e, "Failed to split Claude weekly usage"))
+                .ok()
+        } else {
+            None
+        };
+        let _ = cx.update_entity(&usage, |model, _| model.set_claude_weekly_split(split));
     }
 
     // Update state
@@ -215,6 +724,7 @@ async fn refresh_provider(provider: ProviderKind, usage: Entity<UsageModel>, cx:
         model.set_refreshing(provider, false);
         match result {
             Ok(snapshot) => {
+                model.record_history(provider, &snapshot, &events);
                 model.set_snapshot(provider, snapshot);
                 model.clear_error(provider);
             }
@@ -224,6 +734,72 @@ async fn refresh_provider(provider: ProviderKind, usage: Entity<UsageModel>, cx:
         }
         cx.notify();
     });
+
+    // Keep the copy of usage widgets read current
+    let widget_usage = cx.update(|cx| {
+        let state = cx.global::<AppState>();
+        let model = state.usage.read(cx);
+        let latest: Vec<_> = state
+            .enabled_providers(cx)
+            .into_iter()
+            .map(|p| {
+                let error = model.get_error(p).filter(|_| state.is_failing(p, cx));
+                (p, model.get_snapshot(p), error)
+            })
+            .collect();
+        WidgetUsage::new(
+            latest
+                .iter()
+                .map(|(p, snapshot, error)| (*p, snapshot.as_ref(), error.as_deref())),
+        )
+    });
+    smol::unblock(move || {
+        if let Err(e) = tokio_runtime().block_on(widget_usage.save(&default_widget_path())) {
+            warn!(error = %e, "Failed to write widget usage");
+        }
+    })
+    .await;
+
+    // Redraw the tray icon with the new usage
+    let _ = cx.update(|cx| crate::tray::refresh_icon(provider, cx));
+
+    // Token costs come from local logs and are scanned in the background
+    let _ = cx.update(|cx| crate::cost::scan_if_enabled(provider, usage, cx));
+}
+
+/// Records a fetched snapshot in the persisted usage history.
+async fn persist_history(
+    provider: ProviderKind,
+    snapshot: &UsageSnapshot,
+    events: &[SnapshotEvent],
+) {
+    let mut guard = HISTORY_STORE.lock().await;
+    if guard.is_none() {
+        match HistoryStore::load_default().await {
+            Ok(store) => *guard = Some(store),
+            Err(e) => {
+                error!(error = %e, "Failed to load usage history");
+                return;
+            }
+        }
+    }
+    let Some(store) = guard.as_mut() else {
+        return;
+    };
+    store.record(provider, snapshot, events);
+    if let Err(e) = store.save().await {
+        error!(error = %e, "Failed to save usage history");
+    }
+}
+
+/// Forgets the snapshots refreshes diff against and the loaded usage
+/// history, e.g. after switching profiles, so the next refresh doesn't
+/// report the switch as changes or write to the old profile's history.
+pub fn forget_snapshots() {
+    if let Ok(mut differ) = SNAPSHOT_DIFFER.lock() {
+        differ.clear();
+    }
+    *HISTORY_STORE.blocking_lock() = None;
 }
 
 /// Triggers an immediate refresh of all providers.
@@ -233,9 +809,11 @@ pub fn trigger_refresh(cx: &mut App) {
     let usage = state.usage.clone();
 
     cx.spawn(async move |mut cx| {
+        queue_refreshes(&providers, &usage, &mut cx);
         for provider in providers {
             refresh_provider(provider, usage.clone(), &mut cx).await;
         }
+        refresh_custom_providers(usage, &mut cx).await;
     })
     .detach();
 }
@@ -0,0 +1,196 @@
+//! Snoozed alerts.
+//!
+//! Alerts about a provider's usage window can be snoozed for an hour or
+//! until the window resets, from the provider's menu card, the command
+//! palette or an `exactobar://snooze` URL. Native notifications are shown
+//! with osascript, which can't put buttons on them, so the URL is the way
+//! to snooze from a notification through Shortcuts.
+//!
+//! Snoozes are kept per provider and window in [`AppState`] and don't
+//! survive a restart. While one is active, quota and pace alerts for the
+//! window are held like they are during Focus; reset notifications still
+//! go out, and end the snooze.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use exactobar_core::ProviderKind;
+use exactobar_store::WindowSlot;
+use gpui::App;
+use tracing::info;
+
+use crate::state::AppState;
+
+/// How long to snooze alerts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozeLength {
+    /// One hour.
+    Hour,
+    /// Until the window resets.
+    UntilReset,
+}
+
+impl SnoozeLength {
+    /// Parses the `for` parameter of a snooze URL: `1h` or `reset`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "1h" => Some(SnoozeLength::Hour),
+            "reset" => Some(SnoozeLength::UntilReset),
+            _ => None,
+        }
+    }
+
+    /// Short label, e.g. for a button.
+    pub fn label(self) -> &'static str {
+        match self {
+            SnoozeLength::Hour => "for 1 Hour",
+            SnoozeLength::UntilReset => "Until Reset",
+        }
+    }
+}
+
+/// When a snooze ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozeEnd {
+    /// At a set time.
+    At(DateTime<Utc>),
+    /// When the window resets, at a time that isn't known.
+    Reset,
+}
+
+/// Active snoozes by provider and window.
+#[derive(Debug, Default)]
+pub struct Snoozes {
+    ends: HashMap<(ProviderKind, WindowSlot), SnoozeEnd>,
+}
+
+impl Snoozes {
+    /// Snoozes a window's alerts. Snoozing until the reset ends at
+    /// `resets_at` when the provider reports it.
+    pub fn snooze(
+        &mut self,
+        provider: ProviderKind,
+        window: WindowSlot,
+        length: SnoozeLength,
+        resets_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> SnoozeEnd {
+        let end = match (length, resets_at) {
+            (SnoozeLength::Hour, _) => SnoozeEnd::At(now + Duration::hours(1)),
+            (SnoozeLength::UntilReset, Some(resets_at)) => SnoozeEnd::At(resets_at),
+            (SnoozeLength::UntilReset, None) => SnoozeEnd::Reset,
+        };
+        self.ends.insert((provider, window), end);
+        end
+    }
+
+    /// Ends a window's snooze, e.g. when it resets or the user resumes.
+    pub fn end(&mut self, provider: ProviderKind, window: WindowSlot) {
+        self.ends.remove(&(provider, window));
+    }
+
+    /// When a window's snooze ends, if it's snoozed now.
+    pub fn get(
+        &self,
+        provider: ProviderKind,
+        window: WindowSlot,
+        now: DateTime<Utc>,
+    ) -> Option<SnoozeEnd> {
+        self.ends
+            .get(&(provider, window))
+            .copied()
+            .filter(|end| !matches!(end, SnoozeEnd::At(at) if *at <= now))
+    }
+
+    /// The provider's windows that are snoozed now.
+    pub fn snoozed_windows(&self, provider: ProviderKind, now: DateTime<Utc>) -> Vec<WindowSlot> {
+        WindowSlot::all()
+            .iter()
+            .copied()
+            .filter(|&window| self.get(provider, window, now).is_some())
+            .collect()
+    }
+}
+
+/// Snoozes the alerts about a provider's session window, which quota and
+/// pace alerts are about, and redraws open windows.
+pub fn snooze(provider: ProviderKind, length: SnoozeLength, cx: &mut App) {
+    let now = Utc::now();
+    let resets_at = cx
+        .global::<AppState>()
+        .get_snapshot(provider, cx)
+        .and_then(|s| s.primary.and_then(|w| w.resets_at));
+    let end = cx.update_global::<AppState, _>(|state, _| {
+        state
+            .snoozes
+            .snooze(provider, WindowSlot::Primary, length, resets_at, now)
+    });
+    info!(provider = ?provider, end = ?end, "Alerts snoozed");
+    cx.refresh_windows();
+}
+
+/// Ends the snooze on a provider's session window.
+pub fn resume(provider: ProviderKind, cx: &mut App) {
+    cx.update_global::<AppState, _>(|state, _| state.snoozes.end(provider, WindowSlot::Primary));
+    info!(provider = ?provider, "Alerts resumed");
+    cx.refresh_windows();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snooze_ends() {
+        let now = Utc::now();
+        let mut snoozes = Snoozes::default();
+        let claude = ProviderKind::Claude;
+
+        snoozes.snooze(claude, WindowSlot::Primary, SnoozeLength::Hour, None, now);
+        assert!(snoozes.get(claude, WindowSlot::Primary, now).is_some());
+        assert!(snoozes.get(claude, WindowSlot::Secondary, now).is_none());
+        assert!(
+            snoozes
+                .get(claude, WindowSlot::Primary, now + Duration::hours(2))
+                .is_none()
+        );
+
+        // Until the reset: at the reported time, or whenever it's seen
+        let resets_at = now + Duration::minutes(30);
+        let end = snoozes.snooze(
+            claude,
+            WindowSlot::Primary,
+            SnoozeLength::UntilReset,
+            Some(resets_at),
+            now,
+        );
+        assert_eq!(end, SnoozeEnd::At(resets_at));
+        let end = snoozes.snooze(
+            claude,
+            WindowSlot::Secondary,
+            SnoozeLength::UntilReset,
+            None,
+            now,
+        );
+        assert_eq!(end, SnoozeEnd::Reset);
+        assert_eq!(
+            snoozes.snoozed_windows(claude, now + Duration::days(30)),
+            [WindowSlot::Secondary]
+        );
+
+        snoozes.end(claude, WindowSlot::Secondary);
+        assert!(
+            snoozes
+                .snoozed_windows(claude, now)
+                .contains(&WindowSlot::Primary)
+        );
+        assert!(snoozes.snoozed_windows(ProviderKind::Codex, now).is_empty());
+    }
+
+    #[test]
+    fn test_parse_length() {
+        assert_eq!(SnoozeLength::parse("1h"), Some(SnoozeLength::Hour));
+        assert_eq!(SnoozeLength::parse("reset"), Some(SnoozeLength::UntilReset));
+        assert_eq!(SnoozeLength::parse("forever"), None);
+    }
+}
@@ -2,13 +2,30 @@
 //!
 //! Manages settings, usage data, and UI state accessible from GPUI context.
 
-use exactobar_core::{ProviderKind, ProviderStatus, UsageSnapshot};
-use exactobar_store::{CookieSource, DataSourceMode, Settings, SettingsStore};
+use chrono::{DateTime, NaiveTime, Utc};
+use exactobar_core::redact;
+use exactobar_core::{FetchSource, ProviderId, ProviderKind, ProviderStatus, UsageSnapshot};
+use exactobar_fetch::host::keychain_preflight;
+use exactobar_fetch::{
+    Browser, FetchStage, KeychainAccess, ParseMode, ProcessSandbox, ProviderBudget, RequestHeaders,
+    RetryStrategy, SourceMode, StrategySettings,
+};
+use exactobar_providers::claude::WeeklySurfaceSplit;
+use exactobar_store::{
+    AttentionStyle, COMPANION_TOKEN, ColorPalette, CookieSource, CostUsageSnapshot, CustomProvider,
+    DataSourceMode, FailureTolerance, IconLayout, IconVisibility, MenuDensity, NoteStore,
+    SecretBackend, Settings, SettingsStore, SnapshotEvent, StoreError, UsageHistory, UsageNote,
+};
 use gpui::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+use crate::cost::CostScan;
+use crate::snooze::Snoozes;
 
 // ============================================================================
 // Tokio Runtime Bridge
@@ -43,6 +60,14 @@ fn tokio_runtime() -> &'static tokio::runtime::Runtime {
     })
 }
 
+/// Set when the app was started with `--locked`.
+static LOCKED_BY_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Locks credential changes for this run, as `--locked` does.
+pub fn lock_by_flag() {
+    LOCKED_BY_FLAG.store(true, Ordering::Relaxed);
+}
+
 // ============================================================================
 // App State
 // ============================================================================
@@ -57,6 +82,8 @@ pub struct AppState {
     pub menu_open: bool,
     /// Whether a refresh is in progress.
     pub refresh_in_progress: bool,
+    /// Alerts the user snoozed, by provider and window.
+    pub snoozes: Snoozes,
 }
 
 impl Global for AppState {}
@@ -72,20 +99,34 @@ impl AppState {
             }
         });
 
+        crate::notifications::send_settings_recovery_notification(&settings_store);
+
         let settings = cx.new(|_| SettingsModel::new(settings_store));
-        let usage = cx.new(|_| UsageModel::new());
+        let usage = cx.new(|_| {
+            let mut usage = UsageModel::new();
+            usage.set_notes(load_notes());
+            usage
+        });
 
         Self {
             settings,
             usage,
             menu_open: false,
             refresh_in_progress: false,
+            snoozes: Snoozes::default(),
         }
     }
 
-    /// Gets the list of enabled providers.
+    /// Gets the list of enabled providers, most constrained first when
+    /// sorting by usage.
     pub fn enabled_providers(&self, cx: &App) -> Vec<ProviderKind> {
-        self.settings.read(cx).enabled_providers()
+        let settings = self.settings.read(cx);
+        let providers = settings.enabled_providers();
+        if settings.sort_by_usage() {
+            self.usage.read(cx).ordered_by_usage(providers)
+        } else {
+            providers
+        }
     }
 
     /// Gets a usage snapshot for a provider.
@@ -103,11 +144,39 @@ impl AppState {
         self.usage.read(cx).is_refreshing(provider)
     }
 
+    /// Gets the stage a provider's refresh is in, if queued or refreshing.
+    pub fn refresh_stage(&self, provider: ProviderKind, cx: &App) -> Option<FetchStage> {
+        self.usage.read(cx).refresh_stage(provider)
+    }
+
     /// Gets the error for a provider.
     pub fn get_error(&self, provider: ProviderKind, cx: &App) -> Option<String> {
         self.usage.read(cx).get_error(provider)
     }
 
+    /// When a rate limited provider may be refreshed again, if it's still
+    /// cooling down.
+    pub fn rate_limited_until(&self, provider: ProviderKind, cx: &App) -> Option<DateTime<Utc>> {
+        self.usage.read(cx).rate_limited_until(provider)
+    }
+
+    /// Whether a provider's fetches have failed past its failure
+    /// tolerance. Failures within it aren't alerted on.
+    pub fn is_failing(&self, provider: ProviderKind, cx: &App) -> bool {
+        let tolerance = self.settings.read(cx).failure_tolerance(provider);
+        self.usage.read(cx).is_past_tolerance(provider, tolerance)
+    }
+
+    /// Gets the token cost snapshot for a provider.
+    pub fn get_cost(&self, provider: ProviderKind, cx: &App) -> Option<CostUsageSnapshot> {
+        self.usage.read(cx).get_cost(provider)
+    }
+
+    /// Gets the running cost scan for a provider.
+    pub fn get_cost_scan(&self, provider: ProviderKind, cx: &App) -> Option<CostScan> {
+        self.usage.read(cx).cost_scan(provider)
+    }
+
     /// Refreshes all enabled providers.
     pub fn refresh_all(&self, cx: &mut App) {
         let providers = self.enabled_providers(cx);
@@ -118,39 +187,119 @@ impl AppState {
         }
     }
 
-    /// Refreshes a single provider.
-    pub fn refresh_provider(&self, provider: ProviderKind, cx: &mut App) {
-        let usage = self.usage.clone();
+    /// Switches to another profile: loads its settings, drops the usage
+    /// shown for the previous profile and refreshes.
+    pub fn switch_profile(&self, name: &str, cx: &mut App) {
+        let result = self.settings.update(cx, |model, cx| {
+            let result = model.switch_profile(name);
+            cx.notify();
+            result
+        });
+        if let Err(e) = result {
+            error!(profile = %name, error = %e, "Failed to switch profile");
+            return;
+        }
 
+        self.usage.update(cx, |model, cx| {
+            *model = UsageModel::new();
+            model.set_notes(load_notes());
+            cx.notify();
+        });
+        crate::refresh::forget_snapshots();
+        crate::refresh::trigger_refresh(cx);
+    }
+
+    /// Moves settings, caches and history to another data directory (the
+    /// platform default for `None`), then reloads everything from there.
+    pub fn relocate_data_dir(&self, target: Option<PathBuf>, cx: &mut App) {
         cx.spawn(async move |mut cx| {
-            // Mark as refreshing
-            let _ = cx.update_entity(&usage, |model, cx| {
-                model.set_refreshing(provider, true);
-                cx.notify();
-            });
-
-            // Execute fetch on Tokio runtime - MUST use this bridge!
-            // Direct pipeline.execute() calls will panic because tokio::process::Command
-            // requires a Tokio runtime, but GPUI runs on smol.
-            let result = crate::refresh::fetch_on_tokio(provider).await;
-
-            // Update state
-            let _ = cx.update_entity(&usage, |model, cx| {
-                model.set_refreshing(provider, false);
-                match result {
-                    Ok(snapshot) => {
-                        model.set_snapshot(provider, snapshot);
-                        model.clear_error(provider);
-                    }
-                    Err(e) => {
-                        model.set_error(provider, e);
-                    }
+            let result =
+                smol::unblock(move || exactobar_store::relocate_data_dir(target.as_deref())).await;
+            match result {
+                Ok(relocation) => {
+                    info!(
+                        to = %relocation.config_dir.display(),
+                        copied = relocation.copied,
+                        "Data directory changed"
+                    );
+                    let _ = cx.update_global::<AppState, _>(|state, cx| {
+                        state.switch_profile(&exactobar_store::active_profile(), cx);
+                    });
                 }
-                cx.notify();
-            });
+                Err(e) => error!(error = %e, "Failed to change data directory"),
+            }
         })
         .detach();
     }
+
+    /// Adds a note for now and shows it on the usage graphs.
+    pub fn add_note(&self, text: String, cx: &mut App) {
+        let usage = self.usage.clone();
+        cx.spawn(async move |mut cx| {
+            // Bridge to tokio for the store's file I/O
+            let result = smol::unblock(move || {
+                tokio_runtime().block_on(async move {
+                    let mut store = NoteStore::load_default().await?;
+                    store.add(UsageNote::new(text))?;
+                    store.save().await?;
+                    Ok::<_, StoreError>(store.notes().to_vec())
+                })
+            })
+            .await;
+
+            match result {
+                Ok(notes) => {
+                    let _ = cx.update_entity(&usage, |model, cx| {
+                        model.set_notes(notes);
+                        cx.notify();
+                    });
+                }
+                Err(e) => error!(error = %e, "Failed to save note"),
+            }
+        })
+        .detach();
+    }
+
+    /// Refreshes a single provider, with the same side effects as a timed
+    /// refresh.
+    pub fn refresh_provider(&self, provider: ProviderKind, cx: &mut App) {
+        let usage = self.usage.clone();
+        cx.spawn(async move |mut cx| {
+            crate::refresh::refresh_provider(provider, usage, &mut cx).await;
+        })
+        .detach();
+    }
+}
+
+/// Loads the current profile's notes, logging and skipping a bad file.
+fn load_notes() -> Vec<UsageNote> {
+    match tokio_runtime().block_on(NoteStore::load_default()) {
+        Ok(store) => store.notes().to_vec(),
+        Err(e) => {
+            error!(error = %e, "Failed to load notes");
+            Vec::new()
+        }
+    }
+}
+
+/// Points CLI discovery at the binary overrides and extra search paths in
+/// `settings`.
+fn apply_binary_search(settings: &Settings) {
+    exactobar_fetch::host::discovery::set_binary_search(
+        settings.binary_search(exactobar_providers::ProviderRegistry::binary_name),
+    );
+}
+
+/// Generates a companion token and stores it in the keychain, logging any
+/// failure. Returns whether a new token was stored.
+fn store_new_companion_token() -> bool {
+    let stored = exactobar_fetch::host::companion::generate_token()
+        .map_err(|e| e.to_string())
+        .and_then(|token| exactobar_store::store_api_key(COMPANION_TOKEN, &token));
+    if let Err(e) = &stored {
+        error!(error = %e, "Failed to store companion token");
+    }
+    stored.is_ok()
 }
 
 // ============================================================================
@@ -162,15 +311,87 @@ impl AppState {
 pub struct SettingsModel {
     store: Arc<RwLock<SettingsStore>>,
     cached_settings: Settings,
+    /// Profile the settings were loaded from.
+    profile: String,
+    /// Keychain pre-flight results from this session (not persisted).
+    keychain_access: HashMap<Browser, KeychainAccess>,
 }
 
 impl SettingsModel {
     pub fn new(store: SettingsStore) -> Self {
         let cached = tokio_runtime().block_on(async { store.get().await });
+        keychain_preflight::set_denied_browsers(cached.keychain_denied_browsers.iter().copied());
+        exactobar_providers::sync_custom_providers(&cached.custom_providers);
+        redact::set_redact_emails(cached.redact_emails_in_logs);
+        exactobar_fetch::set_parse_mode(cached.parse_mode);
+        crate::theme::set_current_palette(cached.color_palette);
+        apply_binary_search(&cached);
+        exactobar_store::set_secret_backends(cached.secret_backends());
         Self {
             store: Arc::new(RwLock::new(store)),
             cached_settings: cached,
+            profile: exactobar_store::current_profile(),
+            keychain_access: HashMap::new(),
+        }
+    }
+
+    // ========================================================================
+    // Locked Mode
+    // ========================================================================
+
+    /// Returns true if credential changes are blocked, by `--locked` or the
+    /// hidden `locked_mode` setting.
+    pub fn is_locked(&self) -> bool {
+        LOCKED_BY_FLAG.load(Ordering::Relaxed) || self.cached_settings.locked_mode
+    }
+
+    /// Returns true (and logs) if `action` is blocked by locked mode.
+    fn refuse_if_locked(&self, action: &str) -> bool {
+        let locked = self.is_locked();
+        if locked {
+            warn!(action, "Blocked by locked mode");
         }
+        locked
+    }
+
+    // ========================================================================
+    // Profiles
+    // ========================================================================
+
+    /// Gets the profile the settings were loaded from.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Gets every profile, default first.
+    pub fn profiles(&self) -> Vec<String> {
+        exactobar_store::list_profiles()
+    }
+
+    /// Makes another profile active and loads its settings.
+    ///
+    /// Saves still pending for the previous profile go to its own store.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), StoreError> {
+        let profile = exactobar_store::set_active_profile(name)?;
+        let store = tokio_runtime().block_on(SettingsStore::load(
+            exactobar_store::profile_settings_path(&profile),
+        ))?;
+        let cached = tokio_runtime().block_on(async { store.get().await });
+
+        keychain_preflight::set_denied_browsers(cached.keychain_denied_browsers.iter().copied());
+        exactobar_providers::sync_custom_providers(&cached.custom_providers);
+        redact::set_redact_emails(cached.redact_emails_in_logs);
+        exactobar_fetch::set_parse_mode(cached.parse_mode);
+        apply_binary_search(&cached);
+        exactobar_store::set_secret_backends(cached.secret_backends());
+        exactobar_fetch::set_snapshot_cache_dir(exactobar_store::profile_cache_dir(&profile));
+        self.store = Arc::new(RwLock::new(store));
+        self.cached_settings = cached;
+        self.apply_companion_endpoint();
+
+        info!(profile = %profile, "Switched profile");
+        self.profile = profile;
+        Ok(())
     }
 
     /// Gets enabled providers.
@@ -182,6 +403,23 @@ impl SettingsModel {
             .collect()
     }
 
+    /// Gets the enabled custom providers.
+    pub fn custom_providers(&self) -> Vec<CustomProvider> {
+        self.cached_settings
+            .custom_providers
+            .iter()
+            .filter(|p| p.enabled)
+            .cloned()
+            .collect()
+    }
+
+    /// Replaces the custom providers and re-registers their ids.
+    pub fn set_custom_providers(&mut self, providers: Vec<CustomProvider>) {
+        exactobar_providers::sync_custom_providers(&providers);
+        self.cached_settings.custom_providers = providers;
+        self.save_async();
+    }
+
     /// Checks if a provider is enabled.
     pub fn is_provider_enabled(&self, provider: ProviderKind) -> bool {
         self.cached_settings.enabled_providers.contains(&provider)
@@ -197,10 +435,16 @@ impl SettingsModel {
         self.cached_settings.merge_icons
     }
 
+    /// Gets whether providers are ordered by remaining quota.
+    pub fn sort_by_usage(&self) -> bool {
+        self.cached_settings.sort_by_usage
+    }
+
     /// Toggles a provider.
     pub fn toggle_provider(&mut self, provider: ProviderKind) {
         if self.cached_settings.enabled_providers.contains(&provider) {
             self.cached_settings.enabled_providers.remove(&provider);
+            crate::refresh::cancel_fetch(provider);
         } else {
             self.cached_settings.enabled_providers.insert(provider);
         }
@@ -219,6 +463,69 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Sets whether providers are ordered by remaining quota.
+    pub fn set_sort_by_usage(&mut self, value: bool) {
+        self.cached_settings.sort_by_usage = value;
+        self.save_async();
+    }
+
+    /// Gets the menu bar icon layout.
+    pub fn icon_layout(&self) -> IconLayout {
+        self.cached_settings.icon_layout
+    }
+
+    /// Sets the menu bar icon layout.
+    pub fn set_icon_layout(&mut self, layout: IconLayout) {
+        self.cached_settings.icon_layout = layout;
+        self.save_async();
+    }
+
+    /// Gets whether the menu bar icon is drawn for the menu bar's appearance.
+    pub fn adaptive_icon(&self) -> bool {
+        self.cached_settings.adaptive_icon
+    }
+
+    /// Sets whether the menu bar icon is drawn for the menu bar's appearance.
+    pub fn set_adaptive_icon(&mut self, value: bool) {
+        self.cached_settings.adaptive_icon = value;
+        self.save_async();
+    }
+
+    /// Gets whether the menu bar icon's meter is outlined.
+    pub fn icon_outline(&self) -> bool {
+        self.cached_settings.icon_outline
+    }
+
+    /// Sets whether the menu bar icon's meter is outlined.
+    pub fn set_icon_outline(&mut self, value: bool) {
+        self.cached_settings.icon_outline = value;
+        self.save_async();
+    }
+
+    /// Gets when menu bar icons are shown.
+    pub fn icon_visibility(&self) -> IconVisibility {
+        self.cached_settings.icon_visibility
+    }
+
+    /// Sets when menu bar icons are shown.
+    pub fn set_icon_visibility(&mut self, visibility: IconVisibility) {
+        self.cached_settings.icon_visibility = visibility;
+        self.save_async();
+    }
+
+    /// Gets the remaining percent at or below which a provider's icon
+    /// counts as low.
+    pub fn icon_visibility_threshold(&self) -> f64 {
+        self.cached_settings.icon_visibility_threshold
+    }
+
+    /// Sets the remaining percent at or below which a provider's icon
+    /// counts as low.
+    pub fn set_icon_visibility_threshold(&mut self, threshold: f64) {
+        self.cached_settings.icon_visibility_threshold = threshold.clamp(0.0, 100.0);
+        self.save_async();
+    }
+
     /// Gets the theme mode.
     pub fn theme_mode(&self) -> exactobar_store::ThemeMode {
         self.cached_settings.theme_mode
@@ -230,6 +537,18 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Gets the color palette.
+    pub fn color_palette(&self) -> ColorPalette {
+        self.cached_settings.color_palette
+    }
+
+    /// Sets the color palette.
+    pub fn set_color_palette(&mut self, palette: ColorPalette) {
+        self.cached_settings.color_palette = palette;
+        crate::theme::set_current_palette(palette);
+        self.save_async();
+    }
+
     /// Gets the underlying settings.
     pub fn settings(&self) -> &Settings {
         &self.cached_settings
@@ -251,6 +570,12 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Sets the timezone reset times are shown in (None = system timezone).
+    pub fn set_reset_timezone(&mut self, zone: Option<String>) {
+        self.cached_settings.reset_timezone = zone;
+        self.save_async();
+    }
+
     /// Sets whether menu bar shows brand icon with percent.
     pub fn set_menu_bar_shows_brand_icon_with_percent(&mut self, value: bool) {
         self.cached_settings.menu_bar_shows_brand_icon_with_percent = value;
@@ -263,6 +588,17 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Gets the menu card density.
+    pub fn menu_density(&self) -> MenuDensity {
+        self.cached_settings.menu_density
+    }
+
+    /// Sets the menu card density.
+    pub fn set_menu_density(&mut self, density: MenuDensity) {
+        self.cached_settings.menu_density = density;
+        self.save_async();
+    }
+
     // ========================================================================
     // Feature Toggles
     // ========================================================================
@@ -273,6 +609,20 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Sets whether email addresses are redacted from logs.
+    pub fn set_redact_emails_in_logs(&mut self, value: bool) {
+        self.cached_settings.redact_emails_in_logs = value;
+        redact::set_redact_emails(value);
+        self.save_async();
+    }
+
+    /// Sets whether provider responses are parsed strictly.
+    pub fn set_parse_mode(&mut self, mode: ParseMode) {
+        self.cached_settings.parse_mode = mode;
+        exactobar_fetch::set_parse_mode(mode);
+        self.save_async();
+    }
+
     /// Sets auto-refresh on wake.
     pub fn set_auto_refresh_on_wake(&mut self, value: bool) {
         self.cached_settings.auto_refresh_on_wake = value;
@@ -291,12 +641,68 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Sets whether a notification is sent when a quota window resets.
+    pub fn set_reset_notifications_enabled(&mut self, value: bool) {
+        self.cached_settings.reset_notifications_enabled = value;
+        self.save_async();
+    }
+
+    /// Gets whether notifications and refreshes are quieted during meetings.
+    pub fn quiet_during_meetings(&self) -> bool {
+        self.cached_settings.quiet_during_meetings
+    }
+
+    /// Sets whether notifications and refreshes are quieted during meetings,
+    /// asking for calendar access when turned on.
+    pub fn set_quiet_during_meetings(&mut self, value: bool) {
+        self.cached_settings.quiet_during_meetings = value;
+        if value {
+            crate::calendar::request_access();
+        }
+        self.save_async();
+    }
+
+    /// Sets whether notifications are held while a Focus is on.
+    pub fn set_respect_focus(&mut self, value: bool) {
+        self.cached_settings.respect_focus = value;
+        self.save_async();
+    }
+
+    /// Sets whether a provider's critical alerts are delivered during Focus.
+    pub fn set_focus_override(&mut self, provider: ProviderKind, enabled: bool) {
+        let overrides = &mut self.cached_settings.focus_overrides;
+        overrides.retain(|p| *p != provider);
+        if enabled {
+            overrides.push(provider);
+        }
+        self.save_async();
+    }
+
+    /// Sets the sound of the notification rule at `index` (None = silent).
+    pub fn set_notification_sound(&mut self, index: usize, sound: Option<String>) {
+        if let Some(rule) = self.cached_settings.notification_rules.get_mut(index) {
+            rule.sound = sound;
+            self.save_async();
+        }
+    }
+
     /// Sets whether cost tracking is enabled.
     pub fn set_cost_usage_enabled(&mut self, value: bool) {
         self.cached_settings.cost_usage_enabled = value;
         self.save_async();
     }
 
+    /// Gets whether the team leaderboard is offered.
+    pub fn team_leaderboard_enabled(&self) -> bool {
+        self.cached_settings.team_leaderboard_enabled
+    }
+
+    /// Sets whether the team leaderboard is offered.
+    pub fn set_team_leaderboard_enabled(&mut self, value: bool) {
+        self.cached_settings.team_leaderboard_enabled = value;
+        self.save_async();
+    }
+
     /// Gets whether random blink animation is enabled.
     pub fn random_blink_enabled(&self) -> bool {
         self.cached_settings.random_blink_enabled
@@ -308,6 +714,24 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Sets the animation played when usage goes critical.
+    pub fn set_attention_animation(&mut self, style: AttentionStyle) {
+        self.cached_settings.attention_animation = style;
+        self.save_async();
+    }
+
+    /// Sets how strongly the attention animation dims the icon (0.0-1.0).
+    pub fn set_attention_intensity(&mut self, intensity: f32) {
+        self.cached_settings.attention_intensity = intensity.clamp(0.0, 1.0);
+        self.save_async();
+    }
+
+    /// Sets how long the attention animation runs, in seconds.
+    pub fn set_attention_duration_secs(&mut self, secs: u32) {
+        self.cached_settings.attention_duration_secs = secs;
+        self.save_async();
+    }
+
     /// Sets whether Claude web extras are enabled.
     pub fn set_claude_web_extras_enabled(&mut self, value: bool) {
         self.cached_settings.claude_web_extras_enabled = value;
@@ -341,6 +765,9 @@ impl SettingsModel {
 
     /// Sets the cookie source for a provider.
     pub fn set_cookie_source(&mut self, provider: ProviderKind, source: CookieSource) {
+        if self.refuse_if_locked("change cookie source") {
+            return;
+        }
         self.cached_settings
             .provider_settings
             .entry(provider)
@@ -349,6 +776,156 @@ impl SettingsModel {
         self.save_async();
     }
 
+    /// Gets the path set for a provider's CLI, if any.
+    pub fn binary_path(&self, provider: ProviderKind) -> Option<PathBuf> {
+        self.cached_settings
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.binary_path.clone())
+    }
+
+    /// Sets the path to a provider's CLI, or goes back to searching for it.
+    pub fn set_binary_path(&mut self, provider: ProviderKind, path: Option<PathBuf>) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .binary_path = path;
+        apply_binary_search(&self.cached_settings);
+        self.save_async();
+    }
+
+    /// Gets the password manager a provider's API keys are read from, if
+    /// not the keychain.
+    pub fn secret_backend(&self, provider: ProviderKind) -> Option<SecretBackend> {
+        self.cached_settings.secret_backend(provider).cloned()
+    }
+
+    /// Sets the password manager a provider's API keys are read from, or
+    /// goes back to the keychain.
+    pub fn set_secret_backend(&mut self, provider: ProviderKind, backend: Option<SecretBackend>) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .secret_backend = backend;
+        exactobar_store::set_secret_backends(self.cached_settings.secret_backends());
+        self.save_async();
+    }
+
+    /// Gets the sandbox for a provider's CLI commands.
+    pub fn sandbox(&self, provider: ProviderKind) -> ProcessSandbox {
+        self.cached_settings.sandbox(provider)
+    }
+
+    /// Sets the sandbox for a provider's CLI commands.
+    pub fn set_sandbox(&mut self, provider: ProviderKind, sandbox: ProcessSandbox) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .sandbox = sandbox;
+        self.save_async();
+    }
+
+    /// Gets which fetch strategies a provider may run.
+    pub fn strategies(&self, provider: ProviderKind) -> StrategySettings {
+        self.cached_settings.strategies(provider)
+    }
+
+    /// Gets the base URL a provider's API requests go to, if it's proxied.
+    pub fn base_url(&self, provider: ProviderKind) -> Option<String> {
+        self.cached_settings.base_url(provider)
+    }
+
+    /// Gets the User-Agent and extra headers sent with a provider's requests.
+    pub fn request_headers(&self, provider: ProviderKind) -> RequestHeaders {
+        self.cached_settings.request_headers(provider)
+    }
+
+    /// Gets the request limits a provider's fetches are held to.
+    pub fn request_budget(&self, provider: ProviderKind) -> ProviderBudget {
+        self.cached_settings.request_budget(provider)
+    }
+
+    /// Gets the local time a provider's session window should last until.
+    pub fn burn_target(&self, provider: ProviderKind) -> Option<NaiveTime> {
+        self.cached_settings.burn_target(provider)
+    }
+
+    /// Gets a provider's failure tolerance: its own, or the global one.
+    pub fn failure_tolerance(&self, provider: ProviderKind) -> FailureTolerance {
+        self.cached_settings.failure_tolerance(provider)
+    }
+
+    /// Sets the failure tolerance of providers without their own.
+    pub fn set_failure_tolerance(&mut self, tolerance: FailureTolerance) {
+        self.cached_settings.failure_tolerance = tolerance;
+        self.save_async();
+    }
+
+    /// Sets or clears (`None`) the request limit for all providers
+    /// together.
+    pub fn set_request_budget(&mut self, per_hour: Option<u32>) {
+        self.cached_settings.request_budget_per_hour = per_hour;
+        self.save_async();
+    }
+
+    /// Sets or clears (`None`) a provider's own request limit.
+    pub fn set_provider_request_budget(&mut self, provider: ProviderKind, per_hour: Option<u32>) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .request_budget_per_hour = per_hour;
+        self.save_async();
+    }
+
+    /// Gets the switcher group a provider is listed under.
+    pub fn provider_group(&self, provider: ProviderKind) -> Option<&str> {
+        self.cached_settings.provider_group(provider)
+    }
+
+    /// Sets or clears (`None`) the switcher group a provider is listed under.
+    pub fn set_provider_group(&mut self, provider: ProviderKind, group: Option<String>) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .group = group
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty());
+        self.save_async();
+    }
+
+    /// Enables or disables one of a provider's fetch strategies.
+    pub fn set_strategy_enabled(&mut self, provider: ProviderKind, id: &str, enabled: bool) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .strategies
+            .set_enabled(id, enabled);
+        self.save_async();
+    }
+
+    /// Sets the order a provider's fetch strategies are tried in, or goes
+    /// back to the built-in order when `ids` is empty.
+    pub fn set_strategy_order(&mut self, provider: ProviderKind, ids: &[&str]) {
+        self.cached_settings
+            .provider_settings
+            .entry(provider)
+            .or_default()
+            .strategies
+            .set_order(ids);
+        self.save_async();
+    }
+
+    /// Gets the fetch source mode for a provider, from its data source setting.
+    pub fn source_mode(&self, provider: ProviderKind) -> SourceMode {
+        self.cached_settings.data_source(provider).source_mode()
+    }
+
     /// Gets the data source mode for Codex.
     pub fn codex_data_source(&self) -> DataSourceMode {
         self.cached_settings.codex_usage_data_source
@@ -371,6 +948,79 @@ impl SettingsModel {
         self.save_async();
     }
 
+    // ========================================================================
+    // Companion Extension
+    // ========================================================================
+
+    /// Gets the companion endpoint token from the keychain, if one has been
+    /// generated.
+    pub fn companion_token(&self) -> Option<String> {
+        exactobar_store::get_api_key(COMPANION_TOKEN)
+    }
+
+    /// Enables or disables the companion endpoint, generating a token on first enable.
+    pub fn set_companion_endpoint_enabled(&mut self, value: bool) {
+        if value && self.refuse_if_locked("enable companion endpoint") {
+            return;
+        }
+        self.cached_settings.companion_endpoint_enabled = value;
+        if value && self.companion_token().is_none() {
+            store_new_companion_token();
+        }
+        self.save_async();
+        self.apply_companion_endpoint();
+    }
+
+    /// Replaces the companion token, invalidating the one the extension holds.
+    pub fn regenerate_companion_token(&mut self) {
+        if self.refuse_if_locked("regenerate companion token") {
+            return;
+        }
+        if store_new_companion_token() {
+            self.apply_companion_endpoint();
+        }
+    }
+
+    /// Starts or stops the companion endpoint to match the current settings.
+    ///
+    /// The endpoint never runs in locked mode, since it imports cookies.
+    pub fn apply_companion_endpoint(&self) {
+        let settings = &self.cached_settings;
+        let token = if settings.companion_endpoint_enabled && !self.is_locked() {
+            self.companion_token()
+        } else {
+            None
+        };
+        match token {
+            Some(token) => crate::companion::start(settings.companion_endpoint_port, token),
+            None => crate::companion::stop(),
+        }
+    }
+
+    // ========================================================================
+    // Keychain Pre-flight
+    // ========================================================================
+
+    /// Gets the pre-flight result for a browser, if it was tested this session.
+    pub fn keychain_access(&self, browser: Browser) -> Option<KeychainAccess> {
+        self.keychain_access.get(&browser).copied()
+    }
+
+    /// Records a pre-flight result and persists the resulting denial list.
+    pub fn record_keychain_access(&mut self, browser: Browser, access: KeychainAccess) {
+        self.keychain_access.insert(browser, access);
+        self.sync_keychain_denials();
+    }
+
+    /// Persists keychain denials recorded by the fetch layer, if they changed.
+    pub fn sync_keychain_denials(&mut self) {
+        let denied = keychain_preflight::denied_browsers();
+        if denied != self.cached_settings.keychain_denied_browsers {
+            self.cached_settings.keychain_denied_browsers = denied;
+            self.save_async();
+        }
+    }
+
     fn save_async(&self) {
         let store = self.store.clone();
         let settings = self.cached_settings.clone();
@@ -400,6 +1050,30 @@ impl SettingsModel {
 // Usage Model
 // ============================================================================
 
+/// Fetch attempts kept per provider.
+pub const MAX_FETCH_ATTEMPTS: usize = 10;
+
+/// One attempt at fetching a provider's usage.
+#[derive(Debug, Clone)]
+pub struct FetchAttempt {
+    /// When the fetch started.
+    pub started_at: DateTime<Utc>,
+    /// How long it took.
+    pub duration: chrono::Duration,
+    /// Where the usage came from, or why the fetch failed.
+    pub result: Result<FetchSource, String>,
+}
+
+/// A provider that answered `429 Too Many Requests`.
+#[derive(Debug, Clone, Copy)]
+struct RateLimit {
+    /// When refreshing may start again.
+    until: DateTime<Utc>,
+    /// Rate limited fetches in a row, for backing off when the provider
+    /// doesn't say how long to wait.
+    streak: u32,
+}
+
 /// Model wrapping usage data for GPUI.
 #[allow(dead_code)]
 pub struct UsageModel {
@@ -407,6 +1081,28 @@ pub struct UsageModel {
     status: std::collections::HashMap<ProviderKind, ProviderStatus>,
     errors: std::collections::HashMap<ProviderKind, String>,
     refreshing: HashSet<ProviderKind>,
+    /// Stage each queued or refreshing provider is in.
+    refresh_stages: std::collections::HashMap<ProviderKind, FetchStage>,
+    costs: std::collections::HashMap<ProviderKind, CostUsageSnapshot>,
+    cost_scans: std::collections::HashMap<ProviderKind, CostScan>,
+    /// Latest fetch result for each custom provider.
+    custom: std::collections::HashMap<ProviderId, Result<UsageSnapshot, String>>,
+    /// Session usage over time, annotated with resets and other events.
+    history: UsageHistory,
+    /// The user's notes, oldest first.
+    notes: Vec<UsageNote>,
+    /// Latest fetch attempts per provider, newest first.
+    fetch_attempts: std::collections::HashMap<ProviderKind, VecDeque<FetchAttempt>>,
+    /// Failed fetches in a row per provider, since its last good one.
+    failure_streaks: std::collections::HashMap<ProviderKind, u32>,
+    /// When each provider last fetched successfully.
+    last_success: std::collections::HashMap<ProviderKind, DateTime<Utc>>,
+    /// Providers rate limited until some time, whose refreshes are skipped.
+    rate_limits: std::collections::HashMap<ProviderKind, RateLimit>,
+    /// Claude's weekly window split by surface, fetched with web extras.
+    claude_weekly_split: Option<WeeklySurfaceSplit>,
+    /// Providers by remaining quota, most constrained first.
+    usage_order: Vec<ProviderKind>,
 }
 
 impl UsageModel {
@@ -416,7 +1112,133 @@ impl UsageModel {
             status: std::collections::HashMap::new(),
             errors: std::collections::HashMap::new(),
             refreshing: HashSet::new(),
+            refresh_stages: std::collections::HashMap::new(),
+            costs: std::collections::HashMap::new(),
+            cost_scans: std::collections::HashMap::new(),
+            custom: std::collections::HashMap::new(),
+            history: UsageHistory::new(),
+            notes: Vec::new(),
+            fetch_attempts: std::collections::HashMap::new(),
+            failure_streaks: std::collections::HashMap::new(),
+            last_success: std::collections::HashMap::new(),
+            rate_limits: std::collections::HashMap::new(),
+            claude_weekly_split: None,
+            usage_order: Vec::new(),
+        }
+    }
+
+    /// Gets the user's notes, oldest first.
+    pub fn notes(&self) -> &[UsageNote] {
+        &self.notes
+    }
+
+    /// Replaces the notes shown on graphs.
+    pub fn set_notes(&mut self, notes: Vec<UsageNote>) {
+        self.notes = notes;
+    }
+
+    /// Gets the usage history for this session.
+    pub fn history(&self) -> &UsageHistory {
+        &self.history
+    }
+
+    /// Records a snapshot and the events diffed from the previous one.
+    pub fn record_history(
+        &mut self,
+        provider: ProviderKind,
+        snapshot: &UsageSnapshot,
+        events: &[SnapshotEvent],
+    ) {
+        self.history.record_snapshot(provider, snapshot, events);
+    }
+
+    /// Gets a provider's latest fetch attempts, newest first.
+    pub fn fetch_attempts(&self, provider: ProviderKind) -> Vec<FetchAttempt> {
+        self.fetch_attempts
+            .get(&provider)
+            .map(|attempts| attempts.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Records a fetch that started at `started_at` and just finished,
+    /// keeping the latest [`MAX_FETCH_ATTEMPTS`].
+    pub fn record_fetch(
+        &mut self,
+        provider: ProviderKind,
+        started_at: DateTime<Utc>,
+        result: &Result<UsageSnapshot, String>,
+    ) {
+        if result.is_ok() {
+            self.failure_streaks.remove(&provider);
+            self.last_success.insert(provider, Utc::now());
+        } else {
+            *self.failure_streaks.entry(provider).or_default() += 1;
+        }
+
+        let attempts = self.fetch_attempts.entry(provider).or_default();
+        attempts.push_front(FetchAttempt {
+            started_at,
+            duration: Utc::now() - started_at,
+            result: result
+                .as_ref()
+                .map(|snapshot| snapshot.fetch_source)
+                .map_err(Clone::clone),
+        });
+        attempts.truncate(MAX_FETCH_ATTEMPTS);
+    }
+
+    /// Records whether a fetch was rate limited. A rate limited provider
+    /// isn't refreshed until its `Retry-After` passes, or, without one,
+    /// until a backoff that doubles with each rate limit in a row.
+    pub fn record_rate_limit(
+        &mut self,
+        provider: ProviderKind,
+        rate_limited: bool,
+        retry_after: Option<u64>,
+    ) {
+        if !rate_limited {
+            self.rate_limits.remove(&provider);
+            return;
         }
+        let streak = self
+            .rate_limits
+            .get(&provider)
+            .map_or(0, |limit| limit.streak)
+            + 1;
+        let cooldown = RetryStrategy::rate_limit_cooldown(retry_after, streak);
+        let until = Utc::now()
+            + chrono::Duration::from_std(cooldown).unwrap_or_else(|_| chrono::Duration::hours(1));
+        info!(provider = ?provider, %until, streak, "Rate limited, holding off refreshes");
+        self.rate_limits
+            .insert(provider, RateLimit { until, streak });
+    }
+
+    /// When a rate limited provider may be refreshed again, if that's
+    /// still ahead.
+    pub fn rate_limited_until(&self, provider: ProviderKind) -> Option<DateTime<Utc>> {
+        self.rate_limits
+            .get(&provider)
+            .map(|limit| limit.until)
+            .filter(|until| *until > Utc::now())
+    }
+
+    /// Whether a provider's failed fetches in a row are past `tolerance`,
+    /// so it should show as failing.
+    pub fn is_past_tolerance(&self, provider: ProviderKind, tolerance: FailureTolerance) -> bool {
+        tolerance.is_exceeded(
+            self.failure_streaks.get(&provider).copied().unwrap_or(0),
+            self.last_success.get(&provider).copied(),
+            Utc::now(),
+        )
+    }
+
+    /// Gets Claude's weekly window split by surface, if web extras fetched it.
+    pub fn claude_weekly_split(&self) -> Option<WeeklySurfaceSplit> {
+        self.claude_weekly_split
+    }
+
+    pub fn set_claude_weekly_split(&mut self, split: Option<WeeklySurfaceSplit>) {
+        self.claude_weekly_split = split;
     }
 
     pub fn get_snapshot(&self, provider: ProviderKind) -> Option<UsageSnapshot> {
@@ -427,6 +1249,31 @@ impl UsageModel {
         self.snapshots.insert(provider, snapshot);
     }
 
+    /// Re-sorts `providers` by their latest remaining quota. Returns whether
+    /// the order changed.
+    pub fn update_usage_order(&mut self, providers: &[ProviderKind]) -> bool {
+        let remaining: HashMap<ProviderKind, f64> = providers
+            .iter()
+            .filter_map(|&p| Some((p, self.snapshots.get(&p)?.min_remaining_percent()?)))
+            .collect();
+        let order = crate::ordering::by_remaining(providers, &self.usage_order, &remaining);
+        let changed = order != self.usage_order;
+        self.usage_order = order;
+        changed
+    }
+
+    /// Orders `providers` by the last usage sort. Providers it hasn't seen
+    /// yet go last.
+    pub fn ordered_by_usage(&self, mut providers: Vec<ProviderKind>) -> Vec<ProviderKind> {
+        providers.sort_by_key(|p| {
+            self.usage_order
+                .iter()
+                .position(|o| o == p)
+                .unwrap_or(usize::MAX)
+        });
+        providers
+    }
+
     pub fn get_status(&self, provider: ProviderKind) -> Option<ProviderStatus> {
         self.status.get(&provider).cloned()
     }
@@ -456,8 +1303,65 @@ impl UsageModel {
             self.refreshing.insert(provider);
         } else {
             self.refreshing.remove(&provider);
+            self.refresh_stages.remove(&provider);
+        }
+    }
+
+    /// Stage a queued or refreshing provider's refresh is in.
+    pub fn refresh_stage(&self, provider: ProviderKind) -> Option<FetchStage> {
+        self.refresh_stages.get(&provider).copied()
+    }
+
+    /// Records the stage a provider's refresh reached. Cleared when it
+    /// stops refreshing.
+    pub fn set_refresh_stage(&mut self, provider: ProviderKind, stage: FetchStage) {
+        self.refresh_stages.insert(provider, stage);
+    }
+
+    pub fn get_cost(&self, provider: ProviderKind) -> Option<CostUsageSnapshot> {
+        self.costs.get(&provider).cloned()
+    }
+
+    pub fn set_cost(&mut self, provider: ProviderKind, cost: CostUsageSnapshot) {
+        self.costs.insert(provider, cost);
+    }
+
+    pub fn cost_scan(&self, provider: ProviderKind) -> Option<CostScan> {
+        self.cost_scans.get(&provider).cloned()
+    }
+
+    pub fn start_cost_scan(&mut self, provider: ProviderKind, scan: CostScan) {
+        self.cost_scans.insert(provider, scan);
+    }
+
+    pub fn set_cost_scan_progress(
+        &mut self,
+        provider: ProviderKind,
+        files_done: usize,
+        files_total: usize,
+    ) {
+        if let Some(scan) = self.cost_scans.get_mut(&provider) {
+            scan.files_done = files_done;
+            scan.files_total = files_total;
         }
     }
+
+    pub fn finish_cost_scan(&mut self, provider: ProviderKind) {
+        self.cost_scans.remove(&provider);
+    }
+
+    /// Gets the latest results for registered custom providers, in
+    /// registration order.
+    pub fn custom_results(&self) -> Vec<(ProviderId, Result<UsageSnapshot, String>)> {
+        ProviderId::registered()
+            .into_iter()
+            .filter_map(|info| Some((info.id, self.custom.get(&info.id)?.clone())))
+            .collect()
+    }
+
+    pub fn set_custom_result(&mut self, id: ProviderId, result: Result<UsageSnapshot, String>) {
+        self.custom.insert(id, result);
+    }
 }
 
 impl Default for UsageModel {
@@ -13,10 +13,10 @@ use std::collections::HashMap;
 // Theme Mode
 // ============================================================================
 
-use exactobar_store::ThemeMode;
+use exactobar_store::{AccountColor, ColorPalette, ThemeMode};
 use gpui::WindowAppearance;
 use std::sync::OnceLock;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 /// Gets the current theme based on mode and system appearance.
 pub fn current_theme(mode: ThemeMode, appearance: WindowAppearance) -> ExactoBarTheme {
@@ -60,6 +60,79 @@ pub fn set_current_theme_mode(mode: ThemeMode, appearance: WindowAppearance) {
         .store(is_dark, Ordering::Relaxed);
 }
 
+// ============================================================================
+// Color Palette
+// ============================================================================
+
+static CURRENT_PALETTE: AtomicU8 = AtomicU8::new(0);
+
+/// Gets the palette usage and status colors are drawn in.
+pub fn current_palette() -> ColorPalette {
+    match CURRENT_PALETTE.load(Ordering::Relaxed) {
+        1 => ColorPalette::ColorblindSafe,
+        2 => ColorPalette::HighContrast,
+        _ => ColorPalette::Standard,
+    }
+}
+
+/// Sets the palette usage and status colors are drawn in.
+pub fn set_current_palette(palette: ColorPalette) {
+    let index = match palette {
+        ColorPalette::Standard => 0,
+        ColorPalette::ColorblindSafe => 1,
+        ColorPalette::HighContrast => 2,
+    };
+    CURRENT_PALETTE.store(index, Ordering::Relaxed);
+}
+
+/// Good, warning and danger colors of a palette.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelColors {
+    pub good: Hsla,
+    pub warning: Hsla,
+    pub danger: Hsla,
+}
+
+impl LevelColors {
+    /// Gets a palette's level colors.
+    pub fn for_palette(palette: ColorPalette) -> Self {
+        match palette {
+            ColorPalette::Standard => Self {
+                good: hsla(142.0 / 360.0, 0.71, 0.45, 1.0),   // Green
+                warning: hsla(38.0 / 360.0, 0.92, 0.50, 1.0), // Orange/Yellow
+                danger: hsla(0.0, 0.72, 0.51, 1.0),           // Red
+            },
+            // Okabe-Ito colors
+            ColorPalette::ColorblindSafe => Self {
+                good: hsla(202.0 / 360.0, 1.0, 0.35, 1.0),   // Blue
+                warning: hsla(41.0 / 360.0, 1.0, 0.45, 1.0), // Orange
+                danger: hsla(26.0 / 360.0, 1.0, 0.42, 1.0),  // Vermillion
+            },
+            ColorPalette::HighContrast => Self {
+                good: hsla(120.0 / 360.0, 1.0, 0.40, 1.0),   // Green
+                warning: hsla(50.0 / 360.0, 1.0, 0.50, 1.0), // Yellow
+                danger: hsla(0.0, 1.0, 0.50, 1.0),           // Red
+            },
+        }
+    }
+
+    /// Gets the current palette's level colors.
+    pub fn current() -> Self {
+        Self::for_palette(current_palette())
+    }
+
+    /// Gets the color for a given USAGE percentage (not remaining!).
+    pub fn for_usage(&self, used_percent: f32) -> Hsla {
+        if used_percent < 50.0 {
+            self.good
+        } else if used_percent < 80.0 {
+            self.warning
+        } else {
+            self.danger
+        }
+    }
+}
+
 // ============================================================================
 // Dark Mode Colors
 // ============================================================================
@@ -255,17 +328,31 @@ pub fn accent() -> Hsla {
 
 /// Success color (good usage levels).
 pub fn success() -> Hsla {
-    hsla(142.0 / 360.0, 0.71, 0.45, 1.0) // Green
+    LevelColors::current().good
 }
 
 /// Warning color (approaching limits).
 pub fn warning() -> Hsla {
-    hsla(38.0 / 360.0, 0.92, 0.50, 1.0) // Orange/Yellow
+    LevelColors::current().warning
 }
 
 /// Error color (exceeded limits or errors).
 pub fn error() -> Hsla {
-    hsla(0.0, 0.72, 0.51, 1.0) // Red
+    LevelColors::current().danger
+}
+
+/// Color for an account label's tag.
+pub fn account_color(color: AccountColor) -> Hsla {
+    match color {
+        AccountColor::Gray => hsla(0.0, 0.0, 0.55, 1.0),
+        AccountColor::Red => hsla(0.0, 0.72, 0.55, 1.0),
+        AccountColor::Orange => hsla(28.0 / 360.0, 0.90, 0.55, 1.0),
+        AccountColor::Yellow => hsla(48.0 / 360.0, 0.95, 0.55, 1.0),
+        AccountColor::Green => hsla(142.0 / 360.0, 0.60, 0.45, 1.0),
+        AccountColor::Blue => hsla(211.0 / 360.0, 0.90, 0.58, 1.0),
+        AccountColor::Purple => hsla(270.0 / 360.0, 0.65, 0.62, 1.0),
+        AccountColor::Pink => hsla(330.0 / 360.0, 0.75, 0.65, 1.0),
+    }
 }
 
 /// Surface color for buttons/controls - semi-transparent dark.
@@ -277,12 +364,18 @@ pub fn surface() -> Hsla {
     }
 }
 
-/// Track color for progress bars - subtle on dark background.
+/// Track color for progress bars - subtle on dark background, stronger
+/// with the high contrast palette.
 pub fn track() -> Hsla {
-    if current_dark_mode() {
+    let track = if current_dark_mode() {
         track_dark()
     } else {
         track_light()
+    };
+    if current_palette() == ColorPalette::HighContrast {
+        transparent(track, (track.a * 2.5).min(1.0))
+    } else {
+        track
     }
 }
 
@@ -312,9 +405,14 @@ pub fn liquid_card_background() -> Hsla {
 /// Returns the appropriate color for a usage percentage (USED, not remaining).
 /// Green = low usage (good), Red = high usage (warning)
 /// Smooth gradient: Green (0%) → Yellow (50%) → Orange (80%) → Red (100%)
+///
+/// Other palettes step between their level colors instead, since a hue
+/// gradient is what they avoid.
 pub fn color_for_usage(used_percent: f64) -> Hsla {
     let used = used_percent as f32;
-    if used < 50.0 {
+    if current_palette() != ColorPalette::Standard {
+        LevelColors::current().for_usage(used)
+    } else if used < 50.0 {
         // Green to Yellow (0-50%)
         let t = used / 50.0;
         hsla(
@@ -391,7 +489,7 @@ impl ExactoBarTheme {
 
     /// Gets the usage bar colors.
     pub fn usage_colors(&self) -> UsageColors {
-        if self.dark_mode {
+        let mut colors = if self.dark_mode {
             UsageColors {
                 good: hsla(142.0 / 360.0, 0.71, 0.45, 1.0),   // Green
                 warning: hsla(38.0 / 360.0, 0.92, 0.50, 1.0), // Yellow
@@ -405,7 +503,23 @@ impl ExactoBarTheme {
                 danger: hsla(0.0, 0.84, 0.50, 1.0),           // Red
                 background: hsla(0.0, 0.0, 0.90, 1.0),        // Light gray
             }
+        };
+
+        let palette = current_palette();
+        if palette != ColorPalette::Standard {
+            let levels = LevelColors::for_palette(palette);
+            colors.good = levels.good;
+            colors.warning = levels.warning;
+            colors.danger = levels.danger;
+        }
+        if palette == ColorPalette::HighContrast {
+            colors.background = if self.dark_mode {
+                lighten(colors.background, 0.15)
+            } else {
+                darken(colors.background, 0.2)
+            };
         }
+        colors
     }
 }
 
@@ -484,6 +598,21 @@ fn provider_colors() -> HashMap<ProviderKind, Hsla> {
         hsla(282.0 / 360.0, 1.0, 0.41, 1.0),
     );
 
+    // GitHub Models - Purple
+    map.insert(
+        ProviderKind::GitHubModels,
+        hsla(262.0 / 360.0, 0.69, 0.59, 1.0),
+    );
+
+    // Hugging Face - Yellow
+    map.insert(
+        ProviderKind::HuggingFace,
+        hsla(45.0 / 360.0, 1.0, 0.56, 1.0),
+    );
+
+    // Replicate - Red
+    map.insert(ProviderKind::Replicate, hsla(7.0 / 360.0, 0.83, 0.57, 1.0));
+
     map
 }
 
@@ -0,0 +1,119 @@
+//! Status item tooltip text.
+//!
+//! Hovering the tray icon shows one line per provider, e.g.
+//! "Claude: 37% left · resets in 1h 12m", so usage can be checked without
+//! opening the panel. The tray refreshes the text every minute to keep the
+//! countdowns current.
+
+use chrono::{DateTime, Utc};
+use exactobar_core::{ProviderKind, UsageSnapshot};
+
+/// Builds the tooltip line for one provider.
+pub fn tooltip_line(
+    provider: ProviderKind,
+    snapshot: Option<&UsageSnapshot>,
+    now: DateTime<Utc>,
+) -> String {
+    let name = provider.display_name();
+
+    let Some(window) = snapshot.and_then(|s| s.primary.as_ref()) else {
+        return format!("{name}: no data");
+    };
+
+    let mut line = format!("{name}: {:.0}% left", window.remaining_percent());
+    if let Some(reset) = window.reset_time(now) {
+        line.push_str(" · ");
+        line.push_str(&format_countdown(reset.at, now));
+    }
+    line
+}
+
+/// Formats a reset time as "resets in 1h 12m".
+fn format_countdown(resets_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (resets_at - now).num_seconds();
+    if seconds <= 0 {
+        return "resets soon".to_string();
+    }
+
+    // Round up so "resets in 0m" never shows while time remains
+    let total_minutes = (seconds + 59) / 60;
+
+    let days = total_minutes / (24 * 60);
+    let hours = total_minutes / 60 % 24;
+    let minutes = total_minutes % 60;
+
+    if days > 0 {
+        format!("resets in {days}d {hours}h")
+    } else if hours > 0 {
+        format!("resets in {hours}h {minutes}m")
+    } else {
+        format!("resets in {minutes}m")
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use exactobar_core::UsageWindow;
+
+    fn snapshot(
+        used_percent: f64,
+        resets_in: Option<Duration>,
+        now: DateTime<Utc>,
+    ) -> UsageSnapshot {
+        let mut window = UsageWindow::new(used_percent);
+        window.resets_at = resets_in.map(|d| now + d);
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(window);
+        snapshot
+    }
+
+    #[test]
+    fn test_tooltip_line_with_reset() {
+        let now = Utc::now();
+        let snap = snapshot(63.0, Some(Duration::minutes(72)), now);
+
+        assert_eq!(
+            tooltip_line(ProviderKind::Claude, Some(&snap), now),
+            "Claude: 37% left · resets in 1h 12m"
+        );
+    }
+
+    #[test]
+    fn test_tooltip_line_without_reset() {
+        let now = Utc::now();
+        let snap = snapshot(10.0, None, now);
+
+        assert_eq!(
+            tooltip_line(ProviderKind::Claude, Some(&snap), now),
+            "Claude: 90% left"
+        );
+        assert_eq!(
+            tooltip_line(ProviderKind::Claude, None, now),
+            "Claude: no data"
+        );
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        let now = Utc::now();
+
+        assert_eq!(
+            format_countdown(now + Duration::seconds(30), now),
+            "resets in 1m"
+        );
+        assert_eq!(
+            format_countdown(now + Duration::hours(50), now),
+            "resets in 2d 2h"
+        );
+        assert_eq!(
+            format_countdown(now - Duration::minutes(5), now),
+            "resets soon"
+        );
+    }
+}
@@ -6,7 +6,7 @@
 #[cfg(target_os = "macos")]
 use cocoa::appkit::NSSquareStatusItemLength;
 #[cfg(target_os = "macos")]
-use cocoa::base::{YES, id, nil};
+use cocoa::base::{NO, YES, id, nil};
 #[cfg(target_os = "macos")]
 use cocoa::foundation::{NSSize, NSString};
 #[cfg(target_os = "macos")]
@@ -18,9 +18,10 @@ use objc::{class, msg_send, sel, sel_impl};
 #[cfg(target_os = "macos")]
 use std::sync::Once;
 
-use exactobar_core::{ProviderKind, StatusIndicator};
+use exactobar_core::{ProviderKind, StatusIndicator, UsageSnapshot};
+use exactobar_store::AttentionStyle;
 use gpui::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::{self, Receiver, Sender};
 use tracing::{debug, info, warn};
 
@@ -30,9 +31,18 @@ use ksni::Icon as KsniIcon;
 #[cfg(target_os = "linux")]
 use ksni::blocking::TrayMethods as KsniTrayMethods;
 
-use crate::icon::{IconAnimationState, IconRenderer, RenderMode, RenderedIcon};
+#[cfg(target_os = "linux")]
+use crate::icon::RenderedIcon;
+use crate::icon::{
+    AttentionAnimation, IconAnimationState, IconRenderer, LoadingPattern, MenuBarAppearance,
+    RenderMode,
+};
+#[cfg(target_os = "macos")]
+use crate::icon::{DESIGN_BACKING_SCALE, icon_scale};
 use crate::menu::TrayMenu;
+use crate::notifications::CRITICAL_THRESHOLD;
 use crate::state::AppState;
+use crate::tooltip;
 
 // ============================================================================
 // Objective-C Delegate for Status Item Clicks
@@ -140,10 +150,80 @@ fn create_delegate(sender: &Sender<StatusItemClickEvent>, provider: Option<Provi
     }
 }
 
+// ============================================================================
+// Display Metrics (macOS)
+// ============================================================================
+
+/// Returns the backing scale factor of the display a status item is on.
+///
+/// Falls back to the main screen's until the item has a window, and to
+/// the icons' design scale without a screen.
+#[cfg(target_os = "macos")]
+fn backing_scale_factor(status_item: id) -> f64 {
+    unsafe {
+        let button: id = msg_send![status_item, button];
+        let window: id = if button != nil {
+            msg_send![button, window]
+        } else {
+            nil
+        };
+        let screen: id = if window != nil {
+            msg_send![window, screen]
+        } else {
+            nil
+        };
+        let screen: id = if screen != nil {
+            screen
+        } else {
+            msg_send![class!(NSScreen), mainScreen]
+        };
+        if screen == nil {
+            return DESIGN_BACKING_SCALE;
+        }
+        msg_send![screen, backingScaleFactor]
+    }
+}
+
+/// Returns the thickness of the menu bar, in points.
+#[cfg(target_os = "macos")]
+fn menu_bar_thickness() -> f64 {
+    unsafe {
+        let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+        msg_send![status_bar, thickness]
+    }
+}
+
 // ============================================================================
 // Linux SNI (StatusNotifierItem) Implementation
 // ============================================================================
 
+/// Scales Linux icons are offered at, for 1x and 2x displays. The host
+/// picks the size that fits each panel.
+#[cfg(target_os = "linux")]
+const LINUX_ICON_SCALES: [f32; 2] = [0.5, 1.0];
+
+/// Converts a rendered icon to ARGB (as required by ksni).
+#[cfg(target_os = "linux")]
+fn to_ksni_icon(rendered: &RenderedIcon) -> KsniIcon {
+    let (width, height, mut pixels) = rendered.to_rgba_pixels();
+
+    // Convert RGBA to ARGB (Linux SNI expects ARGB in network byte order)
+    for pixel in pixels.chunks_exact_mut(4) {
+        // RGBA -> ARGB: [R, G, B, A] -> [A, R, G, B]
+        let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+        pixel[0] = a;
+        pixel[1] = r;
+        pixel[2] = g;
+        pixel[3] = b;
+    }
+
+    KsniIcon {
+        width: width as i32,
+        height: height as i32,
+        data: pixels,
+    }
+}
+
 /// Event sent when a Linux tray action is triggered.
 #[cfg(target_os = "linux")]
 #[derive(Debug, Clone)]
@@ -169,15 +249,24 @@ enum LinuxTrayEvent {
 struct LinuxTray {
     /// Channel sender for communicating events back to GPUI.
     event_sender: Sender<LinuxTrayEvent>,
-    /// The tray icon (ARGB format).
-    icon: KsniIcon,
+    /// The tray icon (ARGB format), in every size of [`LINUX_ICON_SCALES`].
+    icons: Vec<KsniIcon>,
+    /// Tooltip text, one line per provider.
+    tooltip: String,
+    /// Whether the icon is shown (see `update_visibility`).
+    visible: bool,
 }
 
 #[cfg(target_os = "linux")]
 impl LinuxTray {
-    /// Creates a new Linux tray with the given event sender and icon.
-    fn new(event_sender: Sender<LinuxTrayEvent>, icon: KsniIcon) -> Self {
-        Self { event_sender, icon }
+    /// Creates a new Linux tray with the given event sender and icons.
+    fn new(event_sender: Sender<LinuxTrayEvent>, icons: Vec<KsniIcon>) -> Self {
+        Self {
+            event_sender,
+            icons,
+            tooltip: String::new(),
+            visible: true,
+        }
     }
 }
 
@@ -192,7 +281,23 @@ impl ksni::Tray for LinuxTray {
     }
 
     fn icon_pixmap(&self) -> Vec<KsniIcon> {
-        vec![self.icon.clone()]
+        self.icons.clone()
+    }
+
+    fn status(&self) -> ksni::Status {
+        if self.visible {
+            ksni::Status::Active
+        } else {
+            ksni::Status::Passive
+        }
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: "ExactoBar".into(),
+            description: self.tooltip.clone(),
+            ..Default::default()
+        }
     }
 
     fn activate(&mut self, x: i32, y: i32) {
@@ -268,6 +373,11 @@ pub struct SystemTray {
     #[cfg(target_os = "macos")]
     click_receiver: Option<Receiver<StatusItemClickEvent>>,
 
+    /// Scale each status item's icon was last drawn at, by provider
+    /// (`None` for the merged item) (macOS).
+    #[cfg(target_os = "macos")]
+    icon_scales: HashMap<Option<ProviderKind>, f32>,
+
     // ========================================================================
     // Linux-specific fields
     // ========================================================================
@@ -295,7 +405,7 @@ pub struct SystemTray {
     /// Currently displayed menu (if any).
     menu_window: Option<AnyWindowHandle>,
 
-    /// Loading animation phase.
+    /// Loading pattern phase, in radians.
     loading_phase: f64,
 
     /// Animation states per provider.
@@ -306,6 +416,12 @@ pub struct SystemTray {
 
     /// Time since last random animation event.
     last_random_event: std::time::Instant,
+
+    /// Running attention animations per provider.
+    attention: HashMap<ProviderKind, AttentionAnimation>,
+
+    /// Providers currently at or above the critical threshold.
+    critical: HashSet<ProviderKind>,
 }
 
 impl Global for SystemTray {}
@@ -322,8 +438,17 @@ impl SystemTray {
         let surprise_me_enabled = state.settings.read(cx).random_blink_enabled();
         let providers = state.enabled_providers(cx);
 
+        let icon_layout = state.settings.read(cx).icon_layout();
+        let color_palette = state.settings.read(cx).color_palette();
+
         // Use template mode for macOS menu bar (automatic dark/light mode)
-        let renderer = IconRenderer::new().with_mode(RenderMode::Template);
+        // unless the icon is drawn for the appearance itself
+        let renderer = IconRenderer::new()
+            .with_mode(render_mode(state.settings.read(cx).adaptive_icon()))
+            .with_layout(icon_layout)
+            .with_palette(color_palette)
+            .with_outline(state.settings.read(cx).icon_outline())
+            .with_appearance(menu_bar_appearance(cx.window_appearance()));
 
         // Create channel for click events from Objective-C delegate
         // Box the sender so it has a stable heap address (survives struct moves)
@@ -342,6 +467,7 @@ impl SystemTray {
             delegates: Vec::new(),
             click_sender,
             click_receiver: Some(click_receiver),
+            icon_scales: HashMap::new(),
             renderer,
             merge_mode,
             menu_window: None,
@@ -349,6 +475,8 @@ impl SystemTray {
             animation_states,
             surprise_me_enabled,
             last_random_event: std::time::Instant::now(),
+            attention: HashMap::new(),
+            critical: HashSet::new(),
         };
 
         // Create native status items
@@ -360,6 +488,7 @@ impl SystemTray {
             }
         }
 
+        tray.update_visibility(cx);
         info!(merge_mode = merge_mode, "Native system tray initialized");
         tray
     }
@@ -421,7 +550,7 @@ impl SystemTray {
             let status = state.get_status(provider, cx);
             let status_indicator = status.map(|s| s.indicator).unwrap_or(StatusIndicator::None);
 
-            let rendered = self.renderer.render(
+            let icon = self.renderer.render_cached(
                 provider,
                 snapshot.as_ref(),
                 false,
@@ -430,7 +559,7 @@ impl SystemTray {
             );
 
             // Set the icon image
-            self.set_status_item_image(status_item, &rendered);
+            self.set_status_item_image(status_item, icon.png());
 
             // Create delegate for handling clicks (instead of NSMenu)
             let delegate = create_delegate(&self.click_sender, Some(provider));
@@ -470,10 +599,10 @@ impl SystemTray {
                 let _: () = msg_send![status_item, retain];
 
                 let snapshot = state.get_snapshot(*first, cx);
-                let rendered = self
-                    .renderer
-                    .render(*first, snapshot.as_ref(), false, None, None);
-                self.set_status_item_image(status_item, &rendered);
+                let icon =
+                    self.renderer
+                        .render_cached(*first, snapshot.as_ref(), false, None, None);
+                self.set_status_item_image(status_item, icon.png());
 
                 // Create delegate for handling clicks (provider=None for merged)
                 let delegate = create_delegate(&self.click_sender, None);
@@ -493,12 +622,10 @@ impl SystemTray {
         }
     }
 
-    /// Sets the image for a status item from a RenderedIcon.
-    fn set_status_item_image(&self, status_item: id, rendered: &RenderedIcon) {
+    /// Sets the image for a status item from a rendered icon's PNG, drawn
+    /// for a display with `backing_scale` device pixels per point.
+    fn set_status_item_image(&self, status_item: id, png_data: &[u8], backing_scale: f64) {
         unsafe {
-            // Convert to PNG bytes
-            let png_data = rendered.to_png();
-
             // Create NSData from PNG bytes
             let ns_data: id = msg_send![
                 class!(NSData),
@@ -512,12 +639,18 @@ impl SystemTray {
 
             if ns_image != nil {
                 // Set as template image for proper dark/light mode support
-                // Template images are rendered by macOS in the appropriate color
-                let _: () = msg_send![ns_image, setTemplate: YES];
-
-                // Set size (18x11 points for our icon dimensions)
-                // macOS handles retina scaling automatically
-                let size = NSSize::new(18.0, 11.0);
+                // Template images are rendered by macOS in the appropriate color;
+                // adaptive icons already are
+                let template = self.renderer.mode() == RenderMode::Template;
+                let _: () = msg_send![ns_image, setTemplate: if template { YES } else { NO }];
+
+                // Size the image in points so its pixels map one to one
+                // onto the display's (18x11 points on a standard menu bar)
+                let (width, height) = self.renderer.pixel_size();
+                let size = NSSize::new(
+                    f64::from(width) / backing_scale,
+                    f64::from(height) / backing_scale,
+                );
                 let _: () = msg_send![ns_image, setSize: size];
 
                 // Get the status item's button and set the image
@@ -538,10 +671,14 @@ impl SystemTray {
 
     /// Updates the icon for a specific provider.
     pub fn update_icon(&mut self, provider: ProviderKind, cx: &mut App) {
+        self.apply_icon_style(cx);
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
-        let is_refreshing = state.is_provider_refreshing(provider, cx);
-        let has_error = state.get_error(provider, cx).is_some();
+        // Until the first fetch lands there's no usage to show, so the
+        // meter plays a loading pattern (advanced by the loading timer)
+        let loading = snapshot.is_none() && state.is_provider_refreshing(provider, cx);
+        // Failures within the provider's tolerance keep the last usage up
+        let has_error = state.is_failing(provider, cx);
         let status = state.get_status(provider, cx);
 
         // Check if snapshot is stale (older than 10 minutes)
@@ -550,37 +687,89 @@ impl SystemTray {
             chrono::Utc::now() - s.updated_at > threshold
         });
 
+        // Start the attention animation if usage just went critical
+        self.check_attention(provider, snapshot.as_ref(), cx);
+
+        // Draw for the display the status item is on
+        let item_key = (!self.merge_mode).then_some(provider);
+        let status_item = self.status_item(item_key);
+        let backing_scale = status_item.map_or(DESIGN_BACKING_SCALE, backing_scale_factor);
+        let scale = icon_scale(backing_scale, menu_bar_thickness());
+        self.renderer.set_scale(scale);
+        self.icon_scales.insert(item_key, scale);
+
         // Get animation state for this provider
         let animation = self.animation_states.get(&provider);
 
-        let rendered = if is_refreshing {
-            self.loading_phase += 0.1;
-            self.renderer.render_loading(provider, self.loading_phase)
+        // Usage icons come from the renderer's cache; loading and error
+        // icons are drawn every time
+        let cached;
+        let drawn;
+        let png: &[u8] = if loading {
+            let pattern = loading_pattern(provider, cx);
+            drawn = self
+                .renderer
+                .render_loading(provider, pattern, self.loading_phase)
+                .to_png();
+            &drawn
         } else if has_error {
-            self.renderer.render_error(provider)
+            drawn = self.renderer.render_error(provider).to_png();
+            &drawn
         } else {
             let status_indicator = status.map(|s| s.indicator).unwrap_or(StatusIndicator::None);
 
-            self.renderer.render(
+            cached = self.renderer.render_cached(
                 provider,
                 snapshot.as_ref(),
                 stale,
                 Some(status_indicator),
                 animation,
-            )
+            );
+            cached.png()
         };
 
-        if self.merge_mode {
-            if let Some(status_item) = self.merged_status_item {
-                self.set_status_item_image(status_item, &rendered);
-            }
-        } else if let Some(&status_item) = self.status_items.get(&provider) {
-            self.set_status_item_image(status_item, &rendered);
+        if let Some(status_item) = status_item {
+            self.set_status_item_image(status_item, png, backing_scale);
         }
 
         debug!(provider = ?provider, stale = stale, "Icon updated");
     }
 
+    /// Returns a provider's status item, or the merged one for `None`.
+    fn status_item(&self, provider: Option<ProviderKind>) -> Option<id> {
+        match provider {
+            Some(provider) => self.status_items.get(&provider).copied(),
+            None => self.merged_status_item,
+        }
+    }
+
+    /// Returns whether a status item moved to a display with another
+    /// backing scale, or the menu bar changed height, since its icon was
+    /// drawn.
+    fn display_scale_changed(&self) -> bool {
+        let thickness = menu_bar_thickness();
+        self.icon_scales.iter().any(|(&key, &scale)| {
+            self.status_item(key).is_some_and(|status_item| {
+                icon_scale(backing_scale_factor(status_item), thickness) != scale
+            })
+        })
+    }
+
+    /// Shows or hides the status items by the icon visibility setting. The
+    /// merged item stays while any provider's icon would be shown.
+    pub fn update_visibility(&self, cx: &App) {
+        let visible = visible_providers(cx);
+        let shown = |is_shown: bool| if is_shown { YES } else { NO };
+        unsafe {
+            if let Some(status_item) = self.merged_status_item {
+                let _: () = msg_send![status_item, setVisible: shown(!visible.is_empty())];
+            }
+            for (provider, &status_item) in &self.status_items {
+                let _: () = msg_send![status_item, setVisible: shown(visible.contains(provider))];
+            }
+        }
+    }
+
     /// Updates all icons based on current state.
     pub fn update_all(&mut self, cx: &mut App) {
         let state = cx.global::<AppState>();
@@ -591,6 +780,44 @@ impl SystemTray {
         }
     }
 
+    /// Updates the hover tooltips with each provider's usage and reset countdown.
+    ///
+    /// The merged item lists every provider, one per line.
+    pub fn update_tooltips(&self, cx: &App) {
+        let state = cx.global::<AppState>();
+        let now = chrono::Utc::now();
+        let line = |provider: ProviderKind| {
+            tooltip::tooltip_line(provider, state.get_snapshot(provider, cx).as_ref(), now)
+        };
+
+        if let Some(status_item) = self.merged_status_item {
+            let text = state
+                .enabled_providers(cx)
+                .into_iter()
+                .map(line)
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.set_status_item_tooltip(status_item, &text);
+        }
+        for (&provider, &status_item) in &self.status_items {
+            self.set_status_item_tooltip(status_item, &line(provider));
+        }
+    }
+
+    /// Sets the tooltip shown when hovering a status item.
+    fn set_status_item_tooltip(&self, status_item: id, text: &str) {
+        unsafe {
+            let button: id = msg_send![status_item, button];
+            if button == nil {
+                return;
+            }
+            let ns_text = NSString::alloc(nil).init_str(text);
+            let _: () = msg_send![button, setToolTip: ns_text];
+            // The button copies the string
+            let _: () = msg_send![ns_text, release];
+        }
+    }
+
     // ========================================================================
     // Animation Methods
     // ========================================================================
@@ -644,6 +871,12 @@ impl SystemTray {
             }
         }
 
+        for provider in self.tick_attention() {
+            if !needs_update.contains(&provider) {
+                needs_update.push(provider);
+            }
+        }
+
         // Only update icons that have active animations
         for provider in needs_update {
             self.update_icon(provider, cx);
@@ -772,9 +1005,29 @@ impl SystemTray {
             }
         }
 
+        self.update_visibility(cx);
         info!(merge_mode = merge, "Merge mode changed");
     }
 
+    /// Recreates the per-provider status items in the current provider
+    /// order. The menu bar puts each new item left of the existing ones, so
+    /// they're created last to first.
+    pub fn reorder_status_items(&mut self, cx: &mut App) {
+        if self.merge_mode {
+            return;
+        }
+
+        self.remove_all_status_items();
+        let state = cx.global::<AppState>();
+        let providers = state.enabled_providers(cx);
+        for provider in providers.into_iter().rev() {
+            self.create_status_item(provider, cx);
+        }
+        self.update_visibility(cx);
+
+        debug!("Status items reordered by usage");
+    }
+
     /// Adds a provider to the tray.
     pub fn add_provider(&mut self, provider: ProviderKind, cx: &mut App) {
         // Ensure animation state exists for this provider
@@ -782,6 +1035,7 @@ impl SystemTray {
 
         if !self.merge_mode && !self.status_items.contains_key(&provider) {
             self.create_status_item(provider, cx);
+            self.update_visibility(cx);
         }
     }
 
@@ -789,6 +1043,8 @@ impl SystemTray {
     pub fn remove_provider(&mut self, provider: ProviderKind) {
         // Clean up animation state
         self.animation_states.remove(&provider);
+        self.attention.remove(&provider);
+        self.critical.remove(&provider);
 
         if let Some(status_item) = self.status_items.remove(&provider) {
             unsafe {
@@ -825,7 +1081,7 @@ impl SystemTray {
 
     /// Toggles the tray menu.
     pub fn toggle_menu(&mut self, provider: Option<ProviderKind>, cx: &mut App) {
-        if self.menu_window.is_some() {
+        if self.menu_is_open(cx) {
             self.close_menu(cx);
         } else {
             self.open_menu(provider, cx);
@@ -973,6 +1229,13 @@ impl SystemTray {
         }
     }
 
+    /// Whether the tray menu is showing. The menu closes itself when it
+    /// loses focus, so the stored handle may be of a closed window.
+    fn menu_is_open(&self, cx: &mut App) -> bool {
+        self.menu_window
+            .is_some_and(|handle| cx.update_window(handle, |_, _, _| {}).is_ok())
+    }
+
     /// Closes the tray menu.
     fn close_menu(&mut self, cx: &mut App) {
         if let Some(handle) = self.menu_window.take() {
@@ -987,10 +1250,10 @@ impl SystemTray {
     pub fn get_icon_png(&self, provider: ProviderKind, cx: &App) -> Option<Vec<u8>> {
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
-        let rendered = self
+        let icon = self
             .renderer
-            .render(provider, snapshot.as_ref(), false, None, None);
-        Some(rendered.to_png())
+            .render_cached(provider, snapshot.as_ref(), false, None, None);
+        Some(icon.png().to_vec())
     }
 }
 
@@ -1003,6 +1266,311 @@ impl Drop for SystemTray {
     }
 }
 
+// ============================================================================
+// Icon & Tooltip Refresh (all platforms)
+// ============================================================================
+
+/// Redraws a provider's tray icon and tooltip after its usage changed,
+/// first moving the status items if sorting by usage changed their order.
+///
+/// Does nothing before the tray has been created.
+pub fn refresh_icon(provider: ProviderKind, cx: &mut App) {
+    let reordered = update_usage_order(cx);
+    if cx.has_global::<SystemTray>() {
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            if reordered {
+                tray.reorder_status_items(cx);
+            }
+            tray.update_icon(provider, cx);
+            tray.update_tooltips(cx);
+            tray.update_visibility(cx);
+        });
+    }
+}
+
+/// Puts the status items in the current provider order, e.g. after sorting
+/// by usage was turned on or off.
+///
+/// Does nothing before the tray has been created.
+pub fn apply_provider_order(cx: &mut App) {
+    update_usage_order(cx);
+    if cx.has_global::<SystemTray>() {
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            tray.reorder_status_items(cx);
+            tray.update_all(cx);
+            tray.update_tooltips(cx);
+            tray.update_visibility(cx);
+        });
+    }
+}
+
+/// Re-sorts providers by remaining quota when sorting by usage. Returns
+/// whether the order changed.
+fn update_usage_order(cx: &mut App) -> bool {
+    let state = cx.global::<AppState>();
+    let settings = state.settings.read(cx);
+    if !settings.sort_by_usage() {
+        return false;
+    }
+    let providers = settings.enabled_providers();
+    let usage = state.usage.clone();
+    usage.update(cx, |model, _| model.update_usage_order(&providers))
+}
+
+/// Redraws every tray icon, e.g. after the icon layout or palette changed.
+///
+/// Does nothing before the tray has been created.
+pub fn refresh_all_icons(cx: &mut App) {
+    if cx.has_global::<SystemTray>() {
+        cx.update_global::<SystemTray, _>(|tray, cx| {
+            tray.update_all(cx);
+            tray.update_visibility(cx);
+        });
+    }
+}
+
+/// Providers whose icons are shown under the icon visibility setting, by
+/// their lowest remaining quota.
+fn visible_providers(cx: &App) -> HashSet<ProviderKind> {
+    let state = cx.global::<AppState>();
+    let settings = state.settings.read(cx).settings();
+    let remaining: Vec<_> = state
+        .enabled_providers(cx)
+        .into_iter()
+        .map(|p| {
+            let snapshot = state.get_snapshot(p, cx);
+            (p, snapshot.and_then(|s| s.min_remaining_percent()))
+        })
+        .collect();
+    settings
+        .icon_visibility
+        .visible(settings.icon_visibility_threshold, &remaining)
+        .into_iter()
+        .collect()
+}
+
+impl SystemTray {
+    /// Starts the tooltip timer.
+    ///
+    /// Refreshes the tooltip once a minute so the reset countdowns stay
+    /// current between usage refreshes.
+    pub fn start_tooltip_timer(&mut self, cx: &mut App) {
+        cx.spawn(async move |cx| {
+            loop {
+                let _ = cx.update_global::<SystemTray, _>(|tray, cx| {
+                    tray.update_tooltips(cx);
+                });
+                smol::Timer::after(std::time::Duration::from_secs(60)).await;
+            }
+        })
+        .detach();
+
+        info!("Tooltip timer started (every minute)");
+    }
+}
+
+// ============================================================================
+// Icon Style (all platforms)
+// ============================================================================
+
+/// Mode icons are drawn in unless adaptive: template images the system
+/// tints on macOS, brand colors elsewhere.
+#[cfg(target_os = "macos")]
+const STANDARD_RENDER_MODE: RenderMode = RenderMode::Template;
+#[cfg(not(target_os = "macos"))]
+const STANDARD_RENDER_MODE: RenderMode = RenderMode::Colored;
+
+/// How often the system appearance is checked for changes.
+const APPEARANCE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Returns the mode icons are drawn in under the adaptive icon setting.
+fn render_mode(adaptive: bool) -> RenderMode {
+    if adaptive {
+        RenderMode::Adaptive
+    } else {
+        STANDARD_RENDER_MODE
+    }
+}
+
+/// Returns whether the menu bar is light or dark under a system appearance.
+fn menu_bar_appearance(appearance: WindowAppearance) -> MenuBarAppearance {
+    match appearance {
+        WindowAppearance::Dark | WindowAppearance::VibrantDark => MenuBarAppearance::Dark,
+        WindowAppearance::Light | WindowAppearance::VibrantLight => MenuBarAppearance::Light,
+    }
+}
+
+impl SystemTray {
+    /// Applies the icon settings and the system appearance to the renderer.
+    fn apply_icon_style(&mut self, cx: &App) {
+        let settings = cx.global::<AppState>().settings.read(cx);
+        self.renderer
+            .set_mode(render_mode(settings.adaptive_icon()));
+        self.renderer.set_layout(settings.icon_layout());
+        self.renderer.set_palette(settings.color_palette());
+        self.renderer.set_outline(settings.icon_outline());
+        self.renderer
+            .set_appearance(menu_bar_appearance(cx.window_appearance()));
+    }
+
+    /// Starts watching the system appearance and the displays.
+    ///
+    /// Adaptive icons are drawn for a light or dark menu bar, so they're
+    /// redrawn when the system switches between the two, and every icon is
+    /// redrawn when its display's scale changes.
+    pub fn start_appearance_observer(&mut self, cx: &mut App) {
+        cx.spawn(async move |cx| {
+            loop {
+                smol::Timer::after(APPEARANCE_POLL_INTERVAL).await;
+                let _ = cx.update_global::<SystemTray, _>(|tray, cx| {
+                    let appearance = menu_bar_appearance(cx.window_appearance());
+                    if appearance != tray.renderer.appearance() {
+                        info!(appearance = ?appearance, "Menu bar appearance changed");
+                        tray.renderer.set_appearance(appearance);
+                        tray.update_all(cx);
+                    } else if tray.display_scale_changed() {
+                        info!("Menu bar display scale changed");
+                        tray.update_all(cx);
+                    }
+                });
+            }
+        })
+        .detach();
+
+        info!("Appearance observer started");
+    }
+}
+
+// ============================================================================
+// Loading Animation (all platforms)
+// ============================================================================
+
+/// Time between frames of the loading pattern.
+const LOADING_FRAME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// How far the loading pattern moves per frame, in radians.
+const LOADING_PHASE_STEP: f64 = 0.15;
+
+/// Returns the pattern a provider's icon plays while it loads: the debug
+/// loading pattern if one is set, otherwise one per provider, so icons
+/// loading side by side don't move in lockstep.
+fn loading_pattern(provider: ProviderKind, cx: &App) -> LoadingPattern {
+    let settings = cx.global::<AppState>().settings.read(cx).settings();
+    if let Some(pattern) = settings
+        .debug_loading_pattern
+        .as_deref()
+        .and_then(LoadingPattern::from_name)
+    {
+        return pattern;
+    }
+    let patterns = LoadingPattern::all();
+    patterns[provider.to_index() % patterns.len()]
+}
+
+impl SystemTray {
+    /// Starts the loading timer.
+    ///
+    /// While any provider's first fetch is in flight, its icon plays a
+    /// loading pattern; otherwise the timer only checks and sleeps.
+    pub fn start_loading_timer(&mut self, cx: &mut App) {
+        cx.spawn(async move |cx| {
+            loop {
+                smol::Timer::after(LOADING_FRAME_INTERVAL).await;
+                let _ = cx.update_global::<SystemTray, _>(|tray, cx| {
+                    tray.tick_loading(cx);
+                });
+            }
+        })
+        .detach();
+
+        info!("Loading timer started");
+    }
+
+    /// Advances the loading pattern and redraws the icons of providers
+    /// still waiting for their first fetch.
+    fn tick_loading(&mut self, cx: &mut App) {
+        let state = cx.global::<AppState>();
+        let loading: Vec<ProviderKind> = state
+            .enabled_providers(cx)
+            .into_iter()
+            .filter(|&provider| {
+                state.is_provider_refreshing(provider, cx)
+                    && state.get_snapshot(provider, cx).is_none()
+            })
+            .collect();
+        if loading.is_empty() {
+            return;
+        }
+
+        self.loading_phase += LOADING_PHASE_STEP;
+        for provider in loading {
+            self.update_icon(provider, cx);
+        }
+    }
+}
+
+// ============================================================================
+// Attention Animation (all platforms)
+// ============================================================================
+
+impl SystemTray {
+    /// Starts the attention animation when a provider crosses the critical
+    /// threshold. Runs once per crossing; usage has to drop back below the
+    /// threshold before it can play again.
+    fn check_attention(
+        &mut self,
+        provider: ProviderKind,
+        snapshot: Option<&UsageSnapshot>,
+        cx: &App,
+    ) {
+        let critical = snapshot
+            .and_then(|s| s.primary.as_ref())
+            .is_some_and(|w| w.meets_threshold(CRITICAL_THRESHOLD));
+        if !critical {
+            self.critical.remove(&provider);
+            return;
+        }
+        if !self.critical.insert(provider) {
+            return; // Already critical
+        }
+
+        let settings = cx.global::<AppState>().settings.read(cx).settings();
+        if settings.attention_animation == AttentionStyle::Off {
+            return;
+        }
+
+        info!(
+            provider = ?provider,
+            style = %settings.attention_animation,
+            "Usage critical, starting attention animation"
+        );
+        self.attention.insert(
+            provider,
+            AttentionAnimation::start(
+                settings.attention_animation,
+                settings.attention_intensity,
+                std::time::Duration::from_secs(u64::from(settings.attention_duration_secs)),
+            ),
+        );
+    }
+
+    /// Advances attention animations, returning providers whose icons changed.
+    fn tick_attention(&mut self) -> Vec<ProviderKind> {
+        let mut changed = Vec::new();
+
+        self.attention.retain(|provider, anim| {
+            let level = anim.level();
+            if let Some(state) = self.animation_states.get_mut(provider) {
+                state.attention = level.unwrap_or(0.0);
+            }
+            changed.push(*provider);
+            level.is_some()
+        });
+
+        changed
+    }
+}
+
 // ============================================================================
 // Linux SNI Implementation
 // ============================================================================
@@ -1020,7 +1588,13 @@ impl SystemTray {
         let providers = state.enabled_providers(cx);
 
         // Use Colored mode for Linux (we'll convert RGBA to ARGB for ksni)
-        let renderer = IconRenderer::new().with_mode(RenderMode::Colored);
+        // unless the icon is drawn for the appearance
+        let renderer = IconRenderer::new()
+            .with_mode(render_mode(state.settings.read(cx).adaptive_icon()))
+            .with_layout(state.settings.read(cx).icon_layout())
+            .with_palette(state.settings.read(cx).color_palette())
+            .with_outline(state.settings.read(cx).icon_outline())
+            .with_appearance(menu_bar_appearance(cx.window_appearance()));
 
         // Create channel for Linux tray events
         let (linux_event_sender, linux_event_receiver) = mpsc::channel();
@@ -1042,10 +1616,13 @@ impl SystemTray {
             animation_states,
             surprise_me_enabled,
             last_random_event: std::time::Instant::now(),
+            attention: HashMap::new(),
+            critical: HashSet::new(),
         };
 
         // Create the SNI tray
         tray.create_sni_tray(cx);
+        tray.update_visibility(cx);
 
         info!(merge_mode = merge_mode, "Linux SNI system tray initialized");
         tray
@@ -1058,10 +1635,10 @@ impl SystemTray {
 
         // Get the first provider for the initial icon
         let first_provider = providers.first().copied();
-        let icon = self.render_linux_icon(first_provider, cx);
+        let icons = self.render_linux_icons(first_provider, cx);
 
         // Create the Linux tray
-        let linux_tray = LinuxTray::new(self.linux_event_sender.clone(), icon);
+        let linux_tray = LinuxTray::new(self.linux_event_sender.clone(), icons);
 
         // Spawn the tray service
         match linux_tray.spawn() {
@@ -1075,8 +1652,8 @@ impl SystemTray {
         }
     }
 
-    /// Renders an icon for Linux in ARGB format (as required by ksni).
-    fn render_linux_icon(&self, provider: Option<ProviderKind>, cx: &App) -> KsniIcon {
+    /// Renders an icon for Linux at every scale in [`LINUX_ICON_SCALES`].
+    fn render_linux_icons(&mut self, provider: Option<ProviderKind>, cx: &App) -> Vec<KsniIcon> {
         let state = cx.global::<AppState>();
 
         // Get snapshot and status for rendering
@@ -1089,39 +1666,23 @@ impl SystemTray {
             (None, StatusIndicator::None)
         };
 
-        // Render the icon
-        let rendered = if let Some(p) = provider {
-            self.renderer
-                .render(p, snapshot.as_ref(), false, Some(status_indicator), None)
-        } else {
-            // Fallback: render a default icon
-            self.renderer.render(
-                ProviderKind::Codex,
-                None,
-                false,
-                Some(StatusIndicator::None),
-                None,
-            )
-        };
-
-        // Get raw RGBA pixels
-        let (width, height, mut pixels) = rendered.to_rgba_pixels();
-
-        // Convert RGBA to ARGB (Linux SNI expects ARGB in network byte order)
-        for pixel in pixels.chunks_exact_mut(4) {
-            // RGBA -> ARGB: [R, G, B, A] -> [A, R, G, B]
-            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
-            pixel[0] = a;
-            pixel[1] = r;
-            pixel[2] = g;
-            pixel[3] = b;
-        }
-
-        KsniIcon {
-            width: width as i32,
-            height: height as i32,
-            data: pixels,
-        }
+        // Fallback: render a default icon
+        let provider = provider.unwrap_or(ProviderKind::Codex);
+
+        LINUX_ICON_SCALES
+            .iter()
+            .map(|&scale| {
+                self.renderer.set_scale(scale);
+                let rendered = self.renderer.render(
+                    provider,
+                    snapshot.as_ref(),
+                    false,
+                    Some(status_indicator),
+                    None,
+                );
+                to_ksni_icon(&rendered)
+            })
+            .collect()
     }
 
     /// Starts the event listener for Linux tray events.
@@ -1166,7 +1727,7 @@ impl SystemTray {
                         LinuxTrayEvent::Quit => {
                             info!("Quit requested from tray menu");
                             let _ = cx.update(|cx| {
-                                cx.quit();
+                                crate::actions::quit(cx);
                             });
                         }
                     }
@@ -1183,10 +1744,14 @@ impl SystemTray {
 
     /// Updates the icon for a specific provider.
     pub fn update_icon(&mut self, provider: ProviderKind, cx: &mut App) {
+        self.apply_icon_style(cx);
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
-        let is_refreshing = state.is_provider_refreshing(provider, cx);
-        let has_error = state.get_error(provider, cx).is_some();
+        // Until the first fetch lands there's no usage to show, so the
+        // meter plays a loading pattern (advanced by the loading timer)
+        let loading = snapshot.is_none() && state.is_provider_refreshing(provider, cx);
+        // Failures within the provider's tolerance keep the last usage up
+        let has_error = state.is_failing(provider, cx);
         let status = state.get_status(provider, cx);
 
         // Check if snapshot is stale (older than 10 minutes)
@@ -1195,52 +1760,69 @@ impl SystemTray {
             chrono::Utc::now() - s.updated_at > threshold
         });
 
+        // Start the attention animation if usage just went critical
+        self.check_attention(provider, snapshot.as_ref(), cx);
+
         // Get animation state for this provider
         let animation = self.animation_states.get(&provider);
-
-        let rendered = if is_refreshing {
-            self.loading_phase += 0.1;
-            self.renderer.render_loading(provider, self.loading_phase)
-        } else if has_error {
-            self.renderer.render_error(provider)
-        } else {
-            let status_indicator = status.map(|s| s.indicator).unwrap_or(StatusIndicator::None);
-
-            self.renderer.render(
-                provider,
-                snapshot.as_ref(),
-                stale,
-                Some(status_indicator),
-                animation,
-            )
-        };
-
-        // Convert to ARGB for ksni
-        let (width, height, mut pixels) = rendered.to_rgba_pixels();
-        for pixel in pixels.chunks_exact_mut(4) {
-            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
-            pixel[0] = a;
-            pixel[1] = r;
-            pixel[2] = g;
-            pixel[3] = b;
+        let status_indicator = status.map(|s| s.indicator).unwrap_or(StatusIndicator::None);
+        let pattern = loading_pattern(provider, cx);
+
+        // Draw every size the host may pick from. Usage icons come from
+        // the renderer's cache; loading and error icons are drawn every time
+        let mut icons = Vec::with_capacity(LINUX_ICON_SCALES.len());
+        for scale in LINUX_ICON_SCALES {
+            self.renderer.set_scale(scale);
+            let cached;
+            let drawn;
+            let rendered = if loading {
+                drawn = self
+                    .renderer
+                    .render_loading(provider, pattern, self.loading_phase);
+                &drawn
+            } else if has_error {
+                drawn = self.renderer.render_error(provider);
+                &drawn
+            } else {
+                cached = self.renderer.render_cached(
+                    provider,
+                    snapshot.as_ref(),
+                    stale,
+                    Some(status_indicator),
+                    animation,
+                );
+                &cached.icon
+            };
+            icons.push(to_ksni_icon(rendered));
         }
 
-        let icon = KsniIcon {
-            width: width as i32,
-            height: height as i32,
-            data: pixels,
-        };
-
         // Update the tray icon
         if let Some(handle) = &self.sni_handle {
             handle.update(|tray| {
-                tray.icon = icon;
+                tray.icons = icons;
             });
         }
 
         debug!(provider = ?provider, stale = stale, "Icon updated (Linux)");
     }
 
+    /// Returns whether icons need redrawing for another display scale
+    /// (never on Linux; the host picks from every size).
+    fn display_scale_changed(&self) -> bool {
+        false
+    }
+
+    /// Hides the tray icon while the icon visibility setting shows no
+    /// provider. Hosts that honor the SNI status move it to the overflow.
+    pub fn update_visibility(&self, cx: &App) {
+        let visible = !visible_providers(cx).is_empty();
+        if let Some(handle) = &self.sni_handle {
+            handle.update(|tray| {
+                tray.visible = visible;
+            });
+        }
+    }
+
     /// Updates all icons based on current state.
     pub fn update_all(&mut self, cx: &mut App) {
         let state = cx.global::<AppState>();
@@ -1252,6 +1834,26 @@ impl SystemTray {
         }
     }
 
+    /// Updates the hover tooltip with each provider's usage and reset countdown.
+    pub fn update_tooltips(&self, cx: &App) {
+        let state = cx.global::<AppState>();
+        let now = chrono::Utc::now();
+        let description = state
+            .enabled_providers(cx)
+            .into_iter()
+            .map(|provider| {
+                tooltip::tooltip_line(provider, state.get_snapshot(provider, cx).as_ref(), now)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(handle) = &self.sni_handle {
+            handle.update(|tray| {
+                tray.tooltip = description;
+            });
+        }
+    }
+
     // ========================================================================
     // Animation Methods
     // ========================================================================
@@ -1295,6 +1897,12 @@ impl SystemTray {
             }
         }
 
+        for provider in self.tick_attention() {
+            if !needs_update.contains(&provider) {
+                needs_update.push(provider);
+            }
+        }
+
         for provider in needs_update {
             self.update_icon(provider, cx);
         }
@@ -1390,6 +1998,10 @@ impl SystemTray {
         debug!(merge_mode = merge, "Merge mode changed (Linux - no-op)");
     }
 
+    /// Reorders status items (no-op on Linux; the single item's menu reads
+    /// the order when it opens).
+    pub fn reorder_status_items(&mut self, _cx: &mut App) {}
+
     /// Adds a provider to the tray.
     pub fn add_provider(&mut self, provider: ProviderKind, _cx: &mut App) {
         self.ensure_animation_state(provider);
@@ -1399,6 +2011,8 @@ impl SystemTray {
     /// Removes a provider from the tray.
     pub fn remove_provider(&mut self, provider: ProviderKind) {
         self.animation_states.remove(&provider);
+        self.attention.remove(&provider);
+        self.critical.remove(&provider);
         // Linux only has one icon, so nothing else to do
     }
 
@@ -1414,7 +2028,7 @@ impl SystemTray {
         click_pos: Option<(i32, i32)>,
         cx: &mut App,
     ) {
-        if self.menu_window.is_some() {
+        if self.menu_is_open(cx) {
             self.close_menu(cx);
         } else {
             self.open_menu_at(provider, click_pos, cx);
@@ -1524,6 +2138,13 @@ impl SystemTray {
         }
     }
 
+    /// Whether the tray menu is showing. The menu closes itself when it
+    /// loses focus, so the stored handle may be of a closed window.
+    fn menu_is_open(&self, cx: &mut App) -> bool {
+        self.menu_window
+            .is_some_and(|handle| cx.update_window(handle, |_, _, _| {}).is_ok())
+    }
+
     /// Closes the tray menu.
     fn close_menu(&mut self, cx: &mut App) {
         if let Some(handle) = self.menu_window.take() {
@@ -1537,10 +2158,10 @@ impl SystemTray {
     pub fn get_icon_png(&self, provider: ProviderKind, cx: &App) -> Option<Vec<u8>> {
         let state = cx.global::<AppState>();
         let snapshot = state.get_snapshot(provider, cx);
-        let rendered = self
+        let icon = self
             .renderer
-            .render(provider, snapshot.as_ref(), false, None, None);
-        Some(rendered.to_png())
+            .render_cached(provider, snapshot.as_ref(), false, None, None);
+        Some(icon.png().to_vec())
     }
 }
 
@@ -0,0 +1,590 @@
+//! Usage history window.
+//!
+//! Full-size charts of every provider window's usage over the last 24
+//! hours, 7 days or 30 days, drawn from the persisted [`HistoryStore`].
+//! Window resets and the user's notes are marked above the bars.
+
+use chrono::{DateTime, Duration, Local, Utc};
+use exactobar_core::ProviderKind;
+use exactobar_store::{HistoryPoint, HistoryStore, SnapshotEventKind, UsageNote, WindowSlot};
+use gpui::prelude::*;
+use gpui::*;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+use crate::menu::usage_color;
+use crate::refresh::tokio_runtime;
+use crate::state::AppState;
+use crate::theme;
+
+/// Global handle to the history window (if open).
+static HISTORY_WINDOW: Mutex<Option<AnyWindowHandle>> = Mutex::new(None);
+
+/// Columns each chart is split into.
+const CHART_BUCKETS: usize = 96;
+
+/// Height of a chart's plot area.
+const CHART_HEIGHT: f32 = 120.;
+
+/// Most notes listed under a chart.
+const MAX_NOTES_LISTED: usize = 5;
+
+// ============================================================================
+// Range
+// ============================================================================
+
+/// Time range the charts cover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryRange {
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryRange {
+    /// All ranges, shortest first.
+    pub fn all() -> &'static [HistoryRange] {
+        &[HistoryRange::Day, HistoryRange::Week, HistoryRange::Month]
+    }
+
+    /// Label shown on the range picker.
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryRange::Day => "24h",
+            HistoryRange::Week => "7d",
+            HistoryRange::Month => "30d",
+        }
+    }
+
+    /// Length of the range.
+    pub fn duration(self) -> Duration {
+        match self {
+            HistoryRange::Day => Duration::hours(24),
+            HistoryRange::Week => Duration::days(7),
+            HistoryRange::Month => Duration::days(30),
+        }
+    }
+
+    /// Format of the time axis labels.
+    fn axis_format(self) -> &'static str {
+        match self {
+            HistoryRange::Day => "%H:%M",
+            HistoryRange::Week => "%a %H:%M",
+            HistoryRange::Month => "%b %-d",
+        }
+    }
+}
+
+// ============================================================================
+// History Window
+// ============================================================================
+
+/// The usage history window content.
+pub struct HistoryWindow {
+    range: HistoryRange,
+    /// Loaded history; `None` while loading.
+    history: Option<Result<HistoryStore, String>>,
+}
+
+impl HistoryWindow {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mut view = Self {
+            range: HistoryRange::Day,
+            history: None,
+        };
+        view.reload(cx);
+        view
+    }
+
+    /// Loads the history from disk again.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let result = smol::unblock(|| tokio_runtime().block_on(HistoryStore::load_default()))
+                .await
+                .map_err(|e| e.to_string());
+            if let Err(e) = &result {
+                error!(error = %e, "Failed to load usage history");
+            }
+            let _ = this.update(cx, |view, cx| {
+                view.history = Some(result);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn render_range_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current = self.range;
+        div()
+            .flex()
+            .gap(px(4.))
+            .children(HistoryRange::all().iter().map(|&range| {
+                let selected = range == current;
+                div()
+                    .id(range.label())
+                    .px(px(10.))
+                    .py(px(4.))
+                    .rounded(px(6.))
+                    .text_sm()
+                    .cursor_pointer()
+                    .when(selected, |el| el.bg(theme::accent()).text_color(white()))
+                    .when(!selected, |el| {
+                        el.text_color(theme::text_primary())
+                            .hover(|s| s.bg(theme::hover()))
+                    })
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.range = range;
+                        cx.notify();
+                    }))
+                    .child(range.label())
+            }))
+    }
+
+    fn render_message(message: impl Into<SharedString>) -> Div {
+        div()
+            .p(px(24.))
+            .text_sm()
+            .text_color(theme::muted())
+            .child(message.into())
+    }
+}
+
+impl Render for HistoryWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let end = Utc::now();
+        let start = end - self.range.duration();
+        let notes = cx.global::<AppState>().usage.read(cx).notes().to_vec();
+
+        let body = match &self.history {
+            None => Self::render_message("Loading history…").into_any_element(),
+            Some(Err(e)) => {
+                Self::render_message(format!("Couldn't load history: {e}")).into_any_element()
+            }
+            Some(Ok(history)) => {
+                let charts: Vec<UsageChart> = history
+                    .providers()
+                    .into_iter()
+                    .flat_map(|provider| {
+                        history
+                            .windows(provider)
+                            .into_iter()
+                            .map(move |slot| (provider, slot))
+                    })
+                    .filter_map(|(provider, slot)| {
+                        UsageChart::new(history, &notes, provider, slot, self.range, start, end)
+                    })
+                    .collect();
+
+                if charts.is_empty() {
+                    Self::render_message(format!(
+                        "No usage recorded in the last {}. History is kept while ExactoBar runs.",
+                        self.range.label()
+                    ))
+                    .into_any_element()
+                } else {
+                    div()
+                        .id("history-charts")
+                        .flex_1()
+                        .overflow_y_scroll()
+                        .p(px(16.))
+                        .flex()
+                        .flex_col()
+                        .gap(px(12.))
+                        .children(charts)
+                        .into_any_element()
+                }
+            }
+        };
+
+        div()
+            .size_full()
+            .bg(theme::surface_background())
+            .text_color(theme::text_primary())
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .px(px(16.))
+                    .py(px(12.))
+                    .border_b_1()
+                    .border_color(theme::border())
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("Usage History"),
+                    )
+                    .child(self.render_range_picker(cx)),
+            )
+            .child(body)
+    }
+}
+
+/// Opens the usage history window, or focuses and reloads it if already
+/// open.
+pub fn open_history(cx: &mut App) {
+    let existing = *HISTORY_WINDOW.lock().unwrap();
+    if let Some(handle) = existing.and_then(|h| h.downcast::<HistoryWindow>()) {
+        if handle
+            .update(cx, |view, window, cx| {
+                window.activate_window();
+                view.reload(cx);
+            })
+            .is_ok()
+        {
+            info!("Focused existing history window");
+            cx.activate(true);
+            return;
+        }
+        // Window was closed, continue to create new one
+    }
+
+    info!("Opening usage history window");
+    cx.activate(true);
+
+    let bounds = Bounds::centered(None, size(px(760.0), px(560.0)), cx);
+
+    let options = WindowOptions {
+        titlebar: Some(TitlebarOptions {
+            title: Some("ExactoBar Usage History".into()),
+            appears_transparent: false,
+            traffic_light_position: None,
+        }),
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        focus: true,
+        show: true,
+        kind: WindowKind::Normal,
+        is_movable: true,
+        display_id: None,
+        window_background: WindowBackgroundAppearance::Opaque,
+        app_id: None,
+        window_min_size: Some(size(px(480.0), px(360.0))),
+        window_decorations: None,
+        is_minimizable: true,
+        is_resizable: true,
+        tabbing_identifier: None,
+    };
+
+    let result = cx.open_window(options, |window, cx| {
+        window.activate_window();
+        cx.new(HistoryWindow::new)
+    });
+
+    match result {
+        Ok(handle) => {
+            let mut guard = HISTORY_WINDOW.lock().unwrap();
+            *guard = Some(handle.into());
+        }
+        Err(e) => {
+            error!(error = ?e, "Failed to open usage history window");
+        }
+    }
+}
+
+// ============================================================================
+// Chart
+// ============================================================================
+
+/// A bar chart of one provider window's usage over a range.
+struct UsageChart {
+    title: String,
+    range: HistoryRange,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// Highest usage in each column, if any was recorded.
+    buckets: Vec<Option<f64>>,
+    /// Columns where the window reset.
+    resets: Vec<usize>,
+    /// Columns with a note.
+    noted: Vec<usize>,
+    notes: Vec<UsageNote>,
+    peak: f64,
+    latest: f64,
+}
+
+impl UsageChart {
+    /// Builds a chart, or None if the window has no points in the range.
+    fn new(
+        history: &HistoryStore,
+        notes: &[UsageNote],
+        provider: ProviderKind,
+        slot: WindowSlot,
+        range: HistoryRange,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Option<Self> {
+        let points = history.series(provider, slot, start);
+        let latest = points.last()?.used_percent;
+        let peak = points.iter().map(|p| p.used_percent).fold(0.0, f64::max);
+
+        let resets = history
+            .events(provider, start)
+            .filter(|a| {
+                a.event.kind() == SnapshotEventKind::WindowReset && a.event.window() == Some(slot)
+            })
+            .filter_map(|a| bucket_index(a.timestamp, start, end, CHART_BUCKETS))
+            .collect();
+        let notes: Vec<UsageNote> = notes
+            .iter()
+            .filter(|n| n.applies_to(provider) && n.timestamp >= start && n.timestamp <= end)
+            .cloned()
+            .collect();
+        let noted = notes
+            .iter()
+            .filter_map(|n| bucket_index(n.timestamp, start, end, CHART_BUCKETS))
+            .collect();
+
+        Some(Self {
+            title: format!("{} · {}", provider.display_name(), slot.label()),
+            range,
+            start,
+            end,
+            buckets: bucket_points(points, start, end, CHART_BUCKETS),
+            resets,
+            noted,
+            notes,
+            peak,
+            latest,
+        })
+    }
+}
+
+impl IntoElement for UsageChart {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let format = self.range.axis_format();
+        let axis_label =
+            |time: DateTime<Utc>| time.with_timezone(&Local).format(format).to_string();
+        let middle = self.start + (self.end - self.start) / 2;
+        let summary = match self.resets.len() {
+            1 => format!("Peak {:.0}% · Now {:.0}% · 1 reset", self.peak, self.latest),
+            n => format!(
+                "Peak {:.0}% · Now {:.0}% · {n} resets",
+                self.peak, self.latest
+            ),
+        };
+        let gridline = |top: f32| {
+            div()
+                .absolute()
+                .left_0()
+                .right_0()
+                .top(px(top))
+                .h(px(1.))
+                .bg(theme::glass_separator())
+        };
+
+        let markers = (0..self.buckets.len()).map(|index| {
+            let reset = self.resets.contains(&index);
+            let noted = self.noted.contains(&index);
+            div()
+                .flex_1()
+                .flex()
+                .justify_center()
+                .when(reset, |el| {
+                    el.child(div().size(px(5.)).rounded_full().bg(theme::accent()))
+                })
+                .when(noted && !reset, |el| {
+                    el.child(div().w(px(1.)).h(px(6.)).bg(theme::muted()))
+                })
+        });
+        let bars = self.buckets.iter().map(|bucket| {
+            div()
+                .flex_1()
+                .h_full()
+                .flex()
+                .flex_col()
+                .justify_end()
+                .when_some(*bucket, |el, used| {
+                    let height = (used.clamp(0.0, 100.0) / 100.0) as f32 * CHART_HEIGHT;
+                    el.child(
+                        div()
+                            .w_full()
+                            .h(px(height.max(1.)))
+                            .rounded_t(px(1.))
+                            .bg(usage_color(used)),
+                    )
+                })
+        });
+
+        div()
+            .p(px(12.))
+            .rounded(px(8.))
+            .bg(theme::card_background())
+            .border_1()
+            .border_color(theme::border())
+            .flex()
+            .flex_col()
+            .gap(px(6.))
+            // Title and summary
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(self.title),
+                    )
+                    .child(div().text_xs().text_color(theme::muted()).child(summary)),
+            )
+            // Reset and note markers
+            .child(
+                div()
+                    .ml(px(36.))
+                    .h(px(6.))
+                    .flex()
+                    .gap(px(1.))
+                    .children(markers),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap(px(4.))
+                    // Percent axis
+                    .child(
+                        div()
+                            .w(px(32.))
+                            .h(px(CHART_HEIGHT))
+                            .flex()
+                            .flex_col()
+                            .justify_between()
+                            .items_end()
+                            .text_xs()
+                            .text_color(theme::muted())
+                            .child("100%")
+                            .child("50%")
+                            .child("0%"),
+                    )
+                    // Plot area
+                    .child(
+                        div()
+                            .relative()
+                            .flex_1()
+                            .h(px(CHART_HEIGHT))
+                            .child(gridline(0.))
+                            .child(gridline(CHART_HEIGHT / 2.))
+                            .child(gridline(CHART_HEIGHT - 1.))
+                            .child(div().size_full().flex().gap(px(1.)).children(bars)),
+                    ),
+            )
+            // Time axis
+            .child(
+                div()
+                    .ml(px(36.))
+                    .flex()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(theme::muted())
+                    .child(axis_label(self.start))
+                    .child(axis_label(middle))
+                    .child(axis_label(self.end)),
+            )
+            // Latest notes in the range
+            .children(
+                self.notes
+                    .iter()
+                    .rev()
+                    .take(MAX_NOTES_LISTED)
+                    .rev()
+                    .map(|note| {
+                        div().text_xs().text_color(theme::muted()).child(format!(
+                            "▏{} {}",
+                            note.timestamp
+                                .with_timezone(&Local)
+                                .format("%a %b %-d %H:%M"),
+                            note.text
+                        ))
+                    }),
+            )
+    }
+}
+
+/// Returns the column of `count` that `time` falls in, or None if it's
+/// outside `start..=end`.
+fn bucket_index(
+    time: DateTime<Utc>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    count: usize,
+) -> Option<usize> {
+    if time < start || time > end || end <= start || count == 0 {
+        return None;
+    }
+    let span = (end - start).num_milliseconds() as f64;
+    let offset = (time - start).num_milliseconds() as f64;
+    let index = (offset / span * count as f64) as usize;
+    Some(index.min(count - 1))
+}
+
+/// Splits `start..=end` into `count` columns holding the highest usage
+/// recorded in each.
+fn bucket_points(
+    points: &[HistoryPoint],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    count: usize,
+) -> Vec<Option<f64>> {
+    let mut buckets = vec![None; count];
+    for point in points {
+        if let Some(index) = bucket_index(point.timestamp, start, end, count) {
+            let bucket: &mut Option<f64> = &mut buckets[index];
+            *bucket = Some(bucket.map_or(point.used_percent, |used| used.max(point.used_percent)));
+        }
+    }
+    buckets
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_index() {
+        let start = Utc::now();
+        let end = start + Duration::hours(24);
+        assert_eq!(bucket_index(start, start, end, 24), Some(0));
+        assert_eq!(
+            bucket_index(start + Duration::minutes(90), start, end, 24),
+            Some(1)
+        );
+        assert_eq!(bucket_index(end, start, end, 24), Some(23));
+        assert_eq!(
+            bucket_index(start - Duration::minutes(1), start, end, 24),
+            None
+        );
+        assert_eq!(
+            bucket_index(end + Duration::minutes(1), start, end, 24),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bucket_points_keeps_peak() {
+        let start = Utc::now();
+        let end = start + Duration::hours(4);
+        let point = |minutes, used_percent| HistoryPoint {
+            timestamp: start + Duration::minutes(minutes),
+            used_percent,
+        };
+        let points = [
+            point(10, 20.0),
+            point(50, 60.0),
+            point(55, 5.0),
+            point(200, 90.0),
+        ];
+
+        assert_eq!(
+            bucket_points(&points, start, end, 4),
+            vec![Some(60.0), None, None, Some(90.0)]
+        );
+    }
+}
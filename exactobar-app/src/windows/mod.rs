@@ -2,16 +2,27 @@
 
 #![allow(dead_code)]
 
+pub mod history;
+pub mod palette;
+pub mod provider_detail;
 pub mod settings;
+pub mod team;
 pub mod update;
 
+pub use history::open_history;
+pub use palette::open_command_palette;
+pub use provider_detail::open_provider_detail;
+pub use team::open_team;
 pub use update::show_update_dialog;
 
+use exactobar_store::{UiState, WindowFrame};
 use gpui::*;
 use std::sync::Mutex;
 use tracing::info;
 
-use settings::SettingsWindow;
+use crate::refresh::tokio_runtime;
+
+use settings::{SettingsPane, SettingsWindow};
 
 /// Global handle to the settings window (if open).
 static SETTINGS_WINDOW: Mutex<Option<AnyWindowHandle>> = Mutex::new(None);
@@ -42,7 +53,17 @@ pub fn open_settings(cx: &mut App) {
     // CRITICAL: For menu bar apps, we must activate the app first!
     cx.activate(true);
 
-    let bounds = Bounds::centered(None, size(px(700.0), px(500.0)), cx);
+    // Reopen where it was last, as long as that's still on a display
+    let ui_state = tokio_runtime().block_on(UiState::load_default());
+    let bounds = ui_state
+        .settings_frame
+        .map(frame_bounds)
+        .filter(|bounds| {
+            cx.displays()
+                .iter()
+                .any(|display| display.bounds().intersects(bounds))
+        })
+        .unwrap_or_else(|| Bounds::centered(None, size(px(700.0), px(500.0)), cx));
 
     let options = WindowOptions {
         titlebar: Some(TitlebarOptions {
@@ -67,7 +88,11 @@ pub fn open_settings(cx: &mut App) {
 
     let result = cx.open_window(options, |window, cx| {
         window.activate_window();
-        cx.new(|_| SettingsWindow::new())
+        cx.new(|_| {
+            let mut view = SettingsWindow::new();
+            view.restore(&ui_state);
+            view
+        })
     });
 
     match result {
@@ -91,6 +116,42 @@ pub fn open_settings(cx: &mut App) {
     }
 }
 
+/// Converts a saved window frame to window bounds, no smaller than the
+/// settings window's minimum size.
+fn frame_bounds(frame: WindowFrame) -> Bounds<Pixels> {
+    Bounds::new(
+        point(px(frame.x), px(frame.y)),
+        size(px(frame.width.max(500.0)), px(frame.height.max(400.0))),
+    )
+}
+
+/// Opens the settings window on a specific pane.
+pub fn open_settings_pane(pane: SettingsPane, cx: &mut App) {
+    open_settings(cx);
+
+    let handle = *SETTINGS_WINDOW.lock().unwrap();
+    if let Some(handle) = handle.and_then(|h| h.downcast::<SettingsWindow>()) {
+        let _ = handle.update(cx, |view, _, cx| {
+            view.set_active_pane(pane);
+            cx.notify();
+        });
+    }
+}
+
+/// Opens the settings window at a setting's row, highlighted, by its
+/// search id (a provider's CLI name for its row on the Providers pane).
+pub fn open_setting(id: &str, cx: &mut App) {
+    open_settings(cx);
+
+    let handle = *SETTINGS_WINDOW.lock().unwrap();
+    if let Some(handle) = handle.and_then(|h| h.downcast::<SettingsWindow>()) {
+        let _ = handle.update(cx, |view, _, cx| {
+            view.reveal_setting(id);
+            cx.notify();
+        });
+    }
+}
+
 /// Clear the settings window handle (call when window closes).
 pub fn clear_settings_window() {
     let mut guard = SETTINGS_WINDOW.lock().unwrap();
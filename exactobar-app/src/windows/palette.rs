@@ -0,0 +1,366 @@
+//! Command palette window.
+//!
+//! A small ⌘K-style window listing every [`AppCommand`]. Typing filters the
+//! list with a fuzzy subsequence match ("rcl" finds "Refresh Claude"),
+//! arrow keys move the selection, Enter runs it and Escape closes.
+
+use gpui::prelude::*;
+use gpui::*;
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::actions::{self, AppCommand};
+use crate::theme;
+
+/// Global handle to the palette window (if open).
+static PALETTE_WINDOW: Mutex<Option<AnyWindowHandle>> = Mutex::new(None);
+
+/// Most rows shown at once.
+const MAX_ROWS: usize = 10;
+
+// ============================================================================
+// Command Palette
+// ============================================================================
+
+/// The command palette window content.
+pub struct CommandPalette {
+    commands: Vec<AppCommand>,
+    labels: Vec<String>,
+    query: String,
+    /// Indices into `commands`, best match first.
+    matches: Vec<usize>,
+    selected: usize,
+    focus_handle: FocusHandle,
+    _activation: Subscription,
+}
+
+impl CommandPalette {
+    fn new(commands: Vec<AppCommand>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let labels: Vec<String> = commands.iter().map(AppCommand::label).collect();
+        let matches = rank("", &labels);
+
+        // Close when the user clicks elsewhere, like a popup menu
+        let activation = cx.observe_window_activation(window, |_, window, _| {
+            if !window.is_window_active() {
+                window.remove_window();
+            }
+        });
+
+        Self {
+            commands,
+            labels,
+            query: String::new(),
+            matches,
+            selected: 0,
+            focus_handle: cx.focus_handle(),
+            _activation: activation,
+        }
+    }
+
+    fn set_query(&mut self, query: String) {
+        self.matches = rank(&query, &self.labels);
+        self.query = query;
+        self.selected = 0;
+    }
+
+    fn run_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(&index) = self.matches.get(self.selected) else {
+            return;
+        };
+        let command = self.commands[index];
+        window.remove_window();
+
+        // Run after the palette is gone so commands can open or focus windows
+        let cx: &mut App = cx;
+        cx.defer(move |cx| command.run(cx));
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        match keystroke.key.as_str() {
+            "escape" => window.remove_window(),
+            "enter" => self.run_selected(window, cx),
+            "up" => self.selected = self.selected.saturating_sub(1),
+            "down" => {
+                self.selected = (self.selected + 1).min(self.matches.len().saturating_sub(1));
+            }
+            "backspace" => {
+                let mut query = self.query.clone();
+                query.pop();
+                self.set_query(query);
+            }
+            _ => {
+                if keystroke.modifiers.platform || keystroke.modifiers.control {
+                    return;
+                }
+                let Some(text) = keystroke
+                    .key_char
+                    .as_ref()
+                    .filter(|t| !t.chars().any(char::is_control))
+                else {
+                    return;
+                };
+                let query = format!("{}{}", self.query, text);
+                self.set_query(query);
+            }
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = if self.query.is_empty() {
+            div().text_color(theme::muted()).child("Type a command…")
+        } else {
+            div()
+                .text_color(theme::text_primary())
+                .child(self.query.clone())
+        };
+
+        // Keep the selection in view
+        let start = self.selected.saturating_sub(MAX_ROWS - 1);
+        let rows = self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(MAX_ROWS)
+            .map(|(position, &index)| {
+                let selected = position == self.selected;
+                div()
+                    .id(("palette-row", position))
+                    .px(px(12.))
+                    .py(px(6.))
+                    .rounded(px(6.))
+                    .cursor_pointer()
+                    .text_sm()
+                    .text_color(theme::text_primary())
+                    .when(selected, |el| el.bg(theme::active()))
+                    .hover(|s| s.bg(theme::hover()))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected = position;
+                        this.run_selected(window, cx);
+                    }))
+                    .child(self.labels[index].clone())
+            })
+            .collect::<Vec<_>>();
+
+        div()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::on_key_down))
+            .size_full()
+            .bg(theme::surface_background())
+            .p(px(8.))
+            .flex()
+            .flex_col()
+            .gap(px(6.))
+            .child(
+                div()
+                    .px(px(12.))
+                    .py(px(8.))
+                    .rounded(px(6.))
+                    .bg(theme::card_background())
+                    .border_1()
+                    .border_color(theme::border())
+                    .text_base()
+                    .child(query),
+            )
+            .when(rows.is_empty(), |el| {
+                el.child(
+                    div()
+                        .px(px(12.))
+                        .py(px(6.))
+                        .text_sm()
+                        .text_color(theme::muted())
+                        .child("No matching commands"),
+                )
+            })
+            .children(rows)
+    }
+}
+
+// ============================================================================
+// Fuzzy Matching
+// ============================================================================
+
+/// Scores how well `query` matches `label`, or `None` if it doesn't.
+///
+/// Every non-space query character must appear in the label in order
+/// (case-insensitive). Matches at word starts and runs of consecutive
+/// characters score higher; skipped characters cost a point each. Each
+/// possible start for the first character is tried and the best kept, so
+/// "cl" matches the "Cl" in "Switch to Claude" rather than the "c" in
+/// "Switch".
+fn fuzzy_score(query: &str, label: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let label: Vec<char> = label.chars().flat_map(char::to_lowercase).collect();
+
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+
+    (0..label.len())
+        .filter(|&start| label[start] == first)
+        .filter_map(|start| score_from(&query, &label, start))
+        .max()
+}
+
+/// Greedily matches `query` against `label` starting at `start`.
+fn score_from(query: &[char], label: &[char], start: usize) -> Option<i32> {
+    let mut wanted = query.iter().peekable();
+    let mut score = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (i, &c) in label.iter().enumerate().skip(start) {
+        let Some(&&next) = wanted.peek() else {
+            break;
+        };
+        if c != next {
+            continue;
+        }
+        wanted.next();
+        score += 1;
+        if i == 0 || !label[i - 1].is_alphanumeric() {
+            score += 5;
+        }
+        match last_match {
+            Some(last) if last + 1 == i => score += 3,
+            Some(last) => score -= i32::try_from(i - last - 1).unwrap_or(i32::MAX),
+            None => {}
+        }
+        last_match = Some(i);
+    }
+
+    wanted.peek().is_none().then_some(score)
+}
+
+/// Returns the indices of labels matching `query`, best first.
+///
+/// Ties keep the original order, so an empty query lists everything as-is.
+fn rank(query: &str, labels: &[String]) -> Vec<usize> {
+    let mut scored: Vec<(usize, i32)> = labels
+        .iter()
+        .enumerate()
+        .filter_map(|(i, label)| fuzzy_score(query, label).map(|score| (i, score)))
+        .collect();
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Opens the command palette, or closes it if it's already open.
+pub fn open_command_palette(cx: &mut App) {
+    {
+        let mut guard = PALETTE_WINDOW.lock().unwrap();
+        if let Some(handle) = guard.take() {
+            if cx
+                .update_window(handle, |_, window, _| window.remove_window())
+                .is_ok()
+            {
+                info!("Closed command palette");
+                return;
+            }
+        }
+    }
+
+    info!("Opening command palette");
+    cx.activate(true);
+
+    let commands = actions::available_commands(cx);
+    let bounds = Bounds::centered(None, size(px(480.0), px(380.0)), cx);
+
+    let options = WindowOptions {
+        titlebar: None,
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        focus: true,
+        show: true,
+        kind: WindowKind::PopUp,
+        is_movable: false,
+        display_id: None,
+        window_background: WindowBackgroundAppearance::Opaque,
+        app_id: None,
+        window_min_size: None,
+        window_decorations: None,
+        is_minimizable: false,
+        is_resizable: false,
+        tabbing_identifier: None,
+    };
+
+    let result = cx.open_window(options, |window, cx| {
+        window.activate_window();
+        let view = cx.new(|cx| CommandPalette::new(commands, window, cx));
+        view.read(cx).focus_handle.focus(window);
+        view
+    });
+
+    match result {
+        Ok(handle) => {
+            let mut guard = PALETTE_WINDOW.lock().unwrap();
+            *guard = Some(handle.into());
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to open command palette");
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score_subsequence() {
+        assert!(fuzzy_score("rcl", "Refresh Claude").is_some());
+        assert!(fuzzy_score("REFRESH", "Refresh Claude").is_some());
+        assert!(fuzzy_score("ref cla", "Refresh Claude").is_some());
+        assert!(fuzzy_score("clr", "Refresh Claude").is_none());
+        assert_eq!(fuzzy_score("", "Quit ExactoBar"), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts() {
+        let word_start = fuzzy_score("cl", "Refresh Claude").unwrap();
+        let mid_word = fuzzy_score("cl", "Enable Cursor Locally").unwrap();
+        let scattered = fuzzy_score("cl", "Open Settings: Advanced Local").unwrap();
+        assert!(word_start >= mid_word);
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_rank() {
+        let items = labels(&[
+            "Refresh All",
+            "Switch to Claude",
+            "Refresh Claude",
+            "Open Settings: General",
+        ]);
+
+        assert_eq!(rank("", &items), vec![0, 1, 2, 3]);
+        assert_eq!(rank("refcl", &items), vec![2]);
+        assert_eq!(rank("claude", &items), vec![1, 2]);
+        assert!(rank("xyz", &items).is_empty());
+    }
+}
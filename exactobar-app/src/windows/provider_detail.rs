@@ -0,0 +1,523 @@
+//! Provider detail window.
+//!
+//! Everything known about one provider: the account and plan, every usage
+//! window, credits, token cost, and the latest fetch attempts with their
+//! errors, plus buttons to refresh, re-authenticate and open the
+//! provider's dashboard. The window follows the usage model, so it
+//! updates as refreshes finish.
+
+use chrono::{Datelike, Local, Utc};
+use exactobar_core::{ProviderKind, ResetZone, UsageSnapshot};
+use exactobar_providers::ProviderRegistry;
+use exactobar_providers::claude::WeeklySurfaceSplit;
+use gpui::prelude::*;
+use gpui::*;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+use crate::actions::AppCommand;
+use crate::components::{ProviderIcon, Spinner};
+use crate::menu::{UsageMetricsSection, open_url};
+use crate::state::{AppState, FetchAttempt, MAX_FETCH_ATTEMPTS};
+use crate::theme;
+
+/// Global handle to the provider detail window (if open).
+static DETAIL_WINDOW: Mutex<Option<AnyWindowHandle>> = Mutex::new(None);
+
+// ============================================================================
+// Provider Detail Window
+// ============================================================================
+
+/// The provider detail window content.
+pub struct ProviderDetailWindow {
+    provider: ProviderKind,
+    /// Re-renders when usage changes.
+    _subscription: Subscription,
+}
+
+impl ProviderDetailWindow {
+    fn new(provider: ProviderKind, cx: &mut Context<Self>) -> Self {
+        let usage = cx.global::<AppState>().usage.clone();
+        Self {
+            provider,
+            _subscription: cx.observe(&usage, |_this, _model, cx| cx.notify()),
+        }
+    }
+
+    fn render_header(&self, refreshing: bool) -> Div {
+        let provider = self.provider;
+        let dashboard_url =
+            ProviderRegistry::get(provider).and_then(|d| d.metadata.dashboard_url.clone());
+
+        let mut actions = div()
+            .flex()
+            .gap(px(6.))
+            .child(
+                action_button("detail-refresh", "Refresh")
+                    .when(refreshing, |el| el.opacity(0.5))
+                    .on_click(move |_, _window, cx| {
+                        if !cx.global::<AppState>().is_provider_refreshing(provider, cx) {
+                            AppCommand::Refresh(provider).run(cx);
+                        }
+                    }),
+            )
+            .child(
+                action_button("detail-reauth", "Re-authenticate…")
+                    .on_click(move |_, _window, cx| super::open_setting(provider.cli_name(), cx)),
+            );
+        if let Some(url) = dashboard_url {
+            actions = actions.child(
+                action_button("detail-dashboard", "Open Dashboard")
+                    .on_click(move |_, _window, _cx| open_url(&url)),
+            );
+        }
+
+        div()
+            .px(px(16.))
+            .py(px(12.))
+            .border_b_1()
+            .border_color(theme::border())
+            .flex()
+            .items_center()
+            .justify_between()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.))
+                    .child(ProviderIcon::new(provider).size(px(22.)))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(provider.display_name().to_string()),
+                    )
+                    .when(refreshing, |el| el.child(Spinner::new())),
+            )
+            .child(actions)
+    }
+}
+
+impl Render for ProviderDetailWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let provider = self.provider;
+        let state = cx.global::<AppState>();
+        let snapshot = state.get_snapshot(provider, cx);
+        let error = state.get_error(provider, cx);
+        let refreshing = state.is_provider_refreshing(provider, cx);
+        let cost = state.get_cost(provider, cx);
+        let attempts = state.usage.read(cx).fetch_attempts(provider);
+        let settings = state.settings.read(cx).settings().clone();
+        let descriptor = ProviderRegistry::get(provider);
+        let weekly_split = state
+            .usage
+            .read(cx)
+            .claude_weekly_split()
+            .filter(|_| provider == ProviderKind::Claude && settings.claude_web_extras_enabled);
+
+        // Identity
+        let identity = snapshot.as_ref().and_then(|s| s.identity.as_ref());
+        let mut identity_section = Section::new("Account");
+        if let Some(label) = identity.and_then(|i| settings.account_label(i)) {
+            identity_section = identity_section.row("Label", label.name.clone());
+        }
+        identity_section = identity_section
+            .row(
+                "Email",
+                identity
+                    .and_then(|i| i.account_email.clone())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+            )
+            .optional_row(
+                "Organization",
+                identity.and_then(|i| i.account_organization.clone()),
+            )
+            .optional_row(
+                "Signed in with",
+                identity
+                    .and_then(|i| i.login_method)
+                    .map(|m| m.label().to_string()),
+            )
+            .row(
+                "Plan",
+                identity
+                    .and_then(|i| i.plan_name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+            );
+
+        // Usage windows
+        let usage_section = match &snapshot {
+            Some(snapshot) => {
+                let session_label =
+                    descriptor.map_or("Session", |d| d.metadata.session_label.as_str());
+                let weekly_label =
+                    descriptor.map_or("Weekly", |d| d.metadata.weekly_label.as_str());
+                let updated = snapshot
+                    .updated_at
+                    .with_timezone(&Local)
+                    .format("%b %-d, %H:%M:%S")
+                    .to_string();
+                Section::new("Usage")
+                    .child(UsageMetricsSection::new(
+                        snapshot,
+                        session_label,
+                        weekly_label,
+                        Some("Search"),
+                        settings.usage_bars_show_used,
+                        settings.reset_times_show_absolute,
+                        ResetZone::from_setting(settings.reset_timezone.as_deref()),
+                    ))
+                    .rows(model_rows(snapshot))
+                    .rows(split_rows(weekly_split))
+                    .row("Source", snapshot.fetch_source.label().to_string())
+                    .row("Updated", updated)
+            }
+            None => Section::new("Usage").note("No usage fetched yet."),
+        };
+
+        // Credits
+        let credits_section = descriptor
+            .map(|d| &d.metadata)
+            .filter(|m| m.supports_credits)
+            .map(|metadata| {
+                let section = Section::new("Credits").note(if metadata.credits_hint.is_empty() {
+                    "Credit balances aren't reported to ExactoBar.".to_string()
+                } else {
+                    format!("{} aren't reported to ExactoBar.", metadata.credits_hint)
+                });
+                match metadata.subscription_dashboard_url.clone() {
+                    Some(url) => section.child(
+                        action_button("detail-buy-credits", "Buy Credits…")
+                            .on_click(move |_, _window, _cx| open_url(&url)),
+                    ),
+                    None => section,
+                }
+            });
+
+        // Cost
+        let cost_section = crate::cost::supports_cost(provider).then(|| {
+            let section = Section::new("Cost");
+            if !settings.cost_usage_enabled {
+                return section.note("Turn on Cost Tracking in Advanced settings to see spend.");
+            }
+            match &cost {
+                Some(cost) => {
+                    let today = Utc::now().date_naive();
+                    let month_start = today.with_day(1).unwrap_or(today);
+                    section
+                        .row("Today", format!("${:.2}", cost.cost_on(today)))
+                        .row(
+                            "This month",
+                            format!("${:.2}", cost.cost_since(month_start)),
+                        )
+                        .row("Last 30 days", format!("${:.2}", cost.total_cost_usd))
+                }
+                None => section.note("No cost scanned yet."),
+            }
+        });
+
+        // Fetch attempts
+        let mut attempts_section = Section::new(format!("Last {MAX_FETCH_ATTEMPTS} Fetches"));
+        if let Some(error) = &error {
+            attempts_section = attempts_section.child(
+                div()
+                    .text_xs()
+                    .text_color(theme::error())
+                    .child(error.lines().next().unwrap_or_default().to_string()),
+            );
+        }
+        attempts_section = if attempts.is_empty() {
+            attempts_section.note("No fetches since ExactoBar started.")
+        } else {
+            attempts_section.children(attempts.into_iter().map(AttemptRow))
+        };
+
+        div()
+            .size_full()
+            .bg(theme::surface_background())
+            .text_color(theme::text_primary())
+            .flex()
+            .flex_col()
+            .child(self.render_header(refreshing))
+            .child(
+                div()
+                    .id("provider-detail-sections")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .p(px(16.))
+                    .flex()
+                    .flex_col()
+                    .gap(px(12.))
+                    .child(identity_section)
+                    .child(usage_section)
+                    .children(credits_section)
+                    .children(cost_section)
+                    .child(attempts_section),
+            )
+    }
+}
+
+/// Opens the detail window on a provider, or switches the open one to it.
+pub fn open_provider_detail(provider: ProviderKind, cx: &mut App) {
+    let existing = *DETAIL_WINDOW.lock().unwrap();
+    if let Some(handle) = existing.and_then(|h| h.downcast::<ProviderDetailWindow>()) {
+        if handle
+            .update(cx, |view, window, cx| {
+                view.provider = provider;
+                window.set_window_title(&title(provider));
+                window.activate_window();
+                cx.notify();
+            })
+            .is_ok()
+        {
+            info!(provider = ?provider, "Focused existing provider detail window");
+            cx.activate(true);
+            return;
+        }
+        // Window was closed, continue to create new one
+    }
+
+    info!(provider = ?provider, "Opening provider detail window");
+    cx.activate(true);
+
+    let bounds = Bounds::centered(None, size(px(520.0), px(640.0)), cx);
+
+    let options = WindowOptions {
+        titlebar: Some(TitlebarOptions {
+            title: Some(title(provider).into()),
+            appears_transparent: false,
+            traffic_light_position: None,
+        }),
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        focus: true,
+        show: true,
+        kind: WindowKind::Normal,
+        is_movable: true,
+        display_id: None,
+        window_background: WindowBackgroundAppearance::Opaque,
+        app_id: None,
+        window_min_size: Some(size(px(420.0), px(360.0))),
+        window_decorations: None,
+        is_minimizable: true,
+        is_resizable: true,
+        tabbing_identifier: None,
+    };
+
+    let result = cx.open_window(options, |window, cx| {
+        window.activate_window();
+        cx.new(|cx| ProviderDetailWindow::new(provider, cx))
+    });
+
+    match result {
+        Ok(handle) => {
+            let mut guard = DETAIL_WINDOW.lock().unwrap();
+            *guard = Some(handle.into());
+        }
+        Err(e) => {
+            error!(error = ?e, "Failed to open provider detail window");
+        }
+    }
+}
+
+fn title(provider: ProviderKind) -> String {
+    format!("{} Details", provider.display_name())
+}
+
+/// One row per model with its own quota.
+fn model_rows(snapshot: &UsageSnapshot) -> Vec<(String, String)> {
+    snapshot
+        .models
+        .iter()
+        .map(|m| {
+            (
+                m.model.clone(),
+                format!("{:.0}% used", m.window.clamped_used_percent()),
+            )
+        })
+        .collect()
+}
+
+/// Rows for Claude's weekly window split by surface.
+fn split_rows(split: Option<WeeklySurfaceSplit>) -> Vec<(String, String)> {
+    split
+        .map(|split| {
+            vec![
+                (
+                    "Weekly · Claude Code".to_string(),
+                    format!("{:.0}% used", split.code_percent),
+                ),
+                (
+                    "Weekly · Web".to_string(),
+                    format!("{:.0}% used", split.web_percent),
+                ),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+fn action_button(id: &'static str, label: &'static str) -> Stateful<Div> {
+    div()
+        .id(id)
+        .px(px(10.))
+        .py(px(4.))
+        .rounded(px(6.))
+        .cursor_pointer()
+        .text_sm()
+        .bg(theme::surface())
+        .border_1()
+        .border_color(theme::border())
+        .hover(|s| s.bg(theme::hover()))
+        .active(|s| s.bg(theme::active()))
+        .child(label)
+}
+
+// ============================================================================
+// Section
+// ============================================================================
+
+/// A titled group of label/value rows.
+struct Section {
+    body: Div,
+}
+
+impl Section {
+    fn new(title: impl Into<SharedString>) -> Self {
+        Self {
+            body: div()
+                .p(px(12.))
+                .rounded(px(8.))
+                .bg(theme::card_background())
+                .border_1()
+                .border_color(theme::glass_separator())
+                .flex()
+                .flex_col()
+                .gap(px(6.))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(title.into()),
+                ),
+        }
+    }
+
+    fn row(self, label: impl Into<SharedString>, value: impl Into<SharedString>) -> Self {
+        self.child(
+            div()
+                .flex()
+                .justify_between()
+                .gap(px(12.))
+                .text_sm()
+                .child(div().text_color(theme::muted()).child(label.into()))
+                .child(value.into()),
+        )
+    }
+
+    fn optional_row(self, label: &'static str, value: Option<String>) -> Self {
+        match value {
+            Some(value) => self.row(label, value),
+            None => self,
+        }
+    }
+
+    fn rows(self, rows: Vec<(String, String)>) -> Self {
+        rows.into_iter()
+            .fold(self, |section, (label, value)| section.row(label, value))
+    }
+
+    fn note(self, text: impl Into<SharedString>) -> Self {
+        self.child(
+            div()
+                .text_sm()
+                .text_color(theme::muted())
+                .child(text.into()),
+        )
+    }
+
+    fn child(mut self, child: impl IntoElement) -> Self {
+        self.body = self.body.child(child);
+        self
+    }
+
+    fn children(mut self, children: impl IntoIterator<Item = impl IntoElement>) -> Self {
+        self.body = self.body.children(children);
+        self
+    }
+}
+
+impl IntoElement for Section {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        self.body
+    }
+}
+
+// ============================================================================
+// Attempt Row
+// ============================================================================
+
+/// A fetch attempt: when, how long, and where usage came from or why it
+/// failed.
+struct AttemptRow(FetchAttempt);
+
+impl IntoElement for AttemptRow {
+    type Element = Div;
+
+    fn into_element(self) -> Self::Element {
+        let attempt = self.0;
+        let time = attempt
+            .started_at
+            .with_timezone(&Local)
+            .format("%H:%M:%S")
+            .to_string();
+        let duration = format!(
+            "{:.1}s",
+            attempt.duration.num_milliseconds() as f64 / 1000.0
+        );
+
+        let (mark, color, summary, details) = match attempt.result {
+            Ok(source) => (
+                "✓",
+                theme::success(),
+                format!("via {}", source.label()),
+                None,
+            ),
+            Err(error) => {
+                let mut lines = error.lines();
+                let summary = lines.next().unwrap_or_default().to_string();
+                let details = lines.collect::<Vec<_>>().join("\n");
+                (
+                    "✗",
+                    theme::error(),
+                    summary,
+                    Some(details).filter(|d| !d.trim().is_empty()),
+                )
+            }
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.))
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .gap(px(8.))
+                    .child(div().text_color(color).child(mark))
+                    .child(div().text_color(theme::muted()).child(time))
+                    .child(div().text_color(theme::muted()).child(duration))
+                    .child(div().flex_1().child(summary)),
+            )
+            .when_some(details, |el, details| {
+                el.child(
+                    div()
+                        .pl(px(20.))
+                        .text_xs()
+                        .text_color(theme::text_secondary())
+                        .child(details),
+                )
+            })
+    }
+}
@@ -1,39 +1,174 @@
 //! Advanced settings pane.
 
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::keychain_preflight;
+use exactobar_fetch::{FetchKind, KeychainAccess, KeychainItem, ParseMode, RequestBudget};
+use exactobar_providers::ProviderRegistry;
+use exactobar_store::keychain::providers as key_names;
+use exactobar_store::{AlertLevel, FailureTolerance, NotificationRule, SecretBackend};
 use gpui::*;
+use std::path::PathBuf;
 
 use super::SettingsTheme;
+use super::api_keys::{self, KeyAuditState};
+use super::providers::ordered_strategies;
+use super::search::Searchable;
 use crate::components::Toggle;
+use crate::notifications::{SYSTEM_SOUNDS, play_sound, send_test_notification};
 use crate::state::AppState;
 
 /// Advanced settings pane.
 pub struct AdvancedPane {
     debug_mode: bool,
+    redact_emails_in_logs: bool,
+    strict_parsing: bool,
     auto_refresh_on_wake: bool,
     status_checks_enabled: bool,
     session_quota_notifications_enabled: bool,
+    reset_notifications_enabled: bool,
+    notification_rules: Vec<NotificationRule>,
+    failure_tolerance: FailureTolerance,
+    quiet_during_meetings: bool,
+    respect_focus: bool,
+    /// Enabled providers and whether their critical alerts break Focus.
+    focus_overrides: Vec<(ProviderKind, String, bool)>,
     cost_usage_enabled: bool,
+    team_leaderboard_enabled: bool,
     random_blink_enabled: bool,
     claude_web_extras_enabled: bool,
     show_optional_credits_and_extra_usage: bool,
     openai_web_access_enabled: bool,
+    companion_endpoint_enabled: bool,
+    companion_endpoint_port: u16,
+    companion_token: Option<String>,
+    /// Whether credential changes are blocked (locked mode).
+    locked: bool,
+    keychain_items: Vec<(KeychainItem, Option<KeychainAccess>)>,
+    /// Stored API keys and their last checks.
+    key_audit: KeyAuditState,
+    /// Providers that are enabled, for what keys in the environment could
+    /// enable.
+    enabled_providers: Vec<ProviderKind>,
+    /// Enabled providers with API keys, and the password manager each
+    /// one's keys are read from.
+    secret_backends: Vec<(ProviderKind, Option<SecretBackend>)>,
+    strategy_orders: Vec<StrategyOrder>,
+    /// Most requests per hour for all providers together.
+    request_budget: Option<u32>,
+    /// Enabled providers' request limits and what they sent.
+    request_budgets: Vec<ProviderRequests>,
     theme: SettingsTheme,
 }
 
+/// An enabled provider's request limit and the requests it sent in the
+/// last hour.
+struct ProviderRequests {
+    provider: ProviderKind,
+    name: String,
+    per_hour: Option<u32>,
+    sent: u32,
+}
+
+/// The order an enabled provider's fetch strategies are tried in.
+struct StrategyOrder {
+    provider: ProviderKind,
+    name: String,
+    /// Strategy IDs and kinds, first tried first.
+    strategies: Vec<(String, FetchKind)>,
+    /// Whether the order was changed from the built-in one.
+    customized: bool,
+}
+
 impl AdvancedPane {
     pub fn new<V: 'static>(cx: &Context<V>, theme: SettingsTheme) -> Self {
         let state = cx.global::<AppState>();
-        let settings = state.settings.read(cx).settings();
+        let model = state.settings.read(cx);
+        let settings = model.settings();
+        let keychain_items = keychain_preflight::required_items()
+            .into_iter()
+            .map(|item| {
+                let access = model.keychain_access(item.browser).or_else(|| {
+                    keychain_preflight::is_denied(item.browser).then_some(KeychainAccess::Denied)
+                });
+                (item, access)
+            })
+            .collect();
+        let strategy_orders = ProviderRegistry::all()
+            .iter()
+            .filter(|desc| model.is_provider_enabled(desc.id))
+            .filter_map(|desc| {
+                let strategy_settings = model.strategies(desc.id);
+                let strategies: Vec<_> = ordered_strategies(desc.id, &strategy_settings)
+                    .into_iter()
+                    .map(|s| (s.id.clone(), s.kind))
+                    .collect();
+                (strategies.len() > 1).then(|| StrategyOrder {
+                    provider: desc.id,
+                    name: desc.display_name().to_string(),
+                    strategies,
+                    customized: !strategy_settings.priorities.is_empty(),
+                })
+            })
+            .collect();
+        let focus_overrides = ProviderRegistry::all()
+            .iter()
+            .filter(|desc| model.is_provider_enabled(desc.id))
+            .map(|desc| {
+                (
+                    desc.id,
+                    desc.display_name().to_string(),
+                    settings.focus_overrides.contains(&desc.id),
+                )
+            })
+            .collect();
+        let sent = RequestBudget::shared().counts();
+        let request_budgets = ProviderRegistry::all()
+            .iter()
+            .filter(|desc| model.is_provider_enabled(desc.id))
+            .map(|desc| ProviderRequests {
+                provider: desc.id,
+                name: desc.display_name().to_string(),
+                per_hour: model.request_budget(desc.id).per_hour,
+                sent: sent.get(&desc.id).copied().unwrap_or(0),
+            })
+            .collect();
         Self {
             debug_mode: settings.debug_mode,
+            redact_emails_in_logs: settings.redact_emails_in_logs,
+            strict_parsing: settings.parse_mode.is_strict(),
             auto_refresh_on_wake: settings.auto_refresh_on_wake,
             status_checks_enabled: settings.status_checks_enabled,
             session_quota_notifications_enabled: settings.session_quota_notifications_enabled,
+            reset_notifications_enabled: settings.reset_notifications_enabled,
+            notification_rules: settings.notification_rules.clone(),
+            failure_tolerance: settings.failure_tolerance,
+            quiet_during_meetings: settings.quiet_during_meetings,
+            respect_focus: settings.respect_focus,
+            focus_overrides,
             cost_usage_enabled: settings.cost_usage_enabled,
+            team_leaderboard_enabled: settings.team_leaderboard_enabled,
             random_blink_enabled: settings.random_blink_enabled,
             claude_web_extras_enabled: settings.claude_web_extras_enabled,
             show_optional_credits_and_extra_usage: settings.show_optional_credits_and_extra_usage,
             openai_web_access_enabled: settings.openai_web_access_enabled,
+            companion_endpoint_enabled: settings.companion_endpoint_enabled,
+            companion_endpoint_port: settings.companion_endpoint_port,
+            companion_token: model.companion_token(),
+            locked: model.is_locked(),
+            keychain_items,
+            key_audit: api_keys::current(cx),
+            enabled_providers: model.enabled_providers(),
+            secret_backends: ProviderRegistry::all()
+                .iter()
+                .filter(|desc| {
+                    model.is_provider_enabled(desc.id)
+                        && !key_names::for_provider(desc.id).is_empty()
+                })
+                .map(|desc| (desc.id, model.secret_backend(desc.id)))
+                .collect(),
+            strategy_orders,
+            request_budget: settings.request_budget_per_hour,
+            request_budgets,
             theme,
         }
     }
@@ -46,6 +181,12 @@ impl IntoElement for AdvancedPane {
         let config_dir = exactobar_store::default_config_dir();
         let cache_dir = exactobar_store::default_cache_dir();
         let theme = self.theme;
+        let hover_bg = theme.hover;
+        let companion_details = self
+            .companion_token
+            .clone()
+            .filter(|_| self.companion_endpoint_enabled && !self.locked);
+        let locked = self.locked;
 
         div()
             .w_full()
@@ -74,6 +215,7 @@ impl IntoElement for AdvancedPane {
             // Debug Mode
             .child(
                 div()
+                    .searchable("debug_mode", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -110,9 +252,95 @@ impl IntoElement for AdvancedPane {
                             }),
                     ),
             )
+            // Redact Emails in Logs
+            .child(
+                div()
+                    .searchable("redact_emails", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Hide Emails in Logs"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Tokens and cookies are always redacted from logs"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-redact-emails")
+                            .checked(self.redact_emails_in_logs)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_redact_emails_in_logs(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
+            // Strict Parsing
+            .child(
+                div()
+                    .searchable("strict_parsing", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Strict Parsing"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Fail on unrecognized provider responses and log why"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-strict-parsing")
+                            .checked(self.strict_parsing)
+                            .on_toggle(|enabled, cx| {
+                                let mode = if enabled {
+                                    ParseMode::Strict
+                                } else {
+                                    ParseMode::Lenient
+                                };
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_parse_mode(mode);
+                                    });
+                                });
+                            }),
+                    ),
+            )
             // Auto-refresh on Wake
             .child(
                 div()
+                    .searchable("auto_refresh_on_wake", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -152,6 +380,7 @@ impl IntoElement for AdvancedPane {
             // Status Page Checks
             .child(
                 div()
+                    .searchable("status_checks", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -191,6 +420,7 @@ impl IntoElement for AdvancedPane {
             // Quota Notifications
             .child(
                 div()
+                    .searchable("quota_notifications", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -227,9 +457,194 @@ impl IntoElement for AdvancedPane {
                             }),
                     ),
             )
+            // Reset Notifications
+            .child(
+                div()
+                    .searchable("reset_notifications", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Reset Notifications"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Notify when a quota window resets"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-reset-notifications")
+                            .checked(self.reset_notifications_enabled)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_reset_notifications_enabled(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
+            // Notification Sounds
+            .when(!self.notification_rules.is_empty(), |el| {
+                el.child(
+                    div()
+                        .searchable("notification_sounds", theme)
+                        .py(px(12.0))
+                        .border_b_1()
+                        .border_color(theme.border)
+                        .flex()
+                        .flex_col()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(2.0))
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .child("Notification Sounds"),
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.text_muted)
+                                        .child("Sound played with each rule's notifications"),
+                                ),
+                        )
+                        .children(
+                            self.notification_rules
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, rule)| render_notification_sound_row(index, rule, theme)),
+                        ),
+                )
+            })
+            // Failure Tolerance
+            .child(render_failure_tolerance(self.failure_tolerance, theme))
+            // Quiet During Meetings
+            .child(
+                div()
+                    .searchable("quiet_during_meetings", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Quiet During Meetings"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Hold alerts and slow refreshes during busy calendar events"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-quiet-during-meetings")
+                            .checked(self.quiet_during_meetings)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_quiet_during_meetings(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
+            // Respect Focus
+            .child(
+                div()
+                    .searchable("respect_focus", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Respect Focus"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Hold non-critical alerts while a Focus is on"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-respect-focus")
+                            .checked(self.respect_focus)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_respect_focus(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
+            .when(self.respect_focus && !self.focus_overrides.is_empty(), |el| {
+                el.child(
+                    div()
+                        .pl(px(16.0))
+                        .py(px(8.0))
+                        .border_b_1()
+                        .border_color(theme.border)
+                        .flex()
+                        .flex_col()
+                        .gap(px(4.0))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("Critical alerts that break through Focus"),
+                        )
+                        .children(
+                            self.focus_overrides
+                                .into_iter()
+                                .map(|(provider, name, enabled)| {
+                                    render_focus_override_row(provider, name, enabled)
+                                }),
+                        ),
+                )
+            })
             // Cost Tracking
             .child(
                 div()
+                    .searchable("cost_tracking", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -266,9 +681,50 @@ impl IntoElement for AdvancedPane {
                             }),
                     ),
             )
+            // Team Leaderboard
+            .child(
+                div()
+                    .searchable("team_leaderboard", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Team Leaderboard"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Rank your team's Cursor or Claude usage (needs an admin key)"),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-team-leaderboard")
+                            .checked(self.team_leaderboard_enabled)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_team_leaderboard_enabled(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
             // Random Blink
             .child(
                 div()
+                    .searchable("random_blink", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -308,6 +764,7 @@ impl IntoElement for AdvancedPane {
             // Claude Web Extras
             .child(
                 div()
+                    .searchable("claude_web_extras", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -329,7 +786,7 @@ impl IntoElement for AdvancedPane {
                                 div()
                                     .text_xs()
                                     .text_color(theme.text_muted)
-                                    .child("Fetch extra Claude usage via browser cookies"),
+                                    .child("Split Claude's weekly usage between Claude Code and the web via browser cookies"),
                             ),
                     )
                     .child(
@@ -347,6 +804,7 @@ impl IntoElement for AdvancedPane {
             // Show Credits & Extras
             .child(
                 div()
+                    .searchable("show_credits_extras", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -386,6 +844,7 @@ impl IntoElement for AdvancedPane {
             // OpenAI Web Access
             .child(
                 div()
+                    .searchable("openai_web_access", theme)
                     .flex()
                     .items_center()
                     .justify_between()
@@ -422,9 +881,262 @@ impl IntoElement for AdvancedPane {
                             }),
                     ),
             )
+            // Companion Extension
+            .child(
+                div()
+                    .searchable("companion_extension", theme)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .py(px(12.0))
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child("Companion Extension"),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child(if locked {
+                                        "Disabled in locked mode"
+                                    } else {
+                                        "Accept cookies pushed by the browser extension"
+                                    }),
+                            ),
+                    )
+                    .child(
+                        Toggle::new("toggle-companion-endpoint")
+                            .checked(self.companion_endpoint_enabled && !locked)
+                            .disabled(locked)
+                            .on_toggle(|enabled, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_companion_endpoint_enabled(enabled);
+                                    });
+                                });
+                            }),
+                    ),
+            )
+            .when_some(companion_details, |el, token| {
+                el.child(
+                    div()
+                        .p(px(12.0))
+                        .rounded(px(8.0))
+                        .bg(theme.code_bg)
+                        .flex()
+                        .flex_col()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(2.0))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.text_muted)
+                                        .child("Endpoint"),
+                                )
+                                .child(div().text_xs().font_family("monospace").child(format!(
+                                    "http://127.0.0.1:{}/v1/cookies",
+                                    self.companion_endpoint_port
+                                ))),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(2.0))
+                                .child(div().text_xs().text_color(theme.text_muted).child("Token"))
+                                .child(div().text_xs().font_family("monospace").child(token)),
+                        )
+                        .child(
+                            div()
+                                .px(px(10.0))
+                                .py(px(4.0))
+                                .rounded(px(6.0))
+                                .border_1()
+                                .border_color(theme.border)
+                                .text_xs()
+                                .cursor_pointer()
+                                .hover(move |s| s.bg(hover_bg))
+                                .on_mouse_down(MouseButton::Left, |_, _window, cx| {
+                                    let settings = cx.global::<AppState>().settings.clone();
+                                    settings.update(cx, |model, cx| {
+                                        model.regenerate_companion_token();
+                                        cx.notify();
+                                    });
+                                })
+                                .child("Regenerate Token"),
+                        ),
+                )
+            })
+            // Strategy Order section
+            .when(!self.strategy_orders.is_empty(), |el| {
+                el.child(
+                    div()
+                        .searchable("strategy_order", theme)
+                        .mt(px(12.0))
+                        .flex()
+                        .flex_col()
+                        .gap(px(12.0))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .text_base()
+                                        .font_weight(FontWeight::SEMIBOLD)
+                                        .child("Strategy Order"),
+                                )
+                                .child(div().text_xs().text_color(theme.text_muted).child(
+                                    "Each provider tries its strategies from top to bottom \
+                                     until one returns usage. Move one up to prefer it, like \
+                                     the CLI over OAuth.",
+                                )),
+                        )
+                        .children(
+                            self.strategy_orders
+                                .into_iter()
+                                .map(move |order| render_strategy_order_row(order, theme)),
+                        ),
+                )
+            })
+            // Request Budget section
+            .child(render_request_budget(
+                self.request_budget,
+                self.request_budgets,
+                theme,
+            ))
+            // Keychain Access section
+            .child(
+                div()
+                    .searchable("keychain_access", theme)
+                    .mt(px(12.0))
+                    .flex()
+                    .flex_col()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(4.0))
+                            .child(
+                                div()
+                                    .text_base()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child("Keychain Access"),
+                            )
+                            .child(div().text_xs().text_color(theme.text_muted).child(
+                                "Chromium-based browsers encrypt cookies with a key stored in \
+                                 the keychain. Reading it shows a system prompt once per \
+                                 browser. If you deny it, ExactoBar won't ask again until \
+                                 you test access here.",
+                            )),
+                    )
+                    .when(self.keychain_items.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("No Chromium-based browsers detected - no prompts needed"),
+                        )
+                    })
+                    .children(self.keychain_items.into_iter().map(move |(item, access)| {
+                        render_keychain_row(item, access, locked, theme)
+                    })),
+            )
+            // Stored API Keys section
+            .child(
+                div()
+                    .searchable("stored_api_keys", theme)
+                    .mt(px(12.0))
+                    .flex()
+                    .flex_col()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .flex()
+                            .items_start()
+                            .justify_between()
+                            .gap(px(12.0))
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap(px(4.0))
+                                    .child(
+                                        div()
+                                            .text_base()
+                                            .font_weight(FontWeight::SEMIBOLD)
+                                            .child("Stored API Keys"),
+                                    )
+                                    .child(div().text_xs().text_color(theme.text_muted).child(
+                                        "API keys ExactoBar keeps in the keychain. Checking \
+                                         fetches from each key's provider; rotate keys that \
+                                         are old or no longer accepted. Keys in environment \
+                                         variables can be imported.",
+                                    )),
+                            )
+                            .child(api_keys::render_check_button(&self.key_audit, theme)),
+                    )
+                    .children(api_keys::render_key_rows(
+                        self.key_audit.clone(),
+                        locked,
+                        theme,
+                    ))
+                    .children(api_keys::render_env_rows(
+                        &self.key_audit,
+                        &self.enabled_providers,
+                        locked,
+                        theme,
+                    )),
+            )
+            // Password Managers section
+            .child(
+                div()
+                    .searchable("secret_backends", theme)
+                    .mt(px(12.0))
+                    .flex()
+                    .flex_col()
+                    .gap(px(4.0))
+                    .child(
+                        div()
+                            .text_base()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("Password Managers"),
+                    )
+                    .child(div().text_xs().text_color(theme.text_muted).child(
+                        "Read a provider's API keys from 1Password (op) or Bitwarden (bw) \
+                         each time they're needed instead of keeping them in the keychain.",
+                    ))
+                    .when(self.secret_backends.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("No enabled providers use API keys"),
+                        )
+                    })
+                    .children(self.secret_backends.into_iter().map(move |(provider, backend)| {
+                        api_keys::render_secret_backend_row(provider, backend, locked, theme)
+                    })),
+            )
             // Paths section
             .child(
                 div()
+                    .searchable("data_locations", theme)
                     .mt(px(12.0))
                     .flex()
                     .flex_col()
@@ -435,6 +1147,7 @@ impl IntoElement for AdvancedPane {
                             .font_weight(FontWeight::SEMIBOLD)
                             .child("Data Locations"),
                     )
+                    .child(render_data_dir_row(theme))
                     .child(
                         div()
                             .p(px(12.0))
@@ -483,3 +1196,613 @@ impl IntoElement for AdvancedPane {
             )
     }
 }
+
+/// Renders the data directory choice, with buttons to move to another
+/// directory or back to the default one.
+fn render_data_dir_row(theme: SettingsTheme) -> Div {
+    let hover_bg = theme.hover;
+    let from_env = exactobar_store::data_dir_from_env().is_some();
+    let custom = exactobar_store::custom_data_dir();
+    let description = match (&custom, from_env) {
+        (Some(dir), true) => format!(
+            "{} (set by {})",
+            dir.display(),
+            exactobar_store::DATA_DIR_ENV
+        ),
+        (Some(dir), false) => dir.display().to_string(),
+        (None, _) => "Default locations below. Choose a folder in iCloud Drive or Dropbox \
+                      to sync settings and history across machines."
+            .to_string(),
+    };
+    let button = |id: &'static str, label: &'static str| {
+        div()
+            .id(id)
+            .px(px(10.0))
+            .py(px(4.0))
+            .rounded(px(6.0))
+            .border_1()
+            .border_color(theme.border)
+            .text_xs()
+            .cursor_pointer()
+            .hover(move |s| s.bg(hover_bg))
+            .child(label)
+    };
+
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .gap(px(12.0))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child("Data Directory"),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child(description),
+                ),
+        )
+        .when(!from_env, |el| {
+            el.child(
+                div()
+                    .flex()
+                    .gap(px(6.0))
+                    .child(
+                        button("data-dir-choose", "Choose…").on_click(|_, _window, cx| {
+                            cx.spawn(async move |mut cx| {
+                                if let Some(dir) = smol::unblock(prompt_for_data_dir).await {
+                                    let _ = cx.update_global::<AppState, _>(|state, cx| {
+                                        state.relocate_data_dir(Some(dir), cx);
+                                    });
+                                }
+                            })
+                            .detach();
+                        }),
+                    )
+                    .when(custom.is_some(), |el| {
+                        el.child(button("data-dir-reset", "Use Default").on_click(
+                            |_, _window, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.relocate_data_dir(None, cx);
+                                });
+                            },
+                        ))
+                    }),
+            )
+        })
+}
+
+/// Asks for a data directory using osascript (native macOS folder picker).
+///
+/// Returns `None` if cancelled.
+fn prompt_for_data_dir() -> Option<PathBuf> {
+    let script = r#"POSIX path of (choose folder with prompt "Choose where ExactoBar keeps its settings and history:")"#;
+
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Renders a provider's strategies in order, with buttons to move each one
+/// up or down and to go back to the built-in order.
+fn render_focus_override_row(provider: ProviderKind, name: String, enabled: bool) -> Div {
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .py(px(4.0))
+        .child(div().text_sm().child(name))
+        .child(
+            Toggle::new(SharedString::from(format!(
+                "toggle-focus-override-{}",
+                provider.cli_name()
+            )))
+            .checked(enabled)
+            .on_toggle(move |enabled, cx| {
+                cx.update_global::<AppState, _>(|state, cx| {
+                    state.settings.update(cx, |model, _| {
+                        model.set_focus_override(provider, enabled);
+                    });
+                });
+            }),
+        )
+}
+
+/// Describes what a notification rule fires on, e.g. "Critical alerts
+/// for Claude".
+fn rule_summary(rule: &NotificationRule) -> String {
+    let trigger = if rule.events.is_empty() {
+        match rule.level {
+            AlertLevel::Warning => "Quota alerts".to_string(),
+            AlertLevel::Critical => "Critical alerts".to_string(),
+        }
+    } else {
+        let events: Vec<String> = rule
+            .events
+            .iter()
+            .map(|event| event.as_str().replace('_', " "))
+            .collect();
+        format!("On {}", events.join(", "))
+    };
+    if rule.providers.is_empty() {
+        format!("{} for all providers", trigger)
+    } else {
+        let names: Vec<&str> = rule.providers.iter().map(|p| p.display_name()).collect();
+        format!("{} for {}", trigger, names.join(", "))
+    }
+}
+
+/// Renders a notification rule's sound choices, previewing each one when
+/// picked, and a button that sends a test notification through the rule.
+fn render_notification_sound_row(
+    index: usize,
+    rule: NotificationRule,
+    theme: SettingsTheme,
+) -> Div {
+    let hover_bg = theme.hover;
+    let options = std::iter::once(None).chain(SYSTEM_SOUNDS.iter().map(|&name| Some(name)));
+    let current = rule.sound.clone();
+
+    div()
+        .pl(px(16.0))
+        .flex()
+        .flex_col()
+        .gap(px(4.0))
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .child(div().text_sm().child(rule_summary(&rule)))
+                .child(
+                    div()
+                        .id(SharedString::from(format!("notification-test-{}", index)))
+                        .px(px(8.0))
+                        .py(px(2.0))
+                        .rounded(px(4.0))
+                        .border_1()
+                        .border_color(theme.border)
+                        .text_xs()
+                        .cursor_pointer()
+                        .hover(move |s| s.bg(hover_bg))
+                        .on_click(move |_, _window, _cx| send_test_notification(&rule))
+                        .child("Test"),
+                ),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_wrap()
+                .gap(px(4.0))
+                .children(options.map(|sound| {
+                    let selected = current.as_deref() == sound;
+                    div()
+                        .id(SharedString::from(format!(
+                            "notification-sound-{}-{}",
+                            index,
+                            sound.unwrap_or("none")
+                        )))
+                        .px(px(8.0))
+                        .py(px(2.0))
+                        .rounded(px(4.0))
+                        .border_1()
+                        .border_color(if selected { theme.link } else { theme.border })
+                        .when(selected, |el| el.bg(theme.selected))
+                        .when(!selected, |el| el.hover(move |s| s.bg(hover_bg)))
+                        .text_xs()
+                        .cursor_pointer()
+                        .on_click(move |_, _window, cx| {
+                            if let Some(sound) = sound {
+                                play_sound(sound);
+                            }
+                            let settings = cx.global::<AppState>().settings.clone();
+                            settings.update(cx, |model, cx| {
+                                model.set_notification_sound(index, sound.map(str::to_string));
+                                cx.notify();
+                            });
+                        })
+                        .child(sound.unwrap_or("None"))
+                })),
+        )
+}
+
+/// Renders the failure tolerance: failed fetches in a row, and time
+/// without a good one, before a provider shows as failing.
+fn render_failure_tolerance(tolerance: FailureTolerance, theme: SettingsTheme) -> Div {
+    const FAILURES: [u32; 4] = [1, 2, 3, 5];
+    const STALE_MINUTES: [Option<u32>; 4] = [None, Some(15), Some(30), Some(60)];
+
+    let chip = move |id: String, label: String, selected: bool, tolerance: FailureTolerance| {
+        let hover_bg = theme.hover;
+        div()
+            .id(SharedString::from(id))
+            .px(px(8.0))
+            .py(px(2.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(if selected { theme.link } else { theme.border })
+            .when(selected, |el| el.bg(theme.selected))
+            .when(!selected, |el| el.hover(move |s| s.bg(hover_bg)))
+            .text_xs()
+            .cursor_pointer()
+            .on_click(move |_, _window, cx| {
+                let settings = cx.global::<AppState>().settings.clone();
+                settings.update(cx, |model, cx| {
+                    model.set_failure_tolerance(tolerance);
+                    cx.notify();
+                });
+            })
+            .child(label)
+    };
+
+    let row = |label: &'static str| {
+        div().flex().items_center().gap(px(4.0)).child(
+            div()
+                .w(px(96.0))
+                .text_xs()
+                .text_color(theme.text_muted)
+                .child(label),
+        )
+    };
+
+    div()
+        .searchable("failure_tolerance", theme)
+        .py(px(12.0))
+        .border_b_1()
+        .border_color(theme.border)
+        .flex()
+        .flex_col()
+        .gap(px(8.0))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child("Failure Tolerance"),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child("Keep showing the last usage until a provider keeps failing"),
+                ),
+        )
+        .child(row("Failures in a row").children(FAILURES.map(|failures| {
+            chip(
+                format!("failure-tolerance-{}", failures),
+                failures.to_string(),
+                tolerance.failures == failures,
+                FailureTolerance {
+                    failures,
+                    ..tolerance
+                },
+            )
+        })))
+        .child(
+            row("Or stale for").children(STALE_MINUTES.map(|stale_minutes| {
+                chip(
+                    format!("failure-stale-{}", stale_minutes.unwrap_or(0)),
+                    match stale_minutes {
+                        None => "Never".to_string(),
+                        Some(60) => "1 hour".to_string(),
+                        Some(minutes) => format!("{} min", minutes),
+                    },
+                    tolerance.stale_minutes == stale_minutes,
+                    FailureTolerance {
+                        stale_minutes,
+                        ..tolerance
+                    },
+                )
+            })),
+        )
+}
+
+fn render_request_budget(
+    global: Option<u32>,
+    providers: Vec<ProviderRequests>,
+    theme: SettingsTheme,
+) -> Div {
+    const GLOBAL_LIMITS: [Option<u32>; 5] = [None, Some(60), Some(120), Some(300), Some(600)];
+    const PROVIDER_LIMITS: [Option<u32>; 5] = [None, Some(10), Some(30), Some(60), Some(120)];
+
+    let chip = move |id: String,
+                     label: String,
+                     selected: bool,
+                     provider: Option<ProviderKind>,
+                     per_hour: Option<u32>| {
+        let hover_bg = theme.hover;
+        div()
+            .id(SharedString::from(id))
+            .px(px(8.0))
+            .py(px(2.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(if selected { theme.link } else { theme.border })
+            .when(selected, |el| el.bg(theme.selected))
+            .when(!selected, |el| el.hover(move |s| s.bg(hover_bg)))
+            .text_xs()
+            .cursor_pointer()
+            .on_click(move |_, _window, cx| {
+                let settings = cx.global::<AppState>().settings.clone();
+                settings.update(cx, |model, cx| {
+                    match provider {
+                        Some(provider) => model.set_provider_request_budget(provider, per_hour),
+                        None => model.set_request_budget(per_hour),
+                    }
+                    cx.notify();
+                });
+            })
+            .child(label)
+    };
+    let limit_label = |per_hour: Option<u32>| match per_hour {
+        None => "No limit".to_string(),
+        Some(per_hour) => per_hour.to_string(),
+    };
+    let total: u32 = providers.iter().map(|p| p.sent).sum();
+
+    div()
+        .searchable("request_budget", theme)
+        .mt(px(12.0))
+        .flex()
+        .flex_col()
+        .gap(px(12.0))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .child(
+                    div()
+                        .text_base()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child("Request Budget"),
+                )
+                .child(div().text_xs().text_color(theme.text_muted).child(
+                    "Polling can spend the quota it reports on. Cap how many fetches go out \
+                     per hour; once a cap is reached, providers wait until the oldest fetch \
+                     is an hour old.",
+                )),
+        )
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .gap(px(4.0))
+                .child(
+                    div()
+                        .w(px(120.0))
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child(format!("All providers ({})", total)),
+                )
+                .children(GLOBAL_LIMITS.map(|per_hour| {
+                    chip(
+                        format!("request-budget-{}", per_hour.unwrap_or(0)),
+                        limit_label(per_hour),
+                        global == per_hour,
+                        None,
+                        per_hour,
+                    )
+                })),
+        )
+        .children(providers.into_iter().map(move |row| {
+            div()
+                .flex()
+                .items_center()
+                .gap(px(4.0))
+                .child(
+                    div()
+                        .w(px(120.0))
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child(format!("{} ({})", row.name, row.sent)),
+                )
+                .children(PROVIDER_LIMITS.map(|per_hour| {
+                    chip(
+                        format!(
+                            "request-budget-{}-{}",
+                            row.provider.cli_name(),
+                            per_hour.unwrap_or(0)
+                        ),
+                        limit_label(per_hour),
+                        row.per_hour == per_hour,
+                        Some(row.provider),
+                        per_hour,
+                    )
+                }))
+        }))
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.text_muted)
+                .child("Fetches sent in the last hour are shown in parentheses."),
+        )
+}
+
+fn render_strategy_order_row(order: StrategyOrder, theme: SettingsTheme) -> Div {
+    let hover_bg = theme.hover;
+    let provider = order.provider;
+    let ids: Vec<String> = order.strategies.iter().map(|(id, _)| id.clone()).collect();
+    let count = ids.len();
+    let button = |id: String, label: &'static str| {
+        div()
+            .id(SharedString::from(id))
+            .px(px(6.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(theme.border)
+            .text_xs()
+            .cursor_pointer()
+            .hover(move |s| s.bg(hover_bg))
+            .child(label)
+    };
+    let move_to = move |ids: &[String], from: usize, to: usize| {
+        let mut ids = ids.to_vec();
+        ids.swap(from, to);
+        move |_: &ClickEvent, _window: &mut Window, cx: &mut App| {
+            let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+            let settings = cx.global::<AppState>().settings.clone();
+            settings.update(cx, |model, cx| {
+                model.set_strategy_order(provider, &ids);
+                cx.notify();
+            });
+        }
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap(px(4.0))
+        .py(px(8.0))
+        .border_b_1()
+        .border_color(theme.border)
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child(order.name),
+                )
+                .when(order.customized, |el| {
+                    el.child(
+                        button(format!("strategy-order-reset-{:?}", provider), "Reset").on_click(
+                            move |_, _window, cx| {
+                                let settings = cx.global::<AppState>().settings.clone();
+                                settings.update(cx, |model, cx| {
+                                    model.set_strategy_order(provider, &[]);
+                                    cx.notify();
+                                });
+                            },
+                        ),
+                    )
+                }),
+        )
+        .children(order.strategies.iter().enumerate().map(|(i, (id, kind))| {
+            div()
+                .flex()
+                .items_center()
+                .gap(px(6.0))
+                .child(
+                    div()
+                        .w(px(16.0))
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child(format!("{}.", i + 1)),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_xs()
+                        .font_family("monospace")
+                        .child(format!("{} ({})", id, kind.display_name())),
+                )
+                .when(i > 0, |el| {
+                    el.child(button(format!("strategy-up-{}", id), "↑").on_click(move_to(
+                        &ids,
+                        i,
+                        i - 1,
+                    )))
+                })
+                .when(i + 1 < count, |el| {
+                    el.child(
+                        button(format!("strategy-down-{}", id), "↓").on_click(move_to(
+                            &ids,
+                            i,
+                            i + 1,
+                        )),
+                    )
+                })
+        }))
+}
+
+fn render_keychain_row(
+    item: KeychainItem,
+    access: Option<KeychainAccess>,
+    locked: bool,
+    theme: SettingsTheme,
+) -> Div {
+    let hover_bg = theme.hover;
+    let browser = item.browser;
+    let status = access.map_or("Not tested", |a| a.description());
+
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .py(px(8.0))
+        .border_b_1()
+        .border_color(theme.border)
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child(browser.display_name()),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(theme.text_muted)
+                        .child(item.service),
+                )
+                .child(div().text_xs().text_color(theme.text_muted).child(status)),
+        )
+        .when(locked, |el| {
+            el.child(div().text_xs().text_color(theme.text_muted).child("Locked"))
+        })
+        .when(!locked, |el| {
+            el.child(
+                div()
+                    .px(px(10.0))
+                    .py(px(4.0))
+                    .rounded(px(6.0))
+                    .border_1()
+                    .border_color(theme.border)
+                    .text_xs()
+                    .cursor_pointer()
+                    .hover(move |s| s.bg(hover_bg))
+                    .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+                        let settings = cx.global::<AppState>().settings.clone();
+                        cx.spawn(async move |mut cx| {
+                            // The keychain call blocks while the system prompt is shown
+                            let access =
+                                smol::unblock(move || keychain_preflight::test_access(browser))
+                                    .await;
+                            let _ = cx.update_entity(&settings, |model, cx| {
+                                model.record_keychain_access(browser, access);
+                                cx.notify();
+                            });
+                        })
+                        .detach();
+                    })
+                    .child("Test Access"),
+            )
+        })
+}
@@ -0,0 +1,604 @@
+//! Stored API key audit for the Advanced pane.
+//!
+//! Lists the API keys ExactoBar keeps in the keychain with how old each
+//! one is, checks them against their providers on request, and deletes or
+//! rotates them. Listing runs `security` once per key and checking fetches
+//! from each provider, so both run in the background and their results are
+//! kept in [`KEY_AUDIT`] across renders of the pane.
+//!
+//! Keys entered in the Providers pane are checked with the provider before
+//! they're stored; a key the provider rejects isn't stored. How the last
+//! entry went is kept in [`KEY_ENTRIES`] and shown under the key.
+//!
+//! Keys found in environment variables are listed with the stored ones,
+//! with which providers they could enable, and can be imported into the
+//! keychain so the app finds them when it isn't started from a shell.
+//!
+//! A provider's keys can be read from 1Password or Bitwarden instead of the
+//! keychain, chosen per provider under Password Managers.
+
+use std::sync::Mutex;
+
+use chrono::Utc;
+use exactobar_core::ProviderKind;
+use exactobar_providers::key_audit::{check_key, key_provider};
+use exactobar_providers::{
+    EnvKey, EnvKeyUse, KeyHealth, KeyValidation, discover_env_keys, import_env_key, validate_key,
+};
+use exactobar_store::{SecretBackend, StoredKey};
+use gpui::*;
+use tracing::{info, warn};
+
+use super::SettingsTheme;
+use super::providers::{prompt_for_api_key_async, prompt_for_secret_reference_async};
+use crate::refresh::tokio_runtime;
+use crate::state::AppState;
+
+/// Stored keys and their last checks, shared by every render of the pane.
+static KEY_AUDIT: Mutex<KeyAuditState> = Mutex::new(KeyAuditState {
+    keys: None,
+    env: Vec::new(),
+    health: Vec::new(),
+    listing: false,
+    checking: false,
+});
+
+/// How the last key entered for each provider did.
+static KEY_ENTRIES: Mutex<Vec<(ProviderKind, KeyEntry)>> = Mutex::new(Vec::new());
+
+/// A key entered in the Providers pane.
+#[derive(Clone)]
+pub enum KeyEntry {
+    /// Being checked with the provider.
+    Checking,
+    /// Checked; stored unless the provider rejected it.
+    Checked(KeyValidation),
+}
+
+/// What's known about the stored keys.
+#[derive(Clone)]
+pub struct KeyAuditState {
+    /// Stored keys; `None` until listed.
+    keys: Option<Vec<StoredKey>>,
+    /// Keys found in environment variables, listed with the stored ones.
+    env: Vec<EnvKey>,
+    /// Last check of each key, by provider name.
+    health: Vec<(&'static str, KeyHealth)>,
+    /// Whether the keys are being listed.
+    listing: bool,
+    /// Whether a check is running.
+    checking: bool,
+}
+
+impl KeyAuditState {
+    /// The last check of a key, if it was checked.
+    fn health(&self, name: &str) -> Option<&KeyHealth> {
+        self.health
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, health)| health)
+    }
+}
+
+/// The audit as of now, listing the stored keys the first time.
+pub fn current(cx: &App) -> KeyAuditState {
+    let audit = KEY_AUDIT.lock().unwrap().clone();
+    if audit.keys.is_none() && !audit.listing {
+        list_keys(cx);
+    }
+    audit
+}
+
+/// Lists the stored keys again in the background.
+fn list_keys(cx: &App) {
+    if let Ok(mut audit) = KEY_AUDIT.lock() {
+        audit.listing = true;
+    }
+    cx.spawn(async move |cx| {
+        let (keys, env) =
+            smol::unblock(|| (exactobar_store::stored_entries(), discover_env_keys())).await;
+        if let Ok(mut audit) = KEY_AUDIT.lock() {
+            audit.env = env;
+            audit
+                .health
+                .retain(|(name, _)| keys.iter().any(|key| key.provider == *name));
+            audit.keys = Some(keys);
+            audit.listing = false;
+        }
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
+
+/// Checks every stored key against its provider.
+fn check_keys(cx: &App) {
+    let names: Vec<&'static str> = match KEY_AUDIT.lock() {
+        Ok(mut audit) if !audit.checking => {
+            audit.checking = true;
+            audit
+                .keys
+                .iter()
+                .flatten()
+                .map(|key| key.provider)
+                .collect()
+        }
+        _ => return,
+    };
+    cx.spawn(async move |cx| {
+        for name in names {
+            let health = smol::unblock(move || tokio_runtime().block_on(check_key(name))).await;
+            info!(
+                key = name,
+                health = health.label(),
+                "Checked stored API key"
+            );
+            if let Ok(mut audit) = KEY_AUDIT.lock() {
+                audit.health.retain(|(key, _)| *key != name);
+                audit.health.push((name, health));
+            }
+            let _ = cx.update(|cx| cx.refresh_windows());
+        }
+        if let Ok(mut audit) = KEY_AUDIT.lock() {
+            audit.checking = false;
+        }
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
+
+/// Deletes a stored key.
+fn delete_key(name: &'static str, cx: &App) {
+    if let Err(e) = exactobar_store::delete_api_key(name) {
+        warn!(key = name, error = %e, "Failed to delete API key");
+    }
+    list_keys(cx);
+}
+
+/// Stores a key found in the environment in the keychain.
+fn import_key(key: EnvKey, cx: &App) {
+    match import_env_key(&key) {
+        Ok(()) => info!(var = key.var, "Imported API key from the environment"),
+        Err(e) => warn!(var = key.var, error = %e, "Failed to import API key"),
+    }
+    list_keys(cx);
+}
+
+/// Asks for a new key and stores it in place of the old one.
+fn rotate_key(name: &'static str, label: String, cx: &App) {
+    cx.spawn(async move |cx| {
+        let Some(key) = prompt_for_api_key_async(&label).await else {
+            return;
+        };
+        if let Err(e) = exactobar_store::store_api_key(name, &key) {
+            warn!(key = name, error = %e, "Failed to store API key");
+            return;
+        }
+        info!(key = name, "API key rotated");
+        if let Ok(mut audit) = KEY_AUDIT.lock() {
+            audit.health.retain(|(key, _)| *key != name);
+        }
+        let _ = cx.update(|cx| list_keys(cx));
+    })
+    .detach();
+}
+
+/// Asks for a provider's key, checks it with the provider and stores it
+/// unless the provider rejects it.
+pub fn enter_key(provider: ProviderKind, name: &'static str, label: String, cx: &App) {
+    cx.spawn(async move |cx| {
+        let Some(key) = prompt_for_api_key_async(&label).await else {
+            return;
+        };
+        set_entry(provider, Some(KeyEntry::Checking));
+        let _ = cx.update(|cx| cx.refresh_windows());
+
+        let validation = {
+            let key = key.clone();
+            smol::unblock(move || tokio_runtime().block_on(validate_key(name, &key))).await
+        };
+        info!(
+            key = name,
+            health = validation.health.label(),
+            "Checked entered API key"
+        );
+        if !matches!(validation.health, KeyHealth::Invalid(_)) {
+            if let Err(e) = exactobar_store::store_api_key(name, key.trim()) {
+                warn!(key = name, error = %e, "Failed to store API key");
+            }
+        }
+        set_entry(provider, Some(KeyEntry::Checked(validation)));
+        let _ = cx.update(|cx| cx.refresh_windows());
+    })
+    .detach();
+}
+
+/// Forgets how the last key entered for a provider did, e.g. when it's
+/// cleared.
+pub fn clear_entry(provider: ProviderKind) {
+    set_entry(provider, None);
+}
+
+fn set_entry(provider: ProviderKind, entry: Option<KeyEntry>) {
+    if let Ok(mut entries) = KEY_ENTRIES.lock() {
+        entries.retain(|(p, _)| *p != provider);
+        entries.extend(entry.map(|entry| (provider, entry)));
+    }
+}
+
+/// Renders how the last key entered for a provider did, if one was.
+pub fn render_entry_status(provider: ProviderKind, theme: SettingsTheme) -> Option<Div> {
+    let entry = KEY_ENTRIES
+        .lock()
+        .ok()?
+        .iter()
+        .find(|(p, _)| *p == provider)
+        .map(|(_, entry)| entry.clone())?;
+    let (text, color) = match entry {
+        KeyEntry::Checking => ("Checking key…".to_string(), theme.text_muted),
+        KeyEntry::Checked(KeyValidation {
+            health: KeyHealth::Valid,
+            account,
+        }) => (
+            match account {
+                Some(account) => format!("✓ Valid key for {}", account),
+                None => "✓ Valid key".to_string(),
+            },
+            theme.success,
+        ),
+        KeyEntry::Checked(KeyValidation {
+            health: KeyHealth::Invalid(reason),
+            ..
+        }) => (
+            format!("✗ Key rejected, not saved: {}", reason),
+            theme.error,
+        ),
+        KeyEntry::Checked(KeyValidation {
+            health: KeyHealth::Unchecked(reason),
+            ..
+        }) => (
+            format!("Saved without checking: {}", reason),
+            theme.text_muted,
+        ),
+    };
+    Some(div().text_xs().text_color(color).child(text))
+}
+
+/// Name shown for a stored key, e.g. "Replicate" or "Cursor (admin)".
+fn key_label(name: &str) -> String {
+    match key_provider(name) {
+        Some((provider, true)) => format!("{} (admin)", provider.display_name()),
+        Some((provider, false)) => provider.display_name().to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Renders the "Check Keys" button.
+pub fn render_check_button(audit: &KeyAuditState, theme: SettingsTheme) -> Div {
+    let hover_bg = theme.hover;
+    let checking = audit.checking;
+    div()
+        .px(px(10.0))
+        .py(px(4.0))
+        .rounded(px(6.0))
+        .border_1()
+        .border_color(theme.border)
+        .text_xs()
+        .when(!checking, |el| {
+            el.cursor_pointer()
+                .hover(move |s| s.bg(hover_bg))
+                .on_mouse_down(MouseButton::Left, |_, _window, cx| check_keys(cx))
+        })
+        .child(if checking {
+            "Checking…"
+        } else {
+            "Check Keys"
+        })
+}
+
+/// Renders a stored key with its age and last check, and buttons to
+/// delete or rotate it.
+fn render_key_row(
+    key: StoredKey,
+    health: Option<KeyHealth>,
+    locked: bool,
+    theme: SettingsTheme,
+) -> Div {
+    let hover_bg = theme.hover;
+    let name = key.provider;
+    let label = key_label(name);
+    let age = key.modified_at.map_or_else(
+        || "Age unknown".to_string(),
+        |at| format!("Changed {} days ago", (Utc::now() - at).num_days().max(0)),
+    );
+    let (status, status_color) = match &health {
+        None => ("Not checked".to_string(), theme.text_muted),
+        Some(KeyHealth::Valid) => ("Valid".to_string(), theme.success),
+        Some(KeyHealth::Invalid(reason)) => (format!("Invalid: {}", reason), theme.error),
+        Some(KeyHealth::Unchecked(reason)) => (format!("Unchecked: {}", reason), theme.text_muted),
+    };
+
+    let button = move |id: String, text: &'static str| {
+        div()
+            .id(SharedString::from(id))
+            .px(px(10.0))
+            .py(px(4.0))
+            .rounded(px(6.0))
+            .border_1()
+            .border_color(theme.border)
+            .text_xs()
+            .cursor_pointer()
+            .hover(move |s| s.bg(hover_bg))
+            .child(text)
+    };
+
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .py(px(8.0))
+        .border_b_1()
+        .border_color(theme.border)
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child(label.clone()),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(theme.text_muted)
+                        .child(key.service),
+                )
+                .child(div().text_xs().text_color(theme.text_muted).child(age))
+                .child(div().text_xs().text_color(status_color).child(status)),
+        )
+        .when(locked, |el| {
+            el.child(div().text_xs().text_color(theme.text_muted).child("Locked"))
+        })
+        .when(!locked, |el| {
+            el.child(
+                div()
+                    .flex()
+                    .gap(px(6.0))
+                    .child(
+                        button(format!("rotate-key-{}", name), "Rotate")
+                            .on_click(move |_, _window, cx| rotate_key(name, label.clone(), cx)),
+                    )
+                    .child(
+                        button(format!("delete-key-{}", name), "Delete")
+                            .on_click(move |_, _window, cx| delete_key(name, cx)),
+                    ),
+            )
+        })
+}
+
+/// The rows for the audit: one per stored key, or a note while there
+/// are none.
+pub fn render_key_rows(audit: KeyAuditState, locked: bool, theme: SettingsTheme) -> Vec<Div> {
+    let note = |text: &'static str| div().text_xs().text_color(theme.text_muted).child(text);
+    match audit.keys.clone() {
+        None => vec![note("Looking for stored keys…")],
+        Some(keys) if keys.is_empty() => vec![note("No API keys stored in the keychain")],
+        Some(keys) => keys
+            .into_iter()
+            .map(|key| {
+                let health = audit.health(key.provider).cloned();
+                render_key_row(key, health, locked, theme)
+            })
+            .collect(),
+    }
+}
+
+/// Renders the keys found in environment variables: what each could
+/// enable, and a button to import it when it can be.
+pub fn render_env_rows(
+    audit: &KeyAuditState,
+    enabled: &[ProviderKind],
+    locked: bool,
+    theme: SettingsTheme,
+) -> Vec<Div> {
+    if audit.env.is_empty() {
+        return Vec::new();
+    }
+    let hover_bg = theme.hover;
+    let heading = div()
+        .pt(px(4.0))
+        .text_sm()
+        .font_weight(FontWeight::MEDIUM)
+        .child("Found in Environment");
+    let rows = audit.env.iter().cloned().map(|key| {
+        let provider = key.provider.display_name();
+        let (note, note_color) = match key.key_use {
+            EnvKeyUse::Unused(reason) => (reason.to_string(), theme.text_muted),
+            _ if !enabled.contains(&key.provider) => {
+                (format!("Could enable {}", provider), theme.success)
+            }
+            EnvKeyUse::Import(_) if key.stored => {
+                (format!("{} · In keychain", provider), theme.text_muted)
+            }
+            EnvKeyUse::Import(_) => (format!("{} · Not in keychain", provider), theme.text_muted),
+            EnvKeyUse::EnvOnly => (
+                format!("{} · Read from the environment", provider),
+                theme.text_muted,
+            ),
+        };
+        let importable = key.can_import() && !locked;
+
+        div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .py(px(8.0))
+            .border_b_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(2.0))
+                    .child(div().text_xs().font_family("monospace").child(key.var))
+                    .child(div().text_xs().text_color(note_color).child(note)),
+            )
+            .when(importable, |el| {
+                el.child(
+                    div()
+                        .id(SharedString::from(format!("import-key-{}", key.var)))
+                        .px(px(10.0))
+                        .py(px(4.0))
+                        .rounded(px(6.0))
+                        .border_1()
+                        .border_color(theme.border)
+                        .text_xs()
+                        .cursor_pointer()
+                        .hover(move |s| s.bg(hover_bg))
+                        .child("Import")
+                        .on_click(move |_, _window, cx| import_key(key.clone(), cx)),
+                )
+            })
+    });
+    std::iter::once(heading).chain(rows).collect()
+}
+
+/// Asks where a password manager keeps a provider's key and reads the
+/// provider's keys from there.
+fn choose_backend(provider: ProviderKind, backend: SecretBackend, cx: &App) {
+    let (prompt, example) = match backend {
+        SecretBackend::OnePassword { .. } => (
+            format!(
+                "1Password secret reference for {}:",
+                provider.display_name()
+            ),
+            "op://Vault/Item/credential",
+        ),
+        SecretBackend::Bitwarden { .. } => (
+            format!("Bitwarden item for {}:", provider.display_name()),
+            "Item name or id",
+        ),
+    };
+    let current = if backend.reference().is_empty() {
+        example.to_string()
+    } else {
+        backend.reference().to_string()
+    };
+    cx.spawn(async move |cx| {
+        let Some(reference) = prompt_for_secret_reference_async(prompt, current).await else {
+            return;
+        };
+        let backend = match backend {
+            SecretBackend::OnePassword { .. } => SecretBackend::OnePassword { reference },
+            SecretBackend::Bitwarden { .. } => SecretBackend::Bitwarden { item: reference },
+        };
+        info!(provider = ?provider, backend = backend.label(), "API keys read from password manager");
+        let _ = cx.update(|cx| {
+            let settings = cx.global::<AppState>().settings.clone();
+            settings.update(cx, |model, cx| {
+                model.set_secret_backend(provider, Some(backend));
+                cx.notify();
+            });
+        });
+    })
+    .detach();
+}
+
+/// Renders where a provider's keys are read from, with chips to switch
+/// between the keychain and the password managers.
+pub fn render_secret_backend_row(
+    provider: ProviderKind,
+    backend: Option<SecretBackend>,
+    locked: bool,
+    theme: SettingsTheme,
+) -> Div {
+    let hover_bg = theme.hover;
+    let choices = [
+        (None, "Keychain"),
+        (
+            Some(SecretBackend::OnePassword {
+                reference: String::new(),
+            }),
+            "1Password",
+        ),
+        (
+            Some(SecretBackend::Bitwarden {
+                item: String::new(),
+            }),
+            "Bitwarden",
+        ),
+    ];
+    let current = backend.as_ref().map(SecretBackend::label);
+    let chips = choices.into_iter().map(|(choice, label)| {
+        let selected = choice.as_ref().map(SecretBackend::label) == current;
+        let choice = match (&choice, &backend) {
+            // Choosing the current one again edits its reference
+            (Some(choice), Some(backend)) if choice.label() == backend.label() => {
+                Some(backend.clone())
+            }
+            _ => choice,
+        };
+        div()
+            .id(SharedString::from(format!(
+                "secret-backend-{}-{}",
+                provider.cli_name(),
+                label
+            )))
+            .px(px(8.0))
+            .py(px(2.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(if selected { theme.link } else { theme.border })
+            .when(selected, |el| el.bg(theme.selected))
+            .text_xs()
+            .when(!locked, |el| {
+                el.cursor_pointer().hover(move |s| s.bg(hover_bg)).on_click(
+                    move |_, _window, cx| match choice.clone() {
+                        Some(backend) => choose_backend(provider, backend, cx),
+                        None => {
+                            let settings = cx.global::<AppState>().settings.clone();
+                            settings.update(cx, |model, cx| {
+                                model.set_secret_backend(provider, None);
+                                cx.notify();
+                            });
+                        }
+                    },
+                )
+            })
+            .child(label)
+    });
+
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .gap(px(12.0))
+        .py(px(8.0))
+        .border_b_1()
+        .border_color(theme.border)
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child(provider.display_name()),
+                )
+                .when_some(backend, |el, backend| {
+                    el.child(
+                        div()
+                            .text_xs()
+                            .font_family("monospace")
+                            .text_color(theme.text_muted)
+                            .child(format!("{} {}", backend.cli(), backend.reference())),
+                    )
+                }),
+        )
+        .child(div().flex().gap(px(4.0)).children(chips))
+}
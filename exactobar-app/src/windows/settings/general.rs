@@ -1,22 +1,42 @@
 //! General settings pane.
 
-use exactobar_store::{RefreshCadence, ThemeMode};
+use std::sync::Arc;
+
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use exactobar_store::{
+    AttentionStyle, ColorPalette, IconLayout, IconVisibility, MenuDensity, RefreshCadence,
+    ThemeMode,
+};
 use gpui::prelude::*;
 use gpui::*;
 
 use super::SettingsTheme;
+use super::search::Searchable;
 use crate::components::Toggle;
-use crate::state::AppState;
+use crate::icon::{ICON_HEIGHT, ICON_WIDTH, IconRenderer, RenderMode};
+use crate::state::{AppState, SettingsModel};
 
 /// General settings pane.
 pub struct GeneralPane {
     cadence: RefreshCadence,
     merge_icons: bool,
+    sort_by_usage: bool,
+    icon_layout: IconLayout,
+    adaptive_icon: bool,
+    icon_outline: bool,
+    icon_visibility: IconVisibility,
+    icon_visibility_threshold: f64,
     theme_mode: ThemeMode,
+    color_palette: ColorPalette,
     usage_bars_show_used: bool,
     reset_times_show_absolute: bool,
+    reset_timezone: Option<String>,
     menu_bar_shows_brand_icon_with_percent: bool,
     switcher_shows_icons: bool,
+    menu_density: MenuDensity,
+    attention_animation: AttentionStyle,
+    attention_intensity: f32,
+    attention_duration_secs: u32,
     theme: SettingsTheme,
 }
 
@@ -27,11 +47,23 @@ impl GeneralPane {
         Self {
             cadence: settings.refresh_cadence,
             merge_icons: settings.merge_icons,
+            sort_by_usage: settings.sort_by_usage,
+            icon_layout: settings.icon_layout,
+            adaptive_icon: settings.adaptive_icon,
+            icon_outline: settings.icon_outline,
+            icon_visibility: settings.icon_visibility,
+            icon_visibility_threshold: settings.icon_visibility_threshold,
             theme_mode: settings.theme_mode,
+            color_palette: settings.color_palette,
             usage_bars_show_used: settings.usage_bars_show_used,
             reset_times_show_absolute: settings.reset_times_show_absolute,
+            reset_timezone: settings.reset_timezone.clone(),
             menu_bar_shows_brand_icon_with_percent: settings.menu_bar_shows_brand_icon_with_percent,
             switcher_shows_icons: settings.switcher_shows_icons,
+            menu_density: settings.menu_density,
+            attention_animation: settings.attention_animation,
+            attention_intensity: settings.attention_intensity,
+            attention_duration_secs: settings.attention_duration_secs,
             theme,
         }
     }
@@ -67,13 +99,35 @@ impl IntoElement for GeneralPane {
                     ),
             )
             .child(render_cadence_section(self.cadence, theme))
-            .child(render_icon_section(self.merge_icons, theme))
-            .child(render_theme_section(self.theme_mode, theme))
+            .child(render_icon_section(
+                self.merge_icons,
+                self.sort_by_usage,
+                self.icon_layout,
+                self.adaptive_icon,
+                self.icon_outline,
+                self.icon_visibility,
+                self.icon_visibility_threshold,
+                self.color_palette,
+                theme,
+            ))
+            .child(render_attention_section(
+                self.attention_animation,
+                self.attention_intensity,
+                self.attention_duration_secs,
+                theme,
+            ))
+            .child(render_theme_section(
+                self.theme_mode,
+                self.color_palette,
+                theme,
+            ))
             .child(render_display_section(
                 self.usage_bars_show_used,
                 self.reset_times_show_absolute,
+                self.reset_timezone,
                 self.menu_bar_shows_brand_icon_with_percent,
                 self.switcher_shows_icons,
+                self.menu_density,
                 theme,
             ))
     }
@@ -89,6 +143,7 @@ fn render_cadence_section(current: RefreshCadence, theme: SettingsTheme) -> Div
     ];
 
     div()
+        .searchable("refresh_cadence", theme)
         .flex()
         .flex_col()
         .gap(px(12.0))
@@ -156,7 +211,17 @@ fn render_radio_option(
         .child(div().text_sm().child(label))
 }
 
-fn render_icon_section(merge_icons: bool, theme: SettingsTheme) -> Div {
+fn render_icon_section(
+    merge_icons: bool,
+    sort_by_usage: bool,
+    layout: IconLayout,
+    adaptive_icon: bool,
+    icon_outline: bool,
+    visibility: IconVisibility,
+    visibility_threshold: f64,
+    palette: ColorPalette,
+    theme: SettingsTheme,
+) -> Div {
     div()
         .flex()
         .flex_col()
@@ -169,6 +234,7 @@ fn render_icon_section(merge_icons: bool, theme: SettingsTheme) -> Div {
         )
         .child(
             div()
+                .searchable("merge_icons", theme)
                 .flex()
                 .items_center()
                 .justify_between()
@@ -198,9 +264,365 @@ fn render_icon_section(merge_icons: bool, theme: SettingsTheme) -> Div {
                         }),
                 ),
         )
+        .child(
+            div()
+                .searchable("sort_by_usage", theme)
+                .flex()
+                .items_center()
+                .justify_between()
+                .py(px(8.0))
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(2.0))
+                        .child(div().text_sm().child("Sort by usage"))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("Put the providers with the least quota left first"),
+                        ),
+                )
+                .child(
+                    Toggle::new("toggle-sort-by-usage")
+                        .checked(sort_by_usage)
+                        .on_toggle(|enabled, cx| {
+                            cx.update_global::<AppState, _>(|state, cx| {
+                                state.settings.update(cx, |model, _| {
+                                    model.set_sort_by_usage(enabled);
+                                });
+                            });
+                            crate::tray::apply_provider_order(cx);
+                        }),
+                ),
+        )
+        .child(render_icon_visibility(
+            visibility,
+            visibility_threshold,
+            theme,
+        ))
+        .child(render_icon_layouts(layout, palette, icon_outline, theme))
+        .child(render_icon_contrast(adaptive_icon, icon_outline, theme))
+}
+
+/// Toggles for drawing the icon to stay readable on any wallpaper.
+fn render_icon_contrast(adaptive_icon: bool, icon_outline: bool, theme: SettingsTheme) -> Div {
+    div()
+        .flex()
+        .flex_col()
+        .child(
+            div()
+                .searchable("adaptive_icon", theme)
+                .flex()
+                .items_center()
+                .justify_between()
+                .py(px(8.0))
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(2.0))
+                        .child(div().text_sm().child("Adaptive icon"))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("Draw the icon for the light or dark menu bar instead of letting the system tint it"),
+                        ),
+                )
+                .child(
+                    Toggle::new("toggle-adaptive-icon")
+                        .checked(adaptive_icon)
+                        .on_toggle(|enabled, cx| {
+                            cx.update_global::<AppState, _>(|state, cx| {
+                                state.settings.update(cx, |model, _| {
+                                    model.set_adaptive_icon(enabled);
+                                });
+                            });
+                            crate::tray::refresh_all_icons(cx);
+                        }),
+                ),
+        )
+        .child(
+            div()
+                .searchable("icon_outline", theme)
+                .flex()
+                .items_center()
+                .justify_between()
+                .py(px(8.0))
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(2.0))
+                        .child(div().text_sm().child("Outline meter"))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("Outline the usage meter so it stands out on busy wallpapers"),
+                        ),
+                )
+                .child(
+                    Toggle::new("toggle-icon-outline")
+                        .checked(icon_outline)
+                        .on_toggle(|enabled, cx| {
+                            cx.update_global::<AppState, _>(|state, cx| {
+                                state.settings.update(cx, |model, _| {
+                                    model.set_icon_outline(enabled);
+                                });
+                            });
+                            crate::tray::refresh_all_icons(cx);
+                        }),
+                ),
+        )
 }
 
-fn render_theme_section(current: ThemeMode, theme: SettingsTheme) -> Div {
+/// When icons are shown, and below how much quota left a provider counts
+/// as low.
+fn render_icon_visibility(current: IconVisibility, threshold: f64, theme: SettingsTheme) -> Div {
+    let thresholds = [(25.0, "25%"), (50.0, "50%"), (75.0, "75%")];
+
+    div()
+        .searchable("icon_visibility", theme)
+        .flex()
+        .flex_col()
+        .gap(px(8.0))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(div().text_sm().child("Show icons"))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child("Hide icons until quota runs low; settings stay reachable with exactobar://settings"),
+                ),
+        )
+        .child(render_choice_row(
+            "When",
+            IconVisibility::all().iter().map(|&option| {
+                render_choice_chip(option.to_string(), option == current, theme, move |model| {
+                    model.set_icon_visibility(option)
+                })
+                .on_mouse_down(MouseButton::Left, |_, _window, cx| {
+                    crate::tray::refresh_all_icons(cx)
+                })
+            }),
+            theme,
+        ))
+        .when(current != IconVisibility::Always, |el| {
+            el.child(render_choice_row(
+                "Low at",
+                thresholds.into_iter().map(|(value, label)| {
+                    render_choice_chip(
+                        format!("{} left", label),
+                        threshold == value,
+                        theme,
+                        move |model| model.set_icon_visibility_threshold(value),
+                    )
+                    .on_mouse_down(MouseButton::Left, |_, _window, cx| {
+                        crate::tray::refresh_all_icons(cx)
+                    })
+                }),
+                theme,
+            ))
+        })
+}
+
+/// Layout choices, each with a preview of the icon it draws.
+fn render_icon_layouts(
+    current: IconLayout,
+    palette: ColorPalette,
+    outline: bool,
+    theme: SettingsTheme,
+) -> Div {
+    let mut sample = UsageSnapshot::new();
+    sample.primary = Some(UsageWindow::new(42.0));
+    sample.secondary = Some(UsageWindow::new(65.0));
+
+    div()
+        .searchable("icon_layout", theme)
+        .flex()
+        .flex_col()
+        .gap(px(8.0))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(2.0))
+                .child(div().text_sm().child("Layout"))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child("What the usage meter in the menu bar shows"),
+                ),
+        )
+        .child(
+            div()
+                .flex()
+                .gap(px(8.0))
+                .children(IconLayout::all().iter().map(|&layout| {
+                    let png = IconRenderer::new()
+                        .with_mode(RenderMode::Colored)
+                        .with_layout(layout)
+                        .with_palette(palette)
+                        .with_outline(outline)
+                        .render(ProviderKind::Claude, Some(&sample), false, None, None)
+                        .to_png();
+                    render_layout_option(layout, png, layout == current, theme)
+                })),
+        )
+}
+
+fn render_layout_option(
+    layout: IconLayout,
+    preview_png: Vec<u8>,
+    selected: bool,
+    theme: SettingsTheme,
+) -> Div {
+    let hover_bg = theme.hover;
+    let preview = Arc::new(Image::from_bytes(ImageFormat::Png, preview_png));
+    div()
+        .flex_1()
+        .px(px(8.0))
+        .py(px(10.0))
+        .rounded(px(6.0))
+        .cursor_pointer()
+        .border_1()
+        .border_color(if selected { theme.link } else { theme.border })
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap(px(6.0))
+        .when(selected, |el| el.bg(theme.selected))
+        .when(!selected, |el| el.hover(move |s| s.bg(hover_bg)))
+        .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+            let settings = cx.global::<AppState>().settings.clone();
+            settings.update(cx, |model, cx| {
+                model.set_icon_layout(layout);
+                cx.notify();
+            });
+            crate::tray::refresh_all_icons(cx);
+        })
+        // The icon is rendered at 2x
+        .child(
+            img(preview)
+                .w(px(ICON_WIDTH as f32 / 2.0))
+                .h(px(ICON_HEIGHT as f32 / 2.0)),
+        )
+        .child(div().text_xs().child(layout.to_string()))
+}
+
+fn render_attention_section(
+    style: AttentionStyle,
+    intensity: f32,
+    duration_secs: u32,
+    theme: SettingsTheme,
+) -> Div {
+    let intensities = [(0.4, "Subtle"), (0.7, "Medium"), (1.0, "Strong")];
+    let durations = [(5, "5s"), (10, "10s"), (30, "30s")];
+    let enabled = style != AttentionStyle::Off;
+
+    div()
+        .searchable("attention_animation", theme)
+        .flex()
+        .flex_col()
+        .gap(px(12.0))
+        .child(
+            div()
+                .text_base()
+                .font_weight(FontWeight::SEMIBOLD)
+                .child("Critical Usage Animation"),
+        )
+        .child(
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Animate the menu bar icon when a provider goes above 95% used"),
+        )
+        .child(render_choice_row(
+            "Style",
+            AttentionStyle::all().iter().map(|&option| {
+                render_choice_chip(option.to_string(), option == style, theme, move |model| {
+                    model.set_attention_animation(option)
+                })
+            }),
+            theme,
+        ))
+        .when(enabled, |el| {
+            el.child(render_choice_row(
+                "Intensity",
+                intensities.into_iter().map(|(value, label)| {
+                    render_choice_chip(
+                        label.to_string(),
+                        (intensity - value).abs() < 0.05,
+                        theme,
+                        move |model| model.set_attention_intensity(value),
+                    )
+                }),
+                theme,
+            ))
+            .child(render_choice_row(
+                "Duration",
+                durations.into_iter().map(|(secs, label)| {
+                    render_choice_chip(
+                        label.to_string(),
+                        duration_secs == secs,
+                        theme,
+                        move |model| model.set_attention_duration_secs(secs),
+                    )
+                }),
+                theme,
+            ))
+        })
+}
+
+fn render_choice_row(
+    label: &'static str,
+    chips: impl IntoIterator<Item = Div>,
+    theme: SettingsTheme,
+) -> Div {
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .child(div().text_sm().text_color(theme.text_muted).child(label))
+        .child(div().flex().gap(px(4.0)).children(chips))
+}
+
+fn render_choice_chip(
+    label: String,
+    selected: bool,
+    theme: SettingsTheme,
+    apply: impl Fn(&mut SettingsModel) + 'static,
+) -> Div {
+    let hover_bg = theme.hover;
+    div()
+        .px(px(10.0))
+        .py(px(4.0))
+        .rounded(px(6.0))
+        .cursor_pointer()
+        .text_sm()
+        .border_1()
+        .border_color(if selected { theme.link } else { theme.border })
+        .when(selected, |el| el.bg(theme.selected))
+        .when(!selected, |el| el.hover(move |s| s.bg(hover_bg)))
+        .on_mouse_down(MouseButton::Left, move |_, _window, cx| {
+            let settings = cx.global::<AppState>().settings.clone();
+            settings.update(cx, |model, cx| {
+                apply(model);
+                cx.notify();
+            });
+        })
+        .child(label)
+}
+
+fn render_theme_section(current: ThemeMode, palette: ColorPalette, theme: SettingsTheme) -> Div {
     let options: Vec<(ThemeMode, &'static str, &'static str)> = vec![
         (
             ThemeMode::Dark,
@@ -220,6 +642,7 @@ fn render_theme_section(current: ThemeMode, theme: SettingsTheme) -> Div {
     ];
 
     div()
+        .searchable("theme_mode", theme)
         .flex()
         .flex_col()
         .gap(px(12.0))
@@ -297,13 +720,31 @@ fn render_theme_section(current: ThemeMode, theme: SettingsTheme) -> Div {
                         )
                 })),
         )
+        .child(
+            render_choice_row(
+                "Usage Colors",
+                ColorPalette::all().iter().map(|&option| {
+                    render_choice_chip(option.to_string(), option == palette, theme, move |model| {
+                        model.set_color_palette(option)
+                    })
+                    // Tray icons don't watch settings, so redraw them
+                    .on_mouse_down(MouseButton::Left, |_, _window, cx| {
+                        crate::tray::refresh_all_icons(cx);
+                    })
+                }),
+                theme,
+            )
+            .searchable("color_palette", theme),
+        )
 }
 
 fn render_display_section(
     usage_bars_show_used: bool,
     reset_times_show_absolute: bool,
+    reset_timezone: Option<String>,
     menu_bar_shows_brand_icon_with_percent: bool,
     switcher_shows_icons: bool,
+    menu_density: MenuDensity,
     theme: SettingsTheme,
 ) -> Div {
     div()
@@ -319,6 +760,7 @@ fn render_display_section(
         // Show used percent toggle
         .child(
             div()
+                .searchable("usage_bars_show_used", theme)
                 .flex()
                 .items_center()
                 .justify_between()
@@ -357,6 +799,7 @@ fn render_display_section(
         // Absolute reset times toggle
         .child(
             div()
+                .searchable("reset_times_show_absolute", theme)
                 .flex()
                 .items_center()
                 .justify_between()
@@ -393,9 +836,29 @@ fn render_display_section(
                         }),
                 ),
         )
+        // Reset time zone
+        .child(
+            div()
+                .searchable("reset_timezone", theme)
+                .py(px(12.0))
+                .border_b_1()
+                .border_color(theme.border)
+                .child(render_choice_row(
+                    "Reset Time Zone",
+                    timezone_options(reset_timezone.as_deref()).into_iter().map(
+                        |(zone, label, selected)| {
+                            render_choice_chip(label, selected, theme, move |model| {
+                                model.set_reset_timezone(zone.clone());
+                            })
+                        },
+                    ),
+                    theme,
+                )),
+        )
         // Brand icon with percent toggle
         .child(
             div()
+                .searchable("brand_icon_with_percent", theme)
                 .flex()
                 .items_center()
                 .justify_between()
@@ -435,10 +898,13 @@ fn render_display_section(
         // Switcher shows icons toggle
         .child(
             div()
+                .searchable("switcher_shows_icons", theme)
                 .flex()
                 .items_center()
                 .justify_between()
                 .py(px(12.0))
+                .border_b_1()
+                .border_color(theme.border)
                 .child(
                     div()
                         .flex()
@@ -469,4 +935,48 @@ fn render_display_section(
                         }),
                 ),
         )
+        // Card density
+        .child(
+            div()
+                .searchable("menu_density", theme)
+                .py(px(12.0))
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .child(render_choice_row(
+                    "Card Density",
+                    MenuDensity::all().iter().map(|&density| {
+                        render_choice_chip(
+                            density.to_string(),
+                            density == menu_density,
+                            theme,
+                            move |model| model.set_menu_density(density),
+                        )
+                    }),
+                    theme,
+                ))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child("Compact shows one line per provider on the All tab"),
+                ),
+        )
+}
+
+/// Timezone choices as (setting, label, selected): the system zone, UTC,
+/// and any other zone set in the settings file.
+fn timezone_options(current: Option<&str>) -> Vec<(Option<String>, String, bool)> {
+    let mut options = vec![
+        (None, "System".to_string()),
+        (Some("UTC"), "UTC".to_string()),
+    ];
+    if let Some(custom) = current.filter(|zone| *zone != "UTC") {
+        options.push((Some(custom), custom.to_string()));
+    }
+
+    options
+        .into_iter()
+        .map(|(zone, label)| (zone.map(str::to_string), label, zone == current))
+        .collect()
 }
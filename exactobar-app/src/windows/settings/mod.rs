@@ -2,28 +2,43 @@
 
 mod about;
 mod advanced;
+mod api_keys;
 mod general;
 mod providers;
+mod search;
 mod theme;
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use gpui::prelude::*;
 use gpui::*;
+use smol::Timer;
+use tracing::warn;
 
 use exactobar_core::ProviderKind;
-use exactobar_store::{CookieSource, DataSourceMode};
+use exactobar_fetch::{FetchKind, ProcessSandbox};
+use exactobar_providers::VersionCheck;
+use exactobar_store::{CookieSource, DataSourceMode, UiState, WindowFrame, default_ui_state_path};
 
 use about::AboutPane;
 use advanced::AdvancedPane;
 use general::GeneralPane;
 use providers::{
     COOKIE_SOURCES, DATA_SOURCE_MODES, ProviderRowData, ProviderStatus, collect_provider_data,
-    get_install_command, prompt_for_api_key_async,
+    get_install_command, prompt_for_binary_async, prompt_for_group_async,
 };
+use search::{Searchable, SettingDescriptor};
 pub use theme::SettingsTheme;
 
 use crate::components::ProviderIcon;
+use crate::refresh::tokio_runtime;
 use crate::state::AppState;
 
+/// How long the window's frame and pane must stay put before they're saved,
+/// so dragging or resizing doesn't write the UI state file every frame.
+const UI_STATE_SAVE_DELAY: Duration = Duration::from_millis(500);
+
 // ============================================================================
 // Settings Window
 // ============================================================================
@@ -32,6 +47,24 @@ use crate::state::AppState;
 pub struct SettingsWindow {
     active_pane: SettingsPane,
     settings_subscription: Option<gpui::Subscription>,
+    /// Text typed in the sidebar search field.
+    search_query: String,
+    /// Focus of the search field, created on first render.
+    search_focus: Option<FocusHandle>,
+    /// Row picked from the search results, highlighted until the pane changes.
+    search_target: Option<(&'static str, ScrollAnchor)>,
+    /// Whether to scroll to the search target on the next frame.
+    scroll_to_target: bool,
+    /// Scroll position of the pane content.
+    scroll_handle: ScrollHandle,
+    /// Watches the window's frame, created on first render.
+    bounds_subscription: Option<gpui::Subscription>,
+    /// The window's last frame, remembered across launches.
+    frame: Option<WindowFrame>,
+    /// The pane last saved to the UI state.
+    saved_pane: SettingsPane,
+    /// Pending UI state save; replacing it cancels the previous one.
+    ui_state_save: Option<Task<()>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -43,16 +76,150 @@ pub enum SettingsPane {
     About,
 }
 
+impl SettingsPane {
+    /// All panes in sidebar order.
+    pub fn all() -> &'static [SettingsPane] {
+        &[
+            SettingsPane::General,
+            SettingsPane::Providers,
+            SettingsPane::Advanced,
+            SettingsPane::About,
+        ]
+    }
+
+    /// The pane's sidebar title.
+    pub fn title(&self) -> &'static str {
+        match self {
+            SettingsPane::General => "General",
+            SettingsPane::Providers => "Providers",
+            SettingsPane::Advanced => "Advanced",
+            SettingsPane::About => "About",
+        }
+    }
+
+    /// Finds a pane by its title, as saved in the UI state.
+    pub fn from_title(title: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|pane| pane.title() == title)
+    }
+}
+
 impl SettingsWindow {
     pub fn new() -> Self {
         println!("🎯 [SW-1] SettingsWindow::new() called!");
         let result = Self {
             active_pane: SettingsPane::default(),
             settings_subscription: None,
+            search_query: String::new(),
+            search_focus: None,
+            search_target: None,
+            scroll_to_target: false,
+            scroll_handle: ScrollHandle::new(),
+            bounds_subscription: None,
+            frame: None,
+            saved_pane: SettingsPane::default(),
+            ui_state_save: None,
         };
         println!("🎯 [SW-2] SettingsWindow::new() returning!");
         result
     }
+
+    /// Restores the pane and frame saved when the window was last open.
+    pub fn restore(&mut self, state: &UiState) {
+        if let Some(pane) = state
+            .settings_pane
+            .as_deref()
+            .and_then(SettingsPane::from_title)
+        {
+            self.active_pane = pane;
+            self.saved_pane = pane;
+        }
+        self.frame = state.settings_frame;
+    }
+
+    /// Saves the window's frame and pane once they've stopped changing
+    /// for [`UI_STATE_SAVE_DELAY`].
+    fn schedule_ui_state_save(&mut self, cx: &mut Context<Self>) {
+        let frame = self.frame;
+        let pane = self.active_pane.title().to_string();
+        self.ui_state_save = Some(cx.spawn(async move |_, _| {
+            Timer::after(UI_STATE_SAVE_DELAY).await;
+            let result = smol::unblock(move || {
+                tokio_runtime().block_on(async move {
+                    let path = default_ui_state_path();
+                    let mut state = UiState::load(&path).await;
+                    state.settings_frame = frame;
+                    state.settings_pane = Some(pane);
+                    state.save(&path).await
+                })
+            })
+            .await;
+            if let Err(e) = result {
+                warn!(error = %e, "Failed to save settings window state");
+            }
+        }));
+    }
+
+    /// Switches to the given pane.
+    pub fn set_active_pane(&mut self, pane: SettingsPane) {
+        self.active_pane = pane;
+        self.search_query.clear();
+        self.search_target = None;
+    }
+
+    /// Switches to a search result's pane and scrolls to its row.
+    fn open_search_result(&mut self, setting: &SettingDescriptor) {
+        self.set_active_pane(setting.pane);
+        self.search_target = Some((
+            setting.id,
+            ScrollAnchor::for_handle(self.scroll_handle.clone()),
+        ));
+        self.scroll_to_target = true;
+    }
+
+    /// Switches to a setting's pane and scrolls to its row, by search id.
+    pub fn reveal_setting(&mut self, id: &str) {
+        if let Some(setting) = search::registry().into_iter().find(|s| s.id == id) {
+            self.open_search_result(&setting);
+        }
+    }
+
+    fn on_search_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        match keystroke.key.as_str() {
+            "escape" => self.search_query.clear(),
+            "enter" => {
+                if let Some(first) = search::search(&self.search_query).first() {
+                    self.open_search_result(first);
+                }
+            }
+            "backspace" => {
+                self.search_query.pop();
+            }
+            _ => {
+                if keystroke.modifiers.platform || keystroke.modifiers.control {
+                    return;
+                }
+                let Some(text) = keystroke
+                    .key_char
+                    .as_ref()
+                    .filter(|t| !t.chars().any(char::is_control))
+                else {
+                    return;
+                };
+                self.search_query.push_str(text);
+            }
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
 }
 
 impl Default for SettingsWindow {
@@ -75,6 +242,25 @@ impl Render for SettingsWindow {
             }));
         }
 
+        if self.bounds_subscription.is_none() {
+            self.bounds_subscription =
+                Some(cx.observe_window_bounds(window, |this, window, cx| {
+                    if let WindowBounds::Windowed(bounds) = window.window_bounds() {
+                        this.frame = Some(WindowFrame {
+                            x: bounds.origin.x.into(),
+                            y: bounds.origin.y.into(),
+                            width: bounds.size.width.into(),
+                            height: bounds.size.height.into(),
+                        });
+                        this.schedule_ui_state_save(cx);
+                    }
+                }));
+        }
+        if self.saved_pane != self.active_pane {
+            self.saved_pane = self.active_pane;
+            self.schedule_ui_state_save(cx);
+        }
+
         // Get theme mode from settings
         let theme_mode = cx.global::<AppState>().settings.read(cx).theme_mode();
 
@@ -97,12 +283,26 @@ impl Render for SettingsWindow {
 
         let active = self.active_pane;
 
-        let content = match self.active_pane {
-            SettingsPane::General => GeneralPane::new(cx, theme).into_any_element(),
-            SettingsPane::Providers => self.render_providers_pane(cx, theme).into_any_element(),
-            SettingsPane::Advanced => AdvancedPane::new(cx, theme).into_any_element(),
-            SettingsPane::About => AboutPane::new(theme).into_any_element(),
+        // Panes highlight the search target's row as they're built
+        search::set_target(self.search_target.clone());
+        let content = if self.search_query.trim().is_empty() {
+            match self.active_pane {
+                SettingsPane::General => GeneralPane::new(cx, theme).into_any_element(),
+                SettingsPane::Providers => self.render_providers_pane(cx, theme).into_any_element(),
+                SettingsPane::Advanced => AdvancedPane::new(cx, theme).into_any_element(),
+                SettingsPane::About => AboutPane::new(theme).into_any_element(),
+            }
+        } else {
+            self.render_search_results(theme, cx).into_any_element()
         };
+        search::set_target(None);
+
+        if self.scroll_to_target {
+            self.scroll_to_target = false;
+            if let Some((_, anchor)) = &self.search_target {
+                anchor.scroll_to(window, cx);
+            }
+        }
 
         // Build sidebar items with click handlers inline
         let sidebar = div()
@@ -116,6 +316,7 @@ impl Render for SettingsWindow {
             .flex_col()
             .gap(px(4.0))
             .text_color(theme.text_primary)
+            .child(self.render_search_field(&theme, window, cx))
             .child(self.sidebar_item(
                 SettingsPane::General,
                 "General",
@@ -161,6 +362,7 @@ impl Render for SettingsWindow {
                     .flex_1()
                     .h_full()
                     .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
                     .child(div().p(px(24.0)).child(content)),
             )
     }
@@ -174,6 +376,7 @@ impl SettingsWindow {
         theme: SettingsTheme,
     ) -> impl IntoElement {
         let providers = collect_provider_data(cx);
+        let locked = cx.global::<AppState>().settings.read(cx).is_locked();
 
         // Separate primary and additional providers
         let (primary, additional): (Vec<_>, Vec<_>) =
@@ -201,7 +404,13 @@ impl SettingsWindow {
                             .text_sm()
                             .text_color(theme.text_muted)
                             .child("Enable the LLM providers you want to monitor"),
-                    ),
+                    )
+                    .when(locked, |el| {
+                        el.child(div().text_xs().text_color(theme.warning).child(
+                            "Locked mode: API keys and cookie sources can't be changed on this \
+                             machine.",
+                        ))
+                    }),
             )
             // Primary Providers section
             .child(
@@ -272,7 +481,6 @@ impl SettingsWindow {
     ) -> Div {
         let provider = data.provider;
         let hover_bg = theme.hover;
-        let has_settings = data.supports_cookies || data.supports_data_source;
         let is_enabled = data.is_enabled;
 
         // Toggle colors
@@ -291,6 +499,7 @@ impl SettingsWindow {
             // Main row
             .child(
                 div()
+                    .searchable(provider.cli_name(), theme)
                     .px(px(16.0))
                     .py(px(12.0))
                     .flex()
@@ -388,8 +597,8 @@ impl SettingsWindow {
                             ),
                     ),
             )
-            // Settings row (only show when enabled and provider has settings)
-            .when(has_settings && is_enabled, |el| {
+            // Settings row (only show when enabled)
+            .when(is_enabled, |el| {
                 el.child(
                     div()
                         .px(px(16.0))
@@ -402,6 +611,7 @@ impl SettingsWindow {
                             el.child(self.render_cookie_source_selector(
                                 provider,
                                 data.current_cookie_source,
+                                data.locked,
                                 theme,
                                 cx,
                             ))
@@ -414,7 +624,47 @@ impl SettingsWindow {
                                 theme,
                                 cx,
                             ))
-                        }),
+                        })
+                        // CLI sandbox options
+                        .when(data.supports_sandbox, |el| {
+                            el.child(self.render_sandbox_selector(
+                                provider,
+                                data.sandbox.clone(),
+                                theme,
+                                cx,
+                            ))
+                        })
+                        // CLI location
+                        .when(data.supports_sandbox, |el| {
+                            el.child(self.render_binary_path_row(
+                                provider,
+                                &data.cli_name,
+                                data.binary_path.clone(),
+                                data.found_binary.clone(),
+                                data.cli_version.clone(),
+                                theme,
+                                cx,
+                            ))
+                        })
+                        // Fetch strategies, when there's more than one to pick from
+                        .when(data.strategies.len() > 1, |el| {
+                            el.child(self.render_strategy_selector(
+                                provider,
+                                &data.strategies,
+                                data.locked,
+                                theme,
+                                cx,
+                            ))
+                        })
+                        // Switcher group
+                        .child(self.render_group_selector(
+                            provider,
+                            &data.name,
+                            data.group.clone(),
+                            &data.groups,
+                            theme,
+                            cx,
+                        )),
                 )
             })
             // Install hint (only show when enabled but CLI is missing)
@@ -450,6 +700,7 @@ impl SettingsWindow {
             // API Key configuration (only for API key providers when enabled)
             .when(is_enabled && data.needs_api_key, |el| {
                 let has_key = data.has_api_key;
+                let locked = data.locked;
                 let api_key_name = data.api_key_name.to_string();
                 let provider_name = data.name.clone();
                 let accent_color = theme.link;
@@ -485,31 +736,37 @@ impl SettingsWindow {
                                         .text_color(success_color)
                                         .child("••••••••••••"),
                                 )
-                                .child(
-                                    div()
-                                        .id(SharedString::from(format!("clear-key-{:?}", provider)))
-                                        .px(px(8.0))
-                                        .py(px(2.0))
-                                        .rounded(px(4.0))
-                                        .bg(surface_color)
-                                        .text_xs()
-                                        .text_color(muted_color)
-                                        .cursor_pointer()
-                                        .hover(|s| s.bg(hover_bg))
-                                        .on_mouse_down(
-                                            MouseButton::Left,
-                                            cx.listener(move |_this, _, _window, cx| {
-                                                let _ = exactobar_store::delete_api_key(
-                                                    &key_name_clear,
-                                                );
-                                                cx.notify();
-                                            }),
-                                        )
-                                        .child("Clear"),
-                                )
+                                .when(!locked, |el| {
+                                    el.child(
+                                        div()
+                                            .id(SharedString::from(format!(
+                                                "clear-key-{:?}",
+                                                provider
+                                            )))
+                                            .px(px(8.0))
+                                            .py(px(2.0))
+                                            .rounded(px(4.0))
+                                            .bg(surface_color)
+                                            .text_xs()
+                                            .text_color(muted_color)
+                                            .cursor_pointer()
+                                            .hover(|s| s.bg(hover_bg))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(move |_this, _, _window, cx| {
+                                                    let _ = exactobar_store::delete_api_key(
+                                                        &key_name_clear,
+                                                    );
+                                                    api_keys::clear_entry(provider);
+                                                    cx.notify();
+                                                }),
+                                            )
+                                            .child("Clear"),
+                                    )
+                                })
                         } else {
                             // No key - show Configure button
-                            let key_name_config = api_key_name.clone();
+                            let key_name_config = data.api_key_name;
                             let name_for_dialog = provider_name.clone();
                             div()
                                 .flex()
@@ -521,47 +778,47 @@ impl SettingsWindow {
                                         .text_color(muted_color)
                                         .child("Not configured"),
                                 )
-                                .child(
-                                    div()
-                                        .id(SharedString::from(format!(
-                                            "config-key-{:?}",
-                                            provider
-                                        )))
-                                        .px(px(8.0))
-                                        .py(px(2.0))
-                                        .rounded(px(4.0))
-                                        .bg(accent_color)
-                                        .text_xs()
-                                        .text_color(white())
-                                        .cursor_pointer()
-                                        .hover(|s| s.opacity(0.9))
-                                        .on_mouse_down(
-                                            MouseButton::Left,
-                                            cx.listener(move |_this, _, _window, cx| {
-                                                let name = name_for_dialog.clone();
-                                                let key_name = key_name_config.clone();
-                                                cx.spawn(async move |_, mut cx| {
-                                                    if let Some(key) =
-                                                        prompt_for_api_key_async(&name).await
-                                                    {
-                                                        let _ = exactobar_store::store_api_key(
-                                                            &key_name, &key,
-                                                        );
-                                                        // Trigger global state refresh to re-render UI
-                                                        let _ = cx.update_global::<AppState, _>(
-                                                            |_state, _cx| {
-                                                                // State change triggers re-render
-                                                            },
-                                                        );
-                                                    }
-                                                })
-                                                .detach();
-                                            }),
-                                        )
-                                        .child("Configure"),
-                                )
+                                .when(!locked, |el| {
+                                    el.child(
+                                        div()
+                                            .id(SharedString::from(format!(
+                                                "config-key-{:?}",
+                                                provider
+                                            )))
+                                            .px(px(8.0))
+                                            .py(px(2.0))
+                                            .rounded(px(4.0))
+                                            .bg(accent_color)
+                                            .text_xs()
+                                            .text_color(white())
+                                            .cursor_pointer()
+                                            .hover(|s| s.opacity(0.9))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(move |_this, _, _window, cx| {
+                                                    api_keys::enter_key(
+                                                        provider,
+                                                        key_name_config,
+                                                        name_for_dialog.clone(),
+                                                        cx,
+                                                    );
+                                                }),
+                                            )
+                                            .child("Configure"),
+                                    )
+                                })
+                        })
+                        .when(locked, |el| {
+                            el.child(div().text_xs().text_color(muted_color).child("Locked"))
                         }),
                 )
+                // How the last key entered did with the provider
+                .when_some(
+                    api_keys::render_entry_status(provider, theme),
+                    |el, status| {
+                        el.child(div().px(px(16.0)).pb(px(12.0)).pl(px(44.0)).child(status))
+                    },
+                )
             })
     }
 
@@ -570,6 +827,7 @@ impl SettingsWindow {
         &self,
         provider: ProviderKind,
         current: CookieSource,
+        locked: bool,
         theme: SettingsTheme,
         cx: &mut Context<Self>,
     ) -> Div {
@@ -606,22 +864,25 @@ impl SettingsWindow {
                             .px(px(8.0))
                             .py(px(4.0))
                             .rounded(px(4.0))
-                            .cursor_pointer()
                             .bg(if is_selected { selected_bg } else { default_bg })
                             .border_1()
                             .border_color(if is_selected { accent } else { border })
                             .child(format!("{}", source))
-                            .on_mouse_down(
-                                MouseButton::Left,
-                                cx.listener(move |_this, _, _window, cx| {
-                                    cx.update_global::<AppState, _>(|state, cx| {
-                                        state.settings.update(cx, |model, _| {
-                                            model.set_cookie_source(provider, source_copy);
+                            // Cookie sources can't change in locked mode
+                            .when(locked, |el| el.opacity(0.5))
+                            .when(!locked, |el| {
+                                el.cursor_pointer().on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(move |_this, _, _window, cx| {
+                                        cx.update_global::<AppState, _>(|state, cx| {
+                                            state.settings.update(cx, |model, _| {
+                                                model.set_cookie_source(provider, source_copy);
+                                            });
                                         });
-                                    });
-                                    cx.notify();
-                                }),
-                            )
+                                        cx.notify();
+                                    }),
+                                )
+                            })
                     })),
             )
     }
@@ -693,6 +954,346 @@ impl SettingsWindow {
             )
     }
 
+    /// Renders where a provider's CLI was found, with a "Locate binary…"
+    /// picker for installs that aren't on PATH.
+    fn render_binary_path_row(
+        &self,
+        provider: ProviderKind,
+        cli_name: &str,
+        binary_path: Option<PathBuf>,
+        found_binary: Option<PathBuf>,
+        cli_version: Option<VersionCheck>,
+        theme: SettingsTheme,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let hover_bg = theme.hover;
+        let version_warning = cli_version.as_ref().and_then(VersionCheck::warning);
+        let version = cli_version
+            .and_then(|check| check.version)
+            .map(|version| format!("v{}", version));
+        let location = match (&binary_path, &found_binary) {
+            (Some(path), _) => path.display().to_string(),
+            (None, Some(found)) => format!("{} (found automatically)", found.display()),
+            (None, None) => "not found".to_string(),
+        };
+        let cli_name = cli_name.to_string();
+
+        div()
+            .pl(px(44.0)) // Indent to align with name
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("CLI path:"),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .font_family("monospace")
+                    .text_color(if found_binary.is_some() {
+                        theme.text_muted
+                    } else {
+                        theme.warning
+                    })
+                    .child(location),
+            )
+            .when_some(version, |el, version| {
+                el.child(div().text_xs().text_color(theme.text_muted).child(version))
+            })
+            .when_some(version_warning, |el, warning| {
+                el.child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.warning)
+                        .child(format!("⚠ {}", warning)),
+                )
+            })
+            .child(
+                div()
+                    .id(SharedString::from(format!("locate-binary-{:?}", provider)))
+                    .px(px(8.0))
+                    .py(px(2.0))
+                    .rounded(px(4.0))
+                    .bg(theme.selected)
+                    .text_xs()
+                    .cursor_pointer()
+                    .hover(move |s| s.bg(hover_bg))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |_this, _, _window, cx| {
+                            let cli_name = cli_name.clone();
+                            cx.spawn(async move |_, mut cx| {
+                                if let Some(path) = prompt_for_binary_async(&cli_name).await {
+                                    let _ = cx.update_global::<AppState, _>(|state, cx| {
+                                        state.settings.update(cx, |model, _| {
+                                            model.set_binary_path(provider, Some(path));
+                                        });
+                                        state.refresh_provider(provider, cx);
+                                    });
+                                }
+                            })
+                            .detach();
+                        }),
+                    )
+                    .child("Locate binary…"),
+            )
+            .when(binary_path.is_some(), |el| {
+                el.child(
+                    div()
+                        .id(SharedString::from(format!("reset-binary-{:?}", provider)))
+                        .px(px(8.0))
+                        .py(px(2.0))
+                        .rounded(px(4.0))
+                        .bg(theme.selected)
+                        .text_xs()
+                        .cursor_pointer()
+                        .hover(move |s| s.bg(hover_bg))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |_this, _, _window, cx| {
+                                cx.update_global::<AppState, _>(|state, cx| {
+                                    state.settings.update(cx, |model, _| {
+                                        model.set_binary_path(provider, None);
+                                    });
+                                });
+                                cx.notify();
+                            }),
+                        )
+                        .child("Reset"),
+                )
+            })
+    }
+
+    /// Renders the switcher group chips, with "New group…" to name another.
+    fn render_group_selector(
+        &self,
+        provider: ProviderKind,
+        provider_name: &str,
+        current: Option<String>,
+        groups: &[String],
+        theme: SettingsTheme,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let hover_bg = theme.hover;
+        let provider_name = provider_name.to_string();
+        let options: Vec<Option<String>> = std::iter::once(None)
+            .chain(groups.iter().cloned().map(Some))
+            .collect();
+
+        div()
+            .pl(px(44.0)) // Indent to align with name
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(div().text_xs().text_color(theme.text_muted).child("Group:"))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap(px(4.0))
+                    .children(options.into_iter().enumerate().map(|(index, group)| {
+                        let is_selected = current == group;
+                        let selected_bg = theme.selected;
+                        let default_bg = theme.bg;
+                        let accent = theme.link;
+                        let border = theme.border;
+                        let label = group.clone().unwrap_or_else(|| "None".to_string());
+
+                        div()
+                            .id(SharedString::from(format!(
+                                "group-{:?}-{}",
+                                provider, index
+                            )))
+                            .text_xs()
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .bg(if is_selected { selected_bg } else { default_bg })
+                            .border_1()
+                            .border_color(if is_selected { accent } else { border })
+                            .child(label)
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |_this, _, _window, cx| {
+                                    let group = group.clone();
+                                    cx.update_global::<AppState, _>(|state, cx| {
+                                        state.settings.update(cx, |model, _| {
+                                            model.set_provider_group(provider, group)
+                                        });
+                                    });
+                                    cx.notify();
+                                }),
+                            )
+                    }))
+                    .child(
+                        div()
+                            .id(SharedString::from(format!("group-{:?}-new", provider)))
+                            .text_xs()
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .rounded(px(4.0))
+                            .bg(theme.selected)
+                            .cursor_pointer()
+                            .hover(move |s| s.bg(hover_bg))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |_this, _, _window, cx| {
+                                    let provider_name = provider_name.clone();
+                                    cx.spawn(async move |_, mut cx| {
+                                        if let Some(group) =
+                                            prompt_for_group_async(&provider_name).await
+                                        {
+                                            let _ = cx.update_global::<AppState, _>(|state, cx| {
+                                                state.settings.update(cx, |model, _| {
+                                                    model.set_provider_group(provider, Some(group));
+                                                });
+                                            });
+                                        }
+                                    })
+                                    .detach();
+                                }),
+                            )
+                            .child("New group…"),
+                    ),
+            )
+    }
+
+    /// Renders the sandbox toggles for a provider's CLI commands.
+    ///
+    /// A restricted PATH has no toggle; it's set with `sandbox.path` in
+    /// the settings file and kept when these change.
+    fn render_sandbox_selector(
+        &self,
+        provider: ProviderKind,
+        current: ProcessSandbox,
+        theme: SettingsTheme,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let options: [(&'static str, &'static str, bool); 2] = [
+            ("clean-env", "Clean environment", current.clean_env),
+            ("no-network", "No network", current.no_network),
+        ];
+
+        div()
+            .pl(px(44.0)) // Indent to align with name
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("CLI sandbox:"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap(px(4.0))
+                    .children(options.into_iter().map(|(id, label, is_selected)| {
+                        let selected_bg = theme.selected;
+                        let default_bg = theme.bg;
+                        let accent = theme.link;
+                        let border = theme.border;
+                        let current = current.clone();
+
+                        div()
+                            .id(SharedString::from(format!("sandbox-{:?}-{}", provider, id)))
+                            .text_xs()
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .bg(if is_selected { selected_bg } else { default_bg })
+                            .border_1()
+                            .border_color(if is_selected { accent } else { border })
+                            .child(label)
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |_this, _, _window, cx| {
+                                    let mut sandbox = current.clone();
+                                    match id {
+                                        "clean-env" => sandbox.clean_env = !sandbox.clean_env,
+                                        _ => sandbox.no_network = !sandbox.no_network,
+                                    }
+                                    cx.update_global::<AppState, _>(|state, cx| {
+                                        state.settings.update(cx, |model, _| {
+                                            model.set_sandbox(provider, sandbox)
+                                        });
+                                    });
+                                    cx.notify();
+                                }),
+                            )
+                    })),
+            )
+    }
+
+    /// Renders a toggle per fetch strategy, in the order they're tried.
+    fn render_strategy_selector(
+        &self,
+        provider: ProviderKind,
+        strategies: &[(String, FetchKind, bool)],
+        locked: bool,
+        theme: SettingsTheme,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        div()
+            .pl(px(44.0)) // Indent to align with name
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Strategies:"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap(px(4.0))
+                    .children(strategies.iter().map(|(id, kind, is_enabled)| {
+                        let label = format!("{} ({})", id, kind.display_name());
+                        let selected_bg = theme.selected;
+                        let default_bg = theme.bg;
+                        let accent = theme.link;
+                        let border = theme.border;
+                        let id = id.clone();
+                        let enabled = *is_enabled;
+
+                        div()
+                            .id(SharedString::from(format!("strategy-{}", id)))
+                            .text_xs()
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .rounded(px(4.0))
+                            .bg(if enabled { selected_bg } else { default_bg })
+                            .border_1()
+                            .border_color(if enabled { accent } else { border })
+                            .when(!enabled, |el| el.text_color(theme.text_muted))
+                            .child(label)
+                            .when(!locked, |el| {
+                                el.cursor_pointer().on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(move |_this, _, _window, cx| {
+                                        cx.update_global::<AppState, _>(|state, cx| {
+                                            state.settings.update(cx, |model, _| {
+                                                model.set_strategy_enabled(provider, &id, !enabled)
+                                            });
+                                        });
+                                        cx.notify();
+                                    }),
+                                )
+                            })
+                    })),
+            )
+    }
+
     /// Creates a sidebar item with a click handler to switch panes.
     fn sidebar_item(
         &self,
@@ -718,7 +1319,7 @@ impl SettingsWindow {
             .on_mouse_down(
                 MouseButton::Left,
                 cx.listener(move |this, _, _window, cx| {
-                    this.active_pane = pane;
+                    this.set_active_pane(pane);
                     cx.notify();
                 }),
             )
@@ -736,4 +1337,95 @@ impl SettingsWindow {
             item.hover(move |s| s.bg(hover_bg))
         }
     }
+
+    /// Renders the search field at the top of the sidebar.
+    fn render_search_field(
+        &mut self,
+        theme: &SettingsTheme,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        let focus = self
+            .search_focus
+            .get_or_insert_with(|| cx.focus_handle())
+            .clone();
+        let focused = focus.is_focused(window);
+
+        let text = if self.search_query.is_empty() {
+            div().text_color(theme.text_muted).child("Search settings")
+        } else {
+            div().child(self.search_query.clone())
+        };
+
+        div()
+            .id("settings-search")
+            .track_focus(&focus)
+            .on_key_down(cx.listener(Self::on_search_key_down))
+            .on_mouse_down(MouseButton::Left, move |_, window, _cx| {
+                focus.focus(window);
+            })
+            .mb(px(8.0))
+            .px(px(10.0))
+            .py(px(6.0))
+            .rounded(px(6.0))
+            .border_1()
+            .border_color(if focused { theme.link } else { theme.border })
+            .bg(theme.bg)
+            .cursor_text()
+            .text_sm()
+            .child(text)
+    }
+
+    /// Renders the settings matching the search, in place of a pane.
+    fn render_search_results(&self, theme: SettingsTheme, cx: &mut Context<Self>) -> Div {
+        let results = search::search(&self.search_query);
+        let hover_bg = theme.hover;
+
+        div()
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap(px(4.0))
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::BOLD)
+                    .mb(px(12.0))
+                    .child("Search Results"),
+            )
+            .when(results.is_empty(), |el| {
+                el.child(div().text_sm().text_color(theme.text_muted).child(format!(
+                    "No settings match \"{}\"",
+                    self.search_query.trim()
+                )))
+            })
+            .children(results.into_iter().map(|setting| {
+                let location = if setting.section == setting.title {
+                    setting.pane.title().to_string()
+                } else {
+                    format!("{} › {}", setting.pane.title(), setting.section)
+                };
+                div()
+                    .id(setting.id)
+                    .px(px(12.0))
+                    .py(px(8.0))
+                    .rounded(px(6.0))
+                    .cursor_pointer()
+                    .flex()
+                    .flex_col()
+                    .gap(px(2.0))
+                    .hover(move |s| s.bg(hover_bg))
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(setting.title),
+                    )
+                    .child(div().text_xs().text_color(theme.text_muted).child(location))
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.open_search_result(&setting);
+                        cx.notify();
+                    }))
+            }))
+    }
 }
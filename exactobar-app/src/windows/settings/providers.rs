@@ -1,9 +1,12 @@
 //! Providers settings pane - helper types and functions.
 
+use std::path::PathBuf;
 use std::process::Command;
 
 use exactobar_core::ProviderKind;
-use exactobar_providers::ProviderRegistry;
+use exactobar_fetch::host::discovery::find_binary;
+use exactobar_fetch::{FetchKind, ProcessSandbox, StrategySettings};
+use exactobar_providers::{PlannedStrategy, ProviderRegistry, VersionCheck, compat};
 use exactobar_store::{CookieSource, DataSourceMode};
 use gpui::{Context, Hsla};
 
@@ -97,11 +100,12 @@ pub fn detect_provider_status(provider: ProviderKind) -> ProviderStatus {
     let cli_name = match provider {
         ProviderKind::Codex => "codex",
         ProviderKind::Claude => "claude",
-        ProviderKind::Copilot => "gh",
+        // GitHub Models uses the same GitHub token as Copilot
+        ProviderKind::Copilot | ProviderKind::GitHubModels => "gh",
         ProviderKind::Gemini => "gcloud",
         ProviderKind::Kiro => {
             // Kiro can be either "kiro-cli" or "kiro"
-            if which::which("kiro-cli").is_ok() || which::which("kiro").is_ok() {
+            if find_binary("kiro-cli").is_some() || find_binary("kiro").is_some() {
                 return ProviderStatus::Available;
             }
             return ProviderStatus::CliMissing;
@@ -123,6 +127,22 @@ pub fn detect_provider_status(provider: ProviderKind) -> ProviderStatus {
             }
             return ProviderStatus::AuthRequired;
         }
+        ProviderKind::HuggingFace => {
+            // Check Keychain first, then env var
+            if exactobar_store::has_api_key("huggingface") || std::env::var("HF_TOKEN").is_ok() {
+                return ProviderStatus::Available;
+            }
+            return ProviderStatus::AuthRequired;
+        }
+        ProviderKind::Replicate => {
+            // Check Keychain first, then env var
+            if exactobar_store::has_api_key("replicate")
+                || std::env::var("REPLICATE_API_TOKEN").is_ok()
+            {
+                return ProviderStatus::Available;
+            }
+            return ProviderStatus::AuthRequired;
+        }
         ProviderKind::Zai => {
             // Check Keychain for z.ai API key
             if exactobar_store::has_api_key("zai") {
@@ -136,8 +156,8 @@ pub fn detect_provider_status(provider: ProviderKind) -> ProviderStatus {
         }
     };
 
-    // Check if CLI exists using the which crate
-    if which::which(cli_name).is_err() {
+    // Check if CLI exists, including binary overrides and extra search paths
+    if find_binary(cli_name).is_none() {
         return ProviderStatus::CliMissing;
     }
 
@@ -156,6 +176,8 @@ pub fn get_install_command(provider: ProviderKind) -> &'static str {
         ProviderKind::Kiro => "npm install -g kiro-cli",
         ProviderKind::Synthetic => "Configure API key in Settings",
         ProviderKind::Zai => "Configure API key in Settings",
+        ProviderKind::HuggingFace => "Configure API key in Settings",
+        ProviderKind::Replicate => "Configure API key in Settings",
         _ => "See provider documentation",
     }
 }
@@ -171,7 +193,11 @@ pub fn get_install_command(provider: ProviderKind) -> &'static str {
 pub fn provider_needs_api_key(provider: ProviderKind) -> bool {
     matches!(
         provider,
-        ProviderKind::Synthetic | ProviderKind::Zai | ProviderKind::Codex
+        ProviderKind::Synthetic
+            | ProviderKind::Zai
+            | ProviderKind::Codex
+            | ProviderKind::HuggingFace
+            | ProviderKind::Replicate
     )
 }
 
@@ -184,6 +210,8 @@ pub fn provider_api_key_name(provider: ProviderKind) -> &'static str {
         ProviderKind::Synthetic => "synthetic",
         ProviderKind::Zai => "zai",
         ProviderKind::Codex => "codex",
+        ProviderKind::HuggingFace => "huggingface",
+        ProviderKind::Replicate => "replicate",
         _ => "",
     }
 }
@@ -207,6 +235,8 @@ pub fn provider_has_api_key(provider: ProviderKind) -> bool {
         ProviderKind::Synthetic => std::env::var("SYNTHETIC_API_KEY").is_ok(),
         ProviderKind::Zai => std::env::var("ZAI_API_KEY").is_ok(),
         ProviderKind::Codex => std::env::var("OPENAI_API_KEY").is_ok(),
+        ProviderKind::HuggingFace => std::env::var("HF_TOKEN").is_ok(),
+        ProviderKind::Replicate => std::env::var("REPLICATE_API_TOKEN").is_ok(),
         _ => false,
     }
 }
@@ -244,6 +274,95 @@ pub fn prompt_for_api_key(provider_name: &str) -> Option<String> {
     None
 }
 
+/// Asks for a CLI binary using osascript (native macOS file picker).
+///
+/// Returns `None` if cancelled.
+pub fn prompt_for_binary(cli_name: &str) -> Option<PathBuf> {
+    let script = format!(
+        r#"POSIX path of (choose file with prompt "Locate the {} CLI:" default location (path to home folder) with invisibles)"#,
+        cli_name
+    );
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .ok()?;
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Asks for a new switcher group name using osascript (native macOS dialog).
+///
+/// Returns `None` if cancelled or left blank.
+pub fn prompt_for_group(provider_name: &str) -> Option<String> {
+    let script = format!(
+        r#"
+        set dialogResult to display dialog "Group to list {} under:" default answer "" buttons {{"Cancel", "Save"}} default button "Save"
+        if button returned of dialogResult is "Save" then
+            return text returned of dialogResult
+        else
+            return ""
+        end if
+        "#,
+        provider_name
+    );
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .ok()?;
+
+    let group = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !group.is_empty()).then_some(group)
+}
+
+/// Show a dialog asking where a password manager keeps a provider's key:
+/// a 1Password secret reference or a Bitwarden item.
+pub fn prompt_for_secret_reference(prompt: &str, current: &str) -> Option<String> {
+    let script = format!(
+        r#"
+        set dialogResult to display dialog "{}" default answer "{}" buttons {{"Cancel", "Save"}} default button "Save"
+        if button returned of dialogResult is "Save" then
+            return text returned of dialogResult
+        else
+            return ""
+        end if
+        "#,
+        prompt.replace('"', "\\\""),
+        current.replace('"', "\\\"")
+    );
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .ok()?;
+
+    let reference = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !reference.is_empty()).then_some(reference)
+}
+
+/// Async version of `prompt_for_secret_reference` that runs on a
+/// background thread.
+pub async fn prompt_for_secret_reference_async(prompt: String, current: String) -> Option<String> {
+    smol::unblock(move || prompt_for_secret_reference(&prompt, &current)).await
+}
+
+/// Async version of `prompt_for_group` that runs on a background thread.
+pub async fn prompt_for_group_async(provider_name: &str) -> Option<String> {
+    let name = provider_name.to_string();
+    smol::unblock(move || prompt_for_group(&name)).await
+}
+
+/// Async version of `prompt_for_binary` that runs on a background thread.
+pub async fn prompt_for_binary_async(cli_name: &str) -> Option<PathBuf> {
+    let name = cli_name.to_string();
+    smol::unblock(move || prompt_for_binary(&name)).await
+}
+
 /// Async version of `prompt_for_api_key` that runs on a background thread.
 ///
 /// Uses `smol::unblock` to run the blocking osascript command without
@@ -268,6 +387,19 @@ pub struct ProviderRowData {
     pub supports_data_source: bool,
     pub current_cookie_source: CookieSource,
     pub current_data_source: Option<DataSourceMode>,
+    /// Whether the provider runs a CLI that can be sandboxed
+    pub supports_sandbox: bool,
+    /// Sandbox for the provider's CLI commands
+    pub sandbox: ProcessSandbox,
+    /// Path the user set for the provider's CLI
+    pub binary_path: Option<PathBuf>,
+    /// Where the provider's CLI was found, if it was
+    pub found_binary: Option<PathBuf>,
+    /// The found CLI's version, once a refresh has detected it
+    pub cli_version: Option<VersionCheck>,
+    /// The provider's fetch strategies in the order they're tried, with
+    /// whether each may run
+    pub strategies: Vec<(String, FetchKind, bool)>,
     /// Provider availability status
     pub status: ProviderStatus,
     /// Whether this provider needs an API key
@@ -276,6 +408,12 @@ pub struct ProviderRowData {
     pub has_api_key: bool,
     /// Keychain storage name for the API key
     pub api_key_name: &'static str,
+    /// Whether credential changes are blocked (locked mode)
+    pub locked: bool,
+    /// Switcher group the provider is listed under
+    pub group: Option<String>,
+    /// Every group in use, to pick from
+    pub groups: Vec<String>,
 }
 
 /// Check if a provider supports cookie-based web fetching.
@@ -296,10 +434,34 @@ pub fn provider_supports_data_source(provider: ProviderKind) -> bool {
     matches!(provider, ProviderKind::Codex | ProviderKind::Claude)
 }
 
+/// Check if a provider runs CLI commands that can be sandboxed.
+pub fn provider_supports_sandbox(provider: ProviderKind) -> bool {
+    matches!(
+        provider,
+        ProviderKind::Codex
+            | ProviderKind::Claude
+            | ProviderKind::Gemini
+            | ProviderKind::Copilot
+            | ProviderKind::Kiro
+    )
+}
+
+/// Returns a provider's strategies in the order its settings have them
+/// tried.
+pub fn ordered_strategies(
+    provider: ProviderKind,
+    settings: &StrategySettings,
+) -> Vec<&'static PlannedStrategy> {
+    let mut strategies: Vec<_> = ProviderRegistry::strategies(provider).iter().collect();
+    strategies.sort_by_key(|s| std::cmp::Reverse(settings.priority(&s.id, s.priority)));
+    strategies
+}
+
 /// Collect all provider data for rendering.
 pub fn collect_provider_data<V: 'static>(cx: &Context<V>) -> Vec<ProviderRowData> {
     let state = cx.global::<AppState>();
     let settings = state.settings.read(cx);
+    let locked = settings.is_locked();
     let all_providers = ProviderRegistry::all();
 
     all_providers
@@ -310,6 +472,20 @@ pub fn collect_provider_data<V: 'static>(cx: &Context<V>) -> Vec<ProviderRowData
             let supports_cookies = provider_supports_cookies(provider);
             let supports_data_source = provider_supports_data_source(provider);
             let current_cookie_source = settings.cookie_source(provider);
+            let supports_sandbox = provider_supports_sandbox(provider);
+            let sandbox = settings.sandbox(provider);
+            let binary_path = settings.binary_path(provider);
+            let found_binary = supports_sandbox
+                .then(|| find_binary(desc.cli_name()))
+                .flatten();
+            let cli_version = supports_sandbox
+                .then(|| compat::cached_check(&desc.cli))
+                .flatten();
+            let strategy_settings = settings.strategies(provider);
+            let strategies = ordered_strategies(provider, &strategy_settings)
+                .into_iter()
+                .map(|s| (s.id.clone(), s.kind, strategy_settings.is_enabled(&s.id)))
+                .collect();
             let current_data_source = if supports_data_source {
                 Some(match provider {
                     ProviderKind::Codex => settings.codex_data_source(),
@@ -338,10 +514,19 @@ pub fn collect_provider_data<V: 'static>(cx: &Context<V>) -> Vec<ProviderRowData
                 supports_data_source,
                 current_cookie_source,
                 current_data_source,
+                supports_sandbox,
+                sandbox,
+                binary_path,
+                found_binary,
+                cli_version,
+                strategies,
                 status,
                 needs_api_key,
                 has_api_key,
                 api_key_name,
+                locked,
+                group: settings.provider_group(provider).map(str::to_string),
+                groups: settings.settings().provider_groups(),
             }
         })
         .collect()
@@ -0,0 +1,223 @@
+//! Settings search.
+//!
+//! Every searchable row is listed in [`registry`] with the pane and section
+//! it's on and words it should be found by. Panes mark the row's element
+//! with [`Searchable::searchable`] using the same id, so when a result is
+//! picked the window can switch panes, highlight the row and scroll to it.
+
+use std::cell::RefCell;
+
+use exactobar_core::ProviderKind;
+use gpui::*;
+
+use super::SettingsPane::{self, Advanced, General, Providers};
+use super::SettingsTheme;
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// A setting that can be searched for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingDescriptor {
+    /// Id the pane marks the row with.
+    pub id: &'static str,
+    /// Pane the row is on.
+    pub pane: SettingsPane,
+    /// Section heading above the row.
+    pub section: &'static str,
+    /// Row title, as shown.
+    pub title: &'static str,
+    /// Other words it should be found by.
+    pub keywords: &'static str,
+}
+
+const fn setting(
+    id: &'static str,
+    pane: SettingsPane,
+    section: &'static str,
+    title: &'static str,
+    keywords: &'static str,
+) -> SettingDescriptor {
+    SettingDescriptor {
+        id,
+        pane,
+        section,
+        title,
+        keywords,
+    }
+}
+
+/// Rows on the General and Advanced panes.
+#[rustfmt::skip]
+const SETTINGS: &[SettingDescriptor] = &[
+    // General
+    setting("refresh_cadence", General, "Refresh Cadence", "Refresh Cadence", "interval auto minutes manual"),
+    setting("merge_icons", General, "Menu Bar Icons", "Merge icons", "single icon tray status"),
+    setting("sort_by_usage", General, "Menu Bar Icons", "Sort by usage", "order remaining constrained switcher tabs"),
+    setting("icon_visibility", General, "Menu Bar Icons", "Show icons", "hide full low threshold only remaining tray visible"),
+    setting("icon_layout", General, "Menu Bar Icons", "Layout", "bar ring gauge number meter tray"),
+    setting("adaptive_icon", General, "Menu Bar Icons", "Adaptive icon", "dark light appearance wallpaper template contrast"),
+    setting("icon_outline", General, "Menu Bar Icons", "Outline meter", "border contrast wallpaper readable"),
+    setting("attention_animation", General, "Critical Usage Animation", "Critical Usage Animation", "pulse blink intensity duration"),
+    setting("theme_mode", General, "Theme", "Theme", "dark light system appearance"),
+    setting("color_palette", General, "Theme", "Usage Colors", "palette colorblind high contrast accessibility"),
+    setting("usage_bars_show_used", General, "Display Options", "Show Used Percent", "remaining progress bars"),
+    setting("reset_times_show_absolute", General, "Display Options", "Absolute Reset Times", "clock countdown"),
+    setting("reset_timezone", General, "Display Options", "Reset Time Zone", "timezone utc"),
+    setting("brand_icon_with_percent", General, "Display Options", "Brand Icon with Percent", "menu bar provider logo"),
+    setting("switcher_shows_icons", General, "Display Options", "Switcher Shows Icons", "tabs"),
+    setting("menu_density", General, "Display Options", "Card Density", "compact detailed rows providers scrolling"),
+    // Advanced
+    setting("debug_mode", Advanced, "Advanced", "Debug Mode", "logging verbose troubleshooting"),
+    setting("redact_emails", Advanced, "Advanced", "Hide Emails in Logs", "privacy redact"),
+    setting("strict_parsing", Advanced, "Advanced", "Strict Parsing", "parse mode responses"),
+    setting("auto_refresh_on_wake", Advanced, "Advanced", "Auto-refresh on Wake", "sleep"),
+    setting("status_checks", Advanced, "Advanced", "Status Page Checks", "outages incidents"),
+    setting("quota_notifications", Advanced, "Advanced", "Quota Notifications", "alerts limits"),
+    setting("reset_notifications", Advanced, "Advanced", "Reset Notifications", "alerts"),
+    setting("notification_sounds", Advanced, "Advanced", "Notification Sounds", "alerts sound chime silent test rules"),
+    setting("failure_tolerance", Advanced, "Advanced", "Failure Tolerance", "flaky errors failed fetches stale hooks"),
+    setting("quiet_during_meetings", Advanced, "Advanced", "Quiet During Meetings", "calendar busy events pause alerts"),
+    setting("respect_focus", Advanced, "Advanced", "Respect Focus", "do not disturb dnd critical override"),
+    setting("cost_tracking", Advanced, "Advanced", "Cost Tracking", "spend logs dollars"),
+    setting("team_leaderboard", Advanced, "Advanced", "Team Leaderboard", "organization admin members top consumers"),
+    setting("random_blink", Advanced, "Advanced", "Random Blink", "animation surprise"),
+    setting("claude_web_extras", Advanced, "Advanced", "Claude Web Extras", "cookies browser weekly split max code"),
+    setting("show_credits_extras", Advanced, "Advanced", "Show Credits & Extras", "extra usage menu"),
+    setting("openai_web_access", Advanced, "Advanced", "OpenAI Web Access", "codex dashboard"),
+    setting("companion_extension", Advanced, "Advanced", "Companion Extension", "browser cookies endpoint token"),
+    setting("strategy_order", Advanced, "Strategy Order", "Strategy Order", "oauth cli fallback"),
+    setting("request_budget", Advanced, "Request Budget", "Request Budget", "limit requests per hour polling quota throttle cap"),
+    setting("keychain_access", Advanced, "Keychain Access", "Keychain Access", "chromium browser prompt"),
+    setting("stored_api_keys", Advanced, "Stored API Keys", "Stored API Keys", "keychain tokens audit rotate delete validate age environment variables import"),
+    setting("secret_backends", Advanced, "Password Managers", "Password Managers", "1password op bitwarden bw secrets api keys vault"),
+    setting("data_locations", Advanced, "Data Locations", "Data Locations", "config cache directory folder"),
+];
+
+/// Returns every searchable setting: the General and Advanced rows, then
+/// one per provider.
+pub fn registry() -> Vec<SettingDescriptor> {
+    SETTINGS
+        .iter()
+        .cloned()
+        .chain(
+            ProviderKind::all()
+                .iter()
+                .map(|provider| SettingDescriptor {
+                    id: provider.cli_name(),
+                    pane: Providers,
+                    section: "Providers",
+                    title: provider.display_name(),
+                    keywords: "provider enable api key cookies",
+                }),
+        )
+        .collect()
+}
+
+/// Returns the settings matching `query`, in registry order.
+///
+/// Every word of the query must appear (case-insensitive) in the title,
+/// section, pane or keywords. An empty query matches nothing.
+pub fn search(query: &str) -> Vec<SettingDescriptor> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    registry()
+        .into_iter()
+        .filter(|setting| {
+            let haystack = format!(
+                "{} {} {} {}",
+                setting.title,
+                setting.section,
+                setting.pane.title(),
+                setting.keywords
+            )
+            .to_lowercase();
+            words.iter().all(|word| haystack.contains(word.as_str()))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Highlighting
+// ============================================================================
+
+thread_local! {
+    /// Row picked from the search results, and the anchor that scrolls to it.
+    static TARGET: RefCell<Option<(&'static str, ScrollAnchor)>> = const { RefCell::new(None) };
+}
+
+/// Sets the row to highlight while the panes render.
+pub fn set_target(target: Option<(&'static str, ScrollAnchor)>) {
+    TARGET.with(|t| *t.borrow_mut() = target);
+}
+
+/// Marks settings rows so search results can jump to them.
+pub trait Searchable {
+    /// Highlights this row, and scrolls to it, if it's the search target.
+    fn searchable(self, id: &'static str, theme: SettingsTheme) -> Self;
+}
+
+impl Searchable for Div {
+    fn searchable(self, id: &'static str, theme: SettingsTheme) -> Self {
+        let anchor = TARGET.with(|t| {
+            t.borrow()
+                .as_ref()
+                .filter(|(target, _)| *target == id)
+                .map(|(_, anchor)| anchor.clone())
+        });
+        match anchor {
+            Some(anchor) => self
+                .bg(theme.selected)
+                .rounded(px(6.0))
+                .anchor_scroll(Some(anchor)),
+            None => self,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(query: &str) -> Vec<&'static str> {
+        search(query).into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_registry_ids_are_unique() {
+        let settings = registry();
+        for (i, a) in settings.iter().enumerate() {
+            assert!(
+                settings[i + 1..].iter().all(|b| a.id != b.id),
+                "duplicate id {}",
+                a.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        assert_eq!(
+            ids("notif"),
+            vec![
+                "quota_notifications",
+                "reset_notifications",
+                "notification_sounds"
+            ]
+        );
+        assert_eq!(ids("RESET notif"), vec!["reset_notifications"]);
+        assert_eq!(ids("colorblind"), vec!["color_palette"]);
+        assert_eq!(ids("claude"), vec!["claude_web_extras", "claude"]);
+        assert!(ids("general").contains(&"merge_icons"));
+        assert!(ids("").is_empty());
+        assert!(ids("nothing matches this").is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! Team leaderboard window.
+//!
+//! Ranks an organization's members by usage over the last week, from the
+//! Cursor and Claude admin APIs. Team reports change slowly and page
+//! through a whole organization, so the window refreshes on its own
+//! [`TEAM_REFRESH_INTERVAL`] rather than the provider refresh cadence.
+
+use std::time::Duration;
+
+use chrono::Local;
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use exactobar_providers::team::TEAM_PROVIDERS;
+use exactobar_providers::{TeamUsage, fetch_team_usage};
+use gpui::prelude::*;
+use gpui::*;
+use smol::Timer;
+use std::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::refresh::tokio_runtime;
+use crate::theme;
+
+/// Global handle to the team window (if open).
+static TEAM_WINDOW: Mutex<Option<AnyWindowHandle>> = Mutex::new(None);
+
+/// How often the open window fetches the team reports again.
+pub const TEAM_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Members listed per provider.
+const TOP_MEMBERS: usize = 10;
+
+// ============================================================================
+// Team Window
+// ============================================================================
+
+/// The team leaderboard window content.
+pub struct TeamWindow {
+    /// Each team provider's report; `None` while loading.
+    reports: Vec<(ProviderKind, Option<Result<TeamUsage, String>>)>,
+}
+
+impl TeamWindow {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let view = Self {
+            reports: TEAM_PROVIDERS.iter().map(|&p| (p, None)).collect(),
+        };
+
+        // Refresh until the window closes
+        cx.spawn(async move |this, cx| {
+            loop {
+                if Self::fetch_all(&this, cx).await.is_err() {
+                    break;
+                }
+                Timer::after(TEAM_REFRESH_INTERVAL).await;
+            }
+        })
+        .detach();
+
+        view
+    }
+
+    /// Fetches every team provider's report. Fails once the window is gone.
+    async fn fetch_all(this: &WeakEntity<Self>, cx: &mut AsyncApp) -> anyhow::Result<()> {
+        for provider in TEAM_PROVIDERS {
+            let result = smol::unblock(move || {
+                tokio_runtime().block_on(fetch_team_usage(provider, &HttpClient::new()))
+            })
+            .await
+            .map_err(|e| e.to_string());
+            if let Err(e) = &result {
+                warn!(provider = ?provider, error = %e, "Failed to fetch team usage");
+            }
+
+            this.update(cx, |view, cx| {
+                if let Some(entry) = view.reports.iter_mut().find(|(p, _)| *p == provider) {
+                    entry.1 = Some(result);
+                }
+                cx.notify();
+            })?;
+        }
+        Ok(())
+    }
+
+    fn render_report(
+        provider: ProviderKind,
+        report: Option<&Result<TeamUsage, String>>,
+    ) -> impl IntoElement {
+        let body = match report {
+            None => Self::render_message("Loading…").into_any_element(),
+            Some(Err(e)) => Self::render_message(e.clone()).into_any_element(),
+            Some(Ok(team)) => {
+                let ranks = team.top(TOP_MEMBERS);
+                if ranks.is_empty() {
+                    Self::render_message("No usage this week.").into_any_element()
+                } else {
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(6.))
+                        .children(ranks.into_iter().enumerate().map(|(i, rank)| {
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(2.))
+                                .child(
+                                    div()
+                                        .flex()
+                                        .justify_between()
+                                        .text_sm()
+                                        .child(format!("{}. {}", i + 1, rank.name))
+                                        .child(div().text_color(theme::muted()).child(format!(
+                                            "{} · {:.1}%",
+                                            team.unit.format(rank.amount),
+                                            rank.percent
+                                        ))),
+                                )
+                                .child(
+                                    div()
+                                        .h(px(4.))
+                                        .w_full()
+                                        .rounded(px(2.))
+                                        .bg(theme::track())
+                                        .child(
+                                            div()
+                                                .h_full()
+                                                .w(relative((rank.percent / 100.0) as f32))
+                                                .rounded(px(2.))
+                                                .bg(theme::accent()),
+                                        ),
+                                )
+                        }))
+                        .into_any_element()
+                }
+            }
+        };
+
+        let summary = match report {
+            Some(Ok(team)) => format!(
+                "{} total · updated {}",
+                team.unit.format(team.total()),
+                team.fetched_at.with_timezone(&Local).format("%H:%M")
+            ),
+            _ => String::new(),
+        };
+
+        div()
+            .p(px(12.))
+            .rounded(px(8.))
+            .bg(theme::card_background())
+            .border_1()
+            .border_color(theme::border())
+            .flex()
+            .flex_col()
+            .gap(px(8.))
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(provider.display_name()),
+                    )
+                    .child(div().text_xs().text_color(theme::muted()).child(summary)),
+            )
+            .child(body)
+    }
+
+    fn render_message(message: impl Into<SharedString>) -> Div {
+        div()
+            .text_sm()
+            .text_color(theme::muted())
+            .child(message.into())
+    }
+}
+
+impl Render for TeamWindow {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .bg(theme::surface_background())
+            .text_color(theme::text_primary())
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .px(px(16.))
+                    .py(px(12.))
+                    .border_b_1()
+                    .border_color(theme::border())
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("Team Leaderboard"),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme::muted())
+                            .child("Top members over the last 7 days"),
+                    ),
+            )
+            .child(
+                div()
+                    .id("team-reports")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .p(px(16.))
+                    .flex()
+                    .flex_col()
+                    .gap(px(12.))
+                    .children(
+                        self.reports.iter().map(|(provider, report)| {
+                            Self::render_report(*provider, report.as_ref())
+                        }),
+                    ),
+            )
+    }
+}
+
+/// Opens the team leaderboard window, or focuses it if already open.
+pub fn open_team(cx: &mut App) {
+    let existing = *TEAM_WINDOW.lock().unwrap();
+    if let Some(handle) = existing.and_then(|h| h.downcast::<TeamWindow>()) {
+        if handle
+            .update(cx, |_, window, _| {
+                window.activate_window();
+            })
+            .is_ok()
+        {
+            info!("Focused existing team window");
+            cx.activate(true);
+            return;
+        }
+        // Window was closed, continue to create new one
+    }
+
+    info!("Opening team leaderboard window");
+    cx.activate(true);
+
+    let bounds = Bounds::centered(None, size(px(520.0), px(560.0)), cx);
+    let options = WindowOptions {
+        titlebar: Some(TitlebarOptions {
+            title: Some("ExactoBar Team Leaderboard".into()),
+            appears_transparent: false,
+            traffic_light_position: None,
+        }),
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        focus: true,
+        show: true,
+        kind: WindowKind::Normal,
+        is_movable: true,
+        display_id: None,
+        window_background: WindowBackgroundAppearance::Opaque,
+        app_id: None,
+        window_min_size: Some(size(px(400.0), px(320.0))),
+        window_decorations: None,
+        is_minimizable: true,
+        is_resizable: true,
+        tabbing_identifier: None,
+    };
+
+    let result = cx.open_window(options, |window, cx| {
+        window.activate_window();
+        cx.new(TeamWindow::new)
+    });
+
+    match result {
+        Ok(handle) => {
+            let mut guard = TEAM_WINDOW.lock().unwrap();
+            *guard = Some(handle.into());
+        }
+        Err(e) => {
+            error!(error = ?e, "Failed to open team leaderboard window");
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Annotate command - attach notes to points in time.
+//!
+//! Notes like "ran evals" or "big refactor" are stored alongside usage
+//! history and shown on the app's usage graphs. With no text, lists the
+//! notes; `--format json` exports them.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use clap::Args;
+use exactobar_providers::ProviderRegistry;
+use exactobar_store::{NoteStore, UsageNote};
+use tracing::info;
+
+use crate::output::JsonFormatter;
+use crate::{Cli, OutputFormat};
+
+/// Arguments for the annotate command.
+#[derive(Args)]
+pub struct AnnotateArgs {
+    /// Note text, e.g. "ran evals". Omit to list notes.
+    pub text: Option<String>,
+
+    /// When the note applies: RFC 3339 or "YYYY-MM-DD HH:MM" local time
+    /// (default: now).
+    #[arg(long)]
+    pub at: Option<String>,
+
+    /// Remove the note with this number, as shown in the list.
+    #[arg(long, conflicts_with_all = ["text", "at"])]
+    pub remove: Option<usize>,
+}
+
+/// Runs the annotate command.
+pub async fn run(args: &AnnotateArgs, cli: &Cli) -> Result<()> {
+    let mut store = NoteStore::load_default().await?;
+
+    if let Some(number) = args.remove {
+        let note = number
+            .checked_sub(1)
+            .and_then(|index| store.remove(index))
+            .with_context(|| format!("No note number {number}"))?;
+        store.save().await?;
+        println!("Removed note: {}", note.text);
+        return Ok(());
+    }
+
+    let Some(text) = &args.text else {
+        return list_notes(&store, cli);
+    };
+
+    let provider = match cli.provider.as_deref() {
+        Some(name) => Some(
+            ProviderRegistry::get_by_cli_name(name)
+                .with_context(|| format!("Unknown provider: {name}"))?
+                .id,
+        ),
+        None => None,
+    };
+    let timestamp = match &args.at {
+        Some(at) => parse_time(at).with_context(|| {
+            format!("Can't read time {at:?}; use RFC 3339 or \"YYYY-MM-DD HH:MM\"")
+        })?,
+        None => Utc::now(),
+    };
+
+    let note = UsageNote {
+        timestamp,
+        text: text.clone(),
+        provider,
+    };
+    store.add(note)?;
+    store.save().await?;
+
+    info!(text = %text, "Note added");
+    println!(
+        "Noted at {}: {}",
+        timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+        text.trim()
+    );
+    Ok(())
+}
+
+fn list_notes(store: &NoteStore, cli: &Cli) -> Result<()> {
+    match cli.format {
+        OutputFormat::Text => {
+            if store.notes().is_empty() {
+                println!("No notes yet. Add one with: exactobar annotate \"ran evals\"");
+            }
+            for (i, note) in store.notes().iter().enumerate() {
+                let provider = note
+                    .provider
+                    .map(|p| format!(" [{}]", p.cli_name()))
+                    .unwrap_or_default();
+                println!(
+                    "{:>3}. {}{} {}",
+                    i + 1,
+                    note.timestamp
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M"),
+                    provider,
+                    note.text
+                );
+            }
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let formatter = JsonFormatter::new(cli.pretty);
+            println!("{}", formatter.format(&store.notes())?);
+        }
+    }
+    Ok(())
+}
+
+/// Parses an RFC 3339 timestamp or a local "YYYY-MM-DD HH:MM".
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(
+            parse_time("2025-01-15T16:00:00Z"),
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 16, 0, 0).unwrap())
+        );
+        let local = parse_time("2025-01-15 16:00").unwrap();
+        assert_eq!(
+            local.with_timezone(&Local).format("%H:%M").to_string(),
+            "16:00"
+        );
+        assert!(parse_time("yesterday").is_none());
+    }
+}
@@ -2,12 +2,23 @@
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use exactobar_core::{ResetZone, parse_clock};
+use exactobar_fetch::{ProcessSandbox, RequestHeaders};
 use exactobar_providers::ProviderRegistry;
-use exactobar_store::{SettingsStore, default_config_dir, default_settings_path};
+use exactobar_providers::sheets::ServiceAccount;
+use exactobar_store::{
+    AccountColor, AccountLabel, DATA_DIR_ENV, FailureTolerance, ProviderSettings, SecretBackend,
+    Settings, SettingsFormat, SettingsStore, SheetsExport, active_profile, checksum_matches,
+    checksum_path, create_profile, current_profile, custom_data_dir, data_dir_from_env,
+    default_cache_dir, default_config_dir, default_settings_path, list_profiles, save_json,
+    set_active_profile,
+};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 use crate::output::JsonFormatter;
-use crate::{Cli, OutputFormat};
+use crate::{Cli, ExitCode, OutputFormat};
 
 /// Arguments for the config command.
 #[derive(Args)]
@@ -43,10 +54,164 @@ pub enum ConfigAction {
         cadence: String,
     },
 
+    /// Set the timezone reset times are shown in.
+    Timezone {
+        /// IANA name like "Europe/Berlin", "UTC", or "local" for the system timezone.
+        zone: String,
+    },
+
+    /// Name a provider account and give it a color tag.
+    Label {
+        /// Provider the account belongs to.
+        provider: String,
+        /// Account email (or organization), as shown by `exactobar usage`.
+        account: String,
+        /// Label to show, e.g. "Work". Omit to remove the label.
+        name: Option<String>,
+        /// Color tag: gray, red, orange, yellow, green, blue, purple, pink.
+        #[arg(long, default_value = "gray")]
+        color: String,
+    },
+
+    /// Send a provider's API requests to a gateway instead of its public
+    /// endpoint.
+    Endpoint {
+        /// Provider to configure.
+        provider: String,
+        /// Base URL, e.g. "https://llm-gateway.example.com/anthropic".
+        /// Omit to use the provider's own endpoint again.
+        url: Option<String>,
+    },
+
+    /// Set the User-Agent and extra headers sent with a provider's
+    /// requests, for endpoints that fingerprint clients.
+    Headers {
+        /// Provider to configure.
+        provider: String,
+        /// User-Agent to send instead of the built-in one.
+        #[arg(long)]
+        user_agent: Option<String>,
+        /// Extra header, e.g. "X-Team: research". Repeat for more; "Name:"
+        /// with no value removes one.
+        #[arg(long = "header", value_name = "NAME: VALUE")]
+        headers: Vec<String>,
+        /// Remove the configured User-Agent and headers first.
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Cap how many fetches go out per hour, for all providers or one.
+    Budget {
+        /// Most fetches per hour. Omit to remove the limit.
+        per_hour: Option<u32>,
+        /// Provider to limit instead of all of them together.
+        #[arg(long)]
+        provider: Option<String>,
+    },
+
+    /// Pace a provider's session window to last until a time of day.
+    Burn {
+        /// Provider to plan.
+        provider: String,
+        /// Local time the quota should last until, e.g. "18:00" or "6pm".
+        /// Omit to stop pacing.
+        until: Option<String>,
+    },
+
+    /// Export daily usage to a Google Sheet. Omit the spreadsheet to stop.
+    Sheets {
+        /// Spreadsheet id, from the sheet's URL.
+        spreadsheet: Option<String>,
+        /// Service account key file (JSON). Share the sheet with the
+        /// account's email as an editor.
+        credentials: Option<PathBuf>,
+    },
+
+    /// Choose the Google Sheet tab a provider's daily rows are appended to.
+    SheetsTab {
+        /// Provider to export.
+        provider: String,
+        /// Tab name, e.g. "Usage". Omit to stop exporting the provider.
+        tab: Option<String>,
+    },
+
+    /// List a provider under a group in the menu's switcher.
+    Group {
+        /// Provider to group.
+        provider: String,
+        /// Group name, e.g. "Coding". Omit to ungroup the provider.
+        group: Option<String>,
+    },
+
+    /// Let a flaky provider fail a few times before it's shown as failing.
+    Tolerance {
+        /// Provider to configure.
+        provider: String,
+        /// Failed fetches in a row before alerting. Omit to use the global
+        /// tolerance again.
+        failures: Option<u32>,
+        /// Also alert after this many minutes without a good fetch.
+        #[arg(long)]
+        stale: Option<u32>,
+    },
+
+    /// Read a provider's API keys from 1Password or Bitwarden instead of
+    /// the keychain.
+    Secret {
+        /// Provider to configure.
+        provider: String,
+        /// Where keys are read from: keychain, 1password or bitwarden.
+        backend: String,
+        /// 1Password secret reference (op://vault/item/field) or Bitwarden
+        /// item name or id.
+        reference: Option<String>,
+    },
+
+    /// Open the settings file in $VISUAL or $EDITOR.
+    Edit,
+
+    /// Check the settings file and print the effective configuration.
+    Validate,
+
+    /// Switch the settings file between JSON and TOML. TOML has stable key
+    /// order and no checksum file, so it can live in a dotfiles repo.
+    Format {
+        /// Format: json or toml.
+        format: String,
+    },
+
+    /// Manage workspace profiles.
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
     /// Reset to defaults.
     Reset,
 }
 
+/// Profile subcommands.
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// List profiles, marking the active one.
+    List,
+
+    /// Create a profile with default settings.
+    Create {
+        /// Profile name, e.g. "Client A".
+        name: String,
+        /// Also make the new profile active.
+        #[arg(long = "use")]
+        activate: bool,
+    },
+
+    /// Make a profile the active one for the app and the CLI.
+    Use {
+        /// Profile name.
+        name: String,
+    },
+}
+
 /// Runs the config command.
 pub async fn run(args: &ConfigArgs, cli: &Cli) -> Result<()> {
     match &args.action {
@@ -55,6 +220,52 @@ pub async fn run(args: &ConfigArgs, cli: &Cli) -> Result<()> {
         ConfigAction::Enable { provider } => enable_provider(provider, cli).await,
         ConfigAction::Disable { provider } => disable_provider(provider, cli).await,
         ConfigAction::Refresh { cadence } => set_refresh(cadence, cli).await,
+        ConfigAction::Timezone { zone } => set_timezone(zone, cli).await,
+        ConfigAction::Label {
+            provider,
+            account,
+            name,
+            color,
+        } => set_label(provider, account, name.as_deref(), color, cli).await,
+        ConfigAction::Endpoint { provider, url } => {
+            set_endpoint(provider, url.as_deref(), cli).await
+        }
+        ConfigAction::Headers {
+            provider,
+            user_agent,
+            headers,
+            reset,
+        } => set_request_headers(provider, user_agent.as_deref(), headers, *reset, cli).await,
+        ConfigAction::Budget { per_hour, provider } => {
+            set_request_budget(*per_hour, provider.as_deref(), cli).await
+        }
+        ConfigAction::Burn { provider, until } => {
+            set_burn_target(provider, until.as_deref(), cli).await
+        }
+        ConfigAction::Sheets {
+            spreadsheet,
+            credentials,
+        } => set_sheets_export(spreadsheet.as_deref(), credentials.as_deref(), cli).await,
+        ConfigAction::SheetsTab { provider, tab } => {
+            set_sheets_tab(provider, tab.as_deref(), cli).await
+        }
+        ConfigAction::Group { provider, group } => {
+            set_provider_group(provider, group.as_deref(), cli).await
+        }
+        ConfigAction::Tolerance {
+            provider,
+            failures,
+            stale,
+        } => set_failure_tolerance(provider, *failures, *stale, cli).await,
+        ConfigAction::Secret {
+            provider,
+            backend,
+            reference,
+        } => set_secret_backend(provider, backend, reference.as_deref(), cli).await,
+        ConfigAction::Edit => edit_config(cli).await,
+        ConfigAction::Validate => validate_config(cli).await,
+        ConfigAction::Format { format } => convert_config(format, cli).await,
+        ConfigAction::Profile { action } => run_profile(action, cli).await,
         ConfigAction::Reset => reset_config(cli).await,
     }
 }
@@ -63,11 +274,20 @@ async fn show_config(cli: &Cli) -> Result<()> {
     let store = SettingsStore::load_default().await?;
     let settings = store.get().await;
 
+    if let Some(issue) = store.load_issue() {
+        match store.restored_from() {
+            Some(backup) => eprintln!("Warning: {issue}; restored from {}", backup.display()),
+            None => eprintln!("Warning: {issue}; showing defaults"),
+        }
+    }
+
     match cli.format {
         OutputFormat::Text => {
             println!("ExactoBar Configuration");
             println!("{}", "─".repeat(40));
             println!();
+            println!("Profile: {}", current_profile());
+            println!();
             println!("Enabled providers:");
             for provider in &settings.enabled_providers {
                 if let Some(desc) = ProviderRegistry::get(*provider) {
@@ -78,9 +298,200 @@ async fn show_config(cli: &Cli) -> Result<()> {
             println!("Refresh cadence: {}", settings.refresh_cadence);
             println!("Auto-refresh on wake: {}", settings.auto_refresh_on_wake);
             println!("Merge icons: {}", settings.merge_icons);
+            println!(
+                "Reset timezone: {}",
+                settings.reset_timezone.as_deref().unwrap_or("local")
+            );
             println!("Debug mode: {}", settings.debug_mode);
+            println!("Redact emails in logs: {}", settings.redact_emails_in_logs);
+            println!("Parse mode: {}", settings.parse_mode);
+            if settings.locked_mode {
+                println!("Locked mode: on (credential changes are disabled in the app)");
+            }
+
+            let mut labeled: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .flat_map(|(provider, ps)| {
+                    ps.account_labels
+                        .iter()
+                        .map(move |(account, label)| (provider.cli_name(), account, label))
+                })
+                .collect();
+            if !labeled.is_empty() {
+                labeled.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+                println!();
+                println!("Account labels:");
+                for (provider, account, label) in labeled {
+                    println!(
+                        "  • {} {}: {} ({})",
+                        provider, account, label.name, label.color
+                    );
+                }
+            }
+
+            let mut binaries: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter_map(|(provider, ps)| Some((provider.cli_name(), ps.binary_path.as_ref()?)))
+                .collect();
+            if !binaries.is_empty() || !settings.extra_search_paths.is_empty() {
+                binaries.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("CLI paths:");
+                for (provider, path) in binaries {
+                    println!("  • {}: {}", provider, path.display());
+                }
+                for dir in &settings.extra_search_paths {
+                    println!("  • also searched: {}", dir.display());
+                }
+            }
+
+            let mut endpoints: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter_map(|(provider, ps)| Some((provider.cli_name(), ps.base_url.as_ref()?)))
+                .collect();
+            if !endpoints.is_empty() {
+                endpoints.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("API endpoints:");
+                for (provider, url) in endpoints {
+                    println!("  • {}: {}", provider, url);
+                }
+            }
+
+            let mut custom_headers: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter(|(_, ps)| !ps.request_headers.is_default())
+                .map(|(provider, ps)| (provider.cli_name(), &ps.request_headers))
+                .collect();
+            if !custom_headers.is_empty() {
+                custom_headers.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Request headers:");
+                for (provider, headers) in custom_headers {
+                    let mut names: Vec<&str> = headers.headers.keys().map(String::as_str).collect();
+                    if headers.user_agent.is_some() {
+                        names.insert(0, "User-Agent");
+                    }
+                    println!("  • {}: {}", provider, names.join(", "));
+                }
+            }
+
+            let mut budgets: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter_map(|(provider, ps)| {
+                    Some((provider.cli_name(), ps.request_budget_per_hour?))
+                })
+                .collect();
+            if !budgets.is_empty() || settings.request_budget_per_hour.is_some() {
+                budgets.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Request budget:");
+                if let Some(per_hour) = settings.request_budget_per_hour {
+                    println!("  • all providers: {}/hour", per_hour);
+                }
+                for (provider, per_hour) in budgets {
+                    println!("  • {}: {}/hour", provider, per_hour);
+                }
+            }
+
+            let mut burn_targets: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter_map(|(provider, ps)| Some((provider.cli_name(), ps.burn_target?)))
+                .collect();
+            if !burn_targets.is_empty() {
+                burn_targets.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Burn targets:");
+                for (provider, target) in burn_targets {
+                    println!("  • {}: until {}", provider, target.format("%H:%M"));
+                }
+            }
+
+            if let Some(export) = &settings.sheets_export {
+                println!();
+                println!("Google Sheets export: {}", export.spreadsheet_id);
+                println!("  Credentials: {}", export.credentials_path.display());
+                for (provider, tab) in settings.sheets_tabs() {
+                    println!("  • {}: {}", provider.cli_name(), tab);
+                }
+            }
+
+            let groups = settings.provider_groups();
+            if !groups.is_empty() {
+                println!();
+                println!("Switcher groups:");
+                for group in groups {
+                    let mut members: Vec<_> = settings
+                        .provider_settings
+                        .iter()
+                        .filter(|(_, ps)| ps.group.as_deref() == Some(group.as_str()))
+                        .map(|(provider, _)| provider.cli_name())
+                        .collect();
+                    members.sort_unstable();
+                    println!("  • {}: {}", group, members.join(", "));
+                }
+            }
+
+            let mut sandboxed: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter(|(_, ps)| ps.sandbox.is_enabled())
+                .map(|(provider, ps)| (provider.cli_name(), &ps.sandbox))
+                .collect();
+            if !sandboxed.is_empty() {
+                sandboxed.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Sandboxed CLIs:");
+                for (provider, sandbox) in sandboxed {
+                    println!("  • {}: {}", provider, describe_sandbox(sandbox));
+                }
+            }
+
+            let mut disabled: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter(|(_, ps)| !ps.strategies.disabled.is_empty())
+                .map(|(provider, ps)| (provider.cli_name(), ps.strategies.disabled.join(", ")))
+                .collect();
+            if !disabled.is_empty() {
+                disabled.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Disabled strategies:");
+                for (provider, strategies) in disabled {
+                    println!("  • {}: {}", provider, strategies);
+                }
+            }
+
+            let mut reordered: Vec<_> = settings
+                .provider_settings
+                .iter()
+                .filter(|(_, ps)| !ps.strategies.priorities.is_empty())
+                .map(|(provider, ps)| {
+                    let priorities: Vec<_> = ps
+                        .strategies
+                        .priorities
+                        .iter()
+                        .map(|(id, priority)| format!("{id}={priority}"))
+                        .collect();
+                    (provider.cli_name(), priorities.join(", "))
+                })
+                .collect();
+            if !reordered.is_empty() {
+                reordered.sort_by_key(|(provider, _)| *provider);
+                println!();
+                println!("Strategy priorities:");
+                for (provider, priorities) in reordered {
+                    println!("  • {}: {}", provider, priorities);
+                }
+            }
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Waybar => {
             let formatter = JsonFormatter::new(cli.pretty);
             let output = formatter.format(&settings)?;
             println!("{}", output);
@@ -90,21 +501,60 @@ async fn show_config(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Lists a sandbox's restrictions, e.g. "clean env, PATH=/usr/bin".
+fn describe_sandbox(sandbox: &ProcessSandbox) -> String {
+    let mut parts = Vec::new();
+    if sandbox.clean_env {
+        parts.push("clean env".to_string());
+    }
+    if !sandbox.path.is_empty() {
+        let dirs: Vec<_> = sandbox
+            .path
+            .iter()
+            .map(|d| d.display().to_string())
+            .collect();
+        parts.push(format!("PATH={}", dirs.join(":")));
+    }
+    if sandbox.no_network {
+        parts.push("no network".to_string());
+    }
+    parts.join(", ")
+}
+
 fn show_paths(cli: &Cli) -> Result<()> {
     let config_dir = default_config_dir();
+    let cache_dir = default_cache_dir();
     let settings_path = default_settings_path();
+    let data_dir = custom_data_dir();
+    let data_dir_source = if data_dir_from_env().is_some() {
+        Some(DATA_DIR_ENV)
+    } else {
+        data_dir.as_ref().map(|_| "settings")
+    };
 
     match cli.format {
         OutputFormat::Text => {
             println!("Configuration Paths");
             println!("{}", "─".repeat(40));
             println!();
+            match (&data_dir, data_dir_source) {
+                (Some(dir), Some(source)) => {
+                    println!("Data dir:      {} (from {source})", dir.display());
+                }
+                _ => println!("Data dir:      platform default"),
+            }
             println!("Config dir:    {}", config_dir.display());
+            println!("Cache dir:     {}", cache_dir.display());
+            println!("Profile:       {}", current_profile());
             println!("Settings file: {}", settings_path.display());
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Waybar => {
             let paths = serde_json::json!({
+                "data_dir": data_dir.map(|dir| dir.display().to_string()),
+                "data_dir_source": data_dir_source,
                 "config_dir": config_dir.display().to_string(),
+                "cache_dir": cache_dir.display().to_string(),
+                "profile": current_profile(),
                 "settings_file": settings_path.display().to_string(),
             });
             let formatter = JsonFormatter::new(cli.pretty);
@@ -165,6 +615,451 @@ async fn set_refresh(cadence: &str, _cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+async fn set_timezone(zone: &str, _cli: &Cli) -> Result<()> {
+    let parsed = ResetZone::parse(zone).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown timezone: {}. Use an IANA name like Europe/Berlin, UTC, or local",
+            zone
+        )
+    })?;
+
+    let store = SettingsStore::load_default().await?;
+    let setting = match parsed {
+        ResetZone::Local => None,
+        ResetZone::Named(_) => Some(parsed.to_string()),
+    };
+    store.set_reset_timezone(setting).await;
+    store.save().await?;
+
+    info!(zone = %parsed, "Reset timezone updated");
+    println!("Reset times shown in: {}", parsed);
+
+    Ok(())
+}
+
+async fn set_label(
+    provider: &str,
+    account: &str,
+    name: Option<&str>,
+    color: &str,
+    _cli: &Cli,
+) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+
+    let label = match name {
+        Some(name) => {
+            let color = AccountColor::from_name(color).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown color: {}. Use: gray, red, orange, yellow, green, blue, purple, pink",
+                    color
+                )
+            })?;
+            Some(AccountLabel {
+                name: name.to_string(),
+                color,
+            })
+        }
+        None => None,
+    };
+
+    let store = SettingsStore::load_default().await?;
+    store
+        .set_account_label(desc.id, account, label.clone())
+        .await;
+    store.save().await?;
+
+    match label {
+        Some(label) => {
+            info!(provider = %desc.display_name(), account, label = %label.name, "Account labeled");
+            println!(
+                "{} account {} labeled \"{}\" ({})",
+                desc.display_name(),
+                account,
+                label.name,
+                label.color
+            );
+        }
+        None => println!(
+            "Removed label from {} account {}",
+            desc.display_name(),
+            account
+        ),
+    }
+
+    Ok(())
+}
+
+async fn set_endpoint(provider: &str, url: Option<&str>, _cli: &Cli) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    let url = url.map(str::trim).filter(|url| !url.is_empty());
+    if let Some(url) = url {
+        check_base_url(url)?;
+    }
+
+    let store = SettingsStore::load_default().await?;
+    store.set_base_url(desc.id, url.map(str::to_string)).await;
+    store.save().await?;
+
+    match url {
+        Some(url) => {
+            info!(provider = %desc.display_name(), url, "Endpoint set");
+            println!("{} API requests now go to {}", desc.display_name(), url);
+        }
+        None => println!("{} uses its own API endpoint again", desc.display_name()),
+    }
+
+    Ok(())
+}
+
+async fn set_request_headers(
+    provider: &str,
+    user_agent: Option<&str>,
+    headers: &[String],
+    reset: bool,
+    _cli: &Cli,
+) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+
+    let store = SettingsStore::load_default().await?;
+    let mut configured = if reset {
+        RequestHeaders::default()
+    } else {
+        store.get().await.request_headers(desc.id)
+    };
+    if let Some(agent) = user_agent.map(str::trim) {
+        configured.user_agent = (!agent.is_empty()).then(|| agent.to_string());
+    }
+    for header in headers {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Expected \"Name: value\", got {:?}", header))?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            anyhow::bail!("Invalid header name: {:?}", name);
+        }
+        match value.trim() {
+            "" => configured.headers.remove(name),
+            value => configured
+                .headers
+                .insert(name.to_string(), value.to_string()),
+        };
+    }
+
+    store.set_request_headers(desc.id, configured.clone()).await;
+    store.save().await?;
+
+    if configured.is_default() {
+        println!("{} sends the built-in headers", desc.display_name());
+    } else {
+        info!(provider = %desc.display_name(), "Request headers set");
+        println!("{} requests send:", desc.display_name());
+        if let Some(agent) = &configured.user_agent {
+            println!("  User-Agent: {}", agent);
+        }
+        for (name, value) in &configured.headers {
+            println!("  {}: {}", name, value);
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_request_budget(
+    per_hour: Option<u32>,
+    provider: Option<&str>,
+    _cli: &Cli,
+) -> Result<()> {
+    if per_hour == Some(0) {
+        anyhow::bail!("The budget must allow at least one fetch per hour");
+    }
+    let desc = provider
+        .map(|provider| {
+            ProviderRegistry::get_by_cli_name(provider)
+                .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))
+        })
+        .transpose()?;
+
+    let store = SettingsStore::load_default().await?;
+    match desc {
+        Some(desc) => store.set_provider_request_budget(desc.id, per_hour).await,
+        None => store.set_request_budget(per_hour).await,
+    }
+    store.save().await?;
+
+    let scope = desc.map_or("All providers", |desc| desc.display_name());
+    match per_hour {
+        Some(per_hour) => {
+            info!(scope, per_hour, "Request budget set");
+            println!("{}: at most {} fetches per hour", scope, per_hour);
+        }
+        None => println!("{}: no request limit", scope),
+    }
+
+    Ok(())
+}
+
+async fn set_burn_target(provider: &str, until: Option<&str>, _cli: &Cli) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    let target = until
+        .map(|until| {
+            parse_clock(until).ok_or_else(|| {
+                anyhow::anyhow!("Invalid time: {} (use e.g. \"18:00\" or \"6pm\")", until)
+            })
+        })
+        .transpose()?;
+
+    let store = SettingsStore::load_default().await?;
+    store.set_burn_target(desc.id, target).await;
+    store.save().await?;
+
+    match target {
+        Some(target) => {
+            info!(provider = %desc.display_name(), %target, "Burn target set");
+            println!(
+                "{} {} window now paced to last until {}",
+                desc.display_name(),
+                desc.metadata.session_label.to_lowercase(),
+                target.format("%H:%M")
+            );
+        }
+        None => println!("{} is no longer paced", desc.display_name()),
+    }
+
+    Ok(())
+}
+
+async fn set_sheets_export(
+    spreadsheet: Option<&str>,
+    credentials: Option<&Path>,
+    _cli: &Cli,
+) -> Result<()> {
+    let export = match spreadsheet {
+        Some(spreadsheet) => {
+            let credentials = credentials.ok_or_else(|| {
+                anyhow::anyhow!("Pass the service account key file after the spreadsheet id")
+            })?;
+            let account = ServiceAccount::load(credentials)?;
+            println!("Share the sheet with {} as an editor", account.client_email);
+            Some(SheetsExport {
+                spreadsheet_id: spreadsheet.to_string(),
+                credentials_path: std::fs::canonicalize(credentials)?,
+            })
+        }
+        None => None,
+    };
+
+    let store = SettingsStore::load_default().await?;
+    let enabled = export.is_some();
+    store.set_sheets_export(export).await;
+    store.save().await?;
+
+    if enabled {
+        info!("Google Sheets export set");
+        println!("Daily usage will be exported for providers with a tab (config sheets-tab)");
+    } else {
+        println!("Google Sheets export stopped");
+    }
+
+    Ok(())
+}
+
+async fn set_sheets_tab(provider: &str, tab: Option<&str>, _cli: &Cli) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    let tab = tab.map(str::trim).filter(|tab| !tab.is_empty());
+
+    let store = SettingsStore::load_default().await?;
+    store.set_sheets_tab(desc.id, tab.map(str::to_string)).await;
+    store.save().await?;
+
+    match tab {
+        Some(tab) => println!(
+            "{} daily usage goes to the \"{}\" tab",
+            desc.display_name(),
+            tab
+        ),
+        None => println!("{} is no longer exported", desc.display_name()),
+    }
+
+    Ok(())
+}
+
+async fn set_provider_group(provider: &str, group: Option<&str>, _cli: &Cli) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    let group = group.map(str::trim).filter(|group| !group.is_empty());
+
+    let store = SettingsStore::load_default().await?;
+    store
+        .set_provider_group(desc.id, group.map(str::to_string))
+        .await;
+    store.save().await?;
+
+    match group {
+        Some(group) => println!("{} is listed under \"{}\"", desc.display_name(), group),
+        None => println!("{} is no longer grouped", desc.display_name()),
+    }
+
+    Ok(())
+}
+
+async fn set_failure_tolerance(
+    provider: &str,
+    failures: Option<u32>,
+    stale: Option<u32>,
+    _cli: &Cli,
+) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    if failures == Some(0) {
+        anyhow::bail!("Failures must be at least 1");
+    }
+    if failures.is_none() && stale.is_some() {
+        anyhow::bail!("Pass the number of failures before --stale");
+    }
+    let tolerance = failures.map(|failures| FailureTolerance {
+        failures,
+        stale_minutes: stale,
+    });
+
+    let store = SettingsStore::load_default().await?;
+    store
+        .set_provider_failure_tolerance(desc.id, tolerance)
+        .await;
+    store.save().await?;
+
+    match tolerance {
+        Some(tolerance) => {
+            print!(
+                "{} shows as failing after {} failed fetches in a row",
+                desc.display_name(),
+                tolerance.failures
+            );
+            match tolerance.stale_minutes {
+                Some(minutes) => println!(" or {} minutes without a good one", minutes),
+                None => println!(),
+            }
+        }
+        None => println!("{} uses the global failure tolerance", desc.display_name()),
+    }
+
+    Ok(())
+}
+
+async fn set_secret_backend(
+    provider: &str,
+    backend: &str,
+    reference: Option<&str>,
+    _cli: &Cli,
+) -> Result<()> {
+    let desc = ProviderRegistry::get_by_cli_name(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    if exactobar_store::keychain::providers::for_provider(desc.id).is_empty() {
+        anyhow::bail!("{} doesn't use API keys", desc.display_name());
+    }
+    let reference = || {
+        reference
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Pass where {} keeps the key", backend))
+    };
+    let backend = match backend {
+        "keychain" => None,
+        "1password" | "op" => Some(SecretBackend::OnePassword {
+            reference: reference()?,
+        }),
+        "bitwarden" | "bw" => Some(SecretBackend::Bitwarden { item: reference()? }),
+        other => anyhow::bail!(
+            "Unknown backend: {} (use keychain, 1password or bitwarden)",
+            other
+        ),
+    };
+
+    let store = SettingsStore::load_default().await?;
+    store.set_secret_backend(desc.id, backend.clone()).await;
+    store.save().await?;
+
+    match backend {
+        Some(backend) => println!(
+            "{} API keys are read with `{} {}`",
+            desc.display_name(),
+            backend.cli(),
+            backend.reference()
+        ),
+        None => println!(
+            "{} API keys are read from the keychain",
+            desc.display_name()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Checks that a provider base URL is an http(s) URL with a host.
+fn check_base_url(url: &str) -> Result<()> {
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| anyhow::anyhow!("Base URL must start with http:// or https://: {}", url))?;
+    if host.split('/').next().unwrap_or_default().is_empty() {
+        anyhow::bail!("Base URL has no host: {}", url);
+    }
+    Ok(())
+}
+
+async fn run_profile(action: &ProfileAction, cli: &Cli) -> Result<()> {
+    match action {
+        ProfileAction::List => {
+            let active = active_profile();
+            let profiles = list_profiles();
+            match cli.format {
+                OutputFormat::Text => {
+                    for profile in &profiles {
+                        let marker = if *profile == active { "*" } else { " " };
+                        println!("{marker} {profile}");
+                    }
+                }
+                OutputFormat::Json | OutputFormat::Waybar => {
+                    let output = serde_json::json!({
+                        "active": active,
+                        "profiles": profiles,
+                    });
+                    let formatter = JsonFormatter::new(cli.pretty);
+                    println!("{}", formatter.format(&output)?);
+                }
+            }
+        }
+        ProfileAction::Create { name, activate } => {
+            let name = create_profile(name).await?;
+            info!(profile = %name, "Profile created");
+            println!("Created profile: {name}");
+            if *activate {
+                set_active_profile(&name)?;
+                println!("Active profile: {name}");
+            }
+        }
+        ProfileAction::Use { name } => {
+            let name = set_active_profile(name)?;
+            info!(profile = %name, "Active profile changed");
+            println!("Active profile: {name}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the app's settings, falling back to defaults if they can't be
+/// read.
+pub(crate) async fn load_settings() -> Settings {
+    match SettingsStore::load_default().await {
+        Ok(store) => store.get().await,
+        Err(_) => Settings::default(),
+    }
+}
+
 async fn reset_config(_cli: &Cli) -> Result<()> {
     let path = default_settings_path();
 
@@ -178,3 +1073,432 @@ async fn reset_config(_cli: &Cli) -> Result<()> {
 
     Ok(())
 }
+
+// ============================================================================
+// Edit & Validate
+// ============================================================================
+
+/// Opens a copy of the settings file in the user's editor and saves it back
+/// if it validates, so the checksum and backups stay current. Invalid edits
+/// are left in the copy and the settings file is untouched.
+async fn edit_config(_cli: &Cli) -> Result<()> {
+    let path = default_settings_path();
+    let format = SettingsFormat::from_path(&path);
+    if !path.exists() {
+        SettingsStore::new(path.clone()).save().await?;
+    }
+
+    let edit_path = path.with_extension(format!("edit.{}", format.extension()));
+    tokio::fs::copy(&path, &edit_path).await?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| default_editor().to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(default_editor());
+
+    let status = tokio::process::Command::new(program)
+        .args(words)
+        .arg(&edit_path)
+        .status()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run editor '{}': {}", editor, e))?;
+    if !status.success() {
+        anyhow::bail!(
+            "Editor exited with {}; settings unchanged (edits kept in {})",
+            status,
+            edit_path.display()
+        );
+    }
+
+    let text = tokio::fs::read_to_string(&edit_path).await?;
+    let validation = validate_as(&text, format);
+    if !validation.is_valid() {
+        for problem in &validation.problems {
+            eprintln!("  ✗ {}", problem);
+        }
+        anyhow::bail!(
+            "Settings unchanged; fix the problems above in {} and run `exactobar config edit` again",
+            edit_path.display()
+        );
+    }
+    for warning in &validation.warnings {
+        eprintln!("  ! {}", warning);
+    }
+
+    match format {
+        SettingsFormat::Json => {
+            // Saving the edited JSON value keeps keys this version doesn't know
+            let value: Value = serde_json::from_str(&text)?;
+            save_json(&path, &value).await?;
+            tokio::fs::remove_file(&edit_path).await?;
+        }
+        // TOML has no checksum to update, so the edit (comments and all)
+        // replaces the file as written
+        SettingsFormat::Toml => tokio::fs::rename(&edit_path, &path).await?,
+    }
+
+    info!(path = %path.display(), "Settings edited");
+    println!("Saved {}", path.display());
+
+    Ok(())
+}
+
+/// Rewrites the settings file in another format and removes the old one.
+async fn convert_config(name: &str, _cli: &Cli) -> Result<()> {
+    let format = SettingsFormat::from_name(name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown format '{}'; use json or toml", name))?;
+    let path = default_settings_path();
+    if SettingsFormat::from_path(&path) == format {
+        println!("Settings are already {}: {}", format, path.display());
+        return Ok(());
+    }
+
+    let store = SettingsStore::load(path.clone()).await?;
+    if let Some(issue) = store.load_issue() {
+        anyhow::bail!(
+            "{}; fix it (see `exactobar config validate`) before converting",
+            issue
+        );
+    }
+    let settings = store.get().await;
+
+    let new_path = path.with_extension(format.extension());
+    let converted = SettingsStore::new(new_path.clone());
+    converted.update(|s| *s = settings).await;
+    converted.save().await?;
+
+    // Backups of a JSON file are kept in case the conversion loses anything
+    if path.exists() {
+        tokio::fs::remove_file(&path).await?;
+    }
+    let checksum = checksum_path(&path);
+    if checksum.exists() {
+        tokio::fs::remove_file(&checksum).await?;
+    }
+
+    info!(from = %path.display(), to = %new_path.display(), "Settings converted");
+    println!("Settings are now {}: {}", format, new_path.display());
+    Ok(())
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) { "notepad" } else { "vi" }
+}
+
+async fn validate_config(cli: &Cli) -> Result<()> {
+    let path = default_settings_path();
+    let mut validation = if path.exists() {
+        validate_as(
+            &tokio::fs::read_to_string(&path).await?,
+            SettingsFormat::from_path(&path),
+        )
+    } else {
+        Validation {
+            settings: Some(Settings::default()),
+            problems: Vec::new(),
+            warnings: vec![format!("{} doesn't exist; using defaults", path.display())],
+        }
+    };
+
+    if path.exists() && !checksum_matches(&path).await? {
+        validation.warnings.push(
+            "File was changed outside ExactoBar and will be restored from a backup on next \
+             load; use `exactobar config edit` to make changes"
+                .to_string(),
+        );
+    }
+
+    match cli.format {
+        OutputFormat::Text => print_validation(&path, &validation),
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let report = serde_json::json!({
+                "path": path.display().to_string(),
+                "valid": validation.is_valid(),
+                "problems": validation.problems,
+                "warnings": validation.warnings,
+                "settings": validation.settings,
+            });
+            println!("{}", JsonFormatter::new(cli.pretty).format(&report)?);
+        }
+    }
+
+    if !validation.is_valid() {
+        std::process::exit(ExitCode::ParseError as i32);
+    }
+    Ok(())
+}
+
+fn print_validation(path: &Path, validation: &Validation) {
+    println!("Validating {}", path.display());
+    println!("{}", "─".repeat(40));
+    for problem in &validation.problems {
+        println!("  ✗ {}", problem);
+    }
+    for warning in &validation.warnings {
+        println!("  ! {}", warning);
+    }
+    if let Some(settings) = &validation.settings {
+        if validation.problems.is_empty() {
+            println!("  ✓ Settings are valid");
+        }
+        println!();
+        println!("Effective configuration:");
+        match serde_json::to_string_pretty(settings) {
+            Ok(json) => println!("{}", json),
+            Err(e) => println!("  (couldn't serialize: {})", e),
+        }
+    }
+}
+
+/// Result of checking a settings file.
+#[derive(Debug, Default)]
+struct Validation {
+    /// Settings with defaults filled in, if the file could be read.
+    settings: Option<Settings>,
+    /// Errors: the file won't load as written.
+    problems: Vec<String>,
+    /// Keys that will be ignored, and other non-fatal issues.
+    warnings: Vec<String>,
+}
+
+impl Validation {
+    /// A file that couldn't be parsed at all.
+    fn problem(problem: String) -> Self {
+        Self {
+            problems: vec![problem],
+            ..Self::default()
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Checks settings in the given format.
+fn validate_as(text: &str, format: SettingsFormat) -> Validation {
+    match format {
+        SettingsFormat::Json => validate(text),
+        SettingsFormat::Toml => validate_toml(text),
+    }
+}
+
+/// Checks settings JSON; see [`check_settings`].
+fn validate(text: &str) -> Validation {
+    match serde_json::from_str(text) {
+        Ok(Value::Object(root)) => check_settings(root),
+        Ok(_) => Validation::problem("Settings must be a JSON object".to_string()),
+        Err(e) => Validation::problem(format!("Invalid JSON: {}", e)),
+    }
+}
+
+/// Checks settings TOML; see [`check_settings`].
+fn validate_toml(text: &str) -> Validation {
+    match toml::from_str(text) {
+        Ok(root) => check_settings(root),
+        Err(e) => Validation::problem(format!("Invalid TOML: {}", e)),
+    }
+}
+
+/// Checks parsed settings: each known key's value on its own (so bad enum
+/// values are reported per key), and unknown keys, which are ignored on
+/// load and reported with the closest known key.
+fn check_settings(root: Map<String, Value>) -> Validation {
+    let mut validation = Validation::default();
+
+    let known = known_keys(&Settings::default());
+    let provider_known = known_keys(&ProviderSettings::default());
+
+    for (key, value) in &root {
+        if !known.contains(key) {
+            validation.warnings.push(unknown_key(key, &known));
+            continue;
+        }
+
+        let single = Value::Object(Map::from_iter([(key.clone(), value.clone())]));
+        if let Err(e) = serde_json::from_value::<Settings>(single) {
+            validation.problems.push(format!("{}: {}", key, e));
+        }
+
+        if key == "provider_settings" {
+            for (provider, entry) in value.as_object().into_iter().flatten() {
+                for field in entry.as_object().into_iter().flatten().map(|(k, _)| k) {
+                    if !provider_known.contains(field) {
+                        let warning = unknown_key(field, &provider_known);
+                        validation
+                            .warnings
+                            .push(format!("provider_settings.{}: {}", provider, warning));
+                    }
+                }
+            }
+        }
+    }
+
+    if validation.problems.is_empty() {
+        match serde_json::from_value::<Settings>(Value::Object(root)) {
+            Ok(settings) => {
+                let mut endpoints: Vec<_> = settings
+                    .provider_settings
+                    .iter()
+                    .filter_map(|(provider, ps)| Some((provider.cli_name(), ps.base_url.as_ref()?)))
+                    .filter_map(|(provider, url)| Some((provider, check_base_url(url).err()?)))
+                    .collect();
+                endpoints.sort_by_key(|(provider, _)| *provider);
+                for (provider, e) in endpoints {
+                    validation
+                        .problems
+                        .push(format!("provider_settings.{}.base_url: {}", provider, e));
+                }
+                if validation.problems.is_empty() {
+                    validation.settings = Some(settings);
+                }
+            }
+            Err(e) => validation.problems.push(e.to_string()),
+        }
+    }
+
+    validation
+}
+
+/// Top-level keys a value serializes to.
+fn known_keys<T: serde::Serialize>(value: &T) -> Vec<String> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map.into_iter().map(|(k, _)| k).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Describes an unknown key, suggesting the closest known one.
+fn unknown_key(key: &str, known: &[String]) -> String {
+    let closest = known
+        .iter()
+        .map(|k| (edit_distance(key, k), k))
+        .filter(|(d, _)| *d <= (key.len() / 3).max(2))
+        .min_by_key(|(d, _)| *d);
+
+    match closest {
+        Some((_, suggestion)) => {
+            format!(
+                "Unknown key '{}' (ignored); did you mean '{}'?",
+                key, suggestion
+            )
+        }
+        None => format!("Unknown key '{}' (ignored)", key),
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("merge_icons", "merge_icons"), 0);
+        assert_eq!(edit_distance("merge_icon", "merge_icons"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_validate_valid_settings() {
+        let validation = validate(r#"{"merge_icons": false, "refresh_cadence": "one_minute"}"#);
+        assert!(validation.is_valid(), "{:?}", validation.problems);
+        assert!(validation.warnings.is_empty());
+
+        let settings = validation.settings.unwrap();
+        assert!(!settings.merge_icons);
+        // Missing keys take their defaults
+        assert_eq!(settings.debug_mode, Settings::default().debug_mode);
+    }
+
+    #[test]
+    fn test_validate_reports_bad_values() {
+        let validation = validate(r#"{"theme_mode": "purple", "debug_mode": "yes"}"#);
+        assert_eq!(validation.problems.len(), 2);
+        assert!(
+            validation
+                .problems
+                .iter()
+                .any(|p| p.starts_with("theme_mode:"))
+        );
+        assert!(validation.settings.is_none());
+
+        assert!(!validate("{\"merge_icons\": tru").is_valid());
+        assert!(!validate("[]").is_valid());
+    }
+
+    #[test]
+    fn test_validate_toml() {
+        let validation = validate_toml(
+            "# From dotfiles\nmerge_icon = true\nrefresh_cadence = \"one_minute\"\n\n\
+             [provider_settings.claude]\napi_key_env = \"ANTHROPIC_API_KEY\"\n",
+        );
+        assert!(validation.is_valid(), "{:?}", validation.problems);
+        assert_eq!(validation.warnings.len(), 1);
+        let settings = validation.settings.unwrap();
+        assert_eq!(
+            settings.provider_settings[&exactobar_core::ProviderKind::Claude]
+                .api_key_env
+                .as_deref(),
+            Some("ANTHROPIC_API_KEY")
+        );
+
+        let validation = validate_as("theme_mode = \"purple\"", SettingsFormat::Toml);
+        assert_eq!(validation.problems.len(), 1);
+        assert!(!validate_toml("merge_icons = ").is_valid());
+    }
+
+    #[test]
+    fn test_validate_base_urls() {
+        let validation = validate(
+            r#"{"provider_settings": {
+                "claude": {"base_url": "https://llm-gateway.example.com/anthropic"},
+                "codex": {"base_url": "llm-gateway.example.com/openai"}}}"#,
+        );
+        assert_eq!(validation.problems.len(), 1);
+        assert!(validation.problems[0].starts_with("provider_settings.codex.base_url:"));
+        assert!(validation.settings.is_none());
+
+        assert!(check_base_url("http://127.0.0.1:8080").is_ok());
+        assert!(check_base_url("https:///v1").is_err());
+    }
+
+    #[test]
+    fn test_validate_suggests_unknown_keys() {
+        let validation = validate(
+            r#"{"merge_icon": true, "zzz": 1,
+                "provider_settings": {"codex": {"cookie_sorce": "chrome"}}}"#,
+        );
+        assert!(validation.is_valid());
+        assert_eq!(validation.warnings.len(), 3);
+
+        // Keys are checked in sorted order
+        assert!(validation.warnings[0].contains("did you mean 'merge_icons'"));
+        assert!(validation.warnings[1].starts_with("provider_settings.codex:"));
+        assert!(validation.warnings[1].contains("'cookie_source'"));
+        assert!(!validation.warnings[2].contains("did you mean"));
+    }
+}
@@ -1,17 +1,24 @@
 //! Cost command - show local token cost report.
 //!
-//! Scans local log files for token usage and calculates costs.
+//! Scans local log files for token usage and calculates costs. Scans are
+//! incremental: only data appended since the last run is parsed, and
+//! `--refresh` re-reads everything.
+//!
+//! Costs are recomputed from token counts with the pricing table (built-in
+//! prices plus the user's `pricing.toml`), so a price change or override is
+//! reflected across the whole history. Entries for unknown models fall back
+//! to the cost recorded in the log.
+//!
+//! `--live` keeps running and redraws today's spend whenever a log changes.
 
 use anyhow::Result;
-use chrono::{DateTime, NaiveDate, Utc};
 use clap::Args;
 use exactobar_core::ProviderKind;
-use exactobar_providers::ProviderRegistry;
-use exactobar_store::{CostUsageSnapshot, DailyCost};
-use serde::Deserialize;
+use exactobar_providers::{LiveCostWatcher, LogIndex, PricingTable, ProviderRegistry, cost};
+use exactobar_store::CostUsageSnapshot;
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::io::{Write, stdout};
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, info, warn};
 
 use crate::output::{JsonFormatter, TextFormatter};
@@ -24,7 +31,7 @@ pub struct CostArgs {
     #[arg(long, short, default_value = "all")]
     pub provider: String,
 
-    /// Refresh (re-scan logs, ignore cache).
+    /// Refresh (re-scan all logs, ignore the incremental index).
     #[arg(long)]
     pub refresh: bool,
 
@@ -35,6 +42,14 @@ pub struct CostArgs {
     /// Show daily breakdown.
     #[arg(long)]
     pub daily: bool,
+
+    /// Show token categories (cache, reasoning) and per-model breakdown.
+    #[arg(long)]
+    pub detailed: bool,
+
+    /// Keep running and update today's spend as the logs change.
+    #[arg(long)]
+    pub live: bool,
 }
 
 /// Runs the cost command.
@@ -44,32 +59,20 @@ pub async fn run(args: &CostArgs, cli: &Cli) -> Result<()> {
     // Determine which providers to scan
     let providers = parse_cost_providers(&args.provider)?;
 
-    // Scan logs for each provider
-    let mut results: HashMap<ProviderKind, CostUsageSnapshot> = HashMap::new();
-
-    for provider in &providers {
-        let desc = ProviderRegistry::get(*provider);
-        if desc.is_none() {
-            continue;
-        }
-
-        let desc = desc.unwrap();
-        if !desc.token_cost.supports_token_cost {
-            continue;
-        }
+    let mut results = scan_costs(&providers, args.days, args.refresh)?;
 
-        // Get log directory
-        if let Some(log_dir_fn) = desc.token_cost.log_directory {
-            if let Some(log_dir) = log_dir_fn() {
-                if log_dir.exists() {
-                    debug!(provider = ?provider, dir = %log_dir.display(), "Scanning logs");
+    // --live keeps running and follows the logs as they grow
+    if args.live && !results.is_empty() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let _watchers = spawn_live_watchers(&providers, args.days, &tx);
+        drop(tx);
 
-                    let snapshot = scan_logs(&log_dir, args.days)?;
-                    results.insert(*provider, snapshot);
-                } else {
-                    debug!(provider = ?provider, "Log directory not found");
-                }
-            }
+        loop {
+            output_live_cost(&results, args, cli)?;
+            let Some((provider, snapshot)) = rx.recv().await else {
+                return Ok(());
+            };
+            results.insert(provider, snapshot);
         }
     }
 
@@ -79,110 +82,112 @@ pub async fn run(args: &CostArgs, cli: &Cli) -> Result<()> {
     Ok(())
 }
 
-/// Scans log files and aggregates token usage.
-fn scan_logs(log_dir: &PathBuf, days: u32) -> Result<CostUsageSnapshot> {
-    let mut total_tokens: u64 = 0;
-    let mut total_cost: f64 = 0.0;
-    let mut daily_map: HashMap<NaiveDate, (u64, f64)> = HashMap::new();
-
-    let cutoff = Utc::now() - chrono::Duration::days(days as i64);
-
-    // Read all .jsonl files
-    let entries = fs::read_dir(log_dir)?;
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        // Only process .jsonl files
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+/// Scans each provider's logs, updating the incremental indexes.
+///
+/// With `refresh`, the indexes are discarded and every log is re-read.
+pub(crate) fn scan_costs(
+    providers: &[ProviderKind],
+    days: u32,
+    refresh: bool,
+) -> Result<HashMap<ProviderKind, CostUsageSnapshot>> {
+    let pricing = PricingTable::load_default();
+    let mut results = HashMap::new();
+
+    for provider in providers {
+        let Some(desc) = ProviderRegistry::get(*provider) else {
             continue;
-        }
+        };
 
-        // Read and parse
-        match fs::read_to_string(&path) {
-            Ok(content) => {
-                for line in content.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-
-                    if let Ok(entry) = serde_json::from_str::<LogEntry>(line) {
-                        // Check date cutoff
-                        if let Some(timestamp) = &entry.timestamp {
-                            if let Ok(dt) = DateTime::parse_from_rfc3339(timestamp) {
-                                if dt < cutoff {
-                                    continue;
-                                }
-
-                                let date = dt.date_naive();
-                                let tokens = entry.total_tokens();
-                                let cost = entry.cost_usd.unwrap_or(0.0);
-
-                                total_tokens += tokens;
-                                total_cost += cost;
-
-                                let entry = daily_map.entry(date).or_insert((0, 0.0));
-                                entry.0 += tokens;
-                                entry.1 += cost;
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                warn!(path = %path.display(), error = %e, "Failed to read log file");
+        let index_path = LogIndex::default_path(desc.cli_name());
+        let mut index = if refresh {
+            LogIndex::default()
+        } else {
+            LogIndex::load(&index_path)
+        };
+
+        if let Some(snapshot) = cost::scan_provider(&desc.token_cost, &mut index, days, &pricing)? {
+            if let Err(e) = index.save(&index_path) {
+                warn!(provider = ?provider, error = %e, "Failed to save cost index");
             }
+            results.insert(*provider, snapshot);
+        } else {
+            debug!(provider = ?provider, "No cost logs found");
         }
     }
 
-    // Build daily breakdown
-    let mut daily: Vec<DailyCost> = daily_map
-        .into_iter()
-        .map(|(date, (tokens, cost))| DailyCost {
-            date: date.and_hms_opt(0, 0, 0).unwrap().and_utc(),
-            tokens,
-            cost_usd: cost,
-        })
-        .collect();
-
-    daily.sort_by_key(|d| d.date);
-
-    Ok(CostUsageSnapshot {
-        total_tokens,
-        total_cost_usd: total_cost,
-        daily,
-        scanned_at: Some(Utc::now()),
-    })
+    Ok(results)
 }
 
-/// Log entry structure (generic for multiple providers).
-#[derive(Debug, Deserialize)]
-struct LogEntry {
-    #[serde(default)]
-    timestamp: Option<String>,
-
-    #[serde(default, alias = "input_tokens")]
-    input_tokens: Option<u64>,
-
-    #[serde(default, alias = "output_tokens")]
-    output_tokens: Option<u64>,
+/// Starts log watchers that send a fresh snapshot whenever a provider's
+/// logs change. Updates stop when the returned watchers are dropped.
+pub(crate) fn spawn_live_watchers(
+    providers: &[ProviderKind],
+    days: u32,
+    tx: &UnboundedSender<(ProviderKind, CostUsageSnapshot)>,
+) -> Vec<LiveCostWatcher> {
+    let pricing = PricingTable::load_default();
+    let mut watchers = Vec::new();
+
+    for &provider in providers {
+        let Some(desc) = ProviderRegistry::get(provider) else {
+            continue;
+        };
 
-    #[serde(default, alias = "total_tokens")]
-    total_tokens: Option<u64>,
+        let tx = tx.clone();
+        match LiveCostWatcher::spawn(
+            &desc.token_cost,
+            LogIndex::default_path(desc.cli_name()),
+            days,
+            pricing.clone(),
+            move |snapshot| {
+                let _ = tx.send((provider, snapshot));
+            },
+        ) {
+            Ok(Some(watcher)) => watchers.push(watcher),
+            Ok(None) => {}
+            Err(e) => warn!(provider = ?provider, error = %e, "Failed to watch cost logs"),
+        }
+    }
 
-    #[serde(default)]
-    cost_usd: Option<f64>,
+    watchers
 }
 
-impl LogEntry {
-    fn total_tokens(&self) -> u64 {
-        self.total_tokens
-            .unwrap_or_else(|| self.input_tokens.unwrap_or(0) + self.output_tokens.unwrap_or(0))
+/// Redraws the live cost view.
+fn output_live_cost(
+    results: &HashMap<ProviderKind, CostUsageSnapshot>,
+    args: &CostArgs,
+    cli: &Cli,
+) -> Result<()> {
+    match cli.format {
+        OutputFormat::Text => {
+            let formatter = TextFormatter::new(!cli.no_color);
+
+            // Clear screen
+            print!("\x1b[2J\x1b[H");
+            stdout().flush()?;
+
+            println!(
+                "ExactoBar Live Cost - {}",
+                chrono::Local::now().format("%H:%M:%S")
+            );
+            println!("{}", "─".repeat(50));
+            println!();
+            println!("{}", formatter.format_today_cost(results));
+            println!();
+            println!("Press Ctrl+C to exit");
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            // One document per update
+            let formatter = JsonFormatter::new(cli.pretty);
+            println!("{}", formatter.format_cost_results(results, args.detailed)?);
+        }
     }
+
+    Ok(())
 }
 
 /// Parses provider selection for cost command.
-fn parse_cost_providers(arg: &str) -> Result<Vec<ProviderKind>> {
+pub(crate) fn parse_cost_providers(arg: &str) -> Result<Vec<ProviderKind>> {
     match arg.to_lowercase().as_str() {
         "all" => {
             // Only providers that support token cost
@@ -209,7 +214,7 @@ fn parse_cost_providers(arg: &str) -> Result<Vec<ProviderKind>> {
 /// Outputs cost results.
 fn output_cost_results(
     results: &HashMap<ProviderKind, CostUsageSnapshot>,
-    _args: &CostArgs,
+    args: &CostArgs,
     cli: &Cli,
 ) -> Result<()> {
     if results.is_empty() {
@@ -238,13 +243,13 @@ fn output_cost_results(
                 first = false;
 
                 let desc = ProviderRegistry::get(*provider);
-                let output = formatter.format_cost(snapshot, desc);
+                let output = formatter.format_cost(snapshot, desc, args.detailed);
                 println!("{}", output);
             }
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Waybar => {
             let formatter = JsonFormatter::new(cli.pretty);
-            let output = formatter.format_cost_results(results)?;
+            let output = formatter.format_cost_results(results, args.detailed)?;
             println!("{}", output);
         }
     }
@@ -261,25 +266,4 @@ mod tests {
         let providers = parse_cost_providers("all").unwrap();
         assert!(!providers.is_empty());
     }
-
-    #[test]
-    fn test_log_entry_total_tokens() {
-        let entry = LogEntry {
-            timestamp: None,
-            input_tokens: Some(100),
-            output_tokens: Some(50),
-            total_tokens: None,
-            cost_usd: None,
-        };
-        assert_eq!(entry.total_tokens(), 150);
-
-        let entry_with_total = LogEntry {
-            timestamp: None,
-            input_tokens: Some(100),
-            output_tokens: Some(50),
-            total_tokens: Some(200),
-            cost_usd: None,
-        };
-        assert_eq!(entry_with_total.total_tokens(), 200);
-    }
 }
@@ -0,0 +1,224 @@
+//! Doctor command - check ExactoBar's setup for problems.
+//!
+//! `--keychain` audits the API keys stored in the system keychain: when
+//! each was last changed (macOS only) and whether its provider still
+//! accepts it. Keys are deleted or rotated from the app's Advanced
+//! settings, or stored again under the same name.
+//!
+//! `--env` lists API keys found in environment variables, which providers
+//! they could enable, and, with `--import`, stores them in the keychain so
+//! the app finds them without the shell's environment.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use exactobar_providers::{EnvKeyUse, KeyHealth, audit_keys, discover_env_keys, import_env_key};
+use serde_json::json;
+
+use crate::{Cli, OutputFormat};
+
+/// Arguments for the doctor command.
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Audit the API keys stored in the keychain.
+    #[arg(long)]
+    pub keychain: bool,
+
+    /// List API keys found in environment variables.
+    #[arg(long)]
+    pub env: bool,
+
+    /// Store the keys found with --env in the keychain.
+    #[arg(long, requires = "env")]
+    pub import: bool,
+}
+
+/// Runs the doctor command. With no check selected, runs them all.
+pub async fn run(args: &DoctorArgs, cli: &Cli) -> Result<()> {
+    let all = !args.keychain && !args.env;
+    if args.keychain || all {
+        audit_keychain(cli).await?;
+    }
+    if args.env || all {
+        discover_env(args.import, cli)?;
+    }
+    Ok(())
+}
+
+/// Prints the stored keys and whether each still validates.
+async fn audit_keychain(cli: &Cli) -> Result<()> {
+    let audits = audit_keys().await;
+    let now = Utc::now();
+
+    match cli.format {
+        OutputFormat::Text => {
+            if audits.is_empty() {
+                println!("No API keys stored in the keychain");
+                return Ok(());
+            }
+            println!("KEY                AGE          STATUS");
+            for audit in &audits {
+                let age = audit
+                    .key
+                    .modified_at
+                    .map(|at| format_age(at, now))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let status = match &audit.health {
+                    KeyHealth::Valid => paint("✓ Valid", "32", cli.no_color),
+                    KeyHealth::Invalid(reason) => {
+                        format!("{} - {}", paint("✗ Invalid", "31", cli.no_color), reason)
+                    }
+                    KeyHealth::Unchecked(reason) => format!("- Unchecked ({})", reason),
+                };
+                println!("{:<18} {:<12} {}", audit.key.provider, age, status);
+            }
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let keys: Vec<_> = audits
+                .iter()
+                .map(|audit| {
+                    let reason = match &audit.health {
+                        KeyHealth::Valid => None,
+                        KeyHealth::Invalid(reason) | KeyHealth::Unchecked(reason) => {
+                            Some(reason.as_str())
+                        }
+                    };
+                    json!({
+                        "key": audit.key.provider,
+                        "service": audit.key.service,
+                        "provider": audit.provider.map(|p| p.cli_name()),
+                        "modified_at": audit.key.modified_at.map(|at| at.to_rfc3339()),
+                        "status": audit.health.label().to_lowercase(),
+                        "reason": reason,
+                    })
+                })
+                .collect();
+            let output = json!({ "keychain": keys });
+            if cli.pretty {
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{}", output);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the API keys found in the environment, importing them first if
+/// asked to.
+fn discover_env(import: bool, cli: &Cli) -> Result<()> {
+    let mut keys = discover_env_keys();
+    let mut errors = Vec::new();
+    if import {
+        for key in keys.iter_mut().filter(|key| key.can_import()) {
+            match import_env_key(key) {
+                Ok(()) => key.stored = true,
+                Err(e) => errors.push((key.var, e)),
+            }
+        }
+    }
+
+    match cli.format {
+        OutputFormat::Text => {
+            if keys.is_empty() {
+                println!("No API keys found in the environment");
+                return Ok(());
+            }
+            println!("VARIABLE               PROVIDER     STATUS");
+            for key in &keys {
+                let status = match key.key_use {
+                    EnvKeyUse::Import(_) if key.stored => {
+                        paint("✓ In keychain", "32", cli.no_color)
+                    }
+                    EnvKeyUse::Import(_) => "Can be imported".to_string(),
+                    EnvKeyUse::EnvOnly => "Read from the environment".to_string(),
+                    EnvKeyUse::Unused(reason) => format!("- Not used ({})", reason),
+                };
+                println!("{:<22} {:<12} {}", key.var, key.provider.cli_name(), status);
+            }
+            for (var, error) in &errors {
+                println!("{} {}: {}", paint("✗", "31", cli.no_color), var, error);
+            }
+            if !import && keys.iter().any(|key| key.can_import()) {
+                println!();
+                println!("Run `exactobar doctor --env --import` to store them in the keychain.");
+            }
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let found: Vec<_> = keys
+                .iter()
+                .map(|key| {
+                    let (status, keychain_name, reason) = match key.key_use {
+                        EnvKeyUse::Import(name) if key.stored => ("stored", Some(name), None),
+                        EnvKeyUse::Import(name) => ("importable", Some(name), None),
+                        EnvKeyUse::EnvOnly => ("env_only", None, None),
+                        EnvKeyUse::Unused(reason) => ("unused", None, Some(reason)),
+                    };
+                    json!({
+                        "variable": key.var,
+                        "provider": key.provider.cli_name(),
+                        "enables_provider": key.enables_provider(),
+                        "keychain_name": keychain_name,
+                        "status": status,
+                        "reason": reason,
+                        "error": errors
+                            .iter()
+                            .find(|(var, _)| *var == key.var)
+                            .map(|(_, error)| error),
+                    })
+                })
+                .collect();
+            let output = json!({ "env": found });
+            if cli.pretty {
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{}", output);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Wraps text in an ANSI color unless color is off.
+fn paint(text: &str, color: &str, no_color: bool) -> String {
+    if no_color {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    }
+}
+
+/// Formats how long ago a key was changed, e.g. "3 months".
+fn format_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let days = (now - at).num_days().max(0);
+    match days {
+        0 => "today".to_string(),
+        1 => "1 day".to_string(),
+        2..=59 => format!("{} days", days),
+        60..=729 => format!("{} months", days / 30),
+        _ => format!("{} years", days / 365),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "today");
+        assert_eq!(format_age(now - chrono::Duration::days(1), now), "1 day");
+        assert_eq!(format_age(now - chrono::Duration::days(45), now), "45 days");
+        assert_eq!(
+            format_age(now - chrono::Duration::days(200), now),
+            "6 months"
+        );
+        assert_eq!(
+            format_age(now - chrono::Duration::days(800), now),
+            "2 years"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Guard command - run a command only when enough quota is left.
+//!
+//! ```bash
+//! exactobar guard --provider claude --min-remaining 20 -- ./long-job.sh
+//! ```
+//!
+//! Fetches usage for the selected providers and checks the lowest remaining
+//! percentage across all of each provider's windows (session, weekly, ...).
+//! If every provider has at least `--min-remaining` left, the command runs
+//! and its exit code is passed through. Otherwise nothing runs and guard
+//! exits with [`ExitCode::QuotaLow`], or [`ExitCode::ProviderMissing`] when
+//! usage couldn't be fetched (unless `--allow-unknown` is given).
+//!
+//! Without a command, guard only performs the check, for use in `if`
+//! statements and CI conditions. Messages go to stderr so the wrapped
+//! command's stdout is untouched.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use exactobar_core::UsageSnapshot;
+use exactobar_fetch::{FetchContext, SNAPSHOT_MAX_AGE};
+use tracing::info;
+
+use crate::commands::{config, usage};
+use crate::{Cli, ExitCode};
+
+/// Arguments for the guard command.
+#[derive(Args)]
+pub struct GuardArgs {
+    /// Minimum remaining percentage required in every usage window.
+    #[arg(long, default_value = "10")]
+    pub min_remaining: f64,
+
+    /// Run the command even if usage can't be fetched.
+    #[arg(long)]
+    pub allow_unknown: bool,
+
+    /// Command to run when the check passes (after `--`).
+    #[arg(last = true)]
+    pub command: Vec<String>,
+}
+
+/// Outcome of checking one provider.
+#[derive(Debug, Clone, PartialEq)]
+enum Verdict {
+    /// Enough quota left.
+    Ok { remaining: f64 },
+    /// Below the minimum.
+    Low { remaining: f64 },
+    /// Usage couldn't be determined.
+    Unknown { reason: String },
+}
+
+/// Runs the guard command.
+pub async fn run(args: &GuardArgs, cli: &Cli) -> Result<()> {
+    let providers = usage::parse_provider_selection(cli.provider.as_ref())?;
+    info!(providers = ?providers, min_remaining = args.min_remaining, "Checking quota");
+
+    let settings = config::load_settings().await;
+    let results = usage::fetch_all(
+        &providers,
+        &FetchContext::new(),
+        &settings,
+        |provider| settings.data_source(provider).source_mode(),
+        SNAPSHOT_MAX_AGE,
+        false,
+    )
+    .await;
+
+    let mut low = false;
+    let mut unknown = false;
+    for provider in &providers {
+        let verdict = check(&results[provider], args.min_remaining);
+        let name = provider.cli_name();
+        let message = match &verdict {
+            Verdict::Ok { remaining } => format!("{}: {:.0}% remaining", name, remaining),
+            Verdict::Low { remaining } => {
+                low = true;
+                format!(
+                    "{}: {:.0}% remaining, below the required {:.0}%",
+                    name, remaining, args.min_remaining
+                )
+            }
+            Verdict::Unknown { reason } => {
+                unknown = true;
+                format!("{}: usage unknown ({})", name, reason)
+            }
+        };
+        if !cli.quiet {
+            eprintln!("{}", message);
+        }
+    }
+
+    if low {
+        exit_without_running(cli, ExitCode::QuotaLow, "Not enough quota left");
+    }
+    if unknown && !args.allow_unknown {
+        exit_without_running(
+            cli,
+            ExitCode::ProviderMissing,
+            "Couldn't check quota (pass --allow-unknown to run anyway)",
+        );
+    }
+
+    let Some((program, program_args)) = args.command.split_first() else {
+        return Ok(());
+    };
+
+    let status = std::process::Command::new(program)
+        .args(program_args)
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    std::process::exit(exit_code(status));
+}
+
+fn exit_without_running(cli: &Cli, code: ExitCode, reason: &str) -> ! {
+    if !cli.quiet {
+        eprintln!("{}; command not run", reason);
+    }
+    std::process::exit(code as i32);
+}
+
+/// Checks a fetch result against the minimum remaining percentage.
+fn check(result: &Result<UsageSnapshot, String>, min_remaining: f64) -> Verdict {
+    let snapshot = match result {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            return Verdict::Unknown {
+                reason: e.lines().next().unwrap_or("fetch failed").to_string(),
+            };
+        }
+    };
+
+    match snapshot.min_remaining_percent() {
+        Some(remaining) if remaining < min_remaining => Verdict::Low { remaining },
+        Some(remaining) => Verdict::Ok { remaining },
+        None => Verdict::Unknown {
+            reason: "no usage windows reported".to_string(),
+        },
+    }
+}
+
+/// Exit code to pass through from the wrapped command.
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    // Killed by a signal: follow the shell convention
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    ExitCode::Error as i32
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use exactobar_core::UsageWindow;
+
+    fn snapshot(primary: f64, secondary: Option<f64>) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(primary));
+        snapshot.secondary = secondary.map(UsageWindow::new);
+        snapshot
+    }
+
+    #[test]
+    fn test_check_uses_lowest_window() {
+        assert_eq!(
+            check(&Ok(snapshot(30.0, None)), 20.0),
+            Verdict::Ok { remaining: 70.0 }
+        );
+        // Session is fine but the weekly window is nearly used up
+        assert_eq!(
+            check(&Ok(snapshot(30.0, Some(90.0))), 20.0),
+            Verdict::Low { remaining: 10.0 }
+        );
+    }
+
+    #[test]
+    fn test_check_unknown() {
+        let failed: Result<UsageSnapshot, String> = Err("Error: not logged in\n\ndetails".into());
+        assert_eq!(
+            check(&failed, 20.0),
+            Verdict::Unknown {
+                reason: "Error: not logged in".to_string()
+            }
+        );
+        assert!(matches!(
+            check(&Ok(UsageSnapshot::new()), 20.0),
+            Verdict::Unknown { .. }
+        ));
+    }
+
+    #[test]
+    fn test_guard_args() {
+        use clap::Parser;
+
+        let cli = Cli::parse_from([
+            "exactobar",
+            "guard",
+            "--provider",
+            "claude",
+            "--min-remaining",
+            "20",
+            "--",
+            "make",
+            "-j4",
+        ]);
+        let Some(crate::Commands::Guard(args)) = &cli.command else {
+            panic!("expected guard command");
+        };
+
+        assert_eq!(cli.provider.as_deref(), Some("claude"));
+        assert_eq!(args.min_remaining, 20.0);
+        assert_eq!(args.command, vec!["make", "-j4"]);
+    }
+}
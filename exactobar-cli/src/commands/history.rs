@@ -0,0 +1,92 @@
+//! History command - maintain the usage history database.
+//!
+//! Usage history is kept in a SQLite database next to the other history
+//! files. `compact` deletes points past the 30-day retention and shrinks
+//! the file; `import` merges a `usage.json` file from an earlier version,
+//! which the app otherwise does on its own the first time it starts.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use exactobar_store::HistoryStore;
+
+use crate::Cli;
+
+/// Arguments for the history command.
+#[derive(Args)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub action: HistoryAction,
+}
+
+/// History subcommands.
+#[derive(Subcommand)]
+pub enum HistoryAction {
+    /// Show where history is stored and how much it holds.
+    Path,
+
+    /// Delete expired points and shrink the database.
+    Compact,
+
+    /// Merge history from a JSON file kept by an earlier version.
+    Import {
+        /// The `usage.json` file to import.
+        file: PathBuf,
+    },
+}
+
+/// Runs the history command.
+pub async fn run(args: &HistoryArgs, _cli: &Cli) -> Result<()> {
+    let mut store = HistoryStore::load_default()
+        .await
+        .context("Failed to open usage history")?;
+
+    match &args.action {
+        HistoryAction::Path => {
+            println!("{}", store.path().display());
+            println!(
+                "{} ({} providers)",
+                format_size(exactobar_store::database_size(store.path())),
+                store.providers().len()
+            );
+        }
+        HistoryAction::Compact => {
+            let compaction = store
+                .compact()
+                .await
+                .context("Failed to compact usage history")?;
+            println!(
+                "Removed {} rows; {} -> {}",
+                compaction.removed,
+                format_size(compaction.size_before),
+                format_size(compaction.size_after)
+            );
+        }
+        HistoryAction::Import { file } => {
+            let imported = store
+                .import_json(file)
+                .await
+                .with_context(|| format!("Failed to import {}", file.display()))?;
+            println!("Imported {} points from {}", imported, file.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a file size, e.g. "1.2 MB".
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
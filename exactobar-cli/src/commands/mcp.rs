@@ -0,0 +1,352 @@
+//! MCP command - serve usage data over the Model Context Protocol.
+//!
+//! Runs a Model Context Protocol server on stdin/stdout (newline-delimited
+//! JSON-RPC 2.0) so agents can check remaining quota before launching big
+//! jobs. Two tools are exposed:
+//!
+//! - `get_usage` - current usage windows for one or more providers
+//! - `get_cost` - token costs from local logs over a range of days
+//!
+//! Tool results are the same JSON documents `--format json` prints. Logs go
+//! to stderr, so stdout carries protocol messages only.
+//!
+//! Register it with an MCP client as a stdio server running `exactobar mcp`.
+
+use anyhow::Result;
+use exactobar_fetch::{FetchContext, SNAPSHOT_MAX_AGE};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, info, warn};
+
+use crate::Cli;
+use crate::commands::{config, cost, usage};
+use crate::output::JsonFormatter;
+
+/// Protocol version used when the client doesn't request one.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A JSON-RPC request or notification.
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Absent for notifications, which get no response.
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// ============================================================================
+// Server Loop
+// ============================================================================
+
+/// Runs the MCP server until stdin closes.
+pub async fn run(_cli: &Cli) -> Result<()> {
+    info!("Starting MCP server on stdio");
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(response) = handle_message(&line).await else {
+            continue;
+        };
+
+        let mut out = serde_json::to_string(&response)?;
+        out.push('\n');
+        stdout.write_all(out.as_bytes()).await?;
+        stdout.flush().await?;
+    }
+
+    info!("MCP client disconnected");
+    Ok(())
+}
+
+/// Handles one message, returning the response to send (if any).
+async fn handle_message(line: &str) -> Option<Value> {
+    let request: Request = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!(error = %e, "Invalid MCP message");
+            return Some(error_response(
+                Value::Null,
+                &RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+            ));
+        }
+    };
+
+    debug!(method = %request.method, "MCP request");
+    let result = dispatch(&request.method, &request.params).await;
+
+    // Notifications never get a response
+    let id = request.id?;
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, &e),
+    })
+}
+
+fn error_response(id: Value, error: &RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Routes a method to its handler.
+async fn dispatch(method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" | "notifications/initialized" | "notifications/cancelled" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(params).await,
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": "exactobar",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+// ============================================================================
+// Tools
+// ============================================================================
+
+/// Tool names and input schemas advertised by `tools/list`.
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "get_usage",
+            "description": "Get current quota usage for LLM providers: used percent, \
+                window length and reset time for each usage window.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "provider": {
+                        "type": "string",
+                        "description": "Provider CLI name (e.g. \"claude\", \"codex\"), \
+                            a comma-separated list, or \"all\". Defaults to codex and claude."
+                    }
+                }
+            }
+        },
+        {
+            "name": "get_cost",
+            "description": "Get token costs computed from local CLI logs, with daily \
+                and per-model breakdowns.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "range": {
+                        "type": "string",
+                        "description": "\"today\", \"week\", \"month\" or a number of \
+                            days like \"14d\". Defaults to \"month\"."
+                    },
+                    "provider": {
+                        "type": "string",
+                        "description": "Provider CLI name, or \"all\" (default)."
+                    }
+                }
+            }
+        }
+    ])
+}
+
+/// Runs a tool. Tool failures are reported in the result, not as errors.
+async fn call_tool(params: &Value) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+    let string_arg = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    info!(tool = name, "MCP tool call");
+    let output = match name {
+        "get_usage" => get_usage(string_arg("provider")).await,
+        "get_cost" => get_cost(string_arg("range"), string_arg("provider")).await,
+        _ => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {}", name),
+            ));
+        }
+    };
+
+    Ok(match output {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": e.to_string() }],
+            "isError": true,
+        }),
+    })
+}
+
+async fn get_usage(provider: Option<String>) -> Result<String> {
+    let providers = usage::parse_provider_selection(provider.as_ref())?;
+    let settings = config::load_settings().await;
+    let results = usage::fetch_all(
+        &providers,
+        &FetchContext::new(),
+        &settings,
+        |provider| settings.data_source(provider).source_mode(),
+        SNAPSHOT_MAX_AGE,
+        false,
+    )
+    .await;
+    JsonFormatter::new(false).format_results(&results)
+}
+
+async fn get_cost(range: Option<String>, provider: Option<String>) -> Result<String> {
+    let days = parse_range(range.as_deref().unwrap_or("month"))?;
+    let providers = cost::parse_cost_providers(provider.as_deref().unwrap_or("all"))?;
+
+    // Log scans are blocking file I/O
+    let results =
+        tokio::task::spawn_blocking(move || cost::scan_costs(&providers, days, false)).await??;
+    JsonFormatter::new(false).format_cost_results(&results, true)
+}
+
+/// Parses a cost range into a number of days.
+fn parse_range(range: &str) -> Result<u32> {
+    let range = range.trim().to_lowercase();
+    let days = match range.as_str() {
+        "today" | "day" => 1,
+        "week" => 7,
+        "month" => 30,
+        other => other
+            .strip_suffix('d')
+            .unwrap_or(other)
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid range: {}. Use today, week, month or a number of days like 14d",
+                    range
+                )
+            })?,
+    };
+    Ok(days)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("today").unwrap(), 1);
+        assert_eq!(parse_range("Week").unwrap(), 7);
+        assert_eq!(parse_range("month").unwrap(), 30);
+        assert_eq!(parse_range("14d").unwrap(), 14);
+        assert_eq!(parse_range("90").unwrap(), 90);
+        assert!(parse_range("0d").is_err());
+        assert!(parse_range("fortnight").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_initialize() {
+        let response = handle_message(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], "exactobar");
+    }
+
+    #[tokio::test]
+    async fn test_notifications_get_no_response() {
+        let response =
+            handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).await;
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tools_list() {
+        let response = handle_message(r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#)
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["get_usage", "get_cost"]);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let response = handle_message("not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = handle_message(
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_usage","arguments":{"provider":"nope"}}}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["result"]["isError"], true);
+    }
+}
@@ -0,0 +1,65 @@
+//! Mock server command - serve canned provider responses.
+//!
+//! Runs a [`MockServer`] answering the Claude, Cursor and Copilot usage
+//! endpoints with fixtures, for demos and end-to-end tests that shouldn't
+//! touch real accounts. Point fetches at it with `--endpoint-override`:
+//!
+//! ```text
+//! exactobar mock-server --port 47330 &
+//! exactobar usage -p copilot --endpoint-override http://127.0.0.1:47330
+//! ```
+//!
+//! Strategies still need a credential to send (any will do), so a provider
+//! that isn't logged in fails before reaching the server.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use exactobar_providers::mock_server::ENDPOINTS;
+use exactobar_providers::{MockServer, fixture_routes};
+use std::path::PathBuf;
+
+use crate::Cli;
+
+/// Arguments for the mock-server command.
+#[derive(Args)]
+pub struct MockServerArgs {
+    /// Directory of fixtures, laid out like the providers crate's
+    /// `tests/corpus`: `claude_oauth/`, `cursor/` and `copilot/`.
+    #[arg(long, value_name = "DIR")]
+    pub fixtures: PathBuf,
+
+    /// Port to listen on (127.0.0.1 only). 0 picks a free port.
+    #[arg(long, default_value = "0")]
+    pub port: u16,
+}
+
+/// Runs the mock server until interrupted.
+pub async fn run(args: &MockServerArgs, _cli: &Cli) -> Result<()> {
+    let routes = fixture_routes(&args.fixtures)
+        .with_context(|| format!("Failed to read fixtures in {}", args.fixtures.display()))?;
+    if routes.is_empty() {
+        let formats: Vec<_> = ENDPOINTS.iter().map(|(_, f)| f.name()).collect();
+        anyhow::bail!(
+            "No fixtures in {}; expected one of: {}",
+            args.fixtures.display(),
+            formats.join(", ")
+        );
+    }
+
+    let server = MockServer::start(args.port, routes.clone())
+        .await
+        .with_context(|| format!("Failed to listen on port {}", args.port))?;
+
+    println!("Mock server listening on {}", server.base_url());
+    for route in &routes {
+        println!("  GET {}", route.path);
+    }
+    println!();
+    println!(
+        "Use --endpoint-override {} to fetch from it.",
+        server.base_url()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
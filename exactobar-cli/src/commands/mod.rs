@@ -1,8 +1,19 @@
 //! CLI command implementations.
 
+pub mod annotate;
 pub mod config;
 pub mod cost;
+pub mod doctor;
+pub mod guard;
+pub mod history;
+pub mod mcp;
+pub mod mock_server;
+pub mod prompt;
 pub mod providers;
+pub mod reconcile;
+pub mod sheets;
+pub mod streamdeck;
 pub mod summary;
+pub mod team;
 pub mod usage;
 pub mod watch;
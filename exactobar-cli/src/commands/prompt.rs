@@ -0,0 +1,399 @@
+//! Prompt command - a short usage segment for shell prompts.
+//!
+//! ```bash
+//! exactobar prompt --provider claude --template "{icon} {remaining}%"
+//! ```
+//!
+//! Prompts run this on every command, so it never waits on a provider. It
+//! prints the latest usage the app or another exactobar run shared (see
+//! [`SnapshotCache`]) and, when that's older than `--max-age`, starts
+//! `exactobar usage` in the background so the next prompt has fresh data.
+//! Providers with no shared usage yet print nothing.
+//!
+//! Templates mix text with placeholders:
+//!
+//! | Placeholder | Value |
+//! |---|---|
+//! | `{icon}` | Provider symbol |
+//! | `{name}` / `{provider}` | Display name / CLI name |
+//! | `{remaining}` / `{used}` | Primary window percentage |
+//! | `{reset}` | Time until the primary window resets, like `2h13m` |
+//! | `{secondary_remaining}` / `{secondary_used}` / `{secondary_reset}` | The same for the secondary window |
+//! | `{age}` | How old the usage is |
+//!
+//! A `[...]` section is left out when a placeholder in it has no value, so
+//! `{remaining}%[ ({reset})]` drops the parentheses when the reset time is
+//! unknown. `{{`, `}}`, `[[` and `]]` print a literal brace or bracket.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use exactobar_fetch::SnapshotCache;
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+use crate::Cli;
+use crate::commands::{config, usage};
+
+/// Template used without `--template`.
+const DEFAULT_TEMPLATE: &str = "{icon} {remaining}%";
+
+/// Arguments for the prompt command.
+#[derive(Args)]
+pub struct PromptArgs {
+    /// Template for each provider's segment, like "{icon} {remaining}%".
+    /// Placeholders: icon, name, provider, remaining, used, reset,
+    /// secondary_remaining, secondary_used, secondary_reset, age.
+    #[arg(long, short, default_value = DEFAULT_TEMPLATE)]
+    pub template: String,
+
+    /// Text between providers' segments.
+    #[arg(long, default_value = " ")]
+    pub separator: String,
+
+    /// Refresh usage in the background when it's older than this (like 60s
+    /// or 5m).
+    #[arg(long, value_name = "AGE", default_value = "60s")]
+    pub max_age: String,
+}
+
+/// Runs the prompt command.
+pub async fn run(args: &PromptArgs, cli: &Cli) -> Result<()> {
+    let template = Template::parse(&args.template)?;
+    let max_age = usage::parse_max_age(&args.max_age)?;
+    let settings = config::load_settings().await;
+    let providers = match &cli.provider {
+        Some(_) => usage::parse_provider_selection(cli.provider.as_ref())?,
+        None => usage::enabled_providers(&settings),
+    };
+
+    let cache = SnapshotCache::shared();
+    let now = Utc::now();
+    let mut segments = Vec::new();
+    let mut stale = Vec::new();
+    for provider in &providers {
+        let key = SnapshotCache::key(*provider, settings.data_source(*provider).source_mode());
+        let latest = cache.latest(&key);
+        let fresh = latest.as_ref().is_some_and(|(_, fetched_at)| {
+            (now - *fetched_at).to_std().is_ok_and(|age| age < max_age)
+        });
+        if !fresh && !cache.is_fetching(&key) {
+            stale.push(*provider);
+        }
+
+        if let Some((result, fetched_at)) = latest {
+            let data = PromptData {
+                provider: *provider,
+                snapshot: &result.snapshot,
+                fetched_at,
+                now,
+            };
+            let segment = template.render(&data);
+            if !segment.is_empty() {
+                segments.push(segment);
+            }
+        }
+    }
+
+    if !stale.is_empty() {
+        refresh_in_background(&stale, &args.max_age, cli);
+    }
+    println!("{}", segments.join(&args.separator));
+    Ok(())
+}
+
+/// Starts `exactobar usage` for `providers` without waiting for it. Its
+/// results land in the shared cache for the next prompt.
+fn refresh_in_background(providers: &[ProviderKind], max_age: &str, cli: &Cli) {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let names: Vec<_> = providers.iter().map(|p| p.cli_name()).collect();
+
+    let mut cmd = Command::new(exe);
+    cmd.args(["usage", "--format", "json", "--max-age", max_age])
+        .args(["--provider", &names.join(",")]);
+    if let Some(profile) = &cli.profile {
+        cmd.args(["--profile", profile]);
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    match cmd.spawn() {
+        Ok(_) => debug!(providers = ?names, "Refreshing usage in the background"),
+        Err(e) => debug!(error = %e, "Failed to start background refresh"),
+    }
+}
+
+// ============================================================================
+// Templates
+// ============================================================================
+
+/// What a template is rendered with.
+struct PromptData<'a> {
+    provider: ProviderKind,
+    snapshot: &'a UsageSnapshot,
+    fetched_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+}
+
+/// A value a placeholder stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Icon,
+    Name,
+    Provider,
+    Remaining,
+    Used,
+    Reset,
+    SecondaryRemaining,
+    SecondaryUsed,
+    SecondaryReset,
+    Age,
+}
+
+impl Field {
+    const NAMES: [(&'static str, Self); 10] = [
+        ("icon", Self::Icon),
+        ("name", Self::Name),
+        ("provider", Self::Provider),
+        ("remaining", Self::Remaining),
+        ("used", Self::Used),
+        ("reset", Self::Reset),
+        ("secondary_remaining", Self::SecondaryRemaining),
+        ("secondary_used", Self::SecondaryUsed),
+        ("secondary_reset", Self::SecondaryReset),
+        ("age", Self::Age),
+    ];
+
+    fn parse(name: &str) -> Result<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| *n == name.trim())
+            .map(|(_, field)| *field)
+            .ok_or_else(|| {
+                let valid: Vec<_> = Self::NAMES.iter().map(|(n, _)| *n).collect();
+                anyhow::anyhow!(
+                    "Unknown placeholder: {{{}}}. Valid placeholders: {}",
+                    name,
+                    valid.join(", ")
+                )
+            })
+    }
+
+    fn value(self, data: &PromptData<'_>) -> Option<String> {
+        let snapshot = data.snapshot;
+        let remaining = |w: &UsageWindow| format!("{:.0}", w.remaining_percent());
+        let used = |w: &UsageWindow| format!("{:.0}", w.clamped_used_percent());
+        let reset = |w: &UsageWindow| w.resets_at.map(|at| format_countdown(at - data.now));
+        match self {
+            Self::Icon => Some(provider_icon(data.provider).to_string()),
+            Self::Name => Some(data.provider.display_name().to_string()),
+            Self::Provider => Some(data.provider.cli_name().to_string()),
+            Self::Remaining => snapshot.primary.as_ref().map(remaining),
+            Self::Used => snapshot.primary.as_ref().map(used),
+            Self::Reset => snapshot.primary.as_ref().and_then(reset),
+            Self::SecondaryRemaining => snapshot.secondary.as_ref().map(remaining),
+            Self::SecondaryUsed => snapshot.secondary.as_ref().map(used),
+            Self::SecondaryReset => snapshot.secondary.as_ref().and_then(reset),
+            Self::Age => Some(format_countdown(data.now - data.fetched_at)),
+        }
+    }
+}
+
+/// A piece of a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field(Field),
+    /// A `[...]` section, left out if a placeholder in it has no value.
+    Optional(Vec<Part>),
+}
+
+/// A parsed prompt template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        // The parts before an open `[`, while parsing inside it
+        let mut outer: Option<Vec<Part>> = None;
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let doubled = matches!(c, '{' | '}' | '[' | ']') && chars.peek() == Some(&c);
+            if doubled {
+                chars.next();
+                text.push(c);
+                continue;
+            }
+            match c {
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        bail!("Unclosed {{ in template: {}", template);
+                    }
+                    flush(&mut text, &mut parts);
+                    parts.push(Part::Field(Field::parse(&name)?));
+                }
+                '[' => {
+                    if outer.is_some() {
+                        bail!("Nested [ in template: {}", template);
+                    }
+                    flush(&mut text, &mut parts);
+                    outer = Some(std::mem::take(&mut parts));
+                }
+                ']' => {
+                    let Some(before) = outer.take() else {
+                        bail!("Unmatched ] in template: {}", template);
+                    };
+                    flush(&mut text, &mut parts);
+                    let section = std::mem::replace(&mut parts, before);
+                    parts.push(Part::Optional(section));
+                }
+                '}' => bail!("Unmatched }} in template: {}", template),
+                c => text.push(c),
+            }
+        }
+        if outer.is_some() {
+            bail!("Unclosed [ in template: {}", template);
+        }
+        flush(&mut text, &mut parts);
+        Ok(Self { parts })
+    }
+
+    fn render(&self, data: &PromptData<'_>) -> String {
+        let mut out = String::new();
+        render_parts(&self.parts, data, &mut out);
+        out
+    }
+}
+
+fn flush(text: &mut String, parts: &mut Vec<Part>) {
+    if !text.is_empty() {
+        parts.push(Part::Text(std::mem::take(text)));
+    }
+}
+
+/// Renders `parts` into `out`, returning false if a placeholder had no
+/// value.
+fn render_parts(parts: &[Part], data: &PromptData<'_>, out: &mut String) -> bool {
+    let mut complete = true;
+    for part in parts {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Field(field) => match field.value(data) {
+                Some(value) => out.push_str(&value),
+                None => complete = false,
+            },
+            Part::Optional(section) => {
+                let mut rendered = String::new();
+                if render_parts(section, data, &mut rendered) {
+                    out.push_str(&rendered);
+                }
+            }
+        }
+    }
+    complete
+}
+
+/// Returns a short symbol for a provider.
+fn provider_icon(provider: ProviderKind) -> &'static str {
+    match provider {
+        ProviderKind::Claude => "✻",
+        ProviderKind::Codex => "◎",
+        ProviderKind::Gemini | ProviderKind::VertexAI => "✦",
+        ProviderKind::Copilot => "⧉",
+        ProviderKind::Cursor => "▲",
+        _ => "●",
+    }
+}
+
+/// Formats a duration compactly: `45s`, `12m`, `2h13m`, `3d4h`.
+fn format_countdown(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    match (days, hours, mins) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h{}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d{}h", d, h),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, snapshot: &UsageSnapshot) -> String {
+        let now = Utc::now();
+        let data = PromptData {
+            provider: ProviderKind::Claude,
+            snapshot,
+            fetched_at: now - Duration::seconds(90),
+            now,
+        };
+        Template::parse(template).unwrap().render(&data)
+    }
+
+    #[test]
+    fn test_render() {
+        let mut snapshot = UsageSnapshot::new();
+        let mut primary = UsageWindow::new(28.0);
+        primary.resets_at = Some(Utc::now() + Duration::minutes(133) + Duration::seconds(30));
+        snapshot.primary = Some(primary);
+
+        assert_eq!(render(DEFAULT_TEMPLATE, &snapshot), "✻ 72%");
+        assert_eq!(
+            render("{provider}: {used}% used[ ({reset})] {age} old", &snapshot),
+            "claude: 28% used (2h13m) 1m old"
+        );
+        // Sections without values are left out, bare placeholders are empty
+        assert_eq!(
+            render("{name} {remaining}%[ wk {secondary_remaining}%]", &snapshot),
+            "Claude 72%"
+        );
+        assert_eq!(render("{secondary_used}%", &snapshot), "%");
+        assert_eq!(render("{{{remaining}}} [[x]]", &snapshot), "{72} [x]");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{remainign}%").is_err());
+        assert!(Template::parse("{remaining").is_err());
+        assert!(Template::parse("[{reset}").is_err());
+        assert!(Template::parse("[[a] b]").is_err());
+        assert!(Template::parse("a]").is_err());
+        assert!(Template::parse("[[{reset}]]").is_ok());
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(Duration::seconds(45)), "45s");
+        assert_eq!(format_countdown(Duration::minutes(12)), "12m");
+        assert_eq!(format_countdown(Duration::hours(2)), "2h");
+        assert_eq!(format_countdown(Duration::minutes(133)), "2h13m");
+        assert_eq!(format_countdown(Duration::hours(76)), "3d4h");
+        assert_eq!(format_countdown(Duration::seconds(-5)), "0s");
+    }
+}
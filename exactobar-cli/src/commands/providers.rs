@@ -38,7 +38,7 @@ pub async fn run(cli: &Cli) -> Result<()> {
                     .count()
             );
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Waybar => {
             let formatter = JsonFormatter::new(cli.pretty);
             let output = formatter.format_providers(&providers)?;
             println!("{}", output);
@@ -0,0 +1,101 @@
+//! Reconcile command - compare an invoice export with computed costs.
+//!
+//! Reads an OpenAI or Anthropic cost export (CSV) and compares each
+//! day/model line with the cost ExactoBar computes from local logs over the
+//! same days. Lines that differ by more than `--tolerance` are listed, which
+//! helps spot missing or outdated entries in the pricing table.
+//!
+//! The provider is inferred from the export (OpenAI → codex, Anthropic →
+//! claude); pass `--provider` for other exports.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use exactobar_providers::cost::{self, Invoice, Reconciliation};
+use exactobar_providers::{LogIndex, PricingTable, ProviderRegistry};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::output::{JsonFormatter, TextFormatter};
+use crate::{Cli, OutputFormat};
+
+/// Arguments for the reconcile command.
+#[derive(Args)]
+pub struct ReconcileArgs {
+    /// Invoice CSV export to compare against.
+    pub invoice: PathBuf,
+
+    /// Largest difference (USD) per day and model still counted as a match.
+    #[arg(long, default_value = "0.01")]
+    pub tolerance: f64,
+
+    /// List every day and model, not just discrepancies.
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// Runs the reconcile command.
+pub async fn run(args: &ReconcileArgs, cli: &Cli) -> Result<()> {
+    let invoice = Invoice::load(&args.invoice)
+        .with_context(|| format!("Failed to load {}", args.invoice.display()))?;
+    info!(
+        lines = invoice.lines.len(),
+        format = ?invoice.format,
+        "Loaded invoice"
+    );
+
+    let provider_name = cli
+        .provider
+        .as_deref()
+        .or(invoice.format.provider_cli_name())
+        .context("Can't tell which provider this invoice is for; pass --provider")?;
+    let Some(desc) = ProviderRegistry::get_by_cli_name(provider_name) else {
+        anyhow::bail!("Unknown provider: {}", provider_name);
+    };
+    if !desc.token_cost.supports_token_cost {
+        anyhow::bail!(
+            "Provider {} does not support token cost tracking",
+            provider_name
+        );
+    }
+
+    // Bring the index up to date before comparing
+    let pricing = PricingTable::load_default();
+    let index_path = LogIndex::default_path(desc.cli_name());
+    let mut index = LogIndex::load(&index_path);
+    if cost::scan_provider(&desc.token_cost, &mut index, 0, &pricing)?.is_some() {
+        if let Err(e) = index.save(&index_path) {
+            warn!(provider = ?desc.id, error = %e, "Failed to save cost index");
+        }
+    }
+
+    let reconciliation = Reconciliation::new(&invoice, &index, &pricing);
+
+    match cli.format {
+        OutputFormat::Text => {
+            let formatter = TextFormatter::new(!cli.no_color);
+            println!(
+                "{}",
+                formatter.format_reconciliation(
+                    &reconciliation,
+                    Some(desc),
+                    args.tolerance,
+                    args.all
+                )
+            );
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let formatter = JsonFormatter::new(cli.pretty);
+            println!(
+                "{}",
+                formatter.format_reconciliation(
+                    desc.id,
+                    &reconciliation,
+                    args.tolerance,
+                    args.all
+                )?
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Sheets command - append daily usage rows to a Google Sheet.
+//!
+//! Exports complete days not exported yet for every provider with a tab
+//! (`exactobar config sheets-tab`), to the spreadsheet set with
+//! `exactobar config sheets`. The app does this on its own once a day; run
+//! this from cron on machines without it.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Args;
+use exactobar_fetch::host::HttpClient;
+use exactobar_providers::sheets::{self, ExportState};
+use exactobar_store::SettingsStore;
+
+use crate::Cli;
+
+/// Arguments for the sheets command.
+#[derive(Args)]
+pub struct SheetsArgs {
+    /// Print the rows that would be appended without sending them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Runs the sheets command.
+pub async fn run(args: &SheetsArgs, _cli: &Cli) -> Result<()> {
+    let settings = SettingsStore::load_default().await?.get().await;
+    let export = settings
+        .sheets_export
+        .as_ref()
+        .context("No spreadsheet set; run `exactobar config sheets <id> <key file>`")?;
+    let tabs = settings.sheets_tabs();
+    if tabs.is_empty() {
+        anyhow::bail!("No providers exported; run `exactobar config sheets-tab <provider> <tab>`");
+    }
+
+    let today = Utc::now().date_naive();
+
+    if args.dry_run {
+        let state = ExportState::load(&ExportState::default_path());
+        let due = sheets::due_rows(&tabs, &state, today).await?;
+        if due.is_empty() {
+            println!("Nothing to export");
+        }
+        for (tab, rows) in &due.tabs {
+            for row in rows {
+                let cells: Vec<String> = row
+                    .values()
+                    .iter()
+                    .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                    .collect();
+                println!("{}: {}", tab, cells.join(", "));
+            }
+        }
+        return Ok(());
+    }
+
+    let appended = sheets::export_due(export, &tabs, &HttpClient::new(), today)
+        .await
+        .context("Failed to export to Google Sheets")?;
+    match appended {
+        0 => println!("Nothing to export"),
+        1 => println!("Appended 1 row"),
+        n => println!("Appended {} rows", n),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,771 @@
+//! Stream Deck command - push usage to a Stream Deck plugin.
+//!
+//! Serves usage on `127.0.0.1` for a Stream Deck plugin (or anything else
+//! on this machine) to show on its keys, fetching on an interval:
+//!
+//! - `GET /v1/state` - the latest usage, as below
+//! - `POST /v1/refresh` - fetches now, skipping cached results. Add
+//!   `?provider=claude` to fetch one provider.
+//! - `GET /v1/ws` - a WebSocket that gets the state on connect and after
+//!   every fetch. Send `{"action":"refresh"}` or
+//!   `{"action":"refresh","provider":"claude"}` when a key is pressed.
+//!
+//! ```json
+//! {
+//!   "updated_at": "2026-10-16T09:30:00Z",
+//!   "providers": [
+//!     {
+//!       "provider": "claude", "name": "Claude", "remaining": 72.0, "used": 28.0,
+//!       "level": "ok", "color": "#34C759", "brand_color": "#D19370",
+//!       "resets_at": "2026-10-16T12:00:00Z", "error": null
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `remaining` and `used` are for the session window. `level` follows the
+//! window closest to its limit: `warning` under 50% left, `critical` under
+//! 20%, or `error` if the fetch failed; `color` is a key color for it.
+//!
+//! Requests from web pages (an `http` or `https` `Origin`) are refused, so
+//! sites open in a browser can't read usage.
+
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use exactobar_core::{ProviderBranding, ProviderKind, UsageSnapshot};
+use exactobar_fetch::{FetchContext, SNAPSHOT_MAX_AGE};
+use exactobar_providers::ProviderRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+use super::{config, usage};
+use crate::Cli;
+
+/// Default port, next to the companion endpoint's.
+pub const DEFAULT_PORT: u16 = 47_322;
+
+/// Shortest interval between scheduled fetches.
+const MIN_INTERVAL_SECS: u64 = 10;
+
+/// How long a connection may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum size of a request's headers.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Maximum size of a WebSocket frame from a plugin.
+const MAX_FRAME_BYTES: u64 = 64 * 1024;
+
+/// Appended to a WebSocket key before hashing it (RFC 6455).
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+// WebSocket opcodes
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// A refresh request: one provider, or all of them.
+type RefreshRequest = Option<ProviderKind>;
+
+/// Arguments for the streamdeck command.
+#[derive(Args)]
+pub struct StreamDeckArgs {
+    /// Port to listen on, on 127.0.0.1.
+    #[arg(long, default_value_t = DEFAULT_PORT)]
+    pub port: u16,
+
+    /// Seconds between fetches (at least 10).
+    #[arg(long, short, default_value = "60")]
+    pub interval: u64,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// How close a provider is to its limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Ok,
+    Warning,
+    Critical,
+    Error,
+}
+
+impl Level {
+    fn from_remaining(remaining: f64) -> Self {
+        if remaining < 20.0 {
+            Self::Critical
+        } else if remaining < 50.0 {
+            Self::Warning
+        } else {
+            Self::Ok
+        }
+    }
+
+    /// Key color for the level.
+    fn color(self) -> &'static str {
+        match self {
+            Self::Ok => "#34C759",
+            Self::Warning => "#FFCC00",
+            Self::Critical => "#FF3B30",
+            Self::Error => "#8E8E93",
+        }
+    }
+}
+
+/// Usage pushed to plugins.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeckState {
+    /// When the last fetch finished; `None` until the first one does.
+    pub updated_at: Option<DateTime<Utc>>,
+    pub providers: Vec<DeckProvider>,
+}
+
+/// One provider's usage, ready to draw on a key.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckProvider {
+    /// CLI name, as used in refresh requests.
+    pub provider: &'static str,
+    pub name: &'static str,
+    /// Remaining percentage of the session window.
+    pub remaining: Option<f64>,
+    /// Used percentage of the session window.
+    pub used: Option<f64>,
+    pub level: Level,
+    /// Key color for `level`.
+    pub color: &'static str,
+    /// Provider's brand color.
+    pub brand_color: String,
+    /// When the session window resets.
+    pub resets_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl DeckState {
+    /// Builds the state for `providers`, in order, from fetch results.
+    fn new(
+        providers: &[ProviderKind],
+        results: &HashMap<ProviderKind, Result<UsageSnapshot, String>>,
+    ) -> Self {
+        let providers = providers
+            .iter()
+            .filter_map(|provider| {
+                let result = results.get(provider)?;
+                let brand_color = ProviderRegistry::get(*provider)
+                    .map_or_else(
+                        || ProviderBranding::for_provider(*provider).color,
+                        |d| d.branding.color,
+                    )
+                    .to_hex();
+                let (window, level, error) = match result {
+                    Ok(snapshot) => (
+                        snapshot.primary.as_ref(),
+                        snapshot
+                            .min_remaining_percent()
+                            .map_or(Level::Ok, Level::from_remaining),
+                        None,
+                    ),
+                    Err(e) => (None, Level::Error, Some(e.clone())),
+                };
+                Some(DeckProvider {
+                    provider: provider.cli_name(),
+                    name: provider.display_name(),
+                    remaining: window.map(|w| w.remaining_percent()),
+                    used: window.map(|w| w.used_percent),
+                    level,
+                    color: level.color(),
+                    brand_color,
+                    resets_at: window.and_then(|w| w.resets_at),
+                    error,
+                })
+            })
+            .collect();
+
+        Self {
+            updated_at: Some(Utc::now()),
+            providers,
+        }
+    }
+}
+
+// ============================================================================
+// Command
+// ============================================================================
+
+/// Runs the streamdeck command until interrupted.
+pub async fn run(args: &StreamDeckArgs, cli: &Cli) -> Result<()> {
+    let settings = config::load_settings().await;
+    let providers = match &cli.provider {
+        Some(_) => usage::parse_provider_selection(cli.provider.as_ref())?,
+        None => usage::enabled_providers(&settings),
+    };
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, args.port))
+        .await
+        .with_context(|| format!("Failed to listen on port {}", args.port))?;
+    let addr = listener.local_addr()?;
+    info!(%addr, "Stream Deck endpoint listening");
+    if !cli.quiet {
+        eprintln!("Serving usage for Stream Deck on http://{addr}/v1/state (ws://{addr}/v1/ws)");
+    }
+
+    let (state_tx, state_rx) = watch::channel(DeckState::default());
+    let (refresh_tx, mut refresh_rx) = mpsc::unbounded_channel();
+    tokio::spawn(serve(listener, state_rx, refresh_tx));
+
+    let ctx = FetchContext::builder()
+        .timeout(Duration::from_secs(30))
+        .build();
+    let source_mode = |provider| settings.data_source(provider).source_mode();
+    let mut ticker =
+        tokio::time::interval(Duration::from_secs(args.interval.max(MIN_INTERVAL_SECS)));
+    let mut results = HashMap::new();
+
+    loop {
+        // Key presses fetch right away, skipping cached results
+        let (targets, max_age) = tokio::select! {
+            _ = ticker.tick() => (providers.clone(), SNAPSHOT_MAX_AGE),
+            Some(request) = refresh_rx.recv() => {
+                let targets = match request {
+                    Some(provider) if providers.contains(&provider) => vec![provider],
+                    Some(_) => continue,
+                    None => providers.clone(),
+                };
+                (targets, Duration::ZERO)
+            }
+        };
+        debug!(providers = ?targets, "Fetching for Stream Deck");
+        results
+            .extend(usage::fetch_all(&targets, &ctx, &settings, source_mode, max_age, false).await);
+        state_tx.send_replace(DeckState::new(&providers, &results));
+    }
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+/// Accepts connections until the task is dropped.
+async fn serve(
+    listener: TcpListener,
+    state: watch::Receiver<DeckState>,
+    refresh: mpsc::UnboundedSender<RefreshRequest>,
+) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(error = %e, "Stream Deck endpoint accept failed");
+                continue;
+            }
+        };
+
+        let (state, refresh) = (state.clone(), refresh.clone());
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state, refresh).await {
+                debug!(%peer, error = %e, "Stream Deck connection error");
+            }
+        });
+    }
+}
+
+/// A request line and headers.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: HashMap<String, String>,
+}
+
+impl Request {
+    /// Parses a request's head, up to but not including the blank line.
+    /// Returns `None` for malformed input.
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.lines();
+        let mut request_line = lines.next()?.split_whitespace();
+        let (method, target) = (request_line.next()?, request_line.next()?);
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+            .collect();
+
+        Some(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            headers,
+        })
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// Returns true if a web page sent the request.
+    fn is_from_web_page(&self) -> bool {
+        self.header("origin")
+            .is_some_and(|origin| origin.starts_with("http://") || origin.starts_with("https://"))
+    }
+
+    /// Returns the `provider` query parameter.
+    fn provider(&self) -> Option<&str> {
+        self.query
+            .split('&')
+            .find_map(|param| param.strip_prefix("provider="))
+    }
+}
+
+/// Reads a request's head. Returns `None` if the connection closes first
+/// or the head is too long.
+async fn read_request<R: AsyncBufRead + Unpin>(read: &mut R) -> io::Result<Option<Request>> {
+    let mut limited = read.take(MAX_HEAD_BYTES);
+    let mut head = String::new();
+    loop {
+        let start = head.len();
+        if limited.read_line(&mut head).await? == 0 {
+            return Ok(None);
+        }
+        if head[start..].trim().is_empty() {
+            return Ok(Request::parse(&head));
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    state: watch::Receiver<DeckState>,
+    refresh: mpsc::UnboundedSender<RefreshRequest>,
+) -> io::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut read = BufReader::new(read);
+
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut read)).await {
+        Ok(Ok(Some(request))) => request,
+        Ok(Ok(None)) => {
+            return write_response(&mut write, 400, r#"{"error":"bad request"}"#).await;
+        }
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return write_response(&mut write, 408, r#"{"error":"timeout"}"#).await,
+    };
+
+    if request.is_from_web_page() {
+        return write_response(&mut write, 403, r#"{"error":"forbidden"}"#).await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/state") => {
+            let body = serde_json::to_string(&*state.borrow()).map_err(io::Error::other)?;
+            write_response(&mut write, 200, &body).await
+        }
+        ("POST", "/v1/refresh") => match lookup_provider(request.provider()) {
+            Ok(provider) => {
+                let _ = refresh.send(provider);
+                write_response(&mut write, 202, r#"{"ok":true}"#).await
+            }
+            Err(()) => write_response(&mut write, 404, r#"{"error":"unknown provider"}"#).await,
+        },
+        ("GET", "/v1/ws") => match request.header("sec-websocket-key") {
+            Some(key) => websocket(read, write, key, state, refresh).await,
+            None => write_response(&mut write, 400, r#"{"error":"expected websocket"}"#).await,
+        },
+        _ => write_response(&mut write, 404, r#"{"error":"not found"}"#).await,
+    }
+}
+
+/// Looks up a provider by CLI name; no name means all providers.
+fn lookup_provider(name: Option<&str>) -> Result<RefreshRequest, ()> {
+    name.map(|name| {
+        ProviderRegistry::get_by_cli_name(name)
+            .map(|desc| desc.id)
+            .ok_or(())
+    })
+    .transpose()
+}
+
+async fn write_response<W: AsyncWrite + Unpin>(
+    write: &mut W,
+    status: u16,
+    body: &str,
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    write.write_all(response.as_bytes()).await?;
+    write.shutdown().await
+}
+
+// ============================================================================
+// WebSocket
+// ============================================================================
+
+/// A message from a plugin.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum PluginMessage {
+    /// A key was pressed: fetch now.
+    Refresh {
+        #[serde(default)]
+        provider: Option<String>,
+    },
+}
+
+/// A frame the writer has to answer.
+enum Control {
+    Ping(Vec<u8>),
+    Close,
+}
+
+/// Completes the WebSocket handshake, then pushes the state whenever it
+/// changes until the plugin disconnects.
+async fn websocket<R, W>(
+    read: R,
+    mut write: W,
+    key: &str,
+    mut state: watch::Receiver<DeckState>,
+    refresh: mpsc::UnboundedSender<RefreshRequest>,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin,
+{
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    write.write_all(response.as_bytes()).await?;
+
+    // Frames are read on their own task so a state push never interrupts
+    // one halfway through
+    let (control_tx, mut control_rx) = mpsc::unbounded_channel();
+    let reader = tokio::spawn(read_messages(read, refresh, control_tx));
+
+    let result = async {
+        state.mark_changed();
+        loop {
+            tokio::select! {
+                changed = state.changed() => {
+                    if changed.is_err() {
+                        return write_frame(&mut write, OP_CLOSE, &[]).await;
+                    }
+                    let text = serde_json::to_string(&*state.borrow_and_update())
+                        .map_err(io::Error::other)?;
+                    write_frame(&mut write, OP_TEXT, text.as_bytes()).await?;
+                }
+                control = control_rx.recv() => match control {
+                    Some(Control::Ping(payload)) => {
+                        write_frame(&mut write, OP_PONG, &payload).await?;
+                    }
+                    // Answer the plugin's close frame, or give up on a
+                    // broken connection
+                    Some(Control::Close) | None => {
+                        return write_frame(&mut write, OP_CLOSE, &[]).await;
+                    }
+                },
+            }
+        }
+    }
+    .await;
+
+    reader.abort();
+    result
+}
+
+/// Returns the `Sec-WebSocket-Accept` value for a `Sec-WebSocket-Key`.
+fn accept_key(key: &str) -> String {
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}{}", key.trim(), WEBSOCKET_GUID).as_bytes(),
+    );
+    BASE64_STANDARD.encode(digest)
+}
+
+/// Reads frames from a plugin, passing refresh requests on, until it
+/// closes the connection.
+async fn read_messages<R: AsyncRead + Unpin>(
+    mut read: R,
+    refresh: mpsc::UnboundedSender<RefreshRequest>,
+    control: mpsc::UnboundedSender<Control>,
+) {
+    loop {
+        let Ok(Some((opcode, payload))) = read_frame(&mut read).await else {
+            break;
+        };
+        match opcode {
+            OP_TEXT => match serde_json::from_slice(&payload) {
+                Ok(PluginMessage::Refresh { provider }) => {
+                    if let Ok(request) = lookup_provider(provider.as_deref()) {
+                        let _ = refresh.send(request);
+                    } else {
+                        debug!(?provider, "Refresh for unknown provider");
+                    }
+                }
+                Err(e) => debug!(error = %e, "Ignoring Stream Deck message"),
+            },
+            OP_PING => {
+                let _ = control.send(Control::Ping(payload));
+            }
+            OP_CLOSE => break,
+            _ => {}
+        }
+    }
+    let _ = control.send(Control::Close);
+}
+
+/// Reads one frame, returning its opcode and unmasked payload. Returns
+/// `None` for frames that aren't masked or are too large.
+///
+/// Messages split across frames aren't reassembled; plugins send short
+/// JSON messages in one frame.
+async fn read_frame<R: AsyncRead + Unpin>(read: &mut R) -> io::Result<Option<(u8, Vec<u8>)>> {
+    let mut head = [0u8; 2];
+    read.read_exact(&mut head).await?;
+    let opcode = head[0] & 0x0F;
+    let masked = head[1] & 0x80 != 0;
+    let len = match head[1] & 0x7F {
+        126 => u64::from(read.read_u16().await?),
+        127 => read.read_u64().await?,
+        len => u64::from(len),
+    };
+
+    // Clients must mask every frame
+    if !masked || len > MAX_FRAME_BYTES {
+        return Ok(None);
+    }
+
+    let mut mask = [0u8; 4];
+    read.read_exact(&mut mask).await?;
+    let mut payload = vec![0u8; len as usize];
+    read.read_exact(&mut payload).await?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok(Some((opcode, payload)))
+}
+
+/// Writes one unmasked, unfragmented frame.
+async fn write_frame<W: AsyncWrite + Unpin>(
+    write: &mut W,
+    opcode: u8,
+    payload: &[u8],
+) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    let len = payload.len();
+    if len < 126 {
+        frame.push(len as u8);
+    } else if let Ok(len) = u16::try_from(len) {
+        frame.push(126);
+        frame.extend_from_slice(&len.to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    frame.extend_from_slice(payload);
+    write.write_all(&frame).await?;
+    write.flush().await
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exactobar_core::UsageWindow;
+    use std::net::SocketAddr;
+
+    async fn start() -> (
+        SocketAddr,
+        watch::Sender<DeckState>,
+        mpsc::UnboundedReceiver<RefreshRequest>,
+    ) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (state_tx, state_rx) = watch::channel(DeckState::default());
+        let (refresh_tx, refresh_rx) = mpsc::unbounded_channel();
+        tokio::spawn(serve(listener, state_rx, refresh_tx));
+        (addr, state_tx, refresh_rx)
+    }
+
+    async fn send(addr: SocketAddr, raw: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut out = String::new();
+        stream.read_to_string(&mut out).await.unwrap();
+        out
+    }
+
+    async fn read_text<R: AsyncRead + Unpin>(read: &mut R) -> String {
+        let mut head = [0u8; 2];
+        read.read_exact(&mut head).await.unwrap();
+        assert_eq!(head[0], 0x80 | OP_TEXT);
+        let len = match head[1] {
+            126 => usize::from(read.read_u16().await.unwrap()),
+            len => usize::from(len),
+        };
+        let mut text = vec![0u8; len];
+        read.read_exact(&mut text).await.unwrap();
+        String::from_utf8(text).unwrap()
+    }
+
+    fn state() -> DeckState {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(85.0));
+        let results = HashMap::from([
+            (ProviderKind::Claude, Ok(snapshot)),
+            (ProviderKind::Codex, Err("not logged in".to_string())),
+        ]);
+        DeckState::new(&[ProviderKind::Claude, ProviderKind::Codex], &results)
+    }
+
+    #[test]
+    fn test_state() {
+        let state = state();
+        let claude = &state.providers[0];
+        assert_eq!(claude.provider, "claude");
+        assert_eq!(claude.remaining, Some(15.0));
+        assert_eq!(claude.level, Level::Critical);
+        assert_eq!(claude.color, "#FF3B30");
+
+        let codex = &state.providers[1];
+        assert_eq!(codex.level, Level::Error);
+        assert_eq!(codex.remaining, None);
+        assert_eq!(codex.error.as_deref(), Some("not logged in"));
+    }
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frames() {
+        let mut out = Vec::new();
+        write_frame(&mut out, OP_TEXT, b"hi").await.unwrap();
+        assert_eq!(out, [0x81, 2, b'h', b'i']);
+
+        let long = vec![b'x'; 300];
+        let mut out = Vec::new();
+        write_frame(&mut out, OP_TEXT, &long).await.unwrap();
+        assert_eq!(&out[..4], [0x81, 126, 1, 44]);
+
+        // Masked frame from a client
+        let mask = [1, 2, 3, 4];
+        let mut frame = vec![0x81, 0x80 | 3];
+        frame.extend_from_slice(&mask);
+        frame.extend(b"abc".iter().zip(mask).map(|(b, m)| b ^ m));
+        let (opcode, payload) = read_frame(&mut frame.as_slice()).await.unwrap().unwrap();
+        assert_eq!(opcode, OP_TEXT);
+        assert_eq!(payload, b"abc");
+
+        // Unmasked frames are refused
+        let frame = [0x81, 3, b'a', b'b', b'c'];
+        assert!(read_frame(&mut frame.as_slice()).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let request = Request::parse(
+            "POST /v1/refresh?provider=codex HTTP/1.1\r\nOrigin: https://example.com\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.path, "/v1/refresh");
+        assert_eq!(request.provider(), Some("codex"));
+        assert!(request.is_from_web_page());
+
+        let request = Request::parse("GET /v1/state HTTP/1.1\r\n").unwrap();
+        assert!(!request.is_from_web_page());
+        assert_eq!(request.provider(), None);
+        assert!(Request::parse("").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoints() {
+        let (addr, state_tx, mut refresh_rx) = start().await;
+        state_tx.send_replace(state());
+
+        let response = send(addr, "GET /v1/state HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains(r#""level":"critical""#));
+
+        let response = send(addr, "POST /v1/refresh?provider=claude HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 202"));
+        assert_eq!(refresh_rx.recv().await, Some(Some(ProviderKind::Claude)));
+
+        let response = send(addr, "POST /v1/refresh?provider=nope HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404"));
+
+        let response = send(
+            addr,
+            "GET /v1/state HTTP/1.1\r\nOrigin: https://example.com\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403"));
+    }
+
+    #[tokio::test]
+    async fn test_websocket() {
+        let (addr, state_tx, mut refresh_rx) = start().await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"GET /v1/ws HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut read = BufReader::new(stream);
+
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            read.read_line(&mut head).await.unwrap();
+        }
+        assert!(head.starts_with("HTTP/1.1 101"));
+        assert!(head.contains("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+        // Current state on connect, then every update
+        assert!(read_text(&mut read).await.contains(r#""providers":[]"#));
+        state_tx.send_replace(state());
+        assert!(read_text(&mut read).await.contains("not logged in"));
+
+        // A key press
+        let message = br#"{"action":"refresh"}"#;
+        let mask = [7, 7, 7, 7];
+        let mut frame = vec![0x81, 0x80 | message.len() as u8];
+        frame.extend_from_slice(&mask);
+        frame.extend(message.iter().map(|b| b ^ 7));
+        read.get_mut().write_all(&frame).await.unwrap();
+        assert_eq!(refresh_rx.recv().await, Some(None));
+    }
+}
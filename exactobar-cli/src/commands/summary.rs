@@ -8,7 +8,7 @@ use std::collections::HashMap;
 use tokio::time::Duration;
 use tracing::info;
 
-use crate::output::{JsonFormatter, TextFormatter};
+use crate::output::{JsonFormatter, TextFormatter, WaybarFormatter};
 use crate::{Cli, OutputFormat};
 
 /// Runs the summary command.
@@ -57,6 +57,9 @@ pub async fn run(cli: &Cli) -> Result<()> {
             let output = formatter.format_summary(&results)?;
             println!("{}", output);
         }
+        OutputFormat::Waybar => {
+            println!("{}", WaybarFormatter.format_summary(&results)?);
+        }
     }
 
     Ok(())
@@ -0,0 +1,56 @@
+//! Team command - rank an organization's members by usage this week.
+//!
+//! Uses the provider's admin API: Cursor's with `CURSOR_ADMIN_API_KEY`,
+//! Anthropic's Claude Code usage report with `ANTHROPIC_ADMIN_KEY`. Either
+//! key can also be stored in the keychain as `cursor_admin` or
+//! `anthropic_admin`.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use exactobar_fetch::host::HttpClient;
+use exactobar_providers::{ProviderRegistry, fetch_team_usage, supports_team_usage};
+
+use crate::output::{JsonFormatter, TextFormatter};
+use crate::{Cli, OutputFormat};
+
+/// Arguments for the team command.
+#[derive(Args)]
+pub struct TeamArgs {
+    /// How many members to list.
+    #[arg(long, short = 'n', default_value = "10")]
+    pub top: usize,
+}
+
+/// Runs the team command.
+pub async fn run(args: &TeamArgs, cli: &Cli) -> Result<()> {
+    let provider_name = cli
+        .provider
+        .as_deref()
+        .context("Pass --provider cursor or --provider claude")?;
+    let Some(desc) = ProviderRegistry::get_by_cli_name(provider_name) else {
+        anyhow::bail!("Unknown provider: {}", provider_name);
+    };
+    if !supports_team_usage(desc.id) {
+        anyhow::bail!(
+            "Provider {} has no team usage API (try cursor or claude)",
+            provider_name
+        );
+    }
+
+    let team = fetch_team_usage(desc.id, &HttpClient::new())
+        .await
+        .with_context(|| format!("Failed to fetch {} team usage", desc.display_name()))?;
+
+    match cli.format {
+        OutputFormat::Text => {
+            let formatter = TextFormatter::new(!cli.no_color);
+            println!("{}", formatter.format_team(&team, Some(desc), args.top));
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let formatter = JsonFormatter::new(cli.pretty);
+            println!("{}", formatter.format_team(&team, args.top)?);
+        }
+    }
+
+    Ok(())
+}
@@ -1,14 +1,22 @@
 //! Usage command - fetch and display provider usage.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
-use exactobar_core::{ProviderKind, UsageSnapshot};
-use exactobar_fetch::{FetchContext, SourceMode};
-use exactobar_providers::ProviderRegistry;
+use exactobar_core::{ProviderKind, ResetZone, UsageSnapshot};
+use exactobar_fetch::{
+    FetchContext, FetchProgress, ParseMode, SNAPSHOT_MAX_AGE, SourceMode, set_parse_mode,
+};
+use exactobar_providers::{ProviderRegistry, RawFormat, compat, recording};
+use exactobar_store::Settings;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tracing::{debug, info, warn};
 
-use crate::output::{JsonFormatter, TextFormatter};
+use crate::commands::config;
+use crate::output::{JsonFormatter, TextFormatter, WaybarFormatter};
 use crate::{Cli, ExitCode, OutputFormat};
 
 /// Arguments for the usage command.
@@ -28,37 +36,116 @@ pub struct UsageArgs {
     pub web_timeout: u64,
 
     /// Source mode for fetching (auto, cli, oauth, api, web).
-    #[arg(long, default_value = "auto")]
-    pub source: String,
+    /// Defaults to each provider's data source from the app's settings.
+    #[arg(long)]
+    pub source: Option<String>,
 
     /// Show raw debug output.
     #[arg(long)]
     pub debug: bool,
+
+    /// Save the raw responses handed to the parsers in this directory, with
+    /// secrets redacted, to attach to a bug report.
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
+    /// Run the parsers against responses saved with --record instead of
+    /// fetching.
+    #[arg(long, value_name = "DIR", conflicts_with = "record")]
+    pub replay: Option<PathBuf>,
+
+    /// Fail on unknown fields, missing data and ambiguous values in provider
+    /// responses, listing what was found. Overrides the parse_mode setting.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Fetch even if the app or another exactobar run fetched usage in the
+    /// last 30 seconds.
+    #[arg(long, conflicts_with = "max_age")]
+    pub fresh: bool,
+
+    /// Send API requests to this base URL instead of the providers' own,
+    /// like a local `exactobar mock-server`. Used by the Claude (OAuth),
+    /// Cursor and Copilot API strategies.
+    #[arg(long, value_name = "URL")]
+    pub endpoint_override: Option<String>,
+
+    /// Reuse usage the app or another exactobar run fetched within this age
+    /// (like 60s, 5m or 1h) and only fetch when it's older. For prompts and
+    /// status bars that run exactobar every few seconds. Defaults to 30s.
+    #[arg(long, value_name = "AGE")]
+    pub max_age: Option<String>,
 }
 
 /// Runs the usage command.
 pub async fn run(args: &UsageArgs, cli: &Cli) -> Result<()> {
+    // Settings shared with the app; flags take precedence over them
+    let settings = config::load_settings().await;
+    if args.strict {
+        set_parse_mode(ParseMode::Strict);
+    }
+
+    if let Some(dir) = &args.replay {
+        return replay(dir, args, cli, &settings);
+    }
+    if let Some(dir) = &args.record {
+        recording::start_recording(dir);
+    }
+
     // Determine which providers to query
     let provider_arg = args.provider.as_ref().or(cli.provider.as_ref());
-    let providers = parse_provider_selection(provider_arg)?;
+    let providers = match provider_arg {
+        Some(_) => parse_provider_selection(provider_arg)?,
+        None => enabled_providers(&settings),
+    };
 
     info!(providers = ?providers, "Fetching usage");
 
     // Create fetch context
-    let source_mode = parse_source_mode(&args.source)?;
-    let ctx = FetchContext::builder()
-        .source_mode(source_mode)
-        .timeout(std::time::Duration::from_secs(args.web_timeout))
-        .build();
-
-    // Fetch usage from each provider (in parallel if multiple)
-    let results = fetch_all(&providers, &ctx).await;
+    let source_flag = args.source.as_deref().map(parse_source_mode).transpose()?;
+    let mut builder = FetchContext::builder()
+        .source_mode(source_flag.unwrap_or_default())
+        .timeout(std::time::Duration::from_secs(args.web_timeout));
+    if let Some(url) = &args.endpoint_override {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            anyhow::bail!("Endpoint override must be an http(s) URL: {url}");
+        }
+        info!(url, "Overriding provider endpoints");
+        builder = builder.endpoint_override(url);
+    }
+    let ctx = builder.build();
+
+    let source_mode =
+        |provider| source_flag.unwrap_or_else(|| settings.data_source(provider).source_mode());
+    warn_incompatible_clis(&providers, source_mode);
+
+    // Fetch usage from each provider (in parallel if multiple), reusing
+    // recent results unless recording, which needs the raw responses
+    let max_age = match &args.max_age {
+        _ if args.fresh || args.record.is_some() => Duration::ZERO,
+        Some(age) => parse_max_age(age)?,
+        None => SNAPSHOT_MAX_AGE,
+    };
+    // With --verbose, each provider's refresh stages go to stderr
+    let show_progress = cli.verbose && !cli.quiet;
+    let results = fetch_all(
+        &providers,
+        &ctx,
+        &settings,
+        source_mode,
+        max_age,
+        show_progress,
+    )
+    .await;
 
     // Check for any successful results
     let has_success = results.values().any(|r| r.is_ok());
 
     // Format and output
-    output_results(&results, args, cli)?;
+    output_results(&results, args, cli, &settings)?;
+    if let Some(dir) = &args.record {
+        eprintln!("Responses recorded to {}", dir.display());
+    }
 
     // Exit code based on results
     if !has_success {
@@ -68,31 +155,85 @@ pub async fn run(args: &UsageArgs, cli: &Cli) -> Result<()> {
     Ok(())
 }
 
-/// Fetches usage from all providers.
-async fn fetch_all(
+/// Fetches usage from all providers, each with the source mode
+/// `source_mode` picks for it and its sandbox, strategies and base URL
+/// from `settings`. Results fetched in the last `max_age` are reused.
+/// With `show_progress`, the stages each fetch goes through are printed
+/// to stderr.
+pub(crate) async fn fetch_all(
     providers: &[ProviderKind],
     ctx: &FetchContext,
+    settings: &Settings,
+    source_mode: impl Fn(ProviderKind) -> SourceMode,
+    max_age: Duration,
+    show_progress: bool,
 ) -> HashMap<ProviderKind, Result<UsageSnapshot, String>> {
     // Note: This runs sequentially because FetchContext isn't Clone.
     // For true parallelism, we'd need to restructure the context.
     let mut results = HashMap::new();
     for provider in providers {
-        let result = fetch_one(*provider, ctx).await;
+        let ctx = ctx
+            .with_source_mode(source_mode(*provider))
+            .with_sandbox(settings.sandbox(*provider))
+            .with_strategies(settings.strategies(*provider))
+            .with_base_url(settings.base_url(*provider))
+            .with_request_headers(settings.request_headers(*provider))
+            .with_request_budget(settings.request_budget(*provider));
+        let result = if show_progress {
+            let (progress_tx, progress_rx) = tokio::sync::mpsc::unbounded_channel();
+            let printer = tokio::spawn(print_progress(*provider, progress_rx));
+            let result = fetch_one(*provider, &ctx.with_progress(progress_tx), max_age).await;
+            let _ = printer.await;
+            result
+        } else {
+            fetch_one(*provider, &ctx, max_age).await
+        };
         results.insert(*provider, result);
     }
 
     results
 }
 
+/// Prints the stages a provider's fetch reports until it finishes.
+async fn print_progress(provider: ProviderKind, mut progress: UnboundedReceiver<FetchProgress>) {
+    let name = ProviderRegistry::get(provider).map_or("unknown", |desc| desc.cli_name());
+    while let Some(progress) = progress.recv().await {
+        match progress.strategy_id {
+            Some(strategy) => eprintln!("{name}: {} ({strategy})", progress.stage),
+            None => eprintln!("{name}: {}", progress.stage),
+        }
+    }
+}
+
+/// Warns about installed CLIs outside the versions their providers'
+/// parsers support, for providers that may run them.
+fn warn_incompatible_clis(
+    providers: &[ProviderKind],
+    source_mode: impl Fn(ProviderKind) -> SourceMode,
+) {
+    for provider in providers {
+        if !source_mode(*provider).allows_cli() {
+            continue;
+        }
+        let warning = compat::check_provider(*provider).and_then(|check| check.warning());
+        if let Some(warning) = warning {
+            eprintln!("warning: {warning}");
+        }
+    }
+}
+
 /// Fetches usage from a single provider.
-async fn fetch_one(provider: ProviderKind, ctx: &FetchContext) -> Result<UsageSnapshot, String> {
+async fn fetch_one(
+    provider: ProviderKind,
+    ctx: &FetchContext,
+    max_age: Duration,
+) -> Result<UsageSnapshot, String> {
     let desc = ProviderRegistry::get(provider)
         .ok_or_else(|| format!("Provider {:?} not found", provider))?;
 
-    debug!(provider = ?provider, "Building pipeline");
+    debug!(provider = ?provider, "Fetching");
 
-    let pipeline = desc.build_pipeline(ctx);
-    let outcome = pipeline.execute(ctx).await;
+    let outcome = desc.fetch_shared(ctx, max_age).await;
 
     match outcome.result {
         Ok(fetch_result) => {
@@ -111,7 +252,7 @@ async fn fetch_one(provider: ProviderKind, ctx: &FetchContext) -> Result<UsageSn
 }
 
 /// Parses provider selection from argument.
-fn parse_provider_selection(arg: Option<&String>) -> Result<Vec<ProviderKind>> {
+pub(crate) fn parse_provider_selection(arg: Option<&String>) -> Result<Vec<ProviderKind>> {
     match arg.map(|s| s.to_lowercase()).as_deref() {
         None | Some("both") | Some("default") => {
             // Default: Codex and Claude (primary providers)
@@ -140,6 +281,20 @@ fn parse_provider_selection(arg: Option<&String>) -> Result<Vec<ProviderKind>> {
     }
 }
 
+/// Providers to query when none are named: those enabled in the app's
+/// settings, in registry order, or the default pair if none are.
+pub(crate) fn enabled_providers(settings: &Settings) -> Vec<ProviderKind> {
+    let enabled: Vec<_> = ProviderRegistry::kinds()
+        .into_iter()
+        .filter(|p| settings.enabled_providers.contains(p))
+        .collect();
+    if enabled.is_empty() {
+        vec![ProviderKind::Codex, ProviderKind::Claude]
+    } else {
+        enabled
+    }
+}
+
 /// Parses source mode from string.
 fn parse_source_mode(s: &str) -> Result<SourceMode> {
     match s.to_lowercase().as_str() {
@@ -156,15 +311,38 @@ fn parse_source_mode(s: &str) -> Result<SourceMode> {
     }
 }
 
+/// Parses an age like `90`, `60s`, `5m` or `1h`; plain numbers are seconds.
+pub(crate) fn parse_max_age(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number.parse().with_context(|| {
+        format!(
+            "Invalid max age: {}. Use a number of seconds, or like 60s, 5m, 1h",
+            s
+        )
+    })?;
+    let secs = match unit.trim() {
+        "" | "s" | "sec" | "secs" => number,
+        "m" | "min" | "mins" => number.saturating_mul(60),
+        "h" | "hr" | "hrs" => number.saturating_mul(3600),
+        _ => anyhow::bail!("Unknown unit in max age: {}. Valid units: s, m, h", s),
+    };
+    Ok(Duration::from_secs(secs))
+}
+
 /// Outputs results in the appropriate format.
 fn output_results(
     results: &HashMap<ProviderKind, Result<UsageSnapshot, String>>,
     args: &UsageArgs,
     cli: &Cli,
+    settings: &Settings,
 ) -> Result<()> {
     match cli.format {
         OutputFormat::Text => {
-            let formatter = TextFormatter::new(!cli.no_color);
+            let formatter = TextFormatter::new(!cli.no_color)
+                .with_reset_zone(ResetZone::from_setting(settings.reset_timezone.as_deref()))
+                .with_account_labels(settings);
 
             // Sort providers for consistent output
             let mut sorted: Vec<_> = results.iter().collect();
@@ -195,8 +373,95 @@ fn output_results(
             let output = formatter.format_results(results)?;
             println!("{}", output);
         }
+        OutputFormat::Waybar => {
+            println!("{}", WaybarFormatter.format_results(results)?);
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// Result of replaying one recording, for JSON output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayOutput<'a> {
+    file: String,
+    provider: &'a str,
+    format: RawFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot: Option<&'a UsageSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// Runs every recording in `dir` through its parser and prints the results.
+/// Exits with `ParseError` if any recording fails to parse.
+fn replay(dir: &Path, args: &UsageArgs, cli: &Cli, settings: &Settings) -> Result<()> {
+    let recordings = recording::load_recordings(dir)
+        .with_context(|| format!("Can't read recordings in {}", dir.display()))?;
+    if recordings.is_empty() {
+        anyhow::bail!("No recordings in {}", dir.display());
+    }
+
+    let replayed: Vec<_> = recordings
+        .iter()
+        .map(|(path, rec)| {
+            let file = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            (file, rec, rec.replay().map_err(|e| e.to_string()))
+        })
+        .collect();
+
+    match cli.format {
+        OutputFormat::Text => {
+            let formatter = TextFormatter::new(!cli.no_color)
+                .with_reset_zone(ResetZone::from_setting(settings.reset_timezone.as_deref()))
+                .with_account_labels(settings);
+
+            for (i, (file, rec, result)) in replayed.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{} ({})", file, rec.format.name());
+                let desc = ProviderRegistry::get(rec.provider());
+                match result {
+                    Ok(snapshot) => {
+                        println!(
+                            "{}",
+                            formatter.format_usage(snapshot, desc, !args.no_credits)
+                        );
+                    }
+                    Err(e) => {
+                        let name = desc.map(|d| d.display_name()).unwrap_or("Unknown");
+                        println!("{}", formatter.format_error(name, e));
+                    }
+                }
+            }
+        }
+        OutputFormat::Json | OutputFormat::Waybar => {
+            let outputs: Vec<_> = replayed
+                .iter()
+                .map(|(file, rec, result)| ReplayOutput {
+                    file: file.clone(),
+                    provider: rec.provider().cli_name(),
+                    format: rec.format,
+                    snapshot: result.as_ref().ok(),
+                    error: result.as_ref().err().map(String::as_str),
+                })
+                .collect();
+            println!("{}", JsonFormatter::new(cli.pretty).format(&outputs)?);
+        }
     }
 
+    if replayed.iter().any(|(_, _, result)| result.is_err()) {
+        std::process::exit(ExitCode::ParseError as i32);
+    }
     Ok(())
 }
 
@@ -234,6 +499,24 @@ mod tests {
         assert_eq!(providers.len(), 2);
     }
 
+    #[test]
+    fn test_enabled_providers() {
+        let mut settings = Settings {
+            enabled_providers: [ProviderKind::Gemini, ProviderKind::Codex].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            enabled_providers(&settings),
+            vec![ProviderKind::Codex, ProviderKind::Gemini]
+        );
+
+        settings.enabled_providers.clear();
+        assert_eq!(
+            enabled_providers(&settings),
+            vec![ProviderKind::Codex, ProviderKind::Claude]
+        );
+    }
+
     #[test]
     fn test_parse_source_mode() {
         assert!(matches!(
@@ -252,4 +535,14 @@ mod tests {
     fn test_parse_source_mode_invalid() {
         assert!(parse_source_mode("invalid").is_err());
     }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(parse_max_age("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_max_age("60s").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_max_age("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_max_age("1h").unwrap(), Duration::from_secs(3600));
+        assert!(parse_max_age("soon").is_err());
+        assert!(parse_max_age("5d").is_err());
+    }
 }
@@ -2,16 +2,21 @@
 
 use anyhow::Result;
 use clap::Args;
-use exactobar_core::{ProviderKind, UsageSnapshot};
-use exactobar_fetch::{FetchContext, SourceMode};
+use exactobar_core::{ProviderKind, ResetZone, UsageSnapshot};
+use exactobar_fetch::{FetchContext, SNAPSHOT_MAX_AGE, SourceMode};
 use exactobar_providers::ProviderRegistry;
+use exactobar_store::Settings;
 use std::collections::HashMap;
 use std::io::{Write, stdout};
 use tokio::time::{Duration, interval};
 use tracing::info;
 
-use crate::Cli;
-use crate::output::TextFormatter;
+use super::{config, cost, usage};
+use crate::output::{TextFormatter, WaybarFormatter};
+use crate::{Cli, OutputFormat};
+
+/// Days of history scanned for `--cost`.
+const COST_DAYS: u32 = 30;
 
 /// Arguments for watch command.
 #[derive(Args)]
@@ -27,6 +32,15 @@ pub struct WatchArgs {
     /// Minimum interval to use.
     #[arg(long, default_value = "10")]
     pub min_interval: u64,
+
+    /// Show today's token spend, updated live as logs change.
+    #[arg(long)]
+    pub cost: bool,
+
+    /// Ring the terminal bell when a provider's usage crosses this
+    /// percentage.
+    #[arg(long, value_name = "PERCENT")]
+    pub beep_at: Option<f64>,
 }
 
 /// Runs the watch command.
@@ -35,6 +49,9 @@ pub async fn run(args: &WatchArgs, cli: &Cli) -> Result<()> {
 
     info!(interval = refresh_interval, "Starting watch mode");
 
+    // Settings shared with the app
+    let settings = config::load_settings().await;
+
     // Determine providers
     let providers = match &args.provider {
         Some(name) if name == "all" => ProviderRegistry::kinds(),
@@ -45,7 +62,7 @@ pub async fn run(args: &WatchArgs, cli: &Cli) -> Result<()> {
                 anyhow::bail!("Unknown provider: {}", name);
             }
         }
-        None => vec![ProviderKind::Codex, ProviderKind::Claude],
+        None => usage::enabled_providers(&settings),
     };
 
     let ctx = FetchContext::builder()
@@ -53,12 +70,40 @@ pub async fn run(args: &WatchArgs, cli: &Cli) -> Result<()> {
         .timeout(Duration::from_secs(30))
         .build();
 
-    let formatter = TextFormatter::new(!cli.no_color);
+    let formatter = TextFormatter::new(!cli.no_color)
+        .with_reset_zone(ResetZone::from_setting(settings.reset_timezone.as_deref()))
+        .with_account_labels(&settings);
+
+    // Token spend comes from local logs and is pushed by file watchers
+    let (cost_tx, mut cost_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (mut costs, _watchers) = if args.cost {
+        let costs = cost::scan_costs(&providers, COST_DAYS, false)?;
+        (
+            costs,
+            cost::spawn_live_watchers(&providers, COST_DAYS, &cost_tx),
+        )
+    } else {
+        (HashMap::new(), Vec::new())
+    };
+    drop(cost_tx);
 
     let mut ticker = interval(Duration::from_secs(refresh_interval));
 
     // Initial fetch
     ticker.tick().await;
+    let mut results = fetch_all(&providers, &ctx, &settings).await;
+    // Results of the tick before, which changes are shown against
+    let mut previous = HashMap::new();
+
+    // Waybar's continuous `exec` modules read a line per update
+    if cli.format == OutputFormat::Waybar {
+        loop {
+            println!("{}", WaybarFormatter.format_summary(&results)?);
+            stdout().flush()?;
+            ticker.tick().await;
+            results = fetch_all(&providers, &ctx, &settings).await;
+        }
+    }
 
     loop {
         // Clear screen
@@ -75,31 +120,125 @@ pub async fn run(args: &WatchArgs, cli: &Cli) -> Result<()> {
         println!("{}", "─".repeat(50));
         println!();
 
-        // Fetch each provider
-        let mut results: HashMap<ProviderKind, Option<UsageSnapshot>> = HashMap::new();
-
-        for provider in &providers {
-            if let Some(desc) = ProviderRegistry::get(*provider) {
-                let pipeline = desc.build_pipeline(&ctx);
-                let outcome = pipeline.execute(&ctx).await;
+        // Display results, marking what moved since the last tick
+        println!("{}", formatter.format_summary_since(&results, &previous));
+        if args.cost && !costs.is_empty() {
+            println!();
+            println!("{}", formatter.format_today_cost(&costs));
+        }
+        println!();
+        println!("Press Ctrl+C to exit");
 
-                match outcome.result {
-                    Ok(fetch_result) => {
-                        results.insert(*provider, Some(fetch_result.snapshot));
-                    }
-                    Err(_) => {
-                        results.insert(*provider, None);
+        // Redraw on the next tick or as soon as spend changes
+        tokio::select! {
+            _ = ticker.tick() => {
+                previous = std::mem::replace(
+                    &mut results,
+                    fetch_all(&providers, &ctx, &settings).await,
+                );
+                if let Some(threshold) = args.beep_at {
+                    if !crossed_threshold(&previous, &results, threshold).is_empty() {
+                        // The terminal bell
+                        print!("\x07");
                     }
                 }
             }
+            Some((provider, snapshot)) = cost_rx.recv() => {
+                costs.insert(provider, snapshot);
+            }
         }
+    }
+}
 
-        // Display results
-        println!("{}", formatter.format_summary(&results));
-        println!();
-        println!("Press Ctrl+C to exit");
+/// Returns the providers whose usage reached `threshold` percent in any
+/// window since the `previous` results.
+fn crossed_threshold(
+    previous: &HashMap<ProviderKind, Option<UsageSnapshot>>,
+    results: &HashMap<ProviderKind, Option<UsageSnapshot>>,
+    threshold: f64,
+) -> Vec<ProviderKind> {
+    let mut crossed: Vec<_> = results
+        .iter()
+        .filter_map(|(provider, snapshot)| {
+            let before = previous.get(provider)?.as_ref()?;
+            let after = snapshot.as_ref()?;
+            (!before.meets_threshold(threshold) && after.meets_threshold(threshold))
+                .then_some(*provider)
+        })
+        .collect();
+    crossed.sort_by_key(|provider| provider.to_index());
+    crossed
+}
 
-        // Wait for next tick
-        ticker.tick().await;
+/// Fetches each provider once, using its data source from settings.
+async fn fetch_all(
+    providers: &[ProviderKind],
+    ctx: &FetchContext,
+    settings: &Settings,
+) -> HashMap<ProviderKind, Option<UsageSnapshot>> {
+    let mut results = HashMap::new();
+
+    for provider in providers {
+        if let Some(desc) = ProviderRegistry::get(*provider) {
+            let ctx = ctx
+                .with_source_mode(settings.data_source(*provider).source_mode())
+                .with_sandbox(settings.sandbox(*provider))
+                .with_strategies(settings.strategies(*provider))
+                .with_base_url(settings.base_url(*provider))
+                .with_request_headers(settings.request_headers(*provider))
+                .with_request_budget(settings.request_budget(*provider));
+            let outcome = desc.fetch_shared(&ctx, SNAPSHOT_MAX_AGE).await;
+            results.insert(*provider, outcome.result.ok().map(|r| r.snapshot));
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exactobar_core::UsageWindow;
+
+    fn results(
+        usage: &[(ProviderKind, Option<f64>)],
+    ) -> HashMap<ProviderKind, Option<UsageSnapshot>> {
+        usage
+            .iter()
+            .map(|&(provider, used)| {
+                let snapshot = used.map(|used| {
+                    let mut snapshot = UsageSnapshot::new();
+                    snapshot.primary = Some(UsageWindow::new(used));
+                    snapshot
+                });
+                (provider, snapshot)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_crossed_threshold() {
+        let previous = results(&[
+            (ProviderKind::Claude, Some(70.0)),
+            (ProviderKind::Codex, Some(85.0)),
+            (ProviderKind::Cursor, None),
+            (ProviderKind::Copilot, Some(40.0)),
+        ]);
+        let current = results(&[
+            (ProviderKind::Claude, Some(82.0)),
+            (ProviderKind::Codex, Some(90.0)),
+            (ProviderKind::Cursor, Some(95.0)),
+            (ProviderKind::Copilot, Some(60.0)),
+        ]);
+
+        // Only Claude went from under to over; Codex was already over and
+        // Cursor has nothing to compare against
+        assert_eq!(
+            crossed_threshold(&previous, &current, 80.0),
+            vec![ProviderKind::Claude]
+        );
+        // Dropping back under isn't a crossing
+        assert!(crossed_threshold(&current, &previous, 80.0).is_empty());
+        assert!(crossed_threshold(&HashMap::new(), &current, 80.0).is_empty());
     }
 }
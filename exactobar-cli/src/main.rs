@@ -50,14 +50,81 @@
 //! # Force CLI source
 //! exactobar usage --source cli
 //!
+//! # Record raw responses for a parser bug report, then replay them
+//! exactobar usage --record ./recordings
+//! exactobar usage --replay ./recordings
+//!
+//! # List unknown fields and out-of-range values in the responses
+//! exactobar usage --replay ./recordings --strict
+//!
 //! # Token cost report
 //! exactobar cost --provider codex
 //!
+//! # Check computed costs against an invoice export
+//! exactobar reconcile invoice.csv
+//!
 //! # List providers
 //! exactobar providers
 //!
+//! # Use the "Client A" profile's providers and settings
+//! exactobar --profile "Client A"
+//!
+//! # Note what you were doing, to explain usage later
+//! exactobar annotate "ran evals"
+//!
+//! # Find stored API keys that are old or no longer accepted
+//! exactobar doctor --keychain
+//!
+//! # Store API keys from environment variables in the keychain
+//! exactobar doctor --env --import
+//!
+//! # Shrink the usage history database
+//! exactobar history compact
+//!
 //! # Watch mode
 //! exactobar watch --interval 30
+//!
+//! # Ring the terminal bell when a provider passes 80% usage
+//! exactobar watch --beep-at 80
+//!
+//! # Only run a job when at least 20% of Claude quota is left
+//! exactobar guard --provider claude --min-remaining 20 -- ./job.sh
+//!
+//! # Top Cursor users in your team this week (needs an admin API key)
+//! exactobar team --provider cursor
+//!
+//! # List Claude under a "Coding" section in the menu switcher
+//! exactobar config group claude Coding
+//!
+//! # Only alert on Cursor after 3 failed fetches or 30 minutes without usage
+//! exactobar config tolerance cursor 3 --stale 30
+//!
+//! # Send a browser User-Agent and a gateway header with Cursor's requests
+//! exactobar config headers cursor --user-agent "Mozilla/5.0 ..." --header "X-Team: ml"
+//!
+//! # Fetch at most 120 times an hour overall, and 20 times for Claude
+//! exactobar config budget 120
+//! exactobar config budget 20 --provider claude
+//!
+//! # Read Replicate's API key from 1Password instead of the keychain
+//! exactobar config secret replicate 1password op://Engineering/Replicate/credential
+//!
+//! # Append yesterday's usage to a Google Sheet (cron-friendly)
+//! exactobar config sheets <spreadsheet-id> key.json
+//! exactobar config sheets-tab claude Usage
+//! exactobar sheets
+//!
+//! # Serve usage to agents over MCP (stdio)
+//! exactobar mcp
+//!
+//! # Reuse usage fetched in the last minute (for tmux status lines)
+//! exactobar usage --max-age 60s
+//!
+//! # Shell prompt segment (starship custom module), never waits on providers
+//! exactobar prompt --provider claude --template "{icon} {remaining}%"
+//!
+//! # Push usage to a Stream Deck plugin (HTTP and WebSocket on localhost)
+//! exactobar streamdeck --port 47322
 //! ```
 
 mod commands;
@@ -65,9 +132,13 @@ mod output;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand, ValueEnum};
+use exactobar_core::redact::{self, RedactingWriter};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
-use commands::{config, cost, providers, summary, usage, watch};
+use commands::{
+    annotate, config, cost, doctor, guard, history, mcp, mock_server, prompt, providers, reconcile,
+    sheets, streamdeck, summary, team, usage, watch,
+};
 
 // ============================================================================
 // CLI Definition
@@ -99,7 +170,16 @@ Examples:
   exactobar --provider all       # All providers
   exactobar --provider codex     # Single provider
   exactobar --format json        # JSON output
+  exactobar --profile clientA    # Use a workspace profile
   exactobar cost                 # Token cost report
+  exactobar annotate "ran evals" # Note a point in time
+  exactobar reconcile cost.csv   # Check costs against an invoice
+  exactobar guard -p claude --min-remaining 20 -- make
+                                 # Run only if enough quota is left
+  exactobar mcp                  # MCP server for agents (stdio)
+  exactobar prompt -p claude -t "{icon} {remaining}%"
+                                 # Shell prompt segment, from cache
+  exactobar streamdeck           # Usage for a Stream Deck plugin
 "#)]
 #[command(version)]
 #[command(author = "ExactoBar Contributors")]
@@ -108,7 +188,7 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// Output format (text or json).
+    /// Output format (text, json or waybar).
     #[arg(long, short = 'f', default_value = "text", global = true)]
     pub format: OutputFormat,
 
@@ -121,6 +201,10 @@ pub struct Cli {
     #[arg(long, short, global = true)]
     pub provider: Option<String>,
 
+    /// Profile to use instead of the active one.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
     /// Include provider status indicators.
     #[arg(long, global = true)]
     pub status: bool,
@@ -149,6 +233,9 @@ pub enum Commands {
     #[command(visible_alias = "c")]
     Cost(cost::CostArgs),
 
+    /// Compare an invoice CSV export with computed token costs.
+    Reconcile(reconcile::ReconcileArgs),
+
     /// List available providers.
     #[command(visible_alias = "p")]
     Providers,
@@ -164,8 +251,41 @@ pub enum Commands {
     /// Manage configuration.
     Config(config::ConfigArgs),
 
+    /// Attach a note to a point in time, or list notes.
+    Annotate(annotate::AnnotateArgs),
+
+    /// Maintain the usage history database.
+    History(history::HistoryArgs),
+
     /// Check provider health/availability.
     Check(CheckArgs),
+
+    /// Check the setup for problems, like stored API keys that stopped
+    /// working.
+    Doctor(doctor::DoctorArgs),
+
+    /// Run a command only if enough quota is left.
+    Guard(guard::GuardArgs),
+
+    /// Rank an organization's members by usage this week (admin API key).
+    Team(team::TeamArgs),
+
+    /// Append daily usage and cost rows to the configured Google Sheet.
+    Sheets(sheets::SheetsArgs),
+
+    /// Serve usage and cost data over the Model Context Protocol (stdio).
+    Mcp,
+
+    /// Print a short usage segment for shell prompts, from cached usage.
+    Prompt(prompt::PromptArgs),
+
+    /// Serve usage to a Stream Deck plugin over HTTP and WebSocket.
+    #[command(name = "streamdeck")]
+    StreamDeck(streamdeck::StreamDeckArgs),
+
+    /// Serve canned provider responses for demos and end-to-end tests.
+    #[command(hide = true)]
+    MockServer(mock_server::MockServerArgs),
 }
 
 /// Arguments for check command.
@@ -184,6 +304,9 @@ pub enum OutputFormat {
     Text,
     /// JSON output for scripting.
     Json,
+    /// Waybar custom module JSON from usage, summary and watch; other
+    /// commands print JSON.
+    Waybar,
 }
 
 /// CLI exit codes.
@@ -199,12 +322,15 @@ pub enum ExitCode {
     ParseError = 3,
     /// Timeout.
     Timeout = 4,
+    /// Not enough quota left (guard).
+    QuotaLow = 5,
 }
 
 // ============================================================================
 // Logging Setup
 // ============================================================================
 
+/// Sets up logging to stderr, with secrets redacted from every line.
 fn setup_logging(verbose: bool, quiet: bool) {
     if quiet {
         return; // No logging in quiet mode
@@ -221,7 +347,7 @@ fn setup_logging(verbose: bool, quiet: bool) {
             fmt::layer()
                 .with_target(false)
                 .without_time()
-                .with_writer(std::io::stderr),
+                .with_writer(|| RedactingWriter::new(std::io::stderr())),
         )
         .with(filter)
         .init();
@@ -235,16 +361,53 @@ fn setup_logging(verbose: bool, quiet: bool) {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    redact::install_panic_hook();
     setup_logging(cli.verbose, cli.quiet);
 
+    if let Some(name) = &cli.profile {
+        let Some(profile) = exactobar_store::find_profile(name) else {
+            if !cli.quiet {
+                eprintln!("Error: Unknown profile: {name}. See `exactobar config profile list`");
+            }
+            std::process::exit(ExitCode::Error as i32);
+        };
+        exactobar_store::set_profile_override(Some(&profile))?;
+    }
+    let settings = config::load_settings().await;
+    if !cli.quiet {
+        redact::set_redact_emails(settings.redact_emails_in_logs);
+    }
+    exactobar_fetch::set_parse_mode(settings.parse_mode);
+    exactobar_fetch::host::discovery::set_binary_search(
+        settings.binary_search(exactobar_providers::ProviderRegistry::binary_name),
+    );
+    exactobar_store::set_secret_backends(settings.secret_backends());
+    // PTY probe output and fetch results are shared with the app and other
+    // CLI runs; fetch results only with runs for the same profile
+    exactobar_fetch::host::probe_cache::set_probe_cache_dir(exactobar_store::default_cache_dir());
+    exactobar_fetch::set_snapshot_cache_dir(exactobar_store::profile_cache_dir(
+        &exactobar_store::current_profile(),
+    ));
+
     let result = match &cli.command {
         Some(Commands::Usage(args)) => usage::run(args, &cli).await,
         Some(Commands::Cost(args)) => cost::run(args, &cli).await,
+        Some(Commands::Reconcile(args)) => reconcile::run(args, &cli).await,
         Some(Commands::Providers) => providers::run(&cli).await,
         Some(Commands::Summary) => summary::run(&cli).await,
         Some(Commands::Watch(args)) => watch::run(args, &cli).await,
         Some(Commands::Config(args)) => config::run(args, &cli).await,
+        Some(Commands::Annotate(args)) => annotate::run(args, &cli).await,
+        Some(Commands::History(args)) => history::run(args, &cli).await,
         Some(Commands::Check(args)) => run_check(args, &cli).await,
+        Some(Commands::Doctor(args)) => doctor::run(args, &cli).await,
+        Some(Commands::Guard(args)) => guard::run(args, &cli).await,
+        Some(Commands::Team(args)) => team::run(args, &cli).await,
+        Some(Commands::Sheets(args)) => sheets::run(args, &cli).await,
+        Some(Commands::Mcp) => mcp::run(&cli).await,
+        Some(Commands::Prompt(args)) => prompt::run(args, &cli).await,
+        Some(Commands::StreamDeck(args)) => streamdeck::run(args, &cli).await,
+        Some(Commands::MockServer(args)) => mock_server::run(args, &cli).await,
         None => {
             // Default to usage command
             usage::run(&usage::UsageArgs::default(), &cli).await
@@ -253,7 +416,7 @@ async fn main() -> Result<()> {
 
     if let Err(e) = result {
         if !cli.quiet {
-            eprintln!("Error: {}", e);
+            eprintln!("Error: {}", redact::redact(&e.to_string()));
         }
         std::process::exit(ExitCode::Error as i32);
     }
@@ -2,8 +2,10 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use exactobar_core::redact::Redactor;
 use exactobar_core::{FetchSource, ProviderKind, UsageSnapshot, UsageWindow};
-use exactobar_providers::ProviderDescriptor;
+use exactobar_providers::cost::Reconciliation;
+use exactobar_providers::{ProviderDescriptor, TeamRank, TeamUnit, TeamUsage};
 use exactobar_store::CostUsageSnapshot;
 use serde::{Serialize, Serializer};
 use std::collections::HashMap;
@@ -28,6 +30,9 @@ pub struct ProviderOutput {
     pub credits: Option<CreditsOutput>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Preferred sources that failed when the usage came from a fallback.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unavailable_sources: Vec<String>,
 }
 
 /// Status indicator.
@@ -98,9 +103,39 @@ pub struct CostOutput {
     pub provider: String,
     pub total_tokens: u64,
     pub total_cost_usd: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_monthly_cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<TokenCategoriesOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<ModelCostOutput>>,
     pub daily: Vec<DailyCostOutput>,
 }
 
+/// Token totals by category (detailed cost report).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCategoriesOutput {
+    pub input: u64,
+    pub output: u64,
+    pub reasoning: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+/// Per-model cost entry (detailed cost report).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCostOutput {
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cost_usd: f64,
+}
+
 /// Daily cost entry.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -110,6 +145,39 @@ pub struct DailyCostOutput {
     pub cost_usd: f64,
 }
 
+/// Invoice reconciliation output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationOutput {
+    pub provider: String,
+    pub invoiced_total_usd: f64,
+    pub computed_total_usd: f64,
+    pub tolerance_usd: f64,
+    pub rows: Vec<ReconcileRowOutput>,
+}
+
+/// One day/model row of a reconciliation.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileRowOutput {
+    pub date: String,
+    pub model: String,
+    pub invoiced_usd: f64,
+    pub computed_usd: f64,
+    pub difference_usd: f64,
+}
+
+/// Team leaderboard output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamOutput {
+    pub provider: String,
+    pub unit: TeamUnit,
+    pub since: DateTime<Utc>,
+    pub total: f64,
+    pub members: Vec<TeamRank>,
+}
+
 /// Provider info output.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -163,14 +231,19 @@ impl JsonFormatter {
         Self { pretty }
     }
 
-    /// Formats any serializable value.
+    /// Formats any serializable value, with tokens and cookies redacted.
+    ///
+    /// Email addresses are kept: account emails are part of the output.
     pub fn format<T: Serialize>(&self, data: &T) -> Result<String> {
         let json = if self.pretty {
             serde_json::to_string_pretty(data)?
         } else {
             serde_json::to_string(data)?
         };
-        Ok(json)
+        Ok(Redactor::new()
+            .with_emails(false)
+            .redact(&json)
+            .into_owned())
     }
 
     /// Formats usage results.
@@ -227,6 +300,11 @@ impl JsonFormatter {
                     usage: Some(usage),
                     credits,
                     error: None,
+                    unavailable_sources: snapshot
+                        .unavailable_sources
+                        .iter()
+                        .map(|source| self.format_source(source))
+                        .collect(),
                 }
             }
             Err(e) => ProviderOutput {
@@ -237,6 +315,7 @@ impl JsonFormatter {
                 usage: None,
                 credits: None,
                 error: Some(e.clone()),
+                unavailable_sources: Vec::new(),
             },
         }
     }
@@ -263,9 +342,12 @@ impl JsonFormatter {
     }
 
     /// Formats cost results.
+    ///
+    /// With `detailed`, includes token categories and per-model costs.
     pub fn format_cost_results(
         &self,
         results: &HashMap<ProviderKind, CostUsageSnapshot>,
+        detailed: bool,
     ) -> Result<String> {
         let outputs: Vec<CostOutput> = results
             .iter()
@@ -273,6 +355,28 @@ impl JsonFormatter {
                 provider: format!("{:?}", provider).to_lowercase(),
                 total_tokens: cost.total_tokens,
                 total_cost_usd: cost.total_cost_usd,
+                projected_monthly_cost_usd: cost.projected_monthly_cost_usd(),
+                tokens: detailed.then_some(TokenCategoriesOutput {
+                    input: cost.tokens.input,
+                    output: cost.tokens.output,
+                    reasoning: cost.tokens.reasoning,
+                    cache_read: cost.tokens.cache_read,
+                    cache_write: cost.tokens.cache_creation,
+                }),
+                models: detailed.then(|| {
+                    cost.models
+                        .iter()
+                        .map(|m| ModelCostOutput {
+                            model: m.model_name.clone(),
+                            input_tokens: m.input_tokens.unwrap_or(0),
+                            output_tokens: m.output_tokens.unwrap_or(0),
+                            reasoning_tokens: m.reasoning_tokens.unwrap_or(0),
+                            cache_read_tokens: m.cache_read_tokens.unwrap_or(0),
+                            cache_write_tokens: m.cache_creation_tokens.unwrap_or(0),
+                            cost_usd: m.cost_usd.unwrap_or(0.0),
+                        })
+                        .collect()
+                }),
                 daily: cost
                     .daily
                     .iter()
@@ -292,6 +396,51 @@ impl JsonFormatter {
         }
     }
 
+    /// Formats an invoice reconciliation.
+    ///
+    /// Only rows off by more than `tolerance` are listed unless `all` is set.
+    pub fn format_reconciliation(
+        &self,
+        provider: ProviderKind,
+        reconciliation: &Reconciliation,
+        tolerance: f64,
+        all: bool,
+    ) -> Result<String> {
+        let output = ReconciliationOutput {
+            provider: format!("{:?}", provider).to_lowercase(),
+            invoiced_total_usd: reconciliation.invoiced_total_usd(),
+            computed_total_usd: reconciliation.computed_total_usd(),
+            tolerance_usd: tolerance,
+            rows: reconciliation
+                .rows
+                .iter()
+                .filter(|r| all || r.difference_usd().abs() > tolerance)
+                .map(|r| ReconcileRowOutput {
+                    date: r.date.format("%Y-%m-%d").to_string(),
+                    model: r.model.clone(),
+                    invoiced_usd: r.invoiced_usd,
+                    computed_usd: r.computed_usd,
+                    difference_usd: r.difference_usd(),
+                })
+                .collect(),
+        };
+
+        self.format(&output)
+    }
+
+    /// Formats a team leaderboard.
+    pub fn format_team(&self, team: &TeamUsage, top: usize) -> Result<String> {
+        let output = TeamOutput {
+            provider: team.provider.cli_name().to_string(),
+            unit: team.unit,
+            since: team.since,
+            total: team.total(),
+            members: team.top(top),
+        };
+
+        self.format(&output)
+    }
+
     /// Formats provider list.
     pub fn format_providers(&self, providers: &[ProviderDescriptor]) -> Result<String> {
         let outputs: Vec<ProviderInfoOutput> = providers
@@ -2,8 +2,10 @@
 
 mod json;
 mod text;
+mod waybar;
 
 pub use json::JsonFormatter;
 pub use text::TextFormatter;
+pub use waybar::WaybarFormatter;
 #[cfg(test)]
 mod tests;
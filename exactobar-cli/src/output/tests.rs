@@ -140,6 +140,38 @@ mod text_formatter_tests {
         assert!(output.contains("Claude") || output.contains("Codex") || output.contains("Cursor"));
     }
 
+    #[test]
+    fn test_format_summary_since() {
+        let formatter = TextFormatter::new(false);
+        let results_at = |claude: f64, codex: f64| {
+            HashMap::from([
+                (ProviderKind::Claude, Some(snapshot_at(claude))),
+                (ProviderKind::Codex, Some(snapshot_at(codex))),
+                (ProviderKind::Cursor, None),
+            ])
+        };
+        let previous = results_at(25.0, 75.0);
+
+        let output = formatter.format_summary_since(&results_at(28.0, 10.0), &previous);
+        let line = |name: &str| output.lines().find(|l| l.starts_with(name)).unwrap();
+        assert!(line("Claude").contains("72% ▼ 3%"));
+        assert!(line("Codex").contains("90% ▲ 65%"));
+
+        // Unchanged quota and the first tick show no arrows
+        let output = formatter.format_summary_since(&previous, &previous);
+        assert!(!output.contains('▼') && !output.contains('▲'));
+        assert_eq!(
+            formatter.format_summary(&previous),
+            formatter.format_summary_since(&previous, &HashMap::new())
+        );
+    }
+
+    fn snapshot_at(used: f64) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(used));
+        snapshot
+    }
+
     #[test]
     fn test_format_providers_header() {
         let formatter = TextFormatter::new(false);
@@ -154,7 +186,7 @@ mod text_formatter_tests {
 #[cfg(test)]
 mod json_formatter_tests {
     use super::super::json::JsonFormatter;
-    use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+    use exactobar_core::{FetchSource, ProviderKind, UsageSnapshot, UsageWindow};
     use std::collections::HashMap;
 
     #[test]
@@ -180,6 +212,23 @@ mod json_formatter_tests {
         assert_eq!(output, r#"{"key":"value"}"#);
     }
 
+    #[test]
+    fn test_format_redacts_secrets() {
+        let formatter = JsonFormatter::new(false);
+
+        let data = serde_json::json!({
+            "companion_token": "0123456789abcdef0123",
+            "email": "ada@example.com",
+        });
+        let output = formatter.format(&data).unwrap();
+
+        // Tokens go, account emails stay
+        assert_eq!(
+            output,
+            r#"{"companion_token":"[REDACTED]","email":"ada@example.com"}"#
+        );
+    }
+
     #[test]
     fn test_format_results_success() {
         let formatter = JsonFormatter::new(true);
@@ -195,6 +244,19 @@ mod json_formatter_tests {
         assert!(parsed.get("provider").is_some() || parsed.get("usage").is_some());
     }
 
+    #[test]
+    fn test_format_results_limited() {
+        let formatter = JsonFormatter::new(false);
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(50.0));
+        snapshot.unavailable_sources = vec![FetchSource::Web];
+
+        let mut results = HashMap::new();
+        results.insert(ProviderKind::Claude, Ok(snapshot));
+        let output = formatter.format_results(&results).unwrap();
+        assert!(output.contains(r#""unavailableSources":["web"]"#));
+    }
+
     #[test]
     fn test_format_results_error() {
         let formatter = JsonFormatter::new(true);
@@ -295,4 +357,65 @@ mod output_snapshot_tests {
             );
         }
     }
+
+    #[test]
+    fn test_output_shows_limited_data() {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(50.0));
+        snapshot.unavailable_sources = vec![FetchSource::Web];
+
+        let output = TextFormatter::new(false).format_usage(&snapshot, None, false);
+        assert!(output.contains("limited data (web source unavailable)"));
+    }
+}
+
+#[cfg(test)]
+mod waybar_formatter_tests {
+    use super::super::waybar::WaybarFormatter;
+    use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+    use std::collections::HashMap;
+
+    fn snapshot(primary_used: f64, secondary_used: f64) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(primary_used));
+        snapshot.secondary = Some(UsageWindow::new(secondary_used));
+        snapshot
+    }
+
+    fn parse(output: &str) -> serde_json::Value {
+        assert!(!output.contains('\n'), "one object per line");
+        serde_json::from_str(output).unwrap()
+    }
+
+    #[test]
+    fn test_waybar_results() {
+        let mut results = HashMap::new();
+        results.insert(ProviderKind::Claude, Ok(snapshot(28.0, 60.0)));
+        results.insert(ProviderKind::Codex, Err("not logged in".to_string()));
+
+        let output = parse(&WaybarFormatter.format_results(&results).unwrap());
+        assert_eq!(output["text"], "Claude 72%");
+        assert_eq!(output["class"], "warning");
+        assert_eq!(output["percentage"], 40);
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.contains("72% left"));
+        assert!(tooltip.contains("Codex: not logged in"));
+    }
+
+    #[test]
+    fn test_waybar_summary_classes() {
+        let mut results = HashMap::new();
+        results.insert(ProviderKind::Codex, Some(snapshot(90.0, 10.0)));
+        results.insert(ProviderKind::Claude, Some(snapshot(10.0, 10.0)));
+        let output = parse(&WaybarFormatter.format_summary(&results).unwrap());
+        // Providers in registry order, class from the lowest
+        assert_eq!(output["text"], "Codex 10%  Claude 90%");
+        assert_eq!(output["class"], "critical");
+
+        results.clear();
+        results.insert(ProviderKind::Claude, None);
+        let output = parse(&WaybarFormatter.format_summary(&results).unwrap());
+        assert_eq!(output["class"], "error");
+        assert_eq!(output["text"], "—");
+    }
 }
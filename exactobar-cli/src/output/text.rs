@@ -1,9 +1,10 @@
 //! Text output formatting with progress bars and colors.
 
-use chrono::{DateTime, Duration, Local, Utc};
-use exactobar_core::{FetchSource, ProviderKind, UsageSnapshot, UsageWindow};
-use exactobar_providers::ProviderDescriptor;
-use exactobar_store::CostUsageSnapshot;
+use chrono::{Duration, Utc};
+use exactobar_core::{FetchSource, ProviderKind, ResetTime, ResetZone, UsageSnapshot, UsageWindow};
+use exactobar_providers::cost::Reconciliation;
+use exactobar_providers::{ProviderDescriptor, TeamUsage};
+use exactobar_store::{AccountColor, AccountLabel, CostUsageSnapshot, Settings};
 use std::collections::HashMap;
 
 // ============================================================================
@@ -27,7 +28,10 @@ const BAR_EMPTY: char = '░';
 pub struct TextFormatter {
     use_colors: bool,
     show_reset_countdown: bool,
+    reset_zone: ResetZone,
     bar_width: usize,
+    /// Account labels per provider, keyed by account id.
+    account_labels: HashMap<ProviderKind, HashMap<String, AccountLabel>>,
 }
 
 impl TextFormatter {
@@ -36,10 +40,29 @@ impl TextFormatter {
         Self {
             use_colors,
             show_reset_countdown: true,
+            reset_zone: ResetZone::Local,
             bar_width: 10,
+            account_labels: HashMap::new(),
         }
     }
 
+    /// Show the account labels from settings next to provider names.
+    pub fn with_account_labels(mut self, settings: &Settings) -> Self {
+        self.account_labels = settings
+            .provider_settings
+            .iter()
+            .filter(|(_, ps)| !ps.account_labels.is_empty())
+            .map(|(provider, ps)| (*provider, ps.account_labels.clone()))
+            .collect();
+        self
+    }
+
+    /// Set the timezone absolute reset times are shown in.
+    pub fn with_reset_zone(mut self, zone: ResetZone) -> Self {
+        self.reset_zone = zone;
+        self
+    }
+
     /// Set the progress bar width.
     #[allow(dead_code)]
     pub fn with_bar_width(mut self, width: usize) -> Self {
@@ -60,7 +83,13 @@ impl TextFormatter {
         let name = desc.map(|d| d.display_name()).unwrap_or("Unknown");
         let source = self.format_source(&snapshot.fetch_source);
 
-        lines.push(format!("{} ({})", self.bold(name), source));
+        match self.account_tag(snapshot) {
+            Some(tag) => lines.push(format!("{} ({}) · {}", self.bold(name), source, tag)),
+            None => lines.push(format!("{} ({})", self.bold(name), source)),
+        }
+        if let Some(limitation) = snapshot.limitation() {
+            lines.push(self.yellow(&format!("⚠ {}", limitation)));
+        }
 
         // Primary window (Session)
         if let Some(primary) = &snapshot.primary {
@@ -107,15 +136,15 @@ impl TextFormatter {
 
     /// Formats a usage window with progress bar.
     fn format_window(&self, window: &UsageWindow, label: &str) -> String {
-        let remaining = 100.0 - window.used_percent;
+        let remaining = window.remaining_percent();
         let bar = self.progress_bar(remaining);
         let pct_str = self.color_for_percent(remaining, &format!("{:.0}% left", remaining));
 
         let mut result = format!("{:<8} {} {}", format!("{}:", label), bar, pct_str);
 
         // Add reset time
-        if let Some(resets_at) = window.resets_at {
-            let reset_str = self.format_reset_time(resets_at);
+        if let Some(reset) = window.reset_time(Utc::now()) {
+            let reset_str = self.format_reset_time(reset);
             result.push_str(&format!("\n         Resets {}", self.dim(&reset_str)));
         } else if let Some(desc) = &window.reset_description {
             result.push_str(&format!("\n         Resets {}", self.dim(desc)));
@@ -139,15 +168,14 @@ impl TextFormatter {
     }
 
     /// Formats reset time as countdown or absolute.
-    fn format_reset_time(&self, resets_at: DateTime<Utc>) -> String {
+    fn format_reset_time(&self, reset: ResetTime) -> String {
         let now = Utc::now();
-        let local_reset = resets_at.with_timezone(&Local);
 
-        if resets_at <= now {
+        if reset.at <= now {
             return "now".to_string();
         }
 
-        let diff = resets_at - now;
+        let diff = reset.at - now;
 
         if self.show_reset_countdown && diff < Duration::hours(24) {
             // Show as relative time
@@ -165,25 +193,7 @@ impl TextFormatter {
             }
         } else {
             // Show as absolute time
-            let today = Local::now().date_naive();
-            let reset_date = local_reset.date_naive();
-
-            if reset_date == today {
-                format!(
-                    "today at {}",
-                    local_reset.format("%l:%M %p").to_string().trim()
-                )
-            } else if reset_date == today + chrono::Days::new(1) {
-                format!(
-                    "tomorrow at {}",
-                    local_reset.format("%l:%M %p").to_string().trim()
-                )
-            } else {
-                format!(
-                    "{}",
-                    local_reset.format("%a at %l:%M %p").to_string().trim()
-                )
-            }
+            reset.format_absolute(&self.reset_zone, now)
         }
     }
 
@@ -200,10 +210,13 @@ impl TextFormatter {
     }
 
     /// Formats cost usage.
+    ///
+    /// With `detailed`, adds token categories and a per-model breakdown.
     pub fn format_cost(
         &self,
         cost: &CostUsageSnapshot,
         desc: Option<&ProviderDescriptor>,
+        detailed: bool,
     ) -> String {
         let mut lines = Vec::new();
 
@@ -219,6 +232,46 @@ impl TextFormatter {
             "Total cost:   {}",
             self.green(&format!("${:.2}", cost.total_cost_usd))
         ));
+        if let Some(projected) = cost.projected_monthly_cost_usd() {
+            lines.push(format!(
+                "Projected:    {}",
+                self.dim(&format!("${:.2} / 30 days", projected))
+            ));
+        }
+
+        if detailed {
+            let tokens = &cost.tokens;
+            lines.push(String::new());
+            lines.push(self.dim("Tokens by category:"));
+            for (label, count) in [
+                ("Input", tokens.input),
+                ("Output", tokens.output),
+                ("Reasoning", tokens.reasoning),
+                ("Cache read", tokens.cache_read),
+                ("Cache write", tokens.cache_creation),
+            ] {
+                lines.push(format!(
+                    "  {:<12} {}",
+                    label,
+                    self.format_number(count as f64)
+                ));
+            }
+
+            if !cost.models.is_empty() {
+                lines.push(String::new());
+                lines.push(self.dim("By model:"));
+                for model in &cost.models {
+                    lines.push(format!(
+                        "  {:<28} {:>8} in {:>8} out {:>8} cache (${:.2})",
+                        model.model_name,
+                        self.format_number(model.input_tokens.unwrap_or(0) as f64),
+                        self.format_number(model.output_tokens.unwrap_or(0) as f64),
+                        self.format_number(model.cache_tokens() as f64),
+                        model.cost_usd.unwrap_or(0.0)
+                    ));
+                }
+            }
+        }
 
         if !cost.daily.is_empty() {
             lines.push(String::new());
@@ -236,6 +289,136 @@ impl TextFormatter {
         lines.join("\n")
     }
 
+    /// Formats today's spend per provider (for live views).
+    pub fn format_today_cost(&self, costs: &HashMap<ProviderKind, CostUsageSnapshot>) -> String {
+        let today = Utc::now().date_naive();
+
+        // Sort by provider kind for consistent order
+        let mut sorted: Vec<_> = costs.iter().collect();
+        sorted.sort_by_key(|(k, _)| format!("{:?}", k));
+
+        let mut lines = vec![self.bold("Today's Spend")];
+        for (provider, cost) in sorted {
+            let name = exactobar_providers::ProviderRegistry::get(*provider)
+                .map(|d| d.display_name())
+                .unwrap_or("Unknown");
+            lines.push(format!(
+                "{:<12} {} {}",
+                name,
+                self.green(&format!("${:>8.2}", cost.cost_on(today))),
+                self.dim(&format!("(${:.2} total)", cost.total_cost_usd))
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Formats an invoice reconciliation.
+    ///
+    /// Only rows off by more than `tolerance` are listed unless `all` is set.
+    pub fn format_reconciliation(
+        &self,
+        reconciliation: &Reconciliation,
+        desc: Option<&ProviderDescriptor>,
+        tolerance: f64,
+        all: bool,
+    ) -> String {
+        let mut lines = Vec::new();
+
+        let name = desc.map(|d| d.display_name()).unwrap_or("Unknown");
+        lines.push(format!("{} Invoice Reconciliation", self.bold(name)));
+        lines.push("─".repeat(40));
+
+        let invoiced = reconciliation.invoiced_total_usd();
+        let computed = reconciliation.computed_total_usd();
+        lines.push(format!("Invoiced:   ${:.2}", invoiced));
+        lines.push(format!("Computed:   ${:.2}", computed));
+        lines.push(format!(
+            "Difference: {}",
+            self.color_for_difference(computed - invoiced, tolerance)
+        ));
+
+        let rows: Vec<_> = reconciliation
+            .rows
+            .iter()
+            .filter(|r| all || r.difference_usd().abs() > tolerance)
+            .collect();
+
+        lines.push(String::new());
+        if rows.is_empty() {
+            lines.push(self.green("Every day and model matches the invoice."));
+            return lines.join("\n");
+        }
+
+        lines.push(self.dim(&format!(
+            "  {:<10}  {:<28} {:>10} {:>10} {:>10}",
+            "Date", "Model", "Invoiced", "Computed", "Diff"
+        )));
+        for row in rows {
+            lines.push(format!(
+                "  {:<10}  {:<28} {:>10} {:>10} {}",
+                row.date.format("%Y-%m-%d"),
+                row.model,
+                format!("${:.2}", row.invoiced_usd),
+                format!("${:.2}", row.computed_usd),
+                self.color_for_difference(row.difference_usd(), tolerance)
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Formats a team leaderboard.
+    pub fn format_team(
+        &self,
+        team: &TeamUsage,
+        desc: Option<&ProviderDescriptor>,
+        top: usize,
+    ) -> String {
+        let mut lines = Vec::new();
+
+        let name = desc.map(|d| d.display_name()).unwrap_or("Unknown");
+        lines.push(format!("{} Team Usage", self.bold(name)));
+        lines.push("─".repeat(40));
+        lines.push(format!(
+            "Since {}: {} across {} members",
+            team.since.format("%Y-%m-%d"),
+            team.unit.format(team.total()),
+            team.members.len()
+        ));
+
+        let ranks = team.top(top);
+        lines.push(String::new());
+        if ranks.is_empty() {
+            lines.push(self.dim("No usage this week."));
+            return lines.join("\n");
+        }
+
+        for (i, rank) in ranks.iter().enumerate() {
+            lines.push(format!(
+                "  {:>2}. {:<32} {:>12} {}",
+                i + 1,
+                rank.name,
+                team.unit.format(rank.amount),
+                self.dim(&format!("{:>5.1}%", rank.percent))
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Formats a signed dollar difference, highlighted when out of tolerance.
+    fn color_for_difference(&self, difference: f64, tolerance: f64) -> String {
+        let text = format!("{:>+10.2}", difference);
+        if difference.abs() <= tolerance {
+            self.green(&text)
+        } else if difference < 0.0 {
+            self.red(&text)
+        } else {
+            self.yellow(&text)
+        }
+    }
+
     /// Formats provider list header.
     pub fn format_providers_header(&self) -> String {
         format!(
@@ -282,6 +465,16 @@ impl TextFormatter {
 
     /// Formats a summary of all providers.
     pub fn format_summary(&self, results: &HashMap<ProviderKind, Option<UsageSnapshot>>) -> String {
+        self.format_summary_since(results, &HashMap::new())
+    }
+
+    /// Formats a summary of all providers, marking remaining quota that
+    /// moved since the `previous` results with an arrow and the change.
+    pub fn format_summary_since(
+        &self,
+        results: &HashMap<ProviderKind, Option<UsageSnapshot>>,
+        previous: &HashMap<ProviderKind, Option<UsageSnapshot>>,
+    ) -> String {
         let mut lines = Vec::new();
 
         lines.push(self.bold("ExactoBar Summary"));
@@ -298,10 +491,22 @@ impl TextFormatter {
 
             if let Some(snap) = snapshot {
                 if let Some(primary) = &snap.primary {
-                    let remaining = 100.0 - primary.used_percent;
+                    let remaining = primary.remaining_percent();
                     let bar = self.progress_bar(remaining);
                     let pct = self.color_for_percent(remaining, &format!("{:.0}%", remaining));
-                    lines.push(format!("{:<12} {} {}", name, bar, pct));
+                    let change = previous
+                        .get(provider)
+                        .and_then(|snapshot| snapshot.as_ref()?.primary.as_ref())
+                        .and_then(|before| {
+                            self.format_change(remaining - before.remaining_percent())
+                        })
+                        .map(|change| format!(" {}", change))
+                        .unwrap_or_default();
+                    let tag = self
+                        .account_tag(snap)
+                        .map(|tag| format!(" {}", tag))
+                        .unwrap_or_default();
+                    lines.push(format!("{:<12} {} {}{}{}", name, bar, pct, change, tag));
                 } else {
                     lines.push(format!("{:<12} {}", name, self.dim("No data")));
                 }
@@ -313,11 +518,49 @@ impl TextFormatter {
         lines.join("\n")
     }
 
+    /// Formats a change in remaining quota as "▼ 3%" (red, quota used) or
+    /// "▲ 40%" (green, e.g. after a reset), or `None` if it shows as 0%.
+    fn format_change(&self, delta: f64) -> Option<String> {
+        let points = delta.round();
+        if points == 0.0 {
+            return None;
+        }
+        Some(if points < 0.0 {
+            self.red(&format!("▼ {:.0}%", -points))
+        } else {
+            self.green(&format!("▲ {:.0}%", points))
+        })
+    }
+
     /// Formats an error message.
     pub fn format_error(&self, provider: &str, error: &str) -> String {
         format!("{}: {} - {}", self.bold(provider), self.red("Error"), error)
     }
 
+    /// Colored "● Work" tag for the snapshot's account, if it has a label.
+    fn account_tag(&self, snapshot: &UsageSnapshot) -> Option<String> {
+        let identity = snapshot.identity.as_ref()?;
+        let label = self
+            .account_labels
+            .get(&identity.provider_id)?
+            .get(&identity.account_id()?)?;
+
+        if !self.use_colors {
+            return Some(label.name.clone());
+        }
+        let color = match label.color {
+            AccountColor::Gray => "\x1b[90m",
+            AccountColor::Red => RED,
+            AccountColor::Orange => "\x1b[38;5;208m",
+            AccountColor::Yellow => YELLOW,
+            AccountColor::Green => GREEN,
+            AccountColor::Blue => BLUE,
+            AccountColor::Purple => "\x1b[35m",
+            AccountColor::Pink => "\x1b[38;5;205m",
+        };
+        Some(format!("{}●{} {}", color, RESET, label.name))
+    }
+
     // ========================================================================
     // Color/style helpers
     // ========================================================================
@@ -462,4 +705,63 @@ mod tests {
         assert!(output.contains("Session:"));
         assert!(output.contains("72% left"));
     }
+
+    #[test]
+    fn test_account_tag() {
+        let mut settings = Settings::default();
+        settings
+            .provider_settings
+            .entry(ProviderKind::Claude)
+            .or_default()
+            .account_labels
+            .insert(
+                "me@example.com".to_string(),
+                AccountLabel {
+                    name: "Work".to_string(),
+                    color: AccountColor::Blue,
+                },
+            );
+
+        let mut snapshot = UsageSnapshot::new();
+        let mut identity = exactobar_core::ProviderIdentity::new(ProviderKind::Claude);
+        identity.account_email = Some("Me@Example.com".to_string());
+        snapshot.identity = Some(identity);
+
+        let plain = TextFormatter::new(false).with_account_labels(&settings);
+        assert_eq!(plain.account_tag(&snapshot).as_deref(), Some("Work"));
+        assert!(
+            plain
+                .format_usage(&snapshot, None, false)
+                .contains("· Work")
+        );
+
+        let colored = TextFormatter::new(true).with_account_labels(&settings);
+        assert_eq!(
+            colored.account_tag(&snapshot).as_deref(),
+            Some("\x1b[34m●\x1b[0m Work")
+        );
+
+        assert_eq!(TextFormatter::new(false).account_tag(&snapshot), None);
+    }
+
+    #[test]
+    fn test_format_today_cost() {
+        let formatter = TextFormatter::new(false);
+        let cost = CostUsageSnapshot {
+            daily: vec![exactobar_store::DailyCost {
+                date: Utc::now(),
+                tokens: 100,
+                cost_usd: 1.5,
+            }],
+            total_tokens: 100,
+            total_cost_usd: 1.5,
+            ..Default::default()
+        };
+        let costs = HashMap::from([(ProviderKind::Codex, cost)]);
+
+        let output = formatter.format_today_cost(&costs);
+        assert!(output.contains("Today's Spend"));
+        assert!(output.contains("Codex"));
+        assert!(output.contains("$    1.50"));
+    }
 }
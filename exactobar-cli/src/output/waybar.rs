@@ -0,0 +1,135 @@
+//! Waybar custom module output.
+//!
+//! Waybar's `custom` modules with `"return-type": "json"` read one object
+//! per line with `text`, `tooltip`, `class` and `percentage`. All selected
+//! providers go into one object: the text lists each provider's remaining
+//! percentage, the tooltip has every window, and the class and percentage
+//! follow the provider closest to its limit, so bar styles can key off
+//! `ok`, `warning`, `critical` or `error`.
+//!
+//! ```json
+//! "custom/exactobar": {
+//!     "exec": "exactobar watch --format waybar",
+//!     "return-type": "json"
+//! }
+//! ```
+
+use anyhow::Result;
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use exactobar_providers::ProviderRegistry;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Remaining percentage below which the class is `warning`.
+const WARNING_BELOW: f64 = 50.0;
+
+/// Remaining percentage below which the class is `critical`.
+const CRITICAL_BELOW: f64 = 20.0;
+
+/// A Waybar custom module update.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WaybarOutput {
+    pub text: String,
+    pub tooltip: String,
+    pub class: &'static str,
+    /// Lowest remaining percentage across providers.
+    pub percentage: u8,
+}
+
+/// Formats usage as Waybar module updates.
+pub struct WaybarFormatter;
+
+impl WaybarFormatter {
+    /// Formats fetch results, including errors.
+    pub fn format_results(
+        &self,
+        results: &HashMap<ProviderKind, Result<UsageSnapshot, String>>,
+    ) -> Result<String> {
+        let results = results
+            .iter()
+            .map(|(provider, result)| (*provider, result.as_ref().map_err(String::as_str)));
+        Ok(serde_json::to_string(&build(results))?)
+    }
+
+    /// Formats a summary where failed providers have no snapshot.
+    pub fn format_summary(
+        &self,
+        results: &HashMap<ProviderKind, Option<UsageSnapshot>>,
+    ) -> Result<String> {
+        let results = results
+            .iter()
+            .map(|(provider, snapshot)| (*provider, snapshot.as_ref().ok_or("usage unavailable")));
+        Ok(serde_json::to_string(&build(results))?)
+    }
+}
+
+fn build<'a>(
+    results: impl Iterator<Item = (ProviderKind, Result<&'a UsageSnapshot, &'a str>)>,
+) -> WaybarOutput {
+    let mut results: Vec<_> = results.collect();
+    results.sort_by_key(|(provider, _)| provider.to_index());
+
+    let mut text = Vec::new();
+    let mut tooltip = Vec::new();
+    let mut lowest: Option<f64> = None;
+    let mut failed = false;
+
+    for (provider, result) in results {
+        let desc = ProviderRegistry::get(provider);
+        let name = provider.display_name();
+        match result {
+            Ok(snapshot) => {
+                if let Some(remaining) = snapshot
+                    .primary
+                    .as_ref()
+                    .map(UsageWindow::remaining_percent)
+                {
+                    text.push(format!("{} {:.0}%", name, remaining));
+                }
+                if let Some(remaining) = snapshot.min_remaining_percent() {
+                    lowest = Some(lowest.map_or(remaining, |l| l.min(remaining)));
+                }
+
+                tooltip.push(name.to_string());
+                let labels = [
+                    desc.map_or("Session", |d| d.metadata.session_label.as_str()),
+                    desc.map_or("Weekly", |d| d.metadata.weekly_label.as_str()),
+                    desc.and_then(|d| d.metadata.opus_label.as_deref())
+                        .unwrap_or("Opus"),
+                ];
+                let windows = [&snapshot.primary, &snapshot.secondary, &snapshot.tertiary];
+                for (label, window) in labels.iter().zip(windows) {
+                    if let Some(window) = window {
+                        tooltip.push(format!(
+                            "  {}: {:.0}% left",
+                            label,
+                            window.remaining_percent()
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                failed = true;
+                tooltip.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+
+    let class = match lowest {
+        Some(remaining) if remaining < CRITICAL_BELOW => "critical",
+        Some(remaining) if remaining < WARNING_BELOW => "warning",
+        Some(_) => "ok",
+        None if failed => "error",
+        None => "ok",
+    };
+    WaybarOutput {
+        text: if text.is_empty() {
+            "—".to_string()
+        } else {
+            text.join("  ")
+        },
+        tooltip: tooltip.join("\n"),
+        class,
+        percentage: lowest.map_or(0, |l| l.clamp(0.0, 100.0).round() as u8),
+    }
+}
@@ -15,12 +15,14 @@
 //! - Domain models (providers, usage data, quotas)
 //! - Error types
 //! - Trait definitions for provider implementations
-//! - Common utilities
+//! - Common utilities, including [`redact`] for scrubbing secrets from logs
 //!
 //! ## Key Types
 //!
 //! ### Provider Types
 //! - [`ProviderKind`] - Enum of all supported LLM providers
+//! - [`ProviderId`] - Id for built-in or runtime-registered providers
+//! - [`ProviderInfo`] - Display metadata for a [`ProviderId`]
 //! - [`Provider`] - Provider configuration
 //! - [`ProviderIdentity`] - Account identity (siloed per provider)
 //! - [`ProviderMetadata`] - Provider capabilities and display info
@@ -29,9 +31,12 @@
 //! ### Usage Types
 //! - [`UsageSnapshot`] - Main container for usage data with multiple windows
 //! - [`UsageWindow`] - Individual usage window (session, weekly, opus)
+//! - [`ModelUsage`] - Per-model usage window
 //! - [`UsageData`] - Legacy simple usage data format
 //! - [`Quota`] - Quota information
 //! - [`Credits`] - Credit-based usage tracking
+//! - [`ResetTime`] - When a window resets, with the provider's timezone
+//! - [`ResetZone`] - Timezone reset times are displayed in
 //!
 //! ### Cost Tracking
 //! - [`CostUsageSnapshot`] - Token cost tracking from local logs
@@ -45,6 +50,7 @@
 
 pub mod error;
 pub mod models;
+pub mod redact;
 pub mod traits;
 
 // Re-export error types
@@ -52,6 +58,8 @@ pub use error::CoreError;
 
 // Re-export all model types
 pub use models::{
+    // Burn budget
+    BurnPlan,
     // Cost tracking
     CostUsageSnapshot,
     // Usage types
@@ -63,18 +71,25 @@ pub use models::{
     IconStyle,
     LoginMethod,
     ModelBreakdown,
+    ModelUsage,
     Provider,
     ProviderBranding,
     ProviderColor,
+    ProviderId,
     ProviderIdentity,
+    ProviderInfo,
     ProviderKind,
     ProviderMetadata,
     ProviderStatus,
     Quota,
+    ResetTime,
+    ResetZone,
     StatusIndicator,
     UsageData,
     UsageSnapshot,
     UsageWindow,
+    next_local_time,
+    parse_clock,
 };
 
 // Re-export traits
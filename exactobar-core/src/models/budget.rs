@@ -0,0 +1,154 @@
+//! Burn budget planning.
+//!
+//! A burn target asks a usage window to last until a time of day, like
+//! "make the 5h Codex window last until 6pm". [`BurnPlan`] spreads the
+//! window's quota evenly from when the window started until the target and
+//! compares the usage so far against that straight line.
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone, Utc};
+
+use super::usage::UsageWindow;
+
+/// Percentage points usage may run ahead of plan before it counts as
+/// burning too fast.
+pub const PACE_MARGIN: f64 = 5.0;
+
+/// A usage window's plan to last until a target time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurnPlan {
+    /// When the quota should run out: the target, or the window's reset if
+    /// that comes first.
+    pub target: DateTime<Utc>,
+    /// Usage the plan allows by now, in percent.
+    pub planned_percent: f64,
+    /// Usage so far, in percent.
+    pub used_percent: f64,
+    /// Remaining quota per hour that lasts until the target, in percent.
+    pub allowed_per_hour: f64,
+}
+
+impl BurnPlan {
+    /// Plans `window` to last until `target`.
+    ///
+    /// Returns `None` when the window doesn't say when it started (it needs
+    /// both a reset time and a duration) or the target has passed.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(window: &UsageWindow, target: DateTime<Utc>, now: DateTime<Utc>) -> Option<Self> {
+        let resets_at = window.resets_at?;
+        let start = resets_at - window.window_duration()?;
+        let target = target.min(resets_at);
+        if target <= now || now < start {
+            return None;
+        }
+
+        let span = (target - start).num_seconds() as f64;
+        let elapsed = (now - start).num_seconds() as f64;
+        let hours_left = (target - now).num_seconds() as f64 / 3600.0;
+        let used_percent = window.clamped_used_percent();
+
+        Some(Self {
+            target,
+            planned_percent: 100.0 * elapsed / span,
+            used_percent,
+            allowed_per_hour: (100.0 - used_percent) / hours_left,
+        })
+    }
+
+    /// How far usage is ahead of plan, in percentage points. Negative when
+    /// behind plan, i.e. there's quota to spare.
+    pub fn pace_delta(&self) -> f64 {
+        self.used_percent - self.planned_percent
+    }
+
+    /// Whether usage is more than [`PACE_MARGIN`] ahead of plan.
+    pub fn is_over_pace(&self) -> bool {
+        self.pace_delta() > PACE_MARGIN
+    }
+}
+
+/// The next time the local clock reads `time`, after `now`.
+pub fn next_local_time(time: NaiveTime, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    next_time_in(&Local, time, now)
+}
+
+fn next_time_in<Z: TimeZone>(
+    zone: &Z,
+    time: NaiveTime,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let today = now.with_timezone(zone).date_naive();
+    [today, today.checked_add_days(Days::new(1))?]
+        .into_iter()
+        .filter_map(|date| zone.from_local_datetime(&date.and_time(time)).earliest())
+        .map(|at| at.with_timezone(&Utc))
+        .find(|at| *at > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(used_percent: f64, resets_at: DateTime<Utc>) -> UsageWindow {
+        UsageWindow {
+            used_percent,
+            window_minutes: Some(300),
+            resets_at: Some(resets_at),
+            reset_description: None,
+        }
+    }
+
+    #[test]
+    fn test_burn_plan() {
+        // 5h window from 14:00 to 19:00, target 18:00, now 16:00
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 16, 0, 0).unwrap();
+        let resets_at = Utc.with_ymd_and_hms(2026, 10, 16, 19, 0, 0).unwrap();
+        let target = Utc.with_ymd_and_hms(2026, 10, 16, 18, 0, 0).unwrap();
+
+        let plan = BurnPlan::new(&session(60.0, resets_at), target, now).unwrap();
+        assert!((plan.planned_percent - 50.0).abs() < 1e-9);
+        assert!((plan.allowed_per_hour - 20.0).abs() < 1e-9);
+        assert!((plan.pace_delta() - 10.0).abs() < 1e-9);
+        assert!(plan.is_over_pace());
+
+        let plan = BurnPlan::new(&session(52.0, resets_at), target, now).unwrap();
+        assert!(!plan.is_over_pace());
+    }
+
+    #[test]
+    fn test_burn_plan_target_after_reset() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 16, 0, 0).unwrap();
+        let resets_at = Utc.with_ymd_and_hms(2026, 10, 16, 19, 0, 0).unwrap();
+        let target = Utc.with_ymd_and_hms(2026, 10, 16, 22, 0, 0).unwrap();
+
+        let plan = BurnPlan::new(&session(0.0, resets_at), target, now).unwrap();
+        assert_eq!(plan.target, resets_at);
+        assert!((plan.planned_percent - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_burn_plan_needs_window_start() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 16, 0, 0).unwrap();
+        let target = Utc.with_ymd_and_hms(2026, 10, 16, 18, 0, 0).unwrap();
+
+        assert!(BurnPlan::new(&UsageWindow::new(50.0), target, now).is_none());
+        let passed = Utc.with_ymd_and_hms(2026, 10, 16, 15, 0, 0).unwrap();
+        let resets_at = Utc.with_ymd_and_hms(2026, 10, 16, 19, 0, 0).unwrap();
+        assert!(BurnPlan::new(&session(50.0, resets_at), passed, now).is_none());
+    }
+
+    #[test]
+    fn test_next_time_in() {
+        let six_pm = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let afternoon = Utc.with_ymd_and_hms(2026, 10, 16, 16, 0, 0).unwrap();
+        assert_eq!(
+            next_time_in(&Utc, six_pm, afternoon),
+            Utc.with_ymd_and_hms(2026, 10, 16, 18, 0, 0).single()
+        );
+
+        let evening = Utc.with_ymd_and_hms(2026, 10, 16, 20, 0, 0).unwrap();
+        assert_eq!(
+            next_time_in(&Utc, six_pm, evening),
+            Utc.with_ymd_and_hms(2026, 10, 17, 18, 0, 0).single()
+        );
+    }
+}
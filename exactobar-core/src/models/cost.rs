@@ -110,6 +110,9 @@ pub struct DailyUsageEntry {
     pub cache_read_tokens: Option<u64>,
     /// Cache creation tokens.
     pub cache_creation_tokens: Option<u64>,
+    /// Reasoning tokens (already counted in `output_tokens`).
+    #[serde(default)]
+    pub reasoning_tokens: Option<u64>,
     /// Total tokens (input + output + cache).
     pub total_tokens: Option<u64>,
     /// Total cost in USD.
@@ -131,6 +134,7 @@ impl DailyUsageEntry {
             output_tokens: None,
             cache_read_tokens: None,
             cache_creation_tokens: None,
+            reasoning_tokens: None,
             total_tokens: None,
             cost_usd: None,
             models_used: None,
@@ -165,6 +169,9 @@ impl DailyUsageEntry {
 // ============================================================================
 
 /// Per-model cost breakdown.
+///
+/// Cache tokens are tracked separately from `input_tokens`. Reasoning tokens
+/// are a subset of `output_tokens` and are not added again to the total.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelBreakdown {
     /// Model name (e.g., "claude-3-opus", "gpt-4").
@@ -175,6 +182,15 @@ pub struct ModelBreakdown {
     pub input_tokens: Option<u64>,
     /// Output tokens for this model.
     pub output_tokens: Option<u64>,
+    /// Cache read tokens for this model.
+    #[serde(default)]
+    pub cache_read_tokens: Option<u64>,
+    /// Cache creation tokens for this model.
+    #[serde(default)]
+    pub cache_creation_tokens: Option<u64>,
+    /// Reasoning tokens for this model.
+    #[serde(default)]
+    pub reasoning_tokens: Option<u64>,
 }
 
 impl ModelBreakdown {
@@ -185,12 +201,20 @@ impl ModelBreakdown {
             cost_usd: None,
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_creation_tokens: None,
+            reasoning_tokens: None,
         }
     }
 
-    /// Returns total tokens for this model.
+    /// Returns total tokens for this model (input + output + cache).
     pub fn total_tokens(&self) -> u64 {
-        self.input_tokens.unwrap_or(0) + self.output_tokens.unwrap_or(0)
+        self.input_tokens.unwrap_or(0) + self.output_tokens.unwrap_or(0) + self.cache_tokens()
+    }
+
+    /// Returns cache read plus cache creation tokens.
+    pub fn cache_tokens(&self) -> u64 {
+        self.cache_read_tokens.unwrap_or(0) + self.cache_creation_tokens.unwrap_or(0)
     }
 }
 
@@ -277,5 +301,12 @@ mod tests {
         breakdown.cost_usd = Some(0.25);
 
         assert_eq!(breakdown.total_tokens(), 800);
+
+        breakdown.cache_read_tokens = Some(1000);
+        breakdown.cache_creation_tokens = Some(200);
+        breakdown.reasoning_tokens = Some(100);
+        assert_eq!(breakdown.cache_tokens(), 1200);
+        // Reasoning is part of output, not counted twice
+        assert_eq!(breakdown.total_tokens(), 2000);
     }
 }
@@ -7,22 +7,31 @@
 //! ## Submodules
 //!
 //! - [`provider`] - Provider types (`ProviderKind`, Identity, Metadata, Branding)
+//! - [`provider_id`] - Provider ids for built-in and custom providers (`ProviderId`)
 //! - [`usage`] - Usage types (`UsageSnapshot`, `UsageWindow`, Credits, Quota)
+//! - [`reset`] - Reset times (`ResetTime`, `ResetZone`)
+//! - [`budget`] - Burn budget planning (`BurnPlan`)
 //! - [`cost`] - Cost tracking (`CostUsageSnapshot`, `DailyUsageEntry`)
 //! - [`status`] - Status and fetch types (`ProviderStatus`, `FetchSource`)
 
+mod budget;
 mod cost;
 mod provider;
+mod provider_id;
+mod reset;
 mod status;
 mod usage;
 
 // Re-export everything at the models level
+pub use budget::{BurnPlan, PACE_MARGIN, next_local_time};
 pub use cost::{CostUsageSnapshot, DailyUsageEntry, ModelBreakdown};
 pub use provider::{
     IconStyle, LoginMethod, Provider, ProviderBranding, ProviderColor, ProviderIdentity,
     ProviderKind, ProviderMetadata,
 };
+pub use provider_id::{ProviderId, ProviderInfo};
+pub use reset::{ResetTime, ResetZone, parse_clock};
 pub use status::{FetchSource, ProviderStatus, StatusIndicator};
-pub use usage::{Credits, Quota, UsageData, UsageSnapshot, UsageWindow};
+pub use usage::{Credits, ModelUsage, Quota, UsageData, UsageSnapshot, UsageWindow};
 #[cfg(test)]
 mod serde_tests;
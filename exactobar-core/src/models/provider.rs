@@ -7,6 +7,7 @@
 //! - [`ProviderMetadata`] - Provider capabilities and display info
 //! - [`ProviderBranding`] - Visual styling
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -43,6 +44,12 @@ pub enum ProviderKind {
     MiniMax,
     /// Synthetic.new
     Synthetic,
+    /// GitHub Models (free-tier inference)
+    GitHubModels,
+    /// Hugging Face Inference Providers
+    HuggingFace,
+    /// Replicate
+    Replicate,
 }
 
 impl ProviderKind {
@@ -62,6 +69,9 @@ impl ProviderKind {
             Self::Antigravity => "Antigravity",
             Self::MiniMax => "MiniMax",
             Self::Synthetic => "Synthetic.new",
+            Self::GitHubModels => "GitHub Models",
+            Self::HuggingFace => "Hugging Face",
+            Self::Replicate => "Replicate",
         }
     }
 
@@ -81,6 +91,9 @@ impl ProviderKind {
             Self::Antigravity,
             Self::MiniMax,
             Self::Synthetic,
+            Self::GitHubModels,
+            Self::HuggingFace,
+            Self::Replicate,
         ]
     }
 
@@ -100,9 +113,20 @@ impl ProviderKind {
             Self::Antigravity => "antigravity",
             Self::MiniMax => "minimax",
             Self::Synthetic => "synthetic",
+            Self::GitHubModels => "githubmodels",
+            Self::HuggingFace => "huggingface",
+            Self::Replicate => "replicate",
         }
     }
 
+    /// Looks up a provider by its CLI name.
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.cli_name() == name)
+    }
+
     /// Converts this provider to an index (position in the `all()` array).
     ///
     /// Useful for compact serialization, e.g., storing in Objective-C ivars.
@@ -178,6 +202,10 @@ pub struct ProviderIdentity {
     pub plan_name: Option<String>,
     /// How the user authenticated.
     pub login_method: Option<LoginMethod>,
+    /// When the login expires and the user has to sign in again. Only
+    /// set for logins that can't renew themselves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_expires_at: Option<DateTime<Utc>>,
 }
 
 impl ProviderIdentity {
@@ -189,9 +217,19 @@ impl ProviderIdentity {
             account_organization: None,
             plan_name: None,
             login_method: None,
+            login_expires_at: None,
         }
     }
 
+    /// Returns a stable key for the account: the lowercased email, or the
+    /// organization if there's no email.
+    pub fn account_id(&self) -> Option<String> {
+        self.account_email
+            .as_deref()
+            .or(self.account_organization.as_deref())
+            .map(|id| id.trim().to_lowercase())
+    }
+
     /// Returns a display string for this identity.
     pub fn display_string(&self) -> String {
         match (&self.account_email, &self.account_organization) {
@@ -220,6 +258,19 @@ pub enum LoginMethod {
     DeviceFlow,
 }
 
+impl LoginMethod {
+    /// Returns a human-readable label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OAuth => "OAuth",
+            Self::ApiKey => "API key",
+            Self::BrowserCookies => "Browser cookies",
+            Self::CLI => "CLI",
+            Self::DeviceFlow => "Device sign-in",
+        }
+    }
+}
+
 // ============================================================================
 // Provider Metadata
 // ============================================================================
@@ -327,6 +378,14 @@ impl ProviderBranding {
             }
             ProviderKind::MiniMax => (IconStyle::MiniMax, ProviderColor::new(0.9, 0.1, 0.3)),
             ProviderKind::Synthetic => (IconStyle::Synthetic, ProviderColor::new(0.0, 0.8, 0.7)),
+            ProviderKind::GitHubModels => (
+                IconStyle::GitHubModels,
+                ProviderColor::new(0.51, 0.31, 0.87),
+            ),
+            ProviderKind::HuggingFace => {
+                (IconStyle::HuggingFace, ProviderColor::new(1.0, 0.82, 0.12))
+            }
+            ProviderKind::Replicate => (IconStyle::Replicate, ProviderColor::new(0.93, 0.29, 0.2)),
         };
 
         Self {
@@ -408,6 +467,12 @@ pub enum IconStyle {
     MiniMax,
     /// Synthetic.new icon.
     Synthetic,
+    /// GitHub Models icon.
+    GitHubModels,
+    /// Hugging Face icon.
+    HuggingFace,
+    /// Replicate icon.
+    Replicate,
     /// Combined/aggregate view icon.
     Combined,
 }
@@ -446,4 +511,16 @@ mod tests {
 
         assert_eq!(identity.display_string(), "test@example.com (Acme Inc)");
     }
+
+    #[test]
+    fn test_identity_account_id() {
+        let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+        assert_eq!(identity.account_id(), None);
+
+        identity.account_organization = Some("Acme Inc".to_string());
+        assert_eq!(identity.account_id().as_deref(), Some("acme inc"));
+
+        identity.account_email = Some("Me@Example.com".to_string());
+        assert_eq!(identity.account_id().as_deref(), Some("me@example.com"));
+    }
 }
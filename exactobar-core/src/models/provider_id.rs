@@ -0,0 +1,296 @@
+//! Provider identifiers.
+//!
+//! [`ProviderKind`] is a closed enum of the providers built into
+//! `ExactoBar`. [`ProviderId`] identifies any provider, including custom
+//! ones registered at runtime with a [`ProviderInfo`]. Built-in ids are
+//! their CLI names ("claude", "vertexai"), so a `ProviderId` serializes
+//! the same way as the `ProviderKind` it stands for.
+//!
+//! Ids are interned: each distinct name is allocated once and ids are
+//! `Copy`, so they work as cheap map keys like `ProviderKind` does.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::provider::ProviderKind;
+use crate::error::CoreError;
+
+/// Interned id strings.
+static INTERNED: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+/// Providers registered at runtime, in registration order.
+static REGISTERED: RwLock<Vec<ProviderInfo>> = RwLock::new(Vec::new());
+
+// ============================================================================
+// Provider Id
+// ============================================================================
+
+/// Identifier for a built-in or runtime-registered provider.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProviderId(&'static str);
+
+impl ProviderId {
+    /// Creates an id from a name, normalized to trimmed lowercase.
+    pub fn new(name: &str) -> Self {
+        let name = name.trim().to_lowercase();
+        if let Some(kind) = ProviderKind::from_cli_name(&name) {
+            return Self(kind.cli_name());
+        }
+        Self(intern(name))
+    }
+
+    /// Returns the id as a string.
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+
+    /// Returns the built-in provider this id refers to, if any.
+    pub fn kind(self) -> Option<ProviderKind> {
+        ProviderKind::from_cli_name(self.0)
+    }
+
+    /// Returns true if this id refers to a built-in provider.
+    pub fn is_builtin(self) -> bool {
+        self.kind().is_some()
+    }
+
+    /// Returns display metadata for this id: built-in defaults, or what was
+    /// registered with [`ProviderId::register`].
+    pub fn info(self) -> Option<ProviderInfo> {
+        if let Some(kind) = self.kind() {
+            return Some(ProviderInfo::for_provider(kind));
+        }
+        read_registered()
+            .iter()
+            .find(|info| info.id == self)
+            .cloned()
+    }
+
+    /// Returns the display name, falling back to the id itself.
+    pub fn display_name(self) -> String {
+        self.info()
+            .map_or_else(|| self.0.to_string(), |info| info.display_name)
+    }
+
+    /// Registers a custom provider, replacing any earlier registration with
+    /// the same id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoreError::InvalidConfig` if the id is empty or belongs to
+    /// a built-in provider.
+    pub fn register(info: ProviderInfo) -> Result<Self, CoreError> {
+        if info.id.is_builtin() {
+            return Err(CoreError::InvalidConfig(format!(
+                "provider id '{}' is reserved for a built-in provider",
+                info.id
+            )));
+        }
+        if info.id.0.is_empty() {
+            return Err(CoreError::InvalidConfig("provider id is empty".to_string()));
+        }
+
+        let id = info.id;
+        let mut registered = REGISTERED.write().unwrap_or_else(PoisonError::into_inner);
+        match registered.iter_mut().find(|existing| existing.id == id) {
+            Some(existing) => *existing = info,
+            None => registered.push(info),
+        }
+        Ok(id)
+    }
+
+    /// Removes a registered custom provider. Returns true if it was found.
+    pub fn unregister(id: ProviderId) -> bool {
+        let mut registered = REGISTERED.write().unwrap_or_else(PoisonError::into_inner);
+        let before = registered.len();
+        registered.retain(|info| info.id != id);
+        registered.len() != before
+    }
+
+    /// Returns the registered custom providers, in registration order.
+    pub fn registered() -> Vec<ProviderInfo> {
+        read_registered().clone()
+    }
+
+    /// Returns every known id: built-in providers first, then registered
+    /// custom providers.
+    pub fn all() -> Vec<ProviderId> {
+        ProviderKind::all()
+            .iter()
+            .map(|&kind| Self::from(kind))
+            .chain(read_registered().iter().map(|info| info.id))
+            .collect()
+    }
+}
+
+fn read_registered() -> RwLockReadGuard<'static, Vec<ProviderInfo>> {
+    REGISTERED.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the interned copy of `name`, allocating it on first use.
+fn intern(name: String) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap_or_else(PoisonError::into_inner);
+    let set = interned.get_or_insert_with(HashSet::new);
+    if let Some(existing) = set.get(name.as_str()) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(name.into_boxed_str());
+    set.insert(leaked);
+    leaked
+}
+
+impl From<ProviderKind> for ProviderId {
+    fn from(kind: ProviderKind) -> Self {
+        Self(kind.cli_name())
+    }
+}
+
+impl PartialEq<ProviderKind> for ProviderId {
+    fn eq(&self, other: &ProviderKind) -> bool {
+        self.0 == other.cli_name()
+    }
+}
+
+impl fmt::Display for ProviderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl fmt::Debug for ProviderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ProviderId({:?})", self.0)
+    }
+}
+
+impl Serialize for ProviderId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProviderId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::new(&name))
+    }
+}
+
+// ============================================================================
+// Provider Info
+// ============================================================================
+
+/// Display metadata for a provider known by [`ProviderId`].
+///
+/// Built-in providers have richer metadata in their descriptors; this is
+/// the subset every provider, custom or not, can supply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderInfo {
+    /// The provider's id.
+    pub id: ProviderId,
+    /// Display name in UI.
+    pub display_name: String,
+    /// Label for the primary window.
+    #[serde(default = "default_session_label")]
+    pub session_label: String,
+    /// Label for the secondary window.
+    #[serde(default = "default_weekly_label")]
+    pub weekly_label: String,
+    /// URL to the provider's dashboard.
+    #[serde(default)]
+    pub dashboard_url: Option<String>,
+}
+
+fn default_session_label() -> String {
+    "Session".to_string()
+}
+
+fn default_weekly_label() -> String {
+    "Weekly".to_string()
+}
+
+impl ProviderInfo {
+    /// Creates info for a custom provider with default window labels.
+    pub fn new(id: &str, display_name: impl Into<String>) -> Self {
+        Self {
+            id: ProviderId::new(id),
+            display_name: display_name.into(),
+            session_label: default_session_label(),
+            weekly_label: default_weekly_label(),
+            dashboard_url: None,
+        }
+    }
+
+    /// Creates default info for a built-in provider.
+    pub fn for_provider(kind: ProviderKind) -> Self {
+        Self {
+            id: kind.into(),
+            display_name: kind.display_name().to_string(),
+            session_label: default_session_label(),
+            weekly_label: default_weekly_label(),
+            dashboard_url: None,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_ids() {
+        let id = ProviderId::new(" Claude ");
+        assert_eq!(id, ProviderId::from(ProviderKind::Claude));
+        assert_eq!(id, ProviderKind::Claude);
+        assert_eq!(id.kind(), Some(ProviderKind::Claude));
+        assert_eq!(id.display_name(), "Claude");
+        assert!(ProviderId::all().starts_with(&[ProviderKind::Codex.into()]));
+    }
+
+    #[test]
+    fn test_custom_ids_are_interned() {
+        let a = ProviderId::new("test-interned");
+        let b = ProviderId::new("TEST-INTERNED");
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert!(!a.is_builtin());
+    }
+
+    #[test]
+    fn test_register_custom_provider() {
+        let id = ProviderId::register(ProviderInfo::new("test-gateway", "My Gateway")).unwrap();
+        assert_eq!(id.display_name(), "My Gateway");
+        assert!(ProviderId::all().contains(&id));
+
+        // Re-registering replaces the metadata
+        ProviderId::register(ProviderInfo::new("test-gateway", "Gateway")).unwrap();
+        assert_eq!(id.display_name(), "Gateway");
+
+        assert!(ProviderId::unregister(id));
+        assert!(id.info().is_none());
+        assert_eq!(id.display_name(), "test-gateway");
+    }
+
+    #[test]
+    fn test_register_rejects_builtin_and_empty() {
+        assert!(ProviderId::register(ProviderInfo::new("codex", "Fake Codex")).is_err());
+        assert!(ProviderId::register(ProviderInfo::new("  ", "Nameless")).is_err());
+    }
+
+    #[test]
+    fn test_serde_matches_provider_kind() {
+        let kind = serde_json::to_string(&ProviderKind::VertexAI).unwrap();
+        let id = serde_json::to_string(&ProviderId::from(ProviderKind::VertexAI)).unwrap();
+        assert_eq!(kind, id);
+
+        let parsed: ProviderId = serde_json::from_str("\"my-llm\"").unwrap();
+        assert_eq!(parsed.as_str(), "my-llm");
+    }
+}
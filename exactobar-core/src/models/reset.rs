@@ -0,0 +1,345 @@
+//! Reset time types.
+//!
+//! Providers report reset times in different shapes: APIs send UTC
+//! timestamps, while CLIs print provider-local text like "2pm (PST)" or
+//! "Jan 5 at 12am (`America/Los_Angeles`)". [`ResetTime`] normalizes both
+//! into an instant plus the timezone the provider used, and [`ResetZone`]
+//! is the timezone the user wants reset times shown in.
+
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+// ============================================================================
+// Reset Time
+// ============================================================================
+
+/// When a usage window resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetTime {
+    /// The reset instant.
+    pub at: DateTime<Utc>,
+    /// Timezone the provider reported the reset in, if it named one.
+    pub provider_zone: Option<Tz>,
+}
+
+impl ResetTime {
+    /// Creates a reset time from an instant.
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self {
+            at,
+            provider_zone: None,
+        }
+    }
+
+    /// Sets the timezone the provider reported the reset in.
+    #[must_use]
+    pub fn with_provider_zone(mut self, zone: Tz) -> Self {
+        self.provider_zone = Some(zone);
+        self
+    }
+
+    /// Parses a provider reset description such as "2pm (PST)",
+    /// "2:30 pm (`Europe/Berlin`)" or "Jan 5 at 12am (`America/Los_Angeles`)".
+    ///
+    /// The description must end with a timezone in parentheses; without one
+    /// the instant is ambiguous and `None` is returned. Times without a date
+    /// are the next occurrence after `now`, and dates without a year are
+    /// the next occurrence (allowing a day of slack for stale data).
+    pub fn parse_description(description: &str, now: DateTime<Utc>) -> Option<Self> {
+        let description = description.trim();
+        let description = strip_prefix_ci(description, "resets").unwrap_or(description);
+
+        let open = description.rfind('(')?;
+        let zone = parse_zone_name(description[open + 1..].strip_suffix(')')?)?;
+        let when = description[..open].trim();
+        let when = strip_prefix_ci(when, "at").unwrap_or(when).trim();
+
+        let (date, time) = match when.split_once(" at ").or_else(|| when.split_once(", ")) {
+            Some((date, time)) => (Some(date), Some(time)),
+            None if parse_clock(when).is_some() => (None, Some(when)),
+            None => (Some(when), None),
+        };
+        let time = match time {
+            Some(time) => parse_clock(time)?,
+            None => NaiveTime::MIN,
+        };
+
+        let local_now = now.with_timezone(&zone);
+        let at = if let Some(date) = date {
+            let (month, day) = parse_month_day(date)?;
+            [local_now.year(), local_now.year() + 1]
+                .into_iter()
+                .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+                .filter_map(|date| zone.from_local_datetime(&date.and_time(time)).earliest())
+                .find(|at| *at + Days::new(1) > local_now)?
+        } else {
+            let today = local_now.date_naive();
+            [today, today + Days::new(1)]
+                .into_iter()
+                .filter_map(|date| zone.from_local_datetime(&date.and_time(time)).earliest())
+                .find(|at| *at > local_now)?
+        };
+
+        Some(Self::new(at.with_timezone(&Utc)).with_provider_zone(zone))
+    }
+
+    /// Formats the reset clock time in `zone`, e.g. "3:00 PM".
+    ///
+    /// An explicit zone adds its abbreviation ("3:00 PM UTC") so it isn't
+    /// mistaken for local time.
+    pub fn format_clock(&self, zone: &ResetZone) -> String {
+        match zone {
+            ResetZone::Local => clock(&self.at.with_timezone(&Local)),
+            ResetZone::Named(tz) => {
+                let at = self.at.with_timezone(tz);
+                format!("{} {}", clock(&at), at.format("%Z"))
+            }
+        }
+    }
+
+    /// Formats the reset as a day and time in `zone`: "today at 3:00 PM",
+    /// "tomorrow at 9:00 AM" or "Mon at 3:00 PM".
+    pub fn format_absolute(&self, zone: &ResetZone, now: DateTime<Utc>) -> String {
+        let (reset_date, today) = match zone {
+            ResetZone::Local => (
+                self.at.with_timezone(&Local).date_naive(),
+                now.with_timezone(&Local).date_naive(),
+            ),
+            ResetZone::Named(tz) => (
+                self.at.with_timezone(tz).date_naive(),
+                now.with_timezone(tz).date_naive(),
+            ),
+        };
+
+        let clock = self.format_clock(zone);
+        if reset_date == today {
+            format!("today at {clock}")
+        } else if reset_date == today + Days::new(1) {
+            format!("tomorrow at {clock}")
+        } else {
+            format!("{} at {}", reset_date.format("%a"), clock)
+        }
+    }
+}
+
+fn clock<T: TimeZone>(at: &DateTime<T>) -> String
+where
+    T::Offset: fmt::Display,
+{
+    at.format("%l:%M %p").to_string().trim().to_string()
+}
+
+// ============================================================================
+// Reset Zone
+// ============================================================================
+
+/// Timezone reset times are displayed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResetZone {
+    /// The system timezone.
+    #[default]
+    Local,
+    /// A specific timezone.
+    Named(Tz),
+}
+
+impl ResetZone {
+    /// Parses a timezone setting: "local", an IANA name like
+    /// "Europe/Berlin", "UTC", or a common abbreviation like "PST".
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.trim().eq_ignore_ascii_case("local") {
+            return Some(Self::Local);
+        }
+        parse_zone_name(name).map(Self::Named)
+    }
+
+    /// Resolves an optional timezone setting, falling back to the system
+    /// timezone when it's unset or not recognized.
+    pub fn from_setting(setting: Option<&str>) -> Self {
+        setting.and_then(Self::parse).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ResetZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+// ============================================================================
+// Parsing Helpers
+// ============================================================================
+
+/// Parses an IANA timezone name or a common abbreviation.
+///
+/// Abbreviations map to a region so daylight saving is applied to the
+/// actual date: "PST" in July is still Pacific time.
+fn parse_zone_name(name: &str) -> Option<Tz> {
+    let name = name.trim();
+    let region = match name.to_ascii_uppercase().as_str() {
+        "UTC" | "GMT" | "Z" => Tz::UTC,
+        "PST" | "PDT" | "PT" => Tz::America__Los_Angeles,
+        "MST" | "MDT" | "MT" => Tz::America__Denver,
+        "CST" | "CDT" | "CT" => Tz::America__Chicago,
+        "EST" | "EDT" | "ET" => Tz::America__New_York,
+        "BST" => Tz::Europe__London,
+        "CET" | "CEST" => Tz::Europe__Paris,
+        "IST" => Tz::Asia__Kolkata,
+        "JST" => Tz::Asia__Tokyo,
+        _ => return name.parse().ok(),
+    };
+    Some(region)
+}
+
+/// Parses a clock time like "2pm", "2:30 PM", "12am" or "14:00".
+pub fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    let (digits, pm) = if let Some(rest) = text.strip_suffix("am") {
+        (rest, Some(false))
+    } else if let Some(rest) = text.strip_suffix("pm") {
+        (rest, Some(true))
+    } else {
+        (text.as_str(), None)
+    };
+
+    let (hour, minute) = match digits.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None if pm.is_some() => (digits.parse::<u32>().ok()?, 0),
+        // A bare number is a day, not a time
+        None => return None,
+    };
+
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parses a month and day like "Jan 5" or "January 15".
+fn parse_month_day(text: &str) -> Option<(u32, u32)> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+
+    let (month, day) = text.trim().split_once(' ')?;
+    let month = month.to_ascii_lowercase();
+    let month = MONTHS.iter().position(|m| month.starts_with(m))?;
+    let day = day.trim().trim_end_matches(',').parse().ok()?;
+    Some((u32::try_from(month).ok()? + 1, day))
+}
+
+fn strip_prefix_ci<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    let rest = &text[prefix.len()..];
+    (head.eq_ignore_ascii_case(prefix) && rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_time_only() {
+        // 10:00 PST
+        let now = utc(2025, 1, 15, 18, 0);
+
+        let reset = ResetTime::parse_description("2pm (PST)", now).unwrap();
+        assert_eq!(reset.at, utc(2025, 1, 15, 22, 0));
+        assert_eq!(reset.provider_zone, Some(Tz::America__Los_Angeles));
+
+        // Already past today, so tomorrow
+        let reset = ResetTime::parse_description("Resets 9:30 am (America/Los_Angeles)", now);
+        assert_eq!(reset.unwrap().at, utc(2025, 1, 16, 17, 30));
+    }
+
+    #[test]
+    fn test_parse_uses_daylight_saving() {
+        // "PST" in July is Pacific daylight time (UTC-7)
+        let now = utc(2025, 7, 1, 12, 0);
+        let reset = ResetTime::parse_description("2pm (PST)", now).unwrap();
+        assert_eq!(reset.at, utc(2025, 7, 1, 21, 0));
+    }
+
+    #[test]
+    fn test_parse_with_date() {
+        let now = utc(2025, 1, 2, 12, 0);
+
+        let reset = ResetTime::parse_description("Jan 5 at 12am (Europe/Berlin)", now).unwrap();
+        assert_eq!(reset.at, utc(2025, 1, 4, 23, 0));
+        assert_eq!(reset.provider_zone, Some(Tz::Europe__Berlin));
+
+        let reset = ResetTime::parse_description("Jan 5, 3:15pm (UTC)", now).unwrap();
+        assert_eq!(reset.at, utc(2025, 1, 5, 15, 15));
+
+        // A date that has passed rolls over to next year
+        let now = utc(2025, 12, 30, 12, 0);
+        let reset = ResetTime::parse_description("Jan 5 (UTC)", now).unwrap();
+        assert_eq!(reset.at, utc(2026, 1, 5, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_rejects_ambiguous() {
+        let now = utc(2025, 1, 15, 18, 0);
+        assert!(ResetTime::parse_description("2pm", now).is_none());
+        assert!(ResetTime::parse_description("in 2 hours", now).is_none());
+        assert!(ResetTime::parse_description("2pm (Mars/Olympus)", now).is_none());
+        assert!(ResetTime::parse_description("13pm (UTC)", now).is_none());
+    }
+
+    #[test]
+    fn test_format_in_named_zone() {
+        let reset = ResetTime::new(utc(2025, 1, 15, 22, 0));
+        let now = utc(2025, 1, 15, 12, 0);
+
+        let utc_zone = ResetZone::Named(Tz::UTC);
+        assert_eq!(reset.format_clock(&utc_zone), "10:00 PM UTC");
+        assert_eq!(
+            reset.format_absolute(&utc_zone, now),
+            "today at 10:00 PM UTC"
+        );
+
+        // Already the next day in Tokyo
+        let tokyo = ResetZone::parse("Asia/Tokyo").unwrap();
+        assert_eq!(reset.format_clock(&tokyo), "7:00 AM JST");
+        assert_eq!(
+            reset.format_absolute(&tokyo, now),
+            "tomorrow at 7:00 AM JST"
+        );
+    }
+
+    #[test]
+    fn test_reset_zone_setting() {
+        assert_eq!(ResetZone::from_setting(None), ResetZone::Local);
+        assert_eq!(ResetZone::from_setting(Some("Local")), ResetZone::Local);
+        assert_eq!(ResetZone::from_setting(Some("nowhere")), ResetZone::Local);
+        assert_eq!(
+            ResetZone::from_setting(Some("utc")),
+            ResetZone::Named(Tz::UTC)
+        );
+        assert_eq!(
+            ResetZone::parse("Europe/Berlin").unwrap().to_string(),
+            "Europe/Berlin"
+        );
+    }
+}
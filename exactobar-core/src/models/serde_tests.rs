@@ -42,6 +42,9 @@ fn test_provider_kind_deserialize_lowercase() {
         (r#""kiro""#, ProviderKind::Kiro),
         (r#""antigravity""#, ProviderKind::Antigravity),
         (r#""minimax""#, ProviderKind::MiniMax),
+        (r#""githubmodels""#, ProviderKind::GitHubModels),
+        (r#""huggingface""#, ProviderKind::HuggingFace),
+        (r#""replicate""#, ProviderKind::Replicate),
     ];
 
     for (json, expected) in test_cases {
@@ -390,6 +393,10 @@ fn test_icon_style_all_variants() {
         IconStyle::Kiro,
         IconStyle::Antigravity,
         IconStyle::MiniMax,
+        IconStyle::Synthetic,
+        IconStyle::GitHubModels,
+        IconStyle::HuggingFace,
+        IconStyle::Replicate,
         IconStyle::Combined,
     ];
 
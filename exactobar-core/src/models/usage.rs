@@ -3,6 +3,7 @@
 //! This module contains types related to usage tracking:
 //! - [`UsageSnapshot`] - Main container with multiple windows
 //! - [`UsageWindow`] - Individual usage window
+//! - [`ModelUsage`] - Per-model usage window
 //! - [`UsageData`] - Legacy simple format
 //! - [`Quota`] - Quota information
 //! - [`Credits`] - Credit-based systems
@@ -11,6 +12,7 @@ use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::ProviderIdentity;
+use super::ResetTime;
 use super::provider::ProviderKind;
 use super::status::FetchSource;
 use crate::error::CoreError;
@@ -43,6 +45,13 @@ pub struct UsageSnapshot {
     /// How this data was fetched.
     #[serde(default)]
     pub fetch_source: FetchSource,
+    /// Per-model quotas, for providers that limit each model separately.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelUsage>,
+    /// Preferred sources that failed when this came from a fallback, so
+    /// the data may be incomplete.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unavailable_sources: Vec<FetchSource>,
 }
 
 impl UsageSnapshot {
@@ -56,6 +65,8 @@ impl UsageSnapshot {
             updated_at: Utc::now(),
             identity: None,
             fetch_source: FetchSource::default(),
+            models: Vec::new(),
+            unavailable_sources: Vec::new(),
         }
     }
 
@@ -64,39 +75,96 @@ impl UsageSnapshot {
         Utc::now() - self.updated_at > threshold
     }
 
+    /// Returns the windows that are present, in order: primary, secondary,
+    /// tertiary, search.
+    pub fn windows(&self) -> impl Iterator<Item = &UsageWindow> {
+        [&self.primary, &self.secondary, &self.tertiary, &self.search]
+            .into_iter()
+            .flatten()
+    }
+
     /// Returns true if any window is approaching its limit (>80%).
     pub fn is_approaching_limit(&self) -> bool {
-        self.primary.as_ref().is_some_and(|w| w.used_percent > 80.0)
-            || self
-                .secondary
-                .as_ref()
-                .is_some_and(|w| w.used_percent > 80.0)
-            || self
-                .tertiary
-                .as_ref()
-                .is_some_and(|w| w.used_percent > 80.0)
-            || self
-                .search
-                .as_ref()
-                .is_some_and(|w| w.used_percent > 80.0)
+        self.windows().any(UsageWindow::is_approaching_limit)
+    }
+
+    /// Returns true if any window has used at least `threshold` percent.
+    pub fn meets_threshold(&self, threshold: f64) -> bool {
+        self.windows().any(|w| w.meets_threshold(threshold))
     }
 
     /// Returns the highest usage percentage across all windows.
     pub fn max_usage_percent(&self) -> f64 {
-        let mut max = 0.0_f64;
-        if let Some(ref w) = self.primary {
-            max = max.max(w.used_percent);
-        }
-        if let Some(ref w) = self.secondary {
-            max = max.max(w.used_percent);
+        self.windows()
+            .map(UsageWindow::clamped_used_percent)
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the lowest remaining percentage across all windows, or
+    /// `None` if there are no windows.
+    pub fn min_remaining_percent(&self) -> Option<f64> {
+        self.windows()
+            .map(UsageWindow::remaining_percent)
+            .reduce(f64::min)
+    }
+
+    /// Combines this snapshot with one for the same provider from another
+    /// source.
+    ///
+    /// Windows present in both are merged with [`UsageWindow::merged_with`];
+    /// windows, identity and models only `other` has are filled in. The
+    /// result takes the later `updated_at` and keeps this snapshot's
+    /// `fetch_source`.
+    #[must_use]
+    pub fn merged_with(&self, other: &UsageSnapshot) -> UsageSnapshot {
+        fn merge(a: Option<&UsageWindow>, b: Option<&UsageWindow>) -> Option<UsageWindow> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.merged_with(b)),
+                (a, b) => a.or(b).cloned(),
+            }
         }
-        if let Some(ref w) = self.tertiary {
-            max = max.max(w.used_percent);
+
+        let mut models = self.models.clone();
+        for model in &other.models {
+            match models.iter_mut().find(|m| m.model == model.model) {
+                Some(existing) => existing.window = existing.window.merged_with(&model.window),
+                None => models.push(model.clone()),
+            }
         }
-        if let Some(ref w) = self.search {
-            max = max.max(w.used_percent);
+
+        UsageSnapshot {
+            primary: merge(self.primary.as_ref(), other.primary.as_ref()),
+            secondary: merge(self.secondary.as_ref(), other.secondary.as_ref()),
+            tertiary: merge(self.tertiary.as_ref(), other.tertiary.as_ref()),
+            search: merge(self.search.as_ref(), other.search.as_ref()),
+            updated_at: self.updated_at.max(other.updated_at),
+            identity: self.identity.clone().or_else(|| other.identity.clone()),
+            fetch_source: self.fetch_source,
+            models,
+            unavailable_sources: self.unavailable_sources.clone(),
         }
-        max
+    }
+
+    /// Describes what's missing when this came from a fallback, like
+    /// "limited data (web source unavailable)".
+    pub fn limitation(&self) -> Option<String> {
+        let names: Vec<&str> = self
+            .unavailable_sources
+            .iter()
+            .map(|source| match source {
+                FetchSource::Web => "web",
+                FetchSource::LocalProbe => "local",
+                FetchSource::Auto => "auto",
+                other => other.label(),
+            })
+            .collect();
+        let (last, rest) = names.split_last()?;
+        let sources = if rest.is_empty() {
+            format!("{last} source")
+        } else {
+            format!("{} and {last} sources", rest.join(", "))
+        };
+        Some(format!("limited data ({sources} unavailable)"))
     }
 
     /// Returns true if any window data is present.
@@ -146,6 +214,12 @@ impl UsageSnapshot {
                 .validate()
                 .map_err(|e| CoreError::InvalidData(format!("search window: {e}")))?;
         }
+        for model in &self.models {
+            model
+                .window
+                .validate()
+                .map_err(|e| CoreError::InvalidData(format!("{} window: {e}", model.model)))?;
+        }
         Ok(())
     }
 
@@ -167,6 +241,28 @@ impl UsageSnapshot {
         if let Some(ref mut search) = self.search {
             search.sanitize();
         }
+        for model in &mut self.models {
+            model.window.sanitize();
+        }
+    }
+}
+
+/// Usage window for a single model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Model name or label (e.g., "Gemini 2.5 Pro").
+    pub model: String,
+    /// Usage of this model's quota.
+    pub window: UsageWindow,
+}
+
+impl ModelUsage {
+    /// Creates a per-model usage entry.
+    pub fn new(model: impl Into<String>, window: UsageWindow) -> Self {
+        Self {
+            model: model.into(),
+            window,
+        }
     }
 }
 
@@ -194,9 +290,54 @@ impl UsageWindow {
         }
     }
 
-    /// Returns the remaining percentage (100 - used).
+    /// Creates a usage window from a remaining percentage, for sources
+    /// that report "X% left".
+    pub fn from_remaining(remaining_percent: f64) -> Self {
+        Self::new(100.0 - remaining_percent)
+    }
+
+    /// Returns the used percentage clamped to [0, 100] (NaN becomes 0).
+    pub fn clamped_used_percent(&self) -> f64 {
+        if self.used_percent.is_finite() {
+            self.used_percent.clamp(0.0, 100.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the remaining percentage (100 - used), clamped to [0, 100].
     pub fn remaining_percent(&self) -> f64 {
-        (100.0 - self.used_percent).max(0.0)
+        100.0 - self.clamped_used_percent()
+    }
+
+    /// Returns the used or remaining percentage, whichever the display
+    /// shows, clamped to [0, 100].
+    pub fn display_percent(&self, show_used: bool) -> f64 {
+        if show_used {
+            self.clamped_used_percent()
+        } else {
+            self.remaining_percent()
+        }
+    }
+
+    /// Returns when this window resets.
+    ///
+    /// Uses `resets_at` when the provider sent a timestamp, otherwise parses
+    /// `reset_description` ("2pm (PST)"). The provider's timezone is taken
+    /// from the description either way.
+    pub fn reset_time(&self, now: DateTime<Utc>) -> Option<ResetTime> {
+        let parsed = self
+            .reset_description
+            .as_deref()
+            .and_then(|description| ResetTime::parse_description(description, now));
+
+        match (self.resets_at, parsed) {
+            (Some(at), parsed) => Some(ResetTime {
+                at,
+                provider_zone: parsed.and_then(|p| p.provider_zone),
+            }),
+            (None, parsed) => parsed,
+        }
     }
 
     /// Returns true if usage is over the limit.
@@ -209,6 +350,34 @@ impl UsageWindow {
         self.used_percent > 80.0
     }
 
+    /// Returns true if at least `threshold` percent has been used.
+    pub fn meets_threshold(&self, threshold: f64) -> bool {
+        self.used_percent >= threshold
+    }
+
+    /// Combines this window with the same window reported by another source.
+    ///
+    /// The higher usage wins, so the result never understates usage; reset
+    /// details the winning source lacks are filled in from the other.
+    #[must_use]
+    pub fn merged_with(&self, other: &UsageWindow) -> UsageWindow {
+        let (primary, fallback) = if other.clamped_used_percent() > self.clamped_used_percent() {
+            (other, self)
+        } else {
+            (self, other)
+        };
+
+        UsageWindow {
+            used_percent: primary.used_percent,
+            window_minutes: primary.window_minutes.or(fallback.window_minutes),
+            resets_at: primary.resets_at.or(fallback.resets_at),
+            reset_description: primary
+                .reset_description
+                .clone()
+                .or_else(|| fallback.reset_description.clone()),
+        }
+    }
+
     /// Returns the window duration as a chrono Duration.
     pub fn window_duration(&self) -> Option<Duration> {
         self.window_minutes.map(|m| Duration::minutes(i64::from(m)))
@@ -384,6 +553,8 @@ impl UsageData {
             updated_at: self.fetched_at,
             identity: None,
             fetch_source: FetchSource::Auto,
+            models: Vec::new(),
+            unavailable_sources: Vec::new(),
         }
     }
 }
@@ -453,6 +624,103 @@ mod tests {
         assert!(window.is_over_limit());
     }
 
+    #[test]
+    fn test_usage_window_normalization() {
+        let window = UsageWindow::from_remaining(30.0);
+        assert_eq!(window.used_percent, 70.0);
+        assert_eq!(window.display_percent(true), 70.0);
+        assert_eq!(window.display_percent(false), 30.0);
+
+        // Out-of-range values are clamped for display
+        assert_eq!(UsageWindow::new(120.0).clamped_used_percent(), 100.0);
+        assert_eq!(UsageWindow::new(-5.0).remaining_percent(), 100.0);
+        assert_eq!(UsageWindow::new(f64::NAN).clamped_used_percent(), 0.0);
+
+        assert!(window.meets_threshold(70.0));
+        assert!(!window.meets_threshold(70.1));
+    }
+
+    #[test]
+    fn test_usage_window_merge() {
+        let mut cli = UsageWindow::new(40.0);
+        cli.reset_description = Some("in 2h".to_string());
+        let mut api = UsageWindow::new(45.0);
+        api.window_minutes = Some(300);
+
+        let merged = cli.merged_with(&api);
+        assert_eq!(merged.used_percent, 45.0);
+        assert_eq!(merged.window_minutes, Some(300));
+        assert_eq!(merged.reset_description.as_deref(), Some("in 2h"));
+    }
+
+    #[test]
+    fn test_usage_window_reset_time() {
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 18, 0, 0).unwrap();
+        let mut window = UsageWindow::new(50.0);
+        assert!(window.reset_time(now).is_none());
+
+        // Parsed from the provider's description
+        window.reset_description = Some("2pm (PST)".to_string());
+        let reset = window.reset_time(now).unwrap();
+        assert_eq!(
+            reset.at,
+            Utc.with_ymd_and_hms(2025, 1, 15, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            reset.provider_zone,
+            Some(chrono_tz::Tz::America__Los_Angeles)
+        );
+
+        // A timestamp wins, but keeps the description's timezone
+        let resets_at = Utc.with_ymd_and_hms(2025, 1, 15, 22, 1, 0).unwrap();
+        window.resets_at = Some(resets_at);
+        let reset = window.reset_time(now).unwrap();
+        assert_eq!(reset.at, resets_at);
+        assert!(reset.provider_zone.is_some());
+    }
+
+    #[test]
+    fn test_usage_snapshot_merge() {
+        let mut a = UsageSnapshot::new();
+        a.primary = Some(UsageWindow::new(20.0));
+        a.models = vec![ModelUsage::new("pro", UsageWindow::new(10.0))];
+
+        let mut b = UsageSnapshot::new();
+        b.primary = Some(UsageWindow::new(25.0));
+        b.secondary = Some(UsageWindow::new(60.0));
+        b.models = vec![
+            ModelUsage::new("pro", UsageWindow::new(15.0)),
+            ModelUsage::new("flash", UsageWindow::new(5.0)),
+        ];
+
+        let merged = a.merged_with(&b);
+        assert_eq!(merged.primary.unwrap().used_percent, 25.0);
+        assert_eq!(merged.secondary.unwrap().used_percent, 60.0);
+        assert_eq!(merged.models.len(), 2);
+        assert_eq!(merged.models[0].window.used_percent, 15.0);
+        assert_eq!(merged.updated_at, a.updated_at.max(b.updated_at));
+    }
+
+    #[test]
+    fn test_usage_snapshot_limitation() {
+        let mut snapshot = UsageSnapshot::new();
+        assert_eq!(snapshot.limitation(), None);
+
+        snapshot.unavailable_sources = vec![FetchSource::Web];
+        assert_eq!(
+            snapshot.limitation().as_deref(),
+            Some("limited data (web source unavailable)")
+        );
+
+        snapshot.unavailable_sources = vec![FetchSource::OAuth, FetchSource::Web];
+        assert_eq!(
+            snapshot.limitation().as_deref(),
+            Some("limited data (OAuth and web sources unavailable)")
+        );
+    }
+
     #[test]
     fn test_usage_snapshot_max_usage() {
         let mut snapshot = UsageSnapshot::new();
@@ -461,7 +729,11 @@ mod tests {
         snapshot.tertiary = Some(UsageWindow::new(30.0));
 
         assert_eq!(snapshot.max_usage_percent(), 85.0);
+        assert_eq!(snapshot.min_remaining_percent(), Some(15.0));
         assert!(snapshot.is_approaching_limit());
+        assert!(snapshot.meets_threshold(85.0));
+        assert!(!snapshot.meets_threshold(90.0));
+        assert_eq!(UsageSnapshot::new().min_remaining_percent(), None);
     }
 
     #[test]
@@ -577,4 +849,23 @@ mod tests {
         assert_eq!(snapshot.primary.as_ref().unwrap().used_percent, 100.0);
         assert_eq!(snapshot.secondary.as_ref().unwrap().used_percent, 0.0);
     }
+
+    #[test]
+    fn test_usage_snapshot_models() {
+        let mut snapshot = UsageSnapshot::new();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("models"));
+
+        snapshot
+            .models
+            .push(ModelUsage::new("gemini-2.5-pro", UsageWindow::new(120.0)));
+        assert!(snapshot.validate().is_err());
+        snapshot.sanitize();
+        assert_eq!(snapshot.models[0].window.used_percent, 100.0);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: UsageSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.models.len(), 1);
+        assert_eq!(parsed.models[0].model, "gemini-2.5-pro");
+    }
 }
@@ -0,0 +1,255 @@
+//! Secret redaction for logs, crash reports and command output.
+//!
+//! Provider code logs HTTP headers, error bodies and identities while
+//! debugging, and any of them can carry an API key, an OAuth token or a
+//! cookie. [`redact`] scrubs text before it leaves the process:
+//!
+//! - `Authorization` schemes (`Bearer ...`, `Basic ...`)
+//! - `Cookie` / `Set-Cookie` values
+//! - `key=value` and JSON fields whose name ends in token, secret,
+//!   password, api key or session key
+//! - Well-known token shapes: `sk-...` keys, GitHub tokens, Google API
+//!   keys and OAuth tokens, JWTs and Slack/Discord webhook URLs
+//! - Email addresses, unless turned off with [`set_redact_emails`]
+//!
+//! [`RedactingWriter`] applies it to a log writer and
+//! [`install_panic_hook`] to crash reports.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use regex::Regex;
+
+/// Replacement for secrets.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Replacement for email addresses.
+pub const REDACTED_EMAIL: &str = "[EMAIL]";
+
+/// Whether [`redact`] scrubs email addresses.
+static REDACT_EMAILS: AtomicBool = AtomicBool::new(true);
+
+/// A pattern and what its matches become.
+struct Rule {
+    regex: Regex,
+    replacement: &'static str,
+}
+
+impl Rule {
+    fn new(pattern: &str, replacement: &'static str) -> Self {
+        Self {
+            regex: Regex::new(pattern).expect("Invalid regex"),
+            replacement,
+        }
+    }
+
+    fn apply<'a>(&self, text: Cow<'a, str>) -> Cow<'a, str> {
+        if self.regex.is_match(&text) {
+            Cow::Owned(self.regex.replace_all(&text, self.replacement).into_owned())
+        } else {
+            text
+        }
+    }
+}
+
+/// Secret rules, in the order they're applied. Header rules run first so a
+/// whole cookie header is replaced rather than its pieces.
+static SECRET_RULES: LazyLock<Vec<Rule>> = LazyLock::new(|| {
+    vec![
+        // Authorization: Bearer abc... / Basic dXNlcjpwYXNz
+        Rule::new(
+            r"(?i)\b(bearer|basic)\s+[A-Za-z0-9._~+/=-]{6,}",
+            "${1} [REDACTED]",
+        ),
+        // Cookie: a=b; c=d (header, JSON field or Debug output)
+        Rule::new(
+            r#"(?i)\b((?:set-)?cookie)(["']?\s*[:=]\s*(?:Some\()?["']?)[^"'\r\n\[]+"#,
+            "${1}${2}[REDACTED]",
+        ),
+        // access_token=..., "api_key": "...", sessionKey: Some("...")
+        Rule::new(
+            r#"(?i)\b([\w.-]*?(?:token|secret|password|passwd|api[_-]?key|apikey|session[_-]?key|session[_-]?id|sessionid))(["']?\s*[:=]\s*(?:Some\()?["']?)[^\s"',;&)}\[\]\\]{8,}"#,
+            "${1}${2}[REDACTED]",
+        ),
+        // OpenAI / Anthropic keys
+        Rule::new(r"\bsk-[A-Za-z0-9_-]{16,}", REDACTED),
+        // GitHub tokens
+        Rule::new(
+            r"\b(?:gh[pousr]_[A-Za-z0-9]{20,}|github_pat_[A-Za-z0-9_]{20,})",
+            REDACTED,
+        ),
+        // Google API keys and OAuth access tokens
+        Rule::new(
+            r"\b(?:AIza[0-9A-Za-z_-]{30,}|ya29\.[0-9A-Za-z_-]{10,})",
+            REDACTED,
+        ),
+        // JWTs
+        Rule::new(
+            r"\beyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}",
+            REDACTED,
+        ),
+        // Webhook URLs carry their secret in the path
+        Rule::new(
+            r#"(https://(?:hooks\.slack\.com/services|(?:discord|discordapp)\.com/api/webhooks))/[^\s"']+"#,
+            "${1}/[REDACTED]",
+        ),
+    ]
+});
+
+static EMAIL_RULE: LazyLock<Rule> = LazyLock::new(|| {
+    Rule::new(
+        r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b",
+        REDACTED_EMAIL,
+    )
+});
+
+// ============================================================================
+// Redactor
+// ============================================================================
+
+/// Scrubs secrets, and optionally email addresses, from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Redactor {
+    emails: bool,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redactor {
+    /// Creates a redactor that scrubs secrets and email addresses.
+    pub const fn new() -> Self {
+        Self { emails: true }
+    }
+
+    /// Creates a redactor following [`set_redact_emails`].
+    pub fn current() -> Self {
+        Self::new().with_emails(redacts_emails())
+    }
+
+    /// Sets whether email addresses are scrubbed.
+    #[must_use]
+    pub const fn with_emails(mut self, emails: bool) -> Self {
+        self.emails = emails;
+        self
+    }
+
+    /// Returns the text with secrets replaced, borrowing it if there were
+    /// none.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for rule in SECRET_RULES.iter() {
+            text = rule.apply(text);
+        }
+        if self.emails {
+            text = EMAIL_RULE.apply(text);
+        }
+        text
+    }
+}
+
+/// Sets whether [`redact`] scrubs email addresses (default: true).
+pub fn set_redact_emails(redact: bool) {
+    REDACT_EMAILS.store(redact, Ordering::Relaxed);
+}
+
+/// Returns whether [`redact`] scrubs email addresses.
+pub fn redacts_emails() -> bool {
+    REDACT_EMAILS.load(Ordering::Relaxed)
+}
+
+/// Scrubs secrets, and email addresses unless turned off, from text.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    Redactor::current().redact(text)
+}
+
+// ============================================================================
+// Log Writer
+// ============================================================================
+
+/// Writer that redacts everything written through it.
+///
+/// Output is buffered and redacted as a whole when flushed or dropped, so a
+/// secret split across writes is still caught. Logging frameworks create
+/// one per event, e.g. with `tracing-subscriber`:
+///
+/// ```ignore
+/// fmt::layer().with_writer(|| RedactingWriter::new(std::io::stderr()))
+/// ```
+#[derive(Debug)]
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    /// Wraps a writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn write_buffered(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.buffer);
+        self.inner.write_all(redact(&text).as_bytes())?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffered()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+// ============================================================================
+// Crash Reports
+// ============================================================================
+
+/// Replaces the panic hook with one that prints a redacted report.
+///
+/// Panic messages often include the value that failed to parse, which can
+/// be a token or a response body. The report otherwise matches the default
+/// hook's, including a backtrace when `RUST_BACKTRACE` asks for one.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        let mut report = format!("thread '{name}' {info}\n");
+
+        let backtrace = std::backtrace::Backtrace::capture();
+        if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+            report.push_str("stack backtrace:\n");
+            report.push_str(&backtrace.to_string());
+        } else {
+            report.push_str(
+                "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n",
+            );
+        }
+
+        let _ = io::stderr().write_all(redact(&report).as_bytes());
+    }));
+}
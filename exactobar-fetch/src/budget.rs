@@ -0,0 +1,216 @@
+//! Outbound request budget.
+//!
+//! Polling a provider spends the very quota `ExactoBar` reports on. The
+//! budget caps how many requests go out per hour, for each provider and
+//! for all of them together, and the pipeline refuses to run a strategy
+//! once a cap is reached (see [`FetchContext::with_request_budget`]).
+//!
+//! Each strategy run counts as one request, whatever the strategy does to
+//! fetch usage. Requests are counted in a sliding hour shared by every
+//! context in the process, so the counts double as accounting of what was
+//! sent, limits or not.
+//!
+//! [`FetchContext::with_request_budget`]: crate::FetchContext::with_request_budget
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use exactobar_core::ProviderKind;
+
+use crate::error::FetchError;
+
+/// The window requests are counted in.
+pub const BUDGET_WINDOW: Duration = Duration::from_secs(3600);
+
+/// The budget shared by all contexts.
+static SHARED: LazyLock<RequestBudget> = LazyLock::new(|| RequestBudget::new(BUDGET_WINDOW));
+
+/// The limits a provider's requests are held to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderBudget {
+    /// Provider the requests are counted for.
+    pub provider: ProviderKind,
+    /// Most requests per hour for this provider, if limited.
+    pub per_hour: Option<u32>,
+    /// Most requests per hour for all providers together, if limited.
+    pub global_per_hour: Option<u32>,
+}
+
+impl ProviderBudget {
+    /// Counts a provider's requests without limiting them.
+    pub fn unlimited(provider: ProviderKind) -> Self {
+        Self {
+            provider,
+            per_hour: None,
+            global_per_hour: None,
+        }
+    }
+}
+
+/// Requests sent in the last window, per provider.
+pub struct RequestBudget {
+    window: Duration,
+    sent: Mutex<HashMap<ProviderKind, VecDeque<Instant>>>,
+}
+
+impl RequestBudget {
+    /// Creates a budget counting requests over `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the budget shared by all contexts.
+    pub fn shared() -> &'static Self {
+        &SHARED
+    }
+
+    /// Counts a request for `budget.provider`, or fails with
+    /// [`FetchError::BudgetExhausted`] if it would go over a limit.
+    pub fn acquire(&self, budget: &ProviderBudget) -> Result<(), FetchError> {
+        self.acquire_at(budget, Instant::now())
+    }
+
+    fn acquire_at(&self, budget: &ProviderBudget, now: Instant) -> Result<(), FetchError> {
+        let mut sent = self.sent.lock().unwrap_or_else(PoisonError::into_inner);
+        for times in sent.values_mut() {
+            while times
+                .front()
+                .is_some_and(|at| now.duration_since(*at) >= self.window)
+            {
+                times.pop_front();
+            }
+        }
+        sent.retain(|_, times| !times.is_empty());
+
+        let window = self.window;
+        let retry_after = |oldest: Option<&Instant>| {
+            oldest.map_or(0, |at| {
+                window
+                    .saturating_sub(now.duration_since(*at))
+                    .as_secs()
+                    .max(1)
+            })
+        };
+
+        let own = sent.get(&budget.provider);
+        if let Some(limit) = budget.per_hour {
+            let count = own.map_or(0, VecDeque::len);
+            if count >= limit as usize {
+                return Err(FetchError::BudgetExhausted {
+                    limit,
+                    global: false,
+                    retry_after: retry_after(own.and_then(VecDeque::front)),
+                });
+            }
+        }
+        if let Some(limit) = budget.global_per_hour {
+            let count: usize = sent.values().map(VecDeque::len).sum();
+            if count >= limit as usize {
+                let oldest = sent.values().filter_map(VecDeque::front).min();
+                return Err(FetchError::BudgetExhausted {
+                    limit,
+                    global: true,
+                    retry_after: retry_after(oldest),
+                });
+            }
+        }
+
+        sent.entry(budget.provider).or_default().push_back(now);
+        Ok(())
+    }
+
+    /// Returns how many requests each provider sent in the last window.
+    pub fn counts(&self) -> HashMap<ProviderKind, u32> {
+        let now = Instant::now();
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(provider, times)| {
+                let recent = times
+                    .iter()
+                    .filter(|at| now.duration_since(**at) < self.window)
+                    .count();
+                (*provider, u32::try_from(recent).unwrap_or(u32::MAX))
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_limit() {
+        let budget = RequestBudget::new(Duration::from_secs(60));
+        let limited = ProviderBudget {
+            per_hour: Some(2),
+            ..ProviderBudget::unlimited(ProviderKind::Claude)
+        };
+        let start = Instant::now();
+
+        assert!(budget.acquire_at(&limited, start).is_ok());
+        assert!(budget.acquire_at(&limited, start).is_ok());
+        let err = budget
+            .acquire_at(&limited, start + Duration::from_secs(20))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FetchError::BudgetExhausted {
+                limit: 2,
+                global: false,
+                retry_after: 40
+            }
+        ));
+
+        // Other providers aren't held to it
+        let other = ProviderBudget::unlimited(ProviderKind::Codex);
+        assert!(budget.acquire_at(&other, start).is_ok());
+
+        // Requests older than the window no longer count
+        assert!(
+            budget
+                .acquire_at(&limited, start + Duration::from_secs(60))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_global_limit() {
+        let budget = RequestBudget::new(Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(
+            budget
+                .acquire_at(&ProviderBudget::unlimited(ProviderKind::Claude), start)
+                .is_ok()
+        );
+        assert!(
+            budget
+                .acquire_at(&ProviderBudget::unlimited(ProviderKind::Codex), start)
+                .is_ok()
+        );
+
+        let global = ProviderBudget {
+            global_per_hour: Some(2),
+            ..ProviderBudget::unlimited(ProviderKind::Cursor)
+        };
+        assert!(matches!(
+            budget.acquire_at(&global, start),
+            Err(FetchError::BudgetExhausted { global: true, .. })
+        ));
+
+        let counts = budget.counts();
+        assert_eq!(counts.get(&ProviderKind::Claude), Some(&1));
+        assert_eq!(counts.get(&ProviderKind::Cursor), None);
+    }
+}
@@ -2,16 +2,27 @@
 //!
 //! The fetch context is passed to all strategies and provides unified
 //! access to system resources like keychain, HTTP client, process runner, etc.
+//!
+//! The keychain, HTTP and process APIs are trait objects ([`KeychainApi`],
+//! [`HttpApi`], [`ProcessApi`]), so [`FetchContextBuilder`] can inject
+//! mocks, sandboxes or offline implementations in place of the system
+//! ones.
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
+use crate::budget::{ProviderBudget, RequestBudget};
+use crate::error::FetchError;
 use crate::host::{
-    browser::BrowserCookieImporter, http::HttpClient, keychain::KeychainApi,
-    keychain::SystemKeychain, process::ProcessRunner, status::StatusPoller,
+    browser::BrowserCookieImporter, http::HttpApi, http::HttpClient, http::RequestHeaders,
+    keychain::KeychainApi, keychain::SystemKeychain, process::ProcessApi, process::ProcessRunner,
+    process::ProcessSandbox, status::StatusPoller,
 };
+use crate::progress::{FetchProgress, FetchStage, ProgressSender};
+use crate::strategy::StrategySettings;
 
 // ============================================================================
 // Source Mode
@@ -72,6 +83,20 @@ pub struct FetchSettings {
     pub max_retries: u32,
     /// Delay between retries.
     pub retry_delay: Duration,
+    /// Which of the provider's strategies may run.
+    pub strategies: StrategySettings,
+    /// Base URL API requests go to instead of the provider's, like a
+    /// corporate gateway or a local mock server for end-to-end tests and
+    /// demos. Only strategies that
+    /// call an HTTP API honor it (see [`FetchContext::api_base`]).
+    pub endpoint_override: Option<String>,
+    /// User-Agent and extra headers sent with the provider's requests.
+    /// Sent by the context's HTTP client; web strategies with their own
+    /// client apply them too.
+    pub request_headers: RequestHeaders,
+    /// Provider the strategies' requests are counted for, and the limits
+    /// they're held to. Requests aren't counted without one.
+    pub request_budget: Option<ProviderBudget>,
 }
 
 impl Default for FetchSettings {
@@ -82,6 +107,10 @@ impl Default for FetchSettings {
             web_debug_dump_html: false,
             max_retries: 2,
             retry_delay: Duration::from_secs(1),
+            strategies: StrategySettings::default(),
+            endpoint_override: None,
+            request_headers: RequestHeaders::default(),
+            request_budget: None,
         }
     }
 }
@@ -132,13 +161,17 @@ pub struct FetchContext {
     /// Secure credential storage.
     pub keychain: Arc<dyn KeychainApi>,
     /// HTTP client with tracing.
-    pub http: Arc<HttpClient>,
+    pub http: Arc<dyn HttpApi>,
     /// Process runner for CLI tools.
-    pub process: Arc<ProcessRunner>,
+    pub process: Arc<dyn ProcessApi>,
     /// Browser cookie importer.
     pub browser: Arc<BrowserCookieImporter>,
     /// Status page poller.
     pub status: Arc<StatusPoller>,
+    /// Cancelled to abort the fetch, e.g. when its provider is turned off.
+    pub cancel: CancellationToken,
+    /// Where the fetch reports its progress, if anywhere.
+    pub progress: Option<ProgressSender>,
     /// Fetch settings.
     pub settings: FetchSettings,
 }
@@ -165,6 +198,8 @@ impl FetchContext {
             process: Arc::new(ProcessRunner::new()),
             browser: Arc::new(BrowserCookieImporter::new()),
             status: Arc::new(StatusPoller::new()),
+            cancel: CancellationToken::new(),
+            progress: None,
             settings,
         }
     }
@@ -179,6 +214,123 @@ impl FetchContext {
         self.settings.timeout
     }
 
+    /// Returns the base URL for a provider API whose default is `default`:
+    /// the endpoint override if one is set, otherwise `default`.
+    pub fn api_base<'a>(&'a self, default: &'a str) -> &'a str {
+        self.settings
+            .endpoint_override
+            .as_deref()
+            .map_or(default, |url| url.trim_end_matches('/'))
+    }
+
+    /// Returns a context sharing this one's host APIs but restricted to
+    /// `mode`, for applying per-provider source overrides.
+    #[must_use]
+    pub fn with_source_mode(&self, mode: SourceMode) -> Self {
+        Self {
+            keychain: Arc::clone(&self.keychain),
+            http: Arc::clone(&self.http),
+            process: Arc::clone(&self.process),
+            browser: Arc::clone(&self.browser),
+            status: Arc::clone(&self.status),
+            cancel: self.cancel.clone(),
+            progress: self.progress.clone(),
+            settings: FetchSettings {
+                source_mode: mode,
+                ..self.settings.clone()
+            },
+        }
+    }
+
+    /// Returns this context with CLI commands run in `sandbox`, for
+    /// applying per-provider sandbox settings.
+    #[must_use]
+    pub fn with_sandbox(mut self, sandbox: ProcessSandbox) -> Self {
+        self.process = self.process.with_sandbox(sandbox);
+        self
+    }
+
+    /// Returns this context sending API requests to `url`, if given, for
+    /// applying per-provider base URLs like a corporate gateway. The host
+    /// is added to the HTTP client's allowlist. An endpoint override set on
+    /// the builder takes precedence.
+    #[must_use]
+    pub fn with_base_url(mut self, url: Option<String>) -> Self {
+        if let (None, Some(url)) = (&self.settings.endpoint_override, url) {
+            self.http = self.http.allowing_host_of(&url);
+            self.settings.endpoint_override = Some(url);
+        }
+        self
+    }
+
+    /// Returns this context sending `headers` with its requests, for
+    /// applying per-provider User-Agent and header settings.
+    #[must_use]
+    pub fn with_request_headers(mut self, headers: RequestHeaders) -> Self {
+        if !headers.is_default() {
+            self.http = self.http.with_request_headers(&headers);
+        }
+        self.settings.request_headers = headers;
+        self
+    }
+
+    /// Returns this context counting its strategies' requests against
+    /// `budget`, for applying request limits. The pipeline stops once a
+    /// limit is reached.
+    #[must_use]
+    pub fn with_request_budget(mut self, budget: ProviderBudget) -> Self {
+        self.settings.request_budget = Some(budget);
+        self
+    }
+
+    /// Counts a request against the context's budget, if it has one.
+    pub fn acquire_request(&self) -> Result<(), FetchError> {
+        match &self.settings.request_budget {
+            Some(budget) => RequestBudget::shared().acquire(budget),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns this context aborting its fetch once `cancel` is cancelled.
+    /// The pipeline stops with [`FetchError::Cancelled`], dropping the
+    /// strategy's in-flight requests and killing its commands.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Returns true if the fetch was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Returns this context reporting its fetch's progress to `progress`.
+    #[must_use]
+    pub fn with_progress(mut self, progress: ProgressSender) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Reports that the fetch reached `stage`, if the context reports
+    /// progress.
+    pub fn report_progress(&self, stage: FetchStage) {
+        if let Some(progress) = &self.progress {
+            let _ = progress.send(FetchProgress {
+                stage,
+                strategy_id: None,
+            });
+        }
+    }
+
+    /// Returns this context limited to the strategies `strategies` allows,
+    /// for applying per-provider strategy settings.
+    #[must_use]
+    pub fn with_strategies(mut self, strategies: StrategySettings) -> Self {
+        self.settings.strategies = strategies;
+        self
+    }
+
     /// Returns true if the given source mode is allowed.
     pub fn allows_source(&self, mode: SourceMode) -> bool {
         self.settings.source_mode == SourceMode::Auto || self.settings.source_mode == mode
@@ -206,8 +358,8 @@ impl std::fmt::Debug for FetchContext {
 /// Builder for constructing a `FetchContext`.
 pub struct FetchContextBuilder {
     keychain: Option<Arc<dyn KeychainApi>>,
-    http: Option<Arc<HttpClient>>,
-    process: Option<Arc<ProcessRunner>>,
+    http: Option<Arc<dyn HttpApi>>,
+    process: Option<Arc<dyn ProcessApi>>,
     browser: Option<Arc<BrowserCookieImporter>>,
     status: Option<Arc<StatusPoller>>,
     settings: FetchSettings,
@@ -232,14 +384,14 @@ impl FetchContextBuilder {
         self
     }
 
-    /// Sets the HTTP client.
-    pub fn http(mut self, http: Arc<HttpClient>) -> Self {
+    /// Sets the HTTP API.
+    pub fn http(mut self, http: Arc<dyn HttpApi>) -> Self {
         self.http = Some(http);
         self
     }
 
-    /// Sets the process runner.
-    pub fn process(mut self, process: Arc<ProcessRunner>) -> Self {
+    /// Sets the process API.
+    pub fn process(mut self, process: Arc<dyn ProcessApi>) -> Self {
         self.process = Some(process);
         self
     }
@@ -274,13 +426,23 @@ impl FetchContextBuilder {
         self
     }
 
+    /// Sends API requests to `url` instead of the providers' own endpoints.
+    pub fn endpoint_override(mut self, url: impl Into<String>) -> Self {
+        self.settings.endpoint_override = Some(url.into());
+        self
+    }
+
     /// Builds the fetch context.
     pub fn build(self) -> FetchContext {
         FetchContext {
             keychain: self
                 .keychain
                 .unwrap_or_else(|| Arc::new(SystemKeychain::new())),
-            http: self.http.unwrap_or_else(|| Arc::new(HttpClient::new())),
+            http: match (self.http, &self.settings.endpoint_override) {
+                (Some(http), Some(url)) => http.allowing_host_of(url),
+                (Some(http), None) => http,
+                (None, _) => Arc::new(HttpClient::new()),
+            },
             process: self
                 .process
                 .unwrap_or_else(|| Arc::new(ProcessRunner::new())),
@@ -288,6 +450,8 @@ impl FetchContextBuilder {
                 .browser
                 .unwrap_or_else(|| Arc::new(BrowserCookieImporter::new())),
             status: self.status.unwrap_or_else(|| Arc::new(StatusPoller::new())),
+            cancel: CancellationToken::new(),
+            progress: None,
             settings: self.settings,
         }
     }
@@ -332,6 +496,139 @@ mod tests {
         assert_eq!(ctx.settings.timeout, Duration::from_secs(60));
     }
 
+    #[test]
+    fn test_with_source_mode() {
+        let ctx = FetchContext::builder()
+            .timeout(Duration::from_secs(60))
+            .build();
+        let web = ctx.with_source_mode(SourceMode::Web);
+
+        assert_eq!(web.settings.source_mode, SourceMode::Web);
+        assert_eq!(web.settings.timeout, Duration::from_secs(60));
+        assert!(Arc::ptr_eq(&web.http, &ctx.http));
+
+        let sandboxed = web.with_sandbox(ProcessSandbox::strict());
+        assert_eq!(sandboxed.settings.source_mode, SourceMode::Web);
+        assert!(sandboxed.process.sandbox().no_network);
+
+        let mut strategies = StrategySettings::default();
+        strategies.set_enabled("claude.pty", false);
+        let limited = sandboxed.with_strategies(strategies);
+        assert!(!limited.settings.strategies.is_enabled("claude.pty"));
+        assert_eq!(limited.settings.source_mode, SourceMode::Web);
+    }
+
+    #[test]
+    fn test_api_base() {
+        let ctx = FetchContext::new();
+        assert_eq!(
+            ctx.api_base("https://api.github.com"),
+            "https://api.github.com"
+        );
+
+        let ctx = FetchContext::builder()
+            .endpoint_override("http://127.0.0.1:8080/")
+            .build();
+        assert_eq!(
+            ctx.api_base("https://api.github.com"),
+            "http://127.0.0.1:8080"
+        );
+        let web = ctx.with_source_mode(SourceMode::Web);
+        assert_eq!(web.api_base("https://cursor.com"), "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_with_base_url() {
+        let gateway = "https://llm-gateway.corp.example/anthropic";
+        let ctx = FetchContext::builder()
+            .http(Arc::new(HttpClient::with_allowed_domains(vec![
+                "anthropic.com".to_string(),
+            ])))
+            .build();
+        assert!(ctx.http.is_domain_allowed(gateway).is_err());
+
+        let ctx = ctx.with_base_url(Some(gateway.to_string()));
+        assert_eq!(ctx.api_base("https://api.anthropic.com"), gateway);
+        assert!(ctx.http.is_domain_allowed(gateway).is_ok());
+        assert!(ctx.http.is_domain_allowed("https://evil.com").is_err());
+
+        let ctx = FetchContext::builder()
+            .endpoint_override("http://127.0.0.1:8080")
+            .build()
+            .with_base_url(Some(gateway.to_string()));
+        assert_eq!(
+            ctx.api_base("https://api.anthropic.com"),
+            "http://127.0.0.1:8080"
+        );
+        let ctx = FetchContext::new().with_base_url(None);
+        assert_eq!(ctx.settings.endpoint_override, None);
+    }
+
+    #[test]
+    fn test_with_request_headers() {
+        let ctx = FetchContext::new();
+        let unchanged = ctx.with_source_mode(SourceMode::Web);
+        let unchanged = unchanged.with_request_headers(RequestHeaders::default());
+        assert!(Arc::ptr_eq(&unchanged.http, &ctx.http));
+
+        let headers = RequestHeaders {
+            user_agent: Some("Mozilla/5.0 Custom".to_string()),
+            ..Default::default()
+        };
+        let custom = FetchContext::new().with_request_headers(headers.clone());
+        assert_eq!(custom.settings.request_headers, headers);
+    }
+
+    /// Answers every command with a canned output, without running it.
+    struct CannedProcess {
+        sandbox: ProcessSandbox,
+    }
+
+    #[async_trait::async_trait]
+    impl ProcessApi for CannedProcess {
+        fn sandbox(&self) -> &ProcessSandbox {
+            &self.sandbox
+        }
+
+        fn with_sandbox(&self, sandbox: ProcessSandbox) -> Arc<dyn ProcessApi> {
+            Arc::new(CannedProcess { sandbox })
+        }
+
+        fn which(&self, cmd: &str) -> Option<std::path::PathBuf> {
+            Some(std::path::PathBuf::from("/canned").join(cmd))
+        }
+
+        async fn run_with_options(
+            &self,
+            cmd: &str,
+            args: &[&str],
+            _env: &[(&str, &str)],
+            _timeout: Option<Duration>,
+        ) -> Result<crate::host::ProcessOutput, crate::error::ProcessError> {
+            Ok(crate::host::ProcessOutput {
+                stdout: format!("{cmd} {}", args.join(" ")),
+                stderr: String::new(),
+                exit_code: 0,
+                duration: Duration::ZERO,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_injected_process() {
+        let ctx = FetchContext::builder()
+            .process(Arc::new(CannedProcess {
+                sandbox: ProcessSandbox::default(),
+            }))
+            .build()
+            .with_sandbox(ProcessSandbox::strict());
+
+        assert!(ctx.process.sandbox().no_network);
+        assert!(ctx.process.command_exists("claude"));
+        let output = ctx.process.run("claude", &["usage"]).await.unwrap();
+        assert_eq!(output.stdout, "claude usage");
+    }
+
     #[test]
     fn test_default_context() {
         let ctx = FetchContext::new();
@@ -3,6 +3,8 @@
 use std::time::Duration;
 use thiserror::Error;
 
+use crate::parse_mode::ParseDiagnostics;
+
 // ============================================================================
 // Main Fetch Error
 // ============================================================================
@@ -25,6 +27,20 @@ pub enum FetchError {
         retry_after: Option<u64>,
     },
 
+    /// The request budget is used up.
+    #[error(
+        "Request budget of {limit}/hour{} used up, next request in {retry_after} seconds",
+        budget_scope(.global)
+    )]
+    BudgetExhausted {
+        /// The limit that was reached.
+        limit: u32,
+        /// Whether it's the limit for all providers together.
+        global: bool,
+        /// Seconds until a request is allowed again.
+        retry_after: u64,
+    },
+
     /// Authentication failed.
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
@@ -37,6 +53,19 @@ pub enum FetchError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// A parser panicked on the response.
+    #[error("Parse failed: {0}")]
+    ParseFailed(String),
+
+    /// A strict parse found problems the lenient parser would skip over.
+    #[error("{parser} response failed strict parsing: {diagnostics}")]
+    StrictParse {
+        /// Parser that rejected the response.
+        parser: String,
+        /// What was wrong with it.
+        diagnostics: ParseDiagnostics,
+    },
+
     /// Core error.
     #[error("Core error: {0}")]
     Core(#[from] exactobar_core::CoreError),
@@ -72,6 +101,16 @@ pub enum FetchError {
     /// Domain not allowed.
     #[error("Domain not allowed: {0}")]
     DomainNotAllowed(String),
+
+    /// The fetch was cancelled.
+    #[error("Fetch cancelled")]
+    Cancelled,
+}
+
+/// Describes which budget a [`FetchError::BudgetExhausted`] is for.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn budget_scope(global: &bool) -> &'static str {
+    if *global { " for all providers" } else { "" }
 }
 
 // ============================================================================
@@ -93,6 +132,10 @@ pub enum HttpError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    /// A header value that can't be sent.
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+
     /// Timeout.
     #[error("Request timed out")]
     Timeout,
@@ -233,6 +276,10 @@ pub enum PtyError {
     /// PTY system unavailable.
     #[error("PTY system unavailable: {0}")]
     SystemUnavailable(String),
+
+    /// The command was cancelled.
+    #[error("Command cancelled")]
+    Cancelled,
 }
 
 // ============================================================================
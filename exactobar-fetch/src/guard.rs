@@ -0,0 +1,67 @@
+//! Containing parser panics.
+//!
+//! Parsers slice strings and divide numbers taken from provider responses.
+//! A response they don't expect must fail that fetch, not panic the
+//! refresh loop: [`catch_parse_panic`] turns a panic into
+//! [`FetchError::ParseFailed`].
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use tracing::error;
+
+use crate::error::FetchError;
+
+/// Runs a parser, returning `FetchError::ParseFailed` if it panics.
+///
+/// The panic hook still runs, so the panic is reported as usual; only the
+/// unwinding stops here.
+pub fn catch_parse_panic<T>(
+    parser: &str,
+    parse: impl FnOnce() -> Result<T, FetchError>,
+) -> Result<T, FetchError> {
+    panic::catch_unwind(AssertUnwindSafe(parse)).unwrap_or_else(|payload| {
+        let message = panic_message(payload.as_ref());
+        error!(parser, panic = %message, "Parser panicked");
+        Err(FetchError::ParseFailed(format!(
+            "{parser} parser panicked: {message}"
+        )))
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_parse_panic() {
+        let ok = catch_parse_panic("test", || Ok(42));
+        assert_eq!(ok.unwrap(), 42);
+
+        let failed = catch_parse_panic("test", || Err::<(), _>(FetchError::Timeout(5)));
+        assert!(matches!(failed, Err(FetchError::Timeout(5))));
+
+        // The reset-description slice the Claude text parser used to do
+        let panicked = catch_parse_panic("test", || {
+            let text = "5% ) (";
+            let (start, end) = (text.find('(').unwrap_or(0), text.find(')').unwrap_or(0));
+            Ok(text[start + 1..end].len())
+        });
+        let Err(FetchError::ParseFailed(message)) = panicked else {
+            panic!("expected ParseFailed, got {panicked:?}");
+        };
+        assert!(message.starts_with("test parser panicked: "));
+    }
+}
@@ -11,6 +11,7 @@
 //! - **Arc**: Same as Chrome (Chromium-based)
 //! - **Brave**: Same as Chrome (Chromium-based)
 //! - **Edge**: Same as Chrome (Chromium-based)
+//! - **Companion**: Cookies pushed by the companion extension (see [`super::companion`])
 //!
 //! ## Security Note
 //!
@@ -24,6 +25,7 @@ use std::fs;
 use std::path::PathBuf;
 use tracing::{debug, instrument, trace, warn};
 
+use super::companion;
 use crate::error::BrowserError;
 
 // ============================================================================
@@ -46,6 +48,8 @@ pub enum Browser {
     Arc,
     /// Brave browser (Chromium-based).
     Brave,
+    /// Cookies pushed by the companion browser extension.
+    Companion,
 }
 
 impl Browser {
@@ -58,6 +62,7 @@ impl Browser {
             Self::Edge => "Edge",
             Self::Arc => "Arc",
             Self::Brave => "Brave",
+            Self::Companion => "Companion Extension",
         }
     }
 
@@ -88,6 +93,7 @@ impl Browser {
             Self::Brave => {
                 home.join("Library/Application Support/BraveSoftware/Brave-Browser/Default/Cookies")
             }
+            Self::Companion => return None,
         };
 
         Some(path)
@@ -108,6 +114,7 @@ impl Browser {
             Self::Edge => home.join(".config/microsoft-edge/Default/Cookies"),
             Self::Arc => return None,
             Self::Brave => home.join(".config/BraveSoftware/Brave-Browser/Default/Cookies"),
+            Self::Companion => return None,
         };
 
         Some(path)
@@ -123,6 +130,20 @@ impl Browser {
         matches!(self, Self::Chrome | Self::Edge | Self::Arc | Self::Brave)
     }
 
+    /// Keychain service holding this browser's cookie encryption key.
+    ///
+    /// Only Chromium-based browsers have one; reading it may trigger a
+    /// system keychain prompt.
+    pub fn safe_storage_service(&self) -> Option<&'static str> {
+        match self {
+            Self::Chrome => Some("Chrome Safe Storage"),
+            Self::Edge => Some("Microsoft Edge Safe Storage"),
+            Self::Arc => Some("Arc Safe Storage"),
+            Self::Brave => Some("Brave Safe Storage"),
+            Self::Safari | Self::Firefox | Self::Companion => None,
+        }
+    }
+
     /// Returns all browser variants.
     pub fn all() -> &'static [Browser] {
         &[
@@ -242,6 +263,14 @@ impl BrowserCookieImporter {
     ) -> Result<Vec<Cookie>, BrowserError> {
         debug!("Importing cookies from browser");
 
+        if browser == Browser::Companion {
+            let cookies = companion::cookies_for_domain(domain);
+            if cookies.is_empty() {
+                return Err(BrowserError::NoCookiesFound(domain.to_string()));
+            }
+            return Ok(cookies);
+        }
+
         let db_path = browser
             .cookie_db_path()
             .ok_or_else(|| BrowserError::BrowserNotFound(browser.display_name().to_string()))?;
@@ -260,6 +289,7 @@ impl BrowserCookieImporter {
             Browser::Chrome | Browser::Edge | Browser::Arc | Browser::Brave => {
                 Self::read_chromium_cookies(&db_path, domain, browser)?
             }
+            Browser::Companion => unreachable!("handled above"),
         };
 
         // Filter out expired cookies
@@ -275,6 +305,9 @@ impl BrowserCookieImporter {
 
     /// Import cookies from the first available browser (in priority order).
     ///
+    /// Cookies pushed by the companion extension are always checked first,
+    /// since they are the freshest and need no database or keychain access.
+    ///
     /// # Errors
     ///
     /// Returns error if no browsers are available or all browsers fail to provide cookies.
@@ -286,6 +319,12 @@ impl BrowserCookieImporter {
     ) -> Result<(Browser, Vec<Cookie>), BrowserError> {
         debug!("Auto-importing cookies");
 
+        let pushed = companion::cookies_for_domain(domain);
+        if !pushed.is_empty() {
+            debug!(count = pushed.len(), "Using companion extension cookies");
+            return Ok((Browser::Companion, pushed));
+        }
+
         let mut last_error = None;
 
         for browser in priority {
@@ -595,17 +634,10 @@ const OUR_BROWSER_KEY_CACHE_SERVICE: &str = "ExactoBar-browser-keys";
 fn get_browser_safe_storage_key(browser: Browser) -> Result<String, BrowserError> {
     use keyring::Entry;
 
-    let (external_service, cache_account) = match browser {
-        Browser::Chrome => ("Chrome Safe Storage", "chrome"),
-        Browser::Edge => ("Microsoft Edge Safe Storage", "edge"),
-        Browser::Arc => ("Arc Safe Storage", "arc"),
-        Browser::Brave => ("Brave Safe Storage", "brave"),
-        _ => {
-            return Err(BrowserError::DecryptionFailed(
-                "Not a Chromium browser".to_string(),
-            ));
-        }
-    };
+    let item = super::keychain_preflight::item_for(browser)
+        .ok_or_else(|| BrowserError::DecryptionFailed("Not a Chromium browser".to_string()))?;
+    let external_service = item.service;
+    let cache_account = super::keychain_preflight::cache_account(browser);
 
     // 1. Check our own keychain cache first (no password prompt!)
     if let Some(cached) =
@@ -615,12 +647,30 @@ fn get_browser_safe_storage_key(browser: Browser) -> Result<String, BrowserError
         return Ok(cached);
     }
 
-    // 2. Not in our cache - read from external keychain (may prompt)
+    // 2. Not in our cache - read from external keychain (may prompt), unless
+    //    the user already denied access and hasn't re-tested it since
+    if super::keychain_preflight::is_denied(browser) {
+        return Err(BrowserError::DecryptionFailed(format!(
+            "Keychain access to {external_service} was denied; re-test it in Settings"
+        )));
+    }
+
     debug!(browser = %browser.display_name(), "Reading Safe Storage key from external keychain");
-    let password =
-        crate::host::keychain::get_password_cached(external_service, "").ok_or_else(|| {
-            BrowserError::DecryptionFailed(format!("No keychain entry for {external_service}"))
-        })?;
+    let password = match super::keychain_preflight::read_key(item) {
+        Ok(password) => password,
+        Err(super::keychain_preflight::KeychainAccess::Denied) => {
+            super::keychain_preflight::mark_denied(browser);
+            return Err(BrowserError::DecryptionFailed(format!(
+                "Keychain access to {external_service} was denied"
+            )));
+        }
+        // No Safe Storage key means the browser has no cookies for us yet
+        Err(_) => {
+            return Err(BrowserError::DecryptionFailed(format!(
+                "No keychain entry for {external_service}"
+            )));
+        }
+    };
 
     // 3. Cache it in our own keychain for next time
     if let Ok(entry) = Entry::new(OUR_BROWSER_KEY_CACHE_SERVICE, cache_account) {
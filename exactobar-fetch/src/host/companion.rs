@@ -0,0 +1,505 @@
+//! Companion browser extension endpoint.
+//!
+//! Some browsers (notably Safari 17+) lock their cookie stores down so that
+//! reading the on-disk database is no longer possible. As an alternative, a
+//! companion browser extension can push fresh cookies to `ExactoBar` over a
+//! small HTTP endpoint bound to `127.0.0.1`.
+//!
+//! ## Protocol
+//!
+//! All requests must carry `Authorization: Bearer <token>`, where the token
+//! is generated by the app and pasted into the extension once.
+//!
+//! - `GET /v1/health` - liveness check, returns `{"ok":true}`
+//! - `POST /v1/cookies` - pushes cookies for a domain:
+//!
+//! ```json
+//! {
+//!   "domain": "claude.ai",
+//!   "cookies": [
+//!     { "name": "sessionKey", "value": "...", "expirationDate": 1767225600 }
+//!   ]
+//! }
+//! ```
+//!
+//! Pushed cookies are kept in memory only and are consulted by
+//! [`BrowserCookieImporter::import_cookies_auto`](super::browser::BrowserCookieImporter::import_cookies_auto)
+//! before any browser database is read.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, instrument, warn};
+
+use super::browser::Cookie;
+
+/// Default port for the companion endpoint.
+pub const DEFAULT_COMPANION_PORT: u16 = 47_321;
+
+/// Maximum accepted request size (headers + body).
+const MAX_REQUEST_BYTES: usize = 256 * 1024;
+
+/// How long a single connection may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// Pushed Cookie Store (Global)
+// ============================================================================
+
+/// Global store of cookies pushed by the companion extension.
+/// Key: normalized domain from the push payload.
+static COMPANION_COOKIES: OnceLock<Mutex<HashMap<String, Vec<Cookie>>>> = OnceLock::new();
+
+/// Get the global pushed cookie store.
+fn get_store() -> &'static Mutex<HashMap<String, Vec<Cookie>>> {
+    COMPANION_COOKIES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Normalizes a domain for use as a store key.
+fn normalize_domain(domain: &str) -> String {
+    domain.trim().trim_start_matches('.').to_lowercase()
+}
+
+/// Replaces the pushed cookies for a domain.
+///
+/// # Panics
+///
+/// Panics if the store mutex is poisoned.
+pub fn store_cookies(domain: &str, cookies: Vec<Cookie>) {
+    let key = normalize_domain(domain);
+    debug!(domain = %key, count = cookies.len(), "Storing companion cookies");
+    get_store().lock().unwrap().insert(key, cookies);
+}
+
+/// Returns unexpired pushed cookies matching the given domain.
+///
+/// # Panics
+///
+/// Panics if the store mutex is poisoned.
+pub fn cookies_for_domain(domain: &str) -> Vec<Cookie> {
+    let store = get_store().lock().unwrap();
+    store
+        .values()
+        .flatten()
+        .filter(|c| !c.is_expired() && c.matches_domain(domain))
+        .cloned()
+        .collect()
+}
+
+/// Removes all pushed cookies.
+///
+/// # Panics
+///
+/// Panics if the store mutex is poisoned.
+pub fn clear_cookies() {
+    get_store().lock().unwrap().clear();
+}
+
+// ============================================================================
+// Token
+// ============================================================================
+
+/// Generates a random 256-bit bearer token, hex encoded.
+///
+/// # Errors
+///
+/// Returns an error if the system random number generator fails.
+pub fn generate_token() -> io::Result<String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| io::Error::other("system RNG unavailable"))?;
+
+    let mut token = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(token, "{b:02x}");
+    }
+    Ok(token)
+}
+
+/// Compares two tokens without short-circuiting on the first mismatch.
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), provided.as_bytes());
+    if a.len() != b.len() || a.is_empty() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ============================================================================
+// Payload Types
+// ============================================================================
+
+/// Body of a `POST /v1/cookies` request.
+#[derive(Debug, Deserialize)]
+pub struct CompanionPayload {
+    /// Domain the cookies were collected for (e.g. `claude.ai`).
+    pub domain: String,
+    /// Cookies in the shape returned by the `chrome.cookies` extension API.
+    pub cookies: Vec<CompanionCookie>,
+}
+
+/// A single cookie as sent by the extension.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionCookie {
+    /// Cookie name.
+    pub name: String,
+    /// Cookie value.
+    pub value: String,
+    /// Cookie domain (defaults to the payload domain).
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Cookie path.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Expiration as Unix seconds.
+    #[serde(default)]
+    pub expiration_date: Option<f64>,
+    /// Whether the cookie requires HTTPS.
+    #[serde(default)]
+    pub secure: bool,
+    /// Whether the cookie is HTTP-only.
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl CompanionPayload {
+    /// Converts the payload into browser cookies.
+    pub fn into_cookies(self) -> Vec<Cookie> {
+        let domain = self.domain;
+        self.cookies
+            .into_iter()
+            .map(|c| Cookie {
+                name: c.name,
+                value: c.value,
+                domain: c.domain.unwrap_or_else(|| domain.clone()),
+                path: c.path.unwrap_or_else(|| "/".to_string()),
+                #[allow(clippy::cast_possible_truncation)]
+                expires: c
+                    .expiration_date
+                    .and_then(|secs| DateTime::<Utc>::from_timestamp(secs as i64, 0)),
+                secure: c.secure,
+                http_only: c.http_only,
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Localhost HTTP endpoint for the companion browser extension.
+pub struct CompanionServer {
+    listener: TcpListener,
+    token: String,
+}
+
+impl CompanionServer {
+    /// Binds the endpoint to `127.0.0.1:<port>`.
+    ///
+    /// Pass port `0` to let the OS pick a free port.
+    pub async fn bind(port: u16, token: impl Into<String>) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        Ok(Self {
+            listener,
+            token: token.into(),
+        })
+    }
+
+    /// Returns the address the endpoint is listening on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the task is dropped.
+    pub async fn serve(self) {
+        if let Ok(addr) = self.local_addr() {
+            info!(%addr, "Companion endpoint listening");
+        }
+
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!(error = %e, "Companion endpoint accept failed");
+                    continue;
+                }
+            };
+
+            let token = self.token.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &token).await {
+                    debug!(%peer, error = %e, "Companion connection error");
+                }
+            });
+        }
+    }
+}
+
+/// A parsed HTTP request.
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+#[instrument(skip_all)]
+async fn handle_connection(mut stream: TcpStream, token: &str) -> io::Result<()> {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(Some(req))) => req,
+        Ok(Ok(None)) => {
+            return write_response(&mut stream, 400, r#"{"error":"bad request"}"#).await;
+        }
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return write_response(&mut stream, 408, r#"{"error":"timeout"}"#).await,
+    };
+
+    let (status, body) = route(&request, token);
+    write_response(&mut stream, status, &body).await
+}
+
+/// Dispatches a request and returns the status code and JSON body.
+fn route(request: &Request, token: &str) -> (u16, String) {
+    let provided = request
+        .headers
+        .get("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+
+    if !tokens_match(token, provided.trim()) {
+        return (401, r#"{"error":"unauthorized"}"#.to_string());
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/health") => (200, r#"{"ok":true}"#.to_string()),
+        ("POST", "/v1/cookies") => {
+            match serde_json::from_slice::<CompanionPayload>(&request.body) {
+                Ok(payload) if !payload.domain.trim().is_empty() => {
+                    let domain = payload.domain.clone();
+                    let cookies = payload.into_cookies();
+                    let count = cookies.len();
+                    store_cookies(&domain, cookies);
+                    (200, format!(r#"{{"ok":true,"stored":{count}}}"#))
+                }
+                Ok(_) => (400, r#"{"error":"missing domain"}"#.to_string()),
+                Err(_) => (400, r#"{"error":"invalid json"}"#.to_string()),
+            }
+        }
+        _ => (404, r#"{"error":"not found"}"#.to_string()),
+    }
+}
+
+/// Reads a single HTTP/1.1 request. Returns `None` for malformed input.
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let Ok(head) = std::str::from_utf8(&buf[..header_end]) else {
+        return Ok(None);
+    };
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    if header_end
+        .checked_add(content_length)
+        .is_none_or(|n| n > MAX_REQUEST_BYTES)
+    {
+        return Ok(None);
+    }
+
+    let mut body = buf[header_end..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        body,
+    }))
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn send(addr: SocketAddr, raw: String) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut out = String::new();
+        stream.read_to_string(&mut out).await.unwrap();
+        out
+    }
+
+    fn post(token: &str, body: &str) -> String {
+        format!(
+            "POST /v1/cookies HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {token}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let a = generate_token().unwrap();
+        let b = generate_token().unwrap();
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abc", "abd"));
+        assert!(!tokens_match("abc", "ab"));
+        assert!(!tokens_match("", ""));
+    }
+
+    #[test]
+    fn test_payload_into_cookies() {
+        let json = r#"{
+            "domain": "cursor.com",
+            "cookies": [
+                { "name": "session", "value": "abc", "httpOnly": true, "expirationDate": 4102444800 },
+                { "name": "other", "value": "xyz", "domain": ".cursor.com", "path": "/api" }
+            ]
+        }"#;
+
+        let payload: CompanionPayload = serde_json::from_str(json).unwrap();
+        let cookies = payload.into_cookies();
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].domain, "cursor.com");
+        assert_eq!(cookies[0].path, "/");
+        assert!(cookies[0].http_only);
+        assert!(cookies[0].expires.is_some());
+        assert_eq!(cookies[1].domain, ".cursor.com");
+        assert_eq!(cookies[1].path, "/api");
+    }
+
+    #[tokio::test]
+    async fn test_server_rejects_bad_token() {
+        let server = CompanionServer::bind(0, "secret").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        let response = send(addr, post("wrong", r#"{"domain":"x.test","cookies":[]}"#)).await;
+        assert!(response.starts_with("HTTP/1.1 401"));
+    }
+
+    #[tokio::test]
+    async fn test_server_stores_pushed_cookies() {
+        let server = CompanionServer::bind(0, "secret").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        let body = r#"{"domain":"companion.test","cookies":[{"name":"sid","value":"42"}]}"#;
+        let response = send(addr, post("secret", body)).await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains(r#""stored":1"#));
+
+        let cookies = cookies_for_domain("companion.test");
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].value, "42");
+    }
+
+    #[tokio::test]
+    async fn test_server_rejects_oversized_content_length() {
+        let server = CompanionServer::bind(0, "secret").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        let response = send(
+            addr,
+            "POST /v1/cookies HTTP/1.1\r\nAuthorization: Bearer secret\r\nContent-Length: 18446744073709551615\r\n\r\n{}".to_string(),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 400"));
+    }
+
+    #[tokio::test]
+    async fn test_server_health_and_not_found() {
+        let server = CompanionServer::bind(0, "secret").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        let health = send(
+            addr,
+            "GET /v1/health HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n".to_string(),
+        )
+        .await;
+        assert!(health.starts_with("HTTP/1.1 200"));
+
+        let missing = send(
+            addr,
+            "GET /nope HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n".to_string(),
+        )
+        .await;
+        assert!(missing.starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,151 @@
+//! Finding provider CLIs.
+//!
+//! An app started from the Dock or a launcher doesn't see the shell's
+//! `PATH`, and CLIs installed through nvm, asdf or Homebrew in a custom
+//! prefix often live outside the default one. [`find_binary`] looks for a
+//! binary in this order:
+//!
+//! 1. the path the user set for it (`binary_path` in a provider's settings)
+//! 2. `PATH`
+//! 3. the extra search paths from settings (`extra_search_paths`)
+//!
+//! The overrides and search paths are process-wide, set with
+//! [`set_binary_search`] when settings load.
+//!
+//! CLIs found outside `PATH` are often scripts run through an interpreter
+//! installed next to them (`#!/usr/bin/env node`), so runners add the
+//! binary's directory to the child's `PATH` ([`path_including`]).
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+use tracing::{debug, warn};
+
+/// The search set by [`set_binary_search`].
+static SEARCH: LazyLock<RwLock<BinarySearch>> =
+    LazyLock::new(|| RwLock::new(BinarySearch::default()));
+
+// ============================================================================
+// Binary Search
+// ============================================================================
+
+/// Where to look for binaries beyond `PATH`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinarySearch {
+    /// Paths to use for binaries, keyed by binary name (`claude`, `gh`).
+    pub overrides: HashMap<String, PathBuf>,
+    /// Directories searched after `PATH`.
+    pub extra_paths: Vec<PathBuf>,
+}
+
+impl BinarySearch {
+    /// Finds `name`: its override if that exists, then on `PATH`, then in
+    /// the extra search paths.
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        if let Some(path) = self.overrides.get(name) {
+            if path.is_file() {
+                return Some(path.clone());
+            }
+            warn!(
+                binary = name,
+                path = %path.display(),
+                "Binary path override doesn't exist, searching PATH"
+            );
+        }
+
+        if let Ok(path) = which::which(name) {
+            return Some(path);
+        }
+
+        if self.extra_paths.is_empty() {
+            return None;
+        }
+        let paths = std::env::join_paths(&self.extra_paths).ok()?;
+        let cwd = std::env::current_dir().ok()?;
+        let found = which::which_in(name, Some(paths), cwd).ok();
+        if let Some(path) = &found {
+            debug!(binary = name, path = %path.display(), "Found binary in extra search paths");
+        }
+        found
+    }
+}
+
+/// Sets the overrides and extra search paths for the whole process.
+pub fn set_binary_search(search: BinarySearch) {
+    *SEARCH.write().unwrap_or_else(PoisonError::into_inner) = search;
+}
+
+/// Finds a binary using the search set by [`set_binary_search`].
+pub fn find_binary(name: &str) -> Option<PathBuf> {
+    SEARCH
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .find(name)
+}
+
+/// Returns `PATH` with `binary`'s directory added at the front, or `None`
+/// if it's already there.
+pub fn path_including(binary: &Path) -> Option<OsString> {
+    let dir = binary.parent().filter(|dir| !dir.as_os_str().is_empty())?;
+    let current = std::env::var_os("PATH").unwrap_or_default();
+    if std::env::split_paths(&current).any(|entry| entry == dir) {
+        return None;
+    }
+    let dirs = std::iter::once(dir.to_path_buf()).chain(std::env::split_paths(&current));
+    std::env::join_paths(dirs).ok()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_executable(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let extra = write_executable(dir.path(), "exactobar-test-cli");
+        let pinned = write_executable(dir.path(), "pinned-sh");
+
+        let mut search = BinarySearch::default();
+        assert_eq!(search.find("exactobar-test-cli"), None);
+
+        search.extra_paths.push(dir.path().to_path_buf());
+        assert_eq!(search.find("exactobar-test-cli"), Some(extra));
+
+        // PATH wins over extra paths, an override over both
+        assert_ne!(search.find("sh"), Some(dir.path().join("sh")));
+        search.overrides.insert("sh".to_string(), pinned.clone());
+        assert_eq!(search.find("sh"), Some(pinned));
+
+        // A missing override falls back to searching
+        search
+            .overrides
+            .insert("sh".to_string(), dir.path().join("missing"));
+        assert!(search.find("sh").is_some());
+    }
+
+    #[test]
+    fn test_path_including() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_including(&dir.path().join("claude")).unwrap();
+        let mut dirs = std::env::split_paths(&path);
+        assert_eq!(dirs.next().as_deref(), Some(dir.path()));
+
+        assert_eq!(path_including(Path::new("claude")), None);
+        let sh = which::which("sh").unwrap();
+        assert_eq!(path_including(&sh), None);
+    }
+}
@@ -4,11 +4,25 @@
 //! - Request/response tracing
 //! - Domain allowlist for security
 //! - Cookie support for web scraping
+//! - Per-provider User-Agent and extra headers ([`RequestHeaders`])
+//! - Conditional requests (`ETag` / `Last-Modified`) answered from a cache
+//!   on `304 Not Modified` ([`HttpClient::get_cached`])
 //! - Convenience methods for common operations
-
-use reqwest::{Client, Response, header, header::HeaderMap};
-use std::time::Duration;
-use tracing::{debug, instrument};
+//!
+//! Fetch contexts hand strategies an [`HttpApi`] rather than the client
+//! itself, so tests and offline modes can answer requests their own way.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, header};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
 use url::Url;
 
 use crate::error::HttpError;
@@ -19,6 +33,167 @@ const DEFAULT_TIMEOUT_SECS: u64 = 30;
 /// User agent string for `ExactoBar`.
 const USER_AGENT: &str = concat!("ExactoBar/", env!("CARGO_PKG_VERSION"));
 
+/// User agent of a current Safari on macOS, sent by web strategies so
+/// dashboards that fingerprint clients see a browser.
+pub const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.1 Safari/605.1.15";
+
+// ============================================================================
+// Request Headers
+// ============================================================================
+
+/// A user's per-provider User-Agent and extra request headers, for
+/// endpoints that fingerprint clients or a gateway that wants its own
+/// headers.
+///
+/// Applied on top of whatever a request already sends, so a configured
+/// header replaces the built-in one of the same name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestHeaders {
+    /// User-Agent sent instead of the built-in one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Extra headers, by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RequestHeaders {
+    /// Returns true if nothing is configured.
+    pub fn is_default(&self) -> bool {
+        self.user_agent.is_none() && self.headers.is_empty()
+    }
+
+    /// The headers as a header map. Names or values that aren't valid in
+    /// HTTP are skipped with a warning.
+    pub fn header_map(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        let user_agent = self
+            .user_agent
+            .as_deref()
+            .map(|agent| ("user-agent", agent));
+        for (name, value) in self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .chain(user_agent)
+        {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                map.insert(name, value);
+            } else {
+                warn!(header = name, "Skipping invalid request header");
+            }
+        }
+        map
+    }
+
+    /// Adds the headers to `headers`, replacing any of the same name.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        for (name, value) in self.header_map() {
+            if let Some(name) = name {
+                headers.insert(name, value);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Response Cache
+// ============================================================================
+
+/// Most response bodies kept for revalidation.
+const RESPONSE_CACHE_CAPACITY: usize = 128;
+
+/// Headers identifying who a request is for. Their values are hashed into
+/// cache keys so two accounts polling the same URL never share a body.
+const IDENTITY_HEADERS: [HeaderName; 2] = [header::AUTHORIZATION, header::COOKIE];
+
+/// The cache shared by every client, since contexts (and their clients)
+/// are built per fetch.
+static RESPONSE_CACHE: LazyLock<ResponseCache> = LazyLock::new(ResponseCache::default);
+
+/// A response body kept with the validators to revalidate it.
+#[derive(Debug, Clone)]
+struct CachedBody {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: String,
+    stored_at: Instant,
+}
+
+/// Bodies of successful responses that carried an `ETag` or
+/// `Last-Modified`, by URL and credentials.
+#[derive(Debug, Default)]
+struct ResponseCache {
+    entries: Mutex<HashMap<String, CachedBody>>,
+}
+
+impl ResponseCache {
+    /// Cache key for a GET of `url` with `headers`: the URL and a digest of
+    /// the credentials, so no secret is kept in the key.
+    fn key(url: &str, headers: &HeaderMap) -> String {
+        let mut identity = ring::digest::Context::new(&ring::digest::SHA256);
+        for name in &IDENTITY_HEADERS {
+            for value in headers.get_all(name) {
+                identity.update(name.as_str().as_bytes());
+                identity.update(value.as_bytes());
+            }
+        }
+        let mut key = format!("{url}#");
+        for byte in &identity.finish().as_ref()[..8] {
+            let _ = write!(key, "{byte:02x}");
+        }
+        key
+    }
+
+    fn get(&self, key: &str) -> Option<CachedBody> {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned()
+    }
+
+    /// Stores a body, dropping the oldest one when full.
+    fn insert(&self, key: String, body: CachedBody) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() >= RESPONSE_CACHE_CAPACITY && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, body);
+    }
+}
+
+/// The status and body of a GET made with [`HttpClient::get_cached`].
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// Response status; `200 OK` when answered from the cache.
+    pub status: StatusCode,
+    /// Response body.
+    pub body: String,
+    /// Whether the server said the cached body is still current.
+    pub not_modified: bool,
+    /// Seconds a `429 Too Many Requests` asked to wait (`Retry-After`).
+    pub retry_after: Option<u64>,
+}
+
+impl CachedResponse {
+    /// Parses the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+}
+
 // ============================================================================
 // HTTP Client
 // ============================================================================
@@ -28,6 +203,7 @@ const USER_AGENT: &str = concat!("ExactoBar/", env!("CARGO_PKG_VERSION"));
 pub struct HttpClient {
     inner: Client,
     allowed_domains: Option<Vec<String>>,
+    request_headers: HeaderMap,
 }
 
 impl HttpClient {
@@ -59,6 +235,7 @@ impl HttpClient {
         Self {
             inner: client,
             allowed_domains: None,
+            request_headers: HeaderMap::new(),
         }
     }
 
@@ -71,8 +248,32 @@ impl HttpClient {
         client
     }
 
+    /// Returns this client sending `headers` with every request.
+    #[must_use]
+    pub fn with_request_headers(mut self, headers: &RequestHeaders) -> Self {
+        self.request_headers = headers.header_map();
+        self
+    }
+
+    /// Adds the host of `url` to the allowlist, if there is one, so a
+    /// configured endpoint (like a corporate gateway) can be reached.
+    pub fn allow_host_of(&mut self, url: &str) {
+        let Some(ref mut allowed) = self.allowed_domains else {
+            return;
+        };
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|parsed| parsed.host_str().map(str::to_string));
+        if let Some(host) = host {
+            if !allowed.contains(&host) {
+                debug!(%host, "Allowing endpoint host");
+                allowed.push(host);
+            }
+        }
+    }
+
     /// Checks if a URL's domain is allowed.
-    fn is_domain_allowed(&self, url: &str) -> Result<(), HttpError> {
+    pub fn is_domain_allowed(&self, url: &str) -> Result<(), HttpError> {
         let Some(ref allowed) = self.allowed_domains else {
             return Ok(()); // No restrictions
         };
@@ -101,7 +302,12 @@ impl HttpClient {
         self.is_domain_allowed(url)?;
         debug!("GET request");
 
-        let response = self.inner.get(url).send().await?;
+        let response = self
+            .inner
+            .get(url)
+            .headers(self.request_headers.clone())
+            .send()
+            .await?;
         debug!(status = %response.status(), "Response received");
         Ok(response)
     }
@@ -116,7 +322,13 @@ impl HttpClient {
         self.is_domain_allowed(url)?;
         debug!("GET request with headers");
 
-        let response = self.inner.get(url).headers(headers).send().await?;
+        let response = self
+            .inner
+            .get(url)
+            .headers(headers)
+            .headers(self.request_headers.clone())
+            .send()
+            .await?;
         debug!(status = %response.status(), "Response received");
         Ok(response)
     }
@@ -131,6 +343,7 @@ impl HttpClient {
             .inner
             .get(url)
             .header(header::AUTHORIZATION, auth_header)
+            .headers(self.request_headers.clone())
             .send()
             .await?;
         debug!(status = %response.status(), "Response received");
@@ -139,7 +352,8 @@ impl HttpClient {
 
     /// Performs a GET request with cookies.
     ///
-    /// Used for web scraping strategies that need browser session cookies.
+    /// Used for web scraping strategies that need browser session cookies,
+    /// so it sends [`BROWSER_USER_AGENT`] unless another is configured.
     #[instrument(skip(self, cookies), fields(url = %url))]
     pub async fn get_with_cookies(&self, url: &str, cookies: &str) -> Result<Response, HttpError> {
         self.is_domain_allowed(url)?;
@@ -148,13 +362,104 @@ impl HttpClient {
         let response = self
             .inner
             .get(url)
+            .header(header::USER_AGENT, BROWSER_USER_AGENT)
             .header(header::COOKIE, cookies)
+            .headers(self.request_headers.clone())
             .send()
             .await?;
         debug!(status = %response.status(), "Response received");
         Ok(response)
     }
 
+    /// Performs a GET request, revalidating the body cached for this URL and
+    /// credentials with `If-None-Match` / `If-Modified-Since`. A
+    /// `304 Not Modified` is answered from the cache, so frequent polls of
+    /// endpoints with validators don't transfer the body again.
+    ///
+    /// Only successful responses with an `ETag` or `Last-Modified` are
+    /// cached.
+    #[instrument(skip(self, headers), fields(url = %url))]
+    pub async fn get_cached(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<CachedResponse, HttpError> {
+        self.is_domain_allowed(url)?;
+        let key = ResponseCache::key(url, &headers);
+        let cached = RESPONSE_CACHE.get(&key);
+        debug!(revalidating = cached.is_some(), "Conditional GET request");
+
+        let mut request = self
+            .inner
+            .get(url)
+            .headers(headers)
+            .headers(self.request_headers.clone());
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(header::IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        debug!(%status, "Response received");
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                return Ok(CachedResponse {
+                    status: StatusCode::OK,
+                    body: cached.body,
+                    not_modified: true,
+                    retry_after: None,
+                });
+            }
+        }
+
+        let retry_after = response.retry_after_secs();
+        let etag = response.headers().get(header::ETAG).cloned();
+        let last_modified = response.headers().get(header::LAST_MODIFIED).cloned();
+        let body = response.text().await?;
+        if status.is_success() && (etag.is_some() || last_modified.is_some()) {
+            RESPONSE_CACHE.insert(
+                key,
+                CachedBody {
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                    stored_at: Instant::now(),
+                },
+            );
+        }
+        Ok(CachedResponse {
+            status,
+            body,
+            not_modified: false,
+            retry_after,
+        })
+    }
+
+    /// Performs a conditional GET request with cookies (see
+    /// [`get_cached`](Self::get_cached)), sending [`BROWSER_USER_AGENT`]
+    /// unless another is configured.
+    pub async fn get_cached_with_cookies(
+        &self,
+        url: &str,
+        cookies: &str,
+    ) -> Result<CachedResponse, HttpError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_static(BROWSER_USER_AGENT),
+        );
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_str(cookies).map_err(|e| HttpError::InvalidHeader(e.to_string()))?,
+        );
+        self.get_cached(url, headers).await
+    }
+
     /// Performs a POST request with JSON body.
     #[instrument(skip(self, body), fields(url = %url))]
     pub async fn post_json<T: serde::Serialize + ?Sized>(
@@ -165,7 +470,36 @@ impl HttpClient {
         self.is_domain_allowed(url)?;
         debug!("POST request with JSON");
 
-        let response = self.inner.post(url).json(body).send().await?;
+        let response = self
+            .inner
+            .post(url)
+            .json(body)
+            .headers(self.request_headers.clone())
+            .send()
+            .await?;
+        debug!(status = %response.status(), "Response received");
+        Ok(response)
+    }
+
+    /// Performs a POST request with a JSON body and an authorization header.
+    #[instrument(skip(self, body, auth_header), fields(url = %url))]
+    pub async fn post_json_with_auth<T: serde::Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &T,
+        auth_header: &str,
+    ) -> Result<Response, HttpError> {
+        self.is_domain_allowed(url)?;
+        debug!("POST request with JSON and auth");
+
+        let response = self
+            .inner
+            .post(url)
+            .header(header::AUTHORIZATION, auth_header)
+            .json(body)
+            .headers(self.request_headers.clone())
+            .send()
+            .await?;
         debug!(status = %response.status(), "Response received");
         Ok(response)
     }
@@ -180,15 +514,32 @@ impl HttpClient {
         self.is_domain_allowed(url)?;
         debug!("POST request with form data");
 
-        let response = self.inner.post(url).form(form).send().await?;
+        let response = self
+            .inner
+            .post(url)
+            .form(form)
+            .headers(self.request_headers.clone())
+            .send()
+            .await?;
         debug!(status = %response.status(), "Response received");
         Ok(response)
     }
 
     /// Returns the inner reqwest client for advanced operations.
+    ///
+    /// Requests made with it don't send the configured request headers;
+    /// use [`request`](Self::request) for that.
     pub fn inner(&self) -> &Client {
         &self.inner
     }
+
+    /// Starts a request with the inner client that sends the configured
+    /// request headers.
+    pub fn request(&self, method: reqwest::Method, url: &str) -> RequestBuilder {
+        self.inner
+            .request(method, url)
+            .headers(self.request_headers.clone())
+    }
 }
 
 impl Default for HttpClient {
@@ -197,6 +548,121 @@ impl Default for HttpClient {
     }
 }
 
+// ============================================================================
+// HTTP API Trait
+// ============================================================================
+
+/// API for the HTTP requests strategies make.
+///
+/// [`HttpClient`] sends them over the network; tests and offline modes can
+/// provide their own through
+/// [`FetchContextBuilder::http`](crate::FetchContextBuilder::http).
+#[async_trait]
+pub trait HttpApi: Send + Sync {
+    /// Checks whether requests to a URL's domain are allowed.
+    fn is_domain_allowed(&self, url: &str) -> Result<(), HttpError>;
+
+    /// Returns an API sending `headers` with every request, for applying
+    /// per-provider headers.
+    fn with_request_headers(&self, headers: &RequestHeaders) -> Arc<dyn HttpApi>;
+
+    /// Returns an API that also allows requests to the host of `url`, for
+    /// applying per-provider endpoints.
+    fn allowing_host_of(&self, url: &str) -> Arc<dyn HttpApi>;
+
+    /// Starts a request that sends the configured request headers.
+    fn request(&self, method: reqwest::Method, url: &str) -> RequestBuilder;
+
+    /// Performs a GET request.
+    async fn get(&self, url: &str) -> Result<Response, HttpError>;
+
+    /// Performs a GET request with custom headers.
+    async fn get_with_headers(&self, url: &str, headers: HeaderMap) -> Result<Response, HttpError>;
+
+    /// Performs a GET request with an authorization header.
+    async fn get_with_auth(&self, url: &str, auth_header: &str) -> Result<Response, HttpError>;
+
+    /// Performs a GET request with browser cookies.
+    async fn get_with_cookies(&self, url: &str, cookies: &str) -> Result<Response, HttpError>;
+
+    /// Performs a GET request revalidated against the response cache (see
+    /// [`HttpClient::get_cached`]).
+    async fn get_cached(&self, url: &str, headers: HeaderMap) -> Result<CachedResponse, HttpError>;
+
+    /// Performs a revalidated GET request with browser cookies.
+    async fn get_cached_with_cookies(
+        &self,
+        url: &str,
+        cookies: &str,
+    ) -> Result<CachedResponse, HttpError>;
+
+    /// Performs a POST request with a JSON body and an authorization header.
+    async fn post_json_with_auth(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        auth_header: &str,
+    ) -> Result<Response, HttpError>;
+}
+
+#[async_trait]
+impl HttpApi for HttpClient {
+    fn is_domain_allowed(&self, url: &str) -> Result<(), HttpError> {
+        HttpClient::is_domain_allowed(self, url)
+    }
+
+    fn with_request_headers(&self, headers: &RequestHeaders) -> Arc<dyn HttpApi> {
+        Arc::new(self.clone().with_request_headers(headers))
+    }
+
+    fn allowing_host_of(&self, url: &str) -> Arc<dyn HttpApi> {
+        let mut client = self.clone();
+        client.allow_host_of(url);
+        Arc::new(client)
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> RequestBuilder {
+        HttpClient::request(self, method, url)
+    }
+
+    async fn get(&self, url: &str) -> Result<Response, HttpError> {
+        HttpClient::get(self, url).await
+    }
+
+    async fn get_with_headers(&self, url: &str, headers: HeaderMap) -> Result<Response, HttpError> {
+        HttpClient::get_with_headers(self, url, headers).await
+    }
+
+    async fn get_with_auth(&self, url: &str, auth_header: &str) -> Result<Response, HttpError> {
+        HttpClient::get_with_auth(self, url, auth_header).await
+    }
+
+    async fn get_with_cookies(&self, url: &str, cookies: &str) -> Result<Response, HttpError> {
+        HttpClient::get_with_cookies(self, url, cookies).await
+    }
+
+    async fn get_cached(&self, url: &str, headers: HeaderMap) -> Result<CachedResponse, HttpError> {
+        HttpClient::get_cached(self, url, headers).await
+    }
+
+    async fn get_cached_with_cookies(
+        &self,
+        url: &str,
+        cookies: &str,
+    ) -> Result<CachedResponse, HttpError> {
+        HttpClient::get_cached_with_cookies(self, url, cookies).await
+    }
+
+    async fn post_json_with_auth(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        auth_header: &str,
+    ) -> Result<Response, HttpError> {
+        HttpClient::post_json_with_auth(self, url, body, auth_header).await
+    }
+}
+
 // ============================================================================
 // Response Extensions
 // ============================================================================
@@ -206,7 +672,8 @@ pub trait ResponseExt {
     /// Check if the response indicates rate limiting.
     fn is_rate_limited(&self) -> bool;
 
-    /// Get the Retry-After header value in seconds.
+    /// Get the Retry-After header value in seconds, whether it's given as
+    /// seconds or as an HTTP date.
     fn retry_after_secs(&self) -> Option<u64>;
 }
 
@@ -219,10 +686,21 @@ impl ResponseExt for Response {
         self.headers()
             .get(header::RETRY_AFTER)
             .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.parse().ok())
+            .and_then(|v| parse_retry_after(v, Utc::now()))
     }
 }
 
+/// Parses a `Retry-After` value, either seconds or an HTTP date, into the
+/// seconds left to wait from `now`.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(secs);
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    u64::try_from((at.with_timezone(&Utc) - now).num_seconds().max(0)).ok()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -269,6 +747,91 @@ mod tests {
         assert!(client.is_domain_allowed("https://any.domain.com").is_ok());
     }
 
+    #[test]
+    fn test_request_headers() {
+        let mut configured = RequestHeaders {
+            user_agent: Some("Mozilla/5.0 Custom".to_string()),
+            ..Default::default()
+        };
+        configured
+            .headers
+            .insert("X-Gateway-Team".to_string(), "ml".to_string());
+        configured
+            .headers
+            .insert("Bad Header".to_string(), "x".to_string());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::USER_AGENT, HeaderValue::from_static(USER_AGENT));
+        configured.apply(&mut headers);
+
+        assert_eq!(headers[header::USER_AGENT], "Mozilla/5.0 Custom");
+        assert_eq!(headers["x-gateway-team"], "ml");
+        assert_eq!(headers.len(), 2);
+        assert!(RequestHeaders::default().is_default());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2026-03-02T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("120", now), Some(120));
+        assert_eq!(
+            parse_retry_after("Mon, 02 Mar 2026 10:05:00 GMT", now),
+            Some(300)
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 02 Mar 2026 09:00:00 GMT", now),
+            Some(0)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_response_cache_keys() {
+        let url = "https://status.openai.com/api/v2/status.json";
+        let anonymous = ResponseCache::key(url, &HeaderMap::new());
+
+        let mut alice = HeaderMap::new();
+        alice.insert(header::COOKIE, HeaderValue::from_static("session=alice"));
+        let mut bob = HeaderMap::new();
+        bob.insert(header::COOKIE, HeaderValue::from_static("session=bob"));
+        let alice_key = ResponseCache::key(url, &alice);
+
+        assert_ne!(anonymous, alice_key);
+        assert_ne!(alice_key, ResponseCache::key(url, &bob));
+        assert_eq!(alice_key, ResponseCache::key(url, &alice));
+        assert!(!alice_key.contains("alice"));
+
+        // Other headers don't change who the request is for
+        alice.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert_eq!(alice_key, ResponseCache::key(url, &alice));
+    }
+
+    #[test]
+    fn test_response_cache_evicts_oldest() {
+        let cache = ResponseCache::default();
+        let now = Instant::now();
+        let body = |text: &str, stored_at: Instant| CachedBody {
+            etag: Some(HeaderValue::from_static("\"v1\"")),
+            last_modified: None,
+            body: text.to_string(),
+            stored_at,
+        };
+        cache.insert(
+            "key0".to_string(),
+            body("oldest", now.checked_sub(Duration::from_secs(60)).unwrap()),
+        );
+        for i in 1..RESPONSE_CACHE_CAPACITY {
+            cache.insert(format!("key{i}"), body("old", now));
+        }
+        cache.insert("newest".to_string(), body("new", now));
+
+        assert!(cache.get("key0").is_none());
+        assert_eq!(cache.get("newest").unwrap().body, "new");
+        assert_eq!(cache.entries.lock().unwrap().len(), RESPONSE_CACHE_CAPACITY);
+    }
+
     #[test]
     fn test_invalid_url() {
         let client = HttpClient::with_allowed_domains(vec!["example.com".to_string()]);
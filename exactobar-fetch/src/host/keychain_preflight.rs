@@ -0,0 +1,254 @@
+//! Keychain access pre-flight for Chromium cookie decryption.
+//!
+//! Chromium-based browsers encrypt their cookies with a key stored in the
+//! system keychain ("Chrome Safe Storage" and friends). Reading that key
+//! makes macOS show a password prompt, which is alarming when it appears
+//! out of nowhere during a background refresh.
+//!
+//! This module lets the UI explain up front which keychain items will be
+//! requested, test access per browser on demand, and remember denials so
+//! background refreshes stop re-prompting until the user re-tests.
+
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::browser::Browser;
+
+// ============================================================================
+// Keychain Items
+// ============================================================================
+
+/// A keychain item that cookie import may request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeychainItem {
+    /// Browser the item belongs to.
+    pub browser: Browser,
+    /// Keychain service name (e.g. "Chrome Safe Storage").
+    pub service: &'static str,
+}
+
+/// Returns the keychain item for a browser, if it needs one.
+pub fn item_for(browser: Browser) -> Option<KeychainItem> {
+    browser
+        .safe_storage_service()
+        .map(|service| KeychainItem { browser, service })
+}
+
+/// Returns the keychain items for all installed browsers that need one.
+pub fn required_items() -> Vec<KeychainItem> {
+    Browser::all()
+        .iter()
+        .filter(|b| b.is_installed())
+        .filter_map(|b| item_for(*b))
+        .collect()
+}
+
+/// Account name under which we cache a browser's Safe Storage key.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn cache_account(browser: Browser) -> &'static str {
+    match browser {
+        Browser::Chrome => "chrome",
+        Browser::Edge => "edge",
+        Browser::Arc => "arc",
+        Browser::Brave => "brave",
+        Browser::Safari | Browser::Firefox | Browser::Companion => "",
+    }
+}
+
+// ============================================================================
+// Access Test
+// ============================================================================
+
+/// Result of testing keychain access for a browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeychainAccess {
+    /// The key was read successfully.
+    Granted,
+    /// The user denied the prompt (or the keychain refused access).
+    Denied,
+    /// The browser has no Safe Storage item in the keychain.
+    NotFound,
+    /// Not applicable on this platform or for this browser.
+    Unsupported,
+}
+
+impl KeychainAccess {
+    /// Returns a short human-readable description.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Granted => "Access granted",
+            Self::Denied => "Access denied - won't prompt again",
+            Self::NotFound => "No keychain item found",
+            Self::Unsupported => "Not needed on this system",
+        }
+    }
+}
+
+/// Tests keychain access for a browser, prompting the user if needed.
+///
+/// This bypasses the denial cache: a refused prompt records a denial, and
+/// any other result clears a previous one.
+pub fn test_access(browser: Browser) -> KeychainAccess {
+    let Some(item) = item_for(browser) else {
+        return KeychainAccess::Unsupported;
+    };
+
+    let access = read_item(item);
+    debug!(browser = %browser.display_name(), ?access, "Keychain pre-flight result");
+
+    match access {
+        KeychainAccess::Granted | KeychainAccess::NotFound => clear_denied(browser),
+        KeychainAccess::Denied => mark_denied(browser),
+        KeychainAccess::Unsupported => {}
+    }
+
+    access
+}
+
+/// Reads a browser's Safe Storage key from the keychain.
+///
+/// Only a refused or cancelled prompt is [`KeychainAccess::Denied`]. A
+/// missing or empty item is [`KeychainAccess::NotFound`]: the browser has
+/// no key yet, so there are no cookies to decrypt.
+#[cfg(target_os = "macos")]
+pub(crate) fn read_key(item: KeychainItem) -> Result<String, KeychainAccess> {
+    match keyring::Entry::new(item.service, "").and_then(|e| e.get_password()) {
+        Ok(password) if !password.is_empty() => Ok(password),
+        Err(keyring::Error::PlatformFailure(e) | keyring::Error::NoStorageAccess(e)) => {
+            debug!(service = item.service, error = %e, "Keychain access refused");
+            Err(KeychainAccess::Denied)
+        }
+        Ok(_) | Err(_) => Err(KeychainAccess::NotFound),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_item(item: KeychainItem) -> KeychainAccess {
+    match read_key(item) {
+        Ok(_) => KeychainAccess::Granted,
+        Err(access) => access,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_item(_item: KeychainItem) -> KeychainAccess {
+    // Chromium cookie decryption is macOS-only, so nothing is ever requested
+    KeychainAccess::Unsupported
+}
+
+// ============================================================================
+// Denial Cache (Global)
+// ============================================================================
+
+/// Browsers whose keychain access was denied.
+static DENIED: OnceLock<Mutex<HashSet<Browser>>> = OnceLock::new();
+
+/// Get the global denial set.
+fn get_denied() -> &'static Mutex<HashSet<Browser>> {
+    DENIED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Returns true if keychain access for this browser was denied.
+///
+/// # Panics
+///
+/// Panics if the denial mutex is poisoned.
+pub fn is_denied(browser: Browser) -> bool {
+    get_denied().lock().unwrap().contains(&browser)
+}
+
+/// Records that keychain access for this browser was denied.
+///
+/// # Panics
+///
+/// Panics if the denial mutex is poisoned.
+pub fn mark_denied(browser: Browser) {
+    get_denied().lock().unwrap().insert(browser);
+}
+
+/// Forgets a previous denial for this browser.
+///
+/// # Panics
+///
+/// Panics if the denial mutex is poisoned.
+pub fn clear_denied(browser: Browser) {
+    get_denied().lock().unwrap().remove(&browser);
+}
+
+/// Returns all denied browsers, in [`Browser::all`] order.
+///
+/// # Panics
+///
+/// Panics if the denial mutex is poisoned.
+pub fn denied_browsers() -> Vec<Browser> {
+    let denied = get_denied().lock().unwrap();
+    Browser::all()
+        .iter()
+        .filter(|b| denied.contains(b))
+        .copied()
+        .collect()
+}
+
+/// Replaces the denial set (e.g. from persisted settings at startup).
+///
+/// # Panics
+///
+/// Panics if the denial mutex is poisoned.
+pub fn set_denied_browsers(browsers: impl IntoIterator<Item = Browser>) {
+    let mut denied = get_denied().lock().unwrap();
+    denied.clear();
+    denied.extend(browsers);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_for_chromium_only() {
+        assert_eq!(
+            item_for(Browser::Chrome).map(|i| i.service),
+            Some("Chrome Safe Storage")
+        );
+        assert!(item_for(Browser::Brave).is_some());
+        assert!(item_for(Browser::Safari).is_none());
+        assert!(item_for(Browser::Firefox).is_none());
+        assert!(item_for(Browser::Companion).is_none());
+    }
+
+    #[test]
+    fn test_required_items_are_chromium() {
+        for item in required_items() {
+            assert!(item.browser.uses_encrypted_cookies());
+        }
+    }
+
+    #[test]
+    fn test_unsupported_browser_access() {
+        assert_eq!(test_access(Browser::Firefox), KeychainAccess::Unsupported);
+        assert!(!is_denied(Browser::Firefox));
+    }
+
+    #[test]
+    fn test_denial_cache() {
+        set_denied_browsers([Browser::Arc]);
+        assert!(is_denied(Browser::Arc));
+
+        mark_denied(Browser::Edge);
+        assert_eq!(denied_browsers(), vec![Browser::Edge, Browser::Arc]);
+
+        clear_denied(Browser::Arc);
+        assert!(!is_denied(Browser::Arc));
+
+        set_denied_browsers([]);
+        assert!(denied_browsers().is_empty());
+    }
+}
@@ -2,24 +2,36 @@
 //!
 //! This module provides abstractions for interacting with external systems:
 //!
+//! - [`discovery`] - Finding provider CLIs outside `PATH`
 //! - [`keychain`] - Secure credential storage (system keychain)
-//! - [`http`] - HTTP client with tracing and domain allowlist
+//! - [`keychain_preflight`] - Keychain access pre-flight for browser cookie decryption
+//! - [`http`] - HTTP client with tracing, domain allowlist, and custom headers
 //! - [`process`] - Subprocess execution for CLI tools
 //! - [`pty`] - PTY-based execution for interactive CLI tools
+//! - [`probe_cache`] - Debounced, shared PTY probe output
 //! - [`status`] - Status page polling (statuspage.io)
 //! - [`browser`] - Browser cookie import
+//! - [`companion`] - Localhost endpoint for the companion browser extension
 
 pub mod browser;
+pub mod companion;
+pub mod discovery;
 pub mod http;
 pub mod keychain;
+pub mod keychain_preflight;
+pub mod probe_cache;
 pub mod process;
 pub mod pty;
 pub mod status;
 
 // Re-export key types
 pub use browser::{Browser, BrowserCookieImporter, Cookie};
-pub use http::HttpClient;
+pub use companion::CompanionServer;
+pub use discovery::BinarySearch;
+pub use http::{CachedResponse, HttpApi, HttpClient, RequestHeaders};
 pub use keychain::{KeychainApi, SystemKeychain};
-pub use process::{ProcessOutput, ProcessRunner};
-pub use pty::{PtyOptions, PtyResult, PtyRunner};
+pub use keychain_preflight::{KeychainAccess, KeychainItem};
+pub use probe_cache::ProbeCache;
+pub use process::{ProcessApi, ProcessOutput, ProcessRunner, ProcessSandbox};
+pub use pty::{LineParser, PtyOptions, PtyResult, PtyRunner};
 pub use status::StatusPoller;
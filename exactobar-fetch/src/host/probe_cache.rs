@@ -0,0 +1,206 @@
+//! Shared cache for PTY probe output.
+//!
+//! PTY probes (`claude` `/usage`, `codex` `/status`) start a whole
+//! interactive CLI session and take several seconds. [`ProbeCache`] runs a
+//! probe at most once per [`MIN_PROBE_INTERVAL`] and hands the output to
+//! everyone who asks in between:
+//!
+//! - Callers in one process that ask for the same probe at the same time
+//!   wait for a single run.
+//! - With a cache directory set ([`set_probe_cache_dir`]), the output is
+//!   also written to `<dir>/probes/<key>.json`, so the app and `exactobar`
+//!   CLI invocations share it. A `<key>.lock` file marks a run in
+//!   progress; another process waits for its output instead of starting a
+//!   second session.
+//!
+//! Only runs that finished without timing out are cached.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use super::pty::PtyResult;
+use crate::error::PtyError;
+use crate::shared_cache::SharedCache;
+
+/// Minimum time between two runs of the same probe.
+pub const MIN_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a lock file holds off other processes. Longer than any probe's
+/// timeout, so a lock left by a crashed process is eventually ignored.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Directory under the cache directory holding probe output.
+const PROBES_DIR: &str = "probes";
+
+/// Cache directory set by [`set_probe_cache_dir`].
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The cache shared by all probes.
+static SHARED: LazyLock<ProbeCache> = LazyLock::new(|| ProbeCache::new(MIN_PROBE_INTERVAL));
+
+/// Shares probe output with other processes through `dir` from now on.
+pub fn set_probe_cache_dir(dir: impl Into<PathBuf>) {
+    if let Ok(mut current) = CACHE_DIR.write() {
+        *current = Some(dir.into());
+    }
+}
+
+fn probe_cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.read().ok().and_then(|dir| dir.clone())
+}
+
+// ============================================================================
+// Probe Cache
+// ============================================================================
+
+/// Debounces and coalesces PTY probe runs.
+pub struct ProbeCache {
+    min_interval: Duration,
+    /// Directory overriding the one set by [`set_probe_cache_dir`].
+    dir: Option<PathBuf>,
+    cache: SharedCache<PtyResult>,
+}
+
+impl ProbeCache {
+    /// Creates a cache that runs each probe at most once per `min_interval`.
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            dir: None,
+            cache: SharedCache::new(LOCK_TIMEOUT),
+        }
+    }
+
+    /// Shares output through `dir` instead of the directory set by
+    /// [`set_probe_cache_dir`].
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Returns the cache shared by all probes.
+    pub fn shared() -> &'static Self {
+        &SHARED
+    }
+
+    /// Returns the output of probe `key`, running `probe` only if no
+    /// process ran it in the last `min_interval`.
+    pub async fn run<F, Fut>(&self, key: &str, probe: F) -> Result<PtyResult, PtyError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<PtyResult, PtyError>>,
+    {
+        let dir = self
+            .dir
+            .clone()
+            .or_else(probe_cache_dir)
+            .map(|dir| dir.join(PROBES_DIR));
+        let min_interval = self.min_interval;
+        self.cache
+            .run(
+                key,
+                dir.as_deref(),
+                min_interval,
+                probe,
+                |result| {
+                    result
+                        .as_ref()
+                        .ok()
+                        .filter(|r| !min_interval.is_zero() && !r.any_timeout())
+                        .cloned()
+                },
+                Ok,
+            )
+            .await
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_cache::lock_path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn pty_result(output: &str, timed_out: bool) -> PtyResult {
+        PtyResult {
+            output: output.to_string(),
+            exit_code: Some(0),
+            duration: Duration::from_millis(20),
+            stopped_on_pattern: None,
+            stopped_by_parser: false,
+            timed_out,
+            idle_timed_out: false,
+        }
+    }
+
+    async fn probe(runs: &AtomicUsize, timed_out: bool) -> Result<PtyResult, PtyError> {
+        runs.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(pty_result("5h limit: 72% left", timed_out))
+    }
+
+    #[tokio::test]
+    async fn test_debounces_and_coalesces() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ProbeCache::new(MIN_PROBE_INTERVAL).with_dir(dir.path());
+        let runs = AtomicUsize::new(0);
+
+        let (a, b) = tokio::join!(
+            cache.run("codex-status", || probe(&runs, false)),
+            cache.run("codex-status", || probe(&runs, false)),
+        );
+        assert_eq!(a.unwrap().output, "5h limit: 72% left");
+        assert_eq!(b.unwrap().output, "5h limit: 72% left");
+        cache
+            .run("codex-status", || probe(&runs, false))
+            .await
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Other probes are separate
+        cache
+            .run("claude-usage", || probe(&runs, false))
+            .await
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(!lock_path(&dir.path().join(PROBES_DIR), "codex-status").exists());
+    }
+
+    #[tokio::test]
+    async fn test_shares_output_between_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = ProbeCache::new(MIN_PROBE_INTERVAL).with_dir(dir.path());
+        let cli = ProbeCache::new(MIN_PROBE_INTERVAL).with_dir(dir.path());
+        let runs = AtomicUsize::new(0);
+
+        app.run("claude-usage", || probe(&runs, false))
+            .await
+            .unwrap();
+        let result = cli
+            .run("claude-usage", || probe(&runs, false))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "5h limit: 72% left");
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_and_zero_interval_are_not_cached() {
+        let runs = AtomicUsize::new(0);
+        let cache = ProbeCache::new(MIN_PROBE_INTERVAL);
+        cache.run("a", || probe(&runs, true)).await.unwrap();
+        cache.run("a", || probe(&runs, true)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let uncached = ProbeCache::new(Duration::ZERO);
+        uncached.run("a", || probe(&runs, false)).await.unwrap();
+        uncached.run("a", || probe(&runs, false)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+}
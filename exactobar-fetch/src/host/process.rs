@@ -2,18 +2,161 @@
 //!
 //! This module provides utilities for running external commands,
 //! particularly CLI tools like `claude`, `gh`, etc.
+//!
+//! Commands inherit the user's full environment by default. A
+//! [`ProcessSandbox`] narrows that down per provider: a minimal
+//! environment, a restricted `PATH`, and no network where the platform
+//! supports it.
+//!
+//! Fetch contexts hand strategies a [`ProcessApi`] rather than the runner
+//! itself, so tests can answer commands without running anything.
 
-use std::path::PathBuf;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tracing::{debug, instrument, warn};
 
+use super::discovery;
 use crate::error::ProcessError;
 
 /// Default command timeout.
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// Environment variables a sandboxed command keeps.
+///
+/// Enough for a CLI to find its config and credentials under the home
+/// directory; API keys, proxies and tokens in the environment are dropped.
+pub const SANDBOX_ENV_ALLOWLIST: &[&str] = &[
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "PATH",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "TERM",
+    "TMPDIR",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_CACHE_HOME",
+    "XDG_RUNTIME_DIR",
+    // Needed by nearly every Windows program
+    "SYSTEMROOT",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+// ============================================================================
+// Process Sandbox
+// ============================================================================
+
+/// Restrictions on the commands a [`ProcessRunner`] starts.
+///
+/// The default restricts nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessSandbox {
+    /// Start commands with only [`SANDBOX_ENV_ALLOWLIST`] from the
+    /// environment.
+    pub clean_env: bool,
+    /// Directories searched for commands, replacing `PATH` both for lookup
+    /// and in the command's environment. Binary overrides and extra search
+    /// paths from settings don't apply. Empty keeps `PATH`.
+    pub path: Vec<PathBuf>,
+    /// Cut commands off from the network: `unshare` on Linux,
+    /// `sandbox-exec` on macOS. Elsewhere, or when the tool is missing, the
+    /// command runs with a warning.
+    pub no_network: bool,
+}
+
+impl ProcessSandbox {
+    /// Creates a sandbox with a minimal environment and no network.
+    pub fn strict() -> Self {
+        Self {
+            clean_env: true,
+            path: Vec::new(),
+            no_network: true,
+        }
+    }
+
+    /// Returns true if the sandbox restricts anything.
+    pub fn is_enabled(&self) -> bool {
+        self.clean_env || !self.path.is_empty() || self.no_network
+    }
+
+    /// Returns the restricted `PATH`, if there is one.
+    fn search_path(&self) -> Option<OsString> {
+        if self.path.is_empty() {
+            return None;
+        }
+        std::env::join_paths(&self.path)
+            .map_err(|e| warn!(error = %e, "Invalid sandbox PATH entry"))
+            .ok()
+    }
+
+    /// Builds the command for `program`, wrapped for network isolation if
+    /// requested, with the sandbox's environment.
+    fn command(&self, program: &Path, args: &[&str]) -> Command {
+        let mut command = if let Some((wrapper, wrapper_args)) =
+            self.no_network.then(network_wrapper).flatten()
+        {
+            let mut command = Command::new(wrapper);
+            command.args(wrapper_args).arg(program);
+            command
+        } else {
+            if self.no_network {
+                warn!(
+                    program = %program.display(),
+                    "Network isolation isn't available here, running with network"
+                );
+            }
+            Command::new(program)
+        };
+        command.args(args);
+
+        if self.clean_env {
+            command.env_clear();
+            for key in SANDBOX_ENV_ALLOWLIST {
+                if let Some(value) = std::env::var_os(key) {
+                    command.env(key, value);
+                }
+            }
+        }
+        if let Some(path) = self.search_path() {
+            command.env("PATH", path);
+        } else if let Some(path) = discovery::path_including(program) {
+            // Let scripts find an interpreter installed next to them
+            command.env("PATH", path);
+        }
+        command
+    }
+}
+
+/// Returns the program and arguments that run a command without network
+/// access on this platform, if they're installed.
+fn network_wrapper() -> Option<(PathBuf, Vec<&'static str>)> {
+    if cfg!(target_os = "linux") {
+        // A new network namespace has only a loopback interface; mapping
+        // the user to root inside a user namespace makes that unprivileged
+        let unshare = which::which("unshare").ok()?;
+        Some((unshare, vec!["--net", "--map-root-user", "--"]))
+    } else if cfg!(target_os = "macos") {
+        let sandbox_exec = which::which("sandbox-exec").ok()?;
+        Some((
+            sandbox_exec,
+            vec!["-p", "(version 1)(allow default)(deny network*)"],
+        ))
+    } else {
+        None
+    }
+}
+
 // ============================================================================
 // Process Output
 // ============================================================================
@@ -56,12 +199,24 @@ impl ProcessOutput {
 
 /// API for running subprocesses (CLI tools).
 #[derive(Debug, Clone, Default)]
-pub struct ProcessRunner;
+pub struct ProcessRunner {
+    sandbox: ProcessSandbox,
+}
 
 impl ProcessRunner {
     /// Creates a new process runner.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Creates a runner that starts every command inside `sandbox`.
+    pub fn sandboxed(sandbox: ProcessSandbox) -> Self {
+        Self { sandbox }
+    }
+
+    /// Returns the sandbox commands run in.
+    pub fn sandbox(&self) -> &ProcessSandbox {
+        &self.sandbox
     }
 
     /// Run a command and capture output.
@@ -119,7 +274,7 @@ impl ProcessRunner {
         env: &[(&str, &str)],
         timeout: Option<Duration>,
     ) -> Result<ProcessOutput, ProcessError> {
-        debug!(args = ?args, "Running command");
+        debug!(args = ?args, sandboxed = self.sandbox.is_enabled(), "Running command");
 
         // Find the command
         let cmd_path = self.which(cmd).ok_or_else(|| {
@@ -130,11 +285,10 @@ impl ProcessRunner {
         let start = Instant::now();
 
         // Build the command
-        let mut command = Command::new(&cmd_path);
-        command
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        let mut command = self.sandbox.command(&cmd_path, args);
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // A cancelled fetch drops this future; don't leave the command running
+        command.kill_on_drop(true);
 
         // Add environment variables
         for (key, value) in env {
@@ -179,16 +333,104 @@ impl ProcessRunner {
         self.which(cmd).is_some()
     }
 
-    /// Find the path to a command.
+    /// Find the path to a command: in the sandbox's `PATH` if it has one,
+    /// otherwise with [`discovery::find_binary`].
     pub fn which(&self, cmd: &str) -> Option<PathBuf> {
-        which::which(cmd).ok()
+        match self.sandbox.search_path() {
+            Some(path) => {
+                let cwd = std::env::current_dir().ok()?;
+                which::which_in(cmd, Some(path), cwd).ok()
+            }
+            None => discovery::find_binary(cmd),
+        }
     }
 
     /// Find all instances of a command on PATH.
     pub fn which_all(&self, cmd: &str) -> Vec<PathBuf> {
-        which::which_all(cmd)
-            .map(Iterator::collect)
-            .unwrap_or_default()
+        let found = match self.sandbox.search_path() {
+            Some(path) => std::env::current_dir()
+                .ok()
+                .and_then(|cwd| which::which_in_all(cmd, Some(path), cwd).ok())
+                .map(Iterator::collect),
+            None => which::which_all(cmd).ok().map(Iterator::collect),
+        };
+        found.unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Process API Trait
+// ============================================================================
+
+/// API for the commands strategies run.
+///
+/// [`ProcessRunner`] starts real processes; tests can provide their own
+/// through [`FetchContextBuilder::process`](crate::FetchContextBuilder::process).
+#[async_trait]
+pub trait ProcessApi: Send + Sync {
+    /// Returns the sandbox commands run in.
+    fn sandbox(&self) -> &ProcessSandbox;
+
+    /// Returns an API running commands in `sandbox`, for applying
+    /// per-provider sandbox settings.
+    fn with_sandbox(&self, sandbox: ProcessSandbox) -> Arc<dyn ProcessApi>;
+
+    /// Find the path to a command.
+    fn which(&self, cmd: &str) -> Option<PathBuf>;
+
+    /// Run a command with environment variables and an optional timeout.
+    async fn run_with_options(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+        timeout: Option<Duration>,
+    ) -> Result<ProcessOutput, ProcessError>;
+
+    /// Run a command and capture output.
+    async fn run(&self, cmd: &str, args: &[&str]) -> Result<ProcessOutput, ProcessError> {
+        self.run_with_timeout(cmd, args, Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .await
+    }
+
+    /// Run a command with timeout.
+    async fn run_with_timeout(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<ProcessOutput, ProcessError> {
+        self.run_with_options(cmd, args, &[], Some(timeout)).await
+    }
+
+    /// Check if a command exists on PATH.
+    fn command_exists(&self, cmd: &str) -> bool {
+        self.which(cmd).is_some()
+    }
+}
+
+#[async_trait]
+impl ProcessApi for ProcessRunner {
+    fn sandbox(&self) -> &ProcessSandbox {
+        &self.sandbox
+    }
+
+    fn with_sandbox(&self, sandbox: ProcessSandbox) -> Arc<dyn ProcessApi> {
+        Arc::new(ProcessRunner::sandboxed(sandbox))
+    }
+
+    fn which(&self, cmd: &str) -> Option<PathBuf> {
+        ProcessRunner::which(self, cmd)
+    }
+
+    async fn run_with_options(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+        timeout: Option<Duration>,
+    ) -> Result<ProcessOutput, ProcessError> {
+        self.run_internal(cmd, args, env, timeout).await
     }
 }
 
@@ -266,6 +508,51 @@ mod tests {
         assert!(!output.stderr.is_empty());
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sandbox_clean_env() {
+        // Cargo sets this for every test process
+        if std::env::var_os("CARGO_MANIFEST_DIR").is_none() {
+            return;
+        }
+        let env = ["-c", "echo \"$CARGO_MANIFEST_DIR\"; echo \"$HOME\""];
+
+        let output = ProcessRunner::new().run("sh", &env).await.unwrap();
+        assert!(!output.stdout.lines().next().unwrap().is_empty());
+
+        let sandboxed = ProcessRunner::sandboxed(ProcessSandbox {
+            clean_env: true,
+            ..Default::default()
+        });
+        let output = sandboxed.run("sh", &env).await.unwrap();
+        let mut lines = output.stdout.lines();
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(
+            lines.next().map(PathBuf::from),
+            std::env::var_os("HOME").map(PathBuf::from)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandbox_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = ProcessRunner::sandboxed(ProcessSandbox {
+            path: vec![dir.path().to_path_buf()],
+            ..Default::default()
+        });
+        assert!(!runner.command_exists("sh"));
+        assert!(runner.which_all("sh").is_empty());
+
+        let runner = ProcessRunner::sandboxed(ProcessSandbox {
+            path: vec![PathBuf::from("/bin")],
+            ..Default::default()
+        });
+        assert_eq!(runner.which("sh"), Some(PathBuf::from("/bin/sh")));
+        assert!(!ProcessSandbox::default().is_enabled());
+        assert!(ProcessSandbox::strict().is_enabled());
+    }
+
     #[tokio::test]
     async fn test_run_not_found() {
         let runner = ProcessRunner::new();
@@ -9,9 +9,13 @@
 //! - Pseudo-terminal emulation for proper TTY support
 //! - Async I/O with configurable timeouts
 //! - Pattern-based stop conditions
+//! - Line-by-line parsing hooks that stop the command once they have what
+//!   they need ([`LineParser`])
 //! - Automatic response to prompts (send on pattern)
 //! - ANSI escape code stripping
 //! - Idle timeout detection
+//! - Cancellation: the command is killed when its token is cancelled
+//!   ([`PtyOptions::cancel_on`]) or the future running it is dropped
 //!
 //! # Example
 //!
@@ -34,14 +38,17 @@
 //! ```
 
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, instrument, trace, warn};
 
+use super::discovery;
 use crate::error::PtyError;
 
 // ============================================================================
@@ -101,6 +108,9 @@ pub struct PtyOptions {
 
     /// Whether to strip ANSI escape codes from the output.
     pub strip_ansi: bool,
+
+    /// Kills the command when cancelled.
+    pub cancel: Option<CancellationToken>,
 }
 
 impl Default for PtyOptions {
@@ -115,6 +125,7 @@ impl Default for PtyOptions {
             send_on_substrings: HashMap::new(),
             settle_after_stop: DEFAULT_SETTLE_TIME,
             strip_ansi: true,
+            cancel: None,
         }
     }
 }
@@ -165,6 +176,60 @@ impl PtyOptions {
         self.idle_timeout = Some(timeout);
         self
     }
+
+    /// Kill the command when `cancel` is cancelled.
+    pub fn cancel_on(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+// ============================================================================
+// Line Parser
+// ============================================================================
+
+/// Incremental parser fed a command's output as it arrives.
+///
+/// Stop patterns only say that something appeared; a line parser can wait
+/// for the exact values a probe needs and stop the command the moment it
+/// has them, instead of waiting for a prompt or the idle timeout.
+pub trait LineParser: Send {
+    /// Reads one line of output, with ANSI codes stripped and surrounding
+    /// whitespace trimmed. Returns true once the parser has everything it
+    /// needs, which stops the command after `settle_after_stop`.
+    fn feed_line(&mut self, line: &str) -> bool;
+}
+
+impl<F: FnMut(&str) -> bool + Send> LineParser for F {
+    fn feed_line(&mut self, line: &str) -> bool {
+        self(line)
+    }
+}
+
+/// Splits raw PTY output into lines as it arrives.
+///
+/// Interactive CLIs redraw with bare carriage returns as often as they use
+/// newlines, so both end a line.
+#[derive(Debug, Default)]
+struct LineSplitter {
+    /// Offset of the first byte not yet returned as part of a line.
+    start: usize,
+}
+
+impl LineSplitter {
+    /// Returns the next complete, non-empty line in `bytes`.
+    fn next_line(&mut self, bytes: &[u8]) -> Option<String> {
+        loop {
+            let rest = bytes.get(self.start..)?;
+            let end = rest.iter().position(|b| matches!(b, b'\n' | b'\r'))?;
+            let line = strip_ansi_codes(&String::from_utf8_lossy(&rest[..end]));
+            self.start += end + 1;
+            let line = line.trim();
+            if !line.is_empty() {
+                return Some(line.to_string());
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -172,7 +237,7 @@ impl PtyOptions {
 // ============================================================================
 
 /// Result of a PTY command execution.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PtyResult {
     /// Combined output from the command (stdout + stderr via PTY).
     pub output: String,
@@ -186,6 +251,11 @@ pub struct PtyResult {
     /// Whether the command was stopped due to a pattern match.
     pub stopped_on_pattern: Option<String>,
 
+    /// Whether the command was stopped because its [`LineParser`] had
+    /// everything it needed.
+    #[serde(default)]
+    pub stopped_by_parser: bool,
+
     /// Whether the command timed out.
     pub timed_out: bool,
 
@@ -200,6 +270,7 @@ impl PtyResult {
             && !self.timed_out
             && !self.idle_timed_out
             && self.stopped_on_pattern.is_none()
+            && !self.stopped_by_parser
     }
 
     /// Returns true if any timeout occurred.
@@ -254,6 +325,37 @@ impl PtyRunner {
         binary: &str,
         input: &str,
         options: PtyOptions,
+    ) -> Result<PtyResult, PtyError> {
+        self.run_inner(binary, input, options, None).await
+    }
+
+    /// Run a command in a PTY, feeding its output to `parser` line by line
+    /// and stopping it as soon as the parser has what it needs.
+    ///
+    /// The full output is still returned, so callers parse it as they
+    /// would after [`run`](Self::run).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the binary is not found, PTY creation fails, or execution errors occur.
+    #[instrument(skip(self, input, parser), fields(binary = %binary))]
+    pub async fn run_with_parser(
+        &self,
+        binary: &str,
+        input: &str,
+        options: PtyOptions,
+        parser: impl LineParser + 'static,
+    ) -> Result<PtyResult, PtyError> {
+        self.run_inner(binary, input, options, Some(Box::new(parser)))
+            .await
+    }
+
+    async fn run_inner(
+        &self,
+        binary: &str,
+        input: &str,
+        options: PtyOptions,
+        parser: Option<Box<dyn LineParser>>,
     ) -> Result<PtyResult, PtyError> {
         // Find the binary
         let binary_path = Self::which(binary).ok_or_else(|| {
@@ -272,11 +374,20 @@ impl PtyRunner {
         let cols = self.cols;
         let rows = self.rows;
         let input = input.to_string();
-        let options_clone = options.clone();
+        let mut options_clone = options.clone();
+
+        // Kill the command if this future is dropped, e.g. by a cancelled
+        // fetch, instead of leaving it running until it times out
+        let cancel = options
+            .cancel
+            .as_ref()
+            .map_or_else(CancellationToken::new, CancellationToken::child_token);
+        let _kill_on_drop = cancel.clone().drop_guard();
+        options_clone.cancel = Some(cancel);
 
         // Run the blocking PTY code in a separate thread
         let result = tokio::task::spawn_blocking(move || {
-            run_pty_blocking(binary_path, input, cols, rows, options_clone)
+            run_pty_blocking(binary_path, input, cols, rows, options_clone, parser)
         })
         .await
         .map_err(|e| PtyError::SpawnFailed(format!("Task join error: {e}")))??;
@@ -286,18 +397,20 @@ impl PtyRunner {
             exit_code = ?result.exit_code,
             output_len = result.output.len(),
             stopped_on = ?result.stopped_on_pattern,
+            stopped_by_parser = result.stopped_by_parser,
             "PTY command completed"
         );
 
         Ok(result)
     }
 
-    /// Find a binary on PATH.
+    /// Find a binary with [`discovery::find_binary`]: its override from
+    /// settings, then on PATH, then in the extra search paths.
     pub fn which(binary: &str) -> Option<PathBuf> {
-        which::which(binary).ok()
+        discovery::find_binary(binary)
     }
 
-    /// Check if a binary exists on PATH.
+    /// Check if a binary can be found.
     pub fn exists(binary: &str) -> bool {
         Self::which(binary).is_some()
     }
@@ -333,6 +446,7 @@ fn run_pty_blocking(
     cols: u16,
     rows: u16,
     options: PtyOptions,
+    mut parser: Option<Box<dyn LineParser>>,
 ) -> Result<PtyResult, PtyError> {
     let start = Instant::now();
 
@@ -366,6 +480,13 @@ fn run_pty_blocking(
     // Ensure we have a proper TERM setting
     cmd.env("TERM", "xterm-256color");
 
+    // Let scripts find an interpreter installed next to them
+    if !options.env.contains_key("PATH") {
+        if let Some(path) = discovery::path_including(&binary_path) {
+            cmd.env("PATH", path);
+        }
+    }
+
     // Spawn the child process
     let mut child = pair
         .slave
@@ -402,13 +523,25 @@ fn run_pty_blocking(
     let mut output_bytes = Vec::new();
     let mut last_output_time = Instant::now();
     let mut stopped_on_pattern: Option<String> = None;
+    let mut stopped_by_parser = false;
     let mut stop_time: Option<Instant> = None;
+    let mut lines = LineSplitter::default();
     let mut sent_patterns: std::collections::HashSet<String> = std::collections::HashSet::new();
 
     // Main loop
     loop {
         let elapsed = start.elapsed();
 
+        if options
+            .cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            debug!("Command cancelled");
+            let _ = child.kill();
+            return Err(PtyError::Cancelled);
+        }
+
         // Check overall timeout
         if elapsed >= options.timeout {
             debug!("Overall timeout reached");
@@ -419,6 +552,7 @@ fn run_pty_blocking(
                 exit_code: None,
                 duration: elapsed,
                 stopped_on_pattern: None,
+                stopped_by_parser: false,
                 timed_out: true,
                 idle_timed_out: false,
             });
@@ -434,6 +568,7 @@ fn run_pty_blocking(
                     exit_code: None,
                     duration: elapsed,
                     stopped_on_pattern: None,
+                    stopped_by_parser: false,
                     timed_out: false,
                     idle_timed_out: true,
                 });
@@ -450,6 +585,7 @@ fn run_pty_blocking(
                     exit_code: None,
                     duration: elapsed,
                     stopped_on_pattern,
+                    stopped_by_parser,
                     timed_out: false,
                     idle_timed_out: false,
                 });
@@ -462,6 +598,17 @@ fn run_pty_blocking(
                 last_output_time = Instant::now();
                 output_bytes.extend_from_slice(&data);
 
+                // Feed complete lines to the parser
+                if let Some(parser) = parser.as_mut() {
+                    while let Some(line) = lines.next_line(&output_bytes) {
+                        if stop_time.is_none() && parser.feed_line(&line) {
+                            debug!("Line parser has what it needs");
+                            stopped_by_parser = true;
+                            stop_time = Some(Instant::now());
+                        }
+                    }
+                }
+
                 // Convert current output to string for pattern matching
                 let current_output = String::from_utf8_lossy(&output_bytes);
 
@@ -536,6 +683,7 @@ fn run_pty_blocking(
         exit_code,
         duration,
         stopped_on_pattern,
+        stopped_by_parser,
         timed_out: false,
         idle_timed_out: false,
     })
@@ -650,6 +798,7 @@ mod tests {
             exit_code: Some(0),
             duration: Duration::from_secs(1),
             stopped_on_pattern: None,
+            stopped_by_parser: false,
             timed_out: false,
             idle_timed_out: false,
         };
@@ -664,6 +813,7 @@ mod tests {
             exit_code: Some(1),
             duration: Duration::from_secs(1),
             stopped_on_pattern: None,
+            stopped_by_parser: false,
             timed_out: false,
             idle_timed_out: false,
         };
@@ -677,6 +827,7 @@ mod tests {
             exit_code: None,
             duration: Duration::from_secs(30),
             stopped_on_pattern: None,
+            stopped_by_parser: false,
             timed_out: true,
             idle_timed_out: false,
         };
@@ -772,6 +923,24 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_run_cancelled() {
+        let runner = PtyRunner::default();
+        let cancel = CancellationToken::new();
+        let options = PtyOptions::with_timeout(Duration::from_secs(10)).cancel_on(cancel.clone());
+
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            cancel.cancel();
+        });
+        let start = Instant::now();
+        let result = runner.run("sh", "-c 'sleep 10'\n", options).await;
+        canceller.await.unwrap();
+
+        assert!(matches!(result, Err(PtyError::Cancelled)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
     #[tokio::test]
     async fn test_run_idle_timeout() {
         let runner = PtyRunner::default();
@@ -786,6 +955,42 @@ mod tests {
         assert!(result.idle_timed_out);
     }
 
+    #[test]
+    fn test_line_splitter() {
+        let mut lines = LineSplitter::default();
+        let mut output = b"\x1b[1mCurrent session\x1b[0m\r\n72% le".to_vec();
+        assert_eq!(lines.next_line(&output).as_deref(), Some("Current session"));
+        assert_eq!(lines.next_line(&output), None);
+
+        // The partial line completes with the next chunk
+        output.extend_from_slice(b"ft\rResets 2pm\n");
+        assert_eq!(lines.next_line(&output).as_deref(), Some("72% left"));
+        assert_eq!(lines.next_line(&output).as_deref(), Some("Resets 2pm"));
+        assert_eq!(lines.next_line(&output), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_parser_stops_early() {
+        let runner = PtyRunner::default();
+        let options = PtyOptions::with_timeout(Duration::from_secs(10));
+
+        // The echoed command line doesn't end in "ready", only its output
+        let result = runner
+            .run_with_parser(
+                "sh",
+                "printf 'rea%sy\\n' d; sleep 10\n",
+                options,
+                |line: &str| line.ends_with("ready"),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.stopped_by_parser, "{result:?}");
+        assert!(!result.timed_out);
+        assert!(result.duration < Duration::from_secs(5));
+        assert!(!result.success());
+    }
+
     #[tokio::test]
     async fn test_run_bash_interactive() {
         // Skip if bash is not available
@@ -1,10 +1,13 @@
 //! Status page polling for provider health.
 //!
 //! This module provides utilities for fetching status information from
-//! provider status pages, primarily using the statuspage.io format.
+//! provider status pages, primarily using the statuspage.io format. Pages
+//! are revalidated with their `ETag`, so unchanged ones aren't downloaded
+//! again on every poll.
 
 use chrono::{DateTime, Utc};
 use exactobar_core::{ProviderStatus, StatusIndicator};
+use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use tracing::{debug, instrument, warn};
 
@@ -83,17 +86,21 @@ impl StatusPoller {
     pub async fn fetch_status(&self, status_url: &str) -> Result<ProviderStatus, StatusError> {
         debug!("Fetching status from statuspage.io endpoint");
 
-        let response = self.client.get(status_url).await.map_err(|e| {
-            warn!(error = %e, "Failed to fetch status");
-            StatusError::Unavailable(e.to_string())
-        })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
+        let response = self
+            .client
+            .get_cached(status_url, HeaderMap::new())
+            .await
+            .map_err(|e| {
+                warn!(error = %e, "Failed to fetch status");
+                StatusError::Unavailable(e.to_string())
+            })?;
+
+        if !response.status.is_success() {
+            let status = response.status;
             return Err(StatusError::Unavailable(format!("HTTP {status}")));
         }
 
-        let data: StatuspageStatus = response.json().await?;
+        let data: StatuspageStatus = response.json()?;
 
         let indicator = parse_statuspage_indicator(&data.status.indicator);
         let updated_at = data
@@ -19,11 +19,13 @@
 //! The [`host`] module provides abstractions for system interactions:
 //!
 //! - [`host::keychain`] - Secure credential storage (system keychain)
+//! - [`host::keychain_preflight`] - Keychain access pre-flight for cookie decryption
 //! - [`host::http`] - HTTP client with tracing and domain allowlist
 //! - [`host::process`] - Subprocess execution for CLI tools
 //! - [`host::pty`] - PTY-based execution for interactive CLI tools
 //! - [`host::status`] - Status page polling (statuspage.io)
 //! - [`host::browser`] - Browser cookie import for web scraping
+//! - [`host::companion`] - Localhost endpoint for the companion browser extension
 //!
 //! ## Fetch Pipeline
 //!
@@ -32,6 +34,10 @@
 //! - [`strategy::FetchStrategy`] - Trait for fetch implementations
 //! - [`pipeline::FetchPipeline`] - Executes strategies in order
 //! - [`context::FetchContext`] - Provides access to host APIs
+//! - [`guard::catch_parse_panic`] - Turns a parser panic into an error
+//! - [`parse_mode::ParseMode`] - Lenient or strict parsing
+//! - [`budget::RequestBudget`] - Requests per hour, per provider and overall
+//! - [`middleware::FetchMiddleware`] - Hooks run around every strategy
 //!
 //! ## Example
 //!
@@ -52,13 +58,20 @@
 //! ```
 
 // Core modules
+pub mod budget;
 pub mod client;
 pub mod context;
 pub mod error;
+pub mod guard;
 pub mod host;
+pub mod middleware;
+pub mod parse_mode;
 pub mod pipeline;
 pub mod probe;
+pub mod progress;
 pub mod retry;
+mod shared_cache;
+pub mod snapshot_cache;
 pub mod strategy;
 
 // Re-export key types at crate root
@@ -71,17 +84,30 @@ pub use error::{
 // Host APIs
 pub use host::{
     browser::{Browser, BrowserCookieImporter, Cookie},
-    http::HttpClient,
+    companion::CompanionServer,
+    http::{BROWSER_USER_AGENT, CachedResponse, HttpApi, HttpClient, RequestHeaders},
     keychain::{KeychainApi, SystemKeychain},
-    process::{ProcessOutput, ProcessRunner},
-    pty::{PtyOptions, PtyResult, PtyRunner},
+    keychain_preflight::{KeychainAccess, KeychainItem},
+    process::{ProcessApi, ProcessOutput, ProcessRunner, ProcessSandbox},
+    pty::{LineParser, PtyOptions, PtyResult, PtyRunner},
     status::StatusPoller,
 };
 
+// Parser panics
+pub use guard::catch_parse_panic;
+pub use parse_mode::{ParseDiagnostics, ParseMode, parse_mode, set_parse_mode};
+
 // Strategy & Pipeline
+pub use budget::{BUDGET_WINDOW, ProviderBudget, RequestBudget};
 pub use context::{FetchContext, FetchContextBuilder, FetchSettings, SourceMode};
+pub use middleware::{FetchMiddleware, RequestBudgetMiddleware};
 pub use pipeline::{FetchAttempt, FetchOutcome, FetchPipeline};
-pub use strategy::{FetchKind, FetchResult, FetchStrategy, StrategyInfo};
+pub use progress::{FetchProgress, FetchStage, ProgressSender};
+pub use snapshot_cache::{SNAPSHOT_MAX_AGE, SnapshotCache, set_snapshot_cache_dir};
+pub use strategy::{FetchKind, FetchResult, FetchStrategy, StrategyInfo, StrategySettings};
+
+// Cancellation
+pub use tokio_util::sync::CancellationToken;
 
 // Legacy exports (for compatibility)
 pub use client::HttpClient as LegacyHttpClient;
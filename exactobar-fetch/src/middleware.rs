@@ -0,0 +1,69 @@
+//! Fetch pipeline middleware.
+//!
+//! Middleware runs around every strategy the pipeline runs, so concerns
+//! that apply to all of them — request budgets, metrics, extra tracing —
+//! are written once instead of in each strategy:
+//!
+//! - [`FetchMiddleware::before_fetch`] runs before the strategy. An error
+//!   stops the pipeline without running it or any strategy after it.
+//! - [`FetchMiddleware::after_fetch`] runs after the strategy with its
+//!   result, which it may change.
+//!
+//! Middleware runs in the order it was added before the strategy, and in
+//! reverse order after it. Pipelines start with [`RequestBudgetMiddleware`].
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::context::FetchContext;
+use crate::error::FetchError;
+use crate::strategy::{FetchResult, FetchStrategy};
+
+/// Hooks run around each strategy a pipeline runs.
+#[async_trait]
+pub trait FetchMiddleware: Send + Sync {
+    /// Name, for logs.
+    fn name(&self) -> &str;
+
+    /// Runs before `strategy` fetches. Returning an error stops the
+    /// pipeline with it.
+    async fn before_fetch(
+        &self,
+        _strategy: &dyn FetchStrategy,
+        _ctx: &FetchContext,
+    ) -> Result<(), FetchError> {
+        Ok(())
+    }
+
+    /// Runs after `strategy` fetched, with what it returned and how long it
+    /// took. Not run for a fetch that was cancelled.
+    async fn after_fetch(
+        &self,
+        _strategy: &dyn FetchStrategy,
+        _ctx: &FetchContext,
+        _result: &mut Result<FetchResult, FetchError>,
+        _elapsed: Duration,
+    ) {
+    }
+}
+
+/// Counts each strategy run against the context's request budget and
+/// stops the pipeline once it's used up.
+#[derive(Debug, Default)]
+pub struct RequestBudgetMiddleware;
+
+#[async_trait]
+impl FetchMiddleware for RequestBudgetMiddleware {
+    fn name(&self) -> &'static str {
+        "request_budget"
+    }
+
+    async fn before_fetch(
+        &self,
+        _strategy: &dyn FetchStrategy,
+        ctx: &FetchContext,
+    ) -> Result<(), FetchError> {
+        ctx.acquire_request()
+    }
+}
@@ -0,0 +1,249 @@
+//! Lenient and strict parsing.
+//!
+//! Parsers are lenient by default: fields they don't know are ignored and
+//! whatever can be read is used. That keeps the app working when a provider
+//! adds a field, but it also hides format changes until something visibly
+//! breaks. Strict mode, meant for debugging, fails the parse instead and
+//! lists what it tripped over:
+//!
+//! - unrecognized fields in JSON responses
+//! - data the parser expected but didn't find
+//! - ambiguous values, like a percentage outside 0-100
+//!
+//! The mode is process-wide, set with [`set_parse_mode`] from the
+//! `parse_mode` setting or `exactobar usage --strict`.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use exactobar_core::{UsageSnapshot, UsageWindow};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::FetchError;
+
+/// Whether parsing is strict.
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+// ============================================================================
+// Parse Mode
+// ============================================================================
+
+/// How parsers treat responses they don't fully understand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Use whatever can be read (default).
+    #[default]
+    Lenient,
+    /// Fail on unknown fields, missing data and ambiguous values.
+    Strict,
+}
+
+impl ParseMode {
+    /// Returns true for strict mode.
+    pub fn is_strict(self) -> bool {
+        self == Self::Strict
+    }
+
+    /// Returns the mode's name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lenient => "lenient",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Sets the parse mode for the whole process.
+pub fn set_parse_mode(mode: ParseMode) {
+    STRICT.store(mode.is_strict(), Ordering::Relaxed);
+}
+
+/// Returns the parse mode set by [`set_parse_mode`].
+pub fn parse_mode() -> ParseMode {
+    if STRICT.load(Ordering::Relaxed) {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    }
+}
+
+// ============================================================================
+// Diagnostics
+// ============================================================================
+
+/// What a strict parse found wrong with a response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParseDiagnostics {
+    /// JSON fields the parser doesn't know, as paths like `five_hour.extra`.
+    pub unrecognized: Vec<String>,
+    /// Data the parser expected but didn't find.
+    pub missing: Vec<String>,
+    /// Values that were read but can't be trusted, with the value.
+    pub ambiguous: Vec<String>,
+}
+
+impl ParseDiagnostics {
+    /// Creates empty diagnostics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if nothing was found wrong.
+    pub fn is_empty(&self) -> bool {
+        self.unrecognized.is_empty() && self.missing.is_empty() && self.ambiguous.is_empty()
+    }
+
+    /// Records the fields of `json` that `T` doesn't deserialize.
+    ///
+    /// Invalid JSON records nothing; the parser reports that itself.
+    pub fn check_json_fields<T: DeserializeOwned>(&mut self, json: &str) {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let mut unrecognized = Vec::new();
+        let parsed: Result<T, _> = serde_ignored::deserialize(&mut deserializer, |path| {
+            // serde_ignored writes `?` for the inside of an Option
+            let path = path.to_string();
+            let segments: Vec<_> = path.split('.').filter(|segment| *segment != "?").collect();
+            unrecognized.push(segments.join("."));
+        });
+        if parsed.is_ok() {
+            self.unrecognized.extend(unrecognized);
+        }
+    }
+
+    /// Records windows a snapshot is missing or can't be trusted in.
+    pub fn check_snapshot(&mut self, snapshot: &UsageSnapshot) {
+        if snapshot.primary.is_none() {
+            self.missing.push("primary window".to_string());
+        }
+        let windows = [
+            ("primary", &snapshot.primary),
+            ("secondary", &snapshot.secondary),
+            ("tertiary", &snapshot.tertiary),
+            ("search", &snapshot.search),
+        ];
+        for (name, window) in windows {
+            if let Some(window) = window {
+                self.check_window(name, window);
+            }
+        }
+    }
+
+    fn check_window(&mut self, name: &str, window: &UsageWindow) {
+        let percent = window.used_percent;
+        if !percent.is_finite() || !(0.0..=100.0).contains(&percent) {
+            self.ambiguous
+                .push(format!("{name}.used_percent = {percent}"));
+        }
+        if window.window_minutes == Some(0) {
+            self.ambiguous.push(format!("{name}.window_minutes = 0"));
+        }
+    }
+
+    /// Returns `FetchError::StrictParse` unless the diagnostics are empty.
+    pub fn into_result(self, parser: &str) -> Result<(), FetchError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(FetchError::StrictParse {
+                parser: parser.to_string(),
+                diagnostics: self,
+            })
+        }
+    }
+}
+
+impl fmt::Display for ParseDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("unrecognized fields", &self.unrecognized),
+            ("missing", &self.missing),
+            ("ambiguous", &self.ambiguous),
+        ];
+        let mut first = true;
+        for (label, items) in sections {
+            if items.is_empty() {
+                continue;
+            }
+            if !first {
+                f.write_str("; ")?;
+            }
+            first = false;
+            write!(f, "{label}: {}", items.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Response {
+        window: Option<Window>,
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Window {
+        #[serde(alias = "percent")]
+        used: f64,
+    }
+
+    #[test]
+    fn test_check_json_fields() {
+        let mut diagnostics = ParseDiagnostics::new();
+        diagnostics.check_json_fields::<Response>(r#"{"window": {"percent": 5.0}}"#);
+        assert!(diagnostics.is_empty());
+
+        diagnostics.check_json_fields::<Response>(
+            r#"{"window": {"used": 5.0, "burst": 1}, "plan": "pro"}"#,
+        );
+        assert_eq!(diagnostics.unrecognized, vec!["window.burst", "plan"]);
+
+        diagnostics.check_json_fields::<Response>("not json");
+        assert_eq!(diagnostics.unrecognized.len(), 2);
+    }
+
+    #[test]
+    fn test_check_snapshot() {
+        let mut diagnostics = ParseDiagnostics::new();
+        diagnostics.check_snapshot(&UsageSnapshot::new());
+        assert_eq!(diagnostics.missing, vec!["primary window"]);
+
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(42.0));
+        snapshot.secondary = Some(UsageWindow::new(142.0));
+        let mut diagnostics = ParseDiagnostics::new();
+        diagnostics.check_snapshot(&snapshot);
+        assert_eq!(diagnostics.ambiguous, vec!["secondary.used_percent = 142"]);
+
+        let error = diagnostics.into_result("test").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "test response failed strict parsing: ambiguous: secondary.used_percent = 142"
+        );
+    }
+
+    #[test]
+    fn test_parse_mode_serde() {
+        assert_eq!(
+            serde_json::to_string(&ParseMode::Strict).unwrap(),
+            r#""strict""#
+        );
+        assert_eq!(ParseMode::default(), ParseMode::Lenient);
+    }
+}
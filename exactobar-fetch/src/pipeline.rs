@@ -2,12 +2,36 @@
 //!
 //! The pipeline takes a list of fetch strategies and executes them in
 //! priority order until one succeeds.
+//!
+//! When a preferred strategy fails and a fallback succeeds, the data may be
+//! incomplete: a CLI fallback often lacks the account or a window the
+//! OAuth API has. The pipeline then keeps trying the remaining strategies
+//! while the result is missing its primary window or identity, merges
+//! what they return, and lists the failed sources in the snapshot's
+//! `unavailable_sources` so the data can be shown as limited instead of
+//! failing outright.
+//!
+//! Every strategy runs through the pipeline's [middleware](crate::middleware),
+//! which starts with the request budget: once the context's budget is used
+//! up the pipeline stops with [`FetchError::BudgetExhausted`] instead of
+//! trying further strategies.
+//!
+//! Cancelling the context's token ([`FetchContext::with_cancellation`])
+//! aborts the strategy in flight, dropping its requests, and stops the
+//! pipeline with [`FetchError::Cancelled`].
+//!
+//! The pipeline reports each strategy's fetching stage to the context's
+//! [progress](crate::progress) sender before running it.
 
+use exactobar_core::{FetchSource, UsageSnapshot};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, instrument, warn};
 
 use crate::context::FetchContext;
 use crate::error::FetchError;
+use crate::middleware::{FetchMiddleware, RequestBudgetMiddleware};
+use crate::progress::{self, FetchStage};
 use crate::strategy::{FetchKind, FetchResult, FetchStrategy};
 
 // ============================================================================
@@ -23,8 +47,12 @@ pub struct FetchAttempt {
     pub kind: FetchKind,
     /// Whether the attempt succeeded.
     pub success: bool,
+    /// Whether the strategy was skipped as not available.
+    pub skipped: bool,
     /// Error if the attempt failed.
     pub error: Option<String>,
+    /// Usage the attempt returned, if it succeeded.
+    pub snapshot: Option<UsageSnapshot>,
     /// How long the attempt took.
     pub duration: Duration,
 }
@@ -36,11 +64,27 @@ impl FetchAttempt {
             strategy_id: strategy_id.into(),
             kind,
             success: true,
+            skipped: false,
             error: None,
+            snapshot: None,
             duration,
         }
     }
 
+    /// Creates a record for a strategy skipped as not available.
+    pub fn skipped(strategy_id: impl Into<String>, kind: FetchKind) -> Self {
+        Self {
+            skipped: true,
+            ..Self::failure(strategy_id, kind, "Not available", Duration::ZERO)
+        }
+    }
+
+    /// Records the usage a successful attempt returned.
+    pub fn with_snapshot(mut self, snapshot: UsageSnapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
     /// Creates a failed attempt record.
     pub fn failure(
         strategy_id: impl Into<String>,
@@ -52,7 +96,9 @@ impl FetchAttempt {
             strategy_id: strategy_id.into(),
             kind,
             success: false,
+            skipped: false,
             error: Some(error.into()),
+            snapshot: None,
             duration,
         }
     }
@@ -89,6 +135,14 @@ impl FetchOutcome {
         self.result.as_ref().ok().map(|r| r.strategy_id.as_str())
     }
 
+    /// Returns true if the fetch succeeded through a fallback after a
+    /// preferred source failed.
+    pub fn is_limited(&self) -> bool {
+        self.result
+            .as_ref()
+            .is_ok_and(|r| !r.snapshot.unavailable_sources.is_empty())
+    }
+
     /// Returns all errors that occurred.
     pub fn errors(&self) -> Vec<&str> {
         self.attempts
@@ -108,23 +162,37 @@ impl FetchOutcome {
 /// Strategies can opt out of fallback on certain errors.
 pub struct FetchPipeline {
     strategies: Vec<Box<dyn FetchStrategy>>,
+    middleware: Vec<Arc<dyn FetchMiddleware>>,
 }
 
 impl FetchPipeline {
     /// Creates an empty pipeline.
     pub fn new() -> Self {
-        Self {
-            strategies: Vec::new(),
-        }
+        Self::with_strategies(Vec::new())
     }
 
     /// Creates a pipeline with the given strategies.
     pub fn with_strategies(strategies: Vec<Box<dyn FetchStrategy>>) -> Self {
-        let mut pipeline = Self { strategies };
+        let mut pipeline = Self {
+            strategies,
+            middleware: vec![Arc::new(RequestBudgetMiddleware)],
+        };
         pipeline.sort_by_priority();
         pipeline
     }
 
+    /// Adds middleware run around every strategy, after the middleware
+    /// already added.
+    pub fn add_middleware(&mut self, middleware: Arc<dyn FetchMiddleware>) {
+        self.middleware.push(middleware);
+    }
+
+    /// Returns this pipeline with `middleware` added.
+    pub fn with_middleware(mut self, middleware: Arc<dyn FetchMiddleware>) -> Self {
+        self.add_middleware(middleware);
+        self
+    }
+
     /// Adds a strategy to the pipeline.
     pub fn add_strategy(&mut self, strategy: Box<dyn FetchStrategy>) {
         self.strategies.push(strategy);
@@ -137,6 +205,23 @@ impl FetchPipeline {
             .sort_by_key(|b| std::cmp::Reverse(b.priority()));
     }
 
+    /// Sorts strategies by the priorities `priority` gives them (highest
+    /// first), keeping the current order between equal ones.
+    pub fn reprioritize(&mut self, mut priority: impl FnMut(&dyn FetchStrategy) -> u32) {
+        self.strategies
+            .sort_by_cached_key(|s| std::cmp::Reverse(priority(s.as_ref())));
+    }
+
+    /// Keeps only the strategies `keep` returns true for.
+    pub fn retain(&mut self, mut keep: impl FnMut(&dyn FetchStrategy) -> bool) {
+        self.strategies.retain(|strategy| keep(strategy.as_ref()));
+    }
+
+    /// Returns the strategies in the order they're tried.
+    pub fn strategies(&self) -> impl Iterator<Item = &dyn FetchStrategy> {
+        self.strategies.iter().map(AsRef::as_ref)
+    }
+
     /// Returns the number of strategies in the pipeline.
     pub fn len(&self) -> usize {
         self.strategies.len()
@@ -174,7 +259,7 @@ impl FetchPipeline {
 
         info!(count = self.strategies.len(), "Executing fetch pipeline");
 
-        for strategy in &self.strategies {
+        for (index, strategy) in self.strategies.iter().enumerate() {
             let strategy_id = strategy.id();
             let kind = strategy.kind();
 
@@ -183,30 +268,51 @@ impl FetchPipeline {
             // Check if strategy is available
             if !strategy.is_available(ctx).await {
                 debug!(strategy = %strategy_id, "Strategy not available, skipping");
-                attempts.push(FetchAttempt::failure(
-                    strategy_id,
-                    kind,
-                    "Not available",
-                    Duration::ZERO,
-                ));
+                attempts.push(FetchAttempt::skipped(strategy_id, kind));
                 continue;
             }
 
             // Try the strategy
-            let attempt_start = Instant::now();
             debug!(strategy = %strategy_id, "Executing strategy");
+            let run = run_strategy(&self.middleware, strategy.as_ref(), ctx).await;
+            let (result, duration) = match run {
+                StrategyRun::Finished(result, duration) => (*result, duration),
+                StrategyRun::Stopped(error) => {
+                    warn!(
+                        strategy = %strategy_id,
+                        error = %error,
+                        "Pipeline stopped"
+                    );
+                    attempts.push(FetchAttempt::failure(
+                        strategy_id,
+                        kind,
+                        error.to_string(),
+                        Duration::ZERO,
+                    ));
+                    return FetchOutcome {
+                        result: Err(error),
+                        attempts,
+                        duration: start.elapsed(),
+                    };
+                }
+            };
 
-            match strategy.fetch(ctx).await {
+            match result {
                 Ok(result) => {
-                    let duration = attempt_start.elapsed();
                     info!(
                         strategy = %strategy_id,
                         duration = ?duration,
                         "Strategy succeeded"
                     );
 
-                    attempts.push(FetchAttempt::success(strategy_id, kind, duration));
+                    attempts.push(
+                        FetchAttempt::success(strategy_id, kind, duration)
+                            .with_snapshot(result.snapshot.clone()),
+                    );
 
+                    let rest = self.strategies[index + 1..].iter().map(Box::as_ref);
+                    let result =
+                        complete_fallback(result, rest, &self.middleware, &mut attempts, ctx).await;
                     return FetchOutcome {
                         result: Ok(result),
                         attempts,
@@ -214,7 +320,6 @@ impl FetchPipeline {
                     };
                 }
                 Err(error) => {
-                    let duration = attempt_start.elapsed();
                     warn!(
                         strategy = %strategy_id,
                         error = %error,
@@ -278,15 +383,36 @@ impl FetchPipeline {
         }
 
         // Execute available strategies
-        for strategy in available {
+        for (index, strategy) in available.iter().enumerate() {
             let strategy_id = strategy.id();
             let kind = strategy.kind();
-            let attempt_start = Instant::now();
+            let (result, duration) =
+                match run_strategy(&self.middleware, strategy.as_ref(), ctx).await {
+                    StrategyRun::Finished(result, duration) => (*result, duration),
+                    StrategyRun::Stopped(error) => {
+                        attempts.push(FetchAttempt::failure(
+                            strategy_id,
+                            kind,
+                            error.to_string(),
+                            Duration::ZERO,
+                        ));
+                        return FetchOutcome {
+                            result: Err(error),
+                            attempts,
+                            duration: start.elapsed(),
+                        };
+                    }
+                };
 
-            match strategy.fetch(ctx).await {
+            match result {
                 Ok(result) => {
-                    let duration = attempt_start.elapsed();
-                    attempts.push(FetchAttempt::success(strategy_id, kind, duration));
+                    attempts.push(
+                        FetchAttempt::success(strategy_id, kind, duration)
+                            .with_snapshot(result.snapshot.clone()),
+                    );
+                    let rest = available[index + 1..].iter().map(AsRef::as_ref);
+                    let result =
+                        complete_fallback(result, rest, &self.middleware, &mut attempts, ctx).await;
                     return FetchOutcome {
                         result: Ok(result),
                         attempts,
@@ -294,7 +420,6 @@ impl FetchPipeline {
                     };
                 }
                 Err(error) => {
-                    let duration = attempt_start.elapsed();
                     attempts.push(FetchAttempt::failure(
                         strategy_id,
                         kind,
@@ -327,6 +452,137 @@ impl Default for FetchPipeline {
     }
 }
 
+// ============================================================================
+// Fallback Results
+// ============================================================================
+
+/// Fills in a fallback's result from the strategies after it and records
+/// which preferred sources failed.
+///
+/// Results from the first strategy tried, or after only skipped ones, are
+/// returned unchanged.
+async fn complete_fallback<'a>(
+    mut result: FetchResult,
+    rest: impl Iterator<Item = &'a dyn FetchStrategy>,
+    middleware: &[Arc<dyn FetchMiddleware>],
+    attempts: &mut Vec<FetchAttempt>,
+    ctx: &FetchContext,
+) -> FetchResult {
+    let source = result.kind.to_fetch_source();
+    let mut unavailable: Vec<FetchSource> = Vec::new();
+    for attempt in attempts.iter().filter(|a| !a.success && !a.skipped) {
+        let failed = attempt.kind.to_fetch_source();
+        if failed != source && !unavailable.contains(&failed) {
+            unavailable.push(failed);
+        }
+    }
+    if unavailable.is_empty() {
+        return result;
+    }
+
+    for strategy in rest {
+        if !is_partial(&result.snapshot) {
+            break;
+        }
+        if !strategy.is_available(ctx).await {
+            attempts.push(FetchAttempt::skipped(strategy.id(), strategy.kind()));
+            continue;
+        }
+
+        debug!(strategy = %strategy.id(), "Filling in fallback result");
+        let (extra, duration) = match run_strategy(middleware, strategy, ctx).await {
+            StrategyRun::Finished(extra, duration) => (*extra, duration),
+            StrategyRun::Stopped(error) => {
+                debug!(error = %error, "Pipeline stopped, keeping limited data");
+                break;
+            }
+        };
+        match extra {
+            Ok(extra) => {
+                attempts.push(
+                    FetchAttempt::success(strategy.id(), strategy.kind(), duration)
+                        .with_snapshot(extra.snapshot.clone()),
+                );
+                result.snapshot = result.snapshot.merged_with(&extra.snapshot);
+            }
+            Err(error) => {
+                attempts.push(FetchAttempt::failure(
+                    strategy.id(),
+                    strategy.kind(),
+                    error.to_string(),
+                    duration,
+                ));
+            }
+        }
+    }
+
+    info!(unavailable = ?unavailable, "Using limited data from a fallback");
+    result.snapshot.unavailable_sources = unavailable;
+    result
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// How a strategy run through the middleware went.
+enum StrategyRun {
+    /// The strategy ran, with its result after the middleware and how long
+    /// it took. Boxed, as a result is several times the size of an error.
+    Finished(Box<Result<FetchResult, FetchError>>, Duration),
+    /// Middleware stopped the pipeline before the strategy ran, or the
+    /// fetch was cancelled.
+    Stopped(FetchError),
+}
+
+/// Runs `strategy` with `middleware` before and, in reverse order, after
+/// it. The strategy is dropped mid-flight if the context is cancelled.
+async fn run_strategy(
+    middleware: &[Arc<dyn FetchMiddleware>],
+    strategy: &dyn FetchStrategy,
+    ctx: &FetchContext,
+) -> StrategyRun {
+    if ctx.is_cancelled() {
+        return StrategyRun::Stopped(FetchError::Cancelled);
+    }
+    for m in middleware {
+        if let Err(error) = m.before_fetch(strategy, ctx).await {
+            debug!(
+                middleware = m.name(),
+                strategy = %strategy.id(),
+                "Middleware stopped the strategy"
+            );
+            return StrategyRun::Stopped(error);
+        }
+    }
+
+    let attempt_start = Instant::now();
+    let mut result = tokio::select! {
+        biased;
+        () = ctx.cancel.cancelled() => {
+            debug!(strategy = %strategy.id(), "Strategy cancelled");
+            return StrategyRun::Stopped(FetchError::Cancelled);
+        }
+        result = progress::in_stage(
+            ctx.progress.as_ref(),
+            strategy.id(),
+            FetchStage::for_kind(strategy.kind()),
+            strategy.fetch(ctx),
+        ) => result,
+    };
+    let duration = attempt_start.elapsed();
+
+    for m in middleware.iter().rev() {
+        m.after_fetch(strategy, ctx, &mut result, duration).await;
+    }
+    StrategyRun::Finished(Box::new(result), duration)
+}
+
+/// Returns true if a snapshot lacks its primary window or identity.
+fn is_partial(snapshot: &UsageSnapshot) -> bool {
+    snapshot.primary.is_none() || snapshot.identity.is_none()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -334,13 +590,15 @@ impl Default for FetchPipeline {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::{ProviderBudget, RequestBudget};
     use async_trait::async_trait;
-    use exactobar_core::UsageSnapshot;
+    use exactobar_core::{ProviderIdentity, ProviderKind, UsageWindow};
 
     struct MockSuccessStrategy {
         id: String,
         available: bool,
         priority: u32,
+        snapshot: UsageSnapshot,
     }
 
     impl MockSuccessStrategy {
@@ -349,6 +607,7 @@ mod tests {
                 id: id.to_string(),
                 available,
                 priority: 50, // Default low priority
+                snapshot: UsageSnapshot::new(),
             }
         }
 
@@ -356,6 +615,11 @@ mod tests {
             self.priority = priority;
             self
         }
+
+        fn with_snapshot(mut self, snapshot: UsageSnapshot) -> Self {
+            self.snapshot = snapshot;
+            self
+        }
     }
 
     #[async_trait]
@@ -374,7 +638,7 @@ mod tests {
 
         async fn fetch(&self, _ctx: &FetchContext) -> Result<FetchResult, FetchError> {
             Ok(FetchResult::new(
-                UsageSnapshot::new(),
+                self.snapshot.clone(),
                 self.id.clone(),
                 FetchKind::CLI,
             ))
@@ -508,4 +772,268 @@ mod tests {
         assert!(outcome.is_success());
         assert_eq!(outcome.successful_strategy(), Some("test.available"));
     }
+
+    #[test]
+    fn test_reprioritize() {
+        let mut pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(MockSuccessStrategy::new("test.oauth", true).with_priority(100)),
+            Box::new(MockSuccessStrategy::new("test.cli", true).with_priority(80)),
+            Box::new(MockSuccessStrategy::new("test.web", true).with_priority(60)),
+        ]);
+        pipeline.reprioritize(|s| {
+            if s.id() == "test.cli" {
+                200
+            } else {
+                s.priority()
+            }
+        });
+
+        let ids: Vec<_> = pipeline.strategies().map(FetchStrategy::id).collect();
+        assert_eq!(ids, ["test.cli", "test.oauth", "test.web"]);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_fills_in_limited_data() {
+        let mut session = UsageSnapshot::new();
+        session.primary = Some(UsageWindow::new(40.0));
+        let mut account = UsageSnapshot::new();
+        account.identity = Some(ProviderIdentity::new(ProviderKind::Claude));
+
+        let pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(MockFailStrategy::new("test.web", true).with_priority(100)),
+            Box::new(
+                MockSuccessStrategy::new("test.cli", true)
+                    .with_priority(50)
+                    .with_snapshot(session),
+            ),
+            Box::new(
+                MockSuccessStrategy::new("test.account", true)
+                    .with_priority(20)
+                    .with_snapshot(account),
+            ),
+            Box::new(MockSuccessStrategy::new("test.unused", true).with_priority(10)),
+        ]);
+
+        let ctx = FetchContext::new();
+        let outcome = pipeline.execute(&ctx).await;
+
+        assert!(outcome.is_limited());
+        assert_eq!(outcome.successful_strategy(), Some("test.cli"));
+        assert_eq!(outcome.attempts_count(), 3);
+        assert!(
+            outcome
+                .attempts
+                .iter()
+                .all(|a| a.success != a.snapshot.is_none())
+        );
+
+        let snapshot = outcome.result.unwrap().snapshot;
+        assert!(snapshot.primary.is_some());
+        assert!(snapshot.identity.is_some());
+        assert_eq!(snapshot.unavailable_sources, vec![FetchSource::Web]);
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_pipeline() {
+        let pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(MockFailStrategy::new("test.fail", true).with_priority(100)),
+            Box::new(MockSuccessStrategy::new("test.success", true).with_priority(50)),
+        ]);
+        let ctx = FetchContext::new().with_request_budget(ProviderBudget {
+            per_hour: Some(1),
+            ..ProviderBudget::unlimited(ProviderKind::Antigravity)
+        });
+
+        let outcome = pipeline.execute(&ctx).await;
+        assert!(matches!(
+            outcome.result,
+            Err(FetchError::BudgetExhausted { limit: 1, .. })
+        ));
+        assert_eq!(outcome.attempts_count(), 2);
+        assert_eq!(
+            RequestBudget::shared()
+                .counts()
+                .get(&ProviderKind::Antigravity),
+            Some(&1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reports_progress() {
+        let pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(MockFailStrategy::new("test.fail", true).with_priority(100)),
+            Box::new(MockSuccessStrategy::new("test.success", true).with_priority(50)),
+        ]);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let ctx = FetchContext::new().with_progress(tx);
+
+        assert!(pipeline.execute(&ctx).await.is_success());
+        drop(ctx);
+
+        let mut reported = Vec::new();
+        while let Some(progress) = rx.recv().await {
+            reported.push((progress.stage, progress.strategy_id.unwrap()));
+        }
+        assert_eq!(
+            reported,
+            [
+                (FetchStage::FetchingWeb, "test.fail".to_string()),
+                (FetchStage::ProbingCli, "test.success".to_string()),
+            ]
+        );
+    }
+
+    /// Never finishes on its own.
+    struct HangingStrategy;
+
+    #[async_trait]
+    impl FetchStrategy for HangingStrategy {
+        fn id(&self) -> &'static str {
+            "test.hang"
+        }
+
+        fn kind(&self) -> FetchKind {
+            FetchKind::OAuth
+        }
+
+        async fn is_available(&self, _ctx: &FetchContext) -> bool {
+            true
+        }
+
+        async fn fetch(&self, _ctx: &FetchContext) -> Result<FetchResult, FetchError> {
+            std::future::pending().await
+        }
+
+        fn priority(&self) -> u32 {
+            100
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_pipeline() {
+        let pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(HangingStrategy),
+            Box::new(MockSuccessStrategy::new("test.success", true).with_priority(50)),
+        ]);
+        let cancel = crate::CancellationToken::new();
+        let ctx = FetchContext::new().with_cancellation(cancel.clone());
+
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel.cancel();
+        });
+        let outcome = pipeline.execute(&ctx).await;
+        canceller.await.unwrap();
+
+        assert!(matches!(outcome.result, Err(FetchError::Cancelled)));
+        assert_eq!(outcome.attempts_count(), 1);
+
+        // Nothing runs once cancelled
+        let outcome = pipeline.execute(&ctx).await;
+        assert!(matches!(outcome.result, Err(FetchError::Cancelled)));
+    }
+
+    /// Records the order hooks ran in and fails strategies it's told to.
+    struct RecordingMiddleware {
+        name: &'static str,
+        calls: Arc<std::sync::Mutex<Vec<String>>>,
+        stop: bool,
+    }
+
+    #[async_trait]
+    impl FetchMiddleware for RecordingMiddleware {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn before_fetch(
+            &self,
+            strategy: &dyn FetchStrategy,
+            _ctx: &FetchContext,
+        ) -> Result<(), FetchError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{} before {}", self.name, strategy.id()));
+            if self.stop {
+                return Err(FetchError::StrategyNotAvailable("stopped".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn after_fetch(
+            &self,
+            strategy: &dyn FetchStrategy,
+            _ctx: &FetchContext,
+            result: &mut Result<FetchResult, FetchError>,
+            _elapsed: Duration,
+        ) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{} after {}", self.name, strategy.id()));
+            if let Ok(result) = result {
+                result.snapshot.primary = Some(UsageWindow::new(10.0));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_runs_around_strategies() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = |name, stop| {
+            Arc::new(RecordingMiddleware {
+                name,
+                calls: Arc::clone(&calls),
+                stop,
+            })
+        };
+        let pipeline = FetchPipeline::with_strategies(vec![Box::new(MockSuccessStrategy::new(
+            "test.success",
+            true,
+        ))])
+        .with_middleware(recorder("outer", false))
+        .with_middleware(recorder("inner", false));
+
+        let ctx = FetchContext::new();
+        let outcome = pipeline.execute(&ctx).await;
+        assert!(outcome.result.unwrap().snapshot.primary.is_some());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "outer before test.success",
+                "inner before test.success",
+                "inner after test.success",
+                "outer after test.success",
+            ]
+        );
+
+        let stopped = FetchPipeline::with_strategies(vec![
+            Box::new(MockFailStrategy::new("test.fail", true).with_priority(100)),
+            Box::new(MockSuccessStrategy::new("test.success", true).with_priority(50)),
+        ])
+        .with_middleware(recorder("stop", true));
+        let outcome = stopped.execute(&ctx).await;
+        assert!(matches!(
+            outcome.result,
+            Err(FetchError::StrategyNotAvailable(_))
+        ));
+        assert_eq!(outcome.attempts_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_skipped_strategies_do_not_limit() {
+        let pipeline = FetchPipeline::with_strategies(vec![
+            Box::new(MockSuccessStrategy::new("test.unavailable", false).with_priority(100)),
+            Box::new(MockSuccessStrategy::new("test.available", true).with_priority(50)),
+            Box::new(MockSuccessStrategy::new("test.unused", true).with_priority(10)),
+        ]);
+
+        let ctx = FetchContext::new();
+        let outcome = pipeline.execute(&ctx).await;
+
+        assert!(!outcome.is_limited());
+        assert_eq!(outcome.attempts_count(), 2);
+        assert!(outcome.attempts[0].skipped);
+    }
 }
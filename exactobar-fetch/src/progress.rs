@@ -0,0 +1,180 @@
+//! Refresh progress.
+//!
+//! A fetch can take several seconds, most of them in a PTY probe or a slow
+//! dashboard. A context given a sender ([`FetchContext::with_progress`])
+//! reports the stage its fetch is in as it moves through it:
+//!
+//! ```text
+//! queued → probing CLI / calling API / fetching web → parsing
+//! ```
+//!
+//! Callers report [`FetchStage::Queued`] themselves when they line up a
+//! fetch. The pipeline reports the fetching stage for each strategy it
+//! runs, and [`report`] lets code deeper in a strategy that has no context
+//! at hand, like the helper recording raw responses before they're parsed,
+//! report [`FetchStage::Parsing`].
+//!
+//! [`FetchContext::with_progress`]: crate::FetchContext::with_progress
+
+use std::future::Future;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::strategy::FetchKind;
+
+/// Sends a fetch's progress.
+pub type ProgressSender = UnboundedSender<FetchProgress>;
+
+tokio::task_local! {
+    /// Where the strategy running on this task reports to.
+    static REPORTER: Reporter;
+}
+
+/// A stage of a provider refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FetchStage {
+    /// Waiting for other providers to finish.
+    Queued,
+    /// Running the provider's CLI.
+    ProbingCli,
+    /// Calling the provider's API.
+    CallingApi,
+    /// Fetching the provider's web dashboard.
+    FetchingWeb,
+    /// Parsing the response.
+    Parsing,
+}
+
+impl FetchStage {
+    /// Number of steps a refresh goes through, for step indicators.
+    pub const STEP_COUNT: usize = 3;
+
+    /// Returns the stage a strategy of `kind` fetches in.
+    pub fn for_kind(kind: FetchKind) -> Self {
+        match kind {
+            FetchKind::CLI | FetchKind::LocalProbe => Self::ProbingCli,
+            FetchKind::OAuth | FetchKind::ApiKey => Self::CallingApi,
+            FetchKind::WebCookies | FetchKind::WebDashboard => Self::FetchingWeb,
+        }
+    }
+
+    /// Returns the step this stage is, from 0 to [`STEP_COUNT`] - 1. The
+    /// fetching stages share a step.
+    ///
+    /// [`STEP_COUNT`]: Self::STEP_COUNT
+    pub fn step(self) -> usize {
+        match self {
+            Self::Queued => 0,
+            Self::ProbingCli | Self::CallingApi | Self::FetchingWeb => 1,
+            Self::Parsing => 2,
+        }
+    }
+
+    /// Returns a label, e.g. "Probing CLI".
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Queued => "Queued",
+            Self::ProbingCli => "Probing CLI",
+            Self::CallingApi => "Calling API",
+            Self::FetchingWeb => "Fetching web",
+            Self::Parsing => "Parsing",
+        }
+    }
+}
+
+impl std::fmt::Display for FetchStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// A stage a fetch reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchProgress {
+    /// The stage.
+    pub stage: FetchStage,
+    /// The strategy in that stage, if one is running.
+    pub strategy_id: Option<String>,
+}
+
+#[derive(Clone)]
+struct Reporter {
+    sender: ProgressSender,
+    strategy_id: String,
+}
+
+impl Reporter {
+    fn send(&self, stage: FetchStage) {
+        // Nobody listening any more is fine
+        let _ = self.sender.send(FetchProgress {
+            stage,
+            strategy_id: Some(self.strategy_id.clone()),
+        });
+    }
+}
+
+/// Runs `fetch` for `strategy_id` with its progress going to `sender`,
+/// reporting the stage it fetches in first.
+pub(crate) async fn in_stage<F: Future>(
+    sender: Option<&ProgressSender>,
+    strategy_id: &str,
+    stage: FetchStage,
+    fetch: F,
+) -> F::Output {
+    match sender {
+        Some(sender) => {
+            let reporter = Reporter {
+                sender: sender.clone(),
+                strategy_id: strategy_id.to_string(),
+            };
+            reporter.send(stage);
+            REPORTER.scope(reporter, fetch).await
+        }
+        None => fetch.await,
+    }
+}
+
+/// Reports that the strategy running on this task reached `stage`. Does
+/// nothing outside a fetch whose context reports progress.
+pub fn report(stage: FetchStage) {
+    let _ = REPORTER.try_with(|reporter| reporter.send(stage));
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_report_in_stage() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        in_stage(Some(&tx), "claude.cli", FetchStage::ProbingCli, async {
+            report(FetchStage::Parsing);
+        })
+        .await;
+        // Outside a fetch, nothing is sent
+        report(FetchStage::Parsing);
+        drop(tx);
+
+        let mut stages = Vec::new();
+        while let Some(progress) = rx.recv().await {
+            assert_eq!(progress.strategy_id.as_deref(), Some("claude.cli"));
+            stages.push(progress.stage);
+        }
+        assert_eq!(stages, [FetchStage::ProbingCli, FetchStage::Parsing]);
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(
+            FetchStage::for_kind(FetchKind::WebCookies),
+            FetchStage::FetchingWeb
+        );
+        assert_eq!(FetchStage::Queued.step(), 0);
+        assert_eq!(FetchStage::CallingApi.step(), 1);
+        assert_eq!(FetchStage::Parsing.step(), FetchStage::STEP_COUNT - 1);
+    }
+}
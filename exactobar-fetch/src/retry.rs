@@ -42,6 +42,29 @@ impl RetryStrategy {
         self
     }
 
+    /// Sets the maximum delay.
+    pub fn with_max_delay(mut self, secs: u64) -> Self {
+        self.max_delay_secs = secs;
+        self
+    }
+
+    /// Backoff for a provider answering `429 Too Many Requests` without a
+    /// `Retry-After`: a minute, doubling with each rate limited fetch in a
+    /// row, up to an hour.
+    pub fn rate_limit() -> Self {
+        Self::new(u32::MAX).with_base_delay(60).with_max_delay(3600)
+    }
+
+    /// How long to hold off fetching after the `streak`th rate limited
+    /// fetch in a row: the `Retry-After` the provider sent, otherwise the
+    /// [`rate_limit`](Self::rate_limit) backoff.
+    pub fn rate_limit_cooldown(retry_after: Option<u64>, streak: u32) -> Duration {
+        retry_after.map_or_else(
+            || Self::rate_limit().delay_for_attempt(streak),
+            Duration::from_secs,
+        )
+    }
+
     /// Enables or disables exponential backoff.
     pub fn with_exponential_backoff(mut self, enabled: bool) -> Self {
         self.exponential_backoff = enabled;
@@ -51,7 +74,8 @@ impl RetryStrategy {
     /// Calculates the delay for a given attempt number.
     pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
         let delay = if self.exponential_backoff {
-            self.base_delay_secs * 2u64.pow(attempt.saturating_sub(1))
+            self.base_delay_secs
+                .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
         } else {
             self.base_delay_secs
         };
@@ -93,4 +117,13 @@ mod tests {
         // Should be capped at 60 seconds
         assert_eq!(strategy.delay_for_attempt(5), Duration::from_secs(60));
     }
+
+    #[test]
+    fn test_rate_limit_cooldown() {
+        let cooldown = RetryStrategy::rate_limit_cooldown;
+        assert_eq!(cooldown(Some(30), 5), Duration::from_secs(30));
+        assert_eq!(cooldown(None, 1), Duration::from_secs(60));
+        assert_eq!(cooldown(None, 3), Duration::from_secs(240));
+        assert_eq!(cooldown(None, 100), Duration::from_secs(3600));
+    }
 }
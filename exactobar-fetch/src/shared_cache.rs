@@ -0,0 +1,239 @@
+//! Cache shared between callers and processes.
+//!
+//! [`SharedCache`] is what [`ProbeCache`](crate::host::ProbeCache) and
+//! [`SnapshotCache`](crate::SnapshotCache) are built on. It hands a recent
+//! value to everyone who asks for the same key within a maximum age:
+//!
+//! - Callers in one process that ask for the same key at the same time
+//!   wait for a single run.
+//! - With a directory, values are also written to `<dir>/<key>.json`. A
+//!   `<key>.lock` file is a lease on the run: another process waits for
+//!   its value instead of running too, until the lease is released or
+//!   expires.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// How often a waiting process checks whether the run it's waiting for
+/// finished.
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A value with when it was stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Cached<T> {
+    pub(crate) cached_at: DateTime<Utc>,
+    pub(crate) value: T,
+}
+
+impl<T> Cached<T> {
+    fn new(value: T) -> Self {
+        Self {
+            cached_at: Utc::now(),
+            value,
+        }
+    }
+
+    fn is_fresh(&self, max_age: Duration) -> bool {
+        (Utc::now() - self.cached_at)
+            .to_std()
+            .is_ok_and(|age| age < max_age)
+    }
+}
+
+/// Latest value per key, behind a lock held while it's produced.
+type Entries<T> = Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<Cached<T>>>>>>;
+
+// ============================================================================
+// Shared Cache
+// ============================================================================
+
+/// Coalesces runs of the same key across callers and processes.
+pub(crate) struct SharedCache<T> {
+    /// How long a lease holds off other processes.
+    lease_timeout: Duration,
+    entries: Entries<T>,
+}
+
+impl<T> SharedCache<T>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    /// Creates an empty cache whose leases expire after `lease_timeout`.
+    pub(crate) fn new(lease_timeout: Duration) -> Self {
+        Self {
+            lease_timeout,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the output for `key`, calling `run` only if no caller (or,
+    /// with a `dir`, no process) stored a value in the last `max_age`.
+    ///
+    /// `keep` picks the value to store from what `run` returned, if any,
+    /// and `reuse` turns a stored value back into an output.
+    pub(crate) async fn run<O, F, Fut>(
+        &self,
+        key: &str,
+        dir: Option<&Path>,
+        max_age: Duration,
+        run: F,
+        keep: impl FnOnce(&O) -> Option<T>,
+        reuse: impl FnOnce(T) -> O,
+    ) -> O
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = O>,
+    {
+        let entry = {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(entries.entry(key.to_string()).or_default())
+        };
+        // Callers in this process queue here while one of them runs
+        let mut cached = entry.lock().await;
+
+        if let Some(hit) = cached.as_ref().filter(|c| c.is_fresh(max_age)) {
+            debug!(key, "Reusing cached value");
+            return reuse(hit.value.clone());
+        }
+
+        let Some(dir) = dir else {
+            let output = run().await;
+            *cached = keep(&output).map(Cached::new);
+            return output;
+        };
+
+        if let Some(hit) = self.wait_for_other_process(dir, key, max_age).await {
+            debug!(key, "Reusing value from another process");
+            let output = reuse(hit.value.clone());
+            *cached = Some(hit);
+            return output;
+        }
+
+        let lease = CacheLock::acquire(dir, key);
+        let output = run().await;
+        *cached = keep(&output).map(Cached::new);
+        if let Some(entry) = cached.as_ref() {
+            if let Err(e) = write_cached(dir, key, entry) {
+                warn!(key, error = %e, "Failed to share cached value");
+            }
+        }
+        drop(lease);
+        output
+    }
+
+    /// Returns true if a process holds the lease on `key` in `dir`.
+    pub(crate) fn is_running(&self, dir: &Path, key: &str) -> bool {
+        lock_age(&lock_path(dir, key)).is_some_and(|age| age < self.lease_timeout)
+    }
+
+    /// Forgets the values stored in this process.
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns a fresh value from `dir`, waiting for it if another process
+    /// holds the lease on `key`.
+    async fn wait_for_other_process(
+        &self,
+        dir: &Path,
+        key: &str,
+        max_age: Duration,
+    ) -> Option<Cached<T>> {
+        let fresh = || read_cached::<T>(dir, key).filter(|c| c.is_fresh(max_age));
+        if let Some(hit) = fresh() {
+            return Some(hit);
+        }
+
+        while self.is_running(dir, key) {
+            debug!(key, "Waiting for another process to finish");
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+        fresh()
+    }
+}
+
+// ============================================================================
+// Files
+// ============================================================================
+
+fn cache_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.json"))
+}
+
+pub(crate) fn lock_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.lock"))
+}
+
+/// Returns the value stored in `dir` for `key`, however old.
+pub(crate) fn read_cached<T: DeserializeOwned>(dir: &Path, key: &str) -> Option<Cached<T>> {
+    let contents = std::fs::read_to_string(cache_path(dir, key)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Writes through a temporary file so a reader never sees half of it.
+fn write_cached<T: Serialize>(dir: &Path, key: &str, entry: &Cached<T>) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let path = cache_path(dir, key);
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    std::fs::write(
+        &tmp,
+        serde_json::to_vec(entry).map_err(std::io::Error::other)?,
+    )?;
+    std::fs::rename(&tmp, &path)
+}
+
+/// Returns how long ago a lock file was taken, if it exists.
+fn lock_age(path: &Path) -> Option<Duration> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(
+        SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default(),
+    )
+}
+
+/// Lock file marking a run in progress, removed when dropped.
+struct CacheLock {
+    path: Option<PathBuf>,
+}
+
+impl CacheLock {
+    /// Takes the lock. Failing to is logged, not returned: the lock only
+    /// saves other processes work.
+    fn acquire(dir: &Path, key: &str) -> Self {
+        let path = lock_path(dir, key);
+        let created = std::fs::create_dir_all(dir).and_then(|()| {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+        });
+        match created {
+            Ok(_) => Self { path: Some(path) },
+            Err(e) => {
+                debug!(path = %path.display(), error = %e, "Failed to take cache lock");
+                Self { path: None }
+            }
+        }
+    }
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Shared cache for fetch results.
+//!
+//! The app refreshes on a timer and scripts or status bars run
+//! `exactobar usage` on their own, so the same provider is often fetched by
+//! several processes within seconds of each other. [`SnapshotCache`] hands
+//! a recent successful result to everyone who asks within a maximum age:
+//!
+//! - Callers in one process that fetch the same key at the same time wait
+//!   for a single fetch.
+//! - With a cache directory set ([`set_snapshot_cache_dir`]), results are
+//!   also written to `<dir>/snapshots/<key>.json`. A `<key>.lock` file is a
+//!   lease on the fetch: another process waits for its result instead of
+//!   fetching too, until the lease is released or expires.
+//!
+//! Results depend on the profile's settings and credentials, so the
+//! directory should be the current profile's cache directory. Changing it
+//! also forgets the results kept in memory.
+//!
+//! Only successful fetches are cached; a failure is retried by the next
+//! caller.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use exactobar_core::ProviderKind;
+
+use crate::context::SourceMode;
+use crate::pipeline::FetchOutcome;
+use crate::shared_cache::{SharedCache, read_cached};
+use crate::strategy::FetchResult;
+
+/// How old a result may be and still be reused.
+pub const SNAPSHOT_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// How long a lease holds off other processes. Longer than a fetch that
+/// tries every strategy with the default timeouts, so a lease left by a
+/// killed process is eventually ignored.
+const LEASE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Directory under the cache directory holding results.
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Cache directory set by [`set_snapshot_cache_dir`].
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The cache shared by all fetches.
+static SHARED: LazyLock<SnapshotCache> = LazyLock::new(SnapshotCache::new);
+
+/// Shares fetch results with other processes through `dir` from now on.
+///
+/// Pass the current profile's cache directory, and call this again when
+/// the profile changes.
+pub fn set_snapshot_cache_dir(dir: impl Into<PathBuf>) {
+    let dir = dir.into();
+    if let Ok(mut current) = CACHE_DIR.write() {
+        if current.as_ref() != Some(&dir) {
+            // What's in memory was fetched for the previous profile
+            SHARED.cache.clear();
+            *current = Some(dir);
+        }
+    }
+}
+
+fn snapshot_cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.read().ok().and_then(|dir| dir.clone())
+}
+
+// ============================================================================
+// Snapshot Cache
+// ============================================================================
+
+/// Coalesces fetches of the same provider across callers and processes.
+pub struct SnapshotCache {
+    /// Directory overriding the one set by [`set_snapshot_cache_dir`].
+    dir: Option<PathBuf>,
+    cache: SharedCache<FetchResult>,
+}
+
+impl Default for SnapshotCache {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            cache: SharedCache::new(LEASE_TIMEOUT),
+        }
+    }
+}
+
+impl SnapshotCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shares results through `dir` instead of the directory set by
+    /// [`set_snapshot_cache_dir`].
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Returns the cache shared by all fetches.
+    pub fn shared() -> &'static Self {
+        &SHARED
+    }
+
+    /// Returns the key for a provider fetched with a source mode, like
+    /// `claude-auto`.
+    pub fn key(provider: ProviderKind, source_mode: SourceMode) -> String {
+        format!("{provider:?}-{source_mode:?}").to_lowercase()
+    }
+
+    /// Returns the outcome of fetch `key`, running `fetch` only if no
+    /// process fetched it successfully in the last `max_age`.
+    ///
+    /// A reused result comes back with no attempts. With a zero `max_age`
+    /// nothing is reused, but the result is still shared.
+    pub async fn run<F, Fut>(&self, key: &str, max_age: Duration, fetch: F) -> FetchOutcome
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = FetchOutcome>,
+    {
+        let dir = self.shared_dir();
+        self.cache
+            .run(
+                key,
+                dir.as_deref(),
+                max_age,
+                fetch,
+                |outcome| outcome.result.as_ref().ok().cloned(),
+                |result| FetchOutcome {
+                    result: Ok(result),
+                    attempts: Vec::new(),
+                    duration: Duration::ZERO,
+                },
+            )
+            .await
+    }
+
+    /// Returns the latest result shared for `key`, however old, and when it
+    /// was fetched. Never fetches or waits.
+    pub fn latest(&self, key: &str) -> Option<(FetchResult, DateTime<Utc>)> {
+        let dir = self.shared_dir()?;
+        read_cached::<FetchResult>(&dir, key).map(|c| (c.value, c.cached_at))
+    }
+
+    /// Returns true if a process holds the lease on fetching `key`.
+    pub fn is_fetching(&self, key: &str) -> bool {
+        self.shared_dir()
+            .is_some_and(|dir| self.cache.is_running(&dir, key))
+    }
+
+    fn shared_dir(&self) -> Option<PathBuf> {
+        self.dir
+            .clone()
+            .or_else(snapshot_cache_dir)
+            .map(|dir| dir.join(SNAPSHOTS_DIR))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FetchError;
+    use crate::shared_cache::lock_path;
+    use crate::strategy::FetchKind;
+    use exactobar_core::{FetchSource, UsageSnapshot};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn fetch(runs: &AtomicUsize, ok: bool) -> FetchOutcome {
+        runs.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let result = if ok {
+            let mut snapshot = UsageSnapshot::new();
+            snapshot.fetch_source = FetchSource::OAuth;
+            Ok(FetchResult::new(snapshot, "claude.oauth", FetchKind::OAuth))
+        } else {
+            Err(FetchError::AllStrategiesFailed)
+        };
+        FetchOutcome {
+            result,
+            attempts: Vec::new(),
+            duration: Duration::from_millis(20),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesces_fetches() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SnapshotCache::new().with_dir(dir.path());
+        let runs = AtomicUsize::new(0);
+
+        let (a, b) = tokio::join!(
+            cache.run("claude-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true)),
+            cache.run("claude-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true)),
+        );
+        assert!(a.is_success() && b.is_success());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Other keys and a zero max age fetch again
+        cache
+            .run("claude-cli", SNAPSHOT_MAX_AGE, || fetch(&runs, true))
+            .await;
+        cache
+            .run("claude-auto", Duration::ZERO, || fetch(&runs, true))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(!lock_path(&dir.path().join(SNAPSHOTS_DIR), "claude-auto").exists());
+    }
+
+    #[tokio::test]
+    async fn test_shares_results_between_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = SnapshotCache::new().with_dir(dir.path());
+        let cli = SnapshotCache::new().with_dir(dir.path());
+        let runs = AtomicUsize::new(0);
+
+        // Failures aren't shared
+        let outcome = app
+            .run("codex-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, false))
+            .await;
+        assert!(!outcome.is_success());
+        app.run("codex-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let outcome = cli
+            .run("codex-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true))
+            .await;
+        assert_eq!(outcome.successful_strategy(), Some("claude.oauth"));
+        assert_eq!(outcome.attempts_count(), 0);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let (result, _) = cli.latest("codex-auto").unwrap();
+        assert_eq!(result.strategy_id, "claude.oauth");
+        assert!(cli.latest("claude-auto").is_none());
+        assert!(!cli.is_fetching("codex-auto"));
+    }
+
+    #[tokio::test]
+    async fn test_changing_dir_forgets_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SnapshotCache::shared();
+        let runs = AtomicUsize::new(0);
+
+        set_snapshot_cache_dir(dir.path().join("personal"));
+        cache
+            .run("gemini-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true))
+            .await;
+        set_snapshot_cache_dir(dir.path().join("work"));
+        assert!(cache.latest("gemini-auto").is_none());
+        cache
+            .run("gemini-auto", SNAPSHOT_MAX_AGE, || fetch(&runs, true))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_key() {
+        assert_eq!(
+            SnapshotCache::key(ProviderKind::Claude, SourceMode::OAuth),
+            "claude-oauth"
+        );
+    }
+}
@@ -7,6 +7,7 @@
 use async_trait::async_trait;
 use exactobar_core::{FetchSource, UsageSnapshot};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
 use crate::context::FetchContext;
@@ -70,7 +71,7 @@ impl fmt::Display for FetchKind {
 // ============================================================================
 
 /// The result of a successful fetch operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchResult {
     /// The fetched usage snapshot.
     pub snapshot: UsageSnapshot,
@@ -160,7 +161,10 @@ pub trait FetchStrategy: Send + Sync {
         match error {
             // Don't fallback on rate limiting - wait and retry same strategy
             // Don't fallback on rate limit or auth errors - likely config issue
-            FetchError::RateLimited { .. } | FetchError::AuthenticationFailed(_) => false,
+            // Don't fallback on strict parse failures - they're being debugged
+            FetchError::RateLimited { .. }
+            | FetchError::AuthenticationFailed(_)
+            | FetchError::StrictParse { .. } => false,
             // Fallback on most other errors
             _ => true,
         }
@@ -216,6 +220,63 @@ impl StrategyInfo {
     }
 }
 
+// ============================================================================
+// Strategy Settings
+// ============================================================================
+
+/// A user's per-provider choice of which strategies to run, and in what
+/// order.
+///
+/// Strategies are named by ID (`claude.pty`, `cursor.local`). Applied by
+/// `ProviderDescriptor::build_pipeline`, which drops disabled strategies
+/// from the pipeline and sorts the rest by their overridden priorities.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategySettings {
+    /// IDs of strategies never to run.
+    pub disabled: Vec<String>,
+    /// Priorities replacing the built-in ones, by strategy ID. Higher is
+    /// tried first.
+    pub priorities: BTreeMap<String, u32>,
+}
+
+impl StrategySettings {
+    /// Returns true if the strategy with this ID may run.
+    pub fn is_enabled(&self, id: &str) -> bool {
+        !self.disabled.iter().any(|disabled| disabled == id)
+    }
+
+    /// Enables or disables the strategy with this ID.
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) {
+        self.disabled.retain(|disabled| disabled != id);
+        if !enabled {
+            self.disabled.push(id.to_string());
+        }
+    }
+
+    /// Returns the priority of the strategy with this ID: its override, or
+    /// `built_in`.
+    pub fn priority(&self, id: &str, built_in: u32) -> u32 {
+        self.priorities.get(id).copied().unwrap_or(built_in)
+    }
+
+    /// Overrides priorities so the strategies are tried in the order of
+    /// `ids`.
+    pub fn set_order(&mut self, ids: &[&str]) {
+        self.priorities.clear();
+        for (i, id) in ids.iter().enumerate() {
+            let rank = u32::try_from(ids.len() - i).unwrap_or(u32::MAX);
+            self.priorities
+                .insert((*id).to_string(), rank.saturating_mul(10));
+        }
+    }
+
+    /// Returns true if every strategy may run, in the built-in order.
+    pub fn is_default(&self) -> bool {
+        self.disabled.is_empty() && self.priorities.is_empty()
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -230,6 +291,26 @@ mod tests {
         assert_eq!(FetchKind::WebCookies.display_name(), "Web Cookies");
     }
 
+    #[test]
+    fn test_strategy_settings() {
+        let mut settings = StrategySettings::default();
+        assert!(settings.is_enabled("claude.pty"));
+
+        settings.set_enabled("claude.pty", false);
+        settings.set_enabled("claude.pty", false);
+        assert!(!settings.is_enabled("claude.pty"));
+        assert_eq!(settings.disabled, vec!["claude.pty"]);
+
+        settings.set_enabled("claude.pty", true);
+        assert!(settings.is_default());
+
+        assert_eq!(settings.priority("claude.pty", 80), 80);
+        settings.set_order(&["claude.pty", "claude.oauth"]);
+        assert_eq!(settings.priority("claude.pty", 80), 20);
+        assert_eq!(settings.priority("claude.oauth", 100), 10);
+        assert!(!settings.is_default());
+    }
+
     #[test]
     fn test_fetch_kind_to_source() {
         assert_eq!(FetchKind::CLI.to_fetch_source(), FetchSource::CLI);
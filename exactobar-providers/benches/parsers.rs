@@ -0,0 +1,111 @@
+//! Provider parser benchmarks.
+//!
+//! Every [`RawFormat`] parses each sample in `tests/corpus/<format>/`, and
+//! a large version of the first one: JSON responses padded with an array
+//! of fields the parser skips, and screens captured over a PTY repeated
+//! with the redraws and colors a real capture is full of.
+
+use std::path::Path;
+use std::time::Duration;
+
+use criterion::{BenchmarkId, Criterion, criterion_group};
+use exactobar_providers::RawFormat;
+
+#[path = "../../benches/regression.rs"]
+mod regression;
+
+/// Entries in the array padding large JSON responses.
+const PADDING_ENTRIES: usize = 2_000;
+
+/// Redraws in a large screen capture.
+const SCREEN_REDRAWS: usize = 200;
+
+/// Most time parsing a large response may take.
+const LARGE_THRESHOLD: Duration = Duration::from_millis(20);
+
+fn samples(format: RawFormat) -> Vec<(String, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/corpus")
+        .join(format.name());
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
+/// Returns a large input that parses like `sample`.
+fn enlarge(sample: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(sample) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            let padding = (0..PADDING_ENTRIES)
+                .map(|i| {
+                    serde_json::json!({
+                        "id": i,
+                        "model": "model-with-a-long-name",
+                        "timestamp": "2025-01-01T00:00:00Z",
+                        "tokens": i * 1000,
+                    })
+                })
+                .collect();
+            fields.insert("events".to_string(), serde_json::Value::Array(padding));
+            serde_json::Value::Object(fields).to_string()
+        }
+        _ => {
+            let colored: String = sample
+                .lines()
+                .map(|line| format!("\x1b[38;5;2m{line}\x1b[0m\r\n"))
+                .collect();
+            format!("\x1b[2J\x1b[H{colored}").repeat(SCREEN_REDRAWS)
+        }
+    }
+}
+
+fn bench_parsers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for &format in RawFormat::ALL {
+        let samples = samples(format);
+        for (name, sample) in &samples {
+            group.bench_with_input(BenchmarkId::new(format.name(), name), sample, |b, raw| {
+                b.iter(|| format.parse(raw));
+            });
+        }
+
+        let large = enlarge(&samples[0].1);
+        assert!(
+            format.parse(&large).is_ok(),
+            "large {} sample doesn't parse",
+            format.name()
+        );
+        group.bench_with_input(
+            BenchmarkId::new(format.name(), "large"),
+            &large,
+            |b, raw| {
+                b.iter(|| format.parse(raw));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn thresholds() -> Vec<(String, Duration)> {
+    RawFormat::ALL
+        .iter()
+        .map(|format| (format!("parse/{}/large", format.name()), LARGE_THRESHOLD))
+        .collect()
+}
+
+criterion_group!(benches, bench_parsers);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    regression::check(&thresholds());
+}
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::AntigravityLocalStrategy;
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn antigravity_descriptor() -> ProviderDescriptor {
@@ -67,5 +68,6 @@ fn antigravity_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &[],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -5,7 +5,8 @@
 
 use chrono::{DateTime, Utc};
 use exactobar_core::{
-    FetchSource, LoginMethod, ProviderIdentity, ProviderKind, UsageSnapshot, UsageWindow,
+    FetchSource, LoginMethod, ModelUsage, ProviderIdentity, ProviderKind, UsageSnapshot,
+    UsageWindow,
 };
 use reqwest::header::CONTENT_TYPE;
 use serde::Deserialize;
@@ -396,6 +397,23 @@ impl AntigravitySnapshot {
             });
         }
 
+        // Every model, for the per-model breakdown
+        snapshot.models = self
+            .model_quotas
+            .iter()
+            .map(|quota| {
+                ModelUsage::new(
+                    quota.label.clone(),
+                    UsageWindow {
+                        used_percent: quota.used_percent(),
+                        window_minutes: None,
+                        resets_at: quota.reset_time,
+                        reset_description: None,
+                    },
+                )
+            })
+            .collect();
+
         // Identity
         let mut identity = ProviderIdentity::new(ProviderKind::Antigravity);
         identity.account_email = self.account_email.clone();
@@ -871,6 +889,8 @@ mod tests {
         let usage = snapshot.to_usage_snapshot().unwrap();
         assert!(usage.primary.is_some());
         assert_eq!(usage.primary.as_ref().unwrap().used_percent, 40.0);
+        assert_eq!(usage.models.len(), 1);
+        assert_eq!(usage.models[0].model, "Claude");
         assert!(usage.identity.is_some());
     }
 }
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::AugmentWebStrategy;
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn augment_descriptor() -> ProviderDescriptor {
@@ -70,5 +71,6 @@ fn augment_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &[],
+        compatible_versions: VersionRange::ANY,
     }
 }
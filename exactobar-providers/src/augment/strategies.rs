@@ -11,8 +11,8 @@ use exactobar_fetch::{
 };
 use tracing::{debug, info, instrument, warn};
 
-use super::parser::parse_augment_response;
 use super::web::AugmentWebClient;
+use crate::recording::{self, RawFormat};
 
 const AUGMENT_DOMAIN: &str = "augmentcode.com";
 const AUGMENT_API: &str = "https://api.augmentcode.com/v1/usage";
@@ -84,8 +84,7 @@ impl FetchStrategy for AugmentWebStrategy {
         debug!("Sending Augment session keepalive");
         let keepalive_result = ctx
             .http
-            .inner()
-            .post(AUGMENT_KEEPALIVE)
+            .request(reqwest::Method::POST, AUGMENT_KEEPALIVE)
             .header(reqwest::header::COOKIE, &cookie_header)
             .send()
             .await;
@@ -105,29 +104,34 @@ impl FetchStrategy for AugmentWebStrategy {
         // Now fetch the actual usage data
         let response = ctx
             .http
-            .get_with_cookies(AUGMENT_API, &cookie_header)
+            .get_cached_with_cookies(AUGMENT_API, &cookie_header)
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(FetchError::RateLimited {
+                retry_after: response.retry_after,
+            });
+        }
+
+        if response.status == reqwest::StatusCode::UNAUTHORIZED {
             return Err(FetchError::AuthenticationFailed(
                 "Augment cookies rejected (session may have expired)".to_string(),
             ));
         }
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(FetchError::InvalidResponse(format!(
                 "API returned {}",
-                response.status()
+                response.status
             )));
         }
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+        let body = response.body;
+
+        recording::record(RawFormat::Augment, &body);
 
-        let snapshot = parse_augment_response(&body)?;
+        let snapshot = RawFormat::Augment.parse(&body)?;
         info!("Fetched Augment usage successfully");
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
@@ -21,11 +21,13 @@
 //! ```
 
 use chrono::{DateTime, Utc};
+use exactobar_fetch::host::http::ResponseExt;
 use serde::Deserialize;
 use tracing::{debug, info, instrument, warn};
 
 use super::error::ClaudeError;
 use super::oauth::ClaudeOAuthCredentials;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // Constants
@@ -273,6 +275,12 @@ impl ClaudeApiClient {
             return Err(ClaudeError::MissingScope("user:profile".to_string()));
         }
 
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ClaudeError::RateLimited {
+                retry_after: response.retry_after_secs(),
+            });
+        }
+
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
             warn!(status = %status, body = %body, "API request failed");
@@ -285,17 +293,15 @@ impl ClaudeApiClient {
             .map_err(|e| ClaudeError::HttpError(e.to_string()))?;
 
         debug!(response_length = body.len(), "Got API response");
-        info!(
-            "Raw OAuth usage response: {}",
-            &body[..body.len().min(2000)]
-        );
+        recording::record(RawFormat::ClaudeOauth, &body);
+        info!("Raw OAuth usage response: {}", preview(&body, 2000));
 
         // Parse OAuth usage response
         let oauth_response: OAuthUsageResponse = serde_json::from_str(&body).map_err(|e| {
             ClaudeError::ParseError(format!(
                 "Failed to parse OAuth response: {} - body: {}",
                 e,
-                &body[..body.len().min(500)]
+                preview(&body, 500)
             ))
         })?;
 
@@ -341,6 +347,12 @@ impl ClaudeApiClient {
             ));
         }
 
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ClaudeError::RateLimited {
+                retry_after: response.retry_after_secs(),
+            });
+        }
+
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
             return Err(ClaudeError::ApiError(format!("HTTP {}: {}", status, body)));
@@ -351,10 +363,8 @@ impl ClaudeApiClient {
             .await
             .map_err(|e| ClaudeError::HttpError(e.to_string()))?;
 
-        debug!(
-            "Raw OAuth usage response: {}",
-            &body[..body.len().min(2000)]
-        );
+        debug!("Raw OAuth usage response: {}", preview(&body, 2000));
+        recording::record(RawFormat::ClaudeOauth, &body);
 
         // Parse OAuth usage response
         let oauth_response: OAuthUsageResponse =
@@ -364,6 +374,18 @@ impl ClaudeApiClient {
     }
 }
 
+/// Returns the start of a response body for logging, cut at a character
+/// boundary.
+fn preview(body: &str, max_bytes: usize) -> &str {
+    let end = body
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take_while(|&end| end <= max_bytes)
+        .last()
+        .unwrap_or(0);
+    &body[..end]
+}
+
 // ============================================================================
 // Conversion to Core Types
 // ============================================================================
@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use super::strategies::{
     ClaudeCliStrategy, ClaudeOAuthStrategy, ClaudePtyStrategy, ClaudeWebStrategy,
 };
+use crate::compat::{CliVersion, VersionRange};
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 /// Creates the Claude provider descriptor.
@@ -58,6 +59,7 @@ fn claude_token_cost() -> TokenCostConfig {
     TokenCostConfig {
         supports_token_cost: true,
         log_directory: Some(claude_log_directory),
+        log_format: None,
     }
 }
 
@@ -109,5 +111,9 @@ fn claude_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &["usage"],
+        compatible_versions: VersionRange::between(
+            CliVersion::new(1, 0, 0),
+            CliVersion::new(3, 0, 0),
+        ),
     }
 }
@@ -30,6 +30,13 @@ pub enum ClaudeError {
     #[error("API request failed: {0}")]
     ApiError(String),
 
+    /// The API answered `429 Too Many Requests`.
+    #[error("Rate limited, retry after {retry_after:?} seconds")]
+    RateLimited {
+        /// Seconds the API asked to wait, if it said.
+        retry_after: Option<u64>,
+    },
+
     /// Authentication failed.
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
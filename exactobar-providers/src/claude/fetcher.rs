@@ -191,7 +191,8 @@ impl ClaudeUsageFetcher {
 
         let client = ClaudeApiClient::new();
         let response = client.fetch_usage(&credentials).await?;
-        let snapshot = response.to_snapshot();
+        let mut snapshot = response.to_snapshot();
+        credentials.stamp_login_expiry(&mut snapshot);
 
         debug!(
             "OAuth snapshot: primary={:?}, secondary={:?}, tertiary={:?}",
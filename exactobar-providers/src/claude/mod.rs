@@ -12,6 +12,8 @@
 //! 2. **Web API** (priority 60): Uses browser cookies for claude.ai
 //!    - Imports cookies from Chrome, Firefox, Safari, etc.
 //!    - Calls `https://claude.ai/api/organizations/<org>/usage`
+//!    - With web extras on, the app also uses it to split the weekly window
+//!      between Claude Code and claude.ai ([`ClaudeWebClient::fetch_weekly_split`])
 //!
 //! 3. **PTY Fallback** (priority 40): Interactive `/usage` command
 //!    - Runs `claude` interactively and parses output
@@ -56,13 +58,17 @@ mod strategies;
 mod web;
 
 // Re-exports
+pub(crate) use api::OAuthUsageResponse;
+pub(crate) use api::USAGE_ENDPOINT;
 pub use api::{ClaudeApiClient, UsageApiResponse};
 pub use descriptor::claude_descriptor;
 pub use error::ClaudeError;
 pub use fetcher::{ClaudeDataSource, ClaudeUsageFetcher};
 pub use oauth::{ClaudeOAuthCredentials, CredentialSource};
-pub use pty_probe::{ClaudePtyProbe, ClaudeStatusSnapshot, parse_usage_output};
+pub use pty_probe::{
+    ClaudePtyProbe, ClaudeStatusSnapshot, UsageLayout, parse_usage_output, parse_usage_output_as,
+};
 pub use strategies::{
     ClaudeCliStrategy, ClaudeOAuthStrategy, ClaudePtyStrategy, ClaudeWebStrategy,
 };
-pub use web::ClaudeWebClient;
+pub use web::{ClaudeWebClient, OrgUsageResponse, WeeklySurfaceSplit};
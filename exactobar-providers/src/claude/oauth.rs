@@ -19,6 +19,7 @@
 //! ```
 
 use chrono::{DateTime, TimeZone, Utc};
+use exactobar_core::{ProviderIdentity, ProviderKind, UsageSnapshot};
 use serde::Deserialize;
 use std::fs;
 use std::path::PathBuf;
@@ -118,6 +119,27 @@ impl ClaudeOAuthCredentials {
         }
     }
 
+    /// When the login has to be renewed by signing in again: the token's
+    /// expiry, unless there's a refresh token to renew it with.
+    pub fn login_expires_at(&self) -> Option<DateTime<Utc>> {
+        match &self.refresh_token {
+            Some(token) if !token.is_empty() => None,
+            _ => self.expires_at,
+        }
+    }
+
+    /// Records when the login expires on a snapshot's identity, so it can
+    /// be warned about ahead of time.
+    pub fn stamp_login_expiry(&self, snapshot: &mut UsageSnapshot) {
+        let Some(expires_at) = self.login_expires_at() else {
+            return;
+        };
+        snapshot
+            .identity
+            .get_or_insert_with(|| ProviderIdentity::new(ProviderKind::Claude))
+            .login_expires_at = Some(expires_at);
+    }
+
     /// Check if the credentials have a specific scope.
     pub fn has_scope(&self, scope: &str) -> bool {
         self.scopes.iter().any(|s| s == scope)
@@ -370,6 +392,29 @@ mod tests {
         assert!(creds.is_expired());
     }
 
+    #[test]
+    fn test_login_expires_at() {
+        let expires_at = Utc::now() + chrono::Duration::days(2);
+        let mut creds = ClaudeOAuthCredentials {
+            access_token: "token".to_string(),
+            refresh_token: None,
+            expires_at: Some(expires_at),
+            scopes: vec![],
+            rate_limit_tier: None,
+            source: CredentialSource::File,
+        };
+        let mut snapshot = UsageSnapshot::new();
+        creds.stamp_login_expiry(&mut snapshot);
+        assert_eq!(
+            snapshot.identity.and_then(|i| i.login_expires_at),
+            Some(expires_at)
+        );
+
+        // A refresh token renews the login
+        creds.refresh_token = Some("refresh".to_string());
+        assert_eq!(creds.login_expires_at(), None);
+    }
+
     #[test]
     fn test_is_expired_none() {
         let creds = ClaudeOAuthCredentials {
@@ -8,6 +8,8 @@ use exactobar_fetch::FetchError;
 use serde::Deserialize;
 use tracing::{debug, warn};
 
+use super::api::OAuthUsageResponse;
+
 // ============================================================================
 // API Response Structures
 // ============================================================================
@@ -122,6 +124,16 @@ pub fn parse_claude_api_response(json_str: &str) -> Result<UsageSnapshot, FetchE
     Ok(snapshot)
 }
 
+/// Parses a Claude OAuth usage endpoint response into a UsageSnapshot, as
+/// the OAuth strategy does.
+pub fn parse_claude_oauth_response(json_str: &str) -> Result<UsageSnapshot, FetchError> {
+    let response: OAuthUsageResponse = serde_json::from_str(json_str).map_err(|e| {
+        warn!(error = %e, "Failed to parse Claude OAuth JSON");
+        FetchError::InvalidResponse(format!("Invalid JSON: {}", e))
+    })?;
+    Ok(response.into_usage_api_response().to_snapshot())
+}
+
 /// Parses Claude CLI output into a UsageSnapshot.
 ///
 /// # Arguments
@@ -217,8 +229,8 @@ pub(crate) fn parse_text_usage_line(text: &str) -> Option<UsageWindow> {
 
     // Look for reset description in parentheses
     if let Some(start) = text.find('(') {
-        if let Some(end) = text.find(')') {
-            let reset_desc = text[start + 1..end].trim();
+        if let Some(len) = text[start..].find(')') {
+            let reset_desc = text[start + 1..start + len].trim();
             if reset_desc.starts_with("resets ") {
                 window.reset_description = Some(reset_desc[7..].to_string());
             } else {
@@ -302,6 +314,10 @@ mod tests {
         let window = parse_text_usage_line("20% used").unwrap();
         assert_eq!(window.used_percent, 20.0);
         assert!(window.reset_description.is_none());
+
+        // A stray ')' before the '(' used to panic
+        let window = parse_text_usage_line("5% used ) (resets soon)").unwrap();
+        assert_eq!(window.reset_description, Some("soon".to_string()));
     }
 
     #[test]
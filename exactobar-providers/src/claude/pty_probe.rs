@@ -21,14 +21,22 @@
 //!
 //! Account: user@example.com
 //! ```
+//!
+//! Claude Code 1.x printed a bare label per limit with the percentage on
+//! the next line, telling the two weekly limits apart only by order. The
+//! installed version picks which layout is tried first ([`UsageLayout`]).
 
-use exactobar_fetch::host::pty::{PtyOptions, PtyRunner};
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::probe_cache::ProbeCache;
+use exactobar_fetch::host::pty::{LineParser, PtyOptions, PtyRunner};
 use regex::Regex;
 use std::sync::LazyLock;
 use std::time::Duration;
 use tracing::{debug, instrument, warn};
 
 use super::error::ClaudeError;
+use crate::compat::{self, CliVersion};
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // Constants
@@ -187,7 +195,16 @@ impl ClaudePtyProbe {
         // Send /usage command followed by exit
         let input = "/usage\nexit\n";
 
-        let result = self.runner.run(CLAUDE_BINARY, input, options).await?;
+        let result = ProbeCache::shared()
+            .run("claude-usage", || {
+                self.runner.run_with_parser(
+                    CLAUDE_BINARY,
+                    input,
+                    options,
+                    UsageLineParser::default(),
+                )
+            })
+            .await?;
 
         debug!(
             output_len = result.output.len(),
@@ -196,8 +213,15 @@ impl ClaudePtyProbe {
             "PTY command completed"
         );
 
-        // Parse the output
-        let snapshot = parse_usage_output(&result.output)?;
+        // Parse the output in the installed version's layout
+        recording::record(RawFormat::ClaudePty, &result.output);
+        let version = tokio::task::spawn_blocking(|| {
+            compat::check_provider(ProviderKind::Claude).and_then(|check| check.version)
+        })
+        .await
+        .ok()
+        .flatten();
+        let snapshot = parse_usage_output_as(&result.output, UsageLayout::for_version(version))?;
 
         if !snapshot.has_data() && !result.timed_out {
             warn!("No usage data found in output");
@@ -223,73 +247,195 @@ impl ClaudePtyProbe {
 
         let input = "/status\nexit\n";
 
-        let result = self.runner.run(CLAUDE_BINARY, input, options).await?;
+        let result = ProbeCache::shared()
+            .run("claude-status", || {
+                self.runner.run(CLAUDE_BINARY, input, options)
+            })
+            .await?;
+
+        recording::record(RawFormat::ClaudePty, &result.output);
 
         parse_usage_output(&result.output)
     }
 }
 
+// ============================================================================
+// Streaming Parser
+// ============================================================================
+
+/// Usage sections of `/usage` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageSection {
+    Session,
+    Weekly,
+    Opus,
+}
+
+/// Watches `/usage` output as it arrives and stops the CLI once every
+/// usage window has a percentage.
+///
+/// Plans without a Sonnet/Opus window never complete here; those runs stop
+/// on [`STOP_PATTERNS`] or the idle timeout as before.
+#[derive(Debug, Default)]
+struct UsageLineParser {
+    section: Option<UsageSection>,
+    session: bool,
+    weekly: bool,
+    opus: bool,
+}
+
+impl LineParser for UsageLineParser {
+    fn feed_line(&mut self, line: &str) -> bool {
+        if is_section_header(line) {
+            self.section = classify_section(&line.to_lowercase());
+        }
+        if extract_percent_left(line)
+            .or_else(|| extract_percent_used(line))
+            .is_some()
+        {
+            match self.section {
+                Some(UsageSection::Session) => self.session = true,
+                Some(UsageSection::Weekly) => self.weekly = true,
+                Some(UsageSection::Opus) => self.opus = true,
+                None => {}
+            }
+        }
+        self.session && self.weekly && self.opus
+    }
+}
+
 // ============================================================================
 // Parser Functions
 // ============================================================================
 
+/// How `/usage` output is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UsageLayout {
+    /// Labeled sections ("Current session", "Current week (all models)").
+    #[default]
+    Sections,
+    /// A bare label per limit with its percentage on the next line
+    /// (Claude Code 1.x).
+    Lines,
+}
+
+impl UsageLayout {
+    /// First version printing labeled sections.
+    const SECTIONS_SINCE: CliVersion = CliVersion::new(2, 0, 0);
+
+    /// Returns the layout `version` prints; sections if it isn't known.
+    pub fn for_version(version: Option<CliVersion>) -> Self {
+        match version {
+            Some(version) if version < Self::SECTIONS_SINCE => Self::Lines,
+            _ => Self::Sections,
+        }
+    }
+}
+
 /// Parse the /usage command output into a snapshot.
-#[instrument(skip(text))]
 pub fn parse_usage_output(text: &str) -> Result<ClaudeStatusSnapshot, ClaudeError> {
+    parse_usage_output_as(text, UsageLayout::default())
+}
+
+/// Parse /usage output, trying `layout` first and the other layout if that
+/// finds no usage.
+#[instrument(skip(text))]
+pub fn parse_usage_output_as(
+    text: &str,
+    layout: UsageLayout,
+) -> Result<ClaudeStatusSnapshot, ClaudeError> {
     let mut snapshot = ClaudeStatusSnapshot {
         raw_text: text.to_string(),
         ..Default::default()
     };
 
+    // Extract account info from full text
+    snapshot.account_email = extract_email(text);
+    snapshot.account_organization = extract_organization(text);
+    snapshot.login_method = extract_login_method(text);
+
+    match layout {
+        UsageLayout::Sections => {
+            parse_sections(text, &mut snapshot);
+            if !snapshot.has_data() {
+                parse_line_by_line(text, &mut snapshot);
+            }
+        }
+        UsageLayout::Lines => {
+            parse_line_by_line(text, &mut snapshot);
+            if !snapshot.has_data() {
+                parse_sections(text, &mut snapshot);
+            }
+        }
+    }
+
+    Ok(snapshot)
+}
+
+/// Parses labeled sections.
+fn parse_sections(text: &str, snapshot: &mut ClaudeStatusSnapshot) {
     // Split into sections based on blank lines or headers
     let sections = split_into_sections(text);
 
     for section in &sections {
-        let section_lower = section.to_lowercase();
-
         // Determine section type and extract data
-        if section_lower.contains("session")
-            || section_lower.contains("5h")
-            || section_lower.contains("5 hour")
-        {
-            if let Some(pct) = extract_percent_left(section) {
-                snapshot.session_percent_left = Some(pct);
-            } else if let Some(pct) = extract_percent_used(section) {
-                snapshot.session_percent_left = Some(100.0 - pct);
+        match classify_section(&section.to_lowercase()) {
+            Some(UsageSection::Session) => {
+                if let Some(pct) = extract_percent_left(section) {
+                    snapshot.session_percent_left = Some(pct);
+                } else if let Some(pct) = extract_percent_used(section) {
+                    snapshot.session_percent_left = Some(100.0 - pct);
+                }
+                snapshot.session_reset = extract_reset_time(section);
             }
-            snapshot.session_reset = extract_reset_time(section);
-        } else if section_lower.contains("week")
-            && (section_lower.contains("all") || !section_lower.contains("sonnet"))
-        {
-            if let Some(pct) = extract_percent_left(section) {
-                snapshot.weekly_percent_left = Some(pct);
-            } else if let Some(pct) = extract_percent_used(section) {
-                snapshot.weekly_percent_left = Some(100.0 - pct);
+            Some(UsageSection::Weekly) => {
+                if let Some(pct) = extract_percent_left(section) {
+                    snapshot.weekly_percent_left = Some(pct);
+                } else if let Some(pct) = extract_percent_used(section) {
+                    snapshot.weekly_percent_left = Some(100.0 - pct);
+                }
+                snapshot.weekly_reset = extract_reset_time(section);
             }
-            snapshot.weekly_reset = extract_reset_time(section);
-        } else if section_lower.contains("opus")
-            || section_lower.contains("sonnet")
-            || section_lower.contains("premium")
-        {
-            if let Some(pct) = extract_percent_left(section) {
-                snapshot.opus_percent_left = Some(pct);
-            } else if let Some(pct) = extract_percent_used(section) {
-                snapshot.opus_percent_left = Some(100.0 - pct);
+            Some(UsageSection::Opus) => {
+                if let Some(pct) = extract_percent_left(section) {
+                    snapshot.opus_percent_left = Some(pct);
+                } else if let Some(pct) = extract_percent_used(section) {
+                    snapshot.opus_percent_left = Some(100.0 - pct);
+                }
             }
+            None => {}
         }
     }
+}
 
-    // Extract account info from full text
-    snapshot.account_email = extract_email(text);
-    snapshot.account_organization = extract_organization(text);
-    snapshot.login_method = extract_login_method(text);
-
-    // Fallback: try line-by-line parsing if no sections found
-    if !snapshot.has_data() {
-        parse_line_by_line(text, &mut snapshot);
+/// Returns which usage window a lowercased section belongs to.
+fn classify_section(section_lower: &str) -> Option<UsageSection> {
+    if section_lower.contains("session")
+        || section_lower.contains("5h")
+        || section_lower.contains("5 hour")
+    {
+        Some(UsageSection::Session)
+    } else if section_lower.contains("week")
+        && (section_lower.contains("all") || !section_lower.contains("sonnet"))
+    {
+        Some(UsageSection::Weekly)
+    } else if section_lower.contains("opus")
+        || section_lower.contains("sonnet")
+        || section_lower.contains("premium")
+    {
+        Some(UsageSection::Opus)
+    } else {
+        None
     }
+}
 
-    Ok(snapshot)
+/// Returns true if a trimmed line starts a new section.
+fn is_section_header(line: &str) -> bool {
+    line.starts_with("Current")
+        || line.starts_with("Session")
+        || line.starts_with("Weekly")
+        || line.starts_with("Opus")
+        || line.starts_with("Sonnet")
 }
 
 /// Split text into logical sections.
@@ -301,12 +447,7 @@ fn split_into_sections(text: &str) -> Vec<String> {
         let line = line.trim();
 
         // New section on headers
-        if line.starts_with("Current")
-            || line.starts_with("Session")
-            || line.starts_with("Weekly")
-            || line.starts_with("Opus")
-            || line.starts_with("Sonnet")
-        {
+        if is_section_header(line) {
             if !current.is_empty() {
                 sections.push(current.clone());
                 current.clear();
@@ -380,7 +521,7 @@ pub fn extract_login_method(text: &str) -> Option<String> {
         .and_then(|caps| Some(caps.get(1)?.as_str().to_lowercase()))
 }
 
-/// Fallback line-by-line parsing.
+/// Parses bare labels followed by a percentage line.
 fn parse_line_by_line(text: &str, snapshot: &mut ClaudeStatusSnapshot) {
     let lines: Vec<&str> = text.lines().collect();
     let mut i = 0;
@@ -581,6 +722,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_usage_layout() {
+        assert_eq!(
+            UsageLayout::for_version(Some(CliVersion::new(1, 0, 72))),
+            UsageLayout::Lines
+        );
+        assert_eq!(
+            UsageLayout::for_version(Some(CliVersion::new(2, 0, 14))),
+            UsageLayout::Sections
+        );
+        assert_eq!(UsageLayout::for_version(None), UsageLayout::Sections);
+
+        // Read as sections, the bare labels all land in the session
+        let output = "Session\n72% left\nWeek\n45% left\nWeek\n80% left\n";
+        let snapshot = parse_usage_output_as(output, UsageLayout::Lines).unwrap();
+        assert_eq!(snapshot.session_percent_left, Some(72.0));
+        assert_eq!(snapshot.weekly_percent_left, Some(45.0));
+        assert_eq!(snapshot.opus_percent_left, Some(80.0));
+
+        let snapshot = parse_usage_output_as(output, UsageLayout::Sections).unwrap();
+        assert_eq!(snapshot.weekly_percent_left, None);
+    }
+
     #[test]
     fn test_snapshot_conversion() {
         let status = ClaudeStatusSnapshot {
@@ -617,6 +781,24 @@ mod tests {
         let _ = ClaudePtyProbe::is_available();
     }
 
+    #[test]
+    fn test_usage_line_parser() {
+        let mut parser = UsageLineParser::default();
+        let lines = [
+            "Current session",
+            "72% left",
+            "Resets 2pm (PST)",
+            "Current week (all models)",
+            "45% left",
+            "Resets Jan 5 at 12am",
+            "Current week (Sonnet)",
+        ];
+        for line in lines {
+            assert!(!parser.feed_line(line), "finished early at {line:?}");
+        }
+        assert!(parser.feed_line("80% left"));
+    }
+
     #[test]
     fn test_split_into_sections() {
         let text = r#"Current session
@@ -13,12 +13,13 @@ use exactobar_fetch::{
 };
 use tracing::{debug, info, instrument};
 
-use super::api::ClaudeApiClient;
+use super::api::{API_BASE_URL, ClaudeApiClient};
+use super::error::ClaudeError;
 use super::fetcher::ClaudeUsageFetcher;
 use super::oauth::ClaudeOAuthCredentials;
-use super::parser::parse_claude_cli_output;
 use super::pty_probe::ClaudePtyProbe;
 use super::web::ClaudeWebClient;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // OAuth Strategy (Highest Priority)
@@ -61,8 +62,8 @@ impl FetchStrategy for ClaudeOAuthStrategy {
         true
     }
 
-    #[instrument(skip(self, _ctx))]
-    async fn fetch(&self, _ctx: &FetchContext) -> Result<FetchResult, FetchError> {
+    #[instrument(skip(self, ctx))]
+    async fn fetch(&self, ctx: &FetchContext) -> Result<FetchResult, FetchError> {
         debug!("Fetching Claude usage via OAuth");
 
         let credentials = ClaudeOAuthCredentials::load()
@@ -74,11 +75,14 @@ impl FetchStrategy for ClaudeOAuthStrategy {
             ));
         }
 
-        let client = ClaudeApiClient::new();
+        let client = ClaudeApiClient::with_base_url(ctx.api_base(API_BASE_URL));
         let response = client
             .fetch_usage(&credentials)
             .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+            .map_err(|e| match e {
+                ClaudeError::RateLimited { retry_after } => FetchError::RateLimited { retry_after },
+                e => FetchError::InvalidResponse(e.to_string()),
+            })?;
 
         // Debug logging to trace data flow
         info!(
@@ -86,7 +90,8 @@ impl FetchStrategy for ClaudeOAuthStrategy {
             response.five_hour, response.seven_day, response.seven_day_sonnet
         );
 
-        let snapshot = response.to_snapshot();
+        let mut snapshot = response.to_snapshot();
+        credentials.stamp_login_expiry(&mut snapshot);
 
         info!(
             "OAuth Snapshot: primary={:?}, secondary={:?}, tertiary={:?}",
@@ -231,11 +236,13 @@ impl FetchStrategy for ClaudeCliStrategy {
             }
 
             // Parse non-JSON output
-            let snapshot = parse_claude_cli_output(&output.stdout, false)?;
+            recording::record(RawFormat::ClaudeCliText, &output.stdout);
+            let snapshot = RawFormat::ClaudeCliText.parse(&output.stdout)?;
             return Ok(FetchResult::new(snapshot, self.id(), self.kind()));
         }
 
-        let snapshot = parse_claude_cli_output(&output.stdout, true)?;
+        recording::record(RawFormat::ClaudeCliJson, &output.stdout);
+        let snapshot = RawFormat::ClaudeCliJson.parse(&output.stdout)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -318,7 +325,8 @@ impl FetchStrategy for ClaudeWebStrategy {
         }
 
         // Fetch usage
-        let client = ClaudeWebClient::new();
+        let client =
+            ClaudeWebClient::new().with_request_headers(ctx.settings.request_headers.clone());
         let response = client
             .fetch_usage(&cookie_header, None)
             .await
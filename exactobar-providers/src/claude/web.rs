@@ -7,8 +7,18 @@
 //!
 //! The session cookie (typically `__Secure-next-auth.session-token` or similar)
 //! must be present for authentication.
+//!
+//! # Weekly Split
+//!
+//! On Max plans, Claude Code and claude.ai share the weekly window. The
+//! organization usage endpoint reports the whole window (`seven_day`) and
+//! what OAuth apps like Claude Code used of it (`seven_day_oauth_apps`),
+//! from which [`WeeklySurfaceSplit`] works out the web share.
 
+use exactobar_fetch::host::browser::{Browser, BrowserCookieImporter};
+use exactobar_fetch::{BROWSER_USER_AGENT, FetchContext, FetchError, RequestHeaders};
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use tracing::{debug, instrument, warn};
 
 use super::error::ClaudeError;
@@ -24,6 +34,12 @@ pub const CLAUDE_DOMAIN: &str = "claude.ai";
 pub const USAGE_ENDPOINT: &str =
     "https://claude.ai/api/organizations/{org}/chat_conversations/usage";
 
+/// Organizations the signed-in user belongs to.
+pub const ORGANIZATIONS_ENDPOINT: &str = "https://claude.ai/api/organizations";
+
+/// Organization usage endpoint, with the weekly window split by surface.
+pub const ORG_USAGE_ENDPOINT: &str = "https://claude.ai/api/organizations/{org}/usage";
+
 /// Default organization ID.
 pub const DEFAULT_ORG: &str = "default";
 
@@ -76,6 +92,7 @@ pub struct WebUsageWindow {
     /// Utilization (alternative name).
     pub utilization: Option<f64>,
     /// Reset time (ISO 8601).
+    #[serde(alias = "resets_at")]
     pub resets_at: Option<String>,
     /// Reset description.
     pub reset_description: Option<String>,
@@ -100,6 +117,7 @@ impl WebUsageWindow {
 /// Organization info from web API.
 #[derive(Debug, Clone, Deserialize)]
 pub struct WebOrganization {
+    #[serde(alias = "uuid")]
     pub id: Option<String>,
     pub name: Option<String>,
 }
@@ -111,18 +129,78 @@ pub struct WebUser {
     pub name: Option<String>,
 }
 
+/// Response from the organization usage endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrgUsageResponse {
+    /// Session/5-hour usage.
+    pub five_hour: Option<WebUsageWindow>,
+    /// Weekly usage across every surface.
+    pub seven_day: Option<WebUsageWindow>,
+    /// Weekly usage by OAuth apps (Claude Code).
+    pub seven_day_oauth_apps: Option<WebUsageWindow>,
+    /// Weekly Opus usage.
+    pub seven_day_opus: Option<WebUsageWindow>,
+}
+
+impl OrgUsageResponse {
+    /// Splits the weekly window between Claude Code and the web, or None
+    /// if the response has no weekly window.
+    pub fn weekly_split(&self) -> Option<WeeklySurfaceSplit> {
+        let total = self.seven_day.as_ref()?.get_used_percent();
+        let code = self
+            .seven_day_oauth_apps
+            .as_ref()
+            .map_or(0.0, WebUsageWindow::get_used_percent);
+        Some(WeeklySurfaceSplit::new(total, code))
+    }
+}
+
+/// How much of the shared weekly window each surface used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeeklySurfaceSplit {
+    /// Percent of the weekly limit used everywhere.
+    pub total_percent: f64,
+    /// Percent used by Claude Code.
+    pub code_percent: f64,
+    /// Percent used on claude.ai and the desktop apps.
+    pub web_percent: f64,
+}
+
+impl WeeklySurfaceSplit {
+    /// Splits `total_percent` into what Claude Code used and the rest.
+    pub fn new(total_percent: f64, code_percent: f64) -> Self {
+        let total_percent = total_percent.clamp(0.0, 100.0);
+        let code_percent = code_percent.clamp(0.0, total_percent);
+        Self {
+            total_percent,
+            code_percent,
+            web_percent: total_percent - code_percent,
+        }
+    }
+}
+
 // ============================================================================
 // Web Client
 // ============================================================================
 
 /// Claude web client using browser cookies.
 #[derive(Debug, Clone, Default)]
-pub struct ClaudeWebClient;
+pub struct ClaudeWebClient {
+    request_headers: RequestHeaders,
+}
 
 impl ClaudeWebClient {
     /// Create a new web client.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Returns this client sending the user's configured User-Agent and
+    /// extra headers.
+    #[must_use]
+    pub fn with_request_headers(mut self, headers: RequestHeaders) -> Self {
+        self.request_headers = headers;
+        self
     }
 
     /// Check if a cookie header has a valid session cookie.
@@ -144,17 +222,79 @@ impl ClaudeWebClient {
         let url = USAGE_ENDPOINT.replace("{org}", org);
 
         debug!(url = %url, "Fetching usage from web API");
+        self.get_json(&url, cookie_header).await
+    }
+
+    /// Fetches the organizations the signed-in user belongs to.
+    #[instrument(skip(self, cookie_header))]
+    pub async fn fetch_organizations(
+        &self,
+        cookie_header: &str,
+    ) -> Result<Vec<WebOrganization>, ClaudeError> {
+        self.get_json(ORGANIZATIONS_ENDPOINT, cookie_header).await
+    }
 
+    /// Fetches an organization's usage, with the weekly window split by
+    /// surface.
+    #[instrument(skip(self, cookie_header))]
+    pub async fn fetch_org_usage(
+        &self,
+        cookie_header: &str,
+        organization_id: &str,
+    ) -> Result<OrgUsageResponse, ClaudeError> {
+        let url = ORG_USAGE_ENDPOINT.replace("{org}", organization_id);
+        debug!(url = %url, "Fetching organization usage");
+        self.get_json(&url, cookie_header).await
+    }
+
+    /// Splits the weekly window between Claude Code and the web, using the
+    /// browser's claude.ai cookies and the user's first organization.
+    pub async fn fetch_weekly_split(
+        &self,
+        ctx: &FetchContext,
+    ) -> Result<WeeklySurfaceSplit, FetchError> {
+        let (_, cookies) = ctx
+            .browser
+            .import_cookies_auto(CLAUDE_DOMAIN, Browser::default_priority())
+            .await
+            .map_err(FetchError::Browser)?;
+        let cookie_header = BrowserCookieImporter::cookies_to_header(&cookies);
+        if !Self::has_session_cookie(&cookie_header) {
+            return Err(FetchError::AuthenticationFailed(
+                "No session cookie found".to_string(),
+            ));
+        }
+
+        let invalid = |e: ClaudeError| FetchError::InvalidResponse(e.to_string());
+        let organizations = self
+            .fetch_organizations(&cookie_header)
+            .await
+            .map_err(invalid)?;
+        let org = organizations
+            .iter()
+            .find_map(|o| o.id.as_deref())
+            .ok_or_else(|| FetchError::InvalidResponse("No organization found".to_string()))?;
+        self.fetch_org_usage(&cookie_header, org)
+            .await
+            .map_err(invalid)?
+            .weekly_split()
+            .ok_or_else(|| FetchError::InvalidResponse("No weekly usage reported".to_string()))
+    }
+
+    /// GETs a claude.ai API endpoint with cookies and parses the JSON.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        cookie_header: &str,
+    ) -> Result<T, ClaudeError> {
         let client = reqwest::Client::new();
         let response = client
-            .get(&url)
+            .get(url)
             .header("Cookie", cookie_header)
             .header("Content-Type", "application/json")
             .header("Accept", "application/json")
-            .header(
-                "User-Agent",
-                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36",
-            )
+            .header("User-Agent", BROWSER_USER_AGENT)
+            .headers(self.request_headers.header_map())
             .send()
             .await
             .map_err(|e| ClaudeError::HttpError(e.to_string()))?;
@@ -184,12 +324,10 @@ impl ClaudeWebClient {
         debug!(len = body.len(), "Received web API response");
 
         // Try to parse as our expected format
-        let usage: WebUsageResponse = serde_json::from_str(&body).map_err(|e| {
+        serde_json::from_str(&body).map_err(|e| {
             warn!(error = %e, body = %body, "Failed to parse web response");
             ClaudeError::ParseError(format!("Failed to parse response: {}", e))
-        })?;
-
-        Ok(usage)
+        })
     }
 
     /// Fetch usage with automatic cookie import.
@@ -349,6 +487,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_weekly_split() {
+        let json = r#"{
+            "five_hour": { "utilization": 12.0, "resets_at": "2025-01-01T12:00:00Z" },
+            "seven_day": { "utilization": 40.0, "resets_at": "2025-01-06T00:00:00Z" },
+            "seven_day_oauth_apps": { "utilization": 25.0, "resets_at": null },
+            "seven_day_opus": null
+        }"#;
+        let response: OrgUsageResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response.seven_day.as_ref().unwrap().resets_at.as_deref(),
+            Some("2025-01-06T00:00:00Z")
+        );
+
+        let split = response.weekly_split().unwrap();
+        assert!((split.code_percent - 25.0).abs() < 0.01);
+        assert!((split.web_percent - 15.0).abs() < 0.01);
+
+        // No Claude Code usage at all
+        let response: OrgUsageResponse =
+            serde_json::from_str(r#"{ "seven_day": { "utilization": 10.0 } }"#).unwrap();
+        let split = response.weekly_split().unwrap();
+        assert!(split.code_percent.abs() < 0.01);
+        assert!((split.web_percent - 10.0).abs() < 0.01);
+
+        // Code usage is never more than the whole window
+        let split = WeeklySurfaceSplit::new(30.0, 35.0);
+        assert!((split.code_percent - 30.0).abs() < 0.01);
+        assert!(split.web_percent.abs() < 0.01);
+
+        let response: OrgUsageResponse = serde_json::from_str("{}").unwrap();
+        assert!(response.weekly_split().is_none());
+    }
+
     #[test]
     fn test_web_usage_window_get_used_percent() {
         // Test used_percent field
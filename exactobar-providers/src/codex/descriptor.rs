@@ -1,11 +1,11 @@
 //! Codex provider descriptor.
 
-use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
-use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
-use std::path::PathBuf;
-
+use super::logs::{CODEX_LOG_FORMAT, CodexLogReader};
 use super::strategies::{CodexApiStrategy, CodexCliStrategy, CodexPtyStrategy, CodexRpcStrategy};
+use crate::compat::{CliVersion, VersionRange};
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
+use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
+use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 /// Creates the Codex provider descriptor.
 pub fn codex_descriptor() -> ProviderDescriptor {
@@ -57,16 +57,11 @@ fn codex_branding() -> ProviderBranding {
 fn codex_token_cost() -> TokenCostConfig {
     TokenCostConfig {
         supports_token_cost: true,
-        log_directory: Some(codex_log_directory),
+        log_directory: Some(CodexLogReader::sessions_dir),
+        log_format: Some(CODEX_LOG_FORMAT),
     }
 }
 
-/// Returns the Codex log directory.
-fn codex_log_directory() -> Option<PathBuf> {
-    // Codex stores logs in ~/.codex/logs or similar
-    dirs::home_dir().map(|h| h.join(".codex").join("logs"))
-}
-
 /// Codex fetch plan.
 fn codex_fetch_plan() -> FetchPlan {
     FetchPlan {
@@ -109,5 +104,9 @@ fn codex_cli_config() -> CliConfig {
         aliases: &["openai"],
         version_args: &["--version"],
         usage_args: &["usage", "--json"],
+        compatible_versions: VersionRange::between(
+            CliVersion::new(0, 40, 0),
+            CliVersion::new(1, 0, 0),
+        ),
     }
 }
@@ -71,6 +71,17 @@ pub enum CodexError {
     AllStrategiesFailed,
 }
 
+impl CodexError {
+    /// Returns true if the app-server connection itself failed, rather than
+    /// a request on it.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(
+            self,
+            Self::ConnectionClosed | Self::IoError(_) | Self::Timeout(_)
+        )
+    }
+}
+
 impl From<std::io::Error> for CodexError {
     fn from(e: std::io::Error) -> Self {
         CodexError::IoError(e.to_string())
@@ -23,7 +23,8 @@ use tracing::{debug, info, instrument, warn};
 use super::auth;
 use super::error::CodexError;
 use super::pty_probe::{CodexPtyProbe, CodexStatusSnapshot};
-use super::rpc::{CodexRpcClient, RateLimitsResult};
+use super::rpc::RateLimitsResult;
+use super::session::CodexRpcSession;
 
 // ============================================================================
 // Fetcher
@@ -153,14 +154,13 @@ impl CodexUsageFetcher {
     async fn fetch_via_rpc(&self) -> Result<UsageSnapshot, CodexError> {
         debug!("Attempting RPC fetch");
 
-        // Spawn app-server (this is blocking, so wrap in spawn_blocking)
+        // The shared app-server is blocking, so wrap in spawn_blocking
         let result = tokio::task::spawn_blocking(|| {
-            let mut client = CodexRpcClient::spawn()?;
-            client.initialize()?;
-            let limits = client.fetch_rate_limits()?;
-            let account = client.fetch_account().ok();
-            client.shutdown();
-            Ok::<_, CodexError>((limits, account))
+            CodexRpcSession::shared().with_client(|client| {
+                let limits = client.fetch_rate_limits()?;
+                let account = client.fetch_account().ok();
+                Ok((limits, account))
+            })
         })
         .await
         .map_err(|e| CodexError::SpawnFailed(format!("Task join error: {}", e)))??;
@@ -257,7 +257,7 @@ fn convert_rpc_to_snapshot(limits: RateLimitsResult) -> UsageSnapshot {
 }
 
 /// Convert PTY status to UsageSnapshot.
-fn convert_pty_to_snapshot(status: CodexStatusSnapshot) -> UsageSnapshot {
+pub(crate) fn convert_pty_to_snapshot(status: CodexStatusSnapshot) -> UsageSnapshot {
     let mut snapshot = UsageSnapshot::new();
     snapshot.fetch_source = FetchSource::CLI;
 
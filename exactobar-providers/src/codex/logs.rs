@@ -0,0 +1,347 @@
+//! Codex session log reader for token cost tracking.
+//!
+//! Codex writes one JSONL "rollout" file per session under
+//! `~/.codex/sessions/YYYY/MM/DD/` (or `$CODEX_HOME/sessions`). Each turn
+//! records the model in a `turn_context` line, and token usage arrives in
+//! `event_msg` lines of type `token_count`:
+//!
+//! ```json
+//! {"timestamp":"...","type":"event_msg","payload":{"type":"token_count","info":{
+//!   "total_token_usage":{"input_tokens":1200,"cached_input_tokens":1000,
+//!     "output_tokens":300,"reasoning_output_tokens":128,"total_tokens":1500},
+//!   "last_token_usage":{...}}}}
+//! ```
+//!
+//! `total_token_usage` is cumulative for the session, so usage is taken as
+//! the difference between consecutive totals. This also ignores the repeated
+//! `token_count` events Codex emits when only rate limits change.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::cost::{LogFormat, UsageRecord, complete_lines};
+use crate::pricing::TokenCounts;
+
+// ============================================================================
+// Log Line Types
+// ============================================================================
+
+/// A single line of a Codex session log.
+#[derive(Debug, Deserialize)]
+struct SessionLine {
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+/// Payload of a `token_count` event.
+#[derive(Debug, Default, Deserialize)]
+struct TokenCountInfo {
+    #[serde(default)]
+    total_token_usage: Option<CodexTokenUsage>,
+    #[serde(default)]
+    last_token_usage: Option<CodexTokenUsage>,
+}
+
+/// Token usage as reported by Codex.
+///
+/// `input_tokens` includes `cached_input_tokens`, and `output_tokens`
+/// includes `reasoning_output_tokens`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct CodexTokenUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    cached_input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    reasoning_output_tokens: u64,
+}
+
+impl CodexTokenUsage {
+    /// Returns the usage since `previous` (a smaller cumulative total).
+    fn since(&self, previous: &Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.saturating_sub(previous.input_tokens),
+            cached_input_tokens: self
+                .cached_input_tokens
+                .saturating_sub(previous.cached_input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(previous.output_tokens),
+            reasoning_output_tokens: self
+                .reasoning_output_tokens
+                .saturating_sub(previous.reasoning_output_tokens),
+        }
+    }
+
+    /// Returns true if no tokens were used.
+    fn is_empty(&self) -> bool {
+        self.input_tokens == 0 && self.output_tokens == 0
+    }
+
+    /// Splits into billing categories (cached input is billed separately).
+    fn token_counts(&self) -> TokenCounts {
+        let cached = self.cached_input_tokens.min(self.input_tokens);
+        TokenCounts {
+            input: self.input_tokens - cached,
+            output: self.output_tokens,
+            cache_read: cached,
+            cache_write: 0,
+        }
+    }
+}
+
+// ============================================================================
+// Log Reader
+// ============================================================================
+
+/// Codex session log reader for token cost tracking.
+#[derive(Debug, Clone, Default)]
+pub struct CodexLogReader;
+
+impl CodexLogReader {
+    /// Creates a new log reader.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the Codex sessions directory.
+    pub fn sessions_dir() -> Option<PathBuf> {
+        let home = std::env::var_os("CODEX_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".codex")))?;
+        Some(home.join("sessions"))
+    }
+
+    /// Check if the sessions directory exists.
+    pub fn has_logs() -> bool {
+        Self::sessions_dir().is_some_and(|p| p.exists())
+    }
+
+    /// Lists session files under `dir` (nested by date).
+    pub fn session_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        collect_session_files(dir, &mut files);
+        debug!(count = files.len(), "Found Codex session files");
+        files
+    }
+
+    /// Parses complete lines of a session log, returning the bytes consumed.
+    fn parse_session(
+        &self,
+        content: &str,
+        state: &mut SessionState,
+        emit: &mut dyn FnMut(UsageRecord),
+    ) -> usize {
+        let complete = complete_lines(content);
+
+        for line in complete.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let Ok(line) = serde_json::from_str::<SessionLine>(line) else {
+                continue; // Skip malformed lines
+            };
+
+            match line.kind.as_str() {
+                "turn_context" | "session_meta" => {
+                    if let Some(m) = line.payload.get("model").and_then(|m| m.as_str()) {
+                        state.model = Some(m.to_string());
+                    }
+                }
+                "event_msg" => {
+                    if line.payload.get("type").and_then(|t| t.as_str()) != Some("token_count") {
+                        continue;
+                    }
+
+                    let info: TokenCountInfo = line
+                        .payload
+                        .get("info")
+                        .and_then(|i| serde_json::from_value(i.clone()).ok())
+                        .unwrap_or_default();
+
+                    let usage = match (info.total_token_usage, info.last_token_usage) {
+                        (Some(total), _) => {
+                            let delta = total.since(&state.total);
+                            state.total = total;
+                            delta
+                        }
+                        (None, Some(last)) => last,
+                        (None, None) => continue,
+                    };
+                    if usage.is_empty() {
+                        continue;
+                    }
+
+                    let Some(timestamp) = line
+                        .timestamp
+                        .as_deref()
+                        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                    else {
+                        continue;
+                    };
+
+                    emit(UsageRecord {
+                        timestamp: timestamp.with_timezone(&Utc),
+                        model: state.model.clone(),
+                        tokens: usage.token_counts(),
+                        reasoning_tokens: usage.reasoning_output_tokens,
+                        total_tokens: None,
+                        cost_usd: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        complete.len()
+    }
+}
+
+/// Per-session context carried between incremental scans.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionState {
+    /// Model of the current turn.
+    #[serde(default)]
+    model: Option<String>,
+    /// Last cumulative token total.
+    #[serde(default)]
+    total: CodexTokenUsage,
+}
+
+/// Log format for the Codex descriptor.
+pub(crate) const CODEX_LOG_FORMAT: LogFormat = LogFormat {
+    list_files: CodexLogReader::session_files,
+    parse: parse_codex_session,
+};
+
+fn parse_codex_session(
+    content: &str,
+    state: &mut serde_json::Value,
+    emit: &mut dyn FnMut(UsageRecord),
+) -> usize {
+    let mut session: SessionState = serde_json::from_value(state.take()).unwrap_or_default();
+    let consumed = CodexLogReader::new().parse_session(content, &mut session, emit);
+    *state = serde_json::to_value(&session).unwrap_or_default();
+    consumed
+}
+
+/// Recursively collects `.jsonl` files.
+fn collect_session_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        warn!(path = %dir.display(), "Failed to read sessions directory");
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_session_files(&path, files);
+        } else if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+            files.push(path);
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::CostAggregator;
+    use crate::pricing::PricingTable;
+
+    const SESSION: &str = r#"
+{"timestamp":"2025-09-20T10:00:00Z","type":"session_meta","payload":{"id":"abc","cwd":"/tmp"}}
+{"timestamp":"2025-09-20T10:00:01Z","type":"turn_context","payload":{"cwd":"/tmp","model":"gpt-5-codex"}}
+{"timestamp":"2025-09-20T10:00:05Z","type":"event_msg","payload":{"type":"token_count","info":null}}
+{"timestamp":"2025-09-20T10:00:10Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1000000,"cached_input_tokens":0,"output_tokens":100000,"reasoning_output_tokens":50000,"total_tokens":1100000}}}}
+{"timestamp":"2025-09-20T10:00:11Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1000000,"cached_input_tokens":0,"output_tokens":100000,"reasoning_output_tokens":50000,"total_tokens":1100000}}}}
+not json
+{"timestamp":"2025-09-20T10:01:00Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":2000000,"cached_input_tokens":800000,"output_tokens":100000,"reasoning_output_tokens":50000,"total_tokens":2100000}}}}
+"#;
+
+    fn read(content: &str) -> exactobar_store::CostUsageSnapshot {
+        let pricing = PricingTable::builtin();
+        let mut agg = CostAggregator::since(&pricing, "2025-09-01T00:00:00Z".parse().unwrap());
+        let mut state = serde_json::Value::Null;
+        parse_codex_session(content, &mut state, &mut |r| agg.add(&r));
+        agg.finish()
+    }
+
+    #[test]
+    fn test_sessions_dir() {
+        let dir = CodexLogReader::sessions_dir();
+        assert!(dir.is_some_and(|d| d.ends_with("sessions")));
+    }
+
+    #[test]
+    fn test_token_usage_split() {
+        let usage = CodexTokenUsage {
+            input_tokens: 1200,
+            cached_input_tokens: 1000,
+            output_tokens: 300,
+            reasoning_output_tokens: 128,
+        };
+        let counts = usage.token_counts();
+        assert_eq!(counts.input, 200);
+        assert_eq!(counts.cache_read, 1000);
+        assert_eq!(counts.output, 300);
+    }
+
+    #[test]
+    fn test_read_session_uses_cumulative_deltas() {
+        let snapshot = read(SESSION);
+
+        // Duplicate token_count is ignored; second turn adds 200K input + 800K cached
+        assert_eq!(snapshot.tokens.input, 1_200_000);
+        assert_eq!(snapshot.tokens.cache_read, 800_000);
+        assert_eq!(snapshot.tokens.output, 100_000);
+        assert_eq!(snapshot.tokens.reasoning, 50_000);
+        assert_eq!(snapshot.total_tokens, 2_100_000);
+
+        // gpt-5-codex: 1.2M * $1.25 + 0.8M * $0.125 + 0.1M * $10
+        assert_eq!(snapshot.models.len(), 1);
+        assert_eq!(snapshot.models[0].model_name, "gpt-5-codex");
+        assert!((snapshot.total_cost_usd - 2.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_read_session_last_usage_fallback() {
+        let session = r#"{"timestamp":"2025-09-20T10:00:10Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":10,"output_tokens":5}}}}"#;
+        let snapshot = read(&format!("{session}\n"));
+
+        assert_eq!(snapshot.total_tokens, 15);
+        assert_eq!(snapshot.models[0].model_name, "unknown");
+    }
+
+    #[test]
+    fn test_incremental_parse_keeps_session_state() {
+        let lines: Vec<&str> = SESSION.trim().lines().collect();
+        let (first, rest) = lines.split_at(4);
+
+        let mut state = serde_json::Value::Null;
+        let mut records = Vec::new();
+        parse_codex_session(&(first.join("\n") + "\n"), &mut state, &mut |r| {
+            records.push(r);
+        });
+        parse_codex_session(&(rest.join("\n") + "\n"), &mut state, &mut |r| {
+            records.push(r);
+        });
+
+        // Model and running total carry over to the second chunk
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(records[1].tokens.input, 200_000);
+        assert_eq!(records[1].tokens.cache_read, 800_000);
+    }
+}
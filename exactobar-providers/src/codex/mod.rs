@@ -6,7 +6,8 @@
 //! ## Fetch Strategies
 //!
 //! 1. **RPC Strategy** (priority 100): JSON-RPC to `codex app-server`
-//!    - Spawns `codex -s read-only -a untrusted app-server`
+//!    - Spawns `codex -s read-only -a untrusted app-server` once and keeps
+//!      it running across refreshes ([`CodexRpcSession`])
 //!    - Sends JSON-RPC messages over stdin/stdout
 //!    - Methods: `initialize`, `account/rateLimits/read`, `account/read`
 //!
@@ -26,6 +27,11 @@
 //! - Reads `~/.codex/auth.json` for account info
 //! - Extracts email and plan from JWT tokens
 //!
+//! ## Token Cost
+//!
+//! - [`CodexLogReader`] parses `~/.codex/sessions` rollout logs into
+//!   per-model token costs for `exactobar cost --provider codex`
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -40,10 +46,12 @@ mod auth;
 mod descriptor;
 mod error;
 mod fetcher;
+mod logs;
 #[allow(unused)] // Parser has test utilities
 pub(crate) mod parser;
 mod pty_probe;
 mod rpc;
+mod session;
 mod strategies;
 
 // Re-exports
@@ -51,6 +59,9 @@ pub use auth::{AccountInfo, read_account_info, try_read_account_info};
 pub use descriptor::codex_descriptor;
 pub use error::CodexError;
 pub use fetcher::CodexUsageFetcher;
+pub(crate) use fetcher::convert_pty_to_snapshot;
+pub use logs::CodexLogReader;
 pub use pty_probe::{CodexPtyProbe, CodexStatusSnapshot, parse_status_output};
 pub use rpc::{CodexRpcClient, RateLimits, RateLimitsResult};
+pub use session::CodexRpcSession;
 pub use strategies::{CodexApiStrategy, CodexCliStrategy, CodexPtyStrategy, CodexRpcStrategy};
@@ -21,13 +21,15 @@
 //! Credits: $112.45
 //! ```
 
-use exactobar_fetch::host::pty::{PtyOptions, PtyRunner};
+use exactobar_fetch::host::probe_cache::ProbeCache;
+use exactobar_fetch::host::pty::{LineParser, PtyOptions, PtyRunner};
 use regex::Regex;
 use std::sync::LazyLock;
 use std::time::Duration;
 use tracing::{debug, instrument, warn};
 
 use super::error::CodexError;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // Constants
@@ -42,11 +44,9 @@ const PTY_TIMEOUT: Duration = Duration::from_secs(30);
 /// Idle timeout (when to stop waiting for more output).
 const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Patterns that indicate we should stop reading.
-const STOP_PATTERNS: &[&str] = &[
-    "Credits:", "limit:", // After seeing limit info
-    "Error:", "error:",
-];
+/// Patterns that indicate we should stop reading. The limit lines are
+/// watched by [`StatusLineParser`], which waits for both windows.
+const STOP_PATTERNS: &[&str] = &["Credits:", "Error:", "error:"];
 
 // ============================================================================
 // Regex Patterns
@@ -148,7 +148,16 @@ impl CodexPtyProbe {
         // Send /status command followed by exit
         let input = "/status\nexit\n";
 
-        let result = self.runner.run(CODEX_BINARY, input, options).await?;
+        let result = ProbeCache::shared()
+            .run("codex-status", || {
+                self.runner.run_with_parser(
+                    CODEX_BINARY,
+                    input,
+                    options,
+                    StatusLineParser::default(),
+                )
+            })
+            .await?;
 
         debug!(
             output_len = result.output.len(),
@@ -158,6 +167,7 @@ impl CodexPtyProbe {
         );
 
         // Parse the output
+        recording::record(RawFormat::CodexPty, &result.output);
         let snapshot = parse_status_output(&result.output)?;
 
         if !snapshot.has_data() && !result.timed_out {
@@ -169,6 +179,37 @@ impl CodexPtyProbe {
     }
 }
 
+// ============================================================================
+// Streaming Parser
+// ============================================================================
+
+/// Watches `/status` output as it arrives and stops the CLI once both
+/// limit windows have a percentage.
+#[derive(Debug, Default)]
+struct StatusLineParser {
+    primary: bool,
+    secondary: bool,
+}
+
+impl LineParser for StatusLineParser {
+    fn feed_line(&mut self, line: &str) -> bool {
+        if let Some((window_type, _)) =
+            parse_percent_left(line).or_else(|| parse_percent_used(line))
+        {
+            let window_type = window_type.to_lowercase();
+            if window_type.contains("5h")
+                || window_type.contains("session")
+                || window_type.contains("daily")
+            {
+                self.primary = true;
+            } else if window_type.contains("week") {
+                self.secondary = true;
+            }
+        }
+        self.primary && self.secondary
+    }
+}
+
 // ============================================================================
 // Parser Functions
 // ============================================================================
@@ -398,6 +439,14 @@ mod tests {
         assert!((snapshot.secondary_used_percent.unwrap() - 40.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_status_line_parser() {
+        let mut parser = StatusLineParser::default();
+        assert!(!parser.feed_line("Account: user@example.com"));
+        assert!(!parser.feed_line("5h limit: 72% left"));
+        assert!(parser.feed_line("Weekly limit: 45% left"));
+    }
+
     #[test]
     fn test_is_available() {
         // This just tests the function exists and runs
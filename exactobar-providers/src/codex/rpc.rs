@@ -22,8 +22,10 @@
 //! client.shutdown();
 //! ```
 
+use exactobar_fetch::host::discovery::{find_binary, path_including};
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{Duration, Instant};
@@ -83,7 +85,6 @@ impl<'a, T: Serialize> RpcRequest<'a, T> {
 struct RpcResponse<T> {
     #[allow(dead_code)]
     jsonrpc: String,
-    #[allow(dead_code)]
     id: u32,
     result: Option<T>,
     error: Option<RpcError>,
@@ -227,14 +228,24 @@ impl CodexRpcClient {
         debug!("Spawning Codex app-server");
 
         // Check if codex exists
-        if which::which(CODEX_BINARY).is_err() {
-            return Err(CodexError::BinaryNotFound(CODEX_BINARY.to_string()));
-        }
+        let program = find_binary(CODEX_BINARY)
+            .ok_or_else(|| CodexError::BinaryNotFound(CODEX_BINARY.to_string()))?;
 
-        // Spawn the process
+        Self::spawn_command(&program, APP_SERVER_ARGS)
+    }
+
+    /// Spawn a JSON-RPC server from any command and create a client.
+    pub(super) fn spawn_command(
+        program: impl AsRef<Path>,
+        args: &[&str],
+    ) -> Result<Self, CodexError> {
         let start = Instant::now();
-        let mut child = Command::new(CODEX_BINARY)
-            .args(APP_SERVER_ARGS)
+        let mut command = Command::new(program.as_ref());
+        if let Some(path) = path_including(program.as_ref()) {
+            command.env("PATH", path);
+        }
+        let mut child = command
+            .args(args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
@@ -316,6 +327,16 @@ impl CodexRpcClient {
         self.server_version.as_deref()
     }
 
+    /// Returns the app-server's process ID.
+    pub fn process_id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns true if the app-server process is still running.
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
     /// Shutdown the RPC client and terminate the process.
     pub fn shutdown(&mut self) {
         debug!("Shutting down RPC client");
@@ -362,6 +383,10 @@ impl CodexRpcClient {
 
                     // Try to parse as our response
                     match serde_json::from_str::<RpcResponse<R>>(line) {
+                        Ok(response) if response.id != id => {
+                            // Late reply to an earlier request on a reused server
+                            trace!(expected = id, got = response.id, "Skipping stale response");
+                        }
                         Ok(response) => {
                             if let Some(error) = response.error {
                                 return Err(CodexError::RpcError {
@@ -0,0 +1,196 @@
+//! Persistent Codex app-server session.
+//!
+//! Spawning `codex app-server` and running `initialize` takes longer than
+//! the requests themselves, and each spawn shows up in process monitors.
+//! [`CodexRpcSession`] keeps one initialized server alive and hands it to
+//! every RPC fetch:
+//!
+//! - The first fetch spawns and initializes the server.
+//! - Later fetches reuse it while its process is running.
+//! - If a reused server fails at the connection level (closed pipe, I/O
+//!   error, timeout), it's killed, a fresh one is spawned and the request
+//!   is retried once.
+//!
+//! The server exits on its own when its stdin closes, so nothing is left
+//! behind when ExactoBar quits.
+
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use tracing::{debug, warn};
+
+use super::error::CodexError;
+use super::rpc::CodexRpcClient;
+
+/// The session shared by all Codex RPC fetches.
+static SHARED: LazyLock<CodexRpcSession> = LazyLock::new(CodexRpcSession::new);
+
+/// Starts an initialized client.
+type Spawner = fn() -> Result<CodexRpcClient, CodexError>;
+
+/// A long-lived, initialized app-server that's restarted when it fails.
+pub struct CodexRpcSession {
+    client: Mutex<Option<CodexRpcClient>>,
+    spawner: Spawner,
+}
+
+impl Default for CodexRpcSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodexRpcSession {
+    /// Creates a session for `codex app-server`. The server is spawned on
+    /// first use.
+    pub fn new() -> Self {
+        Self::with_spawner(spawn_app_server)
+    }
+
+    fn with_spawner(spawner: Spawner) -> Self {
+        Self {
+            client: Mutex::new(None),
+            spawner,
+        }
+    }
+
+    /// Returns the session shared by all Codex RPC fetches.
+    pub fn shared() -> &'static Self {
+        &SHARED
+    }
+
+    /// Runs `request` against the session's server, spawning or restarting
+    /// it as needed.
+    ///
+    /// Blocks while the request runs; call it from `spawn_blocking`.
+    /// Requests from other threads wait their turn.
+    pub fn with_client<T>(
+        &self,
+        mut request: impl FnMut(&mut CodexRpcClient) -> Result<T, CodexError>,
+    ) -> Result<T, CodexError> {
+        let mut slot = self.client.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let reused = slot.as_mut().is_some_and(CodexRpcClient::is_alive);
+        if reused {
+            debug!("Reusing Codex app-server");
+        } else {
+            *slot = None;
+        }
+        let client = match slot.take() {
+            Some(client) => client,
+            None => (self.spawner)()?,
+        };
+        let client = slot.insert(client);
+
+        match request(client) {
+            Err(e) if reused && e.is_connection_error() => {
+                warn!(error = %e, "Codex app-server failed, restarting");
+                *slot = None;
+                let client = slot.insert((self.spawner)()?);
+                let result = request(client);
+                if result.as_ref().is_err_and(CodexError::is_connection_error) {
+                    *slot = None;
+                }
+                result
+            }
+            Err(e) => {
+                if e.is_connection_error() {
+                    *slot = None;
+                }
+                Err(e)
+            }
+            ok => ok,
+        }
+    }
+
+    /// Returns the running server's process ID, if there is one.
+    pub fn process_id(&self) -> Option<u32> {
+        let mut slot = self.client.lock().unwrap_or_else(PoisonError::into_inner);
+        let client = slot.as_mut()?;
+        client.is_alive().then(|| client.process_id())
+    }
+
+    /// Shuts the server down. The next request spawns a new one.
+    pub fn shutdown(&self) {
+        let mut slot = self.client.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(mut client) = slot.take() {
+            client.shutdown();
+        }
+    }
+}
+
+fn spawn_app_server() -> Result<CodexRpcClient, CodexError> {
+    let mut client = CodexRpcClient::spawn()?;
+    client.initialize()?;
+    Ok(client)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Answers rate limit requests with an error and everything else with an
+    /// empty result, like an app-server for an account without a plan.
+    const ECHO_SERVER: &str = r#"while IFS= read -r line; do
+        id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+        [ -z "$id" ] && continue
+        case "$line" in
+            *rateLimits*) printf '{"jsonrpc":"2.0","id":%s,"error":{"code":-32000,"message":"no plan"}}\n' "$id" ;;
+            *) printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+        esac
+    done"#;
+
+    fn spawn_echo_server() -> Result<CodexRpcClient, CodexError> {
+        let mut client = CodexRpcClient::spawn_command("sh", &["-c", ECHO_SERVER])?;
+        client.initialize()?;
+        Ok(client)
+    }
+
+    fn kill(pid: u32) {
+        std::process::Command::new("kill")
+            .arg(pid.to_string())
+            .status()
+            .unwrap();
+        // Let the process exit before the next liveness check
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_session_reuses_and_restarts_server() {
+        let session = CodexRpcSession::with_spawner(spawn_echo_server);
+        assert_eq!(session.process_id(), None);
+
+        let account = session.with_client(CodexRpcClient::fetch_account).unwrap();
+        assert_eq!(account.email, None);
+        let first = session.process_id().unwrap();
+
+        session.with_client(CodexRpcClient::fetch_account).unwrap();
+        assert_eq!(session.process_id(), Some(first));
+
+        // A server that died between refreshes is replaced
+        kill(first);
+        session.with_client(CodexRpcClient::fetch_account).unwrap();
+        let second = session.process_id().unwrap();
+        assert_ne!(second, first);
+
+        session.shutdown();
+        assert_eq!(session.process_id(), None);
+    }
+
+    #[test]
+    fn test_session_keeps_server_after_request_error() {
+        let session = CodexRpcSession::with_spawner(spawn_echo_server);
+
+        // An RPC error comes from a working server, so it's kept
+        let error = session
+            .with_client(CodexRpcClient::fetch_rate_limits)
+            .unwrap_err();
+        assert!(matches!(error, CodexError::RpcError { code: -32000, .. }));
+        let pid = session.process_id().unwrap();
+        session.with_client(CodexRpcClient::fetch_account).unwrap();
+        assert_eq!(session.process_id(), Some(pid));
+    }
+}
@@ -16,8 +16,8 @@ use exactobar_fetch::{
 use tracing::{debug, instrument, warn};
 
 use super::fetcher::CodexUsageFetcher;
-use super::parser::parse_codex_cli_output;
 use super::pty_probe::CodexPtyProbe;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // RPC Strategy (Highest Priority)
@@ -25,8 +25,9 @@ use super::pty_probe::CodexPtyProbe;
 
 /// Codex RPC strategy using JSON-RPC to `codex app-server`.
 ///
-/// This is the primary strategy for Codex. It spawns the app-server
-/// and communicates via JSON-RPC over stdin/stdout.
+/// This is the primary strategy for Codex. It talks JSON-RPC over
+/// stdin/stdout to an app-server that's spawned on the first fetch and
+/// kept running for later ones.
 pub struct CodexRpcStrategy;
 
 impl CodexRpcStrategy {
@@ -206,7 +207,8 @@ impl FetchStrategy for CodexCliStrategy {
         }
 
         // Parse the output
-        let snapshot = parse_codex_cli_output(&output.stdout)?;
+        recording::record(RawFormat::CodexCli, &output.stdout);
+        let snapshot = RawFormat::CodexCli.parse(&output.stdout)?;
 
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
@@ -236,9 +238,15 @@ impl CodexApiStrategy {
         }
     }
 
-    /// Gets the API key from keychain or environment.
+    /// Gets the API key from Settings (keychain or password manager), the
+    /// OpenAI keychain entry, or the environment.
     async fn get_api_key(&self, ctx: &FetchContext) -> Option<String> {
-        // Try keychain first
+        if let Some(key) = exactobar_store::get_api_key(exactobar_store::keychain::providers::CODEX)
+        {
+            return Some(key);
+        }
+
+        // Then the OpenAI keychain entry
         if let Ok(Some(key)) = ctx.keychain.get(services::OPENAI, accounts::API_KEY).await {
             return Some(key);
         }
@@ -280,7 +288,7 @@ impl FetchStrategy for CodexApiStrategy {
         // OpenAI doesn't have a direct usage API endpoint that works with API keys
         // in the same way - the usage endpoint requires organization-level access.
         // For now, we return a placeholder indicating the API key is valid.
-        let url = format!("{}/models", self.api_base);
+        let url = format!("{}/models", ctx.api_base(self.api_base));
         let auth_header = format!("Bearer {}", api_key);
 
         let response = ctx
@@ -0,0 +1,382 @@
+//! CLI version compatibility.
+//!
+//! The CLI strategies parse output that changes between CLI releases. Each
+//! descriptor lists the versions its parsers are known to work with
+//! ([`CliConfig::compatible_versions`]), and [`check_cli`] runs the CLI's
+//! version command to compare the installed one against them:
+//!
+//! - older than the range: the CLI probably lacks a command we run, so the
+//!   user should update it
+//! - newer than the range: it may work, but its output hasn't been tested
+//!
+//! Parsers that support several output formats use the detected version to
+//! pick one (see [`crate::claude::UsageLayout`]).
+//!
+//! Versions are cached per binary and its modification time, so the version
+//! command runs once per install.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::discovery::{find_binary, path_including};
+use regex::Regex;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::descriptor::CliConfig;
+use crate::registry::ProviderRegistry;
+
+/// How long a version command may run.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The first `major.minor[.patch]` in a version command's output.
+static VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").unwrap());
+
+/// A binary and its modification time.
+type InstallKey = (PathBuf, Option<SystemTime>);
+
+/// Detected versions per install.
+static DETECTED: LazyLock<Mutex<HashMap<InstallKey, Option<CliVersion>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// ============================================================================
+// Versions
+// ============================================================================
+
+/// A CLI's `major.minor.patch` version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct CliVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl CliVersion {
+    /// Creates a version.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Reads the first version number in a version command's output, like
+    /// `2.0.14 (Claude Code)`, `codex-cli 0.46.0` or `gh version 2.40.1`.
+    pub fn parse(output: &str) -> Option<Self> {
+        let caps = VERSION_RE.captures(output)?;
+        let part = |i: usize| caps.get(i).map_or(Some(0), |m| m.as_str().parse().ok());
+        Some(Self::new(part(1)?, part(2)?, part(3)?))
+    }
+}
+
+impl fmt::Display for CliVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The CLI versions a provider's parsers are known to work with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VersionRange {
+    /// Oldest supported version.
+    pub min: Option<CliVersion>,
+    /// First version that hasn't been tested.
+    pub below: Option<CliVersion>,
+}
+
+impl VersionRange {
+    /// Every version.
+    pub const ANY: Self = Self {
+        min: None,
+        below: None,
+    };
+
+    /// Versions from `min` up to, not including, `below`.
+    pub const fn between(min: CliVersion, below: CliVersion) -> Self {
+        Self {
+            min: Some(min),
+            below: Some(below),
+        }
+    }
+
+    /// Returns how `version` relates to the range.
+    pub fn status(&self, version: CliVersion) -> VersionStatus {
+        if self.min.is_some_and(|min| version < min) {
+            VersionStatus::TooOld
+        } else if self.below.is_some_and(|below| version >= below) {
+            VersionStatus::Untested
+        } else {
+            VersionStatus::Compatible
+        }
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.below) {
+            (None, None) => f.write_str("any"),
+            (Some(min), None) => write!(f, ">= {min}"),
+            (None, Some(below)) => write!(f, "< {below}"),
+            (Some(min), Some(below)) => write!(f, ">= {min}, < {below}"),
+        }
+    }
+}
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+/// How an installed CLI's version relates to the supported range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionStatus {
+    /// Within the range.
+    Compatible,
+    /// Older than the oldest supported version.
+    TooOld,
+    /// Newer than the tested versions.
+    Untested,
+    /// The version command failed or printed no version.
+    Unknown,
+}
+
+/// The result of checking an installed CLI's version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionCheck {
+    /// CLI name.
+    pub cli: &'static str,
+    /// Where the CLI was found.
+    pub binary: PathBuf,
+    /// Installed version, if it could be read.
+    pub version: Option<CliVersion>,
+    /// Supported versions.
+    #[serde(skip)]
+    pub range: VersionRange,
+    /// How the version relates to the range.
+    pub status: VersionStatus,
+}
+
+impl VersionCheck {
+    /// Returns true unless the CLI is known to be outside the range.
+    pub fn is_compatible(&self) -> bool {
+        matches!(
+            self.status,
+            VersionStatus::Compatible | VersionStatus::Unknown
+        )
+    }
+
+    /// Describes the problem with the installed version, if there is one.
+    pub fn warning(&self) -> Option<String> {
+        let version = self.version?;
+        match self.status {
+            VersionStatus::TooOld => Some(format!(
+                "{} {version} is older than supported ({}); update it",
+                self.cli, self.range
+            )),
+            VersionStatus::Untested => Some(format!(
+                "{} {version} is newer than the tested versions ({}); usage may not parse",
+                self.cli, self.range
+            )),
+            VersionStatus::Compatible | VersionStatus::Unknown => None,
+        }
+    }
+}
+
+/// Checks the installed version of a CLI, running its version command if
+/// this install hasn't been checked yet.
+///
+/// Returns `None` if the descriptor has no CLI or it isn't installed.
+/// Blocks while the version command runs.
+pub fn check_cli(cli: &CliConfig) -> Option<VersionCheck> {
+    check(cli, true)
+}
+
+/// Like [`check_cli`], but only uses versions already detected, so it
+/// never blocks on the CLI.
+pub fn cached_check(cli: &CliConfig) -> Option<VersionCheck> {
+    check(cli, false)
+}
+
+/// Checks the installed version of a provider's CLI. See [`check_cli`].
+pub fn check_provider(provider: ProviderKind) -> Option<VersionCheck> {
+    check_cli(&ProviderRegistry::get(provider)?.cli)
+}
+
+fn check(cli: &CliConfig, detect: bool) -> Option<VersionCheck> {
+    if cli.name.is_empty() {
+        return None;
+    }
+    let binary = find_binary(cli.name)?;
+    let key = (binary.clone(), modified(&binary));
+
+    let cached = DETECTED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+        .copied();
+    let version = match cached {
+        Some(version) => version,
+        None if detect => {
+            let version = detect_version(&binary, cli.version_args);
+            DETECTED
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, version);
+            version
+        }
+        None => return None,
+    };
+
+    let range = cli.compatible_versions;
+    let status = version.map_or(VersionStatus::Unknown, |v| range.status(v));
+    Some(VersionCheck {
+        cli: cli.name,
+        binary,
+        version,
+        range,
+        status,
+    })
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Runs `binary` with `args` and reads the version it prints.
+fn detect_version(binary: &Path, args: &[&str]) -> Option<CliVersion> {
+    let mut cmd = Command::new(binary);
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    if let Some(path) = path_including(binary) {
+        cmd.env("PATH", path);
+    }
+    let mut child = cmd.spawn().ok()?;
+
+    // Version output is a line or two, so it fits in the pipe while we wait
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < VERSION_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            _ => {
+                debug!(binary = %binary.display(), "Version command didn't finish");
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    let version = CliVersion::parse(&output);
+    debug!(binary = %binary.display(), version = ?version, "Detected CLI version");
+    version
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            CliVersion::parse("2.0.14 (Claude Code)"),
+            Some(CliVersion::new(2, 0, 14))
+        );
+        assert_eq!(
+            CliVersion::parse("codex-cli 0.46.0"),
+            Some(CliVersion::new(0, 46, 0))
+        );
+        assert_eq!(
+            CliVersion::parse("gh version 2.40.1 (2023-12-13)\nhttps://github.com/cli/cli"),
+            Some(CliVersion::new(2, 40, 1))
+        );
+        assert_eq!(
+            CliVersion::parse("kiro 1.2"),
+            Some(CliVersion::new(1, 2, 0))
+        );
+        assert_eq!(CliVersion::parse("unknown"), None);
+        assert!(CliVersion::new(1, 10, 0) > CliVersion::new(1, 9, 3));
+    }
+
+    #[test]
+    fn test_version_range() {
+        let range = VersionRange::between(CliVersion::new(1, 0, 0), CliVersion::new(3, 0, 0));
+        assert_eq!(
+            range.status(CliVersion::new(0, 9, 9)),
+            VersionStatus::TooOld
+        );
+        assert_eq!(
+            range.status(CliVersion::new(1, 0, 0)),
+            VersionStatus::Compatible
+        );
+        assert_eq!(
+            range.status(CliVersion::new(3, 0, 0)),
+            VersionStatus::Untested
+        );
+        assert_eq!(
+            VersionRange::ANY.status(CliVersion::new(0, 0, 1)),
+            VersionStatus::Compatible
+        );
+        assert_eq!(range.to_string(), ">= 1.0.0, < 3.0.0");
+    }
+
+    #[test]
+    fn test_warning() {
+        let check = VersionCheck {
+            cli: "claude",
+            binary: PathBuf::from("/usr/local/bin/claude"),
+            version: Some(CliVersion::new(0, 2, 9)),
+            range: VersionRange::between(CliVersion::new(1, 0, 0), CliVersion::new(3, 0, 0)),
+            status: VersionStatus::TooOld,
+        };
+        assert!(!check.is_compatible());
+        assert_eq!(
+            check.warning().unwrap(),
+            "claude 0.2.9 is older than supported (>= 1.0.0, < 3.0.0); update it"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_cli() {
+        let cli = CliConfig {
+            name: "sh",
+            version_args: &["-c", "echo 'sh 4.2.0'"],
+            compatible_versions: VersionRange::between(
+                CliVersion::new(4, 0, 0),
+                CliVersion::new(4, 1, 0),
+            ),
+            ..CliConfig::default()
+        };
+        assert_eq!(cached_check(&cli), None);
+
+        let check = check_cli(&cli).unwrap();
+        assert_eq!(check.version, Some(CliVersion::new(4, 2, 0)));
+        assert_eq!(check.status, VersionStatus::Untested);
+        assert_eq!(cached_check(&cli), Some(check));
+
+        let missing = CliConfig {
+            name: "exactobar-missing-cli",
+            ..CliConfig::default()
+        };
+        assert_eq!(check_cli(&missing), None);
+    }
+}
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::{CopilotApiStrategy, CopilotEnvStrategy};
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn copilot_descriptor() -> ProviderDescriptor {
@@ -74,5 +75,6 @@ fn copilot_cli_config() -> CliConfig {
         aliases: &["copilot"],
         version_args: &["--version"],
         usage_args: &["copilot", "usage"],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -53,5 +53,6 @@ pub use descriptor::copilot_descriptor;
 pub use device_flow::{AccessTokenResponse, CopilotDeviceFlow, DeviceFlowResult, DeviceFlowStart};
 pub use error::CopilotError;
 pub use fetcher::{CopilotDataSource, CopilotUsageFetcher};
+pub(crate) use strategies::USAGE_ENDPOINT;
 pub use strategies::{CopilotApiStrategy, CopilotEnvStrategy};
 pub use token_store::CopilotTokenStore;
@@ -9,10 +9,13 @@ use exactobar_fetch::{
 };
 use tracing::{debug, instrument};
 
-use super::parser::parse_copilot_response;
+use crate::recording::{self, RawFormat};
 
 const COPILOT_API_BASE: &str = "https://api.github.com";
 
+/// Copilot usage endpoint.
+pub(crate) const USAGE_ENDPOINT: &str = "/copilot/usage";
+
 // ============================================================================
 // API Strategy (Device Flow OAuth)
 // ============================================================================
@@ -81,7 +84,7 @@ impl FetchStrategy for CopilotApiStrategy {
             .await
             .ok_or_else(|| FetchError::AuthenticationFailed("No GitHub token".to_string()))?;
 
-        let url = format!("{}/copilot/usage", self.api_base);
+        let url = format!("{}{}", ctx.api_base(self.api_base), USAGE_ENDPOINT);
         let auth_header = format!("Bearer {}", token);
 
         let response = ctx
@@ -108,7 +111,9 @@ impl FetchStrategy for CopilotApiStrategy {
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_copilot_response(&body)?;
+        recording::record(RawFormat::Copilot, &body);
+
+        let snapshot = RawFormat::Copilot.parse(&body)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -163,7 +168,7 @@ impl FetchStrategy for CopilotEnvStrategy {
         let token = Self::get_env_token()
             .ok_or_else(|| FetchError::AuthenticationFailed("No env token".to_string()))?;
 
-        let url = format!("{}/copilot/usage", COPILOT_API_BASE);
+        let url = format!("{}{}", ctx.api_base(COPILOT_API_BASE), USAGE_ENDPOINT);
         let auth_header = format!("Bearer {}", token);
 
         let response = ctx
@@ -184,7 +189,9 @@ impl FetchStrategy for CopilotEnvStrategy {
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_copilot_response(&body)?;
+        recording::record(RawFormat::Copilot, &body);
+
+        let snapshot = RawFormat::Copilot.parse(&body)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
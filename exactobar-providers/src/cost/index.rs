@@ -0,0 +1,436 @@
+//! Incremental log index.
+//!
+//! For every log file, the index stores how many bytes have been parsed, a
+//! checksum of the file's head (to notice truncation or replacement), the
+//! parser's per-file state, and the usage parsed so far bucketed by day and
+//! model. Buckets keep raw token counts rather than costs, so a pricing
+//! change still reprices the whole history.
+//!
+//! Index files live in `<cache dir>/cost_index/<provider>.json`.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::{ParseFn, ScanProgress, UsageRecord};
+use crate::pricing::TokenCounts;
+
+/// Bump when the bucket format or parser semantics change.
+const INDEX_VERSION: u32 = 1;
+
+/// Number of leading bytes checksummed to detect a replaced file.
+const HEAD_LEN: u64 = 4096;
+
+// ============================================================================
+// Log Index
+// ============================================================================
+
+/// Parse progress and bucketed usage for a provider's log files.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LogIndex {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    files: HashMap<PathBuf, FileIndex>,
+}
+
+impl LogIndex {
+    /// Returns the index path for a provider (by CLI name).
+    pub fn default_path(provider: &str) -> PathBuf {
+        exactobar_store::default_cost_index_dir().join(format!("{provider}.json"))
+    }
+
+    /// Loads an index, starting fresh if it is missing, corrupt or outdated.
+    pub fn load(path: &Path) -> Self {
+        let index = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok());
+
+        match index {
+            Some(index) if index.version == INDEX_VERSION => index,
+            _ => {
+                debug!(path = %path.display(), "Starting new cost index");
+                Self::default()
+            }
+        }
+    }
+
+    /// Saves the index atomically (temp file + rename).
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        self.version = INDEX_VERSION;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string(self)?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, json)?;
+        std::fs::rename(&temp_path, path)
+    }
+
+    /// Returns the number of indexed files.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Parses new data in `files`, dropping entries for files that are gone.
+    ///
+    /// `progress` is called after each file. Returns false if `cancel` was
+    /// set; files parsed up to that point keep their progress, and the
+    /// rest are left as they were.
+    pub(super) fn update(
+        &mut self,
+        files: &[PathBuf],
+        parse: ParseFn,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(ScanProgress),
+    ) -> bool {
+        let mut updated = HashMap::with_capacity(files.len());
+
+        for (done, path) in files.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                debug!(done, total = files.len(), "Log scan cancelled");
+                updated.extend(self.files.drain());
+                self.files = updated;
+                return false;
+            }
+
+            let mut entry = self.files.remove(path).unwrap_or_default();
+            if let Err(e) = entry.update(path, parse) {
+                warn!(path = %path.display(), error = %e, "Failed to index log file");
+            }
+            updated.insert(path.clone(), entry);
+
+            progress(ScanProgress {
+                files_done: done + 1,
+                files_total: files.len(),
+            });
+        }
+
+        self.files = updated;
+        true
+    }
+
+    /// Calls `f` with one record per (day, model) bucket.
+    pub(super) fn for_each_record(&self, mut f: impl FnMut(&UsageRecord)) {
+        for bucket in self.files.values().flat_map(|file| &file.buckets) {
+            f(&bucket.to_record());
+        }
+    }
+}
+
+// ============================================================================
+// File Index
+// ============================================================================
+
+/// Parse progress for one log file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileIndex {
+    /// Bytes parsed so far.
+    offset: u64,
+    /// Length of the checksummed head.
+    head_len: u64,
+    /// FNV-1a checksum of the first `head_len` bytes.
+    head_checksum: u64,
+    /// Parser state carried between scans.
+    #[serde(default)]
+    state: serde_json::Value,
+    /// Usage parsed so far.
+    #[serde(default)]
+    buckets: Vec<UsageBucket>,
+}
+
+impl FileIndex {
+    /// Parses whatever was appended since the last update.
+    fn update(&mut self, path: &Path, parse: ParseFn) -> io::Result<()> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+
+        if !self.matches(&mut file, len)? {
+            debug!(path = %path.display(), "Log file changed, re-indexing");
+            *self = Self::default();
+        }
+        if len == self.offset {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let content = match std::str::from_utf8(&bytes) {
+            Ok(content) => content,
+            // Stop at a split or invalid character; it's retried next scan
+            Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default(),
+        };
+
+        let mut buckets: HashMap<(NaiveDate, Option<String>), UsageBucket> = self
+            .buckets
+            .drain(..)
+            .map(|b| ((b.date, b.model.clone()), b))
+            .collect();
+        let consumed = parse(content, &mut self.state, &mut |record| {
+            let date = record.timestamp.date_naive();
+            buckets
+                .entry((date, record.model.clone()))
+                .or_insert_with(|| UsageBucket::new(date, record.model.clone()))
+                .add(&record);
+        });
+
+        self.offset += consumed as u64;
+        self.buckets = buckets.into_values().collect();
+        self.buckets
+            .sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.model.cmp(&b.model)));
+
+        if self.head_len < HEAD_LEN.min(self.offset) {
+            self.head_len = HEAD_LEN.min(self.offset);
+            self.head_checksum = head_checksum(&mut file, self.head_len)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if `file` still starts with the data indexed so far.
+    fn matches(&self, file: &mut File, len: u64) -> io::Result<bool> {
+        if len < self.offset {
+            return Ok(false);
+        }
+        if self.head_len == 0 {
+            return Ok(true);
+        }
+        Ok(head_checksum(file, self.head_len)? == self.head_checksum)
+    }
+}
+
+/// Checksums the first `len` bytes of a file.
+fn head_checksum(file: &mut File, len: u64) -> io::Result<u64> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut head = Vec::new();
+    file.take(len).read_to_end(&mut head)?;
+    Ok(fnv1a(&head))
+}
+
+/// 64-bit FNV-1a hash (stable across releases, unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// ============================================================================
+// Usage Bucket
+// ============================================================================
+
+/// Usage for one model on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct UsageBucket {
+    date: NaiveDate,
+    model: Option<String>,
+    input: u64,
+    output: u64,
+    cache_read: u64,
+    cache_write: u64,
+    reasoning: u64,
+    total_tokens: u64,
+    cost_usd: Option<f64>,
+}
+
+impl UsageBucket {
+    fn new(date: NaiveDate, model: Option<String>) -> Self {
+        Self {
+            date,
+            model,
+            input: 0,
+            output: 0,
+            cache_read: 0,
+            cache_write: 0,
+            reasoning: 0,
+            total_tokens: 0,
+            cost_usd: None,
+        }
+    }
+
+    fn add(&mut self, record: &UsageRecord) {
+        self.input += record.tokens.input;
+        self.output += record.tokens.output;
+        self.cache_read += record.tokens.cache_read;
+        self.cache_write += record.tokens.cache_write;
+        self.reasoning += record.reasoning_tokens;
+        self.total_tokens += record.total_tokens();
+        if let Some(cost) = record.cost_usd {
+            self.cost_usd = Some(self.cost_usd.unwrap_or(0.0) + cost);
+        }
+    }
+
+    fn to_record(&self) -> UsageRecord {
+        UsageRecord {
+            timestamp: self.date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+            model: self.model.clone(),
+            tokens: TokenCounts {
+                input: self.input,
+                output: self.output,
+                cache_read: self.cache_read,
+                cache_write: self.cache_write,
+            },
+            reasoning_tokens: self.reasoning,
+            total_tokens: Some(self.total_tokens),
+            cost_usd: self.cost_usd,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::JSONL_FORMAT;
+    use std::io::Write;
+
+    fn line(day: u32, input: u64) -> String {
+        format!(
+            "{{\"timestamp\":\"2025-07-{day:02}T10:00:00Z\",\"model\":\"gpt-5\",\"input_tokens\":{input}}}\n"
+        )
+    }
+
+    fn update(index: &mut LogIndex, files: &[PathBuf]) -> bool {
+        index.update(
+            files,
+            JSONL_FORMAT.parse,
+            &AtomicBool::new(false),
+            &mut |_| {},
+        )
+    }
+
+    fn totals(index: &LogIndex) -> (u64, usize) {
+        let mut tokens = 0;
+        let mut records = 0;
+        index.for_each_record(|r| {
+            tokens += r.tokens.input;
+            records += 1;
+        });
+        (tokens, records)
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("exactobar-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_incremental_append() {
+        let path = temp_log("append.jsonl");
+        std::fs::write(&path, line(1, 10) + &line(1, 20)).unwrap();
+
+        let mut index = LogIndex::default();
+        let files = vec![path.clone()];
+        update(&mut index, &files);
+        assert_eq!(totals(&index), (30, 1));
+
+        // Append a full line and a partial one
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        write!(file, "{}{{\"timestamp\":", line(2, 5)).unwrap();
+        update(&mut index, &files);
+        assert_eq!(totals(&index), (35, 2));
+
+        // Unchanged file is not re-counted
+        update(&mut index, &files);
+        assert_eq!(totals(&index), (35, 2));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replaced_file_is_reindexed() {
+        let path = temp_log("replaced.jsonl");
+        std::fs::write(&path, line(1, 10) + &line(1, 20)).unwrap();
+
+        let mut index = LogIndex::default();
+        let files = vec![path.clone()];
+        update(&mut index, &files);
+
+        // Truncated and rewritten with different content
+        std::fs::write(&path, line(3, 7)).unwrap();
+        update(&mut index, &files);
+        assert_eq!(totals(&index), (7, 1));
+
+        // Removed files are dropped
+        update(&mut index, &[]);
+        assert_eq!(index.file_count(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_progress_and_cancel() {
+        let first = temp_log("cancel-1.jsonl");
+        let second = temp_log("cancel-2.jsonl");
+        std::fs::write(&first, line(1, 10)).unwrap();
+        std::fs::write(&second, line(2, 20)).unwrap();
+        let files = vec![first.clone(), second.clone()];
+
+        // Cancel after the first file
+        let cancel = AtomicBool::new(false);
+        let mut reports = Vec::new();
+        let mut index = LogIndex::default();
+        let finished = index.update(&files, JSONL_FORMAT.parse, &cancel, &mut |p| {
+            reports.push(p);
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert!(!finished);
+        assert_eq!(
+            reports,
+            vec![ScanProgress {
+                files_done: 1,
+                files_total: 2
+            }]
+        );
+        assert_eq!(totals(&index), (10, 1));
+
+        // The next scan picks up where the cancelled one stopped
+        assert!(update(&mut index, &files));
+        assert_eq!(totals(&index), (30, 2));
+
+        std::fs::remove_file(&first).unwrap();
+        std::fs::remove_file(&second).unwrap();
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = temp_log("roundtrip.jsonl");
+        std::fs::write(&path, line(1, 10)).unwrap();
+        let index_path = temp_log("index.json");
+
+        let mut index = LogIndex::default();
+        update(&mut index, std::slice::from_ref(&path));
+        index.save(&index_path).unwrap();
+
+        let loaded = LogIndex::load(&index_path);
+        assert_eq!(loaded.file_count(), 1);
+        assert_eq!(totals(&loaded), (10, 1));
+
+        // Outdated versions start fresh
+        std::fs::write(
+            &index_path,
+            r#"{"version":0,"files":{"/tmp/x.jsonl":{"offset":1,"head_len":0,"head_checksum":0}}}"#,
+        )
+        .unwrap();
+        assert_eq!(LogIndex::load(&index_path).file_count(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&index_path).unwrap();
+    }
+}
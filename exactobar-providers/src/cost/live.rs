@@ -0,0 +1,195 @@
+//! Live cost tracking.
+//!
+//! A [`LiveCostWatcher`] watches a provider's log directory and rescans it
+//! whenever a log file changes. Scans are incremental, so each update only
+//! parses the lines appended since the previous one.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use exactobar_store::CostUsageSnapshot;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{debug, warn};
+
+use super::{LogIndex, scan_provider};
+use crate::descriptor::TokenCostConfig;
+use crate::pricing::PricingTable;
+
+/// Quiet period after a change before rescanning, so a burst of writes
+/// causes a single rescan.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// ============================================================================
+// Live Cost Watcher
+// ============================================================================
+
+/// Rescans a provider's logs as they change.
+///
+/// The watcher runs on its own thread and stops when dropped.
+pub struct LiveCostWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for LiveCostWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LiveCostWatcher").finish_non_exhaustive()
+    }
+}
+
+impl LiveCostWatcher {
+    /// Starts watching a provider's log directory.
+    ///
+    /// The index at `index_path` is loaded on the watcher thread and saved
+    /// after each rescan; `on_update` receives the new snapshot. Nothing is
+    /// reported until the first change, so callers typically run
+    /// [`scan_provider`] once up front. Returns `Ok(None)` when the provider
+    /// doesn't track token cost or has no logs on this machine.
+    pub fn spawn<F>(
+        config: &'static TokenCostConfig,
+        index_path: PathBuf,
+        days: u32,
+        pricing: PricingTable,
+        mut on_update: F,
+    ) -> io::Result<Option<Self>>
+    where
+        F: FnMut(CostUsageSnapshot) + Send + 'static,
+    {
+        if !config.supports_token_cost {
+            return Ok(None);
+        }
+        let Some(log_dir) = config.log_directory.and_then(|dir| dir()) else {
+            return Ok(None);
+        };
+        if !log_dir.exists() {
+            return Ok(None);
+        }
+
+        let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+        let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+        watcher
+            .watch(&log_dir, RecursiveMode::Recursive)
+            .map_err(io::Error::other)?;
+        debug!(dir = %log_dir.display(), "Watching logs for live cost");
+
+        std::thread::Builder::new()
+            .name("live-cost".to_string())
+            .spawn(move || {
+                let mut index = LogIndex::load(&index_path);
+
+                // The channel closes when the watcher is dropped
+                while let Ok(event) = rx.recv() {
+                    if !is_log_change(&event) {
+                        continue;
+                    }
+                    loop {
+                        match rx.recv_timeout(DEBOUNCE) {
+                            Ok(_) => {}
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+
+                    match scan_provider(config, &mut index, days, &pricing) {
+                        Ok(Some(snapshot)) => {
+                            if let Err(e) = index.save(&index_path) {
+                                warn!(error = %e, "Failed to save cost index");
+                            }
+                            on_update(snapshot);
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!(error = %e, "Live cost scan failed"),
+                    }
+                }
+            })?;
+
+        Ok(Some(Self { _watcher: watcher }))
+    }
+}
+
+/// Returns true for events that may change a log's contents.
+fn is_log_change(event: &notify::Result<Event>) -> bool {
+    event.as_ref().is_ok_and(|e| {
+        matches!(
+            e.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        )
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn log_dir() -> Option<PathBuf> {
+        Some(std::env::temp_dir().join(format!("exactobar-live-{}", std::process::id())))
+    }
+
+    static CONFIG: TokenCostConfig = TokenCostConfig {
+        supports_token_cost: true,
+        log_directory: Some(log_dir),
+        log_format: None,
+    };
+
+    #[test]
+    fn test_reports_appended_usage() {
+        let dir = log_dir().unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("usage.jsonl");
+        std::fs::write(&log, "").unwrap();
+        let index_path =
+            std::env::temp_dir().join(format!("exactobar-live-index-{}.json", std::process::id()));
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = LiveCostWatcher::spawn(
+            &CONFIG,
+            index_path.clone(),
+            30,
+            PricingTable::builtin(),
+            move |snapshot| {
+                let _ = tx.send(snapshot);
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+        writeln!(
+            file,
+            "{{\"timestamp\":\"{}\",\"input_tokens\":42}}",
+            chrono::Utc::now().to_rfc3339()
+        )
+        .unwrap();
+
+        let snapshot = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(snapshot.total_tokens, 42);
+
+        drop(watcher);
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_file(&index_path);
+    }
+
+    #[test]
+    fn test_unsupported_provider() {
+        static UNSUPPORTED: TokenCostConfig = TokenCostConfig {
+            supports_token_cost: false,
+            log_directory: None,
+            log_format: None,
+        };
+
+        let watcher = LiveCostWatcher::spawn(
+            &UNSUPPORTED,
+            PathBuf::new(),
+            30,
+            PricingTable::builtin(),
+            |_| {},
+        );
+        assert!(watcher.unwrap().is_none());
+    }
+}
@@ -0,0 +1,532 @@
+//! Token cost aggregation from local usage logs.
+//!
+//! Log readers turn provider-specific log lines into [`UsageRecord`]s and
+//! feed them to a [`CostAggregator`], which prices them with the
+//! [`PricingTable`] and builds the [`CostUsageSnapshot`] shown by
+//! `exactobar cost`.
+//!
+//! Providers whose logs are flat JSONL files with token counts per line use
+//! the generic [`JSONL_FORMAT`]. Providers with their own format set
+//! [`TokenCostConfig::log_format`].
+//!
+//! Scans are incremental: a [`LogIndex`] remembers how far each file has
+//! been parsed, so only lines appended since the last scan are read. A
+//! [`LiveCostWatcher`] builds on that to rescan as the logs change.
+//!
+//! [`Reconciliation`] checks computed costs against a vendor invoice export.
+
+mod index;
+mod live;
+mod reconcile;
+
+pub use index::LogIndex;
+pub use live::LiveCostWatcher;
+pub use reconcile::{
+    Invoice, InvoiceError, InvoiceFormat, InvoiceLine, ReconcileRow, Reconciliation,
+};
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use exactobar_core::ModelBreakdown;
+use exactobar_store::{CostUsageSnapshot, DailyCost, TokenCategories};
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::descriptor::TokenCostConfig;
+use crate::pricing::{PricingTable, TokenCounts};
+
+// ============================================================================
+// Log Format
+// ============================================================================
+
+/// Parses complete records from `content`, returning the bytes consumed.
+///
+/// Parsing stops before a trailing partial record so it can be re-read once
+/// the writer finishes it. `state` carries per-file context (such as the
+/// current model) between incremental parses and is persisted in the index.
+pub type ParseFn = fn(&str, &mut serde_json::Value, &mut dyn FnMut(UsageRecord)) -> usize;
+
+/// How to find and parse a provider's usage logs.
+#[derive(Debug, Clone, Copy)]
+pub struct LogFormat {
+    /// Lists the log files under the provider's log directory.
+    pub list_files: fn(&Path) -> Vec<PathBuf>,
+    /// Parses usage records from a log file.
+    pub parse: ParseFn,
+}
+
+/// Returns `content` up to and including its last newline.
+///
+/// Line-based parsers use this to leave a partially written line for the
+/// next scan.
+pub fn complete_lines(content: &str) -> &str {
+    content.rfind('\n').map_or("", |end| &content[..=end])
+}
+
+// ============================================================================
+// Usage Record
+// ============================================================================
+
+/// A single priced unit of usage (usually one request or turn).
+#[derive(Debug, Clone, Default)]
+pub struct UsageRecord {
+    /// When the usage happened.
+    pub timestamp: DateTime<Utc>,
+    /// Model name, if the log records one.
+    pub model: Option<String>,
+    /// Token counts by billing category.
+    pub tokens: TokenCounts,
+    /// Reasoning tokens (already included in `tokens.output`).
+    pub reasoning_tokens: u64,
+    /// Total tokens as logged, if different from the category sum.
+    pub total_tokens: Option<u64>,
+    /// Cost as logged, used when the model has no price.
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageRecord {
+    /// Returns the logged total, or input + output + cache.
+    pub fn total_tokens(&self) -> u64 {
+        self.total_tokens.unwrap_or(
+            self.tokens.input
+                + self.tokens.output
+                + self.tokens.cache_read
+                + self.tokens.cache_write,
+        )
+    }
+
+    /// Prices the record at the rate in effect on its day, falling back to the logged cost.
+    pub fn cost(&self, pricing: &PricingTable) -> f64 {
+        self.model
+            .as_deref()
+            .and_then(|model| pricing.cost_for(model, self.timestamp.date_naive(), &self.tokens))
+            .or(self.cost_usd)
+            .unwrap_or(0.0)
+    }
+}
+
+// ============================================================================
+// Aggregator
+// ============================================================================
+
+/// Accumulates usage records into a cost snapshot.
+#[derive(Debug)]
+pub struct CostAggregator<'a> {
+    pricing: &'a PricingTable,
+    cutoff: DateTime<Utc>,
+    total_tokens: u64,
+    total_cost: f64,
+    tokens: TokenCategories,
+    daily: HashMap<NaiveDate, (u64, f64)>,
+    models: HashMap<String, ModelBreakdown>,
+}
+
+impl<'a> CostAggregator<'a> {
+    /// Creates an aggregator covering the last `days` days.
+    pub fn new(pricing: &'a PricingTable, days: u32) -> Self {
+        Self::since(
+            pricing,
+            Utc::now() - chrono::Duration::days(i64::from(days)),
+        )
+    }
+
+    /// Creates an aggregator covering usage at or after `cutoff`.
+    pub fn since(pricing: &'a PricingTable, cutoff: DateTime<Utc>) -> Self {
+        Self {
+            pricing,
+            cutoff,
+            total_tokens: 0,
+            total_cost: 0.0,
+            tokens: TokenCategories::default(),
+            daily: HashMap::new(),
+            models: HashMap::new(),
+        }
+    }
+
+    /// Adds a record. Records from days before the cutoff day are ignored.
+    pub fn add(&mut self, record: &UsageRecord) {
+        if record.timestamp.date_naive() < self.cutoff.date_naive() {
+            return;
+        }
+
+        let tokens = record.total_tokens();
+        let cost = record.cost(self.pricing);
+
+        self.total_tokens += tokens;
+        self.total_cost += cost;
+
+        self.tokens.input += record.tokens.input;
+        self.tokens.output += record.tokens.output;
+        self.tokens.cache_read += record.tokens.cache_read;
+        self.tokens.cache_creation += record.tokens.cache_write;
+        self.tokens.reasoning += record.reasoning_tokens;
+
+        let day = self
+            .daily
+            .entry(record.timestamp.date_naive())
+            .or_insert((0, 0.0));
+        day.0 += tokens;
+        day.1 += cost;
+
+        let model = record.model.as_deref().unwrap_or("unknown");
+        let breakdown = self
+            .models
+            .entry(model.to_string())
+            .or_insert_with(|| ModelBreakdown::new(model));
+        add_tokens(&mut breakdown.input_tokens, record.tokens.input);
+        add_tokens(&mut breakdown.output_tokens, record.tokens.output);
+        add_tokens(&mut breakdown.cache_read_tokens, record.tokens.cache_read);
+        add_tokens(
+            &mut breakdown.cache_creation_tokens,
+            record.tokens.cache_write,
+        );
+        add_tokens(&mut breakdown.reasoning_tokens, record.reasoning_tokens);
+        breakdown.cost_usd = Some(breakdown.cost_usd.unwrap_or(0.0) + cost);
+    }
+
+    /// Builds the snapshot.
+    pub fn finish(self) -> CostUsageSnapshot {
+        let mut daily: Vec<DailyCost> = self
+            .daily
+            .into_iter()
+            .map(|(date, (tokens, cost))| DailyCost {
+                date: date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+                tokens,
+                cost_usd: cost,
+            })
+            .collect();
+        daily.sort_by_key(|d| d.date);
+
+        let mut models: Vec<ModelBreakdown> = self.models.into_values().collect();
+        models.sort_by(|a, b| {
+            b.cost_usd
+                .unwrap_or(0.0)
+                .total_cmp(&a.cost_usd.unwrap_or(0.0))
+                .then_with(|| a.model_name.cmp(&b.model_name))
+        });
+
+        CostUsageSnapshot {
+            daily,
+            total_tokens: self.total_tokens,
+            total_cost_usd: self.total_cost,
+            tokens: self.tokens,
+            models,
+            scanned_at: Some(Utc::now()),
+        }
+    }
+}
+
+/// Adds to an optional counter, leaving it unset for zero counts.
+fn add_tokens(total: &mut Option<u64>, count: u64) {
+    if count > 0 {
+        *total = Some(total.unwrap_or(0) + count);
+    }
+}
+
+// ============================================================================
+// Provider Scan
+// ============================================================================
+
+/// Progress of a log scan, reported after each file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Files scanned so far.
+    pub files_done: usize,
+    /// Files to scan in total.
+    pub files_total: usize,
+}
+
+/// Scans a provider's local logs into a cost snapshot.
+///
+/// Only data appended since the last scan is parsed; `index` is updated in
+/// place and should be saved afterwards. Returns `Ok(None)` when the
+/// provider doesn't track token cost or has no logs on this machine.
+pub fn scan_provider(
+    config: &TokenCostConfig,
+    index: &mut LogIndex,
+    days: u32,
+    pricing: &PricingTable,
+) -> io::Result<Option<CostUsageSnapshot>> {
+    scan_provider_with_progress(
+        config,
+        index,
+        days,
+        pricing,
+        &AtomicBool::new(false),
+        &mut |_| {},
+    )
+}
+
+/// Like [`scan_provider`], reporting progress and stopping early on `cancel`.
+///
+/// A cancelled scan returns an [`io::ErrorKind::Interrupted`] error. The
+/// files parsed before cancellation keep their progress in `index`, so
+/// saving it lets the next scan resume from there.
+pub fn scan_provider_with_progress(
+    config: &TokenCostConfig,
+    index: &mut LogIndex,
+    days: u32,
+    pricing: &PricingTable,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(ScanProgress),
+) -> io::Result<Option<CostUsageSnapshot>> {
+    if !config.supports_token_cost {
+        return Ok(None);
+    }
+
+    let Some(log_dir) = config.log_directory.and_then(|dir| dir()) else {
+        return Ok(None);
+    };
+    if !log_dir.exists() {
+        debug!(dir = %log_dir.display(), "Log directory not found");
+        return Ok(None);
+    }
+
+    debug!(dir = %log_dir.display(), "Scanning logs");
+    let format = config.log_format.unwrap_or(JSONL_FORMAT);
+    let files = (format.list_files)(&log_dir);
+    progress(ScanProgress {
+        files_done: 0,
+        files_total: files.len(),
+    });
+    if !index.update(&files, format.parse, cancel, progress) {
+        return Err(io::Error::new(
+            io::ErrorKind::Interrupted,
+            "log scan cancelled",
+        ));
+    }
+
+    let mut aggregator = CostAggregator::new(pricing, days);
+    index.for_each_record(|record| aggregator.add(record));
+
+    Ok(Some(aggregator.finish()))
+}
+
+// ============================================================================
+// Generic JSONL Format
+// ============================================================================
+
+/// Flat JSONL logs with one usage entry per line.
+pub const JSONL_FORMAT: LogFormat = LogFormat {
+    list_files: list_jsonl_files,
+    parse: parse_jsonl,
+};
+
+/// Log entry structure (generic for multiple providers).
+#[derive(Debug, Deserialize)]
+struct LogEntry {
+    #[serde(default)]
+    timestamp: Option<String>,
+
+    #[serde(default)]
+    model: Option<String>,
+
+    #[serde(default)]
+    input_tokens: Option<u64>,
+
+    #[serde(default)]
+    output_tokens: Option<u64>,
+
+    #[serde(default)]
+    total_tokens: Option<u64>,
+
+    #[serde(default, alias = "cache_read_input_tokens")]
+    cache_read_tokens: Option<u64>,
+
+    #[serde(default, alias = "cache_creation_input_tokens")]
+    cache_creation_tokens: Option<u64>,
+
+    #[serde(default, alias = "reasoning_output_tokens")]
+    reasoning_tokens: Option<u64>,
+
+    #[serde(default)]
+    cost_usd: Option<f64>,
+}
+
+impl LogEntry {
+    /// Converts to a record; entries without a valid timestamp are skipped.
+    fn into_record(self) -> Option<UsageRecord> {
+        let timestamp = DateTime::parse_from_rfc3339(self.timestamp.as_deref()?).ok()?;
+
+        Some(UsageRecord {
+            timestamp: timestamp.with_timezone(&Utc),
+            model: self.model,
+            tokens: TokenCounts {
+                input: self.input_tokens.unwrap_or(0),
+                output: self.output_tokens.unwrap_or(0),
+                cache_read: self.cache_read_tokens.unwrap_or(0),
+                cache_write: self.cache_creation_tokens.unwrap_or(0),
+            },
+            reasoning_tokens: self.reasoning_tokens.unwrap_or(0),
+            total_tokens: self.total_tokens,
+            cost_usd: self.cost_usd,
+        })
+    }
+}
+
+/// Lists the `.jsonl` files directly inside `dir`.
+fn list_jsonl_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect()
+}
+
+/// Parses one usage entry per complete line.
+fn parse_jsonl(
+    content: &str,
+    _state: &mut serde_json::Value,
+    emit: &mut dyn FnMut(UsageRecord),
+) -> usize {
+    let complete = complete_lines(content);
+
+    for line in complete.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(record) = serde_json::from_str::<LogEntry>(line)
+            .ok()
+            .and_then(LogEntry::into_record)
+        {
+            emit(record);
+        }
+    }
+
+    complete.len()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u32, model: &str, input: u64, output: u64) -> UsageRecord {
+        UsageRecord {
+            timestamp: NaiveDate::from_ymd_opt(2025, 7, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc(),
+            model: Some(model.to_string()),
+            tokens: TokenCounts {
+                input,
+                output,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn aggregator(pricing: &PricingTable) -> CostAggregator<'_> {
+        let cutoff = NaiveDate::from_ymd_opt(2025, 7, 2)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+        CostAggregator::since(pricing, cutoff)
+    }
+
+    #[test]
+    fn test_log_entry_total_tokens() {
+        let json = r#"{"timestamp":"2025-07-03T00:00:00Z","input_tokens":100,"output_tokens":50}"#;
+        let entry: LogEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.into_record().unwrap().total_tokens(), 150);
+
+        let json = r#"{"timestamp":"2025-07-03T00:00:00Z","input_tokens":100,"output_tokens":50,"total_tokens":200}"#;
+        let entry: LogEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.into_record().unwrap().total_tokens(), 200);
+
+        let json = r#"{"input_tokens":100}"#;
+        let entry: LogEntry = serde_json::from_str(json).unwrap();
+        assert!(entry.into_record().is_none());
+    }
+
+    #[test]
+    fn test_record_repriced_from_table() {
+        let pricing = PricingTable::builtin();
+
+        let json = r#"{"timestamp":"2025-07-01T00:00:00Z","model":"claude-sonnet-4-20250514","input_tokens":1000000,"output_tokens":0,"cache_read_input_tokens":1000000,"cost_usd":99.0}"#;
+        let record = serde_json::from_str::<LogEntry>(json)
+            .unwrap()
+            .into_record()
+            .unwrap();
+        // $3 input + $0.30 cache read, ignoring the stale logged cost
+        assert!((record.cost(&pricing) - 3.3).abs() < 1e-9);
+
+        let unknown = r#"{"timestamp":"2025-07-01T00:00:00Z","model":"mystery","input_tokens":10,"cost_usd":0.5}"#;
+        let record = serde_json::from_str::<LogEntry>(unknown)
+            .unwrap()
+            .into_record()
+            .unwrap();
+        assert!((record.cost(&pricing) - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_aggregator_categories_and_models() {
+        let pricing = PricingTable::builtin();
+        let mut agg = aggregator(&pricing);
+
+        let mut cached = record(3, "gpt-5", 100, 40);
+        cached.tokens.cache_read = 500;
+        cached.reasoning_tokens = 30;
+        agg.add(&cached);
+        agg.add(&cached);
+        agg.add(&record(4, "claude-sonnet-4", 1_000_000, 0));
+        // Before the cutoff
+        agg.add(&record(1, "gpt-5", 1_000_000, 0));
+
+        let snapshot = agg.finish();
+        assert_eq!(snapshot.daily.len(), 2);
+        assert_eq!(snapshot.tokens.cache_read, 1000);
+        assert_eq!(snapshot.tokens.reasoning, 60);
+        assert_eq!(snapshot.tokens.input, 1_000_200);
+        assert_eq!(snapshot.total_tokens, 1_001_280);
+
+        // Most expensive first
+        assert_eq!(snapshot.models[0].model_name, "claude-sonnet-4");
+        let gpt = &snapshot.models[1];
+        assert_eq!(gpt.input_tokens, Some(200));
+        assert_eq!(gpt.reasoning_tokens, Some(60));
+        assert_eq!(gpt.cache_creation_tokens, None);
+    }
+
+    #[test]
+    fn test_parse_jsonl_leaves_partial_line() {
+        let content =
+            "{\"timestamp\":\"2025-07-03T00:00:00Z\",\"input_tokens\":1}\n{\"timestamp\":";
+
+        let mut records = Vec::new();
+        let consumed = parse_jsonl(content, &mut serde_json::Value::Null, &mut |r| {
+            records.push(r);
+        });
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(&content[consumed..], "{\"timestamp\":");
+        assert_eq!(complete_lines("no newline"), "");
+    }
+
+    #[test]
+    fn test_scan_provider_without_logs() {
+        let pricing = PricingTable::builtin();
+        let config = TokenCostConfig::default();
+        let mut index = LogIndex::default();
+        assert!(
+            scan_provider(&config, &mut index, 30, &pricing)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
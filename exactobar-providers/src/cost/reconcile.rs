@@ -0,0 +1,445 @@
+//! Invoice reconciliation.
+//!
+//! Compares a provider's invoice CSV export with the costs ExactoBar
+//! computes from local logs, per day and model. Large differences usually
+//! point at a missing or outdated entry in the pricing table.
+//!
+//! Both OpenAI (`start_time`, `line_item`, `amount_value`) and Anthropic
+//! (`usage_date_utc`, `model`, `cost_usd`) cost exports are recognised, as
+//! is any CSV with `date`, `model` and `cost` columns. Model names on both
+//! sides are matched through the pricing table, so `Claude Sonnet 4` on an
+//! invoice lines up with `claude-sonnet-4-20250514` in the logs.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate};
+use thiserror::Error;
+
+use super::LogIndex;
+use crate::pricing::PricingTable;
+
+/// Accepted headers for the usage date.
+const DATE_COLUMNS: &[&str] = &["usage_date_utc", "start_time", "date", "day", "usage_date"];
+/// Accepted headers for the model.
+const MODEL_COLUMNS: &[&str] = &["model", "model_name", "line_item", "description"];
+/// Accepted headers for the cost.
+const COST_COLUMNS: &[&str] = &["cost_usd", "amount_value", "amount_usd", "cost", "amount"];
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Errors reading an invoice export.
+#[derive(Debug, Error)]
+pub enum InvoiceError {
+    /// Failed to read the file.
+    #[error("Failed to read invoice: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A required column is missing.
+    #[error("Invoice has no {0} column")]
+    MissingColumn(&'static str),
+
+    /// A row could not be parsed.
+    #[error("Invalid invoice row {row}: {message}")]
+    InvalidRow {
+        /// 1-based row number (the header is row 1).
+        row: usize,
+        /// What was wrong with it.
+        message: String,
+    },
+}
+
+// ============================================================================
+// Invoice
+// ============================================================================
+
+/// The vendor an invoice export came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceFormat {
+    /// OpenAI cost export.
+    OpenAi,
+    /// Anthropic console cost export.
+    Anthropic,
+    /// Any other CSV with date, model and cost columns.
+    Generic,
+}
+
+impl InvoiceFormat {
+    /// Guesses the vendor from the header row.
+    fn detect(headers: &[String]) -> Self {
+        let has = |name: &str| headers.iter().any(|h| h == name);
+        if has("usage_date_utc") || has("workspace") {
+            Self::Anthropic
+        } else if has("line_item") || has("amount_value") {
+            Self::OpenAi
+        } else {
+            Self::Generic
+        }
+    }
+
+    /// Returns the CLI name of the provider whose logs match this invoice.
+    pub fn provider_cli_name(self) -> Option<&'static str> {
+        match self {
+            Self::OpenAi => Some("codex"),
+            Self::Anthropic => Some("claude"),
+            Self::Generic => None,
+        }
+    }
+}
+
+/// One billed line of an invoice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    /// Usage day.
+    pub date: NaiveDate,
+    /// Model as named on the invoice.
+    pub model: String,
+    /// Billed amount in USD.
+    pub cost_usd: f64,
+}
+
+/// A parsed invoice export.
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    /// Detected vendor.
+    pub format: InvoiceFormat,
+    /// Billed lines.
+    pub lines: Vec<InvoiceLine>,
+}
+
+impl Invoice {
+    /// Reads an invoice CSV export.
+    pub fn load(path: &Path) -> Result<Self, InvoiceError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses an invoice CSV export.
+    pub fn parse(content: &str) -> Result<Self, InvoiceError> {
+        let mut rows = parse_csv(content).into_iter();
+        let headers: Vec<String> = rows
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect();
+
+        let column = |names: &[&str], what: &'static str| {
+            names
+                .iter()
+                .find_map(|name| headers.iter().position(|h| h == name))
+                .ok_or(InvoiceError::MissingColumn(what))
+        };
+        let date_col = column(DATE_COLUMNS, "date")?;
+        let model_col = column(MODEL_COLUMNS, "model")?;
+        let cost_col = column(COST_COLUMNS, "cost")?;
+
+        let mut lines = Vec::new();
+        for (i, row) in rows.enumerate() {
+            let row_number = i + 2;
+            if row.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            let field = |col: usize| row.get(col).map_or("", |f| f.trim());
+            let invalid = |message: String| InvoiceError::InvalidRow {
+                row: row_number,
+                message,
+            };
+
+            let date = parse_date(field(date_col))
+                .ok_or_else(|| invalid(format!("bad date {:?}", field(date_col))))?;
+            let cost_usd = parse_amount(field(cost_col))
+                .ok_or_else(|| invalid(format!("bad amount {:?}", field(cost_col))))?;
+
+            // OpenAI line items read "gpt-4o-2024-08-06, input"
+            let model = field(model_col).split(',').next().unwrap_or("").trim();
+
+            lines.push(InvoiceLine {
+                date,
+                model: if model.is_empty() {
+                    "unknown".to_string()
+                } else {
+                    model.to_string()
+                },
+                cost_usd,
+            });
+        }
+
+        Ok(Self {
+            format: InvoiceFormat::detect(&headers),
+            lines,
+        })
+    }
+
+    /// Returns the first and last invoiced day.
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first = self.lines.iter().map(|l| l.date).min()?;
+        let last = self.lines.iter().map(|l| l.date).max()?;
+        Some((first, last))
+    }
+}
+
+/// Parses `2025-07-01`, an RFC 3339 timestamp, or Unix seconds.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    if let Some(date) = value
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    {
+        return Some(date);
+    }
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.date_naive());
+    }
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|ts| ts.date_naive())
+}
+
+/// Parses an amount such as `12.5`, `$1,234.56` or `-0.10`.
+fn parse_amount(value: &str) -> Option<f64> {
+    let cleaned: String = value.chars().filter(|c| !matches!(c, '$' | ',')).collect();
+    cleaned.trim().parse().ok()
+}
+
+/// Splits CSV content into rows of fields (RFC 4180 quoting).
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
+
+// ============================================================================
+// Reconciliation
+// ============================================================================
+
+/// Invoiced and computed cost for one model on one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconcileRow {
+    /// Usage day.
+    pub date: NaiveDate,
+    /// Pricing table model (or the raw name if it has no price).
+    pub model: String,
+    /// Amount billed on the invoice.
+    pub invoiced_usd: f64,
+    /// Amount computed from local logs.
+    pub computed_usd: f64,
+}
+
+impl ReconcileRow {
+    /// Returns computed minus invoiced (negative when logs undercount).
+    pub fn difference_usd(&self) -> f64 {
+        self.computed_usd - self.invoiced_usd
+    }
+}
+
+/// Per-day, per-model comparison of an invoice with computed costs.
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    /// Rows ordered by day, then model.
+    pub rows: Vec<ReconcileRow>,
+}
+
+impl Reconciliation {
+    /// Compares an invoice with the usage in `index` over the invoiced days.
+    pub fn new(invoice: &Invoice, index: &LogIndex, pricing: &PricingTable) -> Self {
+        let Some((first, last)) = invoice.date_range() else {
+            return Self::default();
+        };
+
+        let mut rows = BTreeMap::new();
+
+        for line in &invoice.lines {
+            row_for(&mut rows, line.date, &line.model, pricing).invoiced_usd += line.cost_usd;
+        }
+
+        index.for_each_record(|record| {
+            let date = record.timestamp.date_naive();
+            if date >= first && date <= last {
+                let model = record.model.as_deref().unwrap_or("unknown");
+                row_for(&mut rows, date, model, pricing).computed_usd += record.cost(pricing);
+            }
+        });
+
+        Self {
+            rows: rows.into_values().collect(),
+        }
+    }
+
+    /// Total billed on the invoice.
+    pub fn invoiced_total_usd(&self) -> f64 {
+        self.rows.iter().map(|r| r.invoiced_usd).sum()
+    }
+
+    /// Total computed from logs over the same days.
+    pub fn computed_total_usd(&self) -> f64 {
+        self.rows.iter().map(|r| r.computed_usd).sum()
+    }
+
+    /// Rows whose difference exceeds `tolerance_usd`.
+    pub fn discrepancies(&self, tolerance_usd: f64) -> impl Iterator<Item = &ReconcileRow> {
+        self.rows
+            .iter()
+            .filter(move |r| r.difference_usd().abs() > tolerance_usd)
+    }
+}
+
+/// Returns the row for a day and model, creating it if needed.
+fn row_for<'a>(
+    rows: &'a mut BTreeMap<(NaiveDate, String), ReconcileRow>,
+    date: NaiveDate,
+    model: &str,
+    pricing: &PricingTable,
+) -> &'a mut ReconcileRow {
+    let model = canonical_model(model, date, pricing);
+    rows.entry((date, model.clone()))
+        .or_insert_with(|| ReconcileRow {
+            date,
+            model,
+            invoiced_usd: 0.0,
+            computed_usd: 0.0,
+        })
+}
+
+/// Maps a model name to its pricing table entry so invoice and log names
+/// line up.
+fn canonical_model(model: &str, date: NaiveDate, pricing: &PricingTable) -> String {
+    let name = model.trim().to_lowercase().replace(' ', "-");
+    pricing
+        .price_for(&name, date)
+        .map_or(name, |price| price.model.to_lowercase())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::JSONL_FORMAT;
+    use std::sync::atomic::AtomicBool;
+
+    const ANTHROPIC: &str = "\u{feff}usage_date_utc,model,workspace,api_key,cost_usd\n\
+        2025-07-01,Claude Sonnet 4,Default,key-1,3.00\n\
+        2025-07-01,claude-3-5-haiku-20241022,Default,key-1,0.50\n\
+        2025-07-02,Claude Sonnet 4,Default,key-1,\"1,000.25\"\n";
+
+    const OPENAI: &str = "start_time,end_time,line_item,project_id,amount_value,amount_currency\n\
+        1751328000,1751414400,\"gpt-4o-2024-08-06, input\",proj_1,0.25,usd\n\
+        1751328000,1751414400,\"gpt-4o-2024-08-06, output\",proj_1,0.75,usd\n";
+
+    #[test]
+    fn test_parse_anthropic_export() {
+        let invoice = Invoice::parse(ANTHROPIC).unwrap();
+        assert_eq!(invoice.format, InvoiceFormat::Anthropic);
+        assert_eq!(invoice.lines.len(), 3);
+        assert_eq!(invoice.lines[0].model, "Claude Sonnet 4");
+        assert!((invoice.lines[2].cost_usd - 1000.25).abs() < 1e-9);
+        assert_eq!(
+            invoice.date_range(),
+            Some((
+                NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 7, 2).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_openai_export() {
+        let invoice = Invoice::parse(OPENAI).unwrap();
+        assert_eq!(invoice.format, InvoiceFormat::OpenAi);
+        assert_eq!(invoice.format.provider_cli_name(), Some("codex"));
+        assert_eq!(invoice.lines[0].model, "gpt-4o-2024-08-06");
+        assert_eq!(
+            invoice.lines[0].date,
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Invoice::parse("date,model\n2025-07-01,x\n"),
+            Err(InvoiceError::MissingColumn("cost"))
+        ));
+        assert!(matches!(
+            Invoice::parse("date,model,cost\nyesterday,x,1\n"),
+            Err(InvoiceError::InvalidRow { row: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reconcile_against_logs() {
+        let dir = std::env::temp_dir().join(format!("exactobar-reconcile-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("usage.jsonl");
+        // claude-sonnet-4: 1M input = $3.00; the 2nd is outside the invoice
+        std::fs::write(
+            &log,
+            "{\"timestamp\":\"2025-07-01T10:00:00Z\",\"model\":\"claude-sonnet-4-20250514\",\"input_tokens\":1000000}\n\
+             {\"timestamp\":\"2025-07-05T10:00:00Z\",\"model\":\"claude-sonnet-4-20250514\",\"input_tokens\":1000000}\n",
+        )
+        .unwrap();
+
+        let mut index = LogIndex::default();
+        index.update(
+            &[log],
+            JSONL_FORMAT.parse,
+            &AtomicBool::new(false),
+            &mut |_| {},
+        );
+
+        let pricing = PricingTable::builtin();
+        let invoice = Invoice::parse(ANTHROPIC).unwrap();
+        let reconciliation = Reconciliation::new(&invoice, &index, &pricing);
+
+        // Sonnet on 07-01 matches; haiku and 07-02 have no local usage
+        let sonnet = &reconciliation.rows[1];
+        assert_eq!(sonnet.model, "claude-sonnet-4");
+        assert!(sonnet.difference_usd().abs() < 1e-9);
+
+        let discrepancies: Vec<_> = reconciliation.discrepancies(0.01).collect();
+        assert_eq!(discrepancies.len(), 2);
+        assert!((reconciliation.computed_total_usd() - 3.0).abs() < 1e-9);
+        assert!((reconciliation.invoiced_total_usd() - 1003.75).abs() < 1e-9);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let rows = parse_csv("a,\"b,c\",\"say \"\"hi\"\"\"\r\n1,2,3");
+        assert_eq!(rows[0], vec!["a", "b,c", "say \"hi\""]);
+        assert_eq!(rows[1], vec!["1", "2", "3"]);
+    }
+}
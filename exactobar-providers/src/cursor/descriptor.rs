@@ -5,6 +5,7 @@ use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 use std::path::PathBuf;
 
 use super::strategies::{CursorLocalStrategy, CursorWebStrategy};
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 /// Creates the Cursor provider descriptor.
@@ -56,6 +57,7 @@ fn cursor_token_cost() -> TokenCostConfig {
     TokenCostConfig {
         supports_token_cost: false, // Cursor uses credits, not tokens
         log_directory: None,
+        log_format: None,
     }
 }
 
@@ -89,6 +91,7 @@ fn cursor_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &[], // No CLI usage command
+        compatible_versions: VersionRange::ANY,
     }
 }
 
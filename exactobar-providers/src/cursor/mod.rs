@@ -44,4 +44,5 @@ pub use error::CursorError;
 pub use fetcher::{CursorDataSource, CursorUsageFetcher};
 pub use local::CursorLocalReader;
 pub use strategies::{CursorLocalStrategy, CursorWebStrategy};
+pub(crate) use web::USAGE_ENDPOINT;
 pub use web::{CursorUsageResponse, CursorWebClient};
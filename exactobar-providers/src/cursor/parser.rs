@@ -8,6 +8,8 @@ use exactobar_fetch::FetchError;
 use serde::Deserialize;
 use tracing::{debug, warn};
 
+use super::web::CursorUsageResponse;
+
 // ============================================================================
 // API Response Structures
 // ============================================================================
@@ -109,6 +111,16 @@ pub struct CursorLocalAccount {
 // Parsers
 // ============================================================================
 
+/// Parses a cursor.com usage response into a UsageSnapshot, as the web
+/// strategy does.
+pub fn parse_cursor_web_response(json_str: &str) -> Result<UsageSnapshot, FetchError> {
+    let response: CursorUsageResponse = serde_json::from_str(json_str).map_err(|e| {
+        warn!(error = %e, "Failed to parse Cursor usage JSON");
+        FetchError::InvalidResponse(format!("Invalid JSON: {}", e))
+    })?;
+    Ok(response.to_snapshot())
+}
+
 /// Parses Cursor API JSON response into a UsageSnapshot.
 #[allow(dead_code)]
 pub fn parse_cursor_api_response(json_str: &str) -> Result<UsageSnapshot, FetchError> {
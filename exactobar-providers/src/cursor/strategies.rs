@@ -7,8 +7,9 @@ use exactobar_fetch::{
 };
 use tracing::{debug, instrument, warn};
 
+use super::error::CursorError;
 use super::local::CursorLocalReader;
-use super::web::CursorWebClient;
+use super::web::{CURSOR_API_BASE, CursorWebClient};
 
 // ============================================================================
 // Web Strategy
@@ -83,11 +84,15 @@ impl FetchStrategy for CursorWebStrategy {
         }
 
         // Fetch usage from API
-        let client = CursorWebClient::new();
+        let client = CursorWebClient::with_base_url(ctx.api_base(CURSOR_API_BASE))
+            .with_request_headers(ctx.settings.request_headers.clone());
         let response = client
             .fetch_usage(&cookie_header)
             .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+            .map_err(|e| match e {
+                CursorError::RateLimited(_) => FetchError::RateLimited { retry_after: None },
+                e => FetchError::InvalidResponse(e.to_string()),
+            })?;
 
         let snapshot = response.to_snapshot();
 
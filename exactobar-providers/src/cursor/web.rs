@@ -5,11 +5,13 @@
 
 use chrono::{DateTime, Utc};
 use exactobar_core::{LoginMethod, ProviderIdentity, ProviderKind, UsageSnapshot, UsageWindow};
+use exactobar_fetch::{BROWSER_USER_AGENT, RequestHeaders};
 use reqwest::header::{ACCEPT, COOKIE, HeaderMap, HeaderValue, USER_AGENT};
 use serde::Deserialize;
 use tracing::{debug, instrument, warn};
 
 use super::error::CursorError;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // Constants
@@ -19,14 +21,11 @@ use super::error::CursorError;
 pub const CURSOR_API_BASE: &str = "https://www.cursor.com";
 
 /// Cursor usage API endpoint.
-const USAGE_ENDPOINT: &str = "/api/usage";
+pub(crate) const USAGE_ENDPOINT: &str = "/api/usage";
 
 /// Cursor auth/me endpoint.
 const AUTH_ME_ENDPOINT: &str = "/api/auth/me";
 
-/// User agent for API requests.
-const USER_AGENT_VALUE: &str = "ExactoBar/1.0";
-
 /// Required cookie name for session.
 const SESSION_COOKIE_NAMES: &[&str] = &[
     "__Secure-next-auth.session-token",
@@ -204,17 +203,36 @@ pub struct CursorAuthResponse {
 #[derive(Debug)]
 pub struct CursorWebClient {
     http: reqwest::Client,
+    base_url: String,
+    request_headers: RequestHeaders,
 }
 
 impl CursorWebClient {
     /// Creates a new Cursor web client.
     pub fn new() -> Self {
+        Self::with_base_url(CURSOR_API_BASE)
+    }
+
+    /// Creates a client with a custom base URL.
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
         let http = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
             .expect("Failed to build HTTP client");
 
-        Self { http }
+        Self {
+            http,
+            base_url: base_url.into(),
+            request_headers: RequestHeaders::default(),
+        }
+    }
+
+    /// Returns this client sending the user's configured User-Agent and
+    /// extra headers.
+    #[must_use]
+    pub fn with_request_headers(mut self, headers: RequestHeaders) -> Self {
+        self.request_headers = headers;
+        self
     }
 
     /// Check if a cookie header contains a valid session cookie.
@@ -236,7 +254,7 @@ impl CursorWebClient {
             return Err(CursorError::NoSessionCookie);
         }
 
-        let url = format!("{}{}", CURSOR_API_BASE, USAGE_ENDPOINT);
+        let url = format!("{}{}", self.base_url, USAGE_ENDPOINT);
         let headers = self.build_headers(cookie_header)?;
 
         let response = self.http.get(&url).headers(headers).send().await?;
@@ -263,6 +281,7 @@ impl CursorWebClient {
 
         let body = response.text().await?;
         debug!(len = body.len(), "Got usage response");
+        recording::record(RawFormat::Cursor, &body);
 
         let usage: CursorUsageResponse = serde_json::from_str(&body).map_err(|e| {
             warn!(error = %e, body = %body, "Failed to parse usage response");
@@ -277,7 +296,7 @@ impl CursorWebClient {
     pub async fn fetch_auth(&self, cookie_header: &str) -> Result<CursorAuthResponse, CursorError> {
         debug!("Fetching Cursor auth info via web API");
 
-        let url = format!("{}{}", CURSOR_API_BASE, AUTH_ME_ENDPOINT);
+        let url = format!("{}{}", self.base_url, AUTH_ME_ENDPOINT);
         let headers = self.build_headers(cookie_header)?;
 
         let response = self.http.get(&url).headers(headers).send().await?;
@@ -306,13 +325,14 @@ impl CursorWebClient {
     fn build_headers(&self, cookie_header: &str) -> Result<HeaderMap, CursorError> {
         let mut headers = HeaderMap::new();
 
-        headers.insert(USER_AGENT, HeaderValue::from_static(USER_AGENT_VALUE));
+        headers.insert(USER_AGENT, HeaderValue::from_static(BROWSER_USER_AGENT));
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(
             COOKIE,
             HeaderValue::from_str(cookie_header)
                 .map_err(|e| CursorError::HttpError(format!("Invalid cookie header: {}", e)))?,
         );
+        self.request_headers.apply(&mut headers);
 
         Ok(headers)
     }
@@ -0,0 +1,143 @@
+//! User-defined providers.
+//!
+//! A custom provider is configured in settings with an id, a display name
+//! and a command (see [`CustomProvider`]). The command runs through `sh -c`
+//! and must print one usage snapshot as JSON in the `exactobar-api` format:
+//!
+//! ```json
+//! {"primary": {"used_percent": 42.0, "window_minutes": 300,
+//!              "resets_at": "2025-01-15T18:00:00Z", "reset_description": null},
+//!  "secondary": null, "updated_at": "2025-01-15T16:00:00Z"}
+//! ```
+//!
+//! Custom providers are known by [`ProviderId`] rather than `ProviderKind`;
+//! [`sync_custom_providers`] registers them so their names and labels
+//! resolve like built-in ones.
+
+use std::time::Duration;
+
+use exactobar_core::{FetchSource, ProviderId, UsageSnapshot};
+use exactobar_fetch::{FetchContext, FetchError};
+use exactobar_store::CustomProvider;
+use tracing::{debug, warn};
+
+/// How long a custom provider's command may run.
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Registers the enabled custom providers and unregisters any that are no
+/// longer configured. Returns the ids that were registered.
+///
+/// Invalid entries (empty ids, or ids of built-in providers) are skipped
+/// with a warning.
+pub fn sync_custom_providers(providers: &[CustomProvider]) -> Vec<ProviderId> {
+    let mut registered = Vec::new();
+    for provider in providers.iter().filter(|p| p.enabled) {
+        match ProviderId::register(provider.info.clone()) {
+            Ok(id) => registered.push(id),
+            Err(e) => warn!(id = %provider.info.id, error = %e, "Skipping custom provider"),
+        }
+    }
+
+    for info in ProviderId::registered() {
+        if !registered.contains(&info.id) {
+            ProviderId::unregister(info.id);
+        }
+    }
+
+    debug!(count = registered.len(), "Custom providers registered");
+    registered
+}
+
+/// Runs a custom provider's command and parses the snapshot it prints.
+pub async fn fetch_custom(
+    provider: &CustomProvider,
+    ctx: &FetchContext,
+) -> Result<UsageSnapshot, FetchError> {
+    debug!(id = %provider.info.id, command = %provider.command, "Fetching custom provider");
+
+    let output = ctx
+        .process
+        .run_with_timeout("sh", &["-c", &provider.command], COMMAND_TIMEOUT)
+        .await?;
+    parse_snapshot(output.stdout_if_success()?)
+}
+
+/// Parses and sanitizes a snapshot printed by a custom provider's command.
+pub fn parse_snapshot(stdout: &str) -> Result<UsageSnapshot, FetchError> {
+    let mut snapshot: UsageSnapshot = serde_json::from_str(stdout.trim())?;
+    if !snapshot.has_data() {
+        return Err(FetchError::InvalidResponse(
+            "custom provider reported no usage windows".to_string(),
+        ));
+    }
+
+    snapshot.sanitize();
+    if snapshot.fetch_source == FetchSource::Auto {
+        snapshot.fetch_source = FetchSource::CLI;
+    }
+    Ok(snapshot)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exactobar_core::ProviderInfo;
+
+    fn custom(id: &str, enabled: bool) -> CustomProvider {
+        CustomProvider {
+            info: ProviderInfo::new(id, id.to_uppercase()),
+            command: "true".to_string(),
+            enabled,
+        }
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        let snapshot = parse_snapshot(
+            r#"{"primary": {"used_percent": 140.0, "window_minutes": null,
+                "resets_at": null, "reset_description": null},
+               "secondary": null, "updated_at": "2025-01-15T16:00:00Z"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(snapshot.primary.unwrap().used_percent, 100.0);
+        assert_eq!(snapshot.fetch_source, FetchSource::CLI);
+
+        assert!(parse_snapshot("not json").is_err());
+        assert!(parse_snapshot(r#"{"updated_at": "2025-01-15T16:00:00Z"}"#).is_err());
+    }
+
+    #[test]
+    fn test_sync_custom_providers() {
+        let registered = sync_custom_providers(&[
+            custom("sync-test-a", true),
+            custom("sync-test-b", false),
+            custom("claude", true),
+        ]);
+        assert_eq!(registered, vec![ProviderId::new("sync-test-a")]);
+        assert_eq!(ProviderId::new("sync-test-a").display_name(), "SYNC-TEST-A");
+        assert!(ProviderId::new("sync-test-b").info().is_none());
+
+        sync_custom_providers(&[]);
+        assert!(ProviderId::new("sync-test-a").info().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_custom() {
+        let mut provider = custom("fetch-test", true);
+        provider.command = r#"echo '{"primary": {"used_percent": 12.5, "window_minutes": null,
+            "resets_at": null, "reset_description": null}, "secondary": null,
+            "updated_at": "2025-01-15T16:00:00Z"}'"#
+            .to_string();
+
+        let snapshot = fetch_custom(&provider, &FetchContext::new()).await.unwrap();
+        assert_eq!(snapshot.primary.unwrap().used_percent, 12.5);
+
+        provider.command = "exit 3".to_string();
+        assert!(fetch_custom(&provider, &FetchContext::new()).await.is_err());
+    }
+}
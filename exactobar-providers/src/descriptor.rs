@@ -8,8 +8,14 @@
 //! - CLI configuration
 
 use exactobar_core::{ProviderBranding, ProviderKind, ProviderMetadata};
-use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
+use exactobar_fetch::{
+    FetchContext, FetchKind, FetchOutcome, FetchPipeline, SnapshotCache, SourceMode,
+};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::compat::VersionRange;
+use crate::cost::LogFormat;
 
 // ============================================================================
 // Provider Descriptor
@@ -50,10 +56,66 @@ impl ProviderDescriptor {
         &self.cli.name
     }
 
-    /// Builds the fetch pipeline for this provider.
+    /// Builds the fetch pipeline for this provider, without the strategies
+    /// the context's strategy settings disable and in the order their
+    /// priorities give.
     pub fn build_pipeline(&self, ctx: &FetchContext) -> FetchPipeline {
-        (self.fetch_plan.build_pipeline)(ctx)
+        let mut pipeline = (self.fetch_plan.build_pipeline)(ctx);
+        let strategies = &ctx.settings.strategies;
+        pipeline.retain(|strategy| strategies.is_enabled(strategy.id()));
+        if !strategies.priorities.is_empty() {
+            pipeline
+                .reprioritize(|strategy| strategies.priority(strategy.id(), strategy.priority()));
+        }
+        pipeline
+    }
+
+    /// Fetches usage through the pipeline, reusing a result this or another
+    /// process fetched with the same source mode in the last `max_age`.
+    pub async fn fetch_shared(&self, ctx: &FetchContext, max_age: Duration) -> FetchOutcome {
+        let mut key = SnapshotCache::key(self.id, ctx.settings.source_mode);
+        // Usage from another endpoint (like a mock server) is kept apart
+        if let Some(url) = &ctx.settings.endpoint_override {
+            key.push('-');
+            key.extend(url.chars().map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            }));
+        }
+        SnapshotCache::shared()
+            .run(&key, max_age, || async {
+                self.build_pipeline(ctx).execute(ctx).await
+            })
+            .await
     }
+
+    /// Returns every strategy the provider can use, in the order they're
+    /// tried when all are allowed.
+    pub fn planned_strategies(&self) -> Vec<PlannedStrategy> {
+        let ctx = FetchContext::new();
+        (self.fetch_plan.build_pipeline)(&ctx)
+            .strategies()
+            .map(|strategy| PlannedStrategy {
+                id: strategy.id().to_string(),
+                kind: strategy.kind(),
+                priority: strategy.priority(),
+            })
+            .collect()
+    }
+}
+
+/// A strategy in a provider's fetch plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedStrategy {
+    /// Strategy ID (`claude.pty`).
+    pub id: String,
+    /// The kind of fetch it uses.
+    pub kind: FetchKind,
+    /// Its built-in priority (higher is tried first).
+    pub priority: u32,
 }
 
 // ============================================================================
@@ -66,6 +128,8 @@ pub struct TokenCostConfig {
     pub supports_token_cost: bool,
     /// Function to get the log directory for this provider.
     pub log_directory: Option<fn() -> Option<PathBuf>>,
+    /// Format of the provider's logs (generic JSONL when `None`).
+    pub log_format: Option<LogFormat>,
 }
 
 impl Default for TokenCostConfig {
@@ -73,6 +137,7 @@ impl Default for TokenCostConfig {
         Self {
             supports_token_cost: false,
             log_directory: None,
+            log_format: None,
         }
     }
 }
@@ -112,6 +177,8 @@ pub struct CliConfig {
     pub version_args: &'static [&'static str],
     /// Arguments to get usage data.
     pub usage_args: &'static [&'static str],
+    /// CLI versions the provider's parsers are known to work with.
+    pub compatible_versions: VersionRange,
 }
 
 impl Default for CliConfig {
@@ -121,6 +188,7 @@ impl Default for CliConfig {
             aliases: &[],
             version_args: &["--version"],
             usage_args: &[],
+            compatible_versions: VersionRange::ANY,
         }
     }
 }
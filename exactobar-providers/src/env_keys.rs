@@ -0,0 +1,233 @@
+//! API keys found in environment variables.
+//!
+//! [`discover_env_keys`] looks through the variables API keys are commonly
+//! kept in (`OPENAI_API_KEY`, `ZAI_API_KEY`, `HF_TOKEN`, ...) and reports
+//! which provider each is for and whether ExactoBar can use it. Keys with a
+//! keychain slot can be imported with [`import_env_key`], so the app finds
+//! them without the shell's environment. Values are never kept; importing
+//! reads the variable again.
+
+use exactobar_core::ProviderKind;
+use exactobar_store::keychain::providers;
+
+/// Prefix of Anthropic Admin API keys, which `ANTHROPIC_API_KEY` sometimes
+/// holds.
+const ANTHROPIC_ADMIN_PREFIX: &str = "sk-ant-admin";
+
+/// A variable a provider's key is commonly kept in.
+struct EnvSource {
+    var: &'static str,
+    provider: ProviderKind,
+    key_use: EnvKeyUse,
+}
+
+/// The variables looked through, in the order they're reported.
+const ENV_SOURCES: &[EnvSource] = &[
+    EnvSource {
+        var: "ANTHROPIC_ADMIN_KEY",
+        provider: ProviderKind::Claude,
+        key_use: EnvKeyUse::Import(providers::ANTHROPIC_ADMIN),
+    },
+    EnvSource {
+        var: "ANTHROPIC_API_KEY",
+        provider: ProviderKind::Claude,
+        key_use: EnvKeyUse::Unused("Claude usage comes from the Claude CLI login"),
+    },
+    EnvSource {
+        var: "OPENAI_API_KEY",
+        provider: ProviderKind::Codex,
+        key_use: EnvKeyUse::Import(providers::CODEX),
+    },
+    EnvSource {
+        var: "GEMINI_API_KEY",
+        provider: ProviderKind::Gemini,
+        key_use: EnvKeyUse::Unused("Gemini quota needs the Gemini CLI's Google login"),
+    },
+    EnvSource {
+        var: "GOOGLE_API_KEY",
+        provider: ProviderKind::Gemini,
+        key_use: EnvKeyUse::Unused("Gemini quota needs the Gemini CLI's Google login"),
+    },
+    EnvSource {
+        var: "ZAI_API_KEY",
+        provider: ProviderKind::Zai,
+        key_use: EnvKeyUse::Import(providers::ZAI),
+    },
+    EnvSource {
+        var: "ZAI_API_TOKEN",
+        provider: ProviderKind::Zai,
+        key_use: EnvKeyUse::Import(providers::ZAI),
+    },
+    EnvSource {
+        var: "SYNTHETIC_API_KEY",
+        provider: ProviderKind::Synthetic,
+        key_use: EnvKeyUse::Import(providers::SYNTHETIC),
+    },
+    EnvSource {
+        var: "HF_TOKEN",
+        provider: ProviderKind::HuggingFace,
+        key_use: EnvKeyUse::Import(providers::HUGGINGFACE),
+    },
+    EnvSource {
+        var: "REPLICATE_API_TOKEN",
+        provider: ProviderKind::Replicate,
+        key_use: EnvKeyUse::Import(providers::REPLICATE),
+    },
+    EnvSource {
+        var: "CURSOR_ADMIN_API_KEY",
+        provider: ProviderKind::Cursor,
+        key_use: EnvKeyUse::Import(providers::CURSOR_ADMIN),
+    },
+    EnvSource {
+        var: "COPILOT_API_TOKEN",
+        provider: ProviderKind::Copilot,
+        key_use: EnvKeyUse::EnvOnly,
+    },
+    EnvSource {
+        var: "GITHUB_TOKEN",
+        provider: ProviderKind::Copilot,
+        key_use: EnvKeyUse::EnvOnly,
+    },
+];
+
+/// What ExactoBar can do with a key found in the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvKeyUse {
+    /// It can be imported into the keychain under this name.
+    Import(&'static str),
+    /// It's read from the environment; there's nothing to import.
+    EnvOnly,
+    /// It isn't used to fetch usage, for this reason.
+    Unused(&'static str),
+}
+
+/// An API key found in an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvKey {
+    /// The variable it's in.
+    pub var: &'static str,
+    /// The provider it's for.
+    pub provider: ProviderKind,
+    /// What can be done with it.
+    pub key_use: EnvKeyUse,
+    /// Whether a key is already stored under its keychain name.
+    pub stored: bool,
+}
+
+impl EnvKey {
+    /// Whether the key lets its provider fetch usage, so the provider could
+    /// be enabled.
+    pub fn enables_provider(&self) -> bool {
+        !matches!(self.key_use, EnvKeyUse::Unused(_))
+    }
+
+    /// Whether importing would store something new.
+    pub fn can_import(&self) -> bool {
+        matches!(self.key_use, EnvKeyUse::Import(_)) && !self.stored
+    }
+}
+
+/// Looks through the environment for API keys.
+pub fn discover_env_keys() -> Vec<EnvKey> {
+    discover_with(|var| std::env::var(var).ok(), exactobar_store::has_api_key)
+}
+
+/// Looks up variables with `lookup` and stored keys with `stored`.
+fn discover_with(
+    lookup: impl Fn(&str) -> Option<String>,
+    stored: impl Fn(&str) -> bool,
+) -> Vec<EnvKey> {
+    let mut found: Vec<EnvKey> = Vec::new();
+    for source in ENV_SOURCES {
+        let Some(value) = lookup(source.var).filter(|value| !value.trim().is_empty()) else {
+            continue;
+        };
+        let key_use = match source.key_use {
+            // Admin keys are sometimes kept as the regular API key
+            EnvKeyUse::Unused(_) if value.trim().starts_with(ANTHROPIC_ADMIN_PREFIX) => {
+                EnvKeyUse::Import(providers::ANTHROPIC_ADMIN)
+            }
+            key_use => key_use,
+        };
+        let stored = match key_use {
+            // Another variable already fills this keychain slot
+            EnvKeyUse::Import(_) if found.iter().any(|key| key.key_use == key_use) => continue,
+            EnvKeyUse::Import(name) => stored(name),
+            _ => false,
+        };
+        found.push(EnvKey {
+            var: source.var,
+            provider: source.provider,
+            key_use,
+            stored,
+        });
+    }
+    found
+}
+
+/// Stores a key found in the environment in the keychain, reading its
+/// variable again.
+pub fn import_env_key(key: &EnvKey) -> Result<(), String> {
+    let EnvKeyUse::Import(name) = key.key_use else {
+        return Err(format!("{} can't be imported", key.var));
+    };
+    let value = std::env::var(key.var).map_err(|_| format!("{} is no longer set", key.var))?;
+    exactobar_store::store_api_key(name, value.trim())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let env = [
+            ("OPENAI_API_KEY", "sk-proj-123"),
+            ("ZAI_API_KEY", "zai-key"),
+            ("ZAI_API_TOKEN", "zai-token"),
+            ("ANTHROPIC_API_KEY", "sk-ant-api03-xyz"),
+            ("HF_TOKEN", "  "),
+            ("GITHUB_TOKEN", "ghp_abc"),
+        ];
+        let keys = discover_with(
+            |var| {
+                env.iter()
+                    .find(|(name, _)| *name == var)
+                    .map(|(_, value)| value.to_string())
+            },
+            |name| name == providers::CODEX,
+        );
+
+        let vars: Vec<&str> = keys.iter().map(|key| key.var).collect();
+        assert_eq!(
+            vars,
+            [
+                "ANTHROPIC_API_KEY",
+                "OPENAI_API_KEY",
+                "ZAI_API_KEY",
+                "GITHUB_TOKEN"
+            ]
+        );
+        assert!(!keys[0].enables_provider());
+        assert!(keys[1].stored && !keys[1].can_import());
+        assert!(keys[2].can_import());
+        assert!(keys[3].enables_provider() && !keys[3].can_import());
+    }
+
+    #[test]
+    fn test_admin_key_in_api_key_var() {
+        let keys = discover_with(
+            |var| (var == "ANTHROPIC_API_KEY").then(|| "sk-ant-admin01-abc".to_string()),
+            |_| false,
+        );
+        assert_eq!(keys.len(), 1);
+        assert_eq!(
+            keys[0].key_use,
+            EnvKeyUse::Import(providers::ANTHROPIC_ADMIN)
+        );
+    }
+}
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::{FactoryLocalStrategy, FactoryWebStrategy};
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn factory_descriptor() -> ProviderDescriptor {
@@ -72,5 +73,6 @@ fn factory_cli_config() -> CliConfig {
         aliases: &["droid"],
         version_args: &["--version"],
         usage_args: &[],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -9,7 +9,7 @@ use exactobar_fetch::{
 use std::path::PathBuf;
 use tracing::{debug, instrument};
 
-use super::parser::parse_factory_response;
+use crate::recording::{self, RawFormat};
 
 const FACTORY_DOMAIN: &str = "app.factory.ai";
 const FACTORY_API: &str = "https://app.factory.ai/api/usage";
@@ -73,29 +73,34 @@ impl FetchStrategy for FactoryWebStrategy {
 
         let response = ctx
             .http
-            .get_with_cookies(FACTORY_API, &cookie_header)
+            .get_cached_with_cookies(FACTORY_API, &cookie_header)
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(FetchError::RateLimited {
+                retry_after: response.retry_after,
+            });
+        }
+
+        if response.status == reqwest::StatusCode::UNAUTHORIZED {
             return Err(FetchError::AuthenticationFailed(
                 "Cookies rejected".to_string(),
             ));
         }
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(FetchError::InvalidResponse(format!(
                 "API returned {}",
-                response.status()
+                response.status
             )));
         }
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+        let body = response.body;
+
+        recording::record(RawFormat::Factory, &body);
 
-        let snapshot = parse_factory_response(&body)?;
+        let snapshot = RawFormat::Factory.parse(&body)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -151,7 +156,9 @@ impl FetchStrategy for FactoryLocalStrategy {
         let content = std::fs::read_to_string(&path)
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_factory_response(&content)?;
+        recording::record(RawFormat::Factory, &content);
+
+        let snapshot = RawFormat::Factory.parse(&content)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -3,7 +3,9 @@
 use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
+use super::logs::{GEMINI_LOG_FORMAT, GeminiLogReader};
 use super::strategies::{GeminiCliStrategy, GeminiOAuthStrategy};
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 /// Creates the Gemini provider descriptor.
@@ -12,12 +14,21 @@ pub fn gemini_descriptor() -> ProviderDescriptor {
         id: ProviderKind::Gemini,
         metadata: gemini_metadata(),
         branding: gemini_branding(),
-        token_cost: TokenCostConfig::default(),
+        token_cost: gemini_token_cost(),
         fetch_plan: gemini_fetch_plan(),
         cli: gemini_cli_config(),
     }
 }
 
+/// Gemini token cost configuration (from gemini-cli local telemetry).
+fn gemini_token_cost() -> TokenCostConfig {
+    TokenCostConfig {
+        supports_token_cost: true,
+        log_directory: Some(GeminiLogReader::gemini_dir),
+        log_format: Some(GEMINI_LOG_FORMAT),
+    }
+}
+
 fn gemini_metadata() -> ProviderMetadata {
     ProviderMetadata {
         id: ProviderKind::Gemini,
@@ -75,5 +86,6 @@ fn gemini_cli_config() -> CliConfig {
         aliases: &["gcloud"],
         version_args: &["--version"],
         usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -0,0 +1,357 @@
+//! Gemini CLI telemetry log reader for token cost tracking.
+//!
+//! With local telemetry enabled (`"telemetry": {"enabled": true, "target":
+//! "local"}`), gemini-cli writes OpenTelemetry log records to
+//! `~/.gemini/telemetry.log`, or to `~/.gemini/tmp/<project>/otel/` when the
+//! local collector is used. Every model call produces a
+//! `gemini_cli.api_response` record:
+//!
+//! ```json
+//! {
+//!   "attributes": {
+//!     "event.name": "gemini_cli.api_response",
+//!     "event.timestamp": "2025-07-01T12:00:00.000Z",
+//!     "model": "gemini-2.5-pro",
+//!     "input_token_count": 1200,
+//!     "output_token_count": 300,
+//!     "cached_content_token_count": 1000,
+//!     "thoughts_token_count": 128,
+//!     "tool_token_count": 0
+//!   }
+//! }
+//! ```
+//!
+//! The telemetry file holds pretty-printed records back to back, and the
+//! collector writes OTLP JSON (`resourceLogs[].scopeLogs[].logRecords[]`
+//! with typed attribute values); both layouts are accepted.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use tracing::debug;
+
+use crate::cost::{LogFormat, UsageRecord};
+use crate::pricing::TokenCounts;
+
+/// Event name of a model response record.
+const API_RESPONSE_EVENT: &str = "gemini_cli.api_response";
+
+// ============================================================================
+// Log Reader
+// ============================================================================
+
+/// Gemini CLI telemetry log reader for token cost tracking.
+#[derive(Debug, Clone, Default)]
+pub struct GeminiLogReader;
+
+impl GeminiLogReader {
+    /// Creates a new log reader.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the gemini-cli home directory (`~/.gemini`).
+    pub fn gemini_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".gemini"))
+    }
+
+    /// Check if any telemetry log exists.
+    pub fn has_logs() -> bool {
+        Self::gemini_dir().is_some_and(|dir| !telemetry_files(&dir).is_empty())
+    }
+
+    /// Lists `telemetry.log` and the collector's `tmp/*/otel/*.log` files.
+    pub fn telemetry_files(dir: &Path) -> Vec<PathBuf> {
+        let files = telemetry_files(dir);
+        debug!(count = files.len(), "Found Gemini telemetry files");
+        files
+    }
+
+    /// Parses complete telemetry records, returning the bytes consumed.
+    ///
+    /// A corrupt record is skipped up to the next line starting with `{`,
+    /// so it doesn't hold back the records logged after it.
+    pub fn parse_telemetry(&self, content: &str, emit: &mut dyn FnMut(UsageRecord)) -> usize {
+        let mut consumed = 0;
+
+        // Records are concatenated JSON values, pretty-printed or one per line
+        loop {
+            let rest = &content[consumed..];
+            let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+            match stream.next() {
+                Some(Ok(value)) => {
+                    consumed += stream.byte_offset();
+                    visit_records(&value, &mut |attributes| {
+                        if let Some(record) = api_response_record(attributes) {
+                            emit(record);
+                        }
+                    });
+                }
+                Some(Err(e)) => {
+                    // A truncated tail has no record after it and is left for
+                    // the next scan
+                    let Some(next) = next_record_start(rest) else {
+                        break;
+                    };
+                    debug!(error = %e, "Skipping corrupt Gemini telemetry record");
+                    consumed += next;
+                }
+                None => break,
+            }
+        }
+
+        consumed
+    }
+}
+
+/// Returns the offset of the next record after the one `content` starts
+/// with: the next line starting with `{`. Fields of pretty-printed records
+/// are indented, so only a top-level record starts a line with `{`.
+fn next_record_start(content: &str) -> Option<usize> {
+    let start = content.find(|c: char| !c.is_whitespace())?;
+    content[start..].find("\n{").map(|i| start + i + 1)
+}
+
+/// Log format for the Gemini descriptor.
+pub(crate) const GEMINI_LOG_FORMAT: LogFormat = LogFormat {
+    list_files: GeminiLogReader::telemetry_files,
+    parse: parse_gemini_telemetry,
+};
+
+fn parse_gemini_telemetry(
+    content: &str,
+    _state: &mut Value,
+    emit: &mut dyn FnMut(UsageRecord),
+) -> usize {
+    GeminiLogReader::new().parse_telemetry(content, emit)
+}
+
+/// Lists `telemetry.log` and the collector's `tmp/*/otel/*.log` files.
+fn telemetry_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    let telemetry = dir.join("telemetry.log");
+    if telemetry.is_file() {
+        files.push(telemetry);
+    }
+
+    if let Ok(projects) = std::fs::read_dir(dir.join("tmp")) {
+        for project in projects.flatten() {
+            let Ok(logs) = std::fs::read_dir(project.path().join("otel")) else {
+                continue;
+            };
+            files.extend(
+                logs.flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("log")),
+            );
+        }
+    }
+
+    files
+}
+
+// ============================================================================
+// Record Parsing
+// ============================================================================
+
+/// Calls `f` with the flattened attributes of every log record in `value`.
+fn visit_records(value: &Value, f: &mut dyn FnMut(&Map<String, Value>)) {
+    // OTLP JSON: resourceLogs[].scopeLogs[].logRecords[]
+    if let Some(resource_logs) = value.get("resourceLogs").and_then(Value::as_array) {
+        for scope_logs in resource_logs
+            .iter()
+            .filter_map(|r| r.get("scopeLogs").and_then(Value::as_array))
+            .flatten()
+        {
+            for record in scope_logs
+                .get("logRecords")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(attributes) = record.get("attributes").and_then(Value::as_array) {
+                    f(&flatten_otlp_attributes(attributes));
+                }
+            }
+        }
+        return;
+    }
+
+    if let Some(attributes) = value.get("attributes").and_then(Value::as_object) {
+        f(attributes);
+    }
+}
+
+/// Converts OTLP `[{key, value: {stringValue|intValue|...}}]` to a plain map.
+fn flatten_otlp_attributes(attributes: &[Value]) -> Map<String, Value> {
+    attributes
+        .iter()
+        .filter_map(|attr| {
+            let key = attr.get("key")?.as_str()?;
+            let typed = attr.get("value")?.as_object()?;
+            let value = typed.values().next()?.clone();
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Builds a usage record from an `api_response` event's attributes.
+fn api_response_record(attributes: &Map<String, Value>) -> Option<UsageRecord> {
+    if attributes.get("event.name").and_then(Value::as_str) != Some(API_RESPONSE_EVENT) {
+        return None;
+    }
+
+    let timestamp = attributes
+        .get("event.timestamp")
+        .and_then(Value::as_str)
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())?
+        .with_timezone(&Utc);
+
+    // OTLP encodes intValue as a string
+    let count = |key: &str| {
+        attributes.get(key).map_or(0, |v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+                .unwrap_or(0)
+        })
+    };
+
+    // Prompt tokens include cached tokens; thoughts are billed as output
+    let input = count("input_token_count") + count("tool_token_count");
+    let cached = count("cached_content_token_count").min(input);
+    let thoughts = count("thoughts_token_count");
+
+    Some(UsageRecord {
+        timestamp,
+        model: attributes
+            .get("model")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        tokens: TokenCounts {
+            input: input - cached,
+            output: count("output_token_count") + thoughts,
+            cache_read: cached,
+            cache_write: 0,
+        },
+        reasoning_tokens: thoughts,
+        total_tokens: None,
+        cost_usd: None,
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::CostAggregator;
+    use crate::pricing::PricingTable;
+
+    const TELEMETRY: &str = r#"{
+  "hrTime": [1751371200, 0],
+  "attributes": {
+    "session.id": "abc",
+    "event.name": "gemini_cli.user_prompt",
+    "event.timestamp": "2025-07-01T12:00:00.000Z",
+    "prompt_length": 42
+  }
+}
+{
+  "hrTime": [1751371201, 0],
+  "attributes": {
+    "event.name": "gemini_cli.api_response",
+    "event.timestamp": "2025-07-01T12:00:01.000Z",
+    "model": "gemini-2.5-pro",
+    "input_token_count": 1200000,
+    "output_token_count": 100000,
+    "cached_content_token_count": 1000000,
+    "thoughts_token_count": 50000,
+    "tool_token_count": 0
+  }
+}
+{"attributes":{"event.name":"gemini_cli.api_response","event.timestamp":"2025-07-01T12:00:02.000Z","model":"gemini-2.5-flash","input_token_count":100,"output_token_count":10}}
+{"truncated":"#;
+
+    const OTLP: &str = r#"{"resourceLogs":[{"scopeLogs":[{"logRecords":[{"attributes":[
+        {"key":"event.name","value":{"stringValue":"gemini_cli.api_response"}},
+        {"key":"event.timestamp","value":{"stringValue":"2025-07-02T08:00:00Z"}},
+        {"key":"model","value":{"stringValue":"gemini-2.5-flash"}},
+        {"key":"input_token_count","value":{"intValue":"400"}},
+        {"key":"output_token_count","value":{"intValue":"100"}}
+    ]}]}]}]}"#;
+
+    fn read(content: &str) -> exactobar_store::CostUsageSnapshot {
+        let pricing = PricingTable::builtin();
+        let mut agg = CostAggregator::since(&pricing, "2025-06-01T00:00:00Z".parse().unwrap());
+        GeminiLogReader::new().parse_telemetry(content, &mut |r| agg.add(&r));
+        agg.finish()
+    }
+
+    #[test]
+    fn test_gemini_dir() {
+        let dir = GeminiLogReader::gemini_dir();
+        assert!(dir.is_some_and(|d| d.ends_with(".gemini")));
+    }
+
+    #[test]
+    fn test_read_pretty_printed_telemetry() {
+        let snapshot = read(TELEMETRY);
+
+        assert_eq!(snapshot.models.len(), 2);
+        assert_eq!(snapshot.tokens.input, 200_100);
+        assert_eq!(snapshot.tokens.cache_read, 1_000_000);
+        assert_eq!(snapshot.tokens.output, 150_010);
+        assert_eq!(snapshot.tokens.reasoning, 50_000);
+
+        // gemini-2.5-pro: 0.2M * $1.25 + 1M * $0.31 + 0.15M * $10
+        let pro = &snapshot.models[0];
+        assert_eq!(pro.model_name, "gemini-2.5-pro");
+        assert!((pro.cost_usd.unwrap() - 2.06).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_read_otlp_records() {
+        let snapshot = read(OTLP);
+
+        assert_eq!(snapshot.total_tokens, 500);
+        assert_eq!(snapshot.models[0].model_name, "gemini-2.5-flash");
+        assert_eq!(snapshot.daily.len(), 1);
+    }
+
+    #[test]
+    fn test_truncated_tail_not_consumed() {
+        let consumed = GeminiLogReader::new().parse_telemetry(TELEMETRY, &mut |_| {});
+        assert_eq!(TELEMETRY[consumed..].trim(), r#"{"truncated":"#);
+    }
+
+    #[test]
+    fn test_skips_corrupt_record() {
+        let content = concat!(
+            r#"{"attributes":{"event.name":"gemini_cli.api_response","event.timestamp":"2025-07-01T12:00:00Z","model":"gemini-2.5-flash","input_token_count":100,"output_token_count":10}}"#,
+            "\n",
+            r#"{"attributes":{"event.name":"gemini_cli.api_response","model":oops}}"#,
+            "\n",
+            r#"{"attributes":{"event.name":"gemini_cli.api_response","event.timestamp":"2025-07-01T12:00:01Z","model":"gemini-2.5-flash","input_token_count":200,"output_token_count":20}}"#,
+            "\n",
+            r#"{"truncated":"#,
+        );
+
+        let mut records = Vec::new();
+        let consumed = GeminiLogReader::new().parse_telemetry(content, &mut |r| records.push(r));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].tokens.input, 200);
+        assert_eq!(&content[consumed..].trim(), &r#"{"truncated":"#);
+    }
+
+    #[test]
+    fn test_ignores_other_events() {
+        let mut attributes = Map::new();
+        attributes.insert("event.name".into(), "gemini_cli.tool_call".into());
+        attributes.insert("event.timestamp".into(), "2025-07-01T00:00:00Z".into());
+        assert!(api_response_record(&attributes).is_none());
+    }
+}
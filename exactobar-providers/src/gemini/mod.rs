@@ -35,6 +35,12 @@
 //! - `GET /v1beta/models` - List available models
 //! - Rate limit info comes from response headers
 //!
+//! ## Token Cost
+//!
+//! [`GeminiLogReader`] reads gemini-cli's local telemetry logs
+//! (`~/.gemini/telemetry.log`) for `exactobar cost --provider gemini`.
+//! Telemetry must be enabled with `"target": "local"` in gemini-cli settings.
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -50,6 +56,7 @@ mod descriptor;
 mod error;
 mod fetcher;
 pub mod gcloud;
+mod logs;
 pub(crate) mod parser;
 mod probe;
 mod pty_probe;
@@ -61,6 +68,7 @@ pub use descriptor::gemini_descriptor;
 pub use error::GeminiError;
 pub use fetcher::{GeminiDataSource, GeminiUsageFetcher};
 pub use gcloud::{AdcCredentials, GcloudCredentials, GcloudToken};
+pub use logs::GeminiLogReader;
 pub use probe::{GeminiAuthType, GeminiCredentials, GeminiModelQuota, GeminiProbe, GeminiSnapshot};
 pub use pty_probe::{GeminiCliQuota, GeminiPtyProbe};
 pub use strategies::{GeminiCliStrategy, GeminiOAuthStrategy};
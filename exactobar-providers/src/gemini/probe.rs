@@ -23,7 +23,8 @@ use tracing::{debug, info, warn};
 
 use super::error::GeminiError;
 use exactobar_core::{
-    FetchSource, LoginMethod, ProviderIdentity, ProviderKind, UsageSnapshot, UsageWindow,
+    FetchSource, LoginMethod, ModelUsage, ProviderIdentity, ProviderKind, UsageSnapshot,
+    UsageWindow,
 };
 
 // ============================================================================
@@ -296,6 +297,23 @@ impl GeminiSnapshot {
             });
         }
 
+        // Every model, for the per-model breakdown
+        snapshot.models = self
+            .model_quotas
+            .iter()
+            .map(|quota| {
+                ModelUsage::new(
+                    quota.model_id.clone(),
+                    UsageWindow {
+                        used_percent: quota.percent_used(),
+                        window_minutes: Some(1440),
+                        resets_at: quota.reset_time,
+                        reset_description: None,
+                    },
+                )
+            })
+            .collect();
+
         // Build identity
         let mut identity = ProviderIdentity::new(ProviderKind::Gemini);
         identity.account_email = self.account_email.clone();
@@ -625,6 +643,11 @@ mod tests {
         assert!(usage.secondary.is_some());
         assert_eq!(usage.secondary.as_ref().unwrap().used_percent, 10.0);
 
+        // Every model is listed individually
+        assert_eq!(usage.models.len(), 2);
+        assert_eq!(usage.models[1].model, "gemini-2.0-flash");
+        assert_eq!(usage.models[1].window.used_percent, 10.0);
+
         // Identity should have email
         assert!(usage.identity.is_some());
         assert_eq!(
@@ -5,8 +5,8 @@ use async_trait::async_trait;
 use exactobar_fetch::{FetchContext, FetchError, FetchKind, FetchResult, FetchStrategy};
 use tracing::{debug, info, instrument, warn};
 
-use super::parser::parse_gemini_response;
 use super::probe::{GeminiCredentials, GeminiProbe};
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // OAuth Strategy
@@ -147,7 +147,9 @@ impl FetchStrategy for GeminiCliStrategy {
             )));
         }
 
-        let snapshot = parse_gemini_response(&output.stdout)?;
+        recording::record(RawFormat::Gemini, &output.stdout);
+
+        let snapshot = RawFormat::Gemini.parse(&output.stdout)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -0,0 +1,253 @@
+//! GitHub Models inference API and its rate-limit headers.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use exactobar_core::{
+    FetchSource, LoginMethod, ModelUsage, ProviderIdentity, ProviderKind, UsageSnapshot,
+    UsageWindow,
+};
+use reqwest::header::HeaderMap;
+use serde_json::json;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// GitHub Models inference API base URL.
+pub const API_BASE_URL: &str = "https://models.github.ai/inference";
+
+/// Chat completions endpoint, used to probe a model's rate limit.
+pub(crate) const COMPLETIONS_ENDPOINT: &str = "/chat/completions";
+
+/// Models tracked by default: one from each free-tier rate-limit tier.
+pub const DEFAULT_MODELS: &[&str] = &["openai/gpt-4o-mini", "openai/gpt-4o"];
+
+/// Request budget limit header.
+const LIMIT_HEADER: &str = "x-ratelimit-limit-requests";
+
+/// Remaining requests header.
+const REMAINING_HEADER: &str = "x-ratelimit-remaining-requests";
+
+/// Headers giving the time until the budget renews, in preference order.
+const RESET_HEADERS: &[&str] = &[
+    "x-ratelimit-reset-requests",
+    "x-ratelimit-renewalperiod-requests",
+    "retry-after",
+];
+
+/// Free-tier request budgets are per day.
+const WINDOW_MINUTES: u32 = 24 * 60;
+
+/// Returns the smallest request that gets rate-limit headers back.
+pub(crate) fn probe_body(model: &str) -> serde_json::Value {
+    json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": "hi" }],
+        "max_tokens": 1,
+    })
+}
+
+// ============================================================================
+// Rate Limits
+// ============================================================================
+
+/// A model's request budget, from a response's rate-limit headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelRateLimit {
+    /// Model id, like `openai/gpt-4o`.
+    pub model: String,
+    /// Requests allowed per window.
+    pub limit: u64,
+    /// Requests left in the window.
+    pub remaining: u64,
+    /// Time until the budget renews, if the response said.
+    pub resets_in: Option<Duration>,
+}
+
+impl ModelRateLimit {
+    /// Reads the rate-limit headers of a response. A `429` with no
+    /// remaining count is taken as an exhausted budget.
+    ///
+    /// Returns `None` if the response has no rate-limit headers.
+    pub fn from_headers(model: &str, headers: &HeaderMap, rate_limited: bool) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+        let number = |name: &str| header(name)?.parse::<u64>().ok();
+
+        let remaining = number(REMAINING_HEADER).or(rate_limited.then_some(0))?;
+        let limit = number(LIMIT_HEADER).unwrap_or(remaining).max(remaining);
+        let resets_in = RESET_HEADERS
+            .iter()
+            .find_map(|name| header(name).and_then(parse_reset));
+
+        Some(Self {
+            model: model.to_string(),
+            limit,
+            remaining,
+            resets_in,
+        })
+    }
+
+    /// Percentage of the budget used. An unknown (zero) limit counts as
+    /// used up.
+    pub fn used_percent(&self) -> f64 {
+        if self.limit == 0 {
+            return 100.0;
+        }
+        (self.limit - self.remaining) as f64 / self.limit as f64 * 100.0
+    }
+
+    /// Returns this budget as a usage window.
+    pub fn to_window(&self) -> UsageWindow {
+        UsageWindow {
+            used_percent: self.used_percent(),
+            window_minutes: Some(WINDOW_MINUTES),
+            resets_at: self
+                .resets_in
+                .and_then(|d| chrono::Duration::from_std(d).ok())
+                .map(|d| Utc::now() + d),
+            reset_description: None,
+        }
+    }
+}
+
+/// Parses a reset time: whole or fractional seconds (`"3600"`, `"12.5"`)
+/// or a duration like `"1h2m3s"`, `"6m0s"` or `"250ms"`.
+pub fn parse_reset(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .filter(|&i| i > 0)?;
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        total += number
+            * match unit {
+                "h" => 3600.0,
+                "m" => 60.0,
+                "s" => 1.0,
+                "ms" => 0.001,
+                _ => return None,
+            };
+        rest = tail;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// Builds a snapshot from each tracked model's budget. The primary window
+/// is the model closest to its limit.
+pub fn to_snapshot(limits: &[ModelRateLimit]) -> UsageSnapshot {
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.fetch_source = FetchSource::OAuth;
+
+    snapshot.primary = limits
+        .iter()
+        .max_by(|a, b| a.used_percent().total_cmp(&b.used_percent()))
+        .map(ModelRateLimit::to_window);
+    snapshot.models = limits
+        .iter()
+        .map(|limit| ModelUsage::new(limit.model.clone(), limit.to_window()))
+        .collect();
+
+    let mut identity = ProviderIdentity::new(ProviderKind::GitHubModels);
+    identity.plan_name = Some("Free tier".to_string());
+    identity.login_method = Some(LoginMethod::OAuth);
+    snapshot.identity = Some(identity);
+
+    snapshot
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let limit = ModelRateLimit::from_headers(
+            "openai/gpt-4o",
+            &headers(&[
+                ("x-ratelimit-limit-requests", "50"),
+                ("x-ratelimit-remaining-requests", "38"),
+                ("x-ratelimit-renewalperiod-requests", "86400"),
+            ]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(limit.limit, 50);
+        assert_eq!(limit.remaining, 38);
+        assert_eq!(limit.resets_in, Some(Duration::from_secs(86400)));
+        assert!((limit.used_percent() - 24.0).abs() < 1e-9);
+
+        assert!(ModelRateLimit::from_headers("openai/gpt-4o", &HeaderMap::new(), false).is_none());
+
+        let limited = ModelRateLimit::from_headers(
+            "openai/gpt-4o",
+            &headers(&[("retry-after", "120")]),
+            true,
+        )
+        .unwrap();
+        assert!((limited.used_percent() - 100.0).abs() < f64::EPSILON);
+        assert_eq!(limited.resets_in, Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_parse_reset() {
+        assert_eq!(parse_reset("3600"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_reset("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_reset("1h2m3s"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_reset("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_reset("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_reset("soon"), None);
+        assert_eq!(parse_reset("5d"), None);
+    }
+
+    #[test]
+    fn test_to_snapshot() {
+        let limits = [
+            ModelRateLimit {
+                model: "openai/gpt-4o-mini".to_string(),
+                limit: 150,
+                remaining: 120,
+                resets_in: None,
+            },
+            ModelRateLimit {
+                model: "openai/gpt-4o".to_string(),
+                limit: 50,
+                remaining: 10,
+                resets_in: Some(Duration::from_secs(3600)),
+            },
+        ];
+        let snapshot = to_snapshot(&limits);
+
+        let primary = snapshot.primary.unwrap();
+        assert!((primary.used_percent - 80.0).abs() < 1e-9);
+        assert!(primary.resets_at.is_some());
+        assert_eq!(snapshot.models.len(), 2);
+        assert_eq!(snapshot.models[0].model, "openai/gpt-4o-mini");
+        assert!((snapshot.models[0].window.used_percent - 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,97 @@
+//! GitHub Models provider descriptor.
+
+use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
+use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
+
+use super::strategies::GitHubModelsApiStrategy;
+use crate::compat::VersionRange;
+use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
+
+// ============================================================================
+// Descriptor
+// ============================================================================
+
+/// Creates the GitHub Models provider descriptor.
+pub fn github_models_descriptor() -> ProviderDescriptor {
+    ProviderDescriptor {
+        id: ProviderKind::GitHubModels,
+        metadata: github_models_metadata(),
+        branding: github_models_branding(),
+        token_cost: TokenCostConfig::default(),
+        fetch_plan: github_models_fetch_plan(),
+        cli: github_models_cli_config(),
+    }
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+fn github_models_metadata() -> ProviderMetadata {
+    ProviderMetadata {
+        id: ProviderKind::GitHubModels,
+        display_name: "GitHub Models".to_string(),
+        session_label: "Daily".to_string(),
+        weekly_label: "Weekly".to_string(),
+        opus_label: None,
+        supports_opus: false,
+        supports_credits: false,
+        credits_hint: String::new(),
+        toggle_title: "Show GitHub Models usage".to_string(),
+        cli_name: "githubmodels".to_string(),
+        default_enabled: false,
+        is_primary_provider: false,
+        uses_account_fallback: false,
+        dashboard_url: Some("https://github.com/marketplace/models".to_string()),
+        subscription_dashboard_url: Some("https://github.com/settings/billing".to_string()),
+        status_page_url: Some("https://www.githubstatus.com/api/v2/status.json".to_string()),
+        status_link_url: Some("https://www.githubstatus.com".to_string()),
+    }
+}
+
+// ============================================================================
+// Branding
+// ============================================================================
+
+fn github_models_branding() -> ProviderBranding {
+    ProviderBranding {
+        icon_style: IconStyle::GitHubModels,
+        icon_resource_name: "icon_githubmodels".to_string(),
+        color: ProviderColor::new(0.51, 0.31, 0.87), // GitHub purple
+    }
+}
+
+// ============================================================================
+// Fetch Plan
+// ============================================================================
+
+fn github_models_fetch_plan() -> FetchPlan {
+    FetchPlan {
+        source_modes: vec![SourceMode::OAuth],
+        build_pipeline: build_github_models_pipeline,
+    }
+}
+
+fn build_github_models_pipeline(ctx: &FetchContext) -> FetchPipeline {
+    let mut strategies: Vec<Box<dyn exactobar_fetch::FetchStrategy>> = Vec::new();
+
+    if ctx.settings.source_mode.allows_oauth() {
+        strategies.push(Box::new(GitHubModelsApiStrategy::new()));
+    }
+
+    FetchPipeline::with_strategies(strategies)
+}
+
+// ============================================================================
+// CLI Config
+// ============================================================================
+
+fn github_models_cli_config() -> CliConfig {
+    CliConfig {
+        name: "githubmodels",
+        aliases: &["github-models", "ghmodels"],
+        version_args: &["--version"],
+        usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
+    }
+}
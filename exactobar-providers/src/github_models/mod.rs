@@ -0,0 +1,27 @@
+//! GitHub Models provider implementation.
+//!
+//! GitHub Models serves inference (on Azure AI) to anyone with a GitHub
+//! account, with free-tier rate limits per model: a daily request budget
+//! that's smaller for high-tier models. There's no usage endpoint, so the
+//! provider sends a one-token completion to each tracked model and reads the
+//! `x-ratelimit-*` response headers.
+//!
+//! It uses the same GitHub token as Copilot: the keychain entry first, then
+//! the [`CopilotTokenStore`](crate::copilot::CopilotTokenStore) sources
+//! (environment, token file, gh CLI config).
+//!
+//! ## Fetch Strategies
+//!
+//! 1. **API Strategy** (priority 100): Probes each of [`DEFAULT_MODELS`]
+//!
+//! Every probe spends one request of the model's budget, so a model is
+//! probed at most once per [`PROBE_INTERVAL`]; refreshes in between reuse
+//! the last headers.
+
+mod api;
+mod descriptor;
+mod strategies;
+
+pub use api::{API_BASE_URL, DEFAULT_MODELS, ModelRateLimit, parse_reset};
+pub use descriptor::github_models_descriptor;
+pub use strategies::{GitHubModelsApiStrategy, PROBE_INTERVAL};
@@ -0,0 +1,209 @@
+//! GitHub Models fetch strategies.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use exactobar_fetch::{
+    FetchContext, FetchError, FetchKind, FetchResult, FetchStrategy,
+    host::keychain::{accounts, services},
+};
+use reqwest::StatusCode;
+use tracing::{debug, instrument, warn};
+
+use super::api::{
+    API_BASE_URL, COMPLETIONS_ENDPOINT, DEFAULT_MODELS, ModelRateLimit, probe_body, to_snapshot,
+};
+use crate::copilot::CopilotTokenStore;
+
+/// Minimum time between probes of a model. Each probe spends a request of
+/// its daily budget, so this caps the cost at 48 requests a day per model.
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Last probe of each model, keyed by base URL and model, and when it ran.
+static PROBES: LazyLock<Mutex<HashMap<String, (Instant, ModelRateLimit)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// ============================================================================
+// API Strategy
+// ============================================================================
+
+/// Reads free-tier rate limits from the GitHub Models inference API.
+pub struct GitHubModelsApiStrategy {
+    models: Vec<String>,
+}
+
+impl GitHubModelsApiStrategy {
+    /// Creates a strategy tracking [`DEFAULT_MODELS`].
+    pub fn new() -> Self {
+        Self::with_models(DEFAULT_MODELS.iter().copied())
+    }
+
+    /// Creates a strategy tracking `models`, by id like `openai/gpt-4o`.
+    pub fn with_models<S: Into<String>>(models: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            models: models.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Gets the GitHub token the same way Copilot does.
+    async fn get_token(&self, ctx: &FetchContext) -> Option<String> {
+        if let Ok(Some(token)) = ctx
+            .keychain
+            .get(services::GITHUB, accounts::OAUTH_TOKEN)
+            .await
+        {
+            return Some(token);
+        }
+
+        tokio::task::spawn_blocking(|| CopilotTokenStore::new().load())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Returns a model's rate limit, probing it unless it was probed in the
+    /// last [`PROBE_INTERVAL`].
+    async fn rate_limit(
+        &self,
+        ctx: &FetchContext,
+        token: &str,
+        model: &str,
+    ) -> Result<ModelRateLimit, FetchError> {
+        let base = ctx.api_base(API_BASE_URL);
+        let key = format!("{base} {model}");
+
+        let cached = PROBES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .filter(|(probed_at, _)| probed_at.elapsed() < PROBE_INTERVAL)
+            .map(|(probed_at, limit)| {
+                let mut limit = limit.clone();
+                limit.resets_in = limit
+                    .resets_in
+                    .map(|d| d.saturating_sub(probed_at.elapsed()));
+                limit
+            });
+        if let Some(limit) = cached {
+            debug!(model, "Reusing recent rate-limit probe");
+            return Ok(limit);
+        }
+
+        let url = format!("{base}{COMPLETIONS_ENDPOINT}");
+        let response = ctx
+            .http
+            .post_json_with_auth(&url, &probe_body(model), &format!("Bearer {token}"))
+            .await
+            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(FetchError::AuthenticationFailed(format!(
+                "GitHub token rejected ({status})"
+            )));
+        }
+
+        let limit = ModelRateLimit::from_headers(
+            model,
+            response.headers(),
+            status == StatusCode::TOO_MANY_REQUESTS,
+        )
+        .ok_or_else(|| {
+            FetchError::InvalidResponse(format!("{model}: no rate-limit headers ({status})"))
+        })?;
+
+        PROBES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, (Instant::now(), limit.clone()));
+        Ok(limit)
+    }
+}
+
+impl Default for GitHubModelsApiStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FetchStrategy for GitHubModelsApiStrategy {
+    fn id(&self) -> &str {
+        "githubmodels.api"
+    }
+
+    fn kind(&self) -> FetchKind {
+        FetchKind::OAuth
+    }
+
+    #[instrument(skip(self, _ctx))]
+    async fn is_available(&self, _ctx: &FetchContext) -> bool {
+        // Like Copilot, leave credential loading (and any keychain prompt)
+        // to fetch()
+        true
+    }
+
+    #[instrument(skip(self, ctx))]
+    async fn fetch(&self, ctx: &FetchContext) -> Result<FetchResult, FetchError> {
+        debug!("Fetching GitHub Models rate limits");
+
+        let token = self
+            .get_token(ctx)
+            .await
+            .ok_or_else(|| FetchError::AuthenticationFailed("No GitHub token".to_string()))?;
+
+        let mut limits = Vec::new();
+        let mut last_error = None;
+        for model in &self.models {
+            match self.rate_limit(ctx, &token, model).await {
+                Ok(limit) => limits.push(limit),
+                Err(e @ FetchError::AuthenticationFailed(_)) => return Err(e),
+                Err(e) => {
+                    warn!(model, error = %e, "Rate-limit probe failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if limits.is_empty() {
+            return Err(last_error
+                .unwrap_or_else(|| FetchError::InvalidResponse("No models to track".to_string())));
+        }
+
+        Ok(FetchResult::new(
+            to_snapshot(&limits),
+            self.id(),
+            self.kind(),
+        ))
+    }
+
+    fn priority(&self) -> u32 {
+        100
+    }
+
+    fn should_fallback(&self, error: &FetchError) -> bool {
+        !matches!(error, FetchError::AuthenticationFailed(_))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_strategy() {
+        let s = GitHubModelsApiStrategy::new();
+        assert_eq!(s.id(), "githubmodels.api");
+        assert_eq!(s.kind(), FetchKind::OAuth);
+        assert_eq!(s.models, DEFAULT_MODELS);
+
+        let s = GitHubModelsApiStrategy::with_models(["meta/llama-3.3-70b-instruct"]);
+        assert_eq!(s.models, ["meta/llama-3.3-70b-instruct"]);
+    }
+}
@@ -0,0 +1,341 @@
+//! Hugging Face Hub API client.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use exactobar_core::{
+    FetchSource, LoginMethod, ProviderIdentity, ProviderKind, UsageSnapshot, UsageWindow,
+};
+use exactobar_fetch::host::HttpApi;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
+
+use super::error::HuggingFaceError;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Hugging Face Hub base URL.
+pub const API_BASE_URL: &str = "https://huggingface.co";
+
+/// Account endpoint.
+pub const WHOAMI_ENDPOINT: &str = "/api/whoami-v2";
+
+/// Inference Providers spend for the current billing period.
+pub const USAGE_ENDPOINT: &str = "/api/settings/billing/usage/inference-providers";
+
+/// Monthly inference credits included with a free account, in USD.
+pub const FREE_MONTHLY_CREDITS: f64 = 0.10;
+
+/// Monthly inference credits included with PRO, in USD.
+pub const PRO_MONTHLY_CREDITS: f64 = 2.00;
+
+// ============================================================================
+// API Response Types
+// ============================================================================
+
+/// Response from `whoami-v2`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhoamiResponse {
+    /// Username.
+    pub name: String,
+    /// Account email, if the token can read it.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Whether the account has PRO.
+    #[serde(default)]
+    pub is_pro: bool,
+}
+
+impl WhoamiResponse {
+    /// Monthly inference credits included with this account's plan.
+    pub fn included_credits(&self) -> f64 {
+        if self.is_pro {
+            PRO_MONTHLY_CREDITS
+        } else {
+            FREE_MONTHLY_CREDITS
+        }
+    }
+
+    /// Plan name for display.
+    pub fn plan_name(&self) -> &'static str {
+        if self.is_pro { "PRO" } else { "Free" }
+    }
+}
+
+/// Inference Providers spend for the current billing period.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HuggingFaceUsage {
+    /// Credits spent this period, in USD.
+    #[serde(alias = "usedCredits")]
+    pub used_usd: f64,
+    /// Credits included with the plan, in USD. Falls back to the plan's
+    /// allowance when missing.
+    #[serde(default, alias = "includedCredits")]
+    pub included_usd: Option<f64>,
+    /// When the billing period ends and credits renew.
+    #[serde(default)]
+    pub period_end: Option<DateTime<Utc>>,
+}
+
+impl HuggingFaceUsage {
+    /// Builds a snapshot with the month's credit consumption as the primary
+    /// window.
+    pub fn to_snapshot(&self, account: &WhoamiResponse) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.fetch_source = FetchSource::Api;
+
+        let included = self
+            .included_usd
+            .unwrap_or_else(|| account.included_credits());
+        let used_percent = if included > 0.0 {
+            (self.used_usd / included) * 100.0
+        } else {
+            0.0
+        };
+
+        snapshot.primary = Some(UsageWindow {
+            used_percent,
+            window_minutes: Some(43200), // ~30 days in minutes
+            resets_at: Some(
+                self.period_end
+                    .unwrap_or_else(|| next_month_start(Utc::now())),
+            ),
+            reset_description: Some(format!("${:.2} of ${:.2} credits", self.used_usd, included)),
+        });
+
+        let mut identity = ProviderIdentity::new(ProviderKind::HuggingFace);
+        identity.account_email = account.email.clone().or_else(|| Some(account.name.clone()));
+        identity.plan_name = Some(account.plan_name().to_string());
+        identity.login_method = Some(LoginMethod::ApiKey);
+        snapshot.identity = Some(identity);
+
+        snapshot
+    }
+}
+
+/// Returns midnight UTC on the first of the month after `now`, when credits
+/// renew if the API doesn't say.
+pub fn next_month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map_or(now, |midnight| Utc.from_utc_datetime(&midnight))
+}
+
+// ============================================================================
+// API Client
+// ============================================================================
+
+/// Hugging Face Hub API client.
+#[derive(Debug, Clone)]
+pub struct HuggingFaceApiClient {
+    base_url: String,
+}
+
+impl Default for HuggingFaceApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HuggingFaceApiClient {
+    /// Creates a new client.
+    pub fn new() -> Self {
+        Self::with_base_url(API_BASE_URL)
+    }
+
+    /// Creates a client with a custom base URL.
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+        }
+    }
+
+    /// Get a token from the Keychain, the environment, or the CLI's token
+    /// file.
+    ///
+    /// The lookup order is:
+    /// 1. System keychain (stored via Settings UI)
+    /// 2. Environment variable `HF_TOKEN`
+    /// 3. `$HF_TOKEN_PATH`, else `$HF_HOME/token`, else
+    ///    `~/.cache/huggingface/token`
+    pub fn get_token() -> Result<String, HuggingFaceError> {
+        if let Some(token) = exactobar_store::get_api_key("huggingface") {
+            return Ok(token);
+        }
+
+        if let Ok(token) = std::env::var("HF_TOKEN") {
+            if !token.trim().is_empty() {
+                return Ok(token.trim().to_string());
+            }
+        }
+
+        let path = std::env::var_os("HF_TOKEN_PATH")
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HF_HOME").map(|home| std::path::Path::new(&home).join("token"))
+            })
+            .or_else(|| {
+                dirs::home_dir().map(|h| h.join(".cache").join("huggingface").join("token"))
+            })
+            .ok_or(HuggingFaceError::TokenNotFound)?;
+
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .ok_or(HuggingFaceError::TokenNotFound)
+    }
+
+    /// Fetch the account the token belongs to.
+    #[instrument(skip(self, http, token))]
+    pub async fn fetch_whoami(
+        &self,
+        http: &dyn HttpApi,
+        token: &str,
+    ) -> Result<WhoamiResponse, HuggingFaceError> {
+        self.get_json(http, WHOAMI_ENDPOINT, token).await
+    }
+
+    /// Fetch this billing period's Inference Providers spend.
+    #[instrument(skip(self, http, token))]
+    pub async fn fetch_usage(
+        &self,
+        http: &dyn HttpApi,
+        token: &str,
+    ) -> Result<HuggingFaceUsage, HuggingFaceError> {
+        self.get_json(http, USAGE_ENDPOINT, token).await
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        http: &dyn HttpApi,
+        endpoint: &str,
+        token: &str,
+    ) -> Result<T, HuggingFaceError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+
+        debug!(url = %url, "Fetching Hugging Face API");
+
+        let response = http
+            .get_with_auth(&url, &format!("Bearer {}", token))
+            .await
+            .map_err(|e| HuggingFaceError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(HuggingFaceError::AuthenticationFailed(
+                "Token rejected".to_string(),
+            ));
+        }
+
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(HuggingFaceError::ApiError(format!(
+                "HTTP {}: {}",
+                status, text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| HuggingFaceError::ParseError(e.to_string()))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(is_pro: bool) -> WhoamiResponse {
+        WhoamiResponse {
+            name: "julien".to_string(),
+            email: None,
+            is_pro,
+        }
+    }
+
+    #[test]
+    fn test_parse_whoami() {
+        let json = r#"{
+          "type": "user",
+          "name": "julien",
+          "fullname": "Julien",
+          "email": "julien@example.com",
+          "isPro": true,
+          "orgs": []
+        }"#;
+
+        let whoami: WhoamiResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(whoami.name, "julien");
+        assert!(whoami.is_pro);
+        assert!((whoami.included_credits() - PRO_MONTHLY_CREDITS).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_to_snapshot() {
+        let json = r#"{
+          "usedUsd": 0.5,
+          "includedUsd": 2.0,
+          "periodEnd": "2026-11-01T00:00:00Z"
+        }"#;
+        let usage: HuggingFaceUsage = serde_json::from_str(json).unwrap();
+
+        let snapshot = usage.to_snapshot(&account(true));
+        let primary = snapshot.primary.unwrap();
+        assert!((primary.used_percent - 25.0).abs() < 1e-9);
+        assert_eq!(
+            primary.resets_at.unwrap().to_rfc3339(),
+            "2026-11-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            primary.reset_description.as_deref(),
+            Some("$0.50 of $2.00 credits")
+        );
+
+        let identity = snapshot.identity.unwrap();
+        assert_eq!(identity.plan_name.as_deref(), Some("PRO"));
+        assert_eq!(identity.account_email.as_deref(), Some("julien"));
+    }
+
+    #[test]
+    fn test_to_snapshot_plan_allowance() {
+        let usage = HuggingFaceUsage {
+            used_usd: 0.05,
+            included_usd: None,
+            period_end: None,
+        };
+
+        let primary = usage.to_snapshot(&account(false)).primary.unwrap();
+        assert!((primary.used_percent - 50.0).abs() < 1e-9);
+        assert!(primary.resets_at.is_some());
+    }
+
+    #[test]
+    fn test_next_month_start() {
+        let mid = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        assert_eq!(
+            next_month_start(mid),
+            Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap()
+        );
+
+        let december = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 0).unwrap();
+        assert_eq!(
+            next_month_start(december),
+            Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
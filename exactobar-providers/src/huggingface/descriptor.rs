@@ -0,0 +1,99 @@
+//! Hugging Face provider descriptor.
+
+use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
+use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
+
+use super::strategies::HuggingFaceApiStrategy;
+use crate::compat::VersionRange;
+use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
+
+// ============================================================================
+// Descriptor
+// ============================================================================
+
+/// Creates the Hugging Face provider descriptor.
+pub fn huggingface_descriptor() -> ProviderDescriptor {
+    ProviderDescriptor {
+        id: ProviderKind::HuggingFace,
+        metadata: huggingface_metadata(),
+        branding: huggingface_branding(),
+        token_cost: TokenCostConfig::default(),
+        fetch_plan: huggingface_fetch_plan(),
+        cli: huggingface_cli_config(),
+    }
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+fn huggingface_metadata() -> ProviderMetadata {
+    ProviderMetadata {
+        id: ProviderKind::HuggingFace,
+        display_name: "Hugging Face".to_string(),
+        session_label: "Credits".to_string(),
+        weekly_label: "Monthly".to_string(),
+        opus_label: None,
+        supports_opus: false,
+        supports_credits: true,
+        credits_hint: "Monthly Inference Providers credits".to_string(),
+        toggle_title: "Show Hugging Face usage".to_string(),
+        cli_name: "huggingface".to_string(),
+        default_enabled: false,
+        is_primary_provider: false,
+        uses_account_fallback: false,
+        dashboard_url: Some(
+            "https://huggingface.co/settings/inference-providers/overview".to_string(),
+        ),
+        subscription_dashboard_url: Some("https://huggingface.co/settings/billing".to_string()),
+        status_page_url: Some("https://status.huggingface.co".to_string()),
+        status_link_url: Some("https://status.huggingface.co".to_string()),
+    }
+}
+
+// ============================================================================
+// Branding
+// ============================================================================
+
+fn huggingface_branding() -> ProviderBranding {
+    ProviderBranding {
+        icon_style: IconStyle::HuggingFace,
+        icon_resource_name: "icon_huggingface".to_string(),
+        color: ProviderColor::new(1.0, 0.82, 0.12), // Hugging Face yellow
+    }
+}
+
+// ============================================================================
+// Fetch Plan
+// ============================================================================
+
+fn huggingface_fetch_plan() -> FetchPlan {
+    FetchPlan {
+        source_modes: vec![SourceMode::ApiKey],
+        build_pipeline: build_huggingface_pipeline,
+    }
+}
+
+fn build_huggingface_pipeline(ctx: &FetchContext) -> FetchPipeline {
+    let mut strategies: Vec<Box<dyn exactobar_fetch::FetchStrategy>> = Vec::new();
+
+    if ctx.settings.source_mode.allows_api_key() {
+        strategies.push(Box::new(HuggingFaceApiStrategy::new()));
+    }
+
+    FetchPipeline::with_strategies(strategies)
+}
+
+// ============================================================================
+// CLI Config
+// ============================================================================
+
+fn huggingface_cli_config() -> CliConfig {
+    CliConfig {
+        name: "huggingface",
+        aliases: &["hf", "hugging-face"],
+        version_args: &["--version"],
+        usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
+    }
+}
@@ -0,0 +1,27 @@
+//! Hugging Face-specific errors.
+
+use thiserror::Error;
+
+/// Hugging Face-specific errors.
+#[derive(Debug, Error)]
+pub enum HuggingFaceError {
+    /// No token in the keychain, environment or token file.
+    #[error("Token not found (set HF_TOKEN or run `huggingface-cli login`)")]
+    TokenNotFound,
+
+    /// HTTP request failed.
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+
+    /// Parse error.
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// API error.
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    /// Authentication failed.
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+}
@@ -0,0 +1,27 @@
+//! Hugging Face Inference Providers implementation.
+//!
+//! Hugging Face routes inference to partner providers (Together, Fireworks,
+//! Replicate, ...) and bills it against monthly credits: a small allowance
+//! for free accounts and a larger one for PRO. Usage past the allowance is
+//! pay-as-you-go.
+//!
+//! The token is a Hugging Face user access token, looked up in the keychain
+//! (stored via Settings), then `HF_TOKEN`, then the token file written by
+//! `huggingface-cli login`.
+//!
+//! ## Fetch Strategies
+//!
+//! 1. **API Strategy** (priority 60): Reads the account from `whoami-v2` and
+//!    the month's inference spend from the billing usage endpoint
+
+mod api;
+mod descriptor;
+mod error;
+mod strategies;
+
+pub use api::{
+    API_BASE_URL, HuggingFaceApiClient, HuggingFaceUsage, WhoamiResponse, next_month_start,
+};
+pub use descriptor::huggingface_descriptor;
+pub use error::HuggingFaceError;
+pub use strategies::HuggingFaceApiStrategy;
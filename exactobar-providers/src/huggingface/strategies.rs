@@ -0,0 +1,101 @@
+//! Hugging Face fetch strategies.
+
+use async_trait::async_trait;
+use exactobar_fetch::{FetchContext, FetchError, FetchKind, FetchResult, FetchStrategy};
+use tracing::{debug, instrument};
+
+use super::api::{API_BASE_URL, HuggingFaceApiClient};
+use super::error::HuggingFaceError;
+
+// ============================================================================
+// API Strategy
+// ============================================================================
+
+/// API strategy for Hugging Face Inference Providers.
+///
+/// Uses a Hugging Face user access token to read the month's credit spend.
+pub struct HuggingFaceApiStrategy;
+
+impl HuggingFaceApiStrategy {
+    /// Creates a new strategy.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HuggingFaceApiStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_fetch_error(error: &HuggingFaceError) -> FetchError {
+    match error {
+        HuggingFaceError::AuthenticationFailed(_) => {
+            FetchError::AuthenticationFailed(error.to_string())
+        }
+        _ => FetchError::InvalidResponse(error.to_string()),
+    }
+}
+
+#[async_trait]
+impl FetchStrategy for HuggingFaceApiStrategy {
+    fn id(&self) -> &str {
+        "huggingface.api"
+    }
+
+    fn kind(&self) -> FetchKind {
+        FetchKind::ApiKey
+    }
+
+    #[instrument(skip(self, _ctx))]
+    async fn is_available(&self, _ctx: &FetchContext) -> bool {
+        HuggingFaceApiClient::get_token().is_ok()
+    }
+
+    #[instrument(skip(self, ctx))]
+    async fn fetch(&self, ctx: &FetchContext) -> Result<FetchResult, FetchError> {
+        debug!("Fetching Hugging Face inference credits");
+
+        let token = HuggingFaceApiClient::get_token()
+            .map_err(|e| FetchError::AuthenticationFailed(e.to_string()))?;
+
+        let client = HuggingFaceApiClient::with_base_url(ctx.api_base(API_BASE_URL));
+        let (account, usage) = tokio::join!(
+            client.fetch_whoami(ctx.http.as_ref(), &token),
+            client.fetch_usage(ctx.http.as_ref(), &token),
+        );
+        let account = account.map_err(|e| to_fetch_error(&e))?;
+        let usage = usage.map_err(|e| to_fetch_error(&e))?;
+
+        debug!("Hugging Face credits fetched successfully");
+        let snapshot = usage.to_snapshot(&account);
+
+        Ok(FetchResult::new(snapshot, self.id(), self.kind()))
+    }
+
+    fn priority(&self) -> u32 {
+        60 // API Key priority
+    }
+
+    fn should_fallback(&self, error: &FetchError) -> bool {
+        !matches!(error, FetchError::AuthenticationFailed(_))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_strategy() {
+        let s = HuggingFaceApiStrategy::new();
+        assert_eq!(s.id(), "huggingface.api");
+        assert_eq!(s.kind(), FetchKind::ApiKey);
+        assert_eq!(s.priority(), 60);
+    }
+}
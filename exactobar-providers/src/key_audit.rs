@@ -0,0 +1,272 @@
+//! Health checks for API keys stored in the keychain.
+//!
+//! [`audit_keys`] lists the keys ExactoBar stored and checks each one
+//! still works: provider keys by running the provider's pipeline and
+//! looking at how its API key strategy did, admin keys by fetching the
+//! team report they're for. A key is only [`KeyHealth::Unchecked`] when
+//! nothing used it, e.g. its strategy is turned off.
+//!
+//! [`validate_key`] checks a key the user just entered, before it's
+//! stored, with the provider's cheapest call that needs it, and reports
+//! the account the key belongs to where the provider says.
+
+use std::fmt::Display;
+
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use exactobar_fetch::{FetchContext, FetchKind};
+use exactobar_store::keychain::providers;
+use exactobar_store::{StoredKey, stored_entries};
+use tracing::debug;
+
+use crate::huggingface::{HuggingFaceApiClient, HuggingFaceError};
+use crate::registry::ProviderRegistry;
+use crate::replicate::{ReplicateApiClient, ReplicateError};
+use crate::synthetic::{SyntheticApiClient, SyntheticError};
+use crate::team::{TeamError, fetch_team_usage};
+use crate::zai::{ZaiApiClient, ZaiError};
+
+/// OpenAI's model list, the cheapest call an API key can make.
+const OPENAI_MODELS_URL: &str = "https://api.openai.com/v1/models";
+
+/// Whether a stored key still works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyHealth {
+    /// The provider accepted the key.
+    Valid,
+    /// The provider rejected the key, or using it failed.
+    Invalid(String),
+    /// The key wasn't used, for this reason.
+    Unchecked(String),
+}
+
+impl KeyHealth {
+    /// Short label, e.g. for a status column.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Valid => "Valid",
+            Self::Invalid(_) => "Invalid",
+            Self::Unchecked(_) => "Unchecked",
+        }
+    }
+}
+
+/// A stored key and how its check went.
+#[derive(Debug, Clone)]
+pub struct KeyAudit {
+    /// The keychain entry.
+    pub key: StoredKey,
+    /// Provider the key is for, if it maps to one.
+    pub provider: Option<ProviderKind>,
+    /// Result of the check.
+    pub health: KeyHealth,
+}
+
+/// How a key the user entered did against its provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyValidation {
+    /// Whether the provider accepted the key.
+    pub health: KeyHealth,
+    /// Account or organization the key belongs to, if the provider says.
+    pub account: Option<String>,
+}
+
+impl KeyValidation {
+    fn valid(account: Option<String>) -> Self {
+        Self {
+            health: KeyHealth::Valid,
+            account,
+        }
+    }
+
+    /// A failed call: the key wasn't checked if the provider couldn't be
+    /// reached, and is invalid otherwise.
+    fn failed(error: &dyn Display, unreachable: bool) -> Self {
+        let reason = error.to_string();
+        Self {
+            health: if unreachable {
+                KeyHealth::Unchecked(reason)
+            } else {
+                KeyHealth::Invalid(reason)
+            },
+            account: None,
+        }
+    }
+}
+
+/// The provider a stored key is for, and whether it's an admin key for
+/// team usage rather than a usage key.
+pub fn key_provider(name: &str) -> Option<(ProviderKind, bool)> {
+    match name {
+        providers::SYNTHETIC => Some((ProviderKind::Synthetic, false)),
+        providers::ZAI => Some((ProviderKind::Zai, false)),
+        providers::CODEX => Some((ProviderKind::Codex, false)),
+        providers::GEMINI => Some((ProviderKind::Gemini, false)),
+        providers::HUGGINGFACE => Some((ProviderKind::HuggingFace, false)),
+        providers::REPLICATE => Some((ProviderKind::Replicate, false)),
+        providers::CURSOR_ADMIN => Some((ProviderKind::Cursor, true)),
+        providers::ANTHROPIC_ADMIN => Some((ProviderKind::Claude, true)),
+        _ => None,
+    }
+}
+
+/// Checks a stored key against its provider.
+pub async fn check_key(name: &str) -> KeyHealth {
+    let Some((provider, admin)) = key_provider(name) else {
+        return KeyHealth::Unchecked("Unknown provider".to_string());
+    };
+
+    if admin {
+        return match fetch_team_usage(provider, &HttpClient::new()).await {
+            Ok(_) => KeyHealth::Valid,
+            Err(e @ TeamError::HttpError(_)) => KeyHealth::Unchecked(e.to_string()),
+            Err(e) => KeyHealth::Invalid(e.to_string()),
+        };
+    }
+
+    let Some(desc) = ProviderRegistry::get(provider) else {
+        return KeyHealth::Unchecked("Unknown provider".to_string());
+    };
+    let ctx = FetchContext::builder().build();
+    let outcome = desc.build_pipeline(&ctx).execute(&ctx).await;
+    debug!(provider = ?provider, attempts = outcome.attempts.len(), "Checked stored key");
+
+    match outcome
+        .attempts
+        .iter()
+        .find(|attempt| attempt.kind == FetchKind::ApiKey && !attempt.skipped)
+    {
+        Some(attempt) if attempt.success => KeyHealth::Valid,
+        Some(attempt) => KeyHealth::Invalid(
+            attempt
+                .error
+                .clone()
+                .unwrap_or_else(|| "Fetch failed".to_string()),
+        ),
+        None if outcome.is_success() => {
+            KeyHealth::Unchecked("Another strategy fetched first".to_string())
+        }
+        None => KeyHealth::Unchecked("No API key strategy ran".to_string()),
+    }
+}
+
+/// Checks a key before it's stored under `name`.
+///
+/// Admin keys are only checked once stored, by [`check_key`].
+pub async fn validate_key(name: &str, key: &str) -> KeyValidation {
+    let key = key.trim();
+    let http = HttpClient::new();
+    match name {
+        providers::SYNTHETIC => match SyntheticApiClient::new().fetch_quota(key).await {
+            Ok(_) => KeyValidation::valid(None),
+            Err(e) => {
+                let unreachable = matches!(e, SyntheticError::HttpError(_));
+                KeyValidation::failed(&e, unreachable)
+            }
+        },
+        providers::ZAI => match ZaiApiClient::new().fetch_usage(key).await {
+            Ok(_) => KeyValidation::valid(None),
+            Err(e) => {
+                let unreachable = matches!(e, ZaiError::HttpError(_));
+                KeyValidation::failed(&e, unreachable)
+            }
+        },
+        providers::CODEX => {
+            match http
+                .get_with_auth(OPENAI_MODELS_URL, &format!("Bearer {}", key))
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    let organization = response
+                        .headers()
+                        .get("openai-organization")
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string);
+                    KeyValidation::valid(organization)
+                }
+                Ok(response) => KeyValidation::failed(
+                    &format!("OpenAI returned {}", response.status()),
+                    response.status().is_server_error(),
+                ),
+                Err(e) => KeyValidation::failed(&e, true),
+            }
+        }
+        providers::HUGGINGFACE => {
+            match HuggingFaceApiClient::new().fetch_whoami(&http, key).await {
+                Ok(whoami) => KeyValidation::valid(Some(whoami.email.unwrap_or(whoami.name))),
+                Err(e) => {
+                    let unreachable = matches!(e, HuggingFaceError::HttpError(_));
+                    KeyValidation::failed(&e, unreachable)
+                }
+            }
+        }
+        providers::REPLICATE => match ReplicateApiClient::new().fetch_account(&http, key).await {
+            Ok(account) => KeyValidation::valid(Some(account.name.unwrap_or(account.username))),
+            Err(e) => {
+                let unreachable = matches!(e, ReplicateError::HttpError(_));
+                KeyValidation::failed(&e, unreachable)
+            }
+        },
+        _ => KeyValidation {
+            health: KeyHealth::Unchecked("Checked once stored".to_string()),
+            account: None,
+        },
+    }
+}
+
+/// Lists the stored keys and checks each one, in [`providers::ALL`] order.
+pub async fn audit_keys() -> Vec<KeyAudit> {
+    let keys = tokio::task::spawn_blocking(stored_entries)
+        .await
+        .unwrap_or_default();
+
+    let mut audits = Vec::with_capacity(keys.len());
+    for key in keys {
+        let health = check_key(key.provider).await;
+        audits.push(KeyAudit {
+            provider: key_provider(key.provider).map(|(provider, _)| provider),
+            key,
+            health,
+        });
+    }
+    audits
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_key_has_a_provider() {
+        for name in providers::ALL {
+            assert!(key_provider(name).is_some(), "{name} has no provider");
+        }
+        assert_eq!(
+            key_provider(providers::ANTHROPIC_ADMIN),
+            Some((ProviderKind::Claude, true))
+        );
+        assert_eq!(key_provider("nope"), None);
+    }
+
+    #[test]
+    fn test_failed_validation() {
+        let offline = KeyValidation::failed(&"HTTP error: timed out", true);
+        assert_eq!(offline.health.label(), "Unchecked");
+        let rejected = KeyValidation::failed(&"Authentication failed: 401", false);
+        assert_eq!(
+            rejected.health,
+            KeyHealth::Invalid("Authentication failed: 401".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_keys_checked_once_stored() {
+        let validation = validate_key(providers::CURSOR_ADMIN, "key").await;
+        assert_eq!(validation.health.label(), "Unchecked");
+        assert_eq!(validation.account, None);
+    }
+}
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::KiroCliStrategy;
+use crate::compat::{CliVersion, VersionRange};
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn kiro_descriptor() -> ProviderDescriptor {
@@ -70,5 +71,9 @@ fn kiro_cli_config() -> CliConfig {
         aliases: &["kiro"],
         version_args: &["--version"],
         usage_args: &["/usage"],
+        compatible_versions: VersionRange::between(
+            CliVersion::new(1, 20, 0),
+            CliVersion::new(2, 0, 0),
+        ),
     }
 }
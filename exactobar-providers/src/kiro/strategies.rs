@@ -8,7 +8,7 @@ use tracing::{debug, instrument, warn};
 
 use super::cli::ensure_logged_in;
 use super::error::KiroError;
-use super::parser::parse_kiro_response;
+use crate::recording::{self, RawFormat};
 
 // ============================================================================
 // CLI Strategy
@@ -80,7 +80,9 @@ impl FetchStrategy for KiroCliStrategy {
             )));
         }
 
-        let snapshot = parse_kiro_response(&output.stdout)?;
+        recording::record(RawFormat::Kiro, &output.stdout);
+
+        let snapshot = RawFormat::Kiro.parse(&output.stdout)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
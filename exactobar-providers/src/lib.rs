@@ -51,6 +51,16 @@
 //! - **Strategies**: Fetch strategy implementations (CLI, OAuth, Web)
 //! - **Parser**: Response parsing for various formats
 //!
+//! The [`pricing`] module holds per-model token prices used to compute costs
+//! from local usage logs, and [`cost`] aggregates those logs into reports.
+//! [`recording`] saves raw responses handed to the parsers and replays them
+//! for bug reports. [`compat`] checks installed CLI versions against the
+//! ranges each descriptor supports. [`mock_server`] serves canned usage
+//! responses for end-to-end tests and demos. [`team`] ranks an
+//! organization's members by usage through the providers' admin APIs.
+//! [`sheets`] appends daily usage and cost rows to a Google Sheet.
+//! [`key_audit`] checks the API keys stored in the keychain still work.
+//!
 //! ## Supported Providers (12 total)
 //!
 //! | Provider | CLI | OAuth | API Key | Web | Local | Status |
@@ -67,6 +77,9 @@
 //! | Kiro (AWS) | ✅ | ❌ | ❌ | ❌ | ❌ | Active |
 //! | MiniMax | ❌ | ❌ | ❌ | ✅ | ✅ | Active |
 //! | Antigravity | ❌ | ❌ | ❌ | ❌ | ✅ | Active |
+//! | GitHub Models | ❌ | ✅ | ❌ | ❌ | ❌ | Active |
+//! | Hugging Face | ❌ | ❌ | ✅ | ❌ | ❌ | Active |
+//! | Replicate | ❌ | ❌ | ✅ | ❌ | ❌ | Active |
 //!
 //! ## Usage
 //!
@@ -84,8 +97,18 @@
 //! let outcome = pipeline.execute(&ctx).await;
 //! ```
 
+pub mod compat;
+pub mod cost;
+pub mod custom;
 pub mod descriptor;
+pub mod env_keys;
+pub mod key_audit;
+pub mod mock_server;
+pub mod pricing;
+pub mod recording;
 pub mod registry;
+pub mod sheets;
+pub mod team;
 
 // Provider modules (alphabetical)
 pub mod antigravity;
@@ -96,17 +119,30 @@ pub mod copilot;
 pub mod cursor;
 pub mod factory;
 pub mod gemini;
+pub mod github_models;
+pub mod huggingface;
 pub mod kiro;
 pub mod minimax;
+pub mod replicate;
 pub mod synthetic;
 pub mod vertexai;
 pub mod zai;
 
 // Re-export key types
+pub use compat::{CliVersion, VersionCheck, VersionRange, VersionStatus};
+pub use cost::{CostAggregator, LiveCostWatcher, LogFormat, LogIndex, UsageRecord};
+pub use custom::{fetch_custom, sync_custom_providers};
 pub use descriptor::{
-    CliConfig, FetchPlan, ProviderDescriptor, ProviderDescriptorBuilder, TokenCostConfig,
+    CliConfig, FetchPlan, PlannedStrategy, ProviderDescriptor, ProviderDescriptorBuilder,
+    TokenCostConfig,
 };
+pub use env_keys::{EnvKey, EnvKeyUse, discover_env_keys, import_env_key};
+pub use key_audit::{KeyAudit, KeyHealth, KeyValidation, audit_keys, validate_key};
+pub use mock_server::{MockRoute, MockServer, fixture_routes};
+pub use pricing::{ModelPrice, PricingTable, TokenCounts};
+pub use recording::{RawFormat, Recording};
 pub use registry::ProviderRegistry;
+pub use team::{TeamError, TeamRank, TeamUnit, TeamUsage, fetch_team_usage, supports_team_usage};
 
 // Re-export provider descriptors
 pub use antigravity::antigravity_descriptor;
@@ -117,8 +153,11 @@ pub use copilot::copilot_descriptor;
 pub use cursor::cursor_descriptor;
 pub use factory::factory_descriptor;
 pub use gemini::gemini_descriptor;
+pub use github_models::github_models_descriptor;
+pub use huggingface::huggingface_descriptor;
 pub use kiro::kiro_descriptor;
 pub use minimax::minimax_descriptor;
+pub use replicate::replicate_descriptor;
 pub use synthetic::synthetic_descriptor;
 pub use vertexai::vertexai_descriptor;
 pub use zai::zai_descriptor;
@@ -132,8 +171,11 @@ pub use copilot::{CopilotApiStrategy, CopilotEnvStrategy};
 pub use cursor::{CursorLocalStrategy, CursorWebStrategy};
 pub use factory::{FactoryLocalStrategy, FactoryWebStrategy};
 pub use gemini::{GeminiCliStrategy, GeminiOAuthStrategy};
+pub use github_models::GitHubModelsApiStrategy;
+pub use huggingface::HuggingFaceApiStrategy;
 pub use kiro::KiroCliStrategy;
 pub use minimax::{MiniMaxLocalStrategy, MiniMaxWebStrategy};
+pub use replicate::ReplicateApiStrategy;
 pub use synthetic::SyntheticApiStrategy;
 pub use vertexai::{VertexAILocalStrategy, VertexAIOAuthStrategy};
 pub use zai::ZaiApiStrategy;
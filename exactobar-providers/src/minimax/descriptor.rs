@@ -6,6 +6,7 @@ use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 use super::strategies::{
     HailuoaiWebStrategy, MiniMaxLocalStorageStrategy, MiniMaxLocalStrategy, MiniMaxWebStrategy,
 };
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn minimax_descriptor() -> ProviderDescriptor {
@@ -81,5 +82,6 @@ fn minimax_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &[],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -15,8 +15,8 @@ use exactobar_fetch::{
 use std::path::PathBuf;
 use tracing::{debug, info, instrument};
 
-use super::parser::parse_minimax_response;
 use super::web::{HAILUOAI_DOMAIN, MINIMAX_DOMAIN, MiniMaxLocalStorage, MiniMaxWebClient};
+use crate::recording::{self, RawFormat};
 
 const MINIMAX_API: &str = "https://api.minimax.chat/v1/usage";
 const HAILUOAI_API: &str = "https://hailuoai.com/api/user/usage";
@@ -86,29 +86,34 @@ impl FetchStrategy for MiniMaxWebStrategy {
 
         let response = ctx
             .http
-            .get_with_cookies(MINIMAX_API, &cookie_header)
+            .get_cached_with_cookies(MINIMAX_API, &cookie_header)
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(FetchError::RateLimited {
+                retry_after: response.retry_after,
+            });
+        }
+
+        if response.status == reqwest::StatusCode::UNAUTHORIZED {
             return Err(FetchError::AuthenticationFailed(
                 "Cookies rejected".to_string(),
             ));
         }
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(FetchError::InvalidResponse(format!(
                 "API returned {}",
-                response.status()
+                response.status
             )));
         }
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+        let body = response.body;
 
-        let snapshot = parse_minimax_response(&body)?;
+        recording::record(RawFormat::Minimax, &body);
+
+        let snapshot = RawFormat::Minimax.parse(&body)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -186,29 +191,34 @@ impl FetchStrategy for HailuoaiWebStrategy {
 
         let response = ctx
             .http
-            .get_with_cookies(HAILUOAI_API, &cookie_header)
+            .get_cached_with_cookies(HAILUOAI_API, &cookie_header)
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(FetchError::RateLimited {
+                retry_after: response.retry_after,
+            });
+        }
+
+        if response.status == reqwest::StatusCode::UNAUTHORIZED {
             return Err(FetchError::AuthenticationFailed(
                 "Hailuoai cookies rejected".to_string(),
             ));
         }
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(FetchError::InvalidResponse(format!(
                 "Hailuoai API returned {}",
-                response.status()
+                response.status
             )));
         }
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
+        let body = response.body;
+
+        recording::record(RawFormat::Minimax, &body);
 
-        let snapshot = parse_minimax_response(&body)?;
+        let snapshot = RawFormat::Minimax.parse(&body)?;
         info!("Fetched MiniMax usage from hailuoai.com");
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
@@ -289,7 +299,9 @@ impl FetchStrategy for MiniMaxLocalStorageStrategy {
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_minimax_response(&body)?;
+        recording::record(RawFormat::Minimax, &body);
+
+        let snapshot = RawFormat::Minimax.parse(&body)?;
         info!("Fetched MiniMax usage from localStorage token");
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
@@ -346,7 +358,9 @@ impl FetchStrategy for MiniMaxLocalStrategy {
         let content = std::fs::read_to_string(&path)
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_minimax_response(&content)?;
+        recording::record(RawFormat::Minimax, &content);
+
+        let snapshot = RawFormat::Minimax.parse(&content)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
@@ -0,0 +1,359 @@
+//! Mock provider API server for end-to-end tests and demos.
+//!
+//! [`MockServer`] answers the usage endpoints of Claude (OAuth), Cursor and
+//! Copilot on `127.0.0.1` with canned responses, so fetches run without
+//! network access or real usage. Point a fetch context at it with
+//! [`FetchContextBuilder::endpoint_override`](exactobar_fetch::FetchContextBuilder::endpoint_override),
+//! or the CLI's `--endpoint-override`:
+//!
+//! ```ignore
+//! let routes = fixture_routes(Path::new("exactobar-providers/tests/corpus"))?;
+//! let server = MockServer::start(0, routes).await?;
+//! let ctx = FetchContext::builder()
+//!     .endpoint_override(server.base_url())
+//!     .build();
+//! ```
+//!
+//! Fixture directories are laid out like `tests/corpus`: a directory per
+//! [`RawFormat`], whose `usage.json` (or else first file by name) is served
+//! at that format's endpoint ([`ENDPOINTS`]). Strategies still look up credentials as usual
+//! before making a request, but the server accepts any.
+//!
+//! It's a development tool, not a real HTTP server: one request per
+//! connection, no TLS, no chunked bodies.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::recording::RawFormat;
+
+/// Maximum accepted request head size.
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// How long a single connection may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Endpoints the server emulates, and the format of their responses.
+pub const ENDPOINTS: &[(&str, RawFormat)] = &[
+    (crate::claude::USAGE_ENDPOINT, RawFormat::ClaudeOauth),
+    (crate::cursor::USAGE_ENDPOINT, RawFormat::Cursor),
+    (crate::copilot::USAGE_ENDPOINT, RawFormat::Copilot),
+];
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// A canned response for a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRoute {
+    /// Request path, without the query string.
+    pub path: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// JSON response body.
+    pub body: String,
+}
+
+impl MockRoute {
+    /// Answers `path` with `200 OK` and `body`.
+    pub fn ok(path: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::status(path, 200, body)
+    }
+
+    /// Answers `path` with `status` and `body`.
+    pub fn status(path: impl Into<String>, status: u16, body: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Returns a route for every endpoint in [`ENDPOINTS`] that has a fixture
+/// in `dir`.
+pub fn fixture_routes(dir: &Path) -> io::Result<Vec<MockRoute>> {
+    let mut routes = Vec::new();
+    for (path, format) in ENDPOINTS {
+        let Ok(entries) = std::fs::read_dir(dir.join(format.name())) else {
+            continue;
+        };
+        let mut files: Vec<_> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+        let fixture = files
+            .iter()
+            .find(|file| file.ends_with("usage.json"))
+            .or(files.first());
+        if let Some(file) = fixture {
+            debug!(path, fixture = %file.display(), "Mock route");
+            routes.push(MockRoute::ok(*path, std::fs::read_to_string(file)?));
+        }
+    }
+    Ok(routes)
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// A running mock server. It stops when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<String>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts serving `routes` on `127.0.0.1:<port>`; port `0` lets the OS
+    /// pick a free one. Other paths get a 404.
+    pub async fn start(port: u16, routes: Vec<MockRoute>) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        let addr = listener.local_addr()?;
+        let routes: Arc<HashMap<String, MockRoute>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|route| (route.path.clone(), route))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        let task = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!(error = %e, "Mock server accept failed");
+                        continue;
+                    }
+                };
+                let routes = Arc::clone(&routes);
+                let log = Arc::clone(&log);
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &routes, &log).await {
+                        debug!(%peer, error = %e, "Mock server connection error");
+                    }
+                });
+            }
+        });
+
+        info!(%addr, "Mock server listening");
+        Ok(Self {
+            addr,
+            requests,
+            task,
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the URL to use as the endpoint override.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the requests served so far, like `GET /copilot/usage`.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    routes: &HashMap<String, MockRoute>,
+    log: &Mutex<Vec<String>>,
+) -> io::Result<()> {
+    let Ok(Ok(Some((method, target)))) =
+        tokio::time::timeout(REQUEST_TIMEOUT, read_request_line(&mut stream)).await
+    else {
+        return write_response(&mut stream, 400, r#"{"error":"bad request"}"#).await;
+    };
+
+    let path = target.split('?').next().unwrap_or_default();
+    log.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(format!("{method} {path}"));
+
+    match routes.get(path) {
+        Some(route) => write_response(&mut stream, route.status, &route.body).await,
+        None => write_response(&mut stream, 404, r#"{"error":"not found"}"#).await,
+    }
+}
+
+/// Reads a request's head and returns its method and target. Returns `None`
+/// for malformed input.
+async fn read_request_line(stream: &mut TcpStream) -> io::Result<Option<(String, String)>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    Ok(match (request_line.next(), request_line.next()) {
+        (Some(method), Some(target)) => Some((method.to_string(), target.to_string())),
+        _ => None,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use async_trait::async_trait;
+    use exactobar_fetch::host::keychain::KeychainApi;
+    use exactobar_fetch::{FetchContext, FetchError, FetchStrategy, KeychainError};
+
+    use super::*;
+    use crate::claude::ClaudeApiClient;
+    use crate::copilot::CopilotApiStrategy;
+    use crate::cursor::CursorWebClient;
+
+    fn corpus_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+    }
+
+    /// Keychain that hands out the same token for everything.
+    struct TestKeychain;
+
+    #[async_trait]
+    impl KeychainApi for TestKeychain {
+        async fn get(&self, _: &str, _: &str) -> Result<Option<String>, KeychainError> {
+            Ok(Some("test-token".to_string()))
+        }
+        async fn set(&self, _: &str, _: &str, _: &str) -> Result<(), KeychainError> {
+            Ok(())
+        }
+        async fn delete(&self, _: &str, _: &str) -> Result<(), KeychainError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_fixture_routes() {
+        let routes = fixture_routes(&corpus_dir()).unwrap();
+        assert_eq!(routes.len(), ENDPOINTS.len());
+        assert!(routes.iter().all(|r| r.status == 200 && !r.body.is_empty()));
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(fixture_routes(empty.path()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetches_from_fixtures() {
+        let server = MockServer::start(0, fixture_routes(&corpus_dir()).unwrap())
+            .await
+            .unwrap();
+        let ctx = FetchContext::builder()
+            .keychain(Arc::new(TestKeychain))
+            .endpoint_override(server.base_url())
+            .build();
+
+        let result = CopilotApiStrategy::new().fetch(&ctx).await.unwrap();
+        assert!(result.snapshot.primary.is_some());
+
+        let claude = ClaudeApiClient::with_base_url(server.base_url())
+            .fetch_usage_with_token("test-token")
+            .await
+            .unwrap();
+        assert!(claude.to_snapshot().primary.is_some());
+
+        let cursor = CursorWebClient::with_base_url(server.base_url())
+            .fetch_usage("cursor_session=test")
+            .await
+            .unwrap();
+        assert!(cursor.to_snapshot().primary.is_some());
+
+        assert_eq!(
+            server.requests(),
+            vec![
+                "GET /copilot/usage",
+                "GET /api/oauth/usage",
+                "GET /api/usage"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_routes() {
+        let server = MockServer::start(
+            0,
+            vec![MockRoute::status(
+                crate::copilot::USAGE_ENDPOINT,
+                401,
+                r#"{"message":"Bad credentials"}"#,
+            )],
+        )
+        .await
+        .unwrap();
+        let ctx = FetchContext::builder()
+            .keychain(Arc::new(TestKeychain))
+            .endpoint_override(server.base_url())
+            .build();
+
+        let error = CopilotApiStrategy::new().fetch(&ctx).await.unwrap_err();
+        assert!(matches!(error, FetchError::AuthenticationFailed(_)));
+
+        let cursor = CursorWebClient::with_base_url(server.base_url())
+            .fetch_usage("cursor_session=test")
+            .await;
+        assert!(cursor.is_err());
+        assert_eq!(server.requests()[1], "GET /api/usage");
+    }
+}
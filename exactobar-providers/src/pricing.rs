@@ -0,0 +1,488 @@
+//! Model pricing tables for token cost computation.
+//!
+//! Provider logs don't always carry a cost, and when they do it reflects the
+//! price at the time of logging. This module keeps a maintained table of
+//! per-model prices (USD per million tokens) with effective dates, so costs
+//! can be recomputed from raw token counts for any day in the history.
+//!
+//! ## User Overrides
+//!
+//! Prices can be overridden from `pricing.toml` in the config directory:
+//!
+//! ```toml
+//! [[models]]
+//! model = "claude-sonnet-4"
+//! input = 3.0
+//! output = 15.0
+//! cache_read = 0.3
+//! cache_write = 3.75
+//! effective_from = "2025-05-22"
+//! ```
+//!
+//! An override replaces every built-in entry for the same model, so list
+//! several entries with different `effective_from` dates to model a price
+//! change. Because costs are always recomputed from token counts at scan
+//! time, editing the file reprices historical usage on the next scan.
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Errors loading pricing overrides.
+#[derive(Debug, Error)]
+pub enum PricingError {
+    /// Failed to read the overrides file.
+    #[error("Failed to read pricing file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failed to parse the overrides file.
+    #[error("Invalid pricing file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+// ============================================================================
+// Model Price
+// ============================================================================
+
+/// Price of a model, in USD per million tokens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Model name prefix (e.g. `claude-sonnet-4` matches `claude-sonnet-4-20250514`).
+    pub model: String,
+    /// Input token rate.
+    pub input: f64,
+    /// Output token rate (reasoning tokens are billed as output).
+    pub output: f64,
+    /// Cache-read token rate (defaults to the input rate).
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+    /// Cache-write token rate (defaults to the input rate).
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+    /// First day this price applies.
+    #[serde(default = "epoch")]
+    pub effective_from: NaiveDate,
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::default()
+}
+
+impl ModelPrice {
+    /// Computes the cost of the given token counts at this price.
+    pub fn cost(&self, tokens: &TokenCounts) -> f64 {
+        let per_token = |rate: f64, count: u64| rate * count as f64 / 1_000_000.0;
+
+        per_token(self.input, tokens.input)
+            + per_token(self.output, tokens.output)
+            + per_token(self.cache_read.unwrap_or(self.input), tokens.cache_read)
+            + per_token(self.cache_write.unwrap_or(self.input), tokens.cache_write)
+    }
+}
+
+/// Token counts to price, by billing category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenCounts {
+    /// Uncached input tokens.
+    pub input: u64,
+    /// Output tokens, including reasoning tokens.
+    pub output: u64,
+    /// Tokens read from the prompt cache.
+    pub cache_read: u64,
+    /// Tokens written to the prompt cache.
+    pub cache_write: u64,
+}
+
+// ============================================================================
+// Pricing Table
+// ============================================================================
+
+/// Shape of the `pricing.toml` overrides file.
+#[derive(Debug, Default, Deserialize)]
+struct PricingFile {
+    #[serde(default)]
+    models: Vec<ModelPrice>,
+}
+
+/// Table of model prices with effective dates.
+#[derive(Debug, Clone)]
+pub struct PricingTable {
+    prices: Vec<ModelPrice>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PricingTable {
+    /// Creates a table from explicit prices.
+    pub fn new(prices: Vec<ModelPrice>) -> Self {
+        Self { prices }
+    }
+
+    /// Returns the built-in pricing table.
+    pub fn builtin() -> Self {
+        Self::new(builtin_prices())
+    }
+
+    /// Loads the built-in table merged with the user's `pricing.toml`, if any.
+    ///
+    /// A malformed overrides file is logged and ignored.
+    pub fn load_default() -> Self {
+        let mut table = Self::builtin();
+        let path = exactobar_store::default_pricing_path();
+
+        if path.exists() {
+            match Self::load_overrides(&path) {
+                Ok(overrides) => {
+                    debug!(count = overrides.len(), path = %path.display(), "Loaded pricing overrides");
+                    table.apply_overrides(overrides);
+                }
+                Err(e) => warn!(path = %path.display(), error = %e, "Ignoring pricing overrides"),
+            }
+        }
+
+        table
+    }
+
+    /// Reads price overrides from a TOML file.
+    pub fn load_overrides(path: &Path) -> Result<Vec<ModelPrice>, PricingError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_overrides(&content)
+    }
+
+    /// Parses price overrides from TOML.
+    pub fn parse_overrides(content: &str) -> Result<Vec<ModelPrice>, PricingError> {
+        let file: PricingFile = toml::from_str(content)?;
+        Ok(file.models)
+    }
+
+    /// Applies overrides, replacing all existing entries for each overridden model.
+    pub fn apply_overrides(&mut self, overrides: Vec<ModelPrice>) {
+        self.prices.retain(|p| {
+            !overrides
+                .iter()
+                .any(|o| o.model.eq_ignore_ascii_case(&p.model))
+        });
+        self.prices.extend(overrides);
+    }
+
+    /// Returns all entries in the table.
+    pub fn prices(&self) -> &[ModelPrice] {
+        &self.prices
+    }
+
+    /// Finds the price for a model on a given day.
+    ///
+    /// The longest matching model prefix wins. A prefix only matches when
+    /// what follows it is a date or release tag, so neither `o3-mini` nor
+    /// `claude-opus-4-6` gets the price of `o3` or `claude-opus-4`. Among its entries, the latest one already in effect is used;
+    /// usage older than every entry falls back to the earliest known price.
+    pub fn price_for(&self, model: &str, date: NaiveDate) -> Option<&ModelPrice> {
+        let name = normalize_model(model);
+
+        let best_prefix = self
+            .prices
+            .iter()
+            .filter(|p| matches_model(&name, &p.model))
+            .map(|p| p.model.len())
+            .max()?;
+
+        let candidates = self
+            .prices
+            .iter()
+            .filter(|p| p.model.len() == best_prefix && matches_model(&name, &p.model));
+
+        let in_effect = candidates
+            .clone()
+            .filter(|p| p.effective_from <= date)
+            .max_by_key(|p| p.effective_from);
+
+        in_effect.or_else(|| candidates.min_by_key(|p| p.effective_from))
+    }
+
+    /// Computes the cost of a request, or `None` if the model is unknown.
+    pub fn cost_for(&self, model: &str, date: NaiveDate, tokens: &TokenCounts) -> Option<f64> {
+        self.price_for(model, date).map(|p| p.cost(tokens))
+    }
+}
+
+/// Whether `model` is `entry`, optionally followed by a date or release tag
+/// (`-20250514`, `-2025-08-07`, `@20240620`, `-preview-05-06`) rather than
+/// a different model's name (`-mini`, `-pro`) or minor version (`-1`, `.1`).
+fn matches_model(model: &str, entry: &str) -> bool {
+    let Some(rest) = model.strip_prefix(entry.to_lowercase().as_str()) else {
+        return false;
+    };
+    if rest.is_empty() || rest.starts_with('@') {
+        return true;
+    }
+    let Some(suffix) = rest.strip_prefix('-') else {
+        return false;
+    };
+    starts_with_date(suffix)
+        || ["latest", "preview", "exp"]
+            .iter()
+            .any(|tag| suffix.starts_with(tag))
+}
+
+/// Whether `s` starts with a `YYYYMMDD` or `YYYY-MM-DD` date.
+fn starts_with_date(s: &str) -> bool {
+    let shape = |pattern: &str| {
+        s.len() >= pattern.len()
+            && s.bytes().zip(pattern.bytes()).all(|(c, p)| match p {
+                b'9' => c.is_ascii_digit(),
+                _ => c == p,
+            })
+            && !s[pattern.len()..].starts_with(|c: char| c.is_ascii_digit())
+    };
+    shape("99999999") || shape("9999-99-99")
+}
+
+/// Lowercases a model name and strips any `vendor/` prefix.
+fn normalize_model(model: &str) -> String {
+    model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .trim()
+        .to_lowercase()
+}
+
+// ============================================================================
+// Built-in Prices
+// ============================================================================
+
+/// `(model, input, output, cache_read, cache_write, (year, month, day))`.
+type BuiltinRow = (&'static str, f64, f64, f64, f64, (i32, u32, u32));
+
+/// Built-in prices in USD per million tokens.
+///
+/// Keep this list in sync with the providers' public pricing pages. When a
+/// price changes, add a new entry with the new `effective_from` date rather
+/// than editing the old one, so history keeps its original price.
+fn builtin_prices() -> Vec<ModelPrice> {
+    #[rustfmt::skip]
+    let table: &[BuiltinRow] = &[
+        // model,               input,  output, cache_read, cache_write, effective
+        // Anthropic
+        ("claude-3-opus",       15.0,  75.0,   1.5,   18.75, (2024, 3, 4)),
+        ("claude-3-5-sonnet",    3.0,  15.0,   0.3,    3.75, (2024, 6, 20)),
+        ("claude-3-5-haiku",     0.8,   4.0,   0.08,   1.0,  (2024, 10, 22)),
+        ("claude-3-7-sonnet",    3.0,  15.0,   0.3,    3.75, (2025, 2, 24)),
+        ("claude-opus-4",       15.0,  75.0,   1.5,   18.75, (2025, 5, 22)),
+        ("claude-opus-4-1",     15.0,  75.0,   1.5,   18.75, (2025, 8, 5)),
+        ("claude-opus-4-5",      5.0,  25.0,   0.5,    6.25, (2025, 11, 24)),
+        ("claude-sonnet-4",      3.0,  15.0,   0.3,    3.75, (2025, 5, 22)),
+        ("claude-sonnet-4-5",    3.0,  15.0,   0.3,    3.75, (2025, 9, 29)),
+        ("claude-haiku-4-5",     1.0,   5.0,   0.1,    1.25, (2025, 10, 15)),
+        // OpenAI
+        ("gpt-4o",               2.5,  10.0,   1.25,   2.5,  (2024, 8, 6)),
+        ("gpt-4o-mini",          0.15,  0.6,   0.075,  0.15, (2024, 7, 18)),
+        ("gpt-4.1",              2.0,   8.0,   0.5,    2.0,  (2025, 4, 14)),
+        ("gpt-4.1-mini",         0.4,   1.6,   0.1,    0.4,  (2025, 4, 14)),
+        ("gpt-4.1-nano",         0.1,   0.4,   0.025,  0.1,  (2025, 4, 14)),
+        ("o4-mini",              1.1,   4.4,   0.275,  1.1,  (2025, 4, 16)),
+        ("codex-mini",           1.5,   6.0,   0.375,  1.5,  (2025, 5, 16)),
+        ("o3",                  10.0,  40.0,   2.5,   10.0,  (2025, 4, 16)),
+        ("o3",                   2.0,   8.0,   0.5,    2.0,  (2025, 6, 10)),
+        ("o3-mini",              1.1,   4.4,   0.55,   1.1,  (2025, 1, 31)),
+        ("gpt-5",                1.25, 10.0,   0.125,  1.25, (2025, 8, 7)),
+        ("gpt-5-mini",           0.25,  2.0,   0.025,  0.25, (2025, 8, 7)),
+        ("gpt-5-nano",           0.05,  0.4,   0.005,  0.05, (2025, 8, 7)),
+        ("gpt-5-codex",          1.25, 10.0,   0.125,  1.25, (2025, 9, 15)),
+        // Google
+        ("gemini-2.0-flash",     0.1,   0.4,   0.025,  0.1,  (2025, 2, 5)),
+        ("gemini-2.5-flash",     0.3,   2.5,   0.075,  0.3,  (2025, 6, 17)),
+        ("gemini-2.5-flash-lite", 0.1,  0.4,   0.025,  0.1,  (2025, 7, 22)),
+        ("gemini-2.5-pro",       1.25, 10.0,   0.31,   1.25, (2025, 6, 17)),
+    ];
+
+    table
+        .iter()
+        .filter_map(
+            |&(model, input, output, cache_read, cache_write, (y, m, d))| {
+                Some(ModelPrice {
+                    model: model.to_string(),
+                    input,
+                    output,
+                    cache_read: Some(cache_read),
+                    cache_write: Some(cache_write),
+                    effective_from: NaiveDate::from_ymd_opt(y, m, d)?,
+                })
+            },
+        )
+        .collect()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_table_parses() {
+        let table = PricingTable::builtin();
+        assert!(table.prices().len() >= 20);
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let table = PricingTable::builtin();
+        let today = date(2026, 1, 1);
+
+        let opus = table.price_for("claude-opus-4-5-20251101", today).unwrap();
+        assert_eq!(opus.model, "claude-opus-4-5");
+
+        let opus41 = table.price_for("claude-opus-4-1-20250805", today).unwrap();
+        assert_eq!(opus41.model, "claude-opus-4-1");
+
+        let codex = table.price_for("gpt-5-codex", today).unwrap();
+        assert_eq!(codex.model, "gpt-5-codex");
+    }
+
+    #[test]
+    fn test_prefix_stops_at_model_name() {
+        let table = PricingTable::builtin();
+        let today = date(2026, 1, 1);
+
+        let o3_mini = table.price_for("o3-mini", today).unwrap();
+        assert_eq!(o3_mini.model, "o3-mini");
+        let nano = table.price_for("gpt-5-nano-2025-08-07", today).unwrap();
+        assert_eq!(nano.model, "gpt-5-nano");
+
+        // Unlisted variants are unknown rather than priced as the base model
+        assert!(table.price_for("o3-pro", today).is_none());
+        assert!(table.price_for("gpt-5-pro", today).is_none());
+
+        let vertex = table
+            .price_for("claude-3-5-sonnet@20240620", today)
+            .unwrap();
+        assert_eq!(vertex.model, "claude-3-5-sonnet");
+        let preview = table
+            .price_for("gemini-2.5-flash-preview-05-20", today)
+            .unwrap();
+        assert_eq!(preview.model, "gemini-2.5-flash");
+    }
+
+    #[test]
+    fn test_minor_version_is_a_different_model() {
+        let table = PricingTable::builtin();
+        let today = date(2026, 1, 1);
+
+        assert!(table.price_for("claude-opus-4-6", today).is_none());
+        assert!(table.price_for("claude-opus-4-6-20260201", today).is_none());
+        assert!(table.price_for("gpt-5.1", today).is_none());
+
+        let sonnet = table
+            .price_for("claude-sonnet-4-5-20250929", today)
+            .unwrap();
+        assert_eq!(sonnet.model, "claude-sonnet-4-5");
+    }
+
+    #[test]
+    fn test_vendor_prefix_and_case() {
+        let table = PricingTable::builtin();
+        let price = table
+            .price_for("anthropic/Claude-Sonnet-4-20250514", date(2025, 7, 1))
+            .unwrap();
+        assert_eq!(price.model, "claude-sonnet-4");
+    }
+
+    #[test]
+    fn test_effective_dates() {
+        let table = PricingTable::builtin();
+
+        let before_cut = table.price_for("o3", date(2025, 5, 1)).unwrap();
+        assert!((before_cut.input - 10.0).abs() < f64::EPSILON);
+
+        let after_cut = table.price_for("o3", date(2025, 7, 1)).unwrap();
+        assert!((after_cut.input - 2.0).abs() < f64::EPSILON);
+
+        // Usage older than any entry falls back to the earliest price
+        let ancient = table.price_for("o3", date(2020, 1, 1)).unwrap();
+        assert!((ancient.input - 10.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_unknown_model() {
+        let table = PricingTable::builtin();
+        assert!(table.price_for("mystery-model", date(2025, 1, 1)).is_none());
+    }
+
+    #[test]
+    fn test_cost_computation() {
+        let price = ModelPrice {
+            model: "m".to_string(),
+            input: 3.0,
+            output: 15.0,
+            cache_read: Some(0.3),
+            cache_write: None,
+            effective_from: epoch(),
+        };
+
+        let tokens = TokenCounts {
+            input: 1_000_000,
+            output: 100_000,
+            cache_read: 1_000_000,
+            cache_write: 1_000_000,
+        };
+
+        // 3.0 + 1.5 + 0.3 + 3.0 (cache write falls back to input rate)
+        assert!((price.cost(&tokens) - 7.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overrides_replace_builtin_entries() {
+        let toml = r#"
+            [[models]]
+            model = "o3"
+            input = 1.0
+            output = 4.0
+
+            [[models]]
+            model = "my-local-model"
+            input = 0.0
+            output = 0.0
+            effective_from = "2025-01-01"
+        "#;
+
+        let overrides = PricingTable::parse_overrides(toml).unwrap();
+        assert_eq!(overrides.len(), 2);
+
+        let mut table = PricingTable::builtin();
+        table.apply_overrides(overrides);
+
+        // Both built-in o3 entries are gone; the override applies to all dates
+        let o3 = table.price_for("o3", date(2025, 5, 1)).unwrap();
+        assert!((o3.input - 1.0).abs() < f64::EPSILON);
+        assert_eq!(table.prices().iter().filter(|p| p.model == "o3").count(), 1);
+
+        assert!(
+            table
+                .price_for("my-local-model", date(2025, 6, 1))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        assert!(PricingTable::parse_overrides("models = 3").is_err());
+    }
+}
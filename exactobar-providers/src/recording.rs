@@ -0,0 +1,416 @@
+//! Recording raw provider responses and replaying them.
+//!
+//! Parser bugs are hard to reproduce: the response that broke a parser is
+//! gone by the time someone reports it. In record mode, every raw response
+//! handed to a parser is also written to a directory, with tokens, cookies
+//! and emails redacted:
+//!
+//! ```text
+//! <dir>/claude-claude_oauth-20250115T160000.123.json
+//! <dir>/codex-codex_cli-20250115T160002.456.json
+//! ```
+//!
+//! `exactobar usage --replay <dir>` runs each recording back through its
+//! parser, so the recordings can be attached to a bug report and the
+//! failure reproduced without the reporter's account.
+//!
+//! Recording is turned on with [`start_recording`] (`exactobar usage
+//! --record <dir>`) or the `EXACTOBAR_RECORD_DIR` environment variable,
+//! which also works for the app.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use exactobar_core::redact::Redactor;
+use exactobar_core::{ProviderKind, UsageSnapshot};
+use exactobar_fetch::progress::{self, FetchStage};
+use exactobar_fetch::{FetchError, ParseDiagnostics, catch_parse_panic, parse_mode};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{augment, claude, codex, copilot, cursor, factory, gemini, kiro, minimax, zai};
+
+/// Environment variable naming a directory to record responses to.
+pub const RECORD_DIR_ENV: &str = "EXACTOBAR_RECORD_DIR";
+
+/// Directory set by [`start_recording`].
+static RECORD_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// ============================================================================
+// Raw Formats
+// ============================================================================
+
+/// Kind of raw response a recording holds, which decides the parser it's
+/// replayed through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawFormat {
+    /// Claude OAuth usage endpoint response.
+    ClaudeOauth,
+    /// `claude` CLI JSON output.
+    ClaudeCliJson,
+    /// `claude` CLI text output.
+    ClaudeCliText,
+    /// `claude` `/usage` screen captured over a PTY.
+    ClaudePty,
+    /// `codex` CLI JSON output.
+    CodexCli,
+    /// `codex` `/status` screen captured over a PTY.
+    CodexPty,
+    /// Copilot usage API response.
+    Copilot,
+    /// cursor.com usage response.
+    Cursor,
+    /// Augment usage API response.
+    Augment,
+    /// Factory usage API response or local token file.
+    Factory,
+    /// `gemini` CLI output.
+    Gemini,
+    /// `kiro-cli` output.
+    Kiro,
+    /// MiniMax usage API response or local config.
+    Minimax,
+    /// z.ai usage API response.
+    Zai,
+}
+
+impl RawFormat {
+    /// Every format, for replaying and fuzzing.
+    pub const ALL: &[Self] = &[
+        Self::ClaudeOauth,
+        Self::ClaudeCliJson,
+        Self::ClaudeCliText,
+        Self::ClaudePty,
+        Self::CodexCli,
+        Self::CodexPty,
+        Self::Copilot,
+        Self::Cursor,
+        Self::Augment,
+        Self::Factory,
+        Self::Gemini,
+        Self::Kiro,
+        Self::Minimax,
+        Self::Zai,
+    ];
+
+    /// Returns the provider the format comes from.
+    pub fn provider(self) -> ProviderKind {
+        match self {
+            Self::ClaudeOauth | Self::ClaudeCliJson | Self::ClaudeCliText | Self::ClaudePty => {
+                ProviderKind::Claude
+            }
+            Self::CodexCli | Self::CodexPty => ProviderKind::Codex,
+            Self::Copilot => ProviderKind::Copilot,
+            Self::Cursor => ProviderKind::Cursor,
+            Self::Augment => ProviderKind::Augment,
+            Self::Factory => ProviderKind::Factory,
+            Self::Gemini => ProviderKind::Gemini,
+            Self::Kiro => ProviderKind::Kiro,
+            Self::Minimax => ProviderKind::MiniMax,
+            Self::Zai => ProviderKind::Zai,
+        }
+    }
+
+    /// Returns the name used in recording files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ClaudeOauth => "claude_oauth",
+            Self::ClaudeCliJson => "claude_cli_json",
+            Self::ClaudeCliText => "claude_cli_text",
+            Self::ClaudePty => "claude_pty",
+            Self::CodexCli => "codex_cli",
+            Self::CodexPty => "codex_pty",
+            Self::Copilot => "copilot",
+            Self::Cursor => "cursor",
+            Self::Augment => "augment",
+            Self::Factory => "factory",
+            Self::Gemini => "gemini",
+            Self::Kiro => "kiro",
+            Self::Minimax => "minimax",
+            Self::Zai => "zai",
+        }
+    }
+
+    /// Returns the format with this [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.name() == name)
+    }
+
+    /// Parses a raw response the way the strategy that fetched it does.
+    ///
+    /// A parser panic becomes `FetchError::ParseFailed` rather than
+    /// unwinding into the refresh loop. In strict mode, a response with
+    /// anything in its [`diagnose`](Self::diagnose) diagnostics is a
+    /// `FetchError::StrictParse`.
+    pub fn parse(self, raw: &str) -> Result<UsageSnapshot, FetchError> {
+        let snapshot = catch_parse_panic(self.name(), || self.parse_unguarded(raw))?;
+        if parse_mode().is_strict() {
+            self.diagnose(raw, &snapshot).into_result(self.name())?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Returns what a strict parse objects to in a response that parsed to
+    /// `snapshot`: unknown JSON fields, a missing primary window and
+    /// out-of-range values. Text formats have no fields to check.
+    pub fn diagnose(self, raw: &str, snapshot: &UsageSnapshot) -> ParseDiagnostics {
+        let mut diagnostics = ParseDiagnostics::new();
+        match self {
+            Self::ClaudeOauth => {
+                diagnostics.check_json_fields::<claude::OAuthUsageResponse>(raw);
+            }
+            Self::ClaudeCliJson => {
+                diagnostics.check_json_fields::<claude::parser::ClaudeApiResponse>(raw);
+            }
+            Self::CodexCli => {
+                diagnostics.check_json_fields::<codex::parser::CodexCliResponse>(raw);
+            }
+            Self::Copilot => {
+                diagnostics.check_json_fields::<copilot::parser::CopilotUsageResponse>(raw);
+            }
+            Self::Cursor => {
+                diagnostics.check_json_fields::<cursor::CursorUsageResponse>(raw);
+            }
+            Self::Augment => {
+                diagnostics.check_json_fields::<augment::parser::AugmentUsageResponse>(raw);
+            }
+            Self::Factory => {
+                diagnostics.check_json_fields::<factory::parser::FactoryUsageResponse>(raw);
+            }
+            Self::Gemini => {
+                diagnostics.check_json_fields::<gemini::parser::GeminiUsageResponse>(raw);
+            }
+            Self::Kiro => {
+                diagnostics.check_json_fields::<kiro::parser::KiroUsageResponse>(raw);
+            }
+            Self::Minimax => {
+                diagnostics.check_json_fields::<minimax::parser::MiniMaxUsageResponse>(raw);
+            }
+            Self::Zai => {
+                diagnostics.check_json_fields::<zai::parser::ZaiUsageResponse>(raw);
+            }
+            Self::ClaudeCliText | Self::ClaudePty | Self::CodexPty => {}
+        }
+        diagnostics.check_snapshot(snapshot);
+        diagnostics
+    }
+
+    fn parse_unguarded(self, raw: &str) -> Result<UsageSnapshot, FetchError> {
+        match self {
+            Self::ClaudeOauth => claude::parser::parse_claude_oauth_response(raw),
+            Self::ClaudeCliJson => claude::parser::parse_claude_cli_output(raw, true),
+            Self::ClaudeCliText => claude::parser::parse_claude_cli_output(raw, false),
+            Self::ClaudePty => claude::parse_usage_output(raw)
+                .map(|status| status.to_snapshot())
+                .map_err(|e| FetchError::InvalidResponse(e.to_string())),
+            Self::CodexCli => codex::parser::parse_codex_cli_output(raw),
+            Self::CodexPty => codex::parse_status_output(raw)
+                .map(codex::convert_pty_to_snapshot)
+                .map_err(|e| FetchError::InvalidResponse(e.to_string())),
+            Self::Copilot => copilot::parser::parse_copilot_response(raw),
+            Self::Cursor => cursor::parser::parse_cursor_web_response(raw),
+            Self::Augment => augment::parser::parse_augment_response(raw),
+            Self::Factory => factory::parser::parse_factory_response(raw),
+            Self::Gemini => gemini::parser::parse_gemini_response(raw),
+            Self::Kiro => kiro::parser::parse_kiro_response(raw),
+            Self::Minimax => minimax::parser::parse_minimax_response(raw),
+            Self::Zai => zai::parser::parse_zai_response(raw),
+        }
+    }
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// A raw response saved in record mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// Kind of response.
+    pub format: RawFormat,
+    /// When it was recorded.
+    pub recorded_at: DateTime<Utc>,
+    /// The response, with secrets redacted.
+    pub raw: String,
+}
+
+impl Recording {
+    /// Creates a recording of a raw response, redacting secrets.
+    pub fn new(format: RawFormat, raw: &str) -> Self {
+        Self {
+            format,
+            recorded_at: Utc::now(),
+            raw: Redactor::new().redact(raw).into_owned(),
+        }
+    }
+
+    /// Returns the provider the recording comes from.
+    pub fn provider(&self) -> ProviderKind {
+        self.format.provider()
+    }
+
+    /// Runs the recording through its parser.
+    pub fn replay(&self) -> Result<UsageSnapshot, FetchError> {
+        self.format.parse(&self.raw)
+    }
+
+    /// Returns the file name the recording is saved under.
+    fn file_name(&self) -> String {
+        format!(
+            "{}-{}-{}.json",
+            self.provider().cli_name(),
+            self.format.name(),
+            self.recorded_at.format("%Y%m%dT%H%M%S%.3f")
+        )
+    }
+
+    /// Saves the recording in a directory, returning its path.
+    pub fn save_in(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+/// Records responses to `dir` from now on.
+pub fn start_recording(dir: impl Into<PathBuf>) {
+    if let Ok(mut current) = RECORD_DIR.write() {
+        *current = Some(dir.into());
+    }
+}
+
+/// Stops recording responses set by [`start_recording`].
+pub fn stop_recording() {
+    if let Ok(mut current) = RECORD_DIR.write() {
+        *current = None;
+    }
+}
+
+/// Returns the directory responses are recorded to, if recording: the one
+/// set by [`start_recording`], then `EXACTOBAR_RECORD_DIR`.
+pub fn recording_dir() -> Option<PathBuf> {
+    RECORD_DIR
+        .read()
+        .ok()
+        .and_then(|dir| dir.clone())
+        .or_else(|| {
+            std::env::var_os(RECORD_DIR_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        })
+}
+
+/// Records a raw response if record mode is on. Failures are logged, never
+/// returned: recording must not break a fetch.
+///
+/// Every response goes through here on its way to its parser, so this is
+/// also where the fetch reports [`FetchStage::Parsing`].
+pub fn record(format: RawFormat, raw: &str) {
+    progress::report(FetchStage::Parsing);
+    let Some(dir) = recording_dir() else {
+        return;
+    };
+    match Recording::new(format, raw).save_in(&dir) {
+        Ok(path) => debug!(path = %path.display(), "Recorded response"),
+        Err(e) => warn!(dir = %dir.display(), error = %e, "Failed to record response"),
+    }
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// Loads every recording in a directory, oldest first.
+///
+/// Files other than `.json` are ignored; a `.json` file that isn't a
+/// recording is an `InvalidData` error naming it.
+pub fn load_recordings(dir: &Path) -> std::io::Result<Vec<(PathBuf, Recording)>> {
+    let mut recordings = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        let recording: Recording = serde_json::from_str(&contents).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{} is not a recording: {e}", path.display()),
+            )
+        })?;
+        recordings.push((path, recording));
+    }
+    recordings.sort_by(|a, b| (a.1.recorded_at, &a.0).cmp(&(b.1.recorded_at, &b.0)));
+    Ok(recordings)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = r#"{"five_hour": {"utilization": 42.0, "resets_at": null}, "access_token": "sk-ant-REDACTED"}"#;
+
+        let recording = Recording::new(RawFormat::ClaudeOauth, raw);
+        assert!(!recording.raw.contains("FAKEFAKE"));
+        let path = recording.save_in(dir.path()).unwrap();
+        assert!(
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("claude-claude_oauth-")
+        );
+        Recording::new(RawFormat::CodexCli, "not json")
+            .save_in(dir.path())
+            .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let loaded = load_recordings(dir.path()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].1, recording);
+
+        let snapshot = loaded[0].1.replay().unwrap();
+        assert_eq!(snapshot.primary.unwrap().used_percent, 42.0);
+        assert_eq!(loaded[1].1.provider(), ProviderKind::Codex);
+        assert!(loaded[1].1.replay().is_err());
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let raw = r#"{"five_hour": {"utilization": 142.0, "resets_at": null, "burst": 1}, "seven_day_sonnet": null}"#;
+        let snapshot = RawFormat::ClaudeOauth.parse_unguarded(raw).unwrap();
+        let diagnostics = RawFormat::ClaudeOauth.diagnose(raw, &snapshot);
+        assert_eq!(
+            diagnostics.unrecognized,
+            vec!["five_hour.burst", "seven_day_sonnet"]
+        );
+        assert_eq!(diagnostics.ambiguous, vec!["primary.used_percent = 142"]);
+
+        // Text formats only get the snapshot checks
+        let snapshot = RawFormat::CodexPty.parse_unguarded("Welcome!").unwrap();
+        let diagnostics = RawFormat::CodexPty.diagnose("Welcome!", &snapshot);
+        assert_eq!(diagnostics.missing, vec!["primary window"]);
+        assert!(diagnostics.unrecognized.is_empty());
+    }
+
+    #[test]
+    fn test_load_rejects_foreign_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("settings.json"), "{}").unwrap();
+        let error = load_recordings(dir.path()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -3,7 +3,7 @@
 //! The registry provides static access to all provider configurations
 //! and is the central point for looking up providers.
 
-use exactobar_core::ProviderKind;
+use exactobar_core::{ProviderId, ProviderInfo, ProviderKind};
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
@@ -13,11 +13,14 @@ use crate::claude::claude_descriptor;
 use crate::codex::codex_descriptor;
 use crate::copilot::copilot_descriptor;
 use crate::cursor::cursor_descriptor;
-use crate::descriptor::ProviderDescriptor;
+use crate::descriptor::{PlannedStrategy, ProviderDescriptor};
 use crate::factory::factory_descriptor;
 use crate::gemini::gemini_descriptor;
+use crate::github_models::github_models_descriptor;
+use crate::huggingface::huggingface_descriptor;
 use crate::kiro::kiro_descriptor;
 use crate::minimax::minimax_descriptor;
+use crate::replicate::replicate_descriptor;
 use crate::synthetic::synthetic_descriptor;
 use crate::vertexai::vertexai_descriptor;
 use crate::zai::zai_descriptor;
@@ -32,13 +35,17 @@ static DESCRIPTORS: OnceLock<Vec<ProviderDescriptor>> = OnceLock::new();
 /// Static storage for CLI name to provider kind mapping.
 static CLI_NAME_MAP: OnceLock<HashMap<String, ProviderKind>> = OnceLock::new();
 
+/// Static storage for each provider's planned strategies.
+static STRATEGIES: OnceLock<HashMap<ProviderKind, Vec<PlannedStrategy>>> = OnceLock::new();
+
 /// Initializes all provider descriptors.
 ///
 /// Providers are ordered by priority/importance:
 /// 1. Primary providers (Codex, Claude)
 /// 2. Popular IDE providers (Cursor, Copilot)
 /// 3. Cloud providers (Gemini, VertexAI)
-/// 4. Other providers (Factory, Zai, Augment, Kiro, MiniMax, Antigravity)
+/// 4. Other providers (Factory, Zai, Augment, Kiro, MiniMax, Antigravity,
+///    Synthetic, GitHub Models, Hugging Face, Replicate)
 fn init_descriptors() -> Vec<ProviderDescriptor> {
     vec![
         // Primary providers
@@ -58,6 +65,9 @@ fn init_descriptors() -> Vec<ProviderDescriptor> {
         minimax_descriptor(),
         antigravity_descriptor(),
         synthetic_descriptor(),
+        github_models_descriptor(),
+        huggingface_descriptor(),
+        replicate_descriptor(),
     ]
 }
 
@@ -99,6 +109,27 @@ impl ProviderRegistry {
         Self::all().iter().find(|d| d.id == id)
     }
 
+    /// Returns the name of the CLI a provider runs, if it has one.
+    pub fn binary_name(id: ProviderKind) -> Option<&'static str> {
+        Self::get(id)
+            .map(|d| d.cli.name)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Returns the strategies a provider can use, in the order they're
+    /// tried when all are allowed.
+    pub fn strategies(id: ProviderKind) -> &'static [PlannedStrategy] {
+        STRATEGIES
+            .get_or_init(|| {
+                Self::all()
+                    .iter()
+                    .map(|d| (d.id, d.planned_strategies()))
+                    .collect()
+            })
+            .get(&id)
+            .map_or(&[], Vec::as_slice)
+    }
+
     /// Returns the CLI name to provider kind mapping.
     pub fn cli_name_map() -> &'static HashMap<String, ProviderKind> {
         CLI_NAME_MAP.get_or_init(|| build_cli_name_map(Self::all()))
@@ -136,6 +167,51 @@ impl ProviderRegistry {
         Self::all().iter().map(|d| d.id).collect()
     }
 
+    // ========================================================================
+    // Provider Ids
+    // ========================================================================
+
+    /// Returns every provider id: built-in providers in registry order, then
+    /// registered custom providers.
+    pub fn ids() -> Vec<ProviderId> {
+        Self::all()
+            .iter()
+            .map(|d| ProviderId::from(d.id))
+            .chain(ProviderId::registered().into_iter().map(|info| info.id))
+            .collect()
+    }
+
+    /// Gets the descriptor for an id, if it names a built-in provider.
+    pub fn get_id(id: ProviderId) -> Option<&'static ProviderDescriptor> {
+        id.kind().and_then(Self::get)
+    }
+
+    /// Returns display metadata for any provider id.
+    ///
+    /// Built-in providers take their labels from their descriptors; custom
+    /// providers return what they were registered with.
+    pub fn info(id: ProviderId) -> Option<ProviderInfo> {
+        let Some(desc) = Self::get_id(id) else {
+            return id.info();
+        };
+        Some(ProviderInfo {
+            id,
+            display_name: desc.metadata.display_name.clone(),
+            session_label: desc.metadata.session_label.clone(),
+            weekly_label: desc.metadata.weekly_label.clone(),
+            dashboard_url: desc.metadata.dashboard_url.clone(),
+        })
+    }
+
+    /// Resolves a CLI name, alias or custom provider id.
+    pub fn resolve(name: &str) -> Option<ProviderId> {
+        if let Some(desc) = Self::get_by_cli_name(name) {
+            return Some(desc.id.into());
+        }
+        let id = ProviderId::new(name);
+        id.info().map(|_| id)
+    }
+
     /// Returns providers that support the given source mode.
     pub fn with_source_mode(mode: exactobar_fetch::SourceMode) -> Vec<&'static ProviderDescriptor> {
         Self::all()
@@ -154,9 +230,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_registry_all_13_providers() {
+    fn test_registry_all_16_providers() {
         let all = ProviderRegistry::all();
-        assert_eq!(all.len(), 13, "Should have exactly 13 providers");
+        assert_eq!(all.len(), 16, "Should have exactly 16 providers");
     }
 
     #[test]
@@ -176,6 +252,9 @@ mod tests {
             ProviderKind::MiniMax,
             ProviderKind::Antigravity,
             ProviderKind::Synthetic,
+            ProviderKind::GitHubModels,
+            ProviderKind::HuggingFace,
+            ProviderKind::Replicate,
         ];
 
         for kind in kinds {
@@ -185,6 +264,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dashboard_urls() {
+        for desc in ProviderRegistry::all() {
+            let url = desc.metadata.dashboard_url.as_deref();
+            if desc.id == ProviderKind::Antigravity {
+                // Local app, no web dashboard
+                assert!(url.is_none());
+            } else {
+                assert!(
+                    url.is_some_and(|u| u.starts_with("https://")),
+                    "{:?} should link to its dashboard",
+                    desc.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_provider_ids() {
+        let ids = ProviderRegistry::ids();
+        assert!(ids.len() >= ProviderRegistry::count());
+        assert_eq!(ids[0], ProviderKind::Codex);
+
+        let info = ProviderRegistry::info(ProviderKind::Claude.into()).unwrap();
+        assert_eq!(
+            info.session_label,
+            ProviderRegistry::get(ProviderKind::Claude)
+                .unwrap()
+                .metadata
+                .session_label
+        );
+
+        assert_eq!(
+            ProviderRegistry::resolve("openai"),
+            Some(ProviderKind::Codex.into())
+        );
+        assert_eq!(ProviderRegistry::resolve("registry-test-unknown"), None);
+        assert!(ProviderRegistry::get_id(ProviderId::new("registry-test-unknown")).is_none());
+    }
+
+    #[test]
+    fn test_strategies() {
+        let ids: Vec<_> = ProviderRegistry::strategies(ProviderKind::Claude)
+            .iter()
+            .map(|s| s.id.as_str())
+            .collect();
+        assert!(ids.contains(&"claude.pty"));
+        assert!(ids.contains(&"claude.oauth"));
+
+        let desc = ProviderRegistry::get(ProviderKind::Claude).unwrap();
+        let mut strategies = exactobar_fetch::StrategySettings::default();
+        strategies.set_enabled("claude.pty", false);
+        let ctx = exactobar_fetch::FetchContext::new().with_strategies(strategies);
+        let pipeline = desc.build_pipeline(&ctx);
+        assert_eq!(pipeline.len(), ids.len() - 1);
+        assert!(pipeline.strategies().all(|s| s.id() != "claude.pty"));
+
+        // Priority overrides move the CLI ahead of OAuth
+        let mut strategies = exactobar_fetch::StrategySettings::default();
+        strategies.priorities.insert("claude.pty".to_string(), 1000);
+        let ctx = exactobar_fetch::FetchContext::new().with_strategies(strategies);
+        let pipeline = desc.build_pipeline(&ctx);
+        assert_eq!(pipeline.strategies().next().unwrap().id(), "claude.pty");
+        assert_eq!(pipeline.len(), ids.len());
+    }
+
     #[test]
     fn test_cli_name_lookup() {
         // Primary names
@@ -231,12 +376,12 @@ mod tests {
 
     #[test]
     fn test_provider_count() {
-        assert_eq!(ProviderRegistry::count(), 13);
+        assert_eq!(ProviderRegistry::count(), 16);
     }
 
     #[test]
     fn test_all_kinds_returned() {
         let kinds = ProviderRegistry::kinds();
-        assert_eq!(kinds.len(), 13);
+        assert_eq!(kinds.len(), 16);
     }
 }
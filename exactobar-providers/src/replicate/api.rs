@@ -0,0 +1,256 @@
+//! Replicate API client.
+
+use chrono::{DateTime, Utc};
+use exactobar_core::{
+    FetchSource, LoginMethod, ProviderIdentity, ProviderKind, UsageSnapshot, UsageWindow,
+};
+use exactobar_fetch::host::HttpApi;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
+
+use super::error::ReplicateError;
+use crate::huggingface::next_month_start;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Replicate API base URL.
+pub const API_BASE_URL: &str = "https://api.replicate.com";
+
+/// Account endpoint.
+pub const ACCOUNT_ENDPOINT: &str = "/v1/account";
+
+/// Spend for the current billing period.
+pub const SPEND_ENDPOINT: &str = "/v1/account/billing/current-period";
+
+// ============================================================================
+// API Response Types
+// ============================================================================
+
+/// Response from the account endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplicateAccount {
+    /// `user` or `organization`.
+    #[serde(rename = "type")]
+    pub account_type: String,
+    /// Username or organization slug.
+    pub username: String,
+    /// Display name.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Spend for the current billing period.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplicateSpend {
+    /// Amount spent this period, in USD.
+    pub spend_usd: f64,
+    /// Monthly spend limit, in USD, if one is configured.
+    #[serde(default)]
+    pub spend_limit_usd: Option<f64>,
+    /// When the billing period ends.
+    #[serde(default)]
+    pub period_end: Option<DateTime<Utc>>,
+}
+
+impl ReplicateSpend {
+    /// Builds a snapshot with the spend against the limit as the primary
+    /// window. Without a limit there's no window, and the spend goes in the
+    /// plan name.
+    pub fn to_snapshot(&self, account: &ReplicateAccount) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.fetch_source = FetchSource::Api;
+
+        let limit = self.spend_limit_usd.filter(|limit| *limit > 0.0);
+        if let Some(limit) = limit {
+            snapshot.primary = Some(UsageWindow {
+                used_percent: (self.spend_usd / limit) * 100.0,
+                window_minutes: Some(43200), // ~30 days in minutes
+                resets_at: Some(
+                    self.period_end
+                        .unwrap_or_else(|| next_month_start(Utc::now())),
+                ),
+                reset_description: Some(format!("${:.2} of ${:.2} limit", self.spend_usd, limit)),
+            });
+        }
+
+        let mut identity = ProviderIdentity::new(ProviderKind::Replicate);
+        identity.account_email = Some(account.username.clone());
+        if account.account_type == "organization" {
+            identity.account_organization = account.name.clone();
+        }
+        identity.plan_name = Some(match limit {
+            Some(_) => "Pay as you go".to_string(),
+            None => format!("${:.2} this month, no spend limit", self.spend_usd),
+        });
+        identity.login_method = Some(LoginMethod::ApiKey);
+        snapshot.identity = Some(identity);
+
+        snapshot
+    }
+}
+
+// ============================================================================
+// API Client
+// ============================================================================
+
+/// Replicate API client.
+#[derive(Debug, Clone)]
+pub struct ReplicateApiClient {
+    base_url: String,
+}
+
+impl Default for ReplicateApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplicateApiClient {
+    /// Creates a new client.
+    pub fn new() -> Self {
+        Self::with_base_url(API_BASE_URL)
+    }
+
+    /// Creates a client with a custom base URL.
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+        }
+    }
+
+    /// Get API token from Keychain first, then environment variable.
+    ///
+    /// The lookup order is:
+    /// 1. System keychain (stored via Settings UI)
+    /// 2. Environment variable `REPLICATE_API_TOKEN`
+    pub fn get_token() -> Result<String, ReplicateError> {
+        if let Some(token) = exactobar_store::get_api_key("replicate") {
+            return Ok(token);
+        }
+
+        std::env::var("REPLICATE_API_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .ok_or(ReplicateError::TokenNotFound)
+    }
+
+    /// Fetch the account the token belongs to.
+    #[instrument(skip(self, http, token))]
+    pub async fn fetch_account(
+        &self,
+        http: &dyn HttpApi,
+        token: &str,
+    ) -> Result<ReplicateAccount, ReplicateError> {
+        self.get_json(http, ACCOUNT_ENDPOINT, token).await
+    }
+
+    /// Fetch this billing period's spend and limit.
+    #[instrument(skip(self, http, token))]
+    pub async fn fetch_spend(
+        &self,
+        http: &dyn HttpApi,
+        token: &str,
+    ) -> Result<ReplicateSpend, ReplicateError> {
+        self.get_json(http, SPEND_ENDPOINT, token).await
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        http: &dyn HttpApi,
+        endpoint: &str,
+        token: &str,
+    ) -> Result<T, ReplicateError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+
+        debug!(url = %url, "Fetching Replicate API");
+
+        let response = http
+            .get_with_auth(&url, &format!("Bearer {}", token))
+            .await
+            .map_err(|e| ReplicateError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(ReplicateError::AuthenticationFailed(
+                "API token rejected".to_string(),
+            ));
+        }
+
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(ReplicateError::ApiError(format!(
+                "HTTP {}: {}",
+                status, text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ReplicateError::ParseError(e.to_string()))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> ReplicateAccount {
+        serde_json::from_str(
+            r#"{
+              "type": "organization",
+              "username": "acme",
+              "name": "Acme Corp",
+              "github_url": "https://github.com/acme"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_snapshot_with_limit() {
+        let json = r#"{
+          "spend_usd": 12.5,
+          "spend_limit_usd": 50.0,
+          "period_end": "2026-11-01T00:00:00Z"
+        }"#;
+        let spend: ReplicateSpend = serde_json::from_str(json).unwrap();
+
+        let snapshot = spend.to_snapshot(&account());
+        let primary = snapshot.primary.unwrap();
+        assert!((primary.used_percent - 25.0).abs() < 1e-9);
+        assert_eq!(
+            primary.reset_description.as_deref(),
+            Some("$12.50 of $50.00 limit")
+        );
+
+        let identity = snapshot.identity.unwrap();
+        assert_eq!(identity.account_email.as_deref(), Some("acme"));
+        assert_eq!(identity.account_organization.as_deref(), Some("Acme Corp"));
+        assert_eq!(identity.plan_name.as_deref(), Some("Pay as you go"));
+    }
+
+    #[test]
+    fn test_to_snapshot_without_limit() {
+        let spend = ReplicateSpend {
+            spend_usd: 3.2,
+            spend_limit_usd: None,
+            period_end: None,
+        };
+
+        let snapshot = spend.to_snapshot(&account());
+        assert!(snapshot.primary.is_none());
+        assert_eq!(
+            snapshot.identity.unwrap().plan_name.as_deref(),
+            Some("$3.20 this month, no spend limit")
+        );
+    }
+}
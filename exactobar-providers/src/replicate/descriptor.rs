@@ -0,0 +1,97 @@
+//! Replicate provider descriptor.
+
+use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, ProviderMetadata};
+use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
+
+use super::strategies::ReplicateApiStrategy;
+use crate::compat::VersionRange;
+use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
+
+// ============================================================================
+// Descriptor
+// ============================================================================
+
+/// Creates the Replicate provider descriptor.
+pub fn replicate_descriptor() -> ProviderDescriptor {
+    ProviderDescriptor {
+        id: ProviderKind::Replicate,
+        metadata: replicate_metadata(),
+        branding: replicate_branding(),
+        token_cost: TokenCostConfig::default(),
+        fetch_plan: replicate_fetch_plan(),
+        cli: replicate_cli_config(),
+    }
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+fn replicate_metadata() -> ProviderMetadata {
+    ProviderMetadata {
+        id: ProviderKind::Replicate,
+        display_name: "Replicate".to_string(),
+        session_label: "Spend".to_string(),
+        weekly_label: "Monthly".to_string(),
+        opus_label: None,
+        supports_opus: false,
+        supports_credits: true,
+        credits_hint: "Monthly spend against your spend limit".to_string(),
+        toggle_title: "Show Replicate usage".to_string(),
+        cli_name: "replicate".to_string(),
+        default_enabled: false,
+        is_primary_provider: false,
+        uses_account_fallback: false,
+        dashboard_url: Some("https://replicate.com/account".to_string()),
+        subscription_dashboard_url: Some("https://replicate.com/account/billing".to_string()),
+        status_page_url: Some("https://www.replicatestatus.com".to_string()),
+        status_link_url: Some("https://www.replicatestatus.com".to_string()),
+    }
+}
+
+// ============================================================================
+// Branding
+// ============================================================================
+
+fn replicate_branding() -> ProviderBranding {
+    ProviderBranding {
+        icon_style: IconStyle::Replicate,
+        icon_resource_name: "icon_replicate".to_string(),
+        color: ProviderColor::new(0.93, 0.29, 0.2), // Replicate red
+    }
+}
+
+// ============================================================================
+// Fetch Plan
+// ============================================================================
+
+fn replicate_fetch_plan() -> FetchPlan {
+    FetchPlan {
+        source_modes: vec![SourceMode::ApiKey],
+        build_pipeline: build_replicate_pipeline,
+    }
+}
+
+fn build_replicate_pipeline(ctx: &FetchContext) -> FetchPipeline {
+    let mut strategies: Vec<Box<dyn exactobar_fetch::FetchStrategy>> = Vec::new();
+
+    if ctx.settings.source_mode.allows_api_key() {
+        strategies.push(Box::new(ReplicateApiStrategy::new()));
+    }
+
+    FetchPipeline::with_strategies(strategies)
+}
+
+// ============================================================================
+// CLI Config
+// ============================================================================
+
+fn replicate_cli_config() -> CliConfig {
+    CliConfig {
+        name: "replicate",
+        aliases: &["rep"],
+        version_args: &["--version"],
+        usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
+    }
+}
@@ -0,0 +1,27 @@
+//! Replicate-specific errors.
+
+use thiserror::Error;
+
+/// Replicate-specific errors.
+#[derive(Debug, Error)]
+pub enum ReplicateError {
+    /// No token in the keychain or environment.
+    #[error("Token not found (set REPLICATE_API_TOKEN)")]
+    TokenNotFound,
+
+    /// HTTP request failed.
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+
+    /// Parse error.
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// API error.
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    /// Authentication failed.
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+}
@@ -0,0 +1,24 @@
+//! Replicate provider implementation.
+//!
+//! Replicate bills pay-as-you-go by prediction time, with an optional
+//! monthly spend limit set on the billing page. The provider shows the
+//! month's spend against that limit as a credits window; without a limit
+//! there's nothing to measure against, so only the spend is shown.
+//!
+//! The API token is looked up in the keychain (stored via Settings), then
+//! `REPLICATE_API_TOKEN`.
+//!
+//! ## Fetch Strategies
+//!
+//! 1. **API Strategy** (priority 60): Reads the account and the current
+//!    billing period's spend
+
+mod api;
+mod descriptor;
+mod error;
+mod strategies;
+
+pub use api::{API_BASE_URL, ReplicateAccount, ReplicateApiClient, ReplicateSpend};
+pub use descriptor::replicate_descriptor;
+pub use error::ReplicateError;
+pub use strategies::ReplicateApiStrategy;
@@ -0,0 +1,101 @@
+//! Replicate fetch strategies.
+
+use async_trait::async_trait;
+use exactobar_fetch::{FetchContext, FetchError, FetchKind, FetchResult, FetchStrategy};
+use tracing::{debug, instrument};
+
+use super::api::{API_BASE_URL, ReplicateApiClient};
+use super::error::ReplicateError;
+
+// ============================================================================
+// API Strategy
+// ============================================================================
+
+/// API strategy for Replicate.
+///
+/// Uses a Replicate API token to read the month's spend and spend limit.
+pub struct ReplicateApiStrategy;
+
+impl ReplicateApiStrategy {
+    /// Creates a new strategy.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ReplicateApiStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_fetch_error(error: &ReplicateError) -> FetchError {
+    match error {
+        ReplicateError::AuthenticationFailed(_) => {
+            FetchError::AuthenticationFailed(error.to_string())
+        }
+        _ => FetchError::InvalidResponse(error.to_string()),
+    }
+}
+
+#[async_trait]
+impl FetchStrategy for ReplicateApiStrategy {
+    fn id(&self) -> &str {
+        "replicate.api"
+    }
+
+    fn kind(&self) -> FetchKind {
+        FetchKind::ApiKey
+    }
+
+    #[instrument(skip(self, _ctx))]
+    async fn is_available(&self, _ctx: &FetchContext) -> bool {
+        ReplicateApiClient::get_token().is_ok()
+    }
+
+    #[instrument(skip(self, ctx))]
+    async fn fetch(&self, ctx: &FetchContext) -> Result<FetchResult, FetchError> {
+        debug!("Fetching Replicate spend");
+
+        let token = ReplicateApiClient::get_token()
+            .map_err(|e| FetchError::AuthenticationFailed(e.to_string()))?;
+
+        let client = ReplicateApiClient::with_base_url(ctx.api_base(API_BASE_URL));
+        let (account, spend) = tokio::join!(
+            client.fetch_account(ctx.http.as_ref(), &token),
+            client.fetch_spend(ctx.http.as_ref(), &token),
+        );
+        let account = account.map_err(|e| to_fetch_error(&e))?;
+        let spend = spend.map_err(|e| to_fetch_error(&e))?;
+
+        debug!("Replicate spend fetched successfully");
+        let snapshot = spend.to_snapshot(&account);
+
+        Ok(FetchResult::new(snapshot, self.id(), self.kind()))
+    }
+
+    fn priority(&self) -> u32 {
+        60 // API Key priority
+    }
+
+    fn should_fallback(&self, error: &FetchError) -> bool {
+        !matches!(error, FetchError::AuthenticationFailed(_))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_strategy() {
+        let s = ReplicateApiStrategy::new();
+        assert_eq!(s.id(), "replicate.api");
+        assert_eq!(s.kind(), FetchKind::ApiKey);
+        assert_eq!(s.priority(), 60);
+    }
+}
@@ -0,0 +1,440 @@
+//! Daily usage export to Google Sheets.
+//!
+//! Appends one row per provider and day to a spreadsheet, so people who
+//! don't run ExactoBar can follow consumption. Each provider opts in with a
+//! tab name; the spreadsheet and the service account key it's written with
+//! are set once in [`SheetsExport`].
+//!
+//! Rows cover complete UTC days, with these columns:
+//!
+//! | Date | Provider | Session peak % | Weekly peak % | Cost (USD) |
+//!
+//! Peaks come from the usage [`HistoryStore`], so only days ExactoBar ran
+//! on are exported; cost comes from local logs for providers that track it.
+//! An [`ExportState`] remembers the last day exported per provider, so each
+//! day is appended once no matter how often [`export_due`] runs.
+
+mod auth;
+
+pub use auth::ServiceAccount;
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Days, NaiveDate, NaiveTime};
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use exactobar_store::{
+    CostUsageSnapshot, HISTORY_RETENTION_DAYS, HistoryStore, SheetsExport, WindowSlot,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use crate::cost::{self, LogIndex};
+use crate::pricing::PricingTable;
+use crate::registry::ProviderRegistry;
+
+/// Sheets API base URL.
+const SHEETS_API_BASE: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+/// Google Sheets export errors.
+#[derive(Debug, Error)]
+pub enum SheetsError {
+    /// The service account key file is missing or invalid.
+    #[error("Credentials error: {0}")]
+    CredentialsError(String),
+
+    /// HTTP request failed.
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+
+    /// Parse error.
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// API error.
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    /// Authentication failed.
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+
+    /// History couldn't be loaded or export progress couldn't be saved.
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+// ============================================================================
+// Rows
+// ============================================================================
+
+/// One provider's usage on one UTC day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyRow {
+    /// The day.
+    pub date: NaiveDate,
+    /// The provider.
+    pub provider: ProviderKind,
+    /// Highest session window usage that day, in percent.
+    pub session_peak: Option<f64>,
+    /// Highest weekly (or monthly) window usage that day, in percent.
+    pub weekly_peak: Option<f64>,
+    /// Cost computed from local logs, in USD.
+    pub cost_usd: Option<f64>,
+}
+
+impl DailyRow {
+    /// Builds a row from `provider`'s history and costs, or `None` if
+    /// nothing was recorded that day.
+    pub fn new(
+        date: NaiveDate,
+        provider: ProviderKind,
+        history: &HistoryStore,
+        costs: Option<&CostUsageSnapshot>,
+    ) -> Option<Self> {
+        let start = date.and_time(NaiveTime::MIN).and_utc();
+        let end = start + chrono::Duration::days(1);
+        let peak = |slot: WindowSlot| {
+            history
+                .series(provider, slot, start)
+                .iter()
+                .take_while(|p| p.timestamp < end)
+                .map(|p| p.used_percent)
+                .reduce(f64::max)
+        };
+        let cost_usd = costs.and_then(|costs| {
+            costs
+                .daily
+                .iter()
+                .find(|d| d.date.date_naive() == date)
+                .map(|d| d.cost_usd)
+        });
+
+        let row = Self {
+            date,
+            provider,
+            session_peak: peak(WindowSlot::Primary),
+            weekly_peak: peak(WindowSlot::Secondary),
+            cost_usd,
+        };
+        (row.session_peak.is_some() || row.weekly_peak.is_some() || row.cost_usd.is_some())
+            .then_some(row)
+    }
+
+    /// Cell values, in column order. Missing values are empty cells.
+    pub fn values(&self) -> Vec<Value> {
+        let number = |value: Option<f64>| {
+            value.map_or(Value::String(String::new()), |v| {
+                json!((v * 100.0).round() / 100.0)
+            })
+        };
+        vec![
+            json!(self.date.format("%Y-%m-%d").to_string()),
+            json!(self.provider.display_name()),
+            number(self.session_peak),
+            number(self.weekly_peak),
+            number(self.cost_usd),
+        ]
+    }
+}
+
+// ============================================================================
+// Export State
+// ============================================================================
+
+/// Last day exported per provider.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExportState {
+    #[serde(default)]
+    last_exported: HashMap<ProviderKind, NaiveDate>,
+}
+
+impl ExportState {
+    /// Returns the state file in the cache directory.
+    pub fn default_path() -> PathBuf {
+        exactobar_store::default_cache_dir().join("sheets_export.json")
+    }
+
+    /// Loads the state, starting fresh if it's missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the state.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// Complete days not yet exported for `provider`, oldest first.
+    ///
+    /// A provider exported for the first time starts with yesterday;
+    /// after a gap, export resumes from the oldest day history still has.
+    pub fn pending_days(&self, provider: ProviderKind, today: NaiveDate) -> Vec<NaiveDate> {
+        let Some(yesterday) = today.pred_opt() else {
+            return Vec::new();
+        };
+        let oldest = today
+            .checked_sub_days(Days::new(HISTORY_RETENTION_DAYS.unsigned_abs()))
+            .unwrap_or(yesterday);
+        let first = match self.last_exported.get(&provider) {
+            Some(last) => last.succ_opt().unwrap_or(today).max(oldest),
+            None => yesterday,
+        };
+        first.iter_days().take_while(|d| *d <= yesterday).collect()
+    }
+
+    /// Records that `provider` is exported up to and including `date`.
+    pub fn mark_exported(&mut self, provider: ProviderKind, date: NaiveDate) {
+        self.last_exported.insert(provider, date);
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Rows not yet exported, grouped by tab, with the last day each provider
+/// covers.
+#[derive(Debug, Default)]
+pub struct DueRows {
+    /// Rows per tab, in provider then date order.
+    pub tabs: BTreeMap<String, Vec<DailyRow>>,
+    /// Last pending day per provider, exported or not (a day without
+    /// usage has no row but is still done).
+    pub through: Vec<(ProviderKind, NaiveDate)>,
+}
+
+impl DueRows {
+    /// Whether there are no rows to append.
+    pub fn is_empty(&self) -> bool {
+        self.tabs.values().all(Vec::is_empty)
+    }
+}
+
+/// Builds the rows due for `tabs` (provider, tab) as of `today`.
+pub async fn due_rows(
+    tabs: &[(ProviderKind, String)],
+    state: &ExportState,
+    today: NaiveDate,
+) -> Result<DueRows, SheetsError> {
+    let history = HistoryStore::load_default()
+        .await
+        .map_err(|e| SheetsError::StorageError(e.to_string()))?;
+
+    let mut due = DueRows::default();
+    for (provider, tab) in tabs {
+        let days = state.pending_days(*provider, today);
+        let Some(&last) = days.last() else {
+            continue;
+        };
+        let costs = scan_costs(*provider);
+        let rows = days
+            .into_iter()
+            .filter_map(|date| DailyRow::new(date, *provider, &history, costs.as_ref()));
+        due.tabs.entry(tab.clone()).or_default().extend(rows);
+        due.through.push((*provider, last));
+    }
+    Ok(due)
+}
+
+/// Appends the rows due as of `today` and records them as exported.
+/// Returns the number of rows appended.
+pub async fn export_due(
+    config: &SheetsExport,
+    tabs: &[(ProviderKind, String)],
+    http: &HttpClient,
+    today: NaiveDate,
+) -> Result<usize, SheetsError> {
+    let state_path = ExportState::default_path();
+    let mut state = ExportState::load(&state_path);
+    let due = due_rows(tabs, &state, today).await?;
+    if due.through.is_empty() {
+        return Ok(0);
+    }
+
+    let mut appended = 0;
+    if !due.is_empty() {
+        let account = ServiceAccount::load(&config.credentials_path)?;
+        let token = account.access_token(http).await?;
+        for (tab, rows) in &due.tabs {
+            if rows.is_empty() {
+                continue;
+            }
+            append_rows(http, &token, &config.spreadsheet_id, tab, rows).await?;
+            appended += rows.len();
+        }
+    }
+
+    for (provider, date) in due.through {
+        state.mark_exported(provider, date);
+    }
+    state
+        .save(&state_path)
+        .map_err(|e| SheetsError::StorageError(e.to_string()))?;
+
+    info!(rows = appended, "Exported usage to Google Sheets");
+    Ok(appended)
+}
+
+/// Appends rows after the last row of a tab.
+pub async fn append_rows(
+    http: &HttpClient,
+    token: &str,
+    spreadsheet_id: &str,
+    tab: &str,
+    rows: &[DailyRow],
+) -> Result<(), SheetsError> {
+    let mut url =
+        reqwest::Url::parse(SHEETS_API_BASE).map_err(|e| SheetsError::ApiError(e.to_string()))?;
+    url.path_segments_mut()
+        .map_err(|()| SheetsError::ApiError("Invalid Sheets API URL".to_string()))?
+        .extend([
+            spreadsheet_id,
+            "values",
+            format!("'{tab}'!A1:append").as_str(),
+        ]);
+    url.query_pairs_mut()
+        .append_pair("valueInputOption", "USER_ENTERED")
+        .append_pair("insertDataOption", "INSERT_ROWS");
+
+    let body = json!({
+        "majorDimension": "ROWS",
+        "values": rows.iter().map(DailyRow::values).collect::<Vec<_>>(),
+    });
+
+    debug!(tab = %tab, rows = rows.len(), "Appending rows to Google Sheet");
+
+    let response = http
+        .post_json_with_auth(url.as_str(), &body, &format!("Bearer {}", token))
+        .await
+        .map_err(|e| SheetsError::HttpError(e.to_string()))?;
+
+    let status = response.status();
+
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(SheetsError::AuthenticationFailed(
+            "Share the sheet with the service account's email as an editor".to_string(),
+        ));
+    }
+
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(SheetsError::ApiError(format!("HTTP {}: {}", status, text)));
+    }
+
+    Ok(())
+}
+
+/// Brings a provider's cost index up to date and returns its daily costs.
+fn scan_costs(provider: ProviderKind) -> Option<CostUsageSnapshot> {
+    let desc = ProviderRegistry::get(provider)?;
+    let pricing = PricingTable::load_default();
+    let index_path = LogIndex::default_path(desc.cli_name());
+    let mut index = LogIndex::load(&index_path);
+
+    let days = u32::try_from(HISTORY_RETENTION_DAYS).unwrap_or(u32::MAX);
+    match cost::scan_provider(&desc.token_cost, &mut index, days, &pricing) {
+        Ok(snapshot) => {
+            if let Err(e) = index.save(&index_path) {
+                warn!(provider = ?provider, error = %e, "Failed to save cost index");
+            }
+            snapshot
+        }
+        Err(e) => {
+            warn!(provider = ?provider, error = %e, "Failed to scan cost logs");
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use exactobar_core::{UsageSnapshot, UsageWindow};
+    use exactobar_store::DailyCost;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_pending_days() {
+        let mut state = ExportState::default();
+        let today = date(16);
+
+        // First export: yesterday only
+        assert_eq!(state.pending_days(ProviderKind::Claude, today), [date(15)]);
+
+        state.mark_exported(ProviderKind::Claude, date(12));
+        assert_eq!(
+            state.pending_days(ProviderKind::Claude, today),
+            [date(13), date(14), date(15)]
+        );
+
+        state.mark_exported(ProviderKind::Claude, date(15));
+        assert!(state.pending_days(ProviderKind::Claude, today).is_empty());
+
+        // Long gaps are capped at the history retention
+        state.mark_exported(ProviderKind::Codex, date(1) - Days::new(60));
+        assert_eq!(
+            state.pending_days(ProviderKind::Codex, today).len(),
+            usize::try_from(HISTORY_RETENTION_DAYS).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = HistoryStore::load(dir.path().join("usage.db"))
+            .await
+            .unwrap();
+        for (hour, session, weekly) in [(9, 20.0, 40.0), (15, 70.0, 52.0), (23, 10.0, 55.0)] {
+            let mut snapshot = UsageSnapshot::new();
+            snapshot.updated_at = Utc.with_ymd_and_hms(2026, 10, 15, hour, 0, 0).unwrap();
+            snapshot.primary = Some(UsageWindow::new(session));
+            snapshot.secondary = Some(UsageWindow::new(weekly));
+            history.record(ProviderKind::Claude, &snapshot, &[]);
+        }
+        let costs = CostUsageSnapshot {
+            daily: vec![DailyCost {
+                date: Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap(),
+                tokens: 1000,
+                cost_usd: 3.456,
+            }],
+            ..Default::default()
+        };
+
+        let row = DailyRow::new(date(15), ProviderKind::Claude, &history, Some(&costs)).unwrap();
+        assert_eq!(row.session_peak, Some(70.0));
+        assert_eq!(row.weekly_peak, Some(55.0));
+        assert_eq!(
+            row.values(),
+            [
+                json!("2026-10-15"),
+                json!("Claude"),
+                json!(70.0),
+                json!(55.0),
+                json!(3.46)
+            ]
+        );
+
+        assert!(DailyRow::new(date(14), ProviderKind::Claude, &history, None).is_none());
+        let no_cost = DailyRow::new(date(15), ProviderKind::Claude, &history, None).unwrap();
+        assert_eq!(no_cost.values()[4], json!(""));
+    }
+}
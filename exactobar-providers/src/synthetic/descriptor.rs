@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::SyntheticApiStrategy;
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 // ============================================================================
@@ -92,5 +93,6 @@ fn synthetic_cli_config() -> CliConfig {
         aliases: &["syn"],
         version_args: &["--version"],
         usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -0,0 +1,219 @@
+//! Anthropic Admin API Claude Code usage report.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Days, Utc};
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use exactobar_store::keychain::providers::ANTHROPIC_ADMIN;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use super::{TeamError, TeamUnit, TeamUsage, admin_key};
+
+/// Claude Code usage report endpoint.
+const USAGE_REPORT_URL: &str =
+    "https://api.anthropic.com/v1/organizations/usage_report/claude_code";
+
+/// API version header value.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Environment variable holding the admin key.
+const ADMIN_KEY_ENV: &str = "ANTHROPIC_ADMIN_KEY";
+
+/// Records per page.
+const PAGE_LIMIT: u32 = 1000;
+
+#[derive(Debug, Deserialize)]
+struct UsageReport {
+    #[serde(default)]
+    data: Vec<UsageRecord>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_page: Option<String>,
+}
+
+/// One actor's usage on one day.
+#[derive(Debug, Deserialize)]
+struct UsageRecord {
+    actor: Actor,
+    #[serde(default)]
+    model_breakdown: Vec<ModelUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Actor {
+    UserActor { email_address: String },
+    ApiActor { api_key_name: String },
+}
+
+impl Actor {
+    fn name(self) -> String {
+        match self {
+            Self::UserActor { email_address } => email_address,
+            Self::ApiActor { api_key_name } => api_key_name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelUsage {
+    estimated_cost: Cost,
+}
+
+/// Cost in cents.
+#[derive(Debug, Deserialize)]
+struct Cost {
+    amount: f64,
+}
+
+impl UsageRecord {
+    fn dollars(&self) -> f64 {
+        self.model_breakdown
+            .iter()
+            .map(|m| m.estimated_cost.amount)
+            .sum::<f64>()
+            / 100.0
+    }
+}
+
+/// Fetches estimated spend per member since `since`.
+///
+/// The report covers one day per query, so each day is fetched in turn.
+#[instrument(skip(http))]
+pub(super) async fn fetch(http: &HttpClient, since: DateTime<Utc>) -> Result<TeamUsage, TeamError> {
+    let key = admin_key(ANTHROPIC_ADMIN, ADMIN_KEY_ENV)?;
+    let headers = build_headers(&key)?;
+
+    let mut records = Vec::new();
+    let today = Utc::now().date_naive();
+    let mut day = since.date_naive();
+    while day <= today {
+        let mut page: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{USAGE_REPORT_URL}?starting_at={}&limit={PAGE_LIMIT}",
+                day.format("%Y-%m-%d")
+            );
+            if let Some(ref page) = page {
+                let _ = write!(url, "&page={page}");
+            }
+
+            let report = fetch_page(http, &url, headers.clone()).await?;
+            records.extend(report.data);
+            match report.next_page.filter(|_| report.has_more) {
+                Some(next) => page = Some(next),
+                None => break,
+            }
+        }
+        day = match day.checked_add_days(Days::new(1)) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    Ok(parse_records(records, since))
+}
+
+fn build_headers(key: &str) -> Result<HeaderMap, TeamError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-api-key",
+        HeaderValue::from_str(key)
+            .map_err(|e| TeamError::HttpError(format!("Invalid admin key: {}", e)))?,
+    );
+    headers.insert(
+        "anthropic-version",
+        HeaderValue::from_static(ANTHROPIC_VERSION),
+    );
+    Ok(headers)
+}
+
+async fn fetch_page(
+    http: &HttpClient,
+    url: &str,
+    headers: HeaderMap,
+) -> Result<UsageReport, TeamError> {
+    debug!(url = %url, "Fetching Claude Code usage report");
+
+    let response = http
+        .get_with_headers(url, headers)
+        .await
+        .map_err(|e| TeamError::HttpError(e.to_string()))?;
+
+    let status = response.status();
+
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(TeamError::AuthenticationFailed(
+            "Admin API key rejected".to_string(),
+        ));
+    }
+
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(TeamError::ApiError(format!("HTTP {}: {}", status, text)));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| TeamError::ParseError(e.to_string()))
+}
+
+fn parse_records(records: Vec<UsageRecord>, since: DateTime<Utc>) -> TeamUsage {
+    TeamUsage::from_amounts(
+        ProviderKind::Claude,
+        TeamUnit::Dollars,
+        records.into_iter().map(|record| {
+            let dollars = record.dollars();
+            (record.actor.name(), dollars)
+        }),
+        since,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records() {
+        let json = r#"{
+            "data": [
+                {
+                    "date": "2026-10-15T00:00:00Z",
+                    "actor": {"type": "user_actor", "email_address": "ana@example.com"},
+                    "organization_id": "org",
+                    "customer_type": "api",
+                    "model_breakdown": [
+                        {"model": "claude-sonnet-4-5", "estimated_cost": {"currency": "USD", "amount": 1250}},
+                        {"model": "claude-haiku-4-5", "estimated_cost": {"currency": "USD", "amount": 50}}
+                    ]
+                },
+                {
+                    "date": "2026-10-15T00:00:00Z",
+                    "actor": {"type": "api_actor", "api_key_name": "ci-bot"},
+                    "model_breakdown": [
+                        {"model": "claude-sonnet-4-5", "estimated_cost": {"currency": "USD", "amount": 400}}
+                    ]
+                }
+            ],
+            "has_more": false,
+            "next_page": null
+        }"#;
+
+        let report: UsageReport = serde_json::from_str(json).unwrap();
+        assert!(!report.has_more);
+
+        let team = parse_records(report.data, Utc::now());
+        let top = team.top(10);
+        assert_eq!(team.unit, TeamUnit::Dollars);
+        assert_eq!(top[0].name, "ana@example.com");
+        assert!((top[0].amount - 13.0).abs() < 1e-9);
+        assert_eq!(top[1].name, "ci-bot");
+        assert!((top[1].amount - 4.0).abs() < 1e-9);
+    }
+}
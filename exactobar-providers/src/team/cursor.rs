@@ -0,0 +1,131 @@
+//! Cursor Admin API daily usage.
+
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use exactobar_store::keychain::providers::CURSOR_ADMIN;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use super::{TeamError, TeamUnit, TeamUsage, admin_key};
+
+/// Daily usage endpoint.
+const DAILY_USAGE_URL: &str = "https://api.cursor.com/teams/daily-usage-data";
+
+/// Environment variable holding the admin key.
+const ADMIN_KEY_ENV: &str = "CURSOR_ADMIN_API_KEY";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DailyUsageRequest {
+    start_date: i64,
+    end_date: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyUsageResponse {
+    #[serde(default)]
+    data: Vec<DailyUsage>,
+}
+
+/// One member's usage on one day.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DailyUsage {
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    subscription_included_reqs: f64,
+    #[serde(default)]
+    usage_based_reqs: f64,
+    #[serde(default)]
+    api_key_reqs: f64,
+}
+
+impl DailyUsage {
+    fn requests(&self) -> f64 {
+        self.subscription_included_reqs + self.usage_based_reqs + self.api_key_reqs
+    }
+}
+
+/// Fetches request counts per member since `since`.
+#[instrument(skip(http))]
+pub(super) async fn fetch(http: &HttpClient, since: DateTime<Utc>) -> Result<TeamUsage, TeamError> {
+    let key = admin_key(CURSOR_ADMIN, ADMIN_KEY_ENV)?;
+    let auth = format!("Basic {}", BASE64_STANDARD.encode(format!("{key}:")));
+    let body = DailyUsageRequest {
+        start_date: since.timestamp_millis(),
+        end_date: Utc::now().timestamp_millis(),
+    };
+
+    debug!("Fetching Cursor team usage");
+
+    let response = http
+        .post_json_with_auth(DAILY_USAGE_URL, &body, &auth)
+        .await
+        .map_err(|e| TeamError::HttpError(e.to_string()))?;
+
+    let status = response.status();
+
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(TeamError::AuthenticationFailed(
+            "Admin API key rejected".to_string(),
+        ));
+    }
+
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(TeamError::ApiError(format!("HTTP {}: {}", status, text)));
+    }
+
+    let usage: DailyUsageResponse = response
+        .json()
+        .await
+        .map_err(|e| TeamError::ParseError(e.to_string()))?;
+
+    Ok(parse_daily_usage(usage, since))
+}
+
+fn parse_daily_usage(usage: DailyUsageResponse, since: DateTime<Utc>) -> TeamUsage {
+    TeamUsage::from_amounts(
+        ProviderKind::Cursor,
+        TeamUnit::Requests,
+        usage.data.into_iter().filter_map(|day| {
+            let requests = day.requests();
+            day.email.map(|email| (email, requests))
+        }),
+        since,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_daily_usage() {
+        let json = r#"{
+            "data": [
+                {"date": 1760572800000, "email": "ana@example.com", "isActive": true,
+                 "subscriptionIncludedReqs": 40, "usageBasedReqs": 5, "apiKeyReqs": 0},
+                {"date": 1760659200000, "email": "ana@example.com", "isActive": true,
+                 "subscriptionIncludedReqs": 15},
+                {"date": 1760659200000, "email": "bo@example.com", "isActive": true,
+                 "subscriptionIncludedReqs": 20, "usageBasedReqs": 0, "apiKeyReqs": 3},
+                {"date": 1760659200000, "isActive": false}
+            ],
+            "period": {"startDate": 1760572800000, "endDate": 1760745600000}
+        }"#;
+
+        let usage: DailyUsageResponse = serde_json::from_str(json).unwrap();
+        let team = parse_daily_usage(usage, Utc::now());
+        let top = team.top(10);
+
+        assert_eq!(team.unit, TeamUnit::Requests);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].name, "ana@example.com");
+        assert_eq!(top[0].amount, 60.0);
+        assert_eq!(top[1].amount, 23.0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Team usage leaderboards from admin APIs.
+//!
+//! Some providers give organization admins a per-member usage report:
+//! Cursor's Admin API counts requests per member per day, and Anthropic's
+//! Claude Code usage report prices each user's token usage. [`fetch_team_usage`]
+//! pulls the last seven days from whichever one `provider` has and sums it
+//! per member into a [`TeamUsage`], which ranks members with [`TeamUsage::top`].
+//!
+//! Both need an admin key, separate from the personal credentials the usage
+//! strategies read.
+
+mod claude;
+mod cursor;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use exactobar_core::ProviderKind;
+use exactobar_fetch::host::HttpClient;
+use serde::Serialize;
+use thiserror::Error;
+
+/// How far back a leaderboard looks.
+pub const TEAM_WINDOW_DAYS: i64 = 7;
+
+/// Team usage errors.
+#[derive(Debug, Error)]
+pub enum TeamError {
+    /// The provider has no team usage API.
+    #[error("{0} has no team usage API")]
+    Unsupported(String),
+
+    /// No admin key in the keychain or environment.
+    #[error("Admin key not found (set {0})")]
+    AdminKeyNotFound(&'static str),
+
+    /// HTTP request failed.
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+
+    /// Parse error.
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// API error.
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    /// Authentication failed.
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+}
+
+/// What a team report counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamUnit {
+    /// Requests.
+    Requests,
+    /// Spend in USD.
+    Dollars,
+}
+
+impl TeamUnit {
+    /// Formats `amount` in this unit, like "1204 req" or "$12.40".
+    pub fn format(self, amount: f64) -> String {
+        match self {
+            Self::Requests => format!("{amount:.0} req"),
+            Self::Dollars => format!("${amount:.2}"),
+        }
+    }
+}
+
+/// One member's usage over the window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamMember {
+    /// Email, or the API key name for usage not tied to a user.
+    pub name: String,
+    /// Usage in the report's unit.
+    pub amount: f64,
+}
+
+/// A ranked member with their share of the team's usage.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamRank {
+    /// Email or key name.
+    pub name: String,
+    /// Usage in the report's unit.
+    pub amount: f64,
+    /// Share of the team total, in percent.
+    pub percent: f64,
+}
+
+/// A team's usage over the last [`TEAM_WINDOW_DAYS`] days.
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamUsage {
+    /// Provider the report came from.
+    pub provider: ProviderKind,
+    /// What the amounts count.
+    pub unit: TeamUnit,
+    /// Per-member totals, in no particular order.
+    pub members: Vec<TeamMember>,
+    /// Start of the window.
+    pub since: DateTime<Utc>,
+    /// When the report was fetched.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl TeamUsage {
+    /// Builds a report by summing `amounts` per name.
+    pub fn from_amounts(
+        provider: ProviderKind,
+        unit: TeamUnit,
+        amounts: impl IntoIterator<Item = (String, f64)>,
+        since: DateTime<Utc>,
+    ) -> Self {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for (name, amount) in amounts {
+            *totals.entry(name).or_default() += amount;
+        }
+
+        Self {
+            provider,
+            unit,
+            members: totals
+                .into_iter()
+                .map(|(name, amount)| TeamMember { name, amount })
+                .collect(),
+            since,
+            fetched_at: Utc::now(),
+        }
+    }
+
+    /// Total usage across the team.
+    pub fn total(&self) -> f64 {
+        self.members.iter().map(|m| m.amount).sum()
+    }
+
+    /// The `n` heaviest members, with their share of the total. Ties are
+    /// broken by name so the order is stable between refreshes.
+    pub fn top(&self, n: usize) -> Vec<TeamRank> {
+        let total = self.total();
+        let mut members: Vec<&TeamMember> =
+            self.members.iter().filter(|m| m.amount > 0.0).collect();
+        members.sort_by(|a, b| {
+            b.amount
+                .total_cmp(&a.amount)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        members
+            .into_iter()
+            .take(n)
+            .map(|m| TeamRank {
+                name: m.name.clone(),
+                amount: m.amount,
+                percent: if total > 0.0 {
+                    100.0 * m.amount / total
+                } else {
+                    0.0
+                },
+            })
+            .collect()
+    }
+}
+
+/// Providers with a team usage API.
+pub const TEAM_PROVIDERS: [ProviderKind; 2] = [ProviderKind::Cursor, ProviderKind::Claude];
+
+/// Whether `provider` has a team usage API.
+pub fn supports_team_usage(provider: ProviderKind) -> bool {
+    TEAM_PROVIDERS.contains(&provider)
+}
+
+/// Fetches the last [`TEAM_WINDOW_DAYS`] days of team usage for `provider`.
+pub async fn fetch_team_usage(
+    provider: ProviderKind,
+    http: &HttpClient,
+) -> Result<TeamUsage, TeamError> {
+    let since = Utc::now() - Duration::days(TEAM_WINDOW_DAYS);
+    match provider {
+        ProviderKind::Cursor => cursor::fetch(http, since).await,
+        ProviderKind::Claude => claude::fetch(http, since).await,
+        other => Err(TeamError::Unsupported(other.display_name().to_string())),
+    }
+}
+
+/// Reads an admin key from the keychain, then the environment.
+fn admin_key(keychain_name: &str, env_var: &'static str) -> Result<String, TeamError> {
+    if let Some(key) = exactobar_store::get_api_key(keychain_name) {
+        return Ok(key);
+    }
+
+    std::env::var(env_var)
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or(TeamError::AdminKeyNotFound(env_var))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(amounts: &[(&str, f64)]) -> TeamUsage {
+        TeamUsage::from_amounts(
+            ProviderKind::Cursor,
+            TeamUnit::Requests,
+            amounts.iter().map(|(n, a)| ((*n).to_string(), *a)),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_top_members() {
+        let team = usage(&[
+            ("ana@example.com", 30.0),
+            ("bo@example.com", 50.0),
+            ("ana@example.com", 10.0),
+            ("cy@example.com", 20.0),
+            ("idle@example.com", 0.0),
+        ]);
+
+        assert_eq!(team.total(), 110.0);
+        let top = team.top(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].name, "bo@example.com");
+        assert_eq!(top[1].name, "ana@example.com");
+        assert_eq!(top[1].amount, 40.0);
+        assert!((top[0].percent - 100.0 * 50.0 / 110.0).abs() < 1e-9);
+
+        // Members without usage aren't ranked
+        assert_eq!(team.top(10).len(), 3);
+    }
+
+    #[test]
+    fn test_top_ties_by_name() {
+        let team = usage(&[("zed@example.com", 5.0), ("amy@example.com", 5.0)]);
+        let names: Vec<_> = team.top(2).into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["amy@example.com", "zed@example.com"]);
+    }
+
+    #[test]
+    fn test_unit_format() {
+        assert_eq!(TeamUnit::Requests.format(1204.4), "1204 req");
+        assert_eq!(TeamUnit::Dollars.format(12.4), "$12.40");
+    }
+}
@@ -5,6 +5,7 @@ use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 use std::path::PathBuf;
 
 use super::strategies::{VertexAILocalStrategy, VertexAIOAuthStrategy};
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn vertexai_descriptor() -> ProviderDescriptor {
@@ -52,6 +53,7 @@ fn vertexai_token_cost() -> TokenCostConfig {
     TokenCostConfig {
         supports_token_cost: true,
         log_directory: Some(vertexai_log_directory),
+        log_format: None,
     }
 }
 
@@ -84,5 +86,6 @@ fn vertexai_cli_config() -> CliConfig {
         aliases: &["vertexai"],
         version_args: &["--version"],
         usage_args: &["ai", "operations", "list"],
+        compatible_versions: VersionRange::ANY,
     }
 }
@@ -38,6 +38,18 @@ pub struct ClaudeLogEntry {
     #[serde(default, alias = "total_tokens")]
     pub total_tokens: Option<u64>,
 
+    /// Tokens read from the prompt cache.
+    #[serde(default, alias = "cache_read_input_tokens")]
+    pub cache_read_tokens: Option<u64>,
+
+    /// Tokens written to the prompt cache.
+    #[serde(default, alias = "cache_creation_input_tokens")]
+    pub cache_creation_tokens: Option<u64>,
+
+    /// Reasoning tokens (a subset of output tokens).
+    #[serde(default, alias = "reasoning_output_tokens")]
+    pub reasoning_tokens: Option<u64>,
+
     /// Cost in USD.
     #[serde(default)]
     pub cost_usd: Option<f64>,
@@ -56,6 +68,15 @@ pub struct TokenUsage {
     /// Total output tokens.
     pub output_tokens: u64,
 
+    /// Total cache read tokens.
+    pub cache_read_tokens: u64,
+
+    /// Total cache creation tokens.
+    pub cache_creation_tokens: u64,
+
+    /// Total reasoning tokens (included in `output_tokens`).
+    pub reasoning_tokens: u64,
+
     /// Total tokens.
     pub total_tokens: u64,
 
@@ -80,11 +101,19 @@ impl TokenUsage {
 
     /// Add a log entry to the aggregation.
     pub fn add_entry(&mut self, entry: &ClaudeLogEntry) {
-        self.input_tokens += entry.input_tokens.unwrap_or(0);
-        self.output_tokens += entry.output_tokens.unwrap_or(0);
+        let input = entry.input_tokens.unwrap_or(0);
+        let output = entry.output_tokens.unwrap_or(0);
+        let cache_read = entry.cache_read_tokens.unwrap_or(0);
+        let cache_creation = entry.cache_creation_tokens.unwrap_or(0);
+
+        self.input_tokens += input;
+        self.output_tokens += output;
+        self.cache_read_tokens += cache_read;
+        self.cache_creation_tokens += cache_creation;
+        self.reasoning_tokens += entry.reasoning_tokens.unwrap_or(0);
         self.total_tokens += entry
             .total_tokens
-            .unwrap_or(entry.input_tokens.unwrap_or(0) + entry.output_tokens.unwrap_or(0));
+            .unwrap_or(input + output + cache_read + cache_creation);
         self.total_cost_usd += entry.cost_usd.unwrap_or(0.0);
         self.request_count += 1;
 
@@ -286,6 +315,9 @@ mod tests {
             input_tokens: Some(100),
             output_tokens: Some(50),
             total_tokens: None,
+            cache_read_tokens: None,
+            cache_creation_tokens: None,
+            reasoning_tokens: None,
             cost_usd: Some(0.01),
             request_type: None,
         };
@@ -296,4 +328,24 @@ mod tests {
         assert_eq!(usage.total_tokens, 150);
         assert_eq!(usage.request_count, 1);
     }
+
+    #[test]
+    fn test_add_entry_with_cache_and_reasoning() {
+        let json = r#"{
+            "model": "claude-sonnet-4",
+            "input_tokens": 100,
+            "output_tokens": 50,
+            "cache_read_input_tokens": 1000,
+            "cache_creation_input_tokens": 200,
+            "reasoning_output_tokens": 20
+        }"#;
+        let entry: ClaudeLogEntry = serde_json::from_str(json).unwrap();
+
+        let mut usage = TokenUsage::default();
+        usage.add_entry(&entry);
+        assert_eq!(usage.cache_read_tokens, 1000);
+        assert_eq!(usage.cache_creation_tokens, 200);
+        assert_eq!(usage.reasoning_tokens, 20);
+        assert_eq!(usage.total_tokens, 1350);
+    }
 }
@@ -4,6 +4,7 @@ use exactobar_core::{IconStyle, ProviderBranding, ProviderColor, ProviderKind, P
 use exactobar_fetch::{FetchContext, FetchPipeline, SourceMode};
 
 use super::strategies::ZaiApiStrategy;
+use crate::compat::VersionRange;
 use crate::descriptor::{CliConfig, FetchPlan, ProviderDescriptor, TokenCostConfig};
 
 pub fn zai_descriptor() -> ProviderDescriptor {
@@ -70,5 +71,6 @@ fn zai_cli_config() -> CliConfig {
         aliases: &[],
         version_args: &["--version"],
         usage_args: &["usage"],
+        compatible_versions: VersionRange::ANY,
     }
 }
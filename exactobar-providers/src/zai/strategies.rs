@@ -6,8 +6,8 @@ use exactobar_core::{FetchSource, UsageSnapshot};
 use exactobar_fetch::{FetchContext, FetchError, FetchKind, FetchResult, FetchStrategy};
 use tracing::{debug, instrument};
 
-use super::parser::parse_zai_response;
 use super::token_store::ZaiTokenStore;
+use crate::recording::{self, RawFormat};
 
 const ZAI_API: &str = "https://api.z.ai/v1/usage";
 
@@ -76,7 +76,9 @@ impl FetchStrategy for ZaiApiStrategy {
             .await
             .map_err(|e| FetchError::InvalidResponse(e.to_string()))?;
 
-        let snapshot = parse_zai_response(&body)?;
+        recording::record(RawFormat::Zai, &body);
+
+        let snapshot = RawFormat::Zai.parse(&body)?;
         Ok(FetchResult::new(snapshot, self.id(), self.kind()))
     }
 
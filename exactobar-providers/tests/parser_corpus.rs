@@ -0,0 +1,104 @@
+//! Parser tests against the shared fixture corpus.
+//!
+//! `tests/corpus/<format>/` holds real-shaped responses for each
+//! [`RawFormat`], and `tests/corpus/malformed/` holds the truncated,
+//! mistyped and out-of-range output providers have produced. The fuzz
+//! targets in `fuzz/` seed from the same directory.
+
+use std::path::{Path, PathBuf};
+
+use exactobar_fetch::FetchError;
+use exactobar_providers::RawFormat;
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn files_in(dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .map(|path| {
+            let contents = std::fs::read_to_string(&path).unwrap();
+            (path, contents)
+        })
+        .collect()
+}
+
+#[test]
+fn test_every_format_has_samples() {
+    for format in RawFormat::ALL {
+        let dir = corpus_dir().join(format.name());
+        assert!(
+            dir.is_dir() && !files_in(&dir).is_empty(),
+            "no samples for {}",
+            format.name()
+        );
+    }
+}
+
+#[test]
+fn test_samples_parse() {
+    for format in RawFormat::ALL {
+        for (path, contents) in files_in(&corpus_dir().join(format.name())) {
+            let snapshot = format
+                .parse(&contents)
+                .unwrap_or_else(|e| panic!("{} failed: {e}", path.display()));
+            assert!(
+                snapshot.primary.is_some() || snapshot.identity.is_some(),
+                "{} parsed to nothing",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_samples_pass_strict_checks() {
+    for format in RawFormat::ALL {
+        for (path, contents) in files_in(&corpus_dir().join(format.name())) {
+            let snapshot = format.parse(&contents).unwrap();
+            let diagnostics = format.diagnose(&contents, &snapshot);
+            assert!(diagnostics.is_empty(), "{}: {diagnostics}", path.display());
+        }
+    }
+}
+
+#[test]
+fn test_no_parser_panics_on_corpus() {
+    let mut dirs = vec![corpus_dir().join("malformed")];
+    dirs.extend(RawFormat::ALL.iter().map(|f| corpus_dir().join(f.name())));
+
+    for dir in dirs {
+        for (path, contents) in files_in(&dir) {
+            for format in RawFormat::ALL {
+                if let Err(FetchError::ParseFailed(message)) = format.parse(&contents) {
+                    panic!("{} on {}: {message}", format.name(), path.display());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_malformed_json_is_rejected() {
+    let truncated = std::fs::read_to_string(corpus_dir().join("malformed/truncated.json")).unwrap();
+    for format in RawFormat::ALL {
+        if matches!(
+            format,
+            RawFormat::ClaudeCliText | RawFormat::ClaudePty | RawFormat::CodexPty
+        ) {
+            // Text scrapers skip what they don't recognise
+            continue;
+        }
+        assert!(
+            format.parse(&truncated).is_err(),
+            "{} accepted truncated JSON",
+            format.name()
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Usage history benchmarks.
+//!
+//! A [`HistoryStore`] holding the full retention of a few providers
+//! refreshed every five minutes, as the history window and graphs query
+//! it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use criterion::{Criterion, criterion_group};
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use exactobar_store::{HISTORY_RETENTION_DAYS, HistoryStore, WindowSlot, diff_snapshots};
+use tokio::runtime::Runtime;
+
+#[path = "../../benches/regression.rs"]
+mod regression;
+
+const PROVIDERS: [ProviderKind; 4] = [
+    ProviderKind::Claude,
+    ProviderKind::Codex,
+    ProviderKind::Cursor,
+    ProviderKind::Copilot,
+];
+
+/// Minutes between refreshes.
+const REFRESH_MINUTES: i64 = 5;
+
+fn snapshot(at: DateTime<Utc>, step: i64) -> UsageSnapshot {
+    // Sessions fill up over five hours, weeks over seven days
+    let mut snapshot = UsageSnapshot::new();
+    snapshot.updated_at = at;
+    snapshot.primary = Some(UsageWindow::new((step % 60) as f64 * 100.0 / 60.0));
+    snapshot.secondary = Some(UsageWindow::new((step % 2016) as f64 * 100.0 / 2016.0));
+    snapshot
+}
+
+/// Records the full retention for every provider.
+fn filled_store(rt: &Runtime, dir: &tempfile::TempDir) -> HistoryStore {
+    let mut store = rt
+        .block_on(HistoryStore::load(dir.path().join("history.db")))
+        .unwrap();
+    let steps = HISTORY_RETENTION_DAYS * 24 * 60 / REFRESH_MINUTES;
+    let start = Utc::now() - chrono::Duration::days(HISTORY_RETENTION_DAYS);
+    for provider in PROVIDERS {
+        let mut previous: Option<UsageSnapshot> = None;
+        for step in 0..steps {
+            let current = snapshot(
+                start + chrono::Duration::minutes(step * REFRESH_MINUTES),
+                step,
+            );
+            let events = previous
+                .as_ref()
+                .map(|previous| diff_snapshots(previous, &current))
+                .unwrap_or_default();
+            store.record(provider, &current, &events);
+            previous = Some(current);
+        }
+    }
+    rt.block_on(store.save()).unwrap();
+    store
+}
+
+fn bench_history(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let store = filled_store(&rt, &dir);
+    let now = Utc::now();
+
+    let mut group = c.benchmark_group("history");
+    group.bench_function("series_day", |b| {
+        b.iter(|| {
+            store
+                .series(
+                    ProviderKind::Claude,
+                    WindowSlot::Primary,
+                    now - chrono::Duration::days(1),
+                )
+                .len()
+        });
+    });
+    group.bench_function("series_month", |b| {
+        b.iter(|| {
+            store
+                .series(
+                    ProviderKind::Claude,
+                    WindowSlot::Secondary,
+                    now - chrono::Duration::days(HISTORY_RETENTION_DAYS),
+                )
+                .len()
+        });
+    });
+    group.bench_function("events_month", |b| {
+        b.iter(|| {
+            store
+                .events(
+                    ProviderKind::Claude,
+                    now - chrono::Duration::days(HISTORY_RETENTION_DAYS),
+                )
+                .count()
+        });
+    });
+    group.bench_function("record", |b| {
+        let live_dir = tempfile::tempdir().unwrap();
+        let mut live = filled_store(&rt, &live_dir);
+        let mut previous = snapshot(now, 0);
+        let mut step = 0;
+        b.iter(|| {
+            step += 1;
+            let current = snapshot(
+                now + chrono::Duration::minutes(step * REFRESH_MINUTES),
+                step,
+            );
+            let events = diff_snapshots(&previous, &current);
+            live.record(ProviderKind::Claude, &current, &events);
+            previous = current;
+        });
+    });
+    group.sample_size(10);
+    group.bench_function("load", |b| {
+        b.iter(|| {
+            rt.block_on(HistoryStore::load(store.path().clone()))
+                .unwrap()
+        });
+    });
+    group.finish();
+}
+
+fn thresholds() -> Vec<(String, Duration)> {
+    [
+        ("history/series_day", Duration::from_micros(50)),
+        ("history/series_month", Duration::from_micros(50)),
+        ("history/events_month", Duration::from_millis(2)),
+        ("history/record", Duration::from_millis(2)),
+        ("history/load", Duration::from_millis(750)),
+    ]
+    .into_iter()
+    .map(|(id, max)| (id.to_string(), max))
+    .collect()
+}
+
+criterion_group!(benches, bench_history);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    regression::check(&thresholds());
+}
@@ -0,0 +1,276 @@
+//! Custom data directory.
+//!
+//! Settings, caches and history normally live in the platform's config and
+//! cache directories. Users who sync their configuration (iCloud Drive,
+//! Dropbox...) can move everything into one directory of their choosing
+//! instead:
+//!
+//! ```text
+//! <data dir>/settings.json
+//! <data dir>/profiles/...
+//! <data dir>/cache/usage_cache.json
+//! <data dir>/cache/history/...
+//! ```
+//!
+//! The chosen directory is recorded in `data_dir` in the platform config
+//! directory, and the `EXACTOBAR_DATA_DIR` environment variable takes
+//! precedence over it. [`default_config_dir`](crate::default_config_dir)
+//! and [`default_cache_dir`](crate::default_cache_dir) follow whichever is
+//! set.
+
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, info};
+
+use crate::error::StoreError;
+use crate::persistence::{platform_cache_dir, platform_config_dir};
+
+/// Environment variable overriding the data directory.
+pub const DATA_DIR_ENV: &str = "EXACTOBAR_DATA_DIR";
+
+/// File in the platform config directory naming the data directory.
+const DATA_DIR_FILE: &str = "data_dir";
+
+/// Directory under a custom data directory holding caches.
+const CACHE_DIR: &str = "cache";
+
+/// Result of moving to another data directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// The new config directory.
+    pub config_dir: PathBuf,
+    /// Files copied from the old directories.
+    pub copied: usize,
+    /// Files left alone because the new directory already had them.
+    pub skipped: usize,
+}
+
+// ============================================================================
+// Lookup
+// ============================================================================
+
+/// Returns the data directory set by `EXACTOBAR_DATA_DIR`, if any.
+pub fn data_dir_from_env() -> Option<PathBuf> {
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Returns the custom data directory in use, if any: the environment
+/// variable first, then the one chosen in settings.
+pub fn custom_data_dir() -> Option<PathBuf> {
+    data_dir_from_env().or_else(|| chosen_data_dir_in(&platform_config_dir()))
+}
+
+fn chosen_data_dir_in(platform_config: &Path) -> Option<PathBuf> {
+    let contents = std::fs::read_to_string(platform_config.join(DATA_DIR_FILE)).ok()?;
+    let path = PathBuf::from(contents.trim());
+    path.is_absolute().then_some(path)
+}
+
+/// Returns the config and cache directories for a data directory, or the
+/// platform ones for `None`.
+fn dirs_for(
+    data_dir: Option<&Path>,
+    platform_config: &Path,
+    platform_cache: &Path,
+) -> (PathBuf, PathBuf) {
+    match data_dir {
+        Some(dir) => (dir.to_path_buf(), data_cache_dir(dir)),
+        None => (platform_config.to_path_buf(), platform_cache.to_path_buf()),
+    }
+}
+
+/// Returns the cache directory inside a custom data directory.
+pub(crate) fn data_cache_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(CACHE_DIR)
+}
+
+// ============================================================================
+// Relocation
+// ============================================================================
+
+/// Moves settings, caches and history to `target`, or back to the platform
+/// directories for `None`, and uses it from now on.
+///
+/// Files are copied, not moved, so the old directories keep a backup.
+/// Files the target already has are left alone: pointing a second machine
+/// at a synced directory picks up the synced settings rather than
+/// overwriting them.
+///
+/// Returns `StoreError::Config` if `EXACTOBAR_DATA_DIR` is set, the target
+/// isn't absolute, or one directory is inside the other.
+pub fn relocate_data_dir(target: Option<&Path>) -> Result<Relocation, StoreError> {
+    if data_dir_from_env().is_some() {
+        return Err(StoreError::Config(format!(
+            "the data directory is set by {DATA_DIR_ENV}"
+        )));
+    }
+    relocate_in(&platform_config_dir(), &platform_cache_dir(), target)
+}
+
+fn relocate_in(
+    platform_config: &Path,
+    platform_cache: &Path,
+    target: Option<&Path>,
+) -> Result<Relocation, StoreError> {
+    if let Some(target) = target {
+        if !target.is_absolute() {
+            return Err(StoreError::Config(format!(
+                "data directory '{}' is not an absolute path",
+                target.display()
+            )));
+        }
+        if target.is_file() {
+            return Err(StoreError::Config(format!(
+                "data directory '{}' is a file",
+                target.display()
+            )));
+        }
+    }
+
+    let current = chosen_data_dir_in(platform_config);
+    let (from_config, from_cache) = dirs_for(current.as_deref(), platform_config, platform_cache);
+    let (to_config, to_cache) = dirs_for(target, platform_config, platform_cache);
+
+    let mut relocation = Relocation {
+        config_dir: to_config.clone(),
+        copied: 0,
+        skipped: 0,
+    };
+    if to_config == from_config {
+        return Ok(relocation);
+    }
+    if to_config.starts_with(&from_config) || from_config.starts_with(&to_config) {
+        return Err(StoreError::Config(format!(
+            "'{}' and '{}' can't be inside one another",
+            to_config.display(),
+            from_config.display()
+        )));
+    }
+
+    let skip = [from_cache.clone(), platform_config.join(DATA_DIR_FILE)];
+    copy_tree(&from_config, &to_config, &skip, &mut relocation)?;
+    copy_tree(&from_cache, &to_cache, &skip, &mut relocation)?;
+
+    let pointer = platform_config.join(DATA_DIR_FILE);
+    match target {
+        Some(target) => {
+            std::fs::create_dir_all(platform_config)?;
+            std::fs::write(&pointer, target.to_string_lossy().as_bytes())?;
+        }
+        None => match std::fs::remove_file(&pointer) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        },
+    }
+
+    info!(
+        to = %to_config.display(),
+        copied = relocation.copied,
+        skipped = relocation.skipped,
+        "Data directory relocated"
+    );
+    Ok(relocation)
+}
+
+/// Copies every file under `from` to the same place under `to`, except the
+/// paths in `skip` and files `to` already has.
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    skip: &[PathBuf],
+    relocation: &mut Relocation,
+) -> Result<(), StoreError> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let dest = to.join(name);
+        if path.is_dir() {
+            copy_tree(&path, &dest, skip, relocation)?;
+        } else if dest.exists() {
+            debug!(path = %dest.display(), "Kept existing file");
+            relocation.skipped += 1;
+        } else {
+            std::fs::copy(&path, &dest)?;
+            relocation.copied += 1;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_relocate_and_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        let cache = dir.path().join("cache");
+        let synced = dir.path().join("Dropbox").join("ExactoBar");
+
+        write(&config.join("settings.json"), "local");
+        write(&config.join("profiles/Work/settings.json"), "work");
+        write(&cache.join("usage_cache.json"), "{}");
+        write(&cache.join("history/notes.json"), "[]");
+        // Already synced from another machine
+        write(&synced.join("settings.json"), "synced");
+
+        let relocation = relocate_in(&config, &cache, Some(&synced)).unwrap();
+        assert_eq!(relocation.config_dir, synced);
+        assert_eq!((relocation.copied, relocation.skipped), (3, 1));
+        assert_eq!(chosen_data_dir_in(&config), Some(synced.clone()));
+        assert_eq!(
+            std::fs::read_to_string(synced.join("settings.json")).unwrap(),
+            "synced"
+        );
+        assert!(synced.join("profiles/Work/settings.json").is_file());
+        assert!(synced.join("cache/history/notes.json").is_file());
+        assert!(!synced.join(DATA_DIR_FILE).exists());
+
+        // Choosing it again is a no-op
+        let again = relocate_in(&config, &cache, Some(&synced)).unwrap();
+        assert_eq!((again.copied, again.skipped), (0, 0));
+
+        // Back to the platform directories; the old files are still there
+        let back = relocate_in(&config, &cache, None).unwrap();
+        assert_eq!(back.config_dir, config);
+        assert_eq!((back.copied, back.skipped), (0, 4));
+        assert_eq!(chosen_data_dir_in(&config), None);
+    }
+
+    #[test]
+    fn test_relocate_rejects_bad_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        let cache = dir.path().join("cache");
+
+        assert!(relocate_in(&config, &cache, Some(Path::new("relative/dir"))).is_err());
+        assert!(relocate_in(&config, &cache, Some(&config.join("nested"))).is_err());
+
+        write(&dir.path().join("file"), "");
+        assert!(relocate_in(&config, &cache, Some(&dir.path().join("file"))).is_err());
+        assert_eq!(chosen_data_dir_in(&config), None);
+    }
+}
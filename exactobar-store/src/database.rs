@@ -0,0 +1,147 @@
+//! `SQLite` database for persisted usage.
+//!
+//! Usage history lives in one `SQLite` database in WAL mode, so the app can
+//! write while the CLI and the history window read, and a save only adds
+//! the new rows instead of rewriting a whole JSON file. The schema version
+//! is kept in `PRAGMA user_version` and upgraded by [`MIGRATIONS`] when the
+//! database is opened.
+//!
+//! Only history is kept here. Latest snapshots are held in memory by the
+//! [`UsageStore`](crate::UsageStore) and fetched again on launch.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::Connection;
+use tracing::debug;
+
+use crate::error::StoreError;
+use crate::persistence::default_history_dir;
+
+/// How long to wait for another process holding the write lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Schema migrations, in order. A database's `user_version` is the number
+/// of migrations already applied to it.
+const MIGRATIONS: &[&str] = &[
+    // 1: usage points and events
+    "CREATE TABLE points (
+        provider TEXT NOT NULL,
+        slot TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        used_percent REAL NOT NULL,
+        PRIMARY KEY (provider, slot, timestamp)
+    ) WITHOUT ROWID;
+    CREATE INDEX points_timestamp ON points (timestamp);
+    CREATE TABLE events (
+        id INTEGER PRIMARY KEY,
+        provider TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        event TEXT NOT NULL
+    );
+    CREATE INDEX events_timestamp ON events (timestamp);",
+];
+
+/// Returns the usage database of the current profile.
+pub fn default_database_path() -> PathBuf {
+    default_history_dir().join("usage.db")
+}
+
+/// Opens a database, creating it if needed and applying pending migrations.
+pub fn open_database(path: &Path) -> Result<Connection, StoreError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut conn = Connection::open(path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    let mode: String =
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+    if !mode.eq_ignore_ascii_case("wal") {
+        debug!(path = %path.display(), mode = %mode, "Database not in WAL mode");
+    }
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+    migrate(&mut conn, path)?;
+    Ok(conn)
+}
+
+/// Applies the migrations the database doesn't have yet.
+fn migrate(conn: &mut Connection, path: &Path) -> Result<(), StoreError> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(StoreError::Corrupted {
+            path: path.to_path_buf(),
+            reason: format!(
+                "schema version {} is newer than this build supports ({})",
+                version,
+                MIGRATIONS.len()
+            ),
+        });
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        debug!(path = %path.display(), version = index + 1, "Database migrated");
+    }
+    Ok(())
+}
+
+/// Returns the size of a database on disk, including its write-ahead log.
+pub fn database_size(path: &Path) -> u64 {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    [path.to_path_buf(), PathBuf::from(wal)]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Runs blocking database work off the async runtime.
+pub(crate) async fn blocking<T, F>(f: F) -> Result<T, StoreError>
+where
+    F: FnOnce() -> Result<T, StoreError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| StoreError::Io(std::io::Error::other(e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_migrates_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history").join("usage.db");
+
+        let conn = open_database(&path).unwrap();
+        let version: usize = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        let mode: String = conn
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        drop(conn);
+
+        // Reopening doesn't run the migrations again
+        open_database(&path).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        drop(conn);
+        assert!(matches!(
+            open_database(&path),
+            Err(StoreError::Corrupted { .. })
+        ));
+    }
+}
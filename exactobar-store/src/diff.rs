@@ -0,0 +1,524 @@
+//! Snapshot diffing.
+//!
+//! Compares consecutive usage snapshots of a provider and reports what
+//! changed as [`SnapshotEvent`]s: a window reset, a plan or account
+//! change, or a big jump in usage. It also warns once when a login that
+//! can't renew itself is about to expire (see
+//! [`ProviderIdentity::login_expires_at`](exactobar_core::ProviderIdentity)),
+//! so the user can sign in again before fetches start failing.
+//!
+//! Notification rules can subscribe to event kinds (see
+//! [`NotificationRule::events`](crate::NotificationRule)), and the events
+//! serialize as `{"kind": "window_reset", ...}` so they can be stored
+//! alongside history.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use serde::{Deserialize, Serialize};
+
+/// Percentage points usage must rise by between snapshots to count as a
+/// big jump.
+pub const BIG_JUMP_PERCENT: f64 = 10.0;
+
+/// Percentage points usage must drop by to count as a reset when the
+/// provider doesn't report reset times.
+///
+/// Some providers report slightly lower usage between refreshes as old
+/// requests age out of a rolling window, which isn't a reset.
+pub const RESET_DROP_PERCENT: f64 = 5.0;
+
+/// How far a reset time must move forward to count as a new window.
+/// Providers recompute reset times on each fetch, so they jitter a little.
+const RESET_TIME_SLACK: Duration = Duration::minutes(1);
+
+/// How long before a login expires to warn about it.
+pub const LOGIN_EXPIRY_WARNING: Duration = Duration::days(3);
+
+// ============================================================================
+// Window Slots
+// ============================================================================
+
+/// One of the usage windows in a [`UsageSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowSlot {
+    /// Session window.
+    Primary,
+    /// Weekly or monthly window.
+    Secondary,
+    /// Premium model window.
+    Tertiary,
+    /// Search quota window.
+    Search,
+}
+
+impl WindowSlot {
+    /// Returns every slot, in snapshot order.
+    pub fn all() -> &'static [WindowSlot] {
+        &[
+            WindowSlot::Primary,
+            WindowSlot::Secondary,
+            WindowSlot::Tertiary,
+            WindowSlot::Search,
+        ]
+    }
+
+    /// Generic label for the window, for providers without their own.
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowSlot::Primary => "Session",
+            WindowSlot::Secondary => "Weekly",
+            WindowSlot::Tertiary => "Premium",
+            WindowSlot::Search => "Search",
+        }
+    }
+
+    /// Returns this slot's window in a snapshot.
+    pub fn window(self, snapshot: &UsageSnapshot) -> Option<&UsageWindow> {
+        match self {
+            WindowSlot::Primary => snapshot.primary.as_ref(),
+            WindowSlot::Secondary => snapshot.secondary.as_ref(),
+            WindowSlot::Tertiary => snapshot.tertiary.as_ref(),
+            WindowSlot::Search => snapshot.search.as_ref(),
+        }
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+/// Kind of a [`SnapshotEvent`], as named in notification rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotEventKind {
+    /// A usage window reset.
+    WindowReset,
+    /// The account's plan changed.
+    PlanChanged,
+    /// A different account is signed in.
+    AccountChanged,
+    /// Usage rose by more than [`BIG_JUMP_PERCENT`] between snapshots.
+    BigJump,
+    /// The login expires within [`LOGIN_EXPIRY_WARNING`].
+    TokenExpiring,
+}
+
+impl SnapshotEventKind {
+    /// Name used in the settings file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SnapshotEventKind::WindowReset => "window_reset",
+            SnapshotEventKind::PlanChanged => "plan_changed",
+            SnapshotEventKind::AccountChanged => "account_changed",
+            SnapshotEventKind::BigJump => "big_jump",
+            SnapshotEventKind::TokenExpiring => "token_expiring",
+        }
+    }
+}
+
+impl std::fmt::Display for SnapshotEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Something that changed between two snapshots of a provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotEvent {
+    /// A usage window reset.
+    WindowReset {
+        /// The window that reset.
+        window: WindowSlot,
+        /// Usage before the reset.
+        previous_percent: f64,
+        /// Usage after the reset.
+        used_percent: f64,
+        /// When the new window resets, if known.
+        resets_at: Option<DateTime<Utc>>,
+    },
+    /// The account's plan changed.
+    PlanChanged {
+        /// Previous plan name.
+        from: String,
+        /// New plan name.
+        to: String,
+    },
+    /// A different account is signed in.
+    AccountChanged {
+        /// Previous account (email or organization).
+        from: String,
+        /// New account.
+        to: String,
+    },
+    /// Usage rose by more than [`BIG_JUMP_PERCENT`].
+    BigJump {
+        /// The window that jumped.
+        window: WindowSlot,
+        /// Usage before the jump.
+        from_percent: f64,
+        /// Usage after the jump.
+        to_percent: f64,
+    },
+    /// The login expires soon and needs renewing.
+    TokenExpiring {
+        /// When the login expires.
+        expires_at: DateTime<Utc>,
+    },
+}
+
+impl SnapshotEvent {
+    /// Returns the event's kind.
+    pub fn kind(&self) -> SnapshotEventKind {
+        match self {
+            SnapshotEvent::WindowReset { .. } => SnapshotEventKind::WindowReset,
+            SnapshotEvent::PlanChanged { .. } => SnapshotEventKind::PlanChanged,
+            SnapshotEvent::AccountChanged { .. } => SnapshotEventKind::AccountChanged,
+            SnapshotEvent::BigJump { .. } => SnapshotEventKind::BigJump,
+            SnapshotEvent::TokenExpiring { .. } => SnapshotEventKind::TokenExpiring,
+        }
+    }
+
+    /// Returns the window the event is about, if any.
+    pub fn window(&self) -> Option<WindowSlot> {
+        match self {
+            SnapshotEvent::WindowReset { window, .. } | SnapshotEvent::BigJump { window, .. } => {
+                Some(*window)
+            }
+            SnapshotEvent::PlanChanged { .. }
+            | SnapshotEvent::AccountChanged { .. }
+            | SnapshotEvent::TokenExpiring { .. } => None,
+        }
+    }
+
+    /// One-line description, e.g. "Session window reset (was 87% used)".
+    pub fn describe(&self) -> String {
+        match self {
+            SnapshotEvent::WindowReset {
+                window,
+                previous_percent,
+                ..
+            } => format!(
+                "{} window reset (was {previous_percent:.0}% used)",
+                window.label()
+            ),
+            SnapshotEvent::PlanChanged { from, to } => format!("Plan changed from {from} to {to}"),
+            SnapshotEvent::AccountChanged { from, to } => {
+                format!("Account changed from {from} to {to}")
+            }
+            SnapshotEvent::BigJump {
+                window,
+                from_percent,
+                to_percent,
+            } => format!(
+                "{} usage jumped from {from_percent:.0}% to {to_percent:.0}%",
+                window.label()
+            ),
+            SnapshotEvent::TokenExpiring { expires_at } => format!(
+                "Login expires {} — sign in again to avoid interruption",
+                format_time_left(*expires_at - Utc::now())
+            ),
+        }
+    }
+}
+
+// ============================================================================
+// Diffing
+// ============================================================================
+
+/// Compares two consecutive snapshots of the same provider.
+///
+/// Plan and account changes are only reported when both snapshots name
+/// one, since some fetch strategies don't report identity at all.
+pub fn diff_snapshots(previous: &UsageSnapshot, current: &UsageSnapshot) -> Vec<SnapshotEvent> {
+    let mut events = Vec::new();
+
+    if let (Some(old), Some(new)) = (&previous.identity, &current.identity) {
+        let accounts = old.account_id().zip(new.account_id());
+        let plans = old.plan_name.clone().zip(new.plan_name.clone());
+        match (accounts, plans) {
+            (Some((from, to)), _) if from != to => {
+                // A new account's plan is expected to differ
+                events.push(SnapshotEvent::AccountChanged { from, to });
+            }
+            (_, Some((from, to))) if from != to => {
+                events.push(SnapshotEvent::PlanChanged { from, to });
+            }
+            _ => {}
+        }
+    }
+
+    for &slot in WindowSlot::all() {
+        let (Some(old), Some(new)) = (slot.window(previous), slot.window(current)) else {
+            continue;
+        };
+        let from = old.clamped_used_percent();
+        let to = new.clamped_used_percent();
+
+        if is_reset(old, new) {
+            events.push(SnapshotEvent::WindowReset {
+                window: slot,
+                previous_percent: from,
+                used_percent: to,
+                resets_at: new.resets_at,
+            });
+        } else if to - from > BIG_JUMP_PERCENT {
+            events.push(SnapshotEvent::BigJump {
+                window: slot,
+                from_percent: from,
+                to_percent: to,
+            });
+        }
+    }
+
+    events
+}
+
+/// Warns about a snapshot's login if it expires within
+/// [`LOGIN_EXPIRY_WARNING`] of `now`. Logins that already expired aren't
+/// warned about; fetching with them fails instead.
+pub fn login_expiry(snapshot: &UsageSnapshot, now: DateTime<Utc>) -> Option<SnapshotEvent> {
+    let expires_at = snapshot.identity.as_ref()?.login_expires_at?;
+    (expires_at > now && expires_at - now <= LOGIN_EXPIRY_WARNING)
+        .then_some(SnapshotEvent::TokenExpiring { expires_at })
+}
+
+/// Formats time until something happens, e.g. "in 2 days".
+fn format_time_left(left: Duration) -> String {
+    match (left.num_days(), left.num_hours(), left.num_minutes()) {
+        (days, _, _) if days > 1 => format!("in {days} days"),
+        (1, _, _) => "in 1 day".to_string(),
+        (_, hours, _) if hours > 1 => format!("in {hours} hours"),
+        (_, 1, _) => "in 1 hour".to_string(),
+        (_, _, minutes) if minutes > 0 => format!("in {minutes} minutes"),
+        _ => "now".to_string(),
+    }
+}
+
+/// A window reset if usage dropped and the reset time moved forward, or,
+/// without reset times, if usage dropped by at least
+/// [`RESET_DROP_PERCENT`].
+fn is_reset(old: &UsageWindow, new: &UsageWindow) -> bool {
+    let from = old.clamped_used_percent();
+    let to = new.clamped_used_percent();
+    match (old.resets_at, new.resets_at) {
+        (Some(old_reset), Some(new_reset)) => {
+            to < from && new_reset >= old_reset + RESET_TIME_SLACK
+        }
+        _ => from - to >= RESET_DROP_PERCENT,
+    }
+}
+
+/// Remembers the last snapshot of each provider and diffs new ones
+/// against it.
+#[derive(Debug, Default)]
+pub struct SnapshotDiffer {
+    last: HashMap<ProviderKind, UsageSnapshot>,
+    /// Login expiry each provider was last warned about.
+    warned_expiry: HashMap<ProviderKind, DateTime<Utc>>,
+}
+
+impl SnapshotDiffer {
+    /// Creates a differ with no snapshots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a provider's snapshot and returns what changed since the
+    /// previous one. The first snapshot of a provider reports nothing but
+    /// an expiring login, which is warned about once per expiry.
+    pub fn observe(
+        &mut self,
+        provider: ProviderKind,
+        snapshot: &UsageSnapshot,
+    ) -> Vec<SnapshotEvent> {
+        let mut events = self
+            .last
+            .get(&provider)
+            .map(|previous| diff_snapshots(previous, snapshot))
+            .unwrap_or_default();
+        if let Some(event @ SnapshotEvent::TokenExpiring { expires_at }) =
+            login_expiry(snapshot, Utc::now())
+        {
+            if self.warned_expiry.insert(provider, expires_at) != Some(expires_at) {
+                events.push(event);
+            }
+        }
+        self.last.insert(provider, snapshot.clone());
+        events
+    }
+
+    /// Forgets a provider's last snapshot.
+    pub fn forget(&mut self, provider: ProviderKind) {
+        self.last.remove(&provider);
+        self.warned_expiry.remove(&provider);
+    }
+
+    /// Forgets every provider's last snapshot.
+    pub fn clear(&mut self) {
+        self.last.clear();
+        self.warned_expiry.clear();
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exactobar_core::ProviderIdentity;
+
+    fn snapshot(used: f64, resets_at: Option<DateTime<Utc>>) -> UsageSnapshot {
+        let mut window = UsageWindow::new(used);
+        window.resets_at = resets_at;
+        UsageSnapshot {
+            primary: Some(window),
+            ..UsageSnapshot::new()
+        }
+    }
+
+    fn with_identity(mut snapshot: UsageSnapshot, email: &str, plan: &str) -> UsageSnapshot {
+        let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+        identity.account_email = Some(email.to_string());
+        identity.plan_name = Some(plan.to_string());
+        snapshot.identity = Some(identity);
+        snapshot
+    }
+
+    #[test]
+    fn test_window_reset() {
+        let now = Utc::now();
+        let before = snapshot(87.0, Some(now));
+        let after = snapshot(0.0, Some(now + Duration::hours(5)));
+
+        let events = diff_snapshots(&before, &after);
+        assert_eq!(
+            events,
+            vec![SnapshotEvent::WindowReset {
+                window: WindowSlot::Primary,
+                previous_percent: 87.0,
+                used_percent: 0.0,
+                resets_at: Some(now + Duration::hours(5)),
+            }]
+        );
+        assert_eq!(events[0].describe(), "Session window reset (was 87% used)");
+
+        // Usage aging out of a rolling window isn't a reset
+        let aged = snapshot(60.0, Some(now));
+        assert!(diff_snapshots(&before, &aged).is_empty());
+
+        // Without reset times, only a clear drop counts
+        assert!(diff_snapshots(&snapshot(40.0, None), &snapshot(37.0, None)).is_empty());
+        assert_eq!(
+            diff_snapshots(&snapshot(40.0, None), &snapshot(2.0, None))[0].kind(),
+            SnapshotEventKind::WindowReset
+        );
+    }
+
+    #[test]
+    fn test_big_jump() {
+        let events = diff_snapshots(&snapshot(20.0, None), &snapshot(35.0, None));
+        assert_eq!(
+            events,
+            vec![SnapshotEvent::BigJump {
+                window: WindowSlot::Primary,
+                from_percent: 20.0,
+                to_percent: 35.0,
+            }]
+        );
+        assert!(diff_snapshots(&snapshot(20.0, None), &snapshot(30.0, None)).is_empty());
+    }
+
+    #[test]
+    fn test_identity_changes() {
+        let base = snapshot(10.0, None);
+        let pro = with_identity(base.clone(), "me@example.com", "Pro");
+        let max = with_identity(base.clone(), "me@example.com", "Max");
+        let other = with_identity(base.clone(), "work@example.com", "Team");
+
+        assert_eq!(
+            diff_snapshots(&pro, &max),
+            vec![SnapshotEvent::PlanChanged {
+                from: "Pro".to_string(),
+                to: "Max".to_string(),
+            }]
+        );
+        assert_eq!(
+            diff_snapshots(&pro, &other),
+            vec![SnapshotEvent::AccountChanged {
+                from: "me@example.com".to_string(),
+                to: "work@example.com".to_string(),
+            }]
+        );
+        // A snapshot without identity doesn't count as a change
+        assert!(diff_snapshots(&pro, &base).is_empty());
+    }
+
+    #[test]
+    fn test_differ_and_serde() {
+        let mut differ = SnapshotDiffer::new();
+        assert!(
+            differ
+                .observe(ProviderKind::Claude, &snapshot(10.0, None))
+                .is_empty()
+        );
+        let events = differ.observe(ProviderKind::Claude, &snapshot(50.0, None));
+        assert_eq!(events.len(), 1);
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["kind"], "big_jump");
+        assert_eq!(json["window"], "primary");
+
+        // Providers are tracked separately
+        assert!(
+            differ
+                .observe(ProviderKind::Codex, &snapshot(90.0, None))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_login_expiry() {
+        let now = Utc::now();
+        let expiring = |expires_at| {
+            let mut snapshot = snapshot(10.0, None);
+            let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+            identity.login_expires_at = Some(expires_at);
+            snapshot.identity = Some(identity);
+            snapshot
+        };
+
+        let soon = now + Duration::days(2) + Duration::minutes(5);
+        assert_eq!(
+            login_expiry(&expiring(soon), now),
+            Some(SnapshotEvent::TokenExpiring { expires_at: soon })
+        );
+        assert!(login_expiry(&expiring(now + Duration::days(10)), now).is_none());
+        assert!(login_expiry(&expiring(now - Duration::hours(1)), now).is_none());
+        assert!(login_expiry(&snapshot(10.0, None), now).is_none());
+        assert_eq!(format_time_left(soon - now), "in 2 days");
+        assert_eq!(format_time_left(Duration::minutes(90)), "in 1 hour");
+
+        // Warned about on the first snapshot, then once per expiry
+        let mut differ = SnapshotDiffer::new();
+        let events = differ.observe(ProviderKind::Claude, &expiring(soon));
+        assert_eq!(events[0].kind(), SnapshotEventKind::TokenExpiring);
+        assert!(
+            differ
+                .observe(ProviderKind::Claude, &expiring(soon))
+                .is_empty()
+        );
+        let renewed = soon + Duration::hours(1);
+        assert_eq!(
+            differ
+                .observe(ProviderKind::Claude, &expiring(renewed))
+                .len(),
+            1
+        );
+    }
+}
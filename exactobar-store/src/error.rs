@@ -29,6 +29,10 @@ pub enum StoreError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Database error.
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
     /// Configuration error.
     #[error("Configuration error: {0}")]
     Config(String),
@@ -37,6 +41,15 @@ pub enum StoreError {
     #[error("Operation timed out")]
     Timeout,
 
+    /// A file on disk failed validation.
+    #[error("Corrupted file {}: {reason}", path.display())]
+    Corrupted {
+        /// The file that failed to load.
+        path: std::path::PathBuf,
+        /// What was wrong with it.
+        reason: String,
+    },
+
     /// Parse error.
     #[error("Parse error: {0}")]
     Parse(String),
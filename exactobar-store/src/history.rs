@@ -1,13 +1,31 @@
 //! Usage history tracking.
+//!
+//! [`UsageHistory`] keeps recent usage points per provider in memory, plus
+//! annotations marking the [`SnapshotEvent`]s seen between them (window
+//! resets, plan changes...). [`HistoryStore`] keeps every window's usage
+//! for the last [`HISTORY_RETENTION_DAYS`] days in the usage database, for
+//! graphs over longer ranges.
 
-use chrono::{DateTime, Utc};
-use exactobar_core::{ProviderKind, UsageData};
+use chrono::{DateTime, Duration, Utc};
+use exactobar_core::{ProviderKind, UsageData, UsageSnapshot};
+use rusqlite::{Connection, params};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use crate::database::{blocking, database_size, default_database_path, open_database};
+use crate::diff::{SnapshotEvent, SnapshotEventKind, WindowSlot};
+use crate::error::StoreError;
+use crate::persistence::load_json;
 
 /// Maximum number of history entries per provider.
 const MAX_HISTORY_ENTRIES: usize = 1000;
 
+/// Maximum number of annotations per provider.
+const MAX_ANNOTATIONS: usize = 100;
+
 /// A single history entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -29,10 +47,20 @@ impl From<&UsageData> for HistoryEntry {
     }
 }
 
+/// An event marked on a provider's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryAnnotation {
+    /// When the event was seen; matches the entry recorded with it.
+    pub timestamp: DateTime<Utc>,
+    /// What happened.
+    pub event: SnapshotEvent,
+}
+
 /// Tracks usage history for all providers.
 #[derive(Debug, Default)]
 pub struct UsageHistory {
     entries: HashMap<ProviderKind, VecDeque<HistoryEntry>>,
+    annotations: HashMap<ProviderKind, VecDeque<HistoryAnnotation>>,
 }
 
 impl UsageHistory {
@@ -43,20 +71,59 @@ impl UsageHistory {
 
     /// Records a new usage data point.
     pub fn record(&mut self, usage: &UsageData) {
-        let entry = HistoryEntry::from(usage);
-        let entries = self
-            .entries
-            .entry(usage.provider_kind)
-            .or_insert_with(VecDeque::new);
+        push_bounded(
+            self.entries.entry(usage.provider_kind).or_default(),
+            HistoryEntry::from(usage),
+            MAX_HISTORY_ENTRIES,
+        );
+    }
 
-        entries.push_back(entry);
+    /// Records a snapshot's session usage along with the events diffed
+    /// from the previous snapshot. Snapshots without a session window
+    /// only record their events.
+    pub fn record_snapshot(
+        &mut self,
+        provider: ProviderKind,
+        snapshot: &UsageSnapshot,
+        events: &[SnapshotEvent],
+    ) {
+        let timestamp = snapshot.updated_at;
+        if let Some(window) = &snapshot.primary {
+            push_bounded(
+                self.entries.entry(provider).or_default(),
+                HistoryEntry {
+                    timestamp,
+                    value: window.clamped_used_percent(),
+                    unit: "%".to_string(),
+                },
+                MAX_HISTORY_ENTRIES,
+            );
+        }
 
-        // Trim if over limit
-        while entries.len() > MAX_HISTORY_ENTRIES {
-            entries.pop_front();
+        let annotations = self.annotations.entry(provider).or_default();
+        for event in events {
+            push_bounded(
+                annotations,
+                HistoryAnnotation {
+                    timestamp,
+                    event: event.clone(),
+                },
+                MAX_ANNOTATIONS,
+            );
         }
     }
 
+    /// Returns the annotations for a provider, oldest first.
+    pub fn annotations(&self, kind: ProviderKind) -> impl Iterator<Item = &HistoryAnnotation> {
+        self.annotations.get(&kind).into_iter().flatten()
+    }
+
+    /// Returns the window resets seen for a provider, oldest first.
+    pub fn resets(&self, kind: ProviderKind) -> impl Iterator<Item = &HistoryAnnotation> {
+        self.annotations(kind)
+            .filter(|a| a.event.kind() == SnapshotEventKind::WindowReset)
+    }
+
     /// Returns history for a specific provider.
     pub fn get(&self, kind: ProviderKind) -> Option<&VecDeque<HistoryEntry>> {
         self.entries.get(&kind)
@@ -88,10 +155,613 @@ impl UsageHistory {
     /// Clears history for a specific provider.
     pub fn clear_provider(&mut self, kind: ProviderKind) {
         self.entries.remove(&kind);
+        self.annotations.remove(&kind);
     }
 
     /// Clears all history.
     pub fn clear_all(&mut self) {
         self.entries.clear();
+        self.annotations.clear();
+    }
+}
+
+// ============================================================================
+// History Store
+// ============================================================================
+
+/// Days of usage kept by [`HistoryStore`].
+pub const HISTORY_RETENTION_DAYS: i64 = 30;
+
+/// Maximum number of stored points per provider window.
+const MAX_STORED_POINTS: usize = 10_000;
+
+/// Samples closer together than this are only stored if usage moved.
+const MIN_SAMPLE_INTERVAL_MINUTES: i64 = 5;
+
+/// Usage change, in percentage points, that is always stored.
+const MIN_SAMPLE_CHANGE_PERCENT: f64 = 1.0;
+
+/// A window's usage at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// When the usage was fetched.
+    pub timestamp: DateTime<Utc>,
+    /// Percent of the window used (0-100).
+    pub used_percent: f64,
+}
+
+/// Stored history of one provider.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProviderHistory {
+    /// Points per window, oldest first.
+    #[serde(default)]
+    windows: HashMap<WindowSlot, Vec<HistoryPoint>>,
+    /// Events seen between snapshots, oldest first.
+    #[serde(default)]
+    events: Vec<HistoryAnnotation>,
+}
+
+/// Name of the JSON file history was kept in before the database.
+const LEGACY_HISTORY_FILE: &str = "usage.json";
+
+/// Returns the usage history database of the current profile.
+pub fn default_history_path() -> PathBuf {
+    default_database_path()
+}
+
+/// What [`HistoryStore::compact`] removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compaction {
+    /// Points and events deleted.
+    pub removed: usize,
+    /// Database size before, in bytes.
+    pub size_before: u64,
+    /// Database size after, in bytes.
+    pub size_after: u64,
+}
+
+/// Usage history of every window, persisted for graphs.
+///
+/// History is kept in memory and in a `SQLite` database (see
+/// [`crate::database`]); [`save`](Self::save) only writes what was
+/// recorded since the last save, so several processes can share the
+/// database.
+#[derive(Debug)]
+pub struct HistoryStore {
+    path: PathBuf,
+    providers: HashMap<ProviderKind, ProviderHistory>,
+    /// Recorded but not saved yet.
+    pending: HashMap<ProviderKind, ProviderHistory>,
+}
+
+impl HistoryStore {
+    /// Loads history from a database, creating it if missing.
+    ///
+    /// History from a `usage.json` file next to the database, as kept by
+    /// earlier versions, is imported and the file renamed.
+    pub async fn load(path: PathBuf) -> Result<Self, StoreError> {
+        let db_path = path.clone();
+        let providers = blocking(move || read_history(&open_database(&db_path)?)).await?;
+        let mut store = Self {
+            path,
+            providers,
+            pending: HashMap::new(),
+        };
+        store.prune(Utc::now() - Duration::days(HISTORY_RETENTION_DAYS));
+
+        let legacy = store.path.with_file_name(LEGACY_HISTORY_FILE);
+        if legacy != store.path && legacy.exists() {
+            let imported = store.import_json(&legacy).await?;
+            tokio::fs::rename(&legacy, legacy.with_extension("json.imported")).await?;
+            info!(path = %legacy.display(), points = imported, "Imported JSON usage history");
+        }
+        Ok(store)
+    }
+
+    /// Loads the current profile's history.
+    pub async fn load_default() -> Result<Self, StoreError> {
+        Self::load(default_history_path()).await
+    }
+
+    /// Returns the database this history is saved to.
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Merges history from a JSON file in the format earlier versions kept
+    /// and saves it. Returns the number of points imported.
+    pub async fn import_json(&mut self, path: &Path) -> Result<usize, StoreError> {
+        let imported: HashMap<ProviderKind, ProviderHistory> = load_json(path).await?;
+        let mut count = 0;
+        for (provider, history) in &imported {
+            let merged = self.providers.entry(*provider).or_default();
+            for (slot, points) in &history.windows {
+                count += points.len();
+                let merged = merged.windows.entry(*slot).or_default();
+                merged.extend(points);
+                merged.sort_by_key(|p| p.timestamp);
+                merged.dedup_by_key(|p| p.timestamp);
+            }
+            merged.events.extend(history.events.iter().cloned());
+            merged.events.sort_by_key(|a| a.timestamp);
+
+            let pending = self.pending.entry(*provider).or_default();
+            for (slot, points) in &history.windows {
+                pending.windows.entry(*slot).or_default().extend(points);
+            }
+            pending.events.extend(history.events.iter().cloned());
+        }
+        self.prune(Utc::now() - Duration::days(HISTORY_RETENTION_DAYS));
+        self.save().await?;
+        Ok(count)
+    }
+
+    /// Records every window of a snapshot along with the events diffed from
+    /// the previous snapshot, and drops points older than the retention.
+    ///
+    /// Samples within a few minutes of the last stored one are skipped
+    /// unless usage moved, so frequent refreshes don't bloat the database.
+    pub fn record(
+        &mut self,
+        provider: ProviderKind,
+        snapshot: &UsageSnapshot,
+        events: &[SnapshotEvent],
+    ) {
+        let timestamp = snapshot.updated_at;
+        let history = self.providers.entry(provider).or_default();
+        let pending = self.pending.entry(provider).or_default();
+
+        for &slot in WindowSlot::all() {
+            let Some(window) = slot.window(snapshot) else {
+                continue;
+            };
+            let point = HistoryPoint {
+                timestamp,
+                used_percent: window.clamped_used_percent(),
+            };
+            let points = history.windows.entry(slot).or_default();
+            let skip = points.last().is_some_and(|last| {
+                point.timestamp - last.timestamp < Duration::minutes(MIN_SAMPLE_INTERVAL_MINUTES)
+                    && (point.used_percent - last.used_percent).abs() < MIN_SAMPLE_CHANGE_PERCENT
+            });
+            if !skip {
+                points.push(point);
+                pending.windows.entry(slot).or_default().push(point);
+            }
+        }
+        let annotations = events.iter().map(|event| HistoryAnnotation {
+            timestamp,
+            event: event.clone(),
+        });
+        history.events.extend(annotations.clone());
+        pending.events.extend(annotations);
+
+        self.prune(timestamp - Duration::days(HISTORY_RETENTION_DAYS));
+    }
+
+    /// Drops everything recorded before `cutoff` from memory.
+    fn prune(&mut self, cutoff: DateTime<Utc>) {
+        for history in self.providers.values_mut() {
+            for points in history.windows.values_mut() {
+                let old = points
+                    .partition_point(|p| p.timestamp < cutoff)
+                    .max(points.len().saturating_sub(MAX_STORED_POINTS));
+                points.drain(..old);
+            }
+            history.windows.retain(|_, points| !points.is_empty());
+            history.events.retain(|a| a.timestamp >= cutoff);
+        }
+        self.providers
+            .retain(|_, h| !h.windows.is_empty() || !h.events.is_empty());
+    }
+
+    /// Returns the providers with stored history.
+    pub fn providers(&self) -> Vec<ProviderKind> {
+        let mut providers: Vec<_> = self.providers.keys().copied().collect();
+        providers.sort_by_key(ProviderKind::cli_name);
+        providers
+    }
+
+    /// Returns the windows with stored points for a provider.
+    pub fn windows(&self, provider: ProviderKind) -> Vec<WindowSlot> {
+        let Some(history) = self.providers.get(&provider) else {
+            return Vec::new();
+        };
+        WindowSlot::all()
+            .iter()
+            .copied()
+            .filter(|slot| history.windows.contains_key(slot))
+            .collect()
+    }
+
+    /// Returns a window's points since `since`, oldest first.
+    pub fn series(
+        &self,
+        provider: ProviderKind,
+        slot: WindowSlot,
+        since: DateTime<Utc>,
+    ) -> &[HistoryPoint] {
+        let Some(points) = self
+            .providers
+            .get(&provider)
+            .and_then(|h| h.windows.get(&slot))
+        else {
+            return &[];
+        };
+        &points[points.partition_point(|p| p.timestamp < since)..]
+    }
+
+    /// Returns a provider's events since `since`, oldest first.
+    pub fn events(
+        &self,
+        provider: ProviderKind,
+        since: DateTime<Utc>,
+    ) -> impl Iterator<Item = &HistoryAnnotation> {
+        self.providers
+            .get(&provider)
+            .into_iter()
+            .flat_map(|h| h.events.iter())
+            .filter(move |a| a.timestamp >= since)
+    }
+
+    /// Saves what was recorded since the last save, and deletes points
+    /// past the retention from the database.
+    pub async fn save(&mut self) -> Result<(), StoreError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.path.clone();
+        let pending = std::mem::take(&mut self.pending);
+        let cutoff = Utc::now() - Duration::days(HISTORY_RETENTION_DAYS);
+        let (pending, result) = blocking(move || {
+            let result = open_database(&path)
+                .and_then(|mut conn| write_history(&mut conn, &pending, cutoff));
+            Ok((pending, result))
+        })
+        .await?;
+
+        if let Err(e) = result {
+            // Keep the points for the next save
+            self.pending = pending;
+            return Err(e);
+        }
+        debug!(path = %self.path.display(), "Usage history saved");
+        Ok(())
+    }
+
+    /// Saves, then deletes everything past the retention or over the
+    /// per-window cap from the database and shrinks the file.
+    pub async fn compact(&mut self) -> Result<Compaction, StoreError> {
+        self.save().await?;
+
+        let path = self.path.clone();
+        let cutoff = Utc::now() - Duration::days(HISTORY_RETENTION_DAYS);
+        let (compaction, providers) = blocking(move || {
+            let size_before = database_size(&path);
+            let mut conn = open_database(&path)?;
+            let removed = compact_history(&mut conn, cutoff)?;
+            conn.execute_batch("VACUUM")?;
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            let providers = read_history(&conn)?;
+            drop(conn);
+            let compaction = Compaction {
+                removed,
+                size_before,
+                size_after: database_size(&path),
+            };
+            Ok((compaction, providers))
+        })
+        .await?;
+
+        self.providers = providers;
+        self.prune(cutoff);
+        info!(
+            path = %self.path.display(),
+            removed = compaction.removed,
+            "Usage history compacted"
+        );
+        Ok(compaction)
+    }
+}
+
+/// Name a provider or window is stored under, as in the JSON files.
+fn column_name<T: Serialize>(value: &T) -> Result<String, StoreError> {
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::String(name) => name,
+        other => other.to_string(),
+    })
+}
+
+/// Parses a stored name; `None` for names this build doesn't know.
+fn from_column<T: DeserializeOwned>(name: String) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(name)).ok()
+}
+
+/// Timestamps are stored as nanoseconds since the epoch, so they read back
+/// exactly; that covers dates up to 2262.
+fn time_column(timestamp: DateTime<Utc>) -> i64 {
+    timestamp.timestamp_nanos_opt().unwrap_or(i64::MAX)
+}
+
+/// Reads every stored point and event, oldest first.
+fn read_history(conn: &Connection) -> Result<HashMap<ProviderKind, ProviderHistory>, StoreError> {
+    let mut providers: HashMap<ProviderKind, ProviderHistory> = HashMap::new();
+
+    let mut stmt = conn.prepare(
+        "SELECT provider, slot, timestamp, used_percent FROM points
+         ORDER BY provider, slot, timestamp",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, f64>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (provider, slot, timestamp, used_percent) = row?;
+        // Skip rows written by a newer build with providers we don't know
+        let (Some(provider), Some(slot)) = (from_column(provider), from_column(slot)) else {
+            continue;
+        };
+        let timestamp = DateTime::from_timestamp_nanos(timestamp);
+        providers
+            .entry(provider)
+            .or_default()
+            .windows
+            .entry(slot)
+            .or_default()
+            .push(HistoryPoint {
+                timestamp,
+                used_percent,
+            });
+    }
+
+    let mut stmt =
+        conn.prepare("SELECT provider, timestamp, event FROM events ORDER BY timestamp, id")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (provider, timestamp, event) = row?;
+        let (Some(provider), Ok(event)) = (from_column(provider), serde_json::from_str(&event))
+        else {
+            continue;
+        };
+        let timestamp = DateTime::from_timestamp_nanos(timestamp);
+        providers
+            .entry(provider)
+            .or_default()
+            .events
+            .push(HistoryAnnotation { timestamp, event });
+    }
+
+    Ok(providers)
+}
+
+/// Inserts recorded history and deletes rows from before `cutoff`.
+fn write_history(
+    conn: &mut Connection,
+    pending: &HashMap<ProviderKind, ProviderHistory>,
+    cutoff: DateTime<Utc>,
+) -> Result<(), StoreError> {
+    let tx = conn.transaction()?;
+    {
+        let mut insert_point = tx.prepare(
+            "INSERT OR REPLACE INTO points (provider, slot, timestamp, used_percent)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        let mut insert_event =
+            tx.prepare("INSERT INTO events (provider, timestamp, event) VALUES (?1, ?2, ?3)")?;
+
+        for (provider, history) in pending {
+            let provider = column_name(provider)?;
+            for (slot, points) in &history.windows {
+                let slot = column_name(slot)?;
+                for point in points {
+                    insert_point.execute(params![
+                        provider,
+                        slot,
+                        time_column(point.timestamp),
+                        point.used_percent
+                    ])?;
+                }
+            }
+            for annotation in &history.events {
+                insert_event.execute(params![
+                    provider,
+                    time_column(annotation.timestamp),
+                    serde_json::to_string(&annotation.event)?
+                ])?;
+            }
+        }
+    }
+    let cutoff = time_column(cutoff);
+    tx.execute("DELETE FROM points WHERE timestamp < ?1", [cutoff])?;
+    tx.execute("DELETE FROM events WHERE timestamp < ?1", [cutoff])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Deletes rows from before `cutoff` and points over the per-window cap.
+/// Returns the number of rows deleted.
+fn compact_history(conn: &mut Connection, cutoff: DateTime<Utc>) -> Result<usize, StoreError> {
+    let tx = conn.transaction()?;
+    let cutoff = time_column(cutoff);
+    let mut removed = tx.execute("DELETE FROM points WHERE timestamp < ?1", [cutoff])?;
+    removed += tx.execute("DELETE FROM events WHERE timestamp < ?1", [cutoff])?;
+    removed += tx.execute(
+        "DELETE FROM points WHERE (provider, slot, timestamp) IN (
+            SELECT provider, slot, timestamp FROM (
+                SELECT provider, slot, timestamp, ROW_NUMBER() OVER (
+                    PARTITION BY provider, slot ORDER BY timestamp DESC
+                ) AS n FROM points
+            ) WHERE n > ?1
+        )",
+        [MAX_STORED_POINTS],
+    )?;
+    tx.commit()?;
+    Ok(removed)
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, max: usize) {
+    queue.push_back(item);
+    while queue.len() > max {
+        queue.pop_front();
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{SnapshotDiffer, WindowSlot};
+    use exactobar_core::UsageWindow;
+
+    fn snapshot(used: f64) -> UsageSnapshot {
+        UsageSnapshot {
+            primary: Some(UsageWindow::new(used)),
+            ..UsageSnapshot::new()
+        }
+    }
+
+    #[test]
+    fn test_record_snapshot_with_reset() {
+        let mut history = UsageHistory::new();
+        let mut differ = SnapshotDiffer::new();
+
+        for used in [40.0, 85.0, 0.0] {
+            let snapshot = snapshot(used);
+            let events = differ.observe(ProviderKind::Claude, &snapshot);
+            history.record_snapshot(ProviderKind::Claude, &snapshot, &events);
+        }
+
+        let values: Vec<f64> = history
+            .get(ProviderKind::Claude)
+            .unwrap()
+            .iter()
+            .map(|e| e.value)
+            .collect();
+        assert_eq!(values, vec![40.0, 85.0, 0.0]);
+
+        // 40 -> 85 is a big jump, 85 -> 0 a reset
+        assert_eq!(history.annotations(ProviderKind::Claude).count(), 2);
+        let resets: Vec<_> = history.resets(ProviderKind::Claude).collect();
+        assert_eq!(resets.len(), 1);
+        assert_eq!(resets[0].event.window(), Some(WindowSlot::Primary));
+        assert_eq!(
+            resets[0].timestamp,
+            history.latest(ProviderKind::Claude).unwrap().timestamp
+        );
+
+        history.clear_provider(ProviderKind::Claude);
+        assert_eq!(history.resets(ProviderKind::Claude).count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_history_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history").join("usage.db");
+        let mut store = HistoryStore::load(path.clone()).await.unwrap();
+        let mut differ = SnapshotDiffer::new();
+
+        let start = Utc::now() - Duration::days(HISTORY_RETENTION_DAYS + 1);
+        let recent_start = Utc::now() - Duration::days(2);
+        let samples = [
+            (start, 10.0),
+            (recent_start, 40.0),
+            // Too soon after the last sample and barely moved: skipped
+            (recent_start + Duration::minutes(1), 40.5),
+            (recent_start + Duration::minutes(2), 90.0),
+            (recent_start + Duration::days(1), 0.0),
+        ];
+        for (timestamp, used) in samples {
+            let snapshot = UsageSnapshot {
+                updated_at: timestamp,
+                ..snapshot(used)
+            };
+            let events = differ.observe(ProviderKind::Claude, &snapshot);
+            store.record(ProviderKind::Claude, &snapshot, &events);
+        }
+        store.save().await.unwrap();
+
+        let loaded = HistoryStore::load(path).await.unwrap();
+        assert_eq!(loaded.providers(), vec![ProviderKind::Claude]);
+        assert_eq!(
+            loaded.windows(ProviderKind::Claude),
+            vec![WindowSlot::Primary]
+        );
+
+        // The first sample is past the retention
+        let all = loaded.series(ProviderKind::Claude, WindowSlot::Primary, start);
+        let values: Vec<f64> = all.iter().map(|p| p.used_percent).collect();
+        assert_eq!(values, vec![40.0, 90.0, 0.0]);
+
+        let recent = loaded.series(
+            ProviderKind::Claude,
+            WindowSlot::Primary,
+            recent_start + Duration::days(1),
+        );
+        assert_eq!(recent.len(), 1);
+        assert!(
+            loaded
+                .series(ProviderKind::Codex, WindowSlot::Primary, start)
+                .is_empty()
+        );
+        assert_eq!(
+            loaded
+                .events(ProviderKind::Claude, start)
+                .filter(|a| a.event.kind() == SnapshotEventKind::WindowReset)
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_store_imports_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join("usage.json");
+        let timestamp = Utc::now() - Duration::hours(3);
+        let json = serde_json::json!({
+            "claude": {
+                "windows": {"primary": [{"timestamp": timestamp, "used_percent": 42.0}]},
+                "events": []
+            }
+        });
+        crate::persistence::save_json(&legacy, &json).await.unwrap();
+
+        let path = dir.path().join("usage.db");
+        let store = HistoryStore::load(path.clone()).await.unwrap();
+        assert!(!legacy.exists());
+        assert!(dir.path().join("usage.json.imported").exists());
+        assert_eq!(
+            store.series(ProviderKind::Claude, WindowSlot::Primary, timestamp),
+            [HistoryPoint {
+                timestamp,
+                used_percent: 42.0
+            }]
+        );
+
+        // Saved to the database, not only loaded
+        let mut reloaded = HistoryStore::load(path).await.unwrap();
+        assert_eq!(reloaded.providers(), vec![ProviderKind::Claude]);
+
+        let compaction = reloaded.compact().await.unwrap();
+        assert_eq!(compaction.removed, 0);
+        assert_eq!(
+            reloaded
+                .series(ProviderKind::Claude, WindowSlot::Primary, timestamp)
+                .len(),
+            1
+        );
     }
 }
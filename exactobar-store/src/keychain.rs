@@ -26,15 +26,38 @@
 //! // Delete an API key
 //! keychain::delete_api_key("synthetic")?;
 //! ```
+//!
+//! ## Auditing
+//!
+//! [`stored_entries`] lists the keys `ExactoBar` has stored, with when each
+//! was last changed where the platform tells (macOS), so old keys can be
+//! found and rotated.
+//!
+//! ## Password Managers
+//!
+//! A key can be read from 1Password or Bitwarden instead (see
+//! [`crate::secrets`]); [`get_api_key`] and [`has_api_key`] then never
+//! look at the keychain for it.
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use keyring::Entry;
-use tracing::debug;
+use tracing::{debug, warn};
+
+use crate::secrets::secret_backend;
 
 /// Service name prefix for `ExactoBar` credentials.
 const SERVICE_PREFIX: &str = "ExactoBar";
 
+/// Name the companion extension's bearer token is stored under.
+///
+/// It isn't a provider key, so it's not in [`providers::ALL`] and the key
+/// audit doesn't list it.
+pub const COMPANION_TOKEN: &str = "companion";
+
 /// Common provider names for API keys.
 pub mod providers {
+    use exactobar_core::ProviderKind;
+
     /// Synthetic.new provider.
     pub const SYNTHETIC: &str = "synthetic";
     /// z.ai provider.
@@ -43,6 +66,52 @@ pub mod providers {
     pub const CODEX: &str = "codex";
     /// Google Gemini provider.
     pub const GEMINI: &str = "gemini";
+    /// Hugging Face Inference provider.
+    pub const HUGGINGFACE: &str = "huggingface";
+    /// Replicate provider.
+    pub const REPLICATE: &str = "replicate";
+    /// Cursor Admin API key, for team usage.
+    pub const CURSOR_ADMIN: &str = "cursor_admin";
+    /// Anthropic Admin API key, for team usage.
+    pub const ANTHROPIC_ADMIN: &str = "anthropic_admin";
+
+    /// Every provider name a key may be stored under.
+    pub const ALL: &[&str] = &[
+        SYNTHETIC,
+        ZAI,
+        CODEX,
+        GEMINI,
+        HUGGINGFACE,
+        REPLICATE,
+        CURSOR_ADMIN,
+        ANTHROPIC_ADMIN,
+    ];
+
+    /// The names a provider's keys are stored under.
+    pub fn for_provider(provider: ProviderKind) -> &'static [&'static str] {
+        match provider {
+            ProviderKind::Synthetic => &[SYNTHETIC],
+            ProviderKind::Zai => &[ZAI],
+            ProviderKind::Codex => &[CODEX],
+            ProviderKind::Gemini => &[GEMINI],
+            ProviderKind::HuggingFace => &[HUGGINGFACE],
+            ProviderKind::Replicate => &[REPLICATE],
+            ProviderKind::Cursor => &[CURSOR_ADMIN],
+            ProviderKind::Claude => &[ANTHROPIC_ADMIN],
+            _ => &[],
+        }
+    }
+}
+
+/// An API key stored in the keychain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredKey {
+    /// Provider identifier, one of [`providers::ALL`].
+    pub provider: &'static str,
+    /// Keychain service the key is stored under.
+    pub service: String,
+    /// When the key was last changed, if the platform reports it.
+    pub modified_at: Option<DateTime<Utc>>,
 }
 
 /// Store an API key in the system keychain.
@@ -74,9 +143,11 @@ pub fn store_api_key(provider: &str, api_key: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Retrieve an API key from the system keychain.
+/// Retrieve an API key from the system keychain, or from the password
+/// manager set for it.
 ///
 /// This uses the global keychain cache to avoid multiple password prompts.
+/// Keys from a password manager aren't cached; its CLI runs every time.
 ///
 /// # Arguments
 /// * `provider` - Provider identifier (e.g., "synthetic", "zai", "codex")
@@ -92,6 +163,13 @@ pub fn store_api_key(provider: &str, api_key: &str) -> Result<(), String> {
 /// }
 /// ```
 pub fn get_api_key(provider: &str) -> Option<String> {
+    if let Some(backend) = secret_backend(provider) {
+        return backend
+            .read()
+            .map_err(|e| warn!(provider = provider, error = %e, "Failed to read API key"))
+            .ok();
+    }
+
     let service = format!("{SERVICE_PREFIX}-{provider}");
 
     // Use the cached getter to avoid multiple keychain prompts
@@ -136,7 +214,57 @@ pub fn delete_api_key(provider: &str) -> Result<(), String> {
     result
 }
 
-/// Check if an API key exists in the system keychain.
+/// Lists the API keys stored for [`providers::ALL`].
+pub fn stored_entries() -> Vec<StoredKey> {
+    providers::ALL
+        .iter()
+        .filter(|provider| in_keychain(provider))
+        .map(|&provider| {
+            let service = format!("{SERVICE_PREFIX}-{provider}");
+            StoredKey {
+                provider,
+                modified_at: modified_at(&service),
+                service,
+            }
+        })
+        .collect()
+}
+
+/// When a key was last changed, from its keychain attributes. Reading
+/// them doesn't read the secret, so it never prompts.
+#[cfg(target_os = "macos")]
+fn modified_at(service: &str) -> Option<DateTime<Utc>> {
+    let output = std::process::Command::new("security")
+        .args(["find-generic-password", "-s", service, "-a", "api_key"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_modified_date(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn modified_at(_service: &str) -> Option<DateTime<Utc>> {
+    None
+}
+
+/// Parses the modification date from `security find-generic-password`
+/// output, e.g. `"mdat"<timedate>=0x... "20240115103000Z\000"`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_modified_date(output: &str) -> Option<DateTime<Utc>> {
+    let line = output
+        .lines()
+        .find(|line| line.trim_start().starts_with("\"mdat\""))?;
+    let quoted = line.rsplit_once(" \"")?.1;
+    let stamp = quoted.get(..14)?;
+    NaiveDateTime::parse_from_str(stamp, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Check if an API key exists in the system keychain, or is read from a
+/// password manager (which isn't run to check).
 ///
 /// # Arguments
 /// * `provider` - Provider identifier (e.g., "synthetic", "zai", "codex")
@@ -151,7 +279,13 @@ pub fn delete_api_key(provider: &str) -> Result<(), String> {
 /// }
 /// ```
 pub fn has_api_key(provider: &str) -> bool {
-    get_api_key(provider).is_some()
+    secret_backend(provider).is_some() || in_keychain(provider)
+}
+
+/// Whether a non-empty key is stored in the keychain itself.
+fn in_keychain(provider: &str) -> bool {
+    let service = format!("{SERVICE_PREFIX}-{provider}");
+    exactobar_fetch::host::keychain::get_password_cached(&service, "api_key").is_some()
 }
 
 // ============================================================================
@@ -161,6 +295,7 @@ pub fn has_api_key(provider: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use exactobar_core::ProviderKind;
 
     #[test]
     fn test_service_name_format() {
@@ -178,6 +313,31 @@ mod tests {
         assert_eq!(providers::ZAI, "zai");
         assert_eq!(providers::CODEX, "codex");
         assert_eq!(providers::GEMINI, "gemini");
+        assert_eq!(providers::HUGGINGFACE, "huggingface");
+        assert_eq!(providers::REPLICATE, "replicate");
+        assert_eq!(providers::CURSOR_ADMIN, "cursor_admin");
+        assert_eq!(providers::ANTHROPIC_ADMIN, "anthropic_admin");
+        assert_eq!(providers::ALL.len(), 8);
+        assert_eq!(
+            providers::for_provider(ProviderKind::Claude),
+            [providers::ANTHROPIC_ADMIN]
+        );
+        assert!(providers::for_provider(ProviderKind::Kiro).is_empty());
+    }
+
+    #[test]
+    fn test_parse_modified_date() {
+        let output = r#"keychain: "/Users/me/Library/Keychains/login.keychain-db"
+class: "genp"
+attributes:
+    "acct"<blob>="api_key"
+    "cdat"<timedate>=0x32303233313030313039303030305A00  "20231001090000Z\000"
+    "mdat"<timedate>=0x32303234303131353130333030305A00  "20240115103000Z\000"
+    "svce"<blob>="ExactoBar-replicate"
+"#;
+        let modified = parse_modified_date(output).unwrap();
+        assert_eq!(modified.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+        assert_eq!(parse_modified_date("class: \"genp\""), None);
     }
 
     // Note: Actual keychain operations require platform access and are typically
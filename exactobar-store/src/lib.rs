@@ -14,8 +14,17 @@
 //! This crate provides:
 //!
 //! - **`UsageStore`**: Main state for provider usage data with watch channels
-//! - **`SettingsStore`**: User preferences with persistence
+//! - **`SettingsStore`**: User preferences with persistence, as JSON or TOML
 //! - **Persistence**: File I/O helpers for JSON data
+//! - **Data directory**: Optional custom location for all of the above
+//! - **Profiles**: Named settings sets with their own files on disk
+//! - **Diffing**: Semantic events between consecutive snapshots
+//! - **`UsageHistory`**: Recent usage points annotated with those events
+//! - **`HistoryStore`**: Every window's usage over the last 30 days, in a
+//!   `SQLite` database
+//! - **`NoteStore`**: User notes marking points in time
+//! - **`WidgetUsage`**: Usage summary written for widgets
+//! - **`UiState`**: Window positions and panes remembered across launches
 //!
 //! ## Usage
 //!
@@ -37,22 +46,66 @@
 //! }
 //! ```
 
+pub mod data_dir;
+pub mod database;
+pub mod diff;
 pub mod error;
+pub mod history;
 pub mod keychain;
+pub mod notes;
 pub mod persistence;
+pub mod profiles;
+pub mod secrets;
 pub mod settings_store;
+pub mod ui_state;
 pub mod usage_store;
+pub mod widget;
 
+pub use data_dir::{
+    DATA_DIR_ENV, Relocation, custom_data_dir, data_dir_from_env, relocate_data_dir,
+};
+pub use database::{database_size, default_database_path, open_database};
+pub use diff::{
+    BIG_JUMP_PERCENT, LOGIN_EXPIRY_WARNING, RESET_DROP_PERCENT, SnapshotDiffer, SnapshotEvent,
+    SnapshotEventKind, WindowSlot, diff_snapshots, login_expiry,
+};
 pub use error::StoreError;
-pub use keychain::{delete_api_key, get_api_key, has_api_key, store_api_key};
+pub use history::{
+    Compaction, HISTORY_RETENTION_DAYS, HistoryAnnotation, HistoryEntry, HistoryPoint,
+    HistoryStore, UsageHistory, default_history_path,
+};
+pub use keychain::{
+    COMPANION_TOKEN, StoredKey, delete_api_key, get_api_key, has_api_key, store_api_key,
+    stored_entries,
+};
+pub use notes::{MAX_NOTE_LEN, NoteStore, UsageNote, default_notes_path};
 pub use persistence::{
-    default_cache_dir, default_cache_path, default_config_dir, default_settings_path, load_json,
-    load_json_or_default, save_json,
+    BACKUP_COUNT, Recovery, backup_path, checksum_matches, checksum_path, default_cache_dir,
+    default_cache_path, default_config_dir, default_cost_index_dir, default_history_dir,
+    default_pricing_path, default_settings_path, load_json, load_json_or_default,
+    load_json_with_recovery, load_toml, platform_cache_dir, platform_config_dir, save_json,
+    save_toml,
 };
+pub use profiles::{
+    DEFAULT_PROFILE, MAX_PROFILE_NAME_LEN, active_profile, create_profile, current_profile,
+    find_profile, is_default_profile, list_profiles, profile_cache_dir, profile_cache_path,
+    profile_settings_path, set_active_profile, set_profile_override, validate_profile_name,
+};
+pub use secrets::{SecretBackend, SecretError, secret_backend, set_secret_backends};
 pub use settings_store::{
-    CookieSource, DataSourceMode, LogLevel, ProviderSettings, RefreshCadence, Settings,
-    SettingsStore, ThemeMode,
+    AccountColor, AccountLabel, AlertLevel, AttentionStyle, ColorPalette, CookieSource,
+    CustomProvider, DataSourceMode, FailureTolerance, HookEvent, IconLayout, IconVisibility,
+    LogLevel, MenuDensity, NotificationChannel, NotificationRule, ProviderSettings, RefreshCadence,
+    Settings, SettingsFormat, SettingsStore, SheetsExport, ShellHook, ThemeMode,
+};
+pub use ui_state::{UiState, WindowFrame, default_ui_state_path};
+pub use usage_store::{
+    AccountUsage, AggregatedUsage, CostUsageSnapshot, DailyCost, LOCAL_MACHINE, TokenCategories,
+    UsageStore,
+};
+pub use widget::{
+    WIDGET_APP_GROUP, WIDGET_FORMAT_VERSION, WidgetProvider, WidgetUsage, WidgetWindow,
+    default_widget_path,
 };
-pub use usage_store::{CostUsageSnapshot, DailyCost, UsageStore};
 #[cfg(test)]
 mod persistence_tests;
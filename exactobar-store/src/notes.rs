@@ -0,0 +1,179 @@
+//! Usage notes.
+//!
+//! Notes mark points in time ("big refactor", "batch eval run") so usage
+//! spikes can be explained later. They're kept in `notes.json` in the
+//! history directory and shown on usage graphs alongside history
+//! annotations.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use exactobar_core::ProviderKind;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::StoreError;
+use crate::persistence::{default_history_dir, load_json, save_json};
+
+/// Maximum length of a note, in characters.
+pub const MAX_NOTE_LEN: usize = 200;
+
+/// A note attached to a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageNote {
+    /// When the note applies.
+    pub timestamp: DateTime<Utc>,
+    /// The note itself.
+    pub text: String,
+    /// Provider the note is about (None = all providers).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderKind>,
+}
+
+impl UsageNote {
+    /// Creates a note for now.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            text: text.into(),
+            provider: None,
+        }
+    }
+
+    /// Returns true if the note should show for a provider.
+    pub fn applies_to(&self, provider: ProviderKind) -> bool {
+        self.provider.is_none_or(|p| p == provider)
+    }
+}
+
+/// Returns the notes file of the current profile.
+pub fn default_notes_path() -> PathBuf {
+    default_history_dir().join("notes.json")
+}
+
+/// Notes loaded from disk, oldest first.
+#[derive(Debug)]
+pub struct NoteStore {
+    path: PathBuf,
+    notes: Vec<UsageNote>,
+}
+
+impl NoteStore {
+    /// Loads notes from a file; a missing file has no notes.
+    pub async fn load(path: PathBuf) -> Result<Self, StoreError> {
+        let mut notes: Vec<UsageNote> = if path.exists() {
+            load_json(&path).await?
+        } else {
+            Vec::new()
+        };
+        notes.sort_by_key(|n| n.timestamp);
+        Ok(Self { path, notes })
+    }
+
+    /// Loads the current profile's notes.
+    pub async fn load_default() -> Result<Self, StoreError> {
+        Self::load(default_notes_path()).await
+    }
+
+    /// Returns every note, oldest first.
+    pub fn notes(&self) -> &[UsageNote] {
+        &self.notes
+    }
+
+    /// Returns the notes in a time range, oldest first.
+    pub fn range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&UsageNote> {
+        self.notes
+            .iter()
+            .filter(|n| n.timestamp >= start && n.timestamp <= end)
+            .collect()
+    }
+
+    /// Adds a note, keeping notes in time order.
+    ///
+    /// Returns `StoreError::Config` if the text is empty or longer than
+    /// [`MAX_NOTE_LEN`].
+    pub fn add(&mut self, mut note: UsageNote) -> Result<(), StoreError> {
+        note.text = note.text.trim().to_string();
+        if note.text.is_empty() {
+            return Err(StoreError::Config("note is empty".to_string()));
+        }
+        if note.text.chars().count() > MAX_NOTE_LEN {
+            return Err(StoreError::Config(format!(
+                "note is longer than {MAX_NOTE_LEN} characters"
+            )));
+        }
+
+        let index = self
+            .notes
+            .partition_point(|n| n.timestamp <= note.timestamp);
+        self.notes.insert(index, note);
+        Ok(())
+    }
+
+    /// Removes the note at `index` (as returned by [`NoteStore::notes`]).
+    pub fn remove(&mut self, index: usize) -> Option<UsageNote> {
+        (index < self.notes.len()).then(|| self.notes.remove(index))
+    }
+
+    /// Saves the notes.
+    pub async fn save(&self) -> Result<(), StoreError> {
+        save_json(&self.path, &self.notes).await?;
+        debug!(path = %self.path.display(), count = self.notes.len(), "Notes saved");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_note_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history").join("notes.json");
+
+        let mut store = NoteStore::load(path.clone()).await.unwrap();
+        assert!(store.notes().is_empty());
+
+        let now = Utc::now();
+        store.add(UsageNote::new("  ran evals ")).unwrap();
+        store
+            .add(UsageNote {
+                timestamp: now - Duration::hours(2),
+                text: "big refactor".to_string(),
+                provider: Some(ProviderKind::Claude),
+            })
+            .unwrap();
+        assert!(store.add(UsageNote::new("   ")).is_err());
+        assert!(
+            store
+                .add(UsageNote::new("x".repeat(MAX_NOTE_LEN + 1)))
+                .is_err()
+        );
+        store.save().await.unwrap();
+
+        let loaded = NoteStore::load(path).await.unwrap();
+        let texts: Vec<_> = loaded.notes().iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, vec!["big refactor", "ran evals"]);
+        assert!(loaded.notes()[0].applies_to(ProviderKind::Claude));
+        assert!(!loaded.notes()[0].applies_to(ProviderKind::Codex));
+        assert!(loaded.notes()[1].applies_to(ProviderKind::Codex));
+        assert_eq!(loaded.range(now - Duration::hours(1), Utc::now()).len(), 1);
+    }
+
+    #[test]
+    fn test_remove_note() {
+        let mut store = NoteStore {
+            path: PathBuf::from("notes.json"),
+            notes: vec![],
+        };
+        store.add(UsageNote::new("one")).unwrap();
+        assert!(store.remove(1).is_none());
+        assert_eq!(store.remove(0).unwrap().text, "one");
+    }
+}
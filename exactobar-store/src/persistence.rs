@@ -4,20 +4,36 @@
 
 use serde::{Serialize, de::DeserializeOwned};
 use std::path::{Path, PathBuf};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
+use crate::data_dir::{custom_data_dir, data_cache_dir};
 use crate::error::StoreError;
+use crate::profiles::{
+    current_profile, profile_cache_dir, profile_cache_path, profile_settings_path,
+};
 
 // ============================================================================
 // Default Paths
 // ============================================================================
 
-/// Returns the default configuration directory.
+/// Returns the configuration directory: the custom data directory if one
+/// is set, otherwise the platform's.
+pub fn default_config_dir() -> PathBuf {
+    custom_data_dir().unwrap_or_else(platform_config_dir)
+}
+
+/// Returns the cache directory: `cache` in the custom data directory if
+/// one is set, otherwise the platform's.
+pub fn default_cache_dir() -> PathBuf {
+    custom_data_dir().map_or_else(platform_cache_dir, |dir| data_cache_dir(&dir))
+}
+
+/// Returns the platform's configuration directory.
 ///
 /// - macOS: `~/Library/Application Support/ExactoBar`
 /// - Linux: `~/.config/exactobar`
 /// - Windows: `%APPDATA%\ExactoBar`
-pub fn default_config_dir() -> PathBuf {
+pub fn platform_config_dir() -> PathBuf {
     #[cfg(target_os = "macos")]
     {
         dirs::home_dir().map_or_else(
@@ -36,12 +52,12 @@ pub fn default_config_dir() -> PathBuf {
     }
 }
 
-/// Returns the default cache directory.
+/// Returns the platform's cache directory.
 ///
 /// - macOS: `~/Library/Caches/ExactoBar`
 /// - Linux: `~/.cache/exactobar`
 /// - Windows: `%LOCALAPPDATA%\ExactoBar\cache`
-pub fn default_cache_dir() -> PathBuf {
+pub fn platform_cache_dir() -> PathBuf {
     #[cfg(target_os = "macos")]
     {
         dirs::home_dir().map_or_else(
@@ -56,14 +72,30 @@ pub fn default_cache_dir() -> PathBuf {
     }
 }
 
-/// Returns the default settings file path.
+/// Returns the settings file path of the current profile.
 pub fn default_settings_path() -> PathBuf {
-    default_config_dir().join("settings.json")
+    profile_settings_path(&current_profile())
+}
+
+/// Returns the default pricing overrides file path.
+pub fn default_pricing_path() -> PathBuf {
+    default_config_dir().join("pricing.toml")
+}
+
+/// Returns the directory for incremental cost log indexes.
+pub fn default_cost_index_dir() -> PathBuf {
+    default_cache_dir().join("cost_index")
+}
+
+/// Returns the history directory of the current profile, holding usage
+/// history and notes.
+pub fn default_history_dir() -> PathBuf {
+    profile_cache_dir(&current_profile()).join("history")
 }
 
-/// Returns the default usage cache file path.
+/// Returns the usage cache file path of the current profile.
 pub fn default_cache_path() -> PathBuf {
-    default_cache_dir().join("usage_cache.json")
+    profile_cache_path(&current_profile())
 }
 
 // ============================================================================
@@ -123,7 +155,7 @@ async fn set_restrictive_dir_permissions(_path: &Path) -> Result<(), StoreError>
 ///
 /// On Unix systems, directories are created with 0o700 permissions
 /// to ensure only the owner can access config files.
-async fn create_secure_parent_dirs(path: &Path) -> Result<(), StoreError> {
+pub(crate) async fn create_secure_parent_dirs(path: &Path) -> Result<(), StoreError> {
     if let Some(parent) = path.parent() {
         if !parent.exists() {
             debug!(path = %parent.display(), "Creating secure directory");
@@ -146,10 +178,85 @@ async fn create_secure_parent_dirs(path: &Path) -> Result<(), StoreError> {
     Ok(())
 }
 
+/// Number of rotating backups kept next to each saved file.
+pub const BACKUP_COUNT: usize = 3;
+
+/// Returns the path of the `n`th backup of a file (1 is the newest).
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    with_suffix(path, &format!("bak{n}"))
+}
+
+/// Returns the path of the checksum written alongside a file.
+pub fn checksum_path(path: &Path) -> PathBuf {
+    with_suffix(path, "sum")
+}
+
+/// Appends `.suffix` to a path's file name (`settings.json` → `settings.json.sum`).
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// FNV-1a 64-bit checksum of `bytes`, as hex.
+///
+/// This guards against torn and truncated writes, not tampering.
+fn checksum(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Writes `bytes` to a temp file, flushes it to disk, and renames it over
+/// `path`, so readers see either the old or the new contents.
+pub(crate) async fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_path = with_suffix(path, "tmp");
+    let mut file = tokio::fs::File::create(&temp_path).await?;
+    file.write_all(bytes).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    tokio::fs::rename(&temp_path, path).await?;
+    Ok(())
+}
+
+/// Shifts the backups of `path` down one slot and copies the current file
+/// into the newest slot. Only a file that passes validation is backed up,
+/// so a corrupt file never pushes out a good backup.
+async fn rotate_backups(path: &Path) -> Result<(), StoreError> {
+    if read_verified(path).await.is_err() {
+        return Ok(());
+    }
+
+    for n in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            tokio::fs::rename(&from, backup_path(path, n + 1)).await?;
+            let from_sum = checksum_path(&from);
+            if from_sum.exists() {
+                tokio::fs::rename(&from_sum, checksum_path(&backup_path(path, n + 1))).await?;
+            }
+        }
+    }
+
+    let newest = backup_path(path, 1);
+    tokio::fs::copy(path, &newest).await?;
+    if checksum_path(path).exists() {
+        tokio::fs::copy(checksum_path(path), checksum_path(&newest)).await?;
+    }
+    set_restrictive_permissions(&newest).await?;
+    Ok(())
+}
+
 /// Saves data to a JSON file with secure permissions.
 ///
-/// Creates parent directories if they don't exist, writes atomically
-/// (via temp file + rename), and sets restrictive permissions on Unix.
+/// Creates parent directories if they don't exist, backs up the previous
+/// file (see [`BACKUP_COUNT`]), writes atomically (via temp file + fsync +
+/// rename) along with a checksum, and sets restrictive permissions on Unix.
 pub async fn save_json<T: Serialize>(path: &Path, data: &T) -> Result<(), StoreError> {
     debug!(path = %path.display(), "Saving JSON file");
 
@@ -159,10 +266,13 @@ pub async fn save_json<T: Serialize>(path: &Path, data: &T) -> Result<(), StoreE
     // Serialize to pretty JSON
     let json = serde_json::to_string_pretty(data)?;
 
-    // Write atomically (write to temp file, then rename)
-    let temp_path = path.with_extension("json.tmp");
-    tokio::fs::write(&temp_path, &json).await?;
-    tokio::fs::rename(&temp_path, path).await?;
+    // Keep the last good version around in case this write is lost
+    rotate_backups(path).await?;
+
+    // Write the file, then its checksum. A crash between the two leaves a
+    // mismatch, which load_json_with_recovery treats as corruption.
+    write_atomic(path, json.as_bytes()).await?;
+    write_atomic(&checksum_path(path), checksum(json.as_bytes()).as_bytes()).await?;
 
     // Set restrictive file permissions (Unix only)
     set_restrictive_permissions(path).await?;
@@ -171,17 +281,112 @@ pub async fn save_json<T: Serialize>(path: &Path, data: &T) -> Result<(), StoreE
     Ok(())
 }
 
+/// Reads a file and checks it against its checksum, if it has one.
+///
+/// Files without a checksum (written by older versions) are accepted.
+async fn read_verified(path: &Path) -> Result<String, StoreError> {
+    let content = tokio::fs::read_to_string(path).await?;
+
+    match tokio::fs::read_to_string(checksum_path(path)).await {
+        Ok(expected) if expected.trim() != checksum(content.as_bytes()) => {
+            Err(StoreError::Corrupted {
+                path: path.to_path_buf(),
+                reason: "checksum mismatch".to_string(),
+            })
+        }
+        Ok(_) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(content),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Returns true if a file matches its checksum, or has none.
+///
+/// A mismatch means the file was torn mid-write or edited by hand; either
+/// way [`load_json_with_recovery`] will replace it with a backup.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read.
+pub async fn checksum_matches(path: &Path) -> Result<bool, StoreError> {
+    match read_verified(path).await {
+        Ok(_) => Ok(true),
+        Err(StoreError::Corrupted { .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
 /// Loads data from a JSON file.
+///
+/// # Errors
+///
+/// Returns `StoreError::Corrupted` if the file doesn't match its checksum
+/// or isn't valid JSON for `T`.
 pub async fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T, StoreError> {
     debug!(path = %path.display(), "Loading JSON file");
 
-    let content = tokio::fs::read_to_string(path).await?;
-    let data = serde_json::from_str(&content)?;
+    let content = read_verified(path).await?;
+    let data = serde_json::from_str(&content).map_err(|e| StoreError::Corrupted {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
 
     debug!(path = %path.display(), "JSON file loaded");
     Ok(data)
 }
 
+/// A corrupt file that was replaced by one of its backups.
+#[derive(Debug)]
+pub struct Recovery {
+    /// The backup that was restored.
+    pub backup: PathBuf,
+    /// Why the original file was rejected.
+    pub error: StoreError,
+}
+
+/// Loads data from a JSON file, restoring the newest valid backup if the
+/// file is corrupt.
+///
+/// The corrupt file is kept as `<name>.corrupt` for inspection. Returns the
+/// data along with a [`Recovery`] describing what happened, if anything.
+///
+/// # Errors
+///
+/// Returns the original error if the file is missing, or if it is corrupt
+/// and no backup is valid either.
+pub async fn load_json_with_recovery<T: DeserializeOwned>(
+    path: &Path,
+) -> Result<(T, Option<Recovery>), StoreError> {
+    let error = match load_json(path).await {
+        Ok(data) => return Ok((data, None)),
+        Err(e @ StoreError::Corrupted { .. }) => e,
+        Err(e) => return Err(e),
+    };
+    warn!(path = %path.display(), error = %error, "File is corrupt, trying backups");
+
+    for n in 1..=BACKUP_COUNT {
+        let backup = backup_path(path, n);
+        if !backup.exists() {
+            continue;
+        }
+        let Ok(data) = load_json(&backup).await else {
+            warn!(backup = %backup.display(), "Backup is corrupt too");
+            continue;
+        };
+
+        tokio::fs::rename(path, with_suffix(path, "corrupt")).await?;
+        tokio::fs::copy(&backup, path).await?;
+        let content = tokio::fs::read(path).await?;
+        write_atomic(&checksum_path(path), checksum(&content).as_bytes()).await?;
+        set_restrictive_permissions(path).await?;
+
+        info!(path = %path.display(), backup = %backup.display(), "Restored from backup");
+        return Ok((data, Some(Recovery { backup, error })));
+    }
+
+    Err(error)
+}
+
 /// Loads data from a JSON file, returning default if not found.
 pub async fn load_json_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
     match load_json(path).await {
@@ -195,6 +400,40 @@ pub async fn load_json_or_default<T: DeserializeOwned + Default>(path: &Path) ->
     }
 }
 
+/// Saves data to a TOML file with secure permissions.
+///
+/// Meant for files kept in version control, like settings in a dotfiles
+/// repo: it's written atomically with restrictive permissions, but without
+/// the backups and checksum [`save_json`] writes next to the file, since
+/// those would flag every hand edit as corruption.
+pub async fn save_toml<T: Serialize>(path: &Path, data: &T) -> Result<(), StoreError> {
+    debug!(path = %path.display(), "Saving TOML file");
+
+    create_secure_parent_dirs(path).await?;
+    let toml = toml::to_string_pretty(data)
+        .map_err(|e| StoreError::Config(format!("can't write {}: {e}", path.display())))?;
+    write_atomic(path, toml.as_bytes()).await?;
+    set_restrictive_permissions(path).await?;
+
+    debug!(path = %path.display(), "TOML file saved securely");
+    Ok(())
+}
+
+/// Loads data from a TOML file.
+///
+/// # Errors
+///
+/// Returns `StoreError::Corrupted` if the file isn't valid TOML for `T`.
+pub async fn load_toml<T: DeserializeOwned>(path: &Path) -> Result<T, StoreError> {
+    debug!(path = %path.display(), "Loading TOML file");
+
+    let content = tokio::fs::read_to_string(path).await?;
+    toml::from_str(&content).map_err(|e| StoreError::Corrupted {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })
+}
+
 /// Ensures a directory exists with secure permissions.
 pub async fn ensure_dir(path: &Path) -> Result<(), StoreError> {
     if !path.exists() {
@@ -231,6 +470,19 @@ mod tests {
         assert!(path.ends_with("settings.json"));
     }
 
+    #[test]
+    fn test_sidecar_paths() {
+        let path = Path::new("/tmp/settings.json");
+        assert_eq!(backup_path(path, 2), Path::new("/tmp/settings.json.bak2"));
+        assert_eq!(checksum_path(path), Path::new("/tmp/settings.json.sum"));
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(b""), "cbf29ce484222325");
+        assert_ne!(checksum(b"{}"), checksum(b"{ }"));
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn test_file_permissions() {
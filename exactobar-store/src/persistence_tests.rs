@@ -5,7 +5,12 @@
 use std::path::PathBuf;
 use tempfile::TempDir;
 
-use crate::persistence::{ensure_dir, load_json, save_json};
+use crate::error::StoreError;
+use crate::persistence::{
+    BACKUP_COUNT, backup_path, checksum_matches, checksum_path, ensure_dir, load_json,
+    load_json_with_recovery, save_json,
+};
+use crate::settings_store::{AccountColor, AccountLabel, SettingsFormat, SettingsStore};
 use crate::settings_store::{DataSourceMode, LogLevel, ProviderSettings, RefreshCadence, Settings};
 use exactobar_core::ProviderKind;
 
@@ -284,6 +289,226 @@ async fn test_atomic_write() {
     assert!(file_path.exists());
 }
 
+// ============================================================================
+// Corruption Recovery Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_truncated_file_fails_checksum() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.json");
+
+    save_json(&file_path, &Settings::default()).await.unwrap();
+    assert!(checksum_path(&file_path).exists());
+
+    // Simulate a torn write that left only part of the file
+    let content = tokio::fs::read_to_string(&file_path).await.unwrap();
+    tokio::fs::write(&file_path, &content[..content.len() / 2])
+        .await
+        .unwrap();
+
+    let result: Result<Settings, _> = load_json(&file_path).await;
+    assert!(matches!(result, Err(StoreError::Corrupted { .. })));
+    assert!(!checksum_matches(&file_path).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_file_without_checksum_still_loads() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("legacy.json");
+    tokio::fs::write(&file_path, r#"{"debug_mode": true}"#)
+        .await
+        .unwrap();
+
+    let loaded: Settings = load_json(&file_path).await.unwrap();
+    assert!(loaded.debug_mode);
+}
+
+#[tokio::test]
+async fn test_backups_rotate() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.json");
+
+    for i in 0..=BACKUP_COUNT + 1 {
+        let data = serde_json::json!({ "generation": i });
+        save_json(&file_path, &data).await.unwrap();
+    }
+
+    // Newest backup holds the generation before the current one
+    let newest: serde_json::Value = load_json(&backup_path(&file_path, 1)).await.unwrap();
+    assert_eq!(newest["generation"], BACKUP_COUNT);
+    let oldest: serde_json::Value = load_json(&backup_path(&file_path, BACKUP_COUNT))
+        .await
+        .unwrap();
+    assert_eq!(oldest["generation"], 1);
+    assert!(!backup_path(&file_path, BACKUP_COUNT + 1).exists());
+}
+
+#[tokio::test]
+async fn test_recovery_restores_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.json");
+
+    let mut settings = Settings {
+        debug_mode: true,
+        ..Default::default()
+    };
+    save_json(&file_path, &settings).await.unwrap();
+    settings.debug_mode = false;
+    save_json(&file_path, &settings).await.unwrap();
+
+    tokio::fs::write(&file_path, "{\"debug_mo").await.unwrap();
+
+    let (loaded, recovery): (Settings, _) = load_json_with_recovery(&file_path).await.unwrap();
+    let recovery = recovery.expect("should report the recovery");
+    assert_eq!(recovery.backup, backup_path(&file_path, 1));
+    assert!(loaded.debug_mode);
+
+    // The restored file is valid again and the corrupt one is kept aside
+    let reloaded: Settings = load_json(&file_path).await.unwrap();
+    assert!(reloaded.debug_mode);
+    assert!(temp_dir.path().join("settings.json.corrupt").exists());
+}
+
+#[tokio::test]
+async fn test_recovery_without_backup_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.json");
+    tokio::fs::write(&file_path, "not json").await.unwrap();
+
+    let result: Result<(Settings, _), _> = load_json_with_recovery(&file_path).await;
+    assert!(matches!(result, Err(StoreError::Corrupted { .. })));
+}
+
+#[tokio::test]
+async fn test_settings_store_reports_corruption() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.json");
+    tokio::fs::write(&file_path, "{").await.unwrap();
+
+    let store = SettingsStore::load(file_path).await.unwrap();
+    assert!(matches!(
+        store.load_issue(),
+        Some(StoreError::Corrupted { .. })
+    ));
+    assert!(store.restored_from().is_none());
+    assert_eq!(
+        store.get().await.refresh_cadence,
+        Settings::default().refresh_cadence
+    );
+}
+
+// ============================================================================
+// TOML Settings Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_toml_settings_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.toml");
+
+    let store = SettingsStore::new(file_path.clone());
+    assert_eq!(store.format(), SettingsFormat::Toml);
+    store
+        .update(|s| {
+            s.refresh_cadence = RefreshCadence::FiveMinutes;
+            s.enabled_providers.insert(ProviderKind::Cursor);
+            s.reset_timezone = Some("Europe/Berlin".to_string());
+            let claude = s.provider_settings.entry(ProviderKind::Claude).or_default();
+            claude.api_key_env = Some("ANTHROPIC_API_KEY".to_string());
+            claude.account_labels.insert(
+                "me@example.com".to_string(),
+                AccountLabel {
+                    name: "Work".to_string(),
+                    color: AccountColor::Blue,
+                },
+            );
+        })
+        .await;
+    store.save().await.unwrap();
+
+    // Written as plain TOML, with nothing alongside it
+    let toml = std::fs::read_to_string(&file_path).unwrap();
+    assert!(
+        toml.contains("refresh_cadence = \"five_minutes\""),
+        "{toml}"
+    );
+    assert!(toml.contains("[provider_settings.claude]"), "{toml}");
+    assert!(!checksum_path(&file_path).exists());
+    assert!(!backup_path(&file_path, 1).exists());
+
+    let loaded = SettingsStore::load(file_path).await.unwrap();
+    assert!(loaded.load_issue().is_none());
+    let settings = loaded.get().await;
+    assert_eq!(settings.refresh_cadence, RefreshCadence::FiveMinutes);
+    assert!(settings.enabled_providers.contains(&ProviderKind::Cursor));
+    assert_eq!(settings.reset_timezone.as_deref(), Some("Europe/Berlin"));
+    let claude = &settings.provider_settings[&ProviderKind::Claude];
+    assert_eq!(claude.api_key_env.as_deref(), Some("ANTHROPIC_API_KEY"));
+    assert_eq!(
+        claude.account_labels["me@example.com"].color,
+        AccountColor::Blue
+    );
+}
+
+#[tokio::test]
+async fn test_saved_settings_are_stable() {
+    let temp_dir = TempDir::new().unwrap();
+
+    for name in ["settings.toml", "settings.json"] {
+        let file_path = temp_dir.path().join(name);
+        let mut settings = Settings {
+            enabled_providers: ProviderKind::all().iter().copied().collect(),
+            ..Default::default()
+        };
+        for provider in ProviderKind::all() {
+            settings
+                .provider_settings
+                .insert(*provider, ProviderSettings::default());
+        }
+
+        let mut saved = Vec::new();
+        for _ in 0..3 {
+            // A fresh store each time, so set and map order differs
+            let store = SettingsStore::new(file_path.clone());
+            store.update(|s| *s = settings.clone()).await;
+            store.save().await.unwrap();
+            saved.push(std::fs::read_to_string(&file_path).unwrap());
+        }
+        assert!(saved.windows(2).all(|w| w[0] == w[1]), "{name} changed");
+    }
+}
+
+#[tokio::test]
+async fn test_toml_settings_hand_edits() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.toml");
+
+    tokio::fs::write(
+        &file_path,
+        "# Synced from dotfiles\nenabled_providers = [\"claude\"]\ndebug_mode = true\n",
+    )
+    .await
+    .unwrap();
+    let store = SettingsStore::load(file_path.clone()).await.unwrap();
+    assert!(store.load_issue().is_none());
+    let settings = store.get().await;
+    assert!(settings.debug_mode);
+    assert_eq!(settings.enabled_providers.len(), 1);
+    assert_eq!(
+        settings.refresh_cadence,
+        Settings::default().refresh_cadence
+    );
+
+    tokio::fs::write(&file_path, "debug_mode = ").await.unwrap();
+    let store = SettingsStore::load(file_path).await.unwrap();
+    assert!(matches!(
+        store.load_issue(),
+        Some(StoreError::Corrupted { .. })
+    ));
+    assert!(!store.get().await.debug_mode);
+}
+
 // ============================================================================
 // Edge Cases
 // ============================================================================
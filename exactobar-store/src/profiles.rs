@@ -0,0 +1,318 @@
+//! Workspace profiles.
+//!
+//! A profile is a named set of settings ("Client A", "Personal") with its
+//! own enabled providers, account labels and thresholds. The default
+//! profile uses the top-level settings file; every other profile lives
+//! under `profiles/<name>/` in the config and cache directories:
+//!
+//! ```text
+//! ~/.config/exactobar/settings.json                    (default)
+//! ~/.config/exactobar/profiles/Client A/settings.json
+//! ~/.cache/exactobar/profiles/Client A/usage_cache.json
+//! ```
+//!
+//! A profile's settings can be `settings.toml` instead, e.g. to keep them
+//! in a dotfiles repo; it's used whenever it exists.
+//!
+//! The active profile is remembered in `active_profile` in the config
+//! directory. A process can override it for its own lifetime with
+//! [`set_profile_override`] (the CLI's `--profile` flag), and
+//! [`default_settings_path`](crate::default_settings_path) and
+//! [`default_cache_path`](crate::default_cache_path) follow whichever
+//! profile is current.
+
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+
+use tracing::{debug, warn};
+
+use crate::error::StoreError;
+use crate::persistence::{default_cache_dir, default_config_dir, save_json};
+use crate::settings_store::Settings;
+
+/// Name of the profile that uses the top-level settings file.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Maximum length of a profile name, in characters.
+pub const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// Directory under the config and cache directories holding profiles.
+const PROFILES_DIR: &str = "profiles";
+
+/// Settings file of a profile, in each format.
+const SETTINGS_FILE: &str = "settings.json";
+const TOML_SETTINGS_FILE: &str = "settings.toml";
+
+/// File in the config directory naming the active profile.
+const ACTIVE_PROFILE_FILE: &str = "active_profile";
+
+/// Profile selected for this process, taking precedence over the active one.
+static PROFILE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+// ============================================================================
+// Names
+// ============================================================================
+
+/// Checks a profile name and returns it trimmed.
+///
+/// Names may contain letters, digits, spaces, `-` and `_`, so they are
+/// safe to use as directory names.
+pub fn validate_profile_name(name: &str) -> Result<String, StoreError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(StoreError::Config("profile name is empty".to_string()));
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(StoreError::Config(format!(
+            "profile name is longer than {MAX_PROFILE_NAME_LEN} characters"
+        )));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|&c| !(c.is_alphanumeric() || matches!(c, ' ' | '-' | '_')))
+    {
+        return Err(StoreError::Config(format!(
+            "profile name '{name}' contains '{c}'; use letters, digits, spaces, '-' or '_'"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Returns true if `name` is the default profile.
+pub fn is_default_profile(name: &str) -> bool {
+    name.trim().eq_ignore_ascii_case(DEFAULT_PROFILE)
+}
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+/// Returns the config directory of a profile.
+pub fn profile_config_dir(name: &str) -> PathBuf {
+    profile_dir(&default_config_dir(), name)
+}
+
+/// Returns the cache directory of a profile.
+pub fn profile_cache_dir(name: &str) -> PathBuf {
+    profile_dir(&default_cache_dir(), name)
+}
+
+/// Returns the settings file of a profile: `settings.toml` if it exists,
+/// otherwise `settings.json`.
+pub fn profile_settings_path(name: &str) -> PathBuf {
+    settings_file_in(&profile_config_dir(name))
+}
+
+fn settings_file_in(dir: &Path) -> PathBuf {
+    let toml = dir.join(TOML_SETTINGS_FILE);
+    if toml.is_file() {
+        toml
+    } else {
+        dir.join(SETTINGS_FILE)
+    }
+}
+
+/// Returns the usage cache file of a profile.
+pub fn profile_cache_path(name: &str) -> PathBuf {
+    profile_cache_dir(name).join("usage_cache.json")
+}
+
+fn profile_dir(root: &Path, name: &str) -> PathBuf {
+    if is_default_profile(name) {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(name.trim())
+    }
+}
+
+// ============================================================================
+// Listing and Creating
+// ============================================================================
+
+/// Returns every profile: the default first, then the others by name.
+pub fn list_profiles() -> Vec<String> {
+    list_profiles_in(&default_config_dir())
+}
+
+fn list_profiles_in(config_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(config_dir.join(PROFILES_DIR))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| settings_file_in(&entry.path()).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| validate_profile_name(name).is_ok() && !is_default_profile(name))
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Finds an existing profile by name, ignoring case.
+pub fn find_profile(name: &str) -> Option<String> {
+    find_profile_in(&default_config_dir(), name)
+}
+
+fn find_profile_in(config_dir: &Path, name: &str) -> Option<String> {
+    let name = name.trim();
+    list_profiles_in(config_dir)
+        .into_iter()
+        .find(|p| p.eq_ignore_ascii_case(name))
+}
+
+/// Creates a profile with default settings and returns its name.
+pub async fn create_profile(name: &str) -> Result<String, StoreError> {
+    create_profile_in(&default_config_dir(), name).await
+}
+
+async fn create_profile_in(config_dir: &Path, name: &str) -> Result<String, StoreError> {
+    let name = validate_profile_name(name)?;
+    if let Some(existing) = find_profile_in(config_dir, &name) {
+        return Err(StoreError::Config(format!(
+            "profile '{existing}' already exists"
+        )));
+    }
+
+    save_json(
+        &profile_dir(config_dir, &name).join(SETTINGS_FILE),
+        &Settings::default(),
+    )
+    .await?;
+    debug!(profile = %name, "Created profile");
+    Ok(name)
+}
+
+// ============================================================================
+// Active Profile
+// ============================================================================
+
+/// Returns the profile this process uses: the override if one is set,
+/// otherwise the active profile.
+pub fn current_profile() -> String {
+    let override_name = PROFILE_OVERRIDE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    override_name.unwrap_or_else(active_profile)
+}
+
+/// Uses `name` for the rest of this process without changing the active
+/// profile. `None` clears the override.
+pub fn set_profile_override(name: Option<&str>) -> Result<(), StoreError> {
+    let name = name.map(validate_profile_name).transpose()?;
+    *PROFILE_OVERRIDE
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = name;
+    Ok(())
+}
+
+/// Returns the active profile, falling back to the default if the one on
+/// record is missing or invalid.
+pub fn active_profile() -> String {
+    active_profile_in(&default_config_dir())
+}
+
+fn active_profile_in(config_dir: &Path) -> String {
+    let Ok(contents) = std::fs::read_to_string(config_dir.join(ACTIVE_PROFILE_FILE)) else {
+        return DEFAULT_PROFILE.to_string();
+    };
+    find_profile_in(config_dir, &contents).unwrap_or_else(|| {
+        warn!(profile = %contents.trim(), "Active profile not found, using default");
+        DEFAULT_PROFILE.to_string()
+    })
+}
+
+/// Makes an existing profile the active one and returns its name.
+pub fn set_active_profile(name: &str) -> Result<String, StoreError> {
+    set_active_profile_in(&default_config_dir(), name)
+}
+
+fn set_active_profile_in(config_dir: &Path, name: &str) -> Result<String, StoreError> {
+    let name = find_profile_in(config_dir, name)
+        .ok_or_else(|| StoreError::Config(format!("profile '{}' not found", name.trim())))?;
+
+    let path = config_dir.join(ACTIVE_PROFILE_FILE);
+    if is_default_profile(&name) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        std::fs::create_dir_all(config_dir)?;
+        std::fs::write(&path, &name)?;
+    }
+    debug!(profile = %name, "Active profile changed");
+    Ok(name)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert_eq!(validate_profile_name("  Client A ").unwrap(), "Client A");
+        assert!(validate_profile_name("client_b-2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+        assert!(validate_profile_name(&"x".repeat(MAX_PROFILE_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_profile_paths() {
+        assert_eq!(
+            profile_settings_path("default"),
+            default_config_dir().join("settings.json")
+        );
+        assert_eq!(
+            profile_cache_path("Client A"),
+            default_cache_dir()
+                .join("profiles")
+                .join("Client A")
+                .join("usage_cache.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_and_activate_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert_eq!(list_profiles_in(root), vec!["default"]);
+        assert_eq!(active_profile_in(root), "default");
+
+        create_profile_in(root, "personal").await.unwrap();
+        create_profile_in(root, "Client A").await.unwrap();
+        assert!(create_profile_in(root, "PERSONAL").await.is_err());
+        assert_eq!(
+            list_profiles_in(root),
+            vec!["default", "Client A", "personal"]
+        );
+
+        assert_eq!(set_active_profile_in(root, "client a").unwrap(), "Client A");
+        assert_eq!(active_profile_in(root), "Client A");
+        assert!(set_active_profile_in(root, "missing").is_err());
+
+        set_active_profile_in(root, "default").unwrap();
+        assert!(!root.join(ACTIVE_PROFILE_FILE).exists());
+        assert_eq!(active_profile_in(root), "default");
+    }
+
+    #[test]
+    fn test_toml_settings_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join(PROFILES_DIR).join("dotfiles");
+        std::fs::create_dir_all(&profile).unwrap();
+        assert_eq!(settings_file_in(&profile), profile.join(SETTINGS_FILE));
+        assert_eq!(list_profiles_in(dir.path()), vec!["default"]);
+
+        std::fs::write(profile.join(TOML_SETTINGS_FILE), "").unwrap();
+        assert_eq!(settings_file_in(&profile), profile.join(TOML_SETTINGS_FILE));
+        assert_eq!(list_profiles_in(dir.path()), vec!["default", "dotfiles"]);
+    }
+}
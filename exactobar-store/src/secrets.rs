@@ -0,0 +1,163 @@
+//! Password manager backends for API keys.
+//!
+//! Teams whose policy keeps API keys out of the OS keychain can have a
+//! provider's keys read from 1Password (`op`) or Bitwarden (`bw`) instead.
+//! The CLI runs each time a key is needed and the key is never written
+//! anywhere, not even to the in-memory keychain cache.
+//!
+//! Backends are chosen per provider (`secret_backend` in its settings) and
+//! set for the whole process with [`set_secret_backends`] when settings
+//! load; [`get_api_key`](crate::get_api_key) reads through them.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+use exactobar_fetch::host::discovery::{find_binary, path_including};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug;
+
+/// Backends by keychain name, set by [`set_secret_backends`].
+static BACKENDS: LazyLock<RwLock<HashMap<String, SecretBackend>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// A password manager a provider's API keys are read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecretBackend {
+    /// 1Password, read with `op read <reference>`.
+    OnePassword {
+        /// Secret reference, e.g. `op://Engineering/OpenAI/credential`.
+        reference: String,
+    },
+    /// Bitwarden, read with `bw get password <item>`. The vault must be
+    /// unlocked, with `BW_SESSION` set where `ExactoBar` runs.
+    Bitwarden {
+        /// Item name or id.
+        item: String,
+    },
+}
+
+/// Why a key couldn't be read from a password manager.
+#[derive(Debug, Error)]
+pub enum SecretError {
+    /// The password manager's CLI isn't installed.
+    #[error("{0} not found; install it or set its path in settings")]
+    NotInstalled(&'static str),
+
+    /// The CLI couldn't be run or reported an error.
+    #[error("{cli} failed: {message}")]
+    Failed {
+        /// The CLI that failed.
+        cli: &'static str,
+        /// What it said.
+        message: String,
+    },
+
+    /// The CLI returned nothing.
+    #[error("{0} returned an empty secret")]
+    Empty(&'static str),
+}
+
+impl SecretBackend {
+    /// The password manager's name, e.g. "1Password".
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecretBackend::OnePassword { .. } => "1Password",
+            SecretBackend::Bitwarden { .. } => "Bitwarden",
+        }
+    }
+
+    /// The CLI the key is read with.
+    pub fn cli(&self) -> &'static str {
+        match self {
+            SecretBackend::OnePassword { .. } => "op",
+            SecretBackend::Bitwarden { .. } => "bw",
+        }
+    }
+
+    /// The secret reference or item name.
+    pub fn reference(&self) -> &str {
+        match self {
+            SecretBackend::OnePassword { reference } => reference,
+            SecretBackend::Bitwarden { item } => item,
+        }
+    }
+
+    fn args(&self) -> Vec<&str> {
+        match self {
+            SecretBackend::OnePassword { reference } => vec!["read", "--no-newline", reference],
+            SecretBackend::Bitwarden { item } => vec!["get", "password", item],
+        }
+    }
+
+    /// Reads the key by running the CLI.
+    pub fn read(&self) -> Result<String, SecretError> {
+        let cli = self.cli();
+        let binary = find_binary(cli).ok_or(SecretError::NotInstalled(cli))?;
+
+        let mut command = Command::new(&binary);
+        command.args(self.args());
+        if let Some(path) = path_including(&binary) {
+            command.env("PATH", path);
+        }
+        let output = command.output().map_err(|e| SecretError::Failed {
+            cli,
+            message: e.to_string(),
+        })?;
+        if !output.status.success() {
+            return Err(SecretError::Failed {
+                cli,
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if secret.is_empty() {
+            return Err(SecretError::Empty(cli));
+        }
+        debug!(cli, "Read API key from password manager");
+        Ok(secret)
+    }
+}
+
+/// Sets the backends keys are read from, by keychain name, for the whole
+/// process. Keys without one are read from the keychain.
+pub fn set_secret_backends(backends: impl IntoIterator<Item = (String, SecretBackend)>) {
+    *BACKENDS.write().unwrap_or_else(PoisonError::into_inner) = backends.into_iter().collect();
+}
+
+/// The backend a key is read from, if it isn't the keychain.
+pub fn secret_backend(name: &str) -> Option<SecretBackend> {
+    BACKENDS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde() {
+        let backend = SecretBackend::OnePassword {
+            reference: "op://Team/OpenAI/credential".to_string(),
+        };
+        let json = serde_json::to_value(&backend).unwrap();
+        assert_eq!(json["kind"], "one_password");
+        assert_eq!(json["reference"], "op://Team/OpenAI/credential");
+
+        let bitwarden: SecretBackend =
+            serde_json::from_str(r#"{"kind": "bitwarden", "item": "Replicate"}"#).unwrap();
+        assert_eq!(bitwarden.cli(), "bw");
+        assert_eq!(bitwarden.args(), ["get", "password", "Replicate"]);
+        assert_eq!(bitwarden.reference(), "Replicate");
+    }
+}
@@ -1,18 +1,36 @@
 //! User preferences store.
 //!
 //! Manages user settings with persistence and change notification.
-
-use exactobar_core::ProviderKind;
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+//!
+//! Settings are saved as JSON (`settings.json`) or, for keeping them in a
+//! dotfiles repo, TOML (`settings.toml`); the format follows the file's
+//! extension. Both are written with sets and maps in sorted order and
+//! nothing time-dependent, so saving unchanged settings leaves the file
+//! unchanged.
+
+use chrono::{DateTime, NaiveTime, Utc};
+use exactobar_core::{ProviderIdentity, ProviderInfo, ProviderKind};
+use exactobar_fetch::host::companion::DEFAULT_COMPANION_PORT;
+use exactobar_fetch::host::discovery::BinarySearch;
+use exactobar_fetch::{
+    Browser, ParseMode, ProcessSandbox, ProviderBudget, RequestHeaders, SourceMode,
+    StrategySettings,
+};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, watch};
 use tracing::{debug, info, warn};
 
+use crate::diff::SnapshotEventKind;
 use crate::error::StoreError;
-use crate::persistence::{default_settings_path, load_json, save_json};
+use crate::keychain::providers;
+use crate::persistence::{
+    default_settings_path, load_json_with_recovery, load_toml, save_json, save_toml,
+};
+use crate::secrets::SecretBackend;
 
 // ============================================================================
 // Settings Types
@@ -27,6 +45,7 @@ pub struct Settings {
     // Core Settings (existing)
     // ========================================================================
     /// Enabled providers.
+    #[serde(serialize_with = "sorted_providers")]
     pub enabled_providers: HashSet<ProviderKind>,
 
     /// Auto-refresh cadence.
@@ -50,12 +69,28 @@ pub struct Settings {
     /// Log level.
     pub log_level: LogLevel,
 
+    /// Replace email addresses with `[EMAIL]` in logs and crash reports.
+    /// Tokens and cookies are always redacted.
+    pub redact_emails_in_logs: bool,
+
+    /// How provider responses are parsed. Strict mode fails on anything
+    /// the parsers don't recognise, for debugging format changes.
+    pub parse_mode: ParseMode,
+
     /// Theme mode preference.
     pub theme_mode: ThemeMode,
 
+    /// Colors used for usage levels and status dots.
+    pub color_palette: ColorPalette,
+
     /// Per-provider settings.
+    #[serde(serialize_with = "sorted_provider_map")]
     pub provider_settings: HashMap<ProviderKind, ProviderSettings>,
 
+    /// Directories searched for provider CLIs that aren't on `PATH`, e.g.
+    /// an nvm or asdf install.
+    pub extra_search_paths: Vec<PathBuf>,
+
     // ========================================================================
     // Display Settings (new from CodexBar)
     // ========================================================================
@@ -65,12 +100,40 @@ pub struct Settings {
     /// Show reset times as absolute clock values instead of countdowns.
     pub reset_times_show_absolute: bool,
 
+    /// Timezone reset times are shown in: an IANA name like "Europe/Berlin"
+    /// or "UTC" (None = system timezone).
+    pub reset_timezone: Option<String>,
+
     /// Use provider branding icons with percentage in menu bar.
     pub menu_bar_shows_brand_icon_with_percent: bool,
 
+    /// What the menu bar icon's usage meter shows.
+    pub icon_layout: IconLayout,
+
+    /// Draw the menu bar icon in white or black for the menu bar's light or
+    /// dark appearance instead of as a template image the system tints.
+    pub adaptive_icon: bool,
+
+    /// Outline the menu bar icon's usage meter.
+    pub icon_outline: bool,
+
+    /// When menu bar icons are shown, by how much quota is left.
+    pub icon_visibility: IconVisibility,
+
+    /// Remaining percent at or below which a provider counts as low for
+    /// `icon_visibility`.
+    pub icon_visibility_threshold: f64,
+
     /// Show provider icons in the in-menu switcher.
     pub switcher_shows_icons: bool,
 
+    /// How much of each provider the menu's All tab shows.
+    pub menu_density: MenuDensity,
+
+    /// Order status items and switcher tabs by remaining quota, most
+    /// constrained first, instead of the fixed order.
+    pub sort_by_usage: bool,
+
     // ========================================================================
     // Feature Toggles (new from CodexBar)
     // ========================================================================
@@ -80,12 +143,62 @@ pub struct Settings {
     /// Show session quota notifications when approaching limits.
     pub session_quota_notifications_enabled: bool,
 
+    /// Send a native notification when a quota window resets.
+    pub reset_notifications_enabled: bool,
+
+    /// During busy calendar events, hold notifications and slow background
+    /// refreshes (macOS Calendar access).
+    pub quiet_during_meetings: bool,
+
+    /// While a macOS Focus is on, hold notifications other than critical
+    /// alerts for `focus_overrides` providers.
+    pub respect_focus: bool,
+
+    /// Providers whose critical alerts are delivered during Focus.
+    pub focus_overrides: Vec<ProviderKind>,
+
+    /// Where quota notifications are delivered, by level and provider.
+    pub notification_rules: Vec<NotificationRule>,
+
+    /// Shell commands run on usage events.
+    pub hooks: Vec<ShellHook>,
+
+    /// How many failed fetches in a row (or how long without a good one)
+    /// before a provider shows as failing and `on_fetch_error` hooks run.
+    /// Providers can override it (`ProviderSettings::failure_tolerance`).
+    pub failure_tolerance: FailureTolerance,
+
+    /// Most requests per hour for all providers together, so polling
+    /// doesn't burn the quota it reports on. Providers can have their own
+    /// limit too (`ProviderSettings::request_budget_per_hour`).
+    pub request_budget_per_hour: Option<u32>,
+
+    /// User-defined providers, fetched by running a command.
+    pub custom_providers: Vec<CustomProvider>,
+
+    /// Google Sheet that daily usage and cost rows are appended to.
+    /// Providers opt in with a tab (`ProviderSettings::sheets_tab`).
+    pub sheets_export: Option<SheetsExport>,
+
     /// Enable provider cost summary from local usage logs.
     pub cost_usage_enabled: bool,
 
+    /// Offer the team leaderboard window, ranking an organization's members
+    /// by usage through admin APIs.
+    pub team_leaderboard_enabled: bool,
+
     /// Enable random blink animation on status icon.
     pub random_blink_enabled: bool,
 
+    /// Animation played when a provider crosses the critical threshold.
+    pub attention_animation: AttentionStyle,
+
+    /// How strongly the attention animation dims the icon (0.0-1.0).
+    pub attention_intensity: f32,
+
+    /// How long the attention animation runs, in seconds.
+    pub attention_duration_secs: u32,
+
     /// Enable Claude web extras (via browser cookies).
     pub claude_web_extras_enabled: bool,
 
@@ -115,6 +228,32 @@ pub struct Settings {
 
     /// Whether provider detection has completed (for first-run experience).
     pub provider_detection_completed: bool,
+
+    // ========================================================================
+    // Companion Extension
+    // ========================================================================
+    /// Run the localhost endpoint the companion browser extension pushes cookies to.
+    ///
+    /// The bearer token it checks is kept in the keychain under
+    /// [`COMPANION_TOKEN`](crate::keychain::COMPANION_TOKEN), not here.
+    pub companion_endpoint_enabled: bool,
+
+    /// Port the companion endpoint listens on (bound to 127.0.0.1 only).
+    pub companion_endpoint_port: u16,
+
+    // ========================================================================
+    // Keychain
+    // ========================================================================
+    /// Browsers whose keychain prompt was denied; we don't prompt for these again.
+    pub keychain_denied_browsers: Vec<Browser>,
+
+    // ========================================================================
+    // Locked Mode
+    // ========================================================================
+    /// Show usage but block credential changes: API keys, cookie sources,
+    /// companion cookie pushes and keychain prompts. Not shown in the
+    /// settings window; set it in the settings file on shared machines.
+    pub locked_mode: bool,
 }
 
 impl Default for Settings {
@@ -133,20 +272,46 @@ impl Default for Settings {
             selected_provider: None,
             debug_mode: false,
             log_level: LogLevel::default(),
+            redact_emails_in_logs: true,
+            parse_mode: ParseMode::default(),
             theme_mode: ThemeMode::Dark,
+            color_palette: ColorPalette::Standard,
             provider_settings: HashMap::new(),
+            extra_search_paths: vec![],
 
             // Display settings - sensible defaults
             usage_bars_show_used: false,
             reset_times_show_absolute: false,
+            reset_timezone: None,
             menu_bar_shows_brand_icon_with_percent: false,
+            icon_layout: IconLayout::DualBars,
+            adaptive_icon: false,
+            icon_outline: false,
+            icon_visibility: IconVisibility::Always,
+            icon_visibility_threshold: 50.0,
             switcher_shows_icons: true,
+            menu_density: MenuDensity::Detailed,
+            sort_by_usage: false,
 
             // Feature toggles - most enabled by default
             status_checks_enabled: true,
             session_quota_notifications_enabled: true,
+            reset_notifications_enabled: false, // Opt-in
+            quiet_during_meetings: false,       // Opt-in - needs Calendar access
+            respect_focus: true,
+            focus_overrides: vec![],
+            notification_rules: vec![NotificationRule::default()],
+            hooks: vec![],
+            failure_tolerance: FailureTolerance::default(),
+            request_budget_per_hour: None,
+            custom_providers: vec![],
+            sheets_export: None,
             cost_usage_enabled: false, // Off by default - requires local logs
+            team_leaderboard_enabled: false, // Off by default - requires an admin key
             random_blink_enabled: false, // Off by default - can be annoying
+            attention_animation: AttentionStyle::Pulse,
+            attention_intensity: 0.7,
+            attention_duration_secs: 10,
             claude_web_extras_enabled: false, // Off by default - requires cookies
             show_optional_credits_and_extra_usage: true,
             openai_web_access_enabled: true,
@@ -159,6 +324,12 @@ impl Default for Settings {
             provider_order: vec![],
             debug_loading_pattern: None,
             provider_detection_completed: false,
+
+            // Companion extension - off by default, opt-in only
+            companion_endpoint_enabled: false,
+            companion_endpoint_port: DEFAULT_COMPANION_PORT,
+            keychain_denied_browsers: vec![],
+            locked_mode: false,
         }
     }
 }
@@ -268,6 +439,196 @@ impl std::fmt::Display for ThemeMode {
     }
 }
 
+/// Colors used for usage levels and status dots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorPalette {
+    /// Green, yellow and red.
+    #[default]
+    Standard,
+    /// Blue, orange and vermillion, told apart with deuteranopia or
+    /// protanopia.
+    ColorblindSafe,
+    /// Saturated colors and stronger tracks.
+    HighContrast,
+}
+
+impl ColorPalette {
+    /// All available palettes.
+    pub fn all() -> &'static [ColorPalette] {
+        &[
+            ColorPalette::Standard,
+            ColorPalette::ColorblindSafe,
+            ColorPalette::HighContrast,
+        ]
+    }
+}
+
+impl std::fmt::Display for ColorPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorPalette::Standard => write!(f, "Standard"),
+            ColorPalette::ColorblindSafe => write!(f, "Colorblind Safe"),
+            ColorPalette::HighContrast => write!(f, "High Contrast"),
+        }
+    }
+}
+
+/// What the menu bar icon's usage meter shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IconLayout {
+    /// One thick session bar.
+    SingleBar,
+    /// A thick session bar over a thin weekly bar.
+    #[default]
+    DualBars,
+    /// A ring gauge of session usage.
+    Ring,
+    /// Session usage as a number.
+    Number,
+}
+
+impl IconLayout {
+    /// All available layouts.
+    pub fn all() -> &'static [IconLayout] {
+        &[
+            IconLayout::SingleBar,
+            IconLayout::DualBars,
+            IconLayout::Ring,
+            IconLayout::Number,
+        ]
+    }
+}
+
+impl std::fmt::Display for IconLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconLayout::SingleBar => write!(f, "One Bar"),
+            IconLayout::DualBars => write!(f, "Two Bars"),
+            IconLayout::Ring => write!(f, "Ring"),
+            IconLayout::Number => write!(f, "Number"),
+        }
+    }
+}
+
+/// When the menu bar icons are shown, judged by each provider's lowest
+/// remaining quota against `icon_visibility_threshold`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IconVisibility {
+    /// Always shown.
+    #[default]
+    Always,
+    /// Hidden while every provider is above the threshold.
+    WhenAnyLow,
+    /// Only the providers at or below the threshold are shown.
+    OnlyLow,
+}
+
+impl IconVisibility {
+    /// All available rules.
+    pub fn all() -> &'static [IconVisibility] {
+        &[
+            IconVisibility::Always,
+            IconVisibility::WhenAnyLow,
+            IconVisibility::OnlyLow,
+        ]
+    }
+
+    /// Picks the providers whose icons are shown, from each one's lowest
+    /// remaining percent. Providers without usage yet count as low, so
+    /// icons don't disappear before the first refresh.
+    pub fn visible(
+        self,
+        threshold: f64,
+        remaining: &[(ProviderKind, Option<f64>)],
+    ) -> Vec<ProviderKind> {
+        let is_low = |remaining: Option<f64>| remaining.is_none_or(|r| r <= threshold);
+        let any_low = remaining.iter().any(|&(_, r)| is_low(r));
+        remaining
+            .iter()
+            .filter(|&&(_, r)| match self {
+                IconVisibility::Always => true,
+                IconVisibility::WhenAnyLow => any_low,
+                IconVisibility::OnlyLow => is_low(r),
+            })
+            .map(|&(provider, _)| provider)
+            .collect()
+    }
+}
+
+impl std::fmt::Display for IconVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconVisibility::Always => write!(f, "Always"),
+            IconVisibility::WhenAnyLow => write!(f, "When Any Is Low"),
+            IconVisibility::OnlyLow => write!(f, "Only Low Providers"),
+        }
+    }
+}
+
+/// How much of each provider the menu's All tab shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuDensity {
+    /// One line per provider, to fit many providers without scrolling.
+    Compact,
+    /// A full card per provider.
+    #[default]
+    Detailed,
+}
+
+impl MenuDensity {
+    /// All available densities.
+    pub fn all() -> &'static [MenuDensity] {
+        &[MenuDensity::Compact, MenuDensity::Detailed]
+    }
+}
+
+impl std::fmt::Display for MenuDensity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MenuDensity::Compact => write!(f, "Compact"),
+            MenuDensity::Detailed => write!(f, "Detailed"),
+        }
+    }
+}
+
+/// Status icon animation when a provider is nearly out of quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AttentionStyle {
+    /// No animation.
+    Off,
+    /// Smoothly fade the icon in and out.
+    #[default]
+    Pulse,
+    /// Flash the icon on and off.
+    Blink,
+}
+
+impl AttentionStyle {
+    /// All available styles.
+    pub fn all() -> &'static [AttentionStyle] {
+        &[
+            AttentionStyle::Off,
+            AttentionStyle::Pulse,
+            AttentionStyle::Blink,
+        ]
+    }
+}
+
+impl std::fmt::Display for AttentionStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttentionStyle::Off => write!(f, "Off"),
+            AttentionStyle::Pulse => write!(f, "Pulse"),
+            AttentionStyle::Blink => write!(f, "Blink"),
+        }
+    }
+}
+
 /// Data source mode for usage fetching.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -294,6 +655,18 @@ impl std::fmt::Display for DataSourceMode {
     }
 }
 
+impl DataSourceMode {
+    /// Returns the fetch source mode this setting selects.
+    pub fn source_mode(self) -> SourceMode {
+        match self {
+            DataSourceMode::Auto => SourceMode::Auto,
+            DataSourceMode::Cli => SourceMode::CLI,
+            DataSourceMode::Web => SourceMode::Web,
+            DataSourceMode::Api => SourceMode::ApiKey,
+        }
+    }
+}
+
 /// Cookie source for web-based data fetching.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -356,141 +729,886 @@ impl std::fmt::Display for CookieSource {
     }
 }
 
-/// Per-provider settings.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(default)]
-pub struct ProviderSettings {
-    /// Data source mode override.
-    pub source_mode: Option<DataSourceMode>,
-
-    /// Cookie source for web-based fetching.
-    pub cookie_source: Option<CookieSource>,
-
-    /// Preferred browser for cookies (legacy, use `cookie_source` instead).
-    pub browser_preference: Option<String>,
+/// Quota alert level a notification rule fires on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertLevel {
+    /// Approaching the limit.
+    #[default]
+    Warning,
+    /// Nearly out of quota.
+    Critical,
+}
 
-    /// Environment variable for API key.
-    pub api_key_env: Option<String>,
+/// A destination for quota notifications.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Native desktop notification.
+    System,
+    /// Slack incoming webhook.
+    Slack {
+        /// Webhook URL (`https://hooks.slack.com/services/...`).
+        webhook_url: String,
+    },
+    /// Discord channel webhook.
+    Discord {
+        /// Webhook URL (`https://discord.com/api/webhooks/...`).
+        webhook_url: String,
+    },
+    /// ntfy topic.
+    Ntfy {
+        /// ntfy server (defaults to `https://ntfy.sh`).
+        #[serde(default = "default_ntfy_server")]
+        server: String,
+        /// Topic to publish to.
+        topic: String,
+    },
+}
 
-    /// Manual cookie header (stored inline for simplicity).
-    pub cookie_header: Option<String>,
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
 }
 
-// ============================================================================
-// Settings Store
-// ============================================================================
+impl std::fmt::Display for NotificationChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationChannel::System => write!(f, "System"),
+            NotificationChannel::Slack { .. } => write!(f, "Slack"),
+            NotificationChannel::Discord { .. } => write!(f, "Discord"),
+            NotificationChannel::Ntfy { topic, .. } => write!(f, "ntfy ({topic})"),
+        }
+    }
+}
 
-/// Persistent settings store with change notifications.
-pub struct SettingsStore {
-    settings: Arc<RwLock<Settings>>,
-    path: PathBuf,
-    notify: watch::Sender<u64>,
-    version: Arc<RwLock<u64>>,
+/// Sends quota notifications at or above a level to a set of channels.
+///
+/// A rule that lists `events` fires on those snapshot events instead of
+/// quota alerts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Lowest alert level this rule fires on.
+    #[serde(default)]
+    pub level: AlertLevel,
+    /// Providers this rule applies to (empty = all).
+    #[serde(default)]
+    pub providers: Vec<ProviderKind>,
+    /// Snapshot events this rule fires on (empty = quota alerts only).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SnapshotEventKind>,
+    /// Where to deliver the notification.
+    pub channels: Vec<NotificationChannel>,
+    /// System sound played with the notification center alert, by name
+    /// (e.g. "Glass"; None = silent).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
 }
 
-impl SettingsStore {
-    /// Creates a new settings store.
-    pub fn new(path: PathBuf) -> Self {
-        let (notify, _) = watch::channel(0);
+impl Default for NotificationRule {
+    fn default() -> Self {
         Self {
-            settings: Arc::new(RwLock::new(Settings::default())),
-            path,
-            notify,
-            version: Arc::new(RwLock::new(0)),
+            level: AlertLevel::Warning,
+            providers: vec![],
+            events: vec![],
+            channels: vec![NotificationChannel::System],
+            sound: None,
         }
     }
+}
 
-    /// Loads settings from the default path.
-    ///
-    /// # Errors
-    ///
-    /// Returns error if settings cannot be loaded from disk.
-    pub async fn load_default() -> Result<Self, StoreError> {
-        Self::load(default_settings_path()).await
+impl NotificationRule {
+    /// Returns true if this rule fires for the provider at this level.
+    pub fn matches(&self, provider: ProviderKind, level: AlertLevel) -> bool {
+        self.events.is_empty() && level >= self.level && self.applies_to(provider)
     }
 
-    /// Loads settings from a path.
-    ///
-    /// # Errors
-    ///
-    /// Returns error if settings cannot be loaded from disk.
-    pub async fn load(path: PathBuf) -> Result<Self, StoreError> {
-        let settings = if path.exists() {
-            info!(path = %path.display(), "Loading settings");
-            load_json(&path).await.unwrap_or_else(|e| {
-                warn!(error = %e, "Failed to load settings, using defaults");
-                Settings::default()
-            })
-        } else {
-            debug!(path = %path.display(), "Settings file not found, using defaults");
-            Settings::default()
-        };
+    /// Returns true if this rule fires for the provider on this event.
+    pub fn matches_event(&self, provider: ProviderKind, event: SnapshotEventKind) -> bool {
+        self.events.contains(&event) && self.applies_to(provider)
+    }
 
-        let (notify, _) = watch::channel(0);
-        Ok(Self {
-            settings: Arc::new(RwLock::new(settings)),
-            path,
-            notify,
-            version: Arc::new(RwLock::new(0)),
-        })
+    fn applies_to(&self, provider: ProviderKind) -> bool {
+        self.providers.is_empty() || self.providers.contains(&provider)
     }
+}
 
-    /// Gets a copy of the current settings.
-    pub async fn get(&self) -> Settings {
-        self.settings.read().await.clone()
+impl Settings {
+    /// Label the user gave the account in `identity`, if any.
+    pub fn account_label(&self, identity: &ProviderIdentity) -> Option<&AccountLabel> {
+        let account_id = identity.account_id()?;
+        self.provider_settings
+            .get(&identity.provider_id)?
+            .account_labels
+            .get(&account_id)
     }
 
-    /// Updates settings and notifies subscribers.
-    pub async fn update<F>(&self, f: F)
-    where
-        F: FnOnce(&mut Settings),
-    {
+    /// Data source mode for a provider.
+    ///
+    /// A per-provider override in `provider_settings` wins; Codex and
+    /// Claude then fall back to their dedicated settings.
+    pub fn data_source(&self, provider: ProviderKind) -> DataSourceMode {
+        if let Some(mode) = self
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.source_mode)
         {
-            let mut settings = self.settings.write().await;
-            f(&mut settings);
+            return mode;
+        }
+        match provider {
+            ProviderKind::Codex => self.codex_usage_data_source,
+            ProviderKind::Claude => self.claude_usage_data_source,
+            _ => DataSourceMode::Auto,
         }
-        self.notify_change().await;
     }
 
-    /// Saves settings to disk.
-    ///
-    /// # Errors
+    /// Binary overrides and extra search paths for finding provider CLIs.
     ///
-    /// Returns error if settings cannot be written to disk.
-    pub async fn save(&self) -> Result<(), StoreError> {
-        let settings = self.settings.read().await;
-        save_json(&self.path, &*settings).await?;
-        info!(path = %self.path.display(), "Settings saved");
-        Ok(())
+    /// `binary_name` maps a provider to the CLI its override applies to.
+    pub fn binary_search(
+        &self,
+        binary_name: impl Fn(ProviderKind) -> Option<&'static str>,
+    ) -> BinarySearch {
+        let overrides = self
+            .provider_settings
+            .iter()
+            .filter_map(|(provider, ps)| {
+                let path = ps.binary_path.clone()?;
+                Some((binary_name(*provider)?.to_string(), path))
+            })
+            .collect();
+        BinarySearch {
+            overrides,
+            extra_paths: self.extra_search_paths.clone(),
+        }
     }
 
-    /// Subscribes to settings changes.
-    pub fn subscribe(&self) -> watch::Receiver<u64> {
-        self.notify.subscribe()
+    /// Sandbox for a provider's CLI commands (unrestricted by default).
+    pub fn sandbox(&self, provider: ProviderKind) -> ProcessSandbox {
+        self.provider_settings
+            .get(&provider)
+            .map(|ps| ps.sandbox.clone())
+            .unwrap_or_default()
     }
 
-    /// Notifies subscribers of a change.
-    async fn notify_change(&self) {
-        let mut version = self.version.write().await;
-        *version += 1;
-        let _ = self.notify.send(*version);
+    /// Which of a provider's fetch strategies may run (all by default).
+    pub fn strategies(&self, provider: ProviderKind) -> StrategySettings {
+        self.provider_settings
+            .get(&provider)
+            .map(|ps| ps.strategies.clone())
+            .unwrap_or_default()
     }
 
-    // ========================================================================
-    // Convenience Methods
-    // ========================================================================
+    /// Base URL for a provider's API requests, if it's proxied.
+    pub fn base_url(&self, provider: ProviderKind) -> Option<String> {
+        self.provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.base_url.clone())
+    }
 
-    /// Checks if a provider is enabled.
-    pub async fn is_provider_enabled(&self, provider: ProviderKind) -> bool {
-        self.settings
-            .read()
-            .await
-            .enabled_providers
-            .contains(&provider)
+    /// User-Agent and extra headers for a provider's requests (none by
+    /// default).
+    pub fn request_headers(&self, provider: ProviderKind) -> RequestHeaders {
+        self.provider_settings
+            .get(&provider)
+            .map(|ps| ps.request_headers.clone())
+            .unwrap_or_default()
     }
 
-    /// Enables or disables a provider.
-    pub async fn set_provider_enabled(&self, provider: ProviderKind, enabled: bool) {
+    /// Request limits for a provider: its own and the one for all
+    /// providers together.
+    pub fn request_budget(&self, provider: ProviderKind) -> ProviderBudget {
+        ProviderBudget {
+            provider,
+            per_hour: self
+                .provider_settings
+                .get(&provider)
+                .and_then(|ps| ps.request_budget_per_hour),
+            global_per_hour: self.request_budget_per_hour,
+        }
+    }
+
+    /// Local time of day a provider's session window should last until, if
+    /// a burn target is set.
+    pub fn burn_target(&self, provider: ProviderKind) -> Option<NaiveTime> {
+        self.provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.burn_target)
+    }
+
+    /// Switcher group a provider is listed under, if any.
+    pub fn provider_group(&self, provider: ProviderKind) -> Option<&str> {
+        self.provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.group.as_deref())
+    }
+
+    /// Names of every provider group, sorted.
+    pub fn provider_groups(&self) -> Vec<String> {
+        let mut groups: Vec<String> = self
+            .provider_settings
+            .values()
+            .filter_map(|ps| ps.group.clone())
+            .collect();
+        groups.sort();
+        groups.dedup();
+        groups
+    }
+
+    /// Splits providers into their groups, keeping their order within
+    /// each. Ungrouped providers come first under `None`, then groups in
+    /// the order their first provider appears.
+    pub fn group_providers(
+        &self,
+        providers: &[ProviderKind],
+    ) -> Vec<(Option<String>, Vec<ProviderKind>)> {
+        let mut groups: Vec<(Option<String>, Vec<ProviderKind>)> = vec![(None, Vec::new())];
+        for &provider in providers {
+            let group = self.provider_group(provider).map(str::to_string);
+            match groups.iter_mut().find(|(name, _)| *name == group) {
+                Some((_, members)) => members.push(provider),
+                None => groups.push((group, vec![provider])),
+            }
+        }
+        groups.retain(|(_, members)| !members.is_empty());
+        groups
+    }
+
+    /// Failure tolerance for a provider: its own, or the global one.
+    pub fn failure_tolerance(&self, provider: ProviderKind) -> FailureTolerance {
+        self.provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.failure_tolerance)
+            .unwrap_or(self.failure_tolerance)
+    }
+
+    /// Password manager a provider's API keys are read from, if not the
+    /// keychain.
+    pub fn secret_backend(&self, provider: ProviderKind) -> Option<&SecretBackend> {
+        self.provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.secret_backend.as_ref())
+    }
+
+    /// Password managers keys are read from, by keychain name, for
+    /// [`set_secret_backends`](crate::set_secret_backends).
+    pub fn secret_backends(&self) -> HashMap<String, SecretBackend> {
+        self.provider_settings
+            .iter()
+            .filter_map(|(provider, ps)| Some((*provider, ps.secret_backend.as_ref()?)))
+            .flat_map(|(provider, backend)| {
+                providers::for_provider(provider)
+                    .iter()
+                    .map(move |name| (name.to_string(), backend.clone()))
+            })
+            .collect()
+    }
+
+    /// Providers exported to Google Sheets, with the tab each one's rows
+    /// go to.
+    pub fn sheets_tabs(&self) -> Vec<(ProviderKind, String)> {
+        let mut tabs: Vec<_> = self
+            .provider_settings
+            .iter()
+            .filter_map(|(provider, ps)| Some((*provider, ps.sheets_tab.clone()?)))
+            .collect();
+        tabs.sort_by_key(|(provider, _)| provider.cli_name());
+        tabs
+    }
+
+    /// Channels to notify for a provider at a level, without duplicates.
+    pub fn notification_channels(
+        &self,
+        provider: ProviderKind,
+        level: AlertLevel,
+    ) -> Vec<&NotificationChannel> {
+        self.channels_where(|rule| rule.matches(provider, level))
+    }
+
+    /// Whether a provider's alert at this level is delivered during Focus.
+    /// Only critical alerts for `focus_overrides` providers are.
+    pub fn breaks_focus(&self, provider: ProviderKind, level: AlertLevel) -> bool {
+        level == AlertLevel::Critical && self.focus_overrides.contains(&provider)
+    }
+
+    /// Channels to notify for a provider's snapshot event, without
+    /// duplicates.
+    pub fn event_channels(
+        &self,
+        provider: ProviderKind,
+        event: SnapshotEventKind,
+    ) -> Vec<&NotificationChannel> {
+        self.channels_where(|rule| rule.matches_event(provider, event))
+    }
+
+    /// Sound for a provider's alert at this level: the first matching rule
+    /// that notifies the system and has one.
+    pub fn notification_sound(&self, provider: ProviderKind, level: AlertLevel) -> Option<&str> {
+        self.sound_where(|rule| rule.matches(provider, level))
+    }
+
+    /// Sound for a provider's snapshot event, picked like
+    /// [`Settings::notification_sound`].
+    pub fn event_sound(&self, provider: ProviderKind, event: SnapshotEventKind) -> Option<&str> {
+        self.sound_where(|rule| rule.matches_event(provider, event))
+    }
+
+    fn sound_where(&self, fires: impl Fn(&NotificationRule) -> bool) -> Option<&str> {
+        self.notification_rules
+            .iter()
+            .filter(|rule| fires(rule) && rule.channels.contains(&NotificationChannel::System))
+            .find_map(|rule| rule.sound.as_deref())
+    }
+
+    fn channels_where(
+        &self,
+        fires: impl Fn(&NotificationRule) -> bool,
+    ) -> Vec<&NotificationChannel> {
+        let mut channels: Vec<&NotificationChannel> = Vec::new();
+        for rule in self.notification_rules.iter().filter(|rule| fires(rule)) {
+            for channel in &rule.channels {
+                if !channels.contains(&channel) {
+                    channels.push(channel);
+                }
+            }
+        }
+        channels
+    }
+}
+
+/// Event that runs a [`ShellHook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// Usage crossed the hook's threshold.
+    OnThreshold,
+    /// Usage dropped back after the quota window reset.
+    OnReset,
+    /// A provider started failing to fetch.
+    OnFetchError,
+}
+
+impl HookEvent {
+    /// Name used in the settings file and the hook environment.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::OnThreshold => "on_threshold",
+            HookEvent::OnReset => "on_reset",
+            HookEvent::OnFetchError => "on_fetch_error",
+        }
+    }
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A shell command run when an event happens.
+///
+/// The command runs through `sh -c` with `EXACTOBAR_*` environment
+/// variables describing the event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShellHook {
+    /// Event that runs the command.
+    pub event: HookEvent,
+    /// Command line passed to `sh -c`.
+    pub command: String,
+    /// Providers this hook applies to (empty = all).
+    #[serde(default)]
+    pub providers: Vec<ProviderKind>,
+    /// Used percentage that fires an `on_threshold` hook.
+    #[serde(default = "default_hook_threshold")]
+    pub threshold: f64,
+}
+
+fn default_hook_threshold() -> f64 {
+    80.0
+}
+
+impl ShellHook {
+    /// Returns true if this hook runs for the provider on this event.
+    pub fn matches(&self, provider: ProviderKind, event: HookEvent) -> bool {
+        self.event == event && (self.providers.is_empty() || self.providers.contains(&provider))
+    }
+}
+
+/// How much failure a provider is allowed before it's alerted on.
+///
+/// Scraped providers fail now and then and recover on the next refresh.
+/// A failure only shows (error icon, `on_fetch_error` hooks) once it's
+/// exceeded either limit; until then the last good usage stays up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailureTolerance {
+    /// Failed fetches in a row that count as failing. 1 alerts on the
+    /// first failure.
+    pub failures: u32,
+    /// Minutes without a successful fetch that count as failing, whatever
+    /// the number of failures.
+    pub stale_minutes: Option<u32>,
+}
+
+impl Default for FailureTolerance {
+    fn default() -> Self {
+        Self {
+            failures: 1,
+            stale_minutes: None,
+        }
+    }
+}
+
+impl FailureTolerance {
+    /// Whether `consecutive` failures in a row, the last good fetch having
+    /// been at `last_success`, are past the tolerance.
+    pub fn is_exceeded(
+        &self,
+        consecutive: u32,
+        last_success: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if consecutive == 0 {
+            return false;
+        }
+        let stale = match (self.stale_minutes, last_success) {
+            (Some(minutes), Some(at)) => now - at >= chrono::Duration::minutes(minutes.into()),
+            _ => false,
+        };
+        consecutive >= self.failures.max(1) || stale
+    }
+}
+
+/// A user-defined provider.
+///
+/// Usage comes from running `command` through `sh -c`; it must print a
+/// usage snapshot as JSON in the `exactobar-api` format. The id must not
+/// clash with a built-in provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomProvider {
+    /// Id and display metadata.
+    #[serde(flatten)]
+    pub info: ProviderInfo,
+    /// Command line passed to `sh -c`.
+    pub command: String,
+    /// Whether the provider is shown and refreshed.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A Google Sheet daily usage rows are appended to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetsExport {
+    /// Spreadsheet id, from the sheet's URL.
+    pub spreadsheet_id: String,
+    /// Service account key file (JSON). The sheet must be shared with the
+    /// account's email as an editor.
+    pub credentials_path: PathBuf,
+}
+
+/// Per-provider settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderSettings {
+    /// Data source mode override.
+    pub source_mode: Option<DataSourceMode>,
+
+    /// Cookie source for web-based fetching.
+    pub cookie_source: Option<CookieSource>,
+
+    /// Preferred browser for cookies (legacy, use `cookie_source` instead).
+    pub browser_preference: Option<String>,
+
+    /// Environment variable for API key.
+    pub api_key_env: Option<String>,
+
+    /// Manual cookie header (stored inline for simplicity).
+    pub cookie_header: Option<String>,
+
+    /// User-chosen labels, keyed by account id (see
+    /// `ProviderIdentity::account_id`).
+    #[serde(serialize_with = "sorted_map")]
+    pub account_labels: HashMap<String, AccountLabel>,
+
+    /// Restrictions on the provider's CLI commands.
+    pub sandbox: ProcessSandbox,
+
+    /// Path to the provider's CLI, used instead of searching for it.
+    pub binary_path: Option<PathBuf>,
+
+    /// Which fetch strategies may run.
+    pub strategies: StrategySettings,
+
+    /// Base URL the provider's API requests go to instead of its public
+    /// endpoint, like an internal gateway proxying the Anthropic API.
+    pub base_url: Option<String>,
+
+    /// User-Agent and extra headers sent with the provider's requests, for
+    /// endpoints that fingerprint clients.
+    #[serde(skip_serializing_if = "RequestHeaders::is_default")]
+    pub request_headers: RequestHeaders,
+
+    /// Most requests per hour for this provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_budget_per_hour: Option<u32>,
+
+    /// Local time of day the session window's quota should last until,
+    /// e.g. 18:00. Usage is paced against it and flagged when ahead.
+    pub burn_target: Option<NaiveTime>,
+
+    /// Tab of the `sheets_export` spreadsheet this provider's daily rows
+    /// are appended to. Providers without one aren't exported.
+    pub sheets_tab: Option<String>,
+
+    /// Group the provider is listed under in the menu's switcher, like
+    /// "Coding" or "Research".
+    pub group: Option<String>,
+
+    /// Failure tolerance used instead of `Settings::failure_tolerance`,
+    /// e.g. a looser one for a flaky web scrape.
+    pub failure_tolerance: Option<FailureTolerance>,
+
+    /// Password manager the provider's API keys are read from instead of
+    /// the keychain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_backend: Option<SecretBackend>,
+}
+
+/// A user-chosen name and color tag for a provider account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountLabel {
+    /// Name shown in place of the account, e.g. "Work".
+    pub name: String,
+    /// Color tag shown next to the name.
+    #[serde(default)]
+    pub color: AccountColor,
+}
+
+/// Color tag for an account label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountColor {
+    /// Neutral gray.
+    #[default]
+    Gray,
+    /// Red.
+    Red,
+    /// Orange.
+    Orange,
+    /// Yellow.
+    Yellow,
+    /// Green.
+    Green,
+    /// Blue.
+    Blue,
+    /// Purple.
+    Purple,
+    /// Pink.
+    Pink,
+}
+
+impl AccountColor {
+    /// All available colors.
+    pub fn all() -> &'static [AccountColor] {
+        &[
+            AccountColor::Gray,
+            AccountColor::Red,
+            AccountColor::Orange,
+            AccountColor::Yellow,
+            AccountColor::Green,
+            AccountColor::Blue,
+            AccountColor::Purple,
+            AccountColor::Pink,
+        ]
+    }
+
+    /// Parses a color name, case-insensitively.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|c| c.to_string().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl std::fmt::Display for AccountColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountColor::Gray => write!(f, "Gray"),
+            AccountColor::Red => write!(f, "Red"),
+            AccountColor::Orange => write!(f, "Orange"),
+            AccountColor::Yellow => write!(f, "Yellow"),
+            AccountColor::Green => write!(f, "Green"),
+            AccountColor::Blue => write!(f, "Blue"),
+            AccountColor::Purple => write!(f, "Purple"),
+            AccountColor::Pink => write!(f, "Pink"),
+        }
+    }
+}
+
+// ============================================================================
+// Stable Serialization
+// ============================================================================
+
+/// Serializes providers by name, so the saved order doesn't change between
+/// runs.
+fn sorted_providers<S: Serializer>(
+    providers: &HashSet<ProviderKind>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut providers: Vec<_> = providers.iter().collect();
+    providers.sort_by_key(|p| p.cli_name());
+    serializer.collect_seq(providers)
+}
+
+/// Serializes a provider map by provider name.
+fn sorted_provider_map<S: Serializer, V: Serialize>(
+    map: &HashMap<ProviderKind, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(p, _)| p.cli_name());
+    serializer.collect_map(entries)
+}
+
+/// Serializes a map by key.
+fn sorted_map<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+// ============================================================================
+// Settings Format
+// ============================================================================
+
+/// File format settings are saved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsFormat {
+    /// JSON, with a checksum and rotating backups next to the file.
+    #[default]
+    Json,
+    /// TOML, for settings kept in version control. Hand edits are expected,
+    /// so there are no checksum or backups; history is up to the repo.
+    Toml,
+}
+
+impl SettingsFormat {
+    /// Detects the format from a file's extension: `.toml` is TOML,
+    /// anything else JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+
+    /// Returns the format's file extension.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+
+    /// Parses a format name (`json` or `toml`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SettingsFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "JSON"),
+            Self::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+// ============================================================================
+// Settings Store
+// ============================================================================
+
+/// Persistent settings store with change notifications.
+pub struct SettingsStore {
+    settings: Arc<RwLock<Settings>>,
+    path: PathBuf,
+    notify: watch::Sender<u64>,
+    version: Arc<RwLock<u64>>,
+    /// Problem found while loading the settings file, if any.
+    load_issue: Option<StoreError>,
+    /// Backup the settings were restored from after `load_issue`.
+    restored_from: Option<PathBuf>,
+}
+
+impl SettingsStore {
+    /// Creates a new settings store.
+    pub fn new(path: PathBuf) -> Self {
+        let (notify, _) = watch::channel(0);
+        Self {
+            settings: Arc::new(RwLock::new(Settings::default())),
+            path,
+            notify,
+            version: Arc::new(RwLock::new(0)),
+            load_issue: None,
+            restored_from: None,
+        }
+    }
+
+    /// Loads settings from the default path.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if settings cannot be loaded from disk.
+    pub async fn load_default() -> Result<Self, StoreError> {
+        Self::load(default_settings_path()).await
+    }
+
+    /// Loads settings from a path.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if settings cannot be loaded from disk.
+    ///
+    /// A corrupt JSON settings file is replaced by its newest valid backup;
+    /// if there is none, or a TOML file doesn't parse, defaults are used. Either way the problem is kept in
+    /// [`SettingsStore::load_issue`] so the UI can report it.
+    pub async fn load(path: PathBuf) -> Result<Self, StoreError> {
+        let mut load_issue = None;
+        let mut restored_from = None;
+        let settings = if !path.exists() {
+            debug!(path = %path.display(), "Settings file not found, using defaults");
+            Settings::default()
+        } else if SettingsFormat::from_path(&path) == SettingsFormat::Toml {
+            info!(path = %path.display(), "Loading TOML settings");
+            load_toml(&path).await.unwrap_or_else(|e| {
+                warn!(error = %e, "Failed to load settings, using defaults");
+                load_issue = Some(e);
+                Settings::default()
+            })
+        } else {
+            info!(path = %path.display(), "Loading settings");
+            match load_json_with_recovery(&path).await {
+                Ok((settings, None)) => settings,
+                Ok((settings, Some(recovery))) => {
+                    warn!(
+                        error = %recovery.error,
+                        backup = %recovery.backup.display(),
+                        "Settings were corrupt, restored from backup"
+                    );
+                    load_issue = Some(recovery.error);
+                    restored_from = Some(recovery.backup);
+                    settings
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to load settings, using defaults");
+                    load_issue = Some(e);
+                    Settings::default()
+                }
+            }
+        };
+
+        let (notify, _) = watch::channel(0);
+        Ok(Self {
+            settings: Arc::new(RwLock::new(settings)),
+            path,
+            notify,
+            version: Arc::new(RwLock::new(0)),
+            load_issue,
+            restored_from,
+        })
+    }
+
+    /// Returns the problem found while loading the settings file, if it
+    /// was corrupt or unreadable.
+    pub fn load_issue(&self) -> Option<&StoreError> {
+        self.load_issue.as_ref()
+    }
+
+    /// Returns the backup the settings were restored from, if the settings
+    /// file was corrupt and a valid backup was found.
+    pub fn restored_from(&self) -> Option<&Path> {
+        self.restored_from.as_deref()
+    }
+
+    /// Returns the settings file's path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the format the settings file is saved in.
+    pub fn format(&self) -> SettingsFormat {
+        SettingsFormat::from_path(&self.path)
+    }
+
+    /// Gets a copy of the current settings.
+    pub async fn get(&self) -> Settings {
+        self.settings.read().await.clone()
+    }
+
+    /// Updates settings and notifies subscribers.
+    pub async fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Settings),
+    {
+        {
+            let mut settings = self.settings.write().await;
+            f(&mut settings);
+        }
+        self.notify_change().await;
+    }
+
+    /// Saves settings to disk, in the file's [`format`](Self::format).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if settings cannot be written to disk.
+    pub async fn save(&self) -> Result<(), StoreError> {
+        let settings = self.settings.read().await;
+        match self.format() {
+            SettingsFormat::Json => save_json(&self.path, &*settings).await?,
+            SettingsFormat::Toml => save_toml(&self.path, &*settings).await?,
+        }
+        info!(path = %self.path.display(), "Settings saved");
+        Ok(())
+    }
+
+    /// Subscribes to settings changes.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.notify.subscribe()
+    }
+
+    /// Notifies subscribers of a change.
+    async fn notify_change(&self) {
+        let mut version = self.version.write().await;
+        *version += 1;
+        let _ = self.notify.send(*version);
+    }
+
+    // ========================================================================
+    // Convenience Methods
+    // ========================================================================
+
+    /// Checks if a provider is enabled.
+    pub async fn is_provider_enabled(&self, provider: ProviderKind) -> bool {
+        self.settings
+            .read()
+            .await
+            .enabled_providers
+            .contains(&provider)
+    }
+
+    /// Enables or disables a provider.
+    pub async fn set_provider_enabled(&self, provider: ProviderKind, enabled: bool) {
         self.update(|s| {
             if enabled {
                 s.enabled_providers.insert(provider);
@@ -540,6 +1658,16 @@ impl SettingsStore {
         self.update(|s| s.reset_times_show_absolute = value).await;
     }
 
+    /// Gets the timezone reset times are shown in (None = system timezone).
+    pub async fn reset_timezone(&self) -> Option<String> {
+        self.settings.read().await.reset_timezone.clone()
+    }
+
+    /// Sets the timezone reset times are shown in.
+    pub async fn set_reset_timezone(&self, zone: Option<String>) {
+        self.update(|s| s.reset_timezone = zone).await;
+    }
+
     /// Gets whether menu bar shows brand icon with percent.
     pub async fn menu_bar_shows_brand_icon_with_percent(&self) -> bool {
         self.settings
@@ -564,6 +1692,26 @@ impl SettingsStore {
         self.update(|s| s.switcher_shows_icons = value).await;
     }
 
+    /// Gets the menu card density.
+    pub async fn menu_density(&self) -> MenuDensity {
+        self.settings.read().await.menu_density
+    }
+
+    /// Sets the menu card density.
+    pub async fn set_menu_density(&self, density: MenuDensity) {
+        self.update(|s| s.menu_density = density).await;
+    }
+
+    /// Gets whether providers are ordered by remaining quota.
+    pub async fn sort_by_usage(&self) -> bool {
+        self.settings.read().await.sort_by_usage
+    }
+
+    /// Sets whether providers are ordered by remaining quota.
+    pub async fn set_sort_by_usage(&self, value: bool) {
+        self.update(|s| s.sort_by_usage = value).await;
+    }
+
     // ========================================================================
     // Feature Toggle Methods
     // ========================================================================
@@ -592,6 +1740,66 @@ impl SettingsStore {
             .await;
     }
 
+    /// Checks if reset notifications are enabled.
+    pub async fn reset_notifications_enabled(&self) -> bool {
+        self.settings.read().await.reset_notifications_enabled
+    }
+
+    /// Sets whether reset notifications are enabled.
+    pub async fn set_reset_notifications_enabled(&self, value: bool) {
+        self.update(|s| s.reset_notifications_enabled = value).await;
+    }
+
+    /// Checks if notifications and refreshes are quieted during meetings.
+    pub async fn quiet_during_meetings(&self) -> bool {
+        self.settings.read().await.quiet_during_meetings
+    }
+
+    /// Sets whether notifications and refreshes are quieted during meetings.
+    pub async fn set_quiet_during_meetings(&self, value: bool) {
+        self.update(|s| s.quiet_during_meetings = value).await;
+    }
+
+    /// Checks if notifications are held while a Focus is on.
+    pub async fn respect_focus(&self) -> bool {
+        self.settings.read().await.respect_focus
+    }
+
+    /// Sets whether notifications are held while a Focus is on.
+    pub async fn set_respect_focus(&self, value: bool) {
+        self.update(|s| s.respect_focus = value).await;
+    }
+
+    /// Gets the notification rules.
+    pub async fn notification_rules(&self) -> Vec<NotificationRule> {
+        self.settings.read().await.notification_rules.clone()
+    }
+
+    /// Sets the notification rules.
+    pub async fn set_notification_rules(&self, rules: Vec<NotificationRule>) {
+        self.update(|s| s.notification_rules = rules).await;
+    }
+
+    /// Gets the shell hooks.
+    pub async fn hooks(&self) -> Vec<ShellHook> {
+        self.settings.read().await.hooks.clone()
+    }
+
+    /// Sets the shell hooks.
+    pub async fn set_hooks(&self, hooks: Vec<ShellHook>) {
+        self.update(|s| s.hooks = hooks).await;
+    }
+
+    /// Gets the user-defined providers.
+    pub async fn custom_providers(&self) -> Vec<CustomProvider> {
+        self.settings.read().await.custom_providers.clone()
+    }
+
+    /// Sets the user-defined providers.
+    pub async fn set_custom_providers(&self, providers: Vec<CustomProvider>) {
+        self.update(|s| s.custom_providers = providers).await;
+    }
+
     /// Gets whether cost usage tracking is enabled.
     pub async fn cost_usage_enabled(&self) -> bool {
         self.settings.read().await.cost_usage_enabled
@@ -602,6 +1810,16 @@ impl SettingsStore {
         self.update(|s| s.cost_usage_enabled = value).await;
     }
 
+    /// Gets whether the team leaderboard is offered.
+    pub async fn team_leaderboard_enabled(&self) -> bool {
+        self.settings.read().await.team_leaderboard_enabled
+    }
+
+    /// Sets whether the team leaderboard is offered.
+    pub async fn set_team_leaderboard_enabled(&self, value: bool) {
+        self.update(|s| s.team_leaderboard_enabled = value).await;
+    }
+
     /// Gets whether random blink animation is enabled.
     pub async fn random_blink_enabled(&self) -> bool {
         self.settings.read().await.random_blink_enabled
@@ -612,6 +1830,61 @@ impl SettingsStore {
         self.update(|s| s.random_blink_enabled = value).await;
     }
 
+    /// Gets the menu bar icon layout.
+    pub async fn icon_layout(&self) -> IconLayout {
+        self.settings.read().await.icon_layout
+    }
+
+    /// Sets the menu bar icon layout.
+    pub async fn set_icon_layout(&self, layout: IconLayout) {
+        self.update(|s| s.icon_layout = layout).await;
+    }
+
+    /// Gets whether the menu bar icon is drawn for the menu bar's appearance.
+    pub async fn adaptive_icon(&self) -> bool {
+        self.settings.read().await.adaptive_icon
+    }
+
+    /// Sets whether the menu bar icon is drawn for the menu bar's appearance.
+    pub async fn set_adaptive_icon(&self, value: bool) {
+        self.update(|s| s.adaptive_icon = value).await;
+    }
+
+    /// Gets whether the menu bar icon's meter is outlined.
+    pub async fn icon_outline(&self) -> bool {
+        self.settings.read().await.icon_outline
+    }
+
+    /// Sets whether the menu bar icon's meter is outlined.
+    pub async fn set_icon_outline(&self, value: bool) {
+        self.update(|s| s.icon_outline = value).await;
+    }
+
+    /// Gets when menu bar icons are shown.
+    pub async fn icon_visibility(&self) -> IconVisibility {
+        self.settings.read().await.icon_visibility
+    }
+
+    /// Sets when menu bar icons are shown, and the remaining percent at or
+    /// below which a provider counts as low.
+    pub async fn set_icon_visibility(&self, visibility: IconVisibility, threshold: f64) {
+        self.update(|s| {
+            s.icon_visibility = visibility;
+            s.icon_visibility_threshold = threshold.clamp(0.0, 100.0);
+        })
+        .await;
+    }
+
+    /// Gets the attention animation style.
+    pub async fn attention_animation(&self) -> AttentionStyle {
+        self.settings.read().await.attention_animation
+    }
+
+    /// Sets the attention animation style.
+    pub async fn set_attention_animation(&self, style: AttentionStyle) {
+        self.update(|s| s.attention_animation = style).await;
+    }
+
     /// Gets whether Claude web extras are enabled.
     pub async fn claude_web_extras_enabled(&self) -> bool {
         self.settings.read().await.claude_web_extras_enabled
@@ -657,6 +1930,16 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Gets the color palette.
+    pub async fn color_palette(&self) -> ColorPalette {
+        self.settings.read().await.color_palette
+    }
+
+    /// Sets the color palette.
+    pub async fn set_color_palette(&self, palette: ColorPalette) {
+        self.update(|s| s.color_palette = palette).await;
+    }
+
     // ========================================================================
     // Data Source Methods
     // ========================================================================
@@ -702,68 +1985,205 @@ impl SettingsStore {
         self.update(|s| s.provider_order = order).await;
     }
 
-    // ========================================================================
-    // Per-Provider Cookie Source Methods
-    // ========================================================================
-
-    /// Gets the cookie source for a provider.
-    pub async fn cookie_source(&self, provider: ProviderKind) -> CookieSource {
-        self.settings
-            .read()
-            .await
-            .provider_settings
-            .get(&provider)
-            .and_then(|ps| ps.cookie_source)
-            .unwrap_or_default()
+    // ========================================================================
+    // Per-Provider Cookie Source Methods
+    // ========================================================================
+
+    /// Gets the cookie source for a provider.
+    pub async fn cookie_source(&self, provider: ProviderKind) -> CookieSource {
+        self.settings
+            .read()
+            .await
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.cookie_source)
+            .unwrap_or_default()
+    }
+
+    /// Sets the cookie source for a provider.
+    pub async fn set_cookie_source(&self, provider: ProviderKind, source: CookieSource) {
+        self.update(|s| {
+            s.provider_settings
+                .entry(provider)
+                .or_default()
+                .cookie_source = Some(source);
+        })
+        .await;
+    }
+
+    /// Gets the data source mode for a provider.
+    pub async fn provider_source_mode(&self, provider: ProviderKind) -> DataSourceMode {
+        self.settings
+            .read()
+            .await
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.source_mode)
+            .unwrap_or_default()
+    }
+
+    /// Sets the data source mode for a provider.
+    pub async fn set_provider_source_mode(&self, provider: ProviderKind, mode: DataSourceMode) {
+        self.update(|s| {
+            s.provider_settings.entry(provider).or_default().source_mode = Some(mode);
+        })
+        .await;
+    }
+
+    /// Gets the manual cookie header for a provider.
+    pub async fn cookie_header(&self, provider: ProviderKind) -> Option<String> {
+        self.settings
+            .read()
+            .await
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.cookie_header.clone())
+    }
+
+    /// Sets the manual cookie header for a provider.
+    pub async fn set_cookie_header(&self, provider: ProviderKind, header: Option<String>) {
+        self.update(|s| {
+            s.provider_settings
+                .entry(provider)
+                .or_default()
+                .cookie_header = header;
+        })
+        .await;
+    }
+
+    /// Gets the label for a provider account.
+    pub async fn account_label(
+        &self,
+        provider: ProviderKind,
+        account_id: &str,
+    ) -> Option<AccountLabel> {
+        self.settings
+            .read()
+            .await
+            .provider_settings
+            .get(&provider)
+            .and_then(|ps| ps.account_labels.get(account_id).cloned())
+    }
+
+    /// Sets or clears (`None`) the label for a provider account.
+    pub async fn set_account_label(
+        &self,
+        provider: ProviderKind,
+        account_id: &str,
+        label: Option<AccountLabel>,
+    ) {
+        let account_id = account_id.trim().to_lowercase();
+        self.update(|s| {
+            let labels = &mut s
+                .provider_settings
+                .entry(provider)
+                .or_default()
+                .account_labels;
+            match label {
+                Some(label) => labels.insert(account_id, label),
+                None => labels.remove(&account_id),
+            };
+        })
+        .await;
+    }
+
+    /// Sets or clears (`None`) the base URL for a provider's API requests.
+    pub async fn set_base_url(&self, provider: ProviderKind, url: Option<String>) {
+        self.update(|s| {
+            s.provider_settings.entry(provider).or_default().base_url = url;
+        })
+        .await;
+    }
+
+    /// Sets the User-Agent and extra headers for a provider's requests.
+    pub async fn set_request_headers(&self, provider: ProviderKind, headers: RequestHeaders) {
+        self.update(|s| {
+            s.provider_settings
+                .entry(provider)
+                .or_default()
+                .request_headers = headers;
+        })
+        .await;
+    }
+
+    /// Sets or clears (`None`) the request limit for all providers
+    /// together.
+    pub async fn set_request_budget(&self, per_hour: Option<u32>) {
+        self.update(|s| s.request_budget_per_hour = per_hour).await;
     }
 
-    /// Sets the cookie source for a provider.
-    pub async fn set_cookie_source(&self, provider: ProviderKind, source: CookieSource) {
+    /// Sets or clears (`None`) a provider's own request limit.
+    pub async fn set_provider_request_budget(&self, provider: ProviderKind, per_hour: Option<u32>) {
         self.update(|s| {
             s.provider_settings
                 .entry(provider)
                 .or_default()
-                .cookie_source = Some(source);
+                .request_budget_per_hour = per_hour;
         })
         .await;
     }
 
-    /// Gets the data source mode for a provider.
-    pub async fn provider_source_mode(&self, provider: ProviderKind) -> DataSourceMode {
-        self.settings
-            .read()
-            .await
-            .provider_settings
-            .get(&provider)
-            .and_then(|ps| ps.source_mode)
-            .unwrap_or_default()
+    /// Sets or clears (`None`) the Google Sheet usage is exported to.
+    pub async fn set_sheets_export(&self, export: Option<SheetsExport>) {
+        self.update(|s| s.sheets_export = export).await;
     }
 
-    /// Sets the data source mode for a provider.
-    pub async fn set_provider_source_mode(&self, provider: ProviderKind, mode: DataSourceMode) {
+    /// Sets or clears (`None`) the sheet tab a provider is exported to.
+    pub async fn set_sheets_tab(&self, provider: ProviderKind, tab: Option<String>) {
         self.update(|s| {
-            s.provider_settings.entry(provider).or_default().source_mode = Some(mode);
+            s.provider_settings.entry(provider).or_default().sheets_tab = tab;
         })
         .await;
     }
 
-    /// Gets the manual cookie header for a provider.
-    pub async fn cookie_header(&self, provider: ProviderKind) -> Option<String> {
-        self.settings
-            .read()
-            .await
-            .provider_settings
-            .get(&provider)
-            .and_then(|ps| ps.cookie_header.clone())
+    /// Sets or clears (`None`) the switcher group a provider is listed
+    /// under. Blank names clear it.
+    pub async fn set_provider_group(&self, provider: ProviderKind, group: Option<String>) {
+        let group = group
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty());
+        self.update(|s| {
+            s.provider_settings.entry(provider).or_default().group = group;
+        })
+        .await;
     }
 
-    /// Sets the manual cookie header for a provider.
-    pub async fn set_cookie_header(&self, provider: ProviderKind, header: Option<String>) {
+    /// Sets the failure tolerance of providers without their own.
+    pub async fn set_failure_tolerance(&self, tolerance: FailureTolerance) {
+        self.update(|s| s.failure_tolerance = tolerance).await;
+    }
+
+    /// Sets or clears (`None`) a provider's own failure tolerance.
+    pub async fn set_provider_failure_tolerance(
+        &self,
+        provider: ProviderKind,
+        tolerance: Option<FailureTolerance>,
+    ) {
         self.update(|s| {
             s.provider_settings
                 .entry(provider)
                 .or_default()
-                .cookie_header = header;
+                .failure_tolerance = tolerance;
+        })
+        .await;
+    }
+
+    /// Sets or clears (`None`, the keychain) the password manager a
+    /// provider's API keys are read from.
+    pub async fn set_secret_backend(&self, provider: ProviderKind, backend: Option<SecretBackend>) {
+        self.update(|s| {
+            s.provider_settings
+                .entry(provider)
+                .or_default()
+                .secret_backend = backend;
+        })
+        .await;
+    }
+
+    /// Sets or clears (`None`) a provider's burn target.
+    pub async fn set_burn_target(&self, provider: ProviderKind, target: Option<NaiveTime>) {
+        self.update(|s| {
+            s.provider_settings.entry(provider).or_default().burn_target = target;
         })
         .await;
     }
@@ -792,6 +2212,20 @@ impl SettingsStore {
     pub async fn set_debug_loading_pattern(&self, pattern: Option<String>) {
         self.update(|s| s.debug_loading_pattern = pattern).await;
     }
+
+    // ========================================================================
+    // Companion Extension Methods
+    // ========================================================================
+
+    /// Gets whether the companion endpoint is enabled.
+    pub async fn companion_endpoint_enabled(&self) -> bool {
+        self.settings.read().await.companion_endpoint_enabled
+    }
+
+    /// Sets whether the companion endpoint is enabled.
+    pub async fn set_companion_endpoint_enabled(&self, value: bool) {
+        self.update(|s| s.companion_endpoint_enabled = value).await;
+    }
 }
 
 // ============================================================================
@@ -799,6 +2233,7 @@ impl SettingsStore {
 // ============================================================================
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
     use super::*;
 
@@ -854,12 +2289,21 @@ mod tests {
         assert!(!settings.usage_bars_show_used);
         assert!(!settings.reset_times_show_absolute);
         assert!(!settings.menu_bar_shows_brand_icon_with_percent);
+        assert!(!settings.adaptive_icon);
+        assert!(!settings.icon_outline);
         assert!(settings.switcher_shows_icons);
+        assert_eq!(settings.menu_density, MenuDensity::Detailed);
+        assert!(!settings.sort_by_usage);
 
         // Feature toggle defaults
         assert!(settings.status_checks_enabled);
         assert!(settings.session_quota_notifications_enabled);
+        assert!(!settings.reset_notifications_enabled);
+        assert!(!settings.quiet_during_meetings);
+        assert!(settings.respect_focus);
+        assert!(settings.focus_overrides.is_empty());
         assert!(!settings.cost_usage_enabled);
+        assert!(!settings.team_leaderboard_enabled);
         assert!(!settings.random_blink_enabled);
         assert!(!settings.claude_web_extras_enabled);
         assert!(settings.show_optional_credits_and_extra_usage);
@@ -872,6 +2316,165 @@ mod tests {
         // Provider order defaults
         assert!(settings.provider_order.is_empty());
         assert!(!settings.provider_detection_completed);
+
+        // Companion endpoint is opt-in
+        assert!(!settings.companion_endpoint_enabled);
+        assert_eq!(settings.companion_endpoint_port, DEFAULT_COMPANION_PORT);
+        assert!(settings.keychain_denied_browsers.is_empty());
+        assert!(!settings.locked_mode);
+        assert!(settings.redact_emails_in_logs);
+        assert_eq!(settings.parse_mode, ParseMode::Lenient);
+
+        // Icon shows session and weekly bars by default
+        assert_eq!(settings.icon_layout, IconLayout::DualBars);
+        assert_eq!(settings.icon_visibility, IconVisibility::Always);
+
+        // Attention animation defaults
+        assert_eq!(settings.attention_animation, AttentionStyle::Pulse);
+        assert_eq!(settings.attention_duration_secs, 10);
+
+        // Notifications go to the system by default
+        assert_eq!(
+            settings.notification_rules,
+            vec![NotificationRule::default()]
+        );
+    }
+
+    #[test]
+    fn test_notification_channels() {
+        let slack = NotificationChannel::Slack {
+            webhook_url: "https://hooks.slack.com/services/T/B/X".to_string(),
+        };
+        let settings = Settings {
+            notification_rules: vec![
+                NotificationRule::default(),
+                NotificationRule {
+                    level: AlertLevel::Critical,
+                    providers: vec![ProviderKind::Claude],
+                    events: vec![],
+                    channels: vec![slack.clone(), NotificationChannel::System],
+                    sound: Some("Sosumi".to_string()),
+                },
+                NotificationRule {
+                    events: vec![SnapshotEventKind::WindowReset],
+                    channels: vec![slack.clone()],
+                    ..NotificationRule::default()
+                },
+            ],
+            ..Settings::default()
+        };
+
+        assert_eq!(
+            settings.notification_channels(ProviderKind::Claude, AlertLevel::Warning),
+            vec![&NotificationChannel::System]
+        );
+        assert_eq!(
+            settings.notification_channels(ProviderKind::Claude, AlertLevel::Critical),
+            vec![&NotificationChannel::System, &slack]
+        );
+        assert_eq!(
+            settings.notification_channels(ProviderKind::Codex, AlertLevel::Critical),
+            vec![&NotificationChannel::System]
+        );
+
+        // Event rules only fire on their events
+        assert_eq!(
+            settings.event_channels(ProviderKind::Codex, SnapshotEventKind::WindowReset),
+            vec![&slack]
+        );
+        assert!(
+            settings
+                .event_channels(ProviderKind::Codex, SnapshotEventKind::BigJump)
+                .is_empty()
+        );
+
+        // Sounds come from the first matching rule that has one
+        assert_eq!(
+            settings.notification_sound(ProviderKind::Claude, AlertLevel::Critical),
+            Some("Sosumi")
+        );
+        assert_eq!(
+            settings.notification_sound(ProviderKind::Claude, AlertLevel::Warning),
+            None
+        );
+        assert_eq!(
+            settings.event_sound(ProviderKind::Codex, SnapshotEventKind::WindowReset),
+            None
+        );
+    }
+
+    #[test]
+    fn test_breaks_focus() {
+        let settings = Settings {
+            focus_overrides: vec![ProviderKind::Claude],
+            ..Settings::default()
+        };
+
+        assert!(settings.breaks_focus(ProviderKind::Claude, AlertLevel::Critical));
+        assert!(!settings.breaks_focus(ProviderKind::Claude, AlertLevel::Warning));
+        assert!(!settings.breaks_focus(ProviderKind::Codex, AlertLevel::Critical));
+    }
+
+    #[test]
+    fn test_notification_rule_serde() {
+        let json = r#"{
+            "level": "critical",
+            "channels": [
+                {"type": "discord", "webhook_url": "https://discord.com/api/webhooks/1/x"},
+                {"type": "ntfy", "topic": "quota"}
+            ]
+        }"#;
+        let rule: NotificationRule = serde_json::from_str(json).unwrap();
+
+        assert_eq!(rule.level, AlertLevel::Critical);
+        assert!(rule.providers.is_empty());
+        assert_eq!(
+            rule.channels[1],
+            NotificationChannel::Ntfy {
+                server: "https://ntfy.sh".to_string(),
+                topic: "quota".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_shell_hook_serde() {
+        let json = r#"{
+            "event": "on_threshold",
+            "command": "crontab -r",
+            "providers": ["claude"],
+            "threshold": 95.0
+        }"#;
+        let hook: ShellHook = serde_json::from_str(json).unwrap();
+
+        assert_eq!(hook.event, HookEvent::OnThreshold);
+        assert_eq!(hook.threshold, 95.0);
+        assert!(hook.matches(ProviderKind::Claude, HookEvent::OnThreshold));
+        assert!(!hook.matches(ProviderKind::Codex, HookEvent::OnThreshold));
+        assert!(!hook.matches(ProviderKind::Claude, HookEvent::OnReset));
+
+        let hook: ShellHook =
+            serde_json::from_str(r#"{"event": "on_fetch_error", "command": "true"}"#).unwrap();
+        assert_eq!(hook.threshold, 80.0);
+        assert!(hook.matches(ProviderKind::Codex, HookEvent::OnFetchError));
+    }
+
+    #[test]
+    fn test_custom_provider_serde() {
+        let json = r#"{
+            "id": "My-Gateway",
+            "display_name": "My Gateway",
+            "command": "curl -s localhost:8080/usage"
+        }"#;
+        let provider: CustomProvider = serde_json::from_str(json).unwrap();
+
+        assert_eq!(provider.info.id.as_str(), "my-gateway");
+        assert_eq!(provider.info.session_label, "Session");
+        assert!(provider.enabled);
+
+        let value = serde_json::to_value(&provider).unwrap();
+        assert_eq!(value["id"], "my-gateway");
+        assert_eq!(value["command"], "curl -s localhost:8080/usage");
     }
 
     #[tokio::test]
@@ -887,6 +2490,11 @@ mod tests {
         assert!(!store.reset_times_show_absolute().await);
         store.set_reset_times_show_absolute(true).await;
         assert!(store.reset_times_show_absolute().await);
+
+        // Test reset timezone override
+        assert!(store.reset_timezone().await.is_none());
+        store.set_reset_timezone(Some("UTC".to_string())).await;
+        assert_eq!(store.reset_timezone().await.as_deref(), Some("UTC"));
     }
 
     #[tokio::test]
@@ -952,6 +2560,350 @@ mod tests {
         assert_eq!(format!("{}", DataSourceMode::Api), "API");
     }
 
+    #[test]
+    fn test_provider_data_source() {
+        let mut settings = Settings {
+            claude_usage_data_source: DataSourceMode::Web,
+            ..Default::default()
+        };
+        settings.provider_settings.insert(
+            ProviderKind::Codex,
+            ProviderSettings {
+                source_mode: Some(DataSourceMode::Cli),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            settings.data_source(ProviderKind::Claude),
+            DataSourceMode::Web
+        );
+        assert_eq!(
+            settings.data_source(ProviderKind::Codex),
+            DataSourceMode::Cli
+        );
+        assert_eq!(
+            settings.data_source(ProviderKind::Gemini),
+            DataSourceMode::Auto
+        );
+        assert_eq!(DataSourceMode::Api.source_mode(), SourceMode::ApiKey);
+    }
+
+    #[test]
+    fn test_provider_sandbox() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"provider_settings": {"gemini": {"sandbox": {"clean_env": true, "path": ["/usr/bin"]}}}}"#,
+        )
+        .unwrap();
+
+        let sandbox = settings.sandbox(ProviderKind::Gemini);
+        assert!(sandbox.clean_env);
+        assert!(!sandbox.no_network);
+        assert_eq!(sandbox.path, vec![PathBuf::from("/usr/bin")]);
+        assert!(!settings.sandbox(ProviderKind::Claude).is_enabled());
+    }
+
+    #[test]
+    fn test_provider_strategies() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"provider_settings": {"claude": {"strategies": {"disabled": ["claude.pty"]}}}}"#,
+        )
+        .unwrap();
+
+        assert!(
+            !settings
+                .strategies(ProviderKind::Claude)
+                .is_enabled("claude.pty")
+        );
+        assert!(
+            settings
+                .strategies(ProviderKind::Claude)
+                .is_enabled("claude.oauth")
+        );
+        assert!(settings.strategies(ProviderKind::Cursor).is_default());
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let mut settings = Settings {
+            extra_search_paths: vec![PathBuf::from("/opt/tools/bin")],
+            ..Default::default()
+        };
+        settings.provider_settings.insert(
+            ProviderKind::Claude,
+            ProviderSettings {
+                binary_path: Some(PathBuf::from("/opt/claude/bin/claude")),
+                ..Default::default()
+            },
+        );
+        settings
+            .provider_settings
+            .insert(ProviderKind::Codex, ProviderSettings::default());
+
+        let search = settings.binary_search(|provider| match provider {
+            ProviderKind::Claude => Some("claude"),
+            _ => None,
+        });
+        assert_eq!(search.overrides.len(), 1);
+        assert_eq!(
+            search.overrides["claude"],
+            PathBuf::from("/opt/claude/bin/claude")
+        );
+        assert_eq!(search.extra_paths, settings.extra_search_paths);
+    }
+
+    #[tokio::test]
+    async fn test_account_labels() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_settings.json"));
+        let label = AccountLabel {
+            name: "Work".to_string(),
+            color: AccountColor::Blue,
+        };
+        store
+            .set_account_label(ProviderKind::Claude, "Me@Example.com", Some(label.clone()))
+            .await;
+
+        let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+        identity.account_email = Some("me@example.com".to_string());
+        assert_eq!(store.get().await.account_label(&identity), Some(&label));
+
+        identity.provider_id = ProviderKind::Codex;
+        assert_eq!(store.get().await.account_label(&identity), None);
+
+        store
+            .set_account_label(ProviderKind::Claude, "me@example.com", None)
+            .await;
+        assert_eq!(
+            store
+                .account_label(ProviderKind::Claude, "me@example.com")
+                .await,
+            None
+        );
+        assert_eq!(
+            AccountColor::from_name(" purple "),
+            Some(AccountColor::Purple)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_burn_target() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_burn_target.json"));
+        let six_pm = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        store
+            .set_burn_target(ProviderKind::Codex, Some(six_pm))
+            .await;
+
+        let settings = store.get().await;
+        assert_eq!(settings.burn_target(ProviderKind::Codex), Some(six_pm));
+        assert_eq!(settings.burn_target(ProviderKind::Claude), None);
+
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""burn_target":"18:00:00""#));
+
+        store.set_burn_target(ProviderKind::Codex, None).await;
+        assert_eq!(store.get().await.burn_target(ProviderKind::Codex), None);
+    }
+
+    #[tokio::test]
+    async fn test_failure_tolerance() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_failure_tolerance.json"));
+        let now = Utc::now();
+        let tolerance = store.get().await.failure_tolerance(ProviderKind::Claude);
+        assert_eq!(tolerance, FailureTolerance::default());
+        assert!(!tolerance.is_exceeded(0, None, now));
+        assert!(tolerance.is_exceeded(1, None, now));
+
+        let flaky = FailureTolerance {
+            failures: 3,
+            stale_minutes: Some(30),
+        };
+        store
+            .set_provider_failure_tolerance(ProviderKind::Claude, Some(flaky))
+            .await;
+        let settings = store.get().await;
+        assert_eq!(settings.failure_tolerance(ProviderKind::Claude), flaky);
+        assert_eq!(
+            settings.failure_tolerance(ProviderKind::Codex),
+            FailureTolerance::default()
+        );
+
+        let recent = Some(now - chrono::Duration::minutes(5));
+        assert!(!flaky.is_exceeded(2, recent, now));
+        assert!(flaky.is_exceeded(3, recent, now));
+        // Long enough without a good fetch, even on the first failure
+        assert!(flaky.is_exceeded(1, Some(now - chrono::Duration::minutes(30)), now));
+        assert!(!flaky.is_exceeded(1, None, now));
+
+        store
+            .set_provider_failure_tolerance(ProviderKind::Claude, None)
+            .await;
+        assert_eq!(
+            store.get().await.failure_tolerance(ProviderKind::Claude),
+            FailureTolerance::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secret_backends() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_secret_backends.json"));
+        assert!(store.get().await.secret_backends().is_empty());
+
+        let backend = SecretBackend::OnePassword {
+            reference: "op://Team/Anthropic Admin/credential".to_string(),
+        };
+        store
+            .set_secret_backend(ProviderKind::Claude, Some(backend.clone()))
+            .await;
+        let settings = store.get().await;
+        assert_eq!(
+            settings.secret_backend(ProviderKind::Claude),
+            Some(&backend)
+        );
+        assert_eq!(
+            settings.secret_backends(),
+            HashMap::from([("anthropic_admin".to_string(), backend)])
+        );
+
+        store.set_secret_backend(ProviderKind::Claude, None).await;
+        assert!(store.get().await.secret_backends().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_headers() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_request_headers.json"));
+        let mut headers = RequestHeaders {
+            user_agent: Some("Mozilla/5.0 Custom".to_string()),
+            ..Default::default()
+        };
+        headers
+            .headers
+            .insert("X-Team".to_string(), "research".to_string());
+        store
+            .set_request_headers(ProviderKind::Cursor, headers.clone())
+            .await;
+
+        let settings = store.get().await;
+        assert_eq!(settings.request_headers(ProviderKind::Cursor), headers);
+        assert!(settings.request_headers(ProviderKind::Claude).is_default());
+
+        let json =
+            serde_json::to_value(&settings.provider_settings[&ProviderKind::Cursor]).unwrap();
+        assert_eq!(json["request_headers"]["user_agent"], "Mozilla/5.0 Custom");
+    }
+
+    #[tokio::test]
+    async fn test_request_budget() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_request_budget.json"));
+        let budget = store.get().await.request_budget(ProviderKind::Claude);
+        assert_eq!(budget, ProviderBudget::unlimited(ProviderKind::Claude));
+
+        store.set_request_budget(Some(120)).await;
+        store
+            .set_provider_request_budget(ProviderKind::Claude, Some(20))
+            .await;
+        let settings = store.get().await;
+        let claude = settings.request_budget(ProviderKind::Claude);
+        assert_eq!(claude.per_hour, Some(20));
+        assert_eq!(claude.global_per_hour, Some(120));
+        assert_eq!(settings.request_budget(ProviderKind::Codex).per_hour, None);
+
+        store
+            .set_provider_request_budget(ProviderKind::Claude, None)
+            .await;
+        let json =
+            serde_json::to_value(&store.get().await.provider_settings[&ProviderKind::Claude])
+                .unwrap();
+        assert!(json.get("request_budget_per_hour").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sheets_tabs() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_sheets_tabs.json"));
+        store
+            .set_sheets_tab(ProviderKind::Codex, Some("Codex".to_string()))
+            .await;
+        store
+            .set_sheets_tab(ProviderKind::Claude, Some("Usage".to_string()))
+            .await;
+        assert_eq!(
+            store.get().await.sheets_tabs(),
+            [
+                (ProviderKind::Claude, "Usage".to_string()),
+                (ProviderKind::Codex, "Codex".to_string()),
+            ]
+        );
+
+        store.set_sheets_tab(ProviderKind::Codex, None).await;
+        assert_eq!(store.get().await.sheets_tabs().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_group_providers() {
+        let store = SettingsStore::new(PathBuf::from("/tmp/test_group_providers.json"));
+        store
+            .set_provider_group(ProviderKind::Gemini, Some("Research".to_string()))
+            .await;
+        store
+            .set_provider_group(ProviderKind::Codex, Some(" Coding ".to_string()))
+            .await;
+        store
+            .set_provider_group(ProviderKind::Claude, Some("Coding".to_string()))
+            .await;
+        store
+            .set_provider_group(ProviderKind::Cursor, Some(String::new()))
+            .await;
+
+        let settings = store.get().await;
+        assert_eq!(settings.provider_group(ProviderKind::Codex), Some("Coding"));
+        assert_eq!(settings.provider_group(ProviderKind::Cursor), None);
+        assert_eq!(settings.provider_groups(), ["Coding", "Research"]);
+        assert_eq!(
+            settings.group_providers(&[
+                ProviderKind::Claude,
+                ProviderKind::Gemini,
+                ProviderKind::Cursor,
+                ProviderKind::Codex,
+            ]),
+            [
+                (None, vec![ProviderKind::Cursor]),
+                (
+                    Some("Coding".to_string()),
+                    vec![ProviderKind::Claude, ProviderKind::Codex]
+                ),
+                (Some("Research".to_string()), vec![ProviderKind::Gemini]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_icon_visibility() {
+        let remaining = [
+            (ProviderKind::Claude, Some(80.0)),
+            (ProviderKind::Codex, Some(60.0)),
+        ];
+        assert_eq!(IconVisibility::Always.visible(50.0, &remaining).len(), 2);
+        assert!(
+            IconVisibility::WhenAnyLow
+                .visible(50.0, &remaining)
+                .is_empty()
+        );
+        assert!(IconVisibility::OnlyLow.visible(50.0, &remaining).is_empty());
+
+        let remaining = [
+            (ProviderKind::Claude, Some(80.0)),
+            (ProviderKind::Codex, Some(20.0)),
+            (ProviderKind::Gemini, None),
+        ];
+        assert_eq!(
+            IconVisibility::WhenAnyLow.visible(25.0, &remaining).len(),
+            3
+        );
+        assert_eq!(
+            IconVisibility::OnlyLow.visible(25.0, &remaining),
+            [ProviderKind::Codex, ProviderKind::Gemini]
+        );
+    }
+
     #[test]
     fn test_cookie_source_display() {
         assert_eq!(format!("{}", CookieSource::Auto), "Auto");
@@ -986,6 +2938,17 @@ mod tests {
         assert_eq!(store.theme_mode().await, ThemeMode::Dark);
     }
 
+    #[test]
+    fn test_color_palette_serialization() {
+        let json = r#"{"color_palette": "colorblind_safe"}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.color_palette, ColorPalette::ColorblindSafe);
+
+        // Files written before palettes existed get the standard colors
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.color_palette, ColorPalette::Standard);
+    }
+
     #[tokio::test]
     async fn test_theme_mode_serialization() {
         use crate::persistence::{load_json, save_json};
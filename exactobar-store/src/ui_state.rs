@@ -0,0 +1,105 @@
+//! Window state remembered across launches.
+//!
+//! Where the settings window was and which pane it showed are kept in
+//! [`default_ui_state_path`], apart from the user's settings: they change
+//! every time a window moves, don't belong in a profile, and losing them
+//! only means windows open in their default place. The file stays in the
+//! platform's configuration directory even with a custom data directory,
+//! since window positions depend on this machine's displays.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::StoreError;
+use crate::persistence::{create_secure_parent_dirs, platform_config_dir, write_atomic};
+
+/// Name of the UI state file.
+const UI_STATE_FILE: &str = "ui-state.json";
+
+/// Returns the UI state file, shared by every profile.
+pub fn default_ui_state_path() -> PathBuf {
+    platform_config_dir().join(UI_STATE_FILE)
+}
+
+/// A window's position and size, in points.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowFrame {
+    /// Left edge.
+    pub x: f32,
+    /// Top edge.
+    pub y: f32,
+    /// Width.
+    pub width: f32,
+    /// Height.
+    pub height: f32,
+}
+
+/// UI state remembered across launches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Last frame of the settings window.
+    pub settings_frame: Option<WindowFrame>,
+    /// Last active settings pane, by title.
+    pub settings_pane: Option<String>,
+}
+
+impl UiState {
+    /// Reads the state; a missing or unreadable file is the default state.
+    pub async fn load(path: &Path) -> Self {
+        let Ok(json) = tokio::fs::read_to_string(path).await else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            debug!(path = %path.display(), error = %e, "Ignoring unreadable UI state");
+            Self::default()
+        })
+    }
+
+    /// Reads the state from [`default_ui_state_path`].
+    pub async fn load_default() -> Self {
+        Self::load(&default_ui_state_path()).await
+    }
+
+    /// Writes the state atomically.
+    pub async fn save(&self, path: &Path) -> Result<(), StoreError> {
+        create_secure_parent_dirs(path).await?;
+        let json = serde_json::to_string_pretty(self)?;
+        write_atomic(path, json.as_bytes()).await?;
+        debug!(path = %path.display(), "Saved UI state");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(UI_STATE_FILE);
+        assert_eq!(UiState::load(&path).await, UiState::default());
+
+        let state = UiState {
+            settings_frame: Some(WindowFrame {
+                x: 120.0,
+                y: 80.0,
+                width: 760.0,
+                height: 540.0,
+            }),
+            settings_pane: Some("Advanced".to_string()),
+        };
+        state.save(&path).await.unwrap();
+        assert_eq!(UiState::load(&path).await, state);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(UiState::load(&path).await, UiState::default());
+    }
+}
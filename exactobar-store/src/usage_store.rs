@@ -1,10 +1,18 @@
 //! Main usage state store.
 //!
 //! Manages provider usage data with change notifications for UI updates.
-
-use chrono::{DateTime, Utc};
-use exactobar_core::{Credits, ProviderKind, ProviderStatus, UsageSnapshot};
-use std::collections::{HashMap, HashSet};
+//!
+//! Snapshots can also come from other machines signed into the same
+//! accounts (e.g. a desktop and a laptop both running Claude Code). The
+//! store keeps each machine's latest snapshot and combines them per
+//! account, with a burn rate fed by every machine's fetches; see
+//! [`UsageStore::aggregated_usage`].
+
+use chrono::{DateTime, NaiveDate, Utc};
+use exactobar_core::{
+    Credits, ModelBreakdown, ProviderIdentity, ProviderKind, ProviderStatus, UsageSnapshot,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, watch};
@@ -25,10 +33,65 @@ pub struct CostUsageSnapshot {
     pub total_tokens: u64,
     /// Total estimated cost (USD).
     pub total_cost_usd: f64,
+    /// Token totals by category.
+    pub tokens: TokenCategories,
+    /// Per-model breakdown, most expensive first.
+    pub models: Vec<ModelBreakdown>,
     /// Last scan timestamp.
     pub scanned_at: Option<DateTime<Utc>>,
 }
 
+/// Token totals split by category.
+///
+/// Reasoning tokens are a subset of output tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenCategories {
+    /// Uncached input tokens.
+    pub input: u64,
+    /// Output tokens (including reasoning).
+    pub output: u64,
+    /// Tokens read from the prompt cache.
+    pub cache_read: u64,
+    /// Tokens written to the prompt cache.
+    pub cache_creation: u64,
+    /// Reasoning tokens.
+    pub reasoning: u64,
+}
+
+impl CostUsageSnapshot {
+    /// Projects a 30-day cost from the average daily cost over the scanned span.
+    ///
+    /// Returns `None` when there is no daily data to project from.
+    pub fn projected_monthly_cost_usd(&self) -> Option<f64> {
+        let first = self.daily.first()?.date;
+        let last = self.daily.last()?.date;
+        let span_days = (last - first).num_days() + 1;
+
+        #[allow(clippy::cast_precision_loss)]
+        let daily_average = self.total_cost_usd / span_days as f64;
+        Some(daily_average * 30.0)
+    }
+
+    /// Returns the cost recorded on a (UTC) day, or zero if there was no usage.
+    pub fn cost_on(&self, date: NaiveDate) -> f64 {
+        self.daily
+            .iter()
+            .filter(|d| d.date.date_naive() == date)
+            .map(|d| d.cost_usd)
+            .sum()
+    }
+
+    /// Returns the cost recorded from a (UTC) day onwards, within the
+    /// scanned days.
+    pub fn cost_since(&self, date: NaiveDate) -> f64 {
+        self.daily
+            .iter()
+            .filter(|d| d.date.date_naive() >= date)
+            .map(|d| d.cost_usd)
+            .sum()
+    }
+}
+
 /// Daily cost breakdown.
 #[derive(Debug, Clone)]
 pub struct DailyCost {
@@ -40,6 +103,154 @@ pub struct DailyCost {
     pub cost_usd: f64,
 }
 
+// ============================================================================
+// Multi-Machine Aggregation
+// ============================================================================
+
+/// Machine name local snapshots are aggregated under.
+pub const LOCAL_MACHINE: &str = "local";
+
+/// How far back session usage samples count towards the burn rate.
+const BURN_RATE_WINDOW: chrono::Duration = chrono::Duration::hours(2);
+
+/// Shortest span of samples a burn rate is worked out over.
+const MIN_BURN_RATE_SPAN: chrono::Duration = chrono::Duration::minutes(1);
+
+/// One account's usage, combined from every machine that reported it.
+#[derive(Debug, Clone)]
+pub struct AccountUsage {
+    /// The account's id (see `ProviderIdentity::account_id`), or `None`
+    /// when the snapshot didn't say which account it was for.
+    pub account_id: Option<String>,
+    /// Machines that reported this account, sorted by name.
+    pub machines: Vec<String>,
+    /// The freshest snapshot any of them reported.
+    pub snapshot: UsageSnapshot,
+    /// Session usage used per hour, in percent, over recent snapshots from
+    /// any machine.
+    pub burn_rate_per_hour: Option<f64>,
+}
+
+/// A provider's usage combined across machines, one entry per account.
+#[derive(Debug, Clone, Default)]
+pub struct AggregatedUsage {
+    /// Accounts, most recently updated first.
+    pub accounts: Vec<AccountUsage>,
+}
+
+impl AggregatedUsage {
+    /// Returns the session usage used per hour across all accounts, or
+    /// `None` if no account has a burn rate yet.
+    pub fn combined_burn_rate_per_hour(&self) -> Option<f64> {
+        self.accounts
+            .iter()
+            .filter_map(|a| a.burn_rate_per_hour)
+            .reduce(|a, b| a + b)
+    }
+}
+
+/// What snapshots are deduplicated by: the account, or the machine when the
+/// account isn't known.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum AccountKey {
+    Account(String),
+    Machine(String),
+}
+
+impl AccountKey {
+    fn of(machine: &str, snapshot: &UsageSnapshot) -> Self {
+        match snapshot
+            .identity
+            .as_ref()
+            .and_then(ProviderIdentity::account_id)
+        {
+            Some(id) => Self::Account(id),
+            None => Self::Machine(machine.to_string()),
+        }
+    }
+}
+
+/// One provider's snapshots from every machine.
+#[derive(Debug, Default)]
+struct MachineSnapshots {
+    /// Latest snapshot from each machine.
+    latest: HashMap<String, UsageSnapshot>,
+    /// Session usage samples per account, oldest first.
+    samples: HashMap<AccountKey, VecDeque<(DateTime<Utc>, f64)>>,
+}
+
+impl MachineSnapshots {
+    fn record(&mut self, machine: &str, snapshot: UsageSnapshot) {
+        if let Some(window) = &snapshot.primary {
+            let samples = self
+                .samples
+                .entry(AccountKey::of(machine, &snapshot))
+                .or_default();
+            let sample = (snapshot.updated_at, window.clamped_used_percent());
+            match samples.back() {
+                // Already have newer usage for this account
+                Some(&(at, _)) if at >= sample.0 => {}
+                // The window reset; earlier samples don't count
+                Some(&(_, used)) if sample.1 < used => {
+                    samples.clear();
+                    samples.push_back(sample);
+                }
+                _ => samples.push_back(sample),
+            }
+            while samples
+                .front()
+                .is_some_and(|&(at, _)| sample.0 - at > BURN_RATE_WINDOW)
+            {
+                samples.pop_front();
+            }
+        }
+        self.latest.insert(machine.to_string(), snapshot);
+    }
+
+    fn aggregate(&self) -> AggregatedUsage {
+        let mut accounts: HashMap<AccountKey, AccountUsage> = HashMap::new();
+        for (machine, snapshot) in &self.latest {
+            let key = AccountKey::of(machine, snapshot);
+            let account_id = match &key {
+                AccountKey::Account(id) => Some(id.clone()),
+                AccountKey::Machine(_) => None,
+            };
+            let burn_rate_per_hour = self.samples.get(&key).and_then(burn_rate);
+            let account = accounts.entry(key).or_insert_with(|| AccountUsage {
+                account_id,
+                machines: Vec::new(),
+                snapshot: snapshot.clone(),
+                burn_rate_per_hour,
+            });
+            account.machines.push(machine.clone());
+            // Every machine sees the same quota, so the freshest wins
+            if snapshot.updated_at > account.snapshot.updated_at {
+                account.snapshot = snapshot.clone();
+            }
+        }
+
+        let mut accounts: Vec<AccountUsage> = accounts.into_values().collect();
+        for account in &mut accounts {
+            account.machines.sort();
+        }
+        accounts.sort_by_key(|a| std::cmp::Reverse(a.snapshot.updated_at));
+        AggregatedUsage { accounts }
+    }
+}
+
+/// Works out percent used per hour from the first and last sample.
+fn burn_rate(samples: &VecDeque<(DateTime<Utc>, f64)>) -> Option<f64> {
+    let (first_at, first) = *samples.front()?;
+    let (last_at, last) = *samples.back()?;
+    let span = last_at - first_at;
+    if span < MIN_BURN_RATE_SPAN {
+        return None;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let hours = span.num_seconds() as f64 / 3600.0;
+    Some((last - first) / hours)
+}
+
 // ============================================================================
 // Inner State
 // ============================================================================
@@ -64,6 +275,8 @@ struct UsageStoreInner {
     errors: HashMap<ProviderKind, String>,
     /// Snapshot timestamps.
     snapshot_times: HashMap<ProviderKind, DateTime<Utc>>,
+    /// Snapshots from every machine, this one included.
+    machines: HashMap<ProviderKind, MachineSnapshots>,
 }
 
 impl Default for UsageStoreInner {
@@ -82,6 +295,7 @@ impl Default for UsageStoreInner {
             refresh_in_progress: HashSet::new(),
             errors: HashMap::new(),
             snapshot_times: HashMap::new(),
+            machines: HashMap::new(),
         }
     }
 }
@@ -158,6 +372,11 @@ impl UsageStore {
     pub async fn set_snapshot(&self, provider: ProviderKind, snapshot: UsageSnapshot) {
         {
             let mut inner = self.inner.write().await;
+            inner
+                .machines
+                .entry(provider)
+                .or_default()
+                .record(LOCAL_MACHINE, snapshot.clone());
             inner.snapshots.insert(provider, snapshot);
             inner.snapshot_times.insert(provider, Utc::now());
             inner.errors.remove(&provider);
@@ -166,6 +385,43 @@ impl UsageStore {
         debug!(provider = ?provider, "Snapshot updated");
     }
 
+    /// Records a snapshot another machine fetched.
+    ///
+    /// Only the machine's latest snapshot is kept. It's combined with this
+    /// machine's in [`aggregated_usage`](Self::aggregated_usage), not
+    /// returned by [`get_snapshot`](Self::get_snapshot).
+    pub async fn record_machine_snapshot(
+        &self,
+        provider: ProviderKind,
+        machine: &str,
+        snapshot: UsageSnapshot,
+    ) {
+        self.inner
+            .write()
+            .await
+            .machines
+            .entry(provider)
+            .or_default()
+            .record(machine, snapshot);
+        self.notify_change().await;
+        debug!(provider = ?provider, machine, "Machine snapshot recorded");
+    }
+
+    /// Combines a provider's snapshots from every machine, one entry per
+    /// account, or `None` if no machine has reported it.
+    ///
+    /// Machines signed into the same account share its quota, so they're
+    /// deduplicated: the account shows the freshest snapshot, and its burn
+    /// rate follows session usage across all of their fetches.
+    pub async fn aggregated_usage(&self, provider: ProviderKind) -> Option<AggregatedUsage> {
+        self.inner
+            .read()
+            .await
+            .machines
+            .get(&provider)
+            .map(MachineSnapshots::aggregate)
+    }
+
     // ========================================================================
     // Provider Management
     // ========================================================================
@@ -397,6 +653,126 @@ mod tests {
         assert!(store.get_snapshot(ProviderKind::Codex).await.is_some());
     }
 
+    #[test]
+    fn test_cost_projection() {
+        let day = |d: u32| {
+            chrono::NaiveDate::from_ymd_opt(2025, 6, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+
+        let empty = CostUsageSnapshot::default();
+        assert!(empty.projected_monthly_cost_usd().is_none());
+
+        // $10 over a 5-day span (only two days with usage) = $2/day
+        let cost = CostUsageSnapshot {
+            daily: vec![
+                DailyCost {
+                    date: day(1),
+                    tokens: 100,
+                    cost_usd: 4.0,
+                },
+                DailyCost {
+                    date: day(5),
+                    tokens: 100,
+                    cost_usd: 6.0,
+                },
+            ],
+            total_tokens: 200,
+            total_cost_usd: 10.0,
+            ..Default::default()
+        };
+        let projected = cost.projected_monthly_cost_usd().unwrap();
+        assert!((projected - 60.0).abs() < 1e-9);
+
+        assert!((cost.cost_on(day(5).date_naive()) - 6.0).abs() < 1e-9);
+        assert!(cost.cost_on(day(3).date_naive()).abs() < 1e-9);
+        assert!((cost.cost_since(day(3).date_naive()) - 6.0).abs() < 1e-9);
+        assert!((cost.cost_since(day(1).date_naive()) - 10.0).abs() < 1e-9);
+    }
+
+    fn account_snapshot(email: Option<&str>, used: f64, minutes_ago: i64) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(exactobar_core::UsageWindow::new(used));
+        snapshot.updated_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+        if let Some(email) = email {
+            let mut identity = ProviderIdentity::new(ProviderKind::Claude);
+            identity.account_email = Some(email.to_string());
+            snapshot.identity = Some(identity);
+        }
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn test_aggregates_machines_by_account() {
+        let store = UsageStore::new();
+        let claude = ProviderKind::Claude;
+        assert!(store.aggregated_usage(claude).await.is_none());
+
+        // Desktop and laptop share one account; a third machine has another
+        store
+            .set_snapshot(claude, account_snapshot(Some("me@example.com"), 20.0, 60))
+            .await;
+        store
+            .record_machine_snapshot(
+                claude,
+                "laptop",
+                account_snapshot(Some("ME@example.com"), 30.0, 30),
+            )
+            .await;
+        store
+            .record_machine_snapshot(
+                claude,
+                "work",
+                account_snapshot(Some("work@example.com"), 5.0, 90),
+            )
+            .await;
+        store
+            .record_machine_snapshot(claude, "old", account_snapshot(None, 50.0, 120))
+            .await;
+
+        let usage = store.aggregated_usage(claude).await.unwrap();
+        assert_eq!(usage.accounts.len(), 3);
+
+        let shared = &usage.accounts[0];
+        assert_eq!(shared.account_id.as_deref(), Some("me@example.com"));
+        assert_eq!(shared.machines, vec!["laptop", LOCAL_MACHINE]);
+        let used = shared.snapshot.primary.as_ref().unwrap().used_percent;
+        assert!((used - 30.0).abs() < 1e-9);
+        // 10% over half an hour, across both machines
+        assert!((shared.burn_rate_per_hour.unwrap() - 20.0).abs() < 0.1);
+
+        assert_eq!(
+            usage.accounts[1].account_id.as_deref(),
+            Some("work@example.com")
+        );
+        assert!(usage.accounts[1].burn_rate_per_hour.is_none());
+        assert!(usage.accounts[2].account_id.is_none());
+        assert!((usage.combined_burn_rate_per_hour().unwrap() - 20.0).abs() < 0.1);
+
+        // Local snapshots still read back as before
+        let local = store.get_snapshot(claude).await.unwrap();
+        assert!((local.primary.unwrap().used_percent - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_burn_rate_restarts_after_reset() {
+        let mut machines = MachineSnapshots::default();
+        let email = Some("me@example.com");
+        machines.record("desktop", account_snapshot(email, 80.0, 50));
+        machines.record("laptop", account_snapshot(email, 90.0, 40));
+        // An older snapshot arriving late doesn't count
+        machines.record("desktop", account_snapshot(email, 85.0, 45));
+        machines.record("desktop", account_snapshot(email, 5.0, 20));
+        machines.record("laptop", account_snapshot(email, 15.0, 0));
+
+        let account = &machines.aggregate().accounts[0];
+        assert_eq!(account.machines, vec!["desktop", "laptop"]);
+        assert!((account.burn_rate_per_hour.unwrap() - 30.0).abs() < 0.1);
+    }
+
     #[tokio::test]
     async fn test_provider_toggle() {
         let store = UsageStore::new();
@@ -0,0 +1,198 @@
+//! Usage file for widgets.
+//!
+//! A widget (like a `WidgetKit` extension showing usage in Notification
+//! Center or on the Lock Screen) runs in its own process and can't ask the
+//! app for usage, so the app writes a small summary after every refresh
+//! to [`default_widget_path`]:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "updated_at": "2026-10-16T09:30:00Z",
+//!   "providers": [
+//!     {
+//!       "provider": "claude",
+//!       "name": "Claude",
+//!       "session": { "used_percent": 28.0, "remaining_percent": 72.0, "resets_at": "2026-10-16T12:00:00Z" },
+//!       "weekly": { "used_percent": 41.0, "remaining_percent": 59.0, "resets_at": null },
+//!       "fetched_at": "2026-10-16T09:29:58Z",
+//!       "error": null
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Providers are in the app's order. `error` is set while a provider's
+//! fetches fail; its windows are then from the last successful fetch, if
+//! there was one. Fields are only added within a `version`.
+//!
+//! On macOS the file is in the app group container shared with the widget
+//! extension ([`WIDGET_APP_GROUP`]), which a sandboxed widget can read;
+//! elsewhere it's in the cache directory.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use exactobar_core::{ProviderKind, UsageSnapshot, UsageWindow};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::StoreError;
+use crate::persistence::{create_secure_parent_dirs, default_cache_dir, write_atomic};
+
+/// Version of the file's format.
+pub const WIDGET_FORMAT_VERSION: u32 = 1;
+
+/// App group shared by the app and its widget extension.
+pub const WIDGET_APP_GROUP: &str = "group.dev.exactobar";
+
+/// Name of the usage file.
+const WIDGET_FILE: &str = "widget-usage.json";
+
+/// Returns where the app writes usage for widgets.
+///
+/// - macOS: `~/Library/Group Containers/group.dev.exactobar/widget-usage.json`
+/// - Elsewhere: `widget-usage.json` in the cache directory
+pub fn default_widget_path() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            return home
+                .join("Library")
+                .join("Group Containers")
+                .join(WIDGET_APP_GROUP)
+                .join(WIDGET_FILE);
+        }
+    }
+    default_cache_dir().join(WIDGET_FILE)
+}
+
+/// A usage window as widgets see it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetWindow {
+    /// Percentage used (0-100).
+    pub used_percent: f64,
+    /// Percentage remaining (0-100).
+    pub remaining_percent: f64,
+    /// When the window resets.
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl From<&UsageWindow> for WidgetWindow {
+    fn from(window: &UsageWindow) -> Self {
+        Self {
+            used_percent: window.used_percent,
+            remaining_percent: window.remaining_percent(),
+            resets_at: window.resets_at,
+        }
+    }
+}
+
+/// One provider's usage for widgets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetProvider {
+    /// Provider, by CLI name.
+    pub provider: ProviderKind,
+    /// Display name.
+    pub name: String,
+    /// Session (primary) window.
+    pub session: Option<WidgetWindow>,
+    /// Weekly (secondary) window.
+    pub weekly: Option<WidgetWindow>,
+    /// When the usage was fetched.
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Why the latest fetch failed.
+    pub error: Option<String>,
+}
+
+/// Usage of every shown provider, as written for widgets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetUsage {
+    /// Format version ([`WIDGET_FORMAT_VERSION`]).
+    pub version: u32,
+    /// When the file was written.
+    pub updated_at: DateTime<Utc>,
+    /// Providers, in display order.
+    pub providers: Vec<WidgetProvider>,
+}
+
+impl WidgetUsage {
+    /// Builds the file's contents from each provider's latest snapshot and
+    /// error. Providers with neither are left out.
+    pub fn new<'a>(
+        providers: impl IntoIterator<Item = (ProviderKind, Option<&'a UsageSnapshot>, Option<&'a str>)>,
+    ) -> Self {
+        let providers = providers
+            .into_iter()
+            .filter(|(_, snapshot, error)| snapshot.is_some() || error.is_some())
+            .map(|(provider, snapshot, error)| WidgetProvider {
+                provider,
+                name: provider.display_name().to_string(),
+                session: snapshot
+                    .and_then(|s| s.primary.as_ref())
+                    .map(WidgetWindow::from),
+                weekly: snapshot
+                    .and_then(|s| s.secondary.as_ref())
+                    .map(WidgetWindow::from),
+                fetched_at: snapshot.map(|s| s.updated_at),
+                error: error.map(str::to_string),
+            })
+            .collect();
+
+        Self {
+            version: WIDGET_FORMAT_VERSION,
+            updated_at: Utc::now(),
+            providers,
+        }
+    }
+
+    /// Writes the file atomically, so widgets never read half of it.
+    pub async fn save(&self, path: &Path) -> Result<(), StoreError> {
+        create_secure_parent_dirs(path).await?;
+        let json = serde_json::to_string_pretty(self)?;
+        write_atomic(path, json.as_bytes()).await?;
+        debug!(path = %path.display(), providers = self.providers.len(), "Wrote widget usage");
+        Ok(())
+    }
+
+    /// Reads a file written by [`save`](Self::save).
+    pub async fn load(path: &Path) -> Result<Self, StoreError> {
+        let json = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("group").join(WIDGET_FILE);
+
+        let mut snapshot = UsageSnapshot::new();
+        snapshot.primary = Some(UsageWindow::new(28.0));
+        let usage = WidgetUsage::new([
+            (ProviderKind::Claude, Some(&snapshot), None),
+            (ProviderKind::Codex, None, Some("not logged in")),
+            (ProviderKind::Cursor, None, None),
+        ]);
+        usage.save(&path).await.unwrap();
+
+        let loaded = WidgetUsage::load(&path).await.unwrap();
+        assert_eq!(loaded, usage);
+        assert_eq!(loaded.providers.len(), 2);
+        let session = loaded.providers[0].session.as_ref().unwrap();
+        assert!((session.remaining_percent - 72.0).abs() < f64::EPSILON);
+        assert!(loaded.providers[0].weekly.is_none());
+        assert_eq!(loaded.providers[1].error.as_deref(), Some("not logged in"));
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains(r#""provider": "claude""#));
+    }
+}
@@ -0,0 +1,20 @@
+//! The remaining providers' API and CLI JSON parsers: Augment, Factory,
+//! Gemini, Kiro, MiniMax and z.ai.
+
+#![no_main]
+
+use exactobar_providers::RawFormat;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for format in [
+        RawFormat::Augment,
+        RawFormat::Factory,
+        RawFormat::Gemini,
+        RawFormat::Kiro,
+        RawFormat::Minimax,
+        RawFormat::Zai,
+    ] {
+        let _ = format.parse(input);
+    }
+});
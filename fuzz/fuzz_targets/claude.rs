@@ -0,0 +1,17 @@
+//! Claude OAuth, CLI (JSON and text) and `/usage` screen parsers.
+
+#![no_main]
+
+use exactobar_providers::RawFormat;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for format in [
+        RawFormat::ClaudeOauth,
+        RawFormat::ClaudeCliJson,
+        RawFormat::ClaudeCliText,
+        RawFormat::ClaudePty,
+    ] {
+        let _ = format.parse(input);
+    }
+});
@@ -0,0 +1,12 @@
+//! Codex CLI JSON and `/status` screen parsers.
+
+#![no_main]
+
+use exactobar_providers::RawFormat;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for format in [RawFormat::CodexCli, RawFormat::CodexPty] {
+        let _ = format.parse(input);
+    }
+});
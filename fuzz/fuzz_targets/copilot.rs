@@ -0,0 +1,10 @@
+//! Copilot usage API response parser.
+
+#![no_main]
+
+use exactobar_providers::RawFormat;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = RawFormat::Copilot.parse(input);
+});